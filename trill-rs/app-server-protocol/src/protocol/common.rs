@@ -140,6 +140,18 @@ client_request_definitions! {
         params: v2::ThreadRollbackParams,
         response: v2::ThreadRollbackResponse,
     },
+    ThreadUndoList => "thread/undo/list" {
+        params: v2::ThreadUndoListParams,
+        response: v2::ThreadUndoListResponse,
+    },
+    ThreadUndo => "thread/undo" {
+        params: v2::ThreadUndoParams,
+        response: v2::ThreadUndoResponse,
+    },
+    ThreadRedo => "thread/redo" {
+        params: v2::ThreadRedoParams,
+        response: v2::ThreadRedoResponse,
+    },
     ThreadList => "thread/list" {
         params: v2::ThreadListParams,
         response: v2::ThreadListResponse,
@@ -202,6 +214,16 @@ client_request_definitions! {
         response: v2::ListMcpServerStatusResponse,
     },
 
+    McpServerPromptGet => "mcpServer/prompt/get" {
+        params: v2::McpServerPromptGetParams,
+        response: v2::McpServerPromptGetResponse,
+    },
+
+    McpServerRootsList => "mcpServer/roots/list" {
+        params: v2::McpServerRootsListParams,
+        response: v2::McpServerRootsListResponse,
+    },
+
     LoginAccount => "account/login/start" {
         params: v2::LoginAccountParams,
         response: v2::LoginAccountResponse,
@@ -549,6 +571,12 @@ server_request_definitions! {
         response: v2::DynamicToolCallResponse,
     },
 
+    /// Sent when an MCP server asks to sample the thread's model.
+    McpSamplingRequestApproval => "mcpServer/sampling/requestApproval" {
+        params: v2::McpSamplingRequestApprovalParams,
+        response: v2::McpSamplingRequestApprovalResponse,
+    },
+
     ChatgptAuthTokensRefresh => "account/chatgptAuthTokens/refresh" {
         params: v2::ChatgptAuthTokensRefreshParams,
         response: v2::ChatgptAuthTokensRefreshResponse,
@@ -615,6 +643,7 @@ server_notification_definitions! {
     TerminalInteraction => "item/commandExecution/terminalInteraction" (v2::TerminalInteractionNotification),
     FileChangeOutputDelta => "item/fileChange/outputDelta" (v2::FileChangeOutputDeltaNotification),
     McpToolCallProgress => "item/mcpToolCall/progress" (v2::McpToolCallProgressNotification),
+    McpSamplingProgress => "mcpServer/sampling/progress" (v2::McpSamplingProgressNotification),
     McpServerOauthLoginCompleted => "mcpServer/oauthLogin/completed" (v2::McpServerOauthLoginCompletedNotification),
     AccountUpdated => "account/updated" (v2::AccountUpdatedNotification),
     AccountRateLimitsUpdated => "account/rateLimits/updated" (v2::AccountRateLimitsUpdatedNotification),
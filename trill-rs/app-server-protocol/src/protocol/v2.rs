@@ -36,13 +36,19 @@ use trill_protocol::protocol::SkillToolDependency as CoreSkillToolDependency;
 use trill_protocol::protocol::SubAgentSource as CoreSubAgentSource;
 use trill_protocol::protocol::TokenUsage as CoreTokenUsage;
 use trill_protocol::protocol::TokenUsageInfo as CoreTokenUsageInfo;
+use trill_protocol::protocol::UndoSnapshot as CoreUndoSnapshot;
 use trill_protocol::user_input::ByteRange as CoreByteRange;
 use trill_protocol::user_input::TextElement as CoreTextElement;
 use trill_protocol::user_input::UserInput as CoreUserInput;
 use trill_utils_absolute_path::AbsolutePathBuf;
 use mcp_types::ContentBlock as McpContentBlock;
+use mcp_types::Prompt as McpPrompt;
+use mcp_types::PromptMessage as McpPromptMessage;
+use mcp_types::RequestId as McpRequestId;
 use mcp_types::Resource as McpResource;
 use mcp_types::ResourceTemplate as McpResourceTemplate;
+use mcp_types::Role as McpRole;
+use mcp_types::Root as McpRoot;
 use mcp_types::Tool as McpTool;
 use schemars::JsonSchema;
 use serde::Deserialize;
@@ -1048,6 +1054,7 @@ pub struct McpServerStatus {
     pub tools: std::collections::HashMap<String, McpTool>,
     pub resources: Vec<McpResource>,
     pub resource_templates: Vec<McpResourceTemplate>,
+    pub prompts: Vec<McpPrompt>,
     pub auth_status: McpAuthStatus,
 }
 
@@ -1061,6 +1068,43 @@ pub struct ListMcpServerStatusResponse {
     pub next_cursor: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerPromptGetParams {
+    pub thread_id: String,
+    pub server: String,
+    pub name: String,
+    /// Values for the prompt's arguments, keyed by argument name.
+    #[serde(default)]
+    pub arguments: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerPromptGetResponse {
+    pub description: Option<String>,
+    pub messages: Vec<McpPromptMessage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerRootsListParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerRootsListResponse {
+    /// Roots advertised to the thread's MCP servers: the cwd first, then any
+    /// extra writable roots. Servers are sent `notifications/roots/list_changed`
+    /// when these change.
+    pub roots: Vec<McpRoot>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1354,6 +1398,84 @@ pub struct ThreadRollbackResponse {
     pub thread: Thread,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadUndoListParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadUndoListResponse {
+    /// Turns with a ghost snapshot, newest first.
+    pub snapshots: Vec<UndoSnapshot>,
+    /// Whether `thread/redo` has an undo to reapply.
+    pub redo_available: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct UndoSnapshot {
+    /// Pass as `numTurns` to `thread/undo` to restore this snapshot.
+    pub num_turns: u32,
+    pub commit_id: String,
+    pub user_message: String,
+    /// Files the turn changed, relative to the thread's cwd.
+    pub changed_files: Vec<PathBuf>,
+}
+
+impl From<CoreUndoSnapshot> for UndoSnapshot {
+    fn from(value: CoreUndoSnapshot) -> Self {
+        Self {
+            num_turns: value.num_turns,
+            commit_id: value.commit_id,
+            user_message: value.user_message,
+            changed_files: value.changed_files,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadUndoParams {
+    pub thread_id: String,
+    /// Restore the working tree from before the last `num_turns` turns. Must be >= 1.
+    ///
+    /// Unlike `thread/rollback`, this reverts local file changes as well as dropping
+    /// the turns from the thread's history.
+    pub num_turns: u32,
+    /// Restore only these files. The thread's history is kept when set.
+    #[serde(default)]
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadUndoResponse {
+    /// Human-readable summary of what was restored.
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadRedoParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadRedoResponse {
+    /// Human-readable summary of what was restored.
+    pub message: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -2574,6 +2696,17 @@ pub struct McpToolCallProgressNotification {
     pub message: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpSamplingProgressNotification {
+    pub thread_id: String,
+    pub turn_id: String,
+    pub server_name: String,
+    pub request_id: McpRequestId,
+    pub message: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -2674,6 +2807,51 @@ pub struct DynamicToolCallResponse {
     pub success: bool,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpSamplingRequestApprovalParams {
+    pub thread_id: String,
+    pub turn_id: String,
+    pub server_name: String,
+    /// Request id chosen by the MCP server.
+    pub request_id: McpRequestId,
+    /// Model the request will run on once the server's hints are mapped.
+    pub model: String,
+    /// Output token limit after the configured cap is applied.
+    pub max_tokens: u32,
+    pub system_prompt: Option<String>,
+    pub messages: Vec<McpSamplingMessage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpSamplingMessage {
+    pub role: McpRole,
+    /// Message text; images and audio are shown as placeholders.
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum McpSamplingApprovalDecision {
+    /// Run this request.
+    Accept,
+    /// Run this request and later ones from the same server without prompting.
+    AcceptForSession,
+    /// Refuse the request; the server receives an error.
+    Decline,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpSamplingRequestApprovalResponse {
+    pub decision: McpSamplingApprovalDecision,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -39,6 +39,11 @@ use trill_app_server_protocol::InterruptConversationResponse;
 use trill_app_server_protocol::ItemCompletedNotification;
 use trill_app_server_protocol::ItemStartedNotification;
 use trill_app_server_protocol::JSONRPCErrorError;
+use trill_app_server_protocol::McpSamplingApprovalDecision;
+use trill_app_server_protocol::McpSamplingMessage;
+use trill_app_server_protocol::McpSamplingProgressNotification;
+use trill_app_server_protocol::McpSamplingRequestApprovalParams;
+use trill_app_server_protocol::McpSamplingRequestApprovalResponse;
 use trill_app_server_protocol::McpToolCallError;
 use trill_app_server_protocol::McpToolCallResult;
 use trill_app_server_protocol::McpToolCallStatus;
@@ -93,6 +98,7 @@ use trill_protocol::plan_tool::UpdatePlanArgs;
 use trill_protocol::protocol::ReviewOutputEvent;
 use trill_protocol::request_user_input::RequestUserInputAnswer as CoreRequestUserInputAnswer;
 use trill_protocol::request_user_input::RequestUserInputResponse as CoreRequestUserInputResponse;
+use mcp_types::RequestId as McpRequestId;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::path::PathBuf;
@@ -294,6 +300,7 @@ pub(crate) async fn apply_bespoke_event_handling(
                         }),
                     })
                     .collect();
+                let call_id = request.call_id.clone();
                 let params = ToolRequestUserInputParams {
                     thread_id: conversation_id.to_string(),
                     turn_id: request.turn_id,
@@ -304,7 +311,7 @@ pub(crate) async fn apply_bespoke_event_handling(
                     .send_request(ServerRequestPayload::ToolRequestUserInput(params))
                     .await;
                 tokio::spawn(async move {
-                    on_request_user_input_response(event_turn_id, rx, conversation).await;
+                    on_request_user_input_response(event_turn_id, call_id, rx, conversation).await;
                 });
             } else {
                 error!(
@@ -317,6 +324,7 @@ pub(crate) async fn apply_bespoke_event_handling(
                 if let Err(err) = conversation
                     .submit(Op::UserInputAnswer {
                         id: event_turn_id,
+                        call_id: Some(request.call_id),
                         response: empty,
                     })
                     .await
@@ -359,6 +367,62 @@ pub(crate) async fn apply_bespoke_event_handling(
                     .await;
             }
         }
+        EventMsg::McpSamplingRequest(request) => {
+            let server_name = request.server_name.clone();
+            let request_id = request.id.clone();
+            if matches!(api_version, ApiVersion::V2) {
+                let params = McpSamplingRequestApprovalParams {
+                    thread_id: conversation_id.to_string(),
+                    turn_id: event_turn_id.clone(),
+                    server_name: request.server_name,
+                    request_id: request.id,
+                    model: request.model,
+                    max_tokens: request.max_tokens,
+                    system_prompt: request.system_prompt,
+                    messages: request
+                        .messages
+                        .into_iter()
+                        .map(|message| McpSamplingMessage {
+                            role: message.role,
+                            text: message.text,
+                        })
+                        .collect(),
+                };
+                let rx = outgoing
+                    .send_request(ServerRequestPayload::McpSamplingRequestApproval(params))
+                    .await;
+                tokio::spawn(async move {
+                    on_mcp_sampling_approval_response(server_name, request_id, rx, conversation)
+                        .await;
+                });
+            } else {
+                error!("MCP sampling is only supported on api v2 (server: {server_name})");
+                if let Err(err) = conversation
+                    .submit(Op::ResolveMcpSampling {
+                        server_name,
+                        request_id,
+                        decision: ReviewDecision::Denied,
+                    })
+                    .await
+                {
+                    error!("failed to submit ResolveMcpSampling: {err}");
+                }
+            }
+        }
+        EventMsg::McpSamplingProgress(progress) => {
+            if let ApiVersion::V2 = api_version {
+                let notification = McpSamplingProgressNotification {
+                    thread_id: conversation_id.to_string(),
+                    turn_id: event_turn_id.clone(),
+                    server_name: progress.server_name,
+                    request_id: progress.id,
+                    message: progress.message,
+                };
+                outgoing
+                    .send_server_notification(ServerNotification::McpSamplingProgress(notification))
+                    .await;
+            }
+        }
         // TODO(celia): properly construct McpToolCall TurnItem in core.
         EventMsg::McpToolCallBegin(begin_event) => {
             let notification = construct_mcp_tool_call_notification(
@@ -1483,6 +1547,7 @@ async fn on_exec_approval_response(
 
 async fn on_request_user_input_response(
     event_turn_id: String,
+    call_id: String,
     receiver: oneshot::Receiver<JsonValue>,
     conversation: Arc<TrillThread>,
 ) {
@@ -1497,6 +1562,7 @@ async fn on_request_user_input_response(
             if let Err(err) = conversation
                 .submit(Op::UserInputAnswer {
                     id: event_turn_id,
+                    call_id: Some(call_id),
                     response: empty,
                 })
                 .await
@@ -1532,6 +1598,7 @@ async fn on_request_user_input_response(
     if let Err(err) = conversation
         .submit(Op::UserInputAnswer {
             id: event_turn_id,
+            call_id: Some(call_id),
             response,
         })
         .await
@@ -1540,6 +1607,41 @@ async fn on_request_user_input_response(
     }
 }
 
+async fn on_mcp_sampling_approval_response(
+    server_name: String,
+    request_id: McpRequestId,
+    receiver: oneshot::Receiver<JsonValue>,
+    conversation: Arc<TrillThread>,
+) {
+    let decision = match receiver.await {
+        Ok(value) => serde_json::from_value::<McpSamplingRequestApprovalResponse>(value)
+            .map(|response| match response.decision {
+                McpSamplingApprovalDecision::Accept => ReviewDecision::Approved,
+                McpSamplingApprovalDecision::AcceptForSession => ReviewDecision::ApprovedForSession,
+                McpSamplingApprovalDecision::Decline => ReviewDecision::Denied,
+            })
+            .unwrap_or_else(|err| {
+                error!("failed to deserialize McpSamplingRequestApprovalResponse: {err}");
+                ReviewDecision::Denied
+            }),
+        Err(err) => {
+            error!("request failed: {err:?}");
+            ReviewDecision::Denied
+        }
+    };
+
+    if let Err(err) = conversation
+        .submit(Op::ResolveMcpSampling {
+            server_name,
+            request_id,
+            decision,
+        })
+        .await
+    {
+        error!("failed to submit ResolveMcpSampling: {err}");
+    }
+}
+
 const REVIEW_FALLBACK_MESSAGE: &str = "Reviewer failed to output a response.";
 
 fn render_review_output_text(output: &ReviewOutputEvent) -> String {
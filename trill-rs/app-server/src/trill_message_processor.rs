@@ -67,7 +67,11 @@ use trill_app_server_protocol::LogoutChatGptResponse;
 use trill_app_server_protocol::McpServerOauthLoginCompletedNotification;
 use trill_app_server_protocol::McpServerOauthLoginParams;
 use trill_app_server_protocol::McpServerOauthLoginResponse;
+use trill_app_server_protocol::McpServerPromptGetParams;
+use trill_app_server_protocol::McpServerPromptGetResponse;
 use trill_app_server_protocol::McpServerRefreshResponse;
+use trill_app_server_protocol::McpServerRootsListParams;
+use trill_app_server_protocol::McpServerRootsListResponse;
 use trill_app_server_protocol::McpServerStatus;
 use trill_app_server_protocol::ModelListParams;
 use trill_app_server_protocol::ModelListResponse;
@@ -109,6 +113,8 @@ use trill_app_server_protocol::ThreadReadParams;
 use trill_app_server_protocol::ThreadReadResponse;
 use trill_app_server_protocol::ThreadResumeParams;
 use trill_app_server_protocol::ThreadResumeResponse;
+use trill_app_server_protocol::ThreadRedoParams;
+use trill_app_server_protocol::ThreadRedoResponse;
 use trill_app_server_protocol::ThreadRollbackParams;
 use trill_app_server_protocol::ThreadSetNameParams;
 use trill_app_server_protocol::ThreadSetNameResponse;
@@ -118,6 +124,10 @@ use trill_app_server_protocol::ThreadStartParams;
 use trill_app_server_protocol::ThreadStartResponse;
 use trill_app_server_protocol::ThreadStartedNotification;
 use trill_app_server_protocol::ThreadUnarchiveParams;
+use trill_app_server_protocol::ThreadUndoListParams;
+use trill_app_server_protocol::ThreadUndoListResponse;
+use trill_app_server_protocol::ThreadUndoParams;
+use trill_app_server_protocol::ThreadUndoResponse;
 use trill_app_server_protocol::ThreadUnarchiveResponse;
 use trill_app_server_protocol::Turn;
 use trill_app_server_protocol::TurnError;
@@ -199,6 +209,7 @@ use trill_protocol::protocol::USER_MESSAGE_BEGIN;
 use trill_protocol::user_input::UserInput as CoreInputItem;
 use trill_rmcp_client::perform_oauth_login_return_url;
 use trill_utils_json_to_toml::json_to_toml;
+use mcp_types::GetPromptRequestParams;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::ffi::OsStr;
@@ -447,6 +458,15 @@ impl CodexMessageProcessor {
             ClientRequest::ThreadRollback { request_id, params } => {
                 self.thread_rollback(request_id, params).await;
             }
+            ClientRequest::ThreadUndoList { request_id, params } => {
+                self.thread_undo_list(request_id, params).await;
+            }
+            ClientRequest::ThreadUndo { request_id, params } => {
+                self.thread_undo(request_id, params).await;
+            }
+            ClientRequest::ThreadRedo { request_id, params } => {
+                self.thread_redo(request_id, params).await;
+            }
             ClientRequest::ThreadList { request_id, params } => {
                 self.thread_list(request_id, params).await;
             }
@@ -513,6 +533,12 @@ impl CodexMessageProcessor {
             ClientRequest::McpServerStatusList { request_id, params } => {
                 self.list_mcp_server_status(request_id, params).await;
             }
+            ClientRequest::McpServerPromptGet { request_id, params } => {
+                self.mcp_server_prompt_get(request_id, params).await;
+            }
+            ClientRequest::McpServerRootsList { request_id, params } => {
+                self.mcp_server_roots_list(request_id, params).await;
+            }
             ClientRequest::LoginAccount { request_id, params } => {
                 self.login_v2(request_id, params).await;
             }
@@ -2051,6 +2077,107 @@ impl CodexMessageProcessor {
         }
     }
 
+    async fn thread_undo_list(&self, request_id: RequestId, params: ThreadUndoListParams) {
+        let (_, thread) = match self.load_thread(&params.thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let outgoing = Arc::clone(&self.outgoing);
+        tokio::spawn(async move {
+            match thread.undo_snapshots().await {
+                Ok(response) => {
+                    let response = ThreadUndoListResponse {
+                        snapshots: response.snapshots.into_iter().map(Into::into).collect(),
+                        redo_available: response.redo_available,
+                    };
+                    outgoing.send_response(request_id, response).await;
+                }
+                Err(err) => {
+                    let error = JSONRPCErrorError {
+                        code: INTERNAL_ERROR_CODE,
+                        message: format!("failed to list undo snapshots: {err:#}"),
+                        data: None,
+                    };
+                    outgoing.send_error(request_id, error).await;
+                }
+            }
+        });
+    }
+
+    async fn thread_undo(&self, request_id: RequestId, params: ThreadUndoParams) {
+        let ThreadUndoParams {
+            thread_id,
+            num_turns,
+            paths,
+        } = params;
+
+        if num_turns == 0 {
+            self.send_invalid_request_error(request_id, "numTurns must be >= 1".to_string())
+                .await;
+            return;
+        }
+
+        let (_, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let outgoing = Arc::clone(&self.outgoing);
+        tokio::spawn(async move {
+            match thread.undo_turns(num_turns, paths).await {
+                Ok(message) => {
+                    outgoing
+                        .send_response(request_id, ThreadUndoResponse { message })
+                        .await;
+                }
+                Err(err) => {
+                    let error = JSONRPCErrorError {
+                        code: INVALID_REQUEST_ERROR_CODE,
+                        message: format!("{err:#}"),
+                        data: None,
+                    };
+                    outgoing.send_error(request_id, error).await;
+                }
+            }
+        });
+    }
+
+    async fn thread_redo(&self, request_id: RequestId, params: ThreadRedoParams) {
+        let (_, thread) = match self.load_thread(&params.thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let outgoing = Arc::clone(&self.outgoing);
+        tokio::spawn(async move {
+            match thread.redo().await {
+                Ok(message) => {
+                    outgoing
+                        .send_response(request_id, ThreadRedoResponse { message })
+                        .await;
+                }
+                Err(err) => {
+                    let error = JSONRPCErrorError {
+                        code: INVALID_REQUEST_ERROR_CODE,
+                        message: format!("{err:#}"),
+                        data: None,
+                    };
+                    outgoing.send_error(request_id, error).await;
+                }
+            }
+        });
+    }
+
     async fn thread_list(&self, request_id: RequestId, params: ThreadListParams) {
         let ThreadListParams {
             cursor,
@@ -3160,6 +3287,7 @@ impl CodexMessageProcessor {
             .chain(snapshot.auth_statuses.keys().cloned())
             .chain(snapshot.resources.keys().cloned())
             .chain(snapshot.resource_templates.keys().cloned())
+            .chain(snapshot.prompts.keys().cloned())
             .collect();
         server_names.sort();
         server_names.dedup();
@@ -3206,6 +3334,7 @@ impl CodexMessageProcessor {
                     .get(name)
                     .cloned()
                     .unwrap_or_default(),
+                prompts: snapshot.prompts.get(name).cloned().unwrap_or_default(),
                 auth_status: snapshot
                     .auth_statuses
                     .get(name)
@@ -3226,6 +3355,62 @@ impl CodexMessageProcessor {
         outgoing.send_response(request_id, response).await;
     }
 
+    async fn mcp_server_prompt_get(&self, request_id: RequestId, params: McpServerPromptGetParams) {
+        let McpServerPromptGetParams {
+            thread_id,
+            server,
+            name,
+            arguments,
+        } = params;
+        let (_, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let outgoing = Arc::clone(&self.outgoing);
+        tokio::spawn(async move {
+            let params = GetPromptRequestParams {
+                name,
+                arguments: (!arguments.is_empty()).then(|| serde_json::json!(arguments)),
+            };
+            match thread.get_mcp_prompt(&server, params).await {
+                Ok(result) => {
+                    let response = McpServerPromptGetResponse {
+                        description: result.description,
+                        messages: result.messages,
+                    };
+                    outgoing.send_response(request_id, response).await;
+                }
+                Err(err) => {
+                    let error = JSONRPCErrorError {
+                        code: INVALID_REQUEST_ERROR_CODE,
+                        message: format!("{err:#}"),
+                        data: None,
+                    };
+                    outgoing.send_error(request_id, error).await;
+                }
+            }
+        });
+    }
+
+    async fn mcp_server_roots_list(&self, request_id: RequestId, params: McpServerRootsListParams) {
+        let (_, thread) = match self.load_thread(&params.thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let roots = thread.mcp_roots().await;
+        self.outgoing
+            .send_response(request_id, McpServerRootsListResponse { roots })
+            .await;
+    }
+
     async fn handle_resume_conversation(
         &self,
         request_id: RequestId,
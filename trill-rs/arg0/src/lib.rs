@@ -2,12 +2,14 @@ use std::future::Future;
 use std::path::Path;
 use std::path::PathBuf;
 
+use clap::Parser;
 use trill_core::CODEX_APPLY_PATCH_ARG1;
 #[cfg(unix)]
 use std::os::unix::fs::symlink;
 use tempfile::TempDir;
 
 const LINUX_SANDBOX_ARG0: &str = "trill-linux-sandbox";
+const NETWORK_PROXY_ARG0: &str = "trill-network-proxy";
 const APPLY_PATCH_ARG0: &str = "apply_patch";
 const MISSPELLED_APPLY_PATCH_ARG0: &str = "applypatch";
 
@@ -23,6 +25,8 @@ pub fn arg0_dispatch() -> Option<TempDir> {
     if exe_name == LINUX_SANDBOX_ARG0 {
         // Safety: [`run_main`] never returns.
         trill_linux_sandbox::run_main();
+    } else if exe_name == NETWORK_PROXY_ARG0 {
+        run_network_proxy();
     } else if exe_name == APPLY_PATCH_ARG0 || exe_name == MISSPELLED_APPLY_PATCH_ARG0 {
         trill_apply_patch::main();
     }
@@ -62,6 +66,22 @@ pub fn arg0_dispatch() -> Option<TempDir> {
     }
 }
 
+/// Runs `trill-network-proxy` (used by sandboxed commands that route their
+/// traffic through a per-command proxy) and exits.
+fn run_network_proxy() -> ! {
+    let args = trill_network_proxy::Args::parse();
+    let result = tokio::runtime::Runtime::new()
+        .map_err(anyhow::Error::from)
+        .and_then(|runtime| runtime.block_on(trill_network_proxy::run_main(args)));
+    match result {
+        Ok(()) => std::process::exit(0),
+        Err(err) => {
+            eprintln!("trill-network-proxy: {err:#}");
+            std::process::exit(1);
+        }
+    }
+}
+
 /// While we want to deploy the Trill CLI as a single executable for simplicity,
 /// we also want to expose some of its functionality as distinct CLIs, so we use
 /// the "arg0 trick" to determine which CLI to dispatch. This effectively allows
@@ -70,7 +90,8 @@ pub fn arg0_dispatch() -> Option<TempDir> {
 ///
 /// When the current executable is invoked through the hard-link or alias named
 /// `trill-linux-sandbox` we *directly* execute
-/// [`trill_linux_sandbox::run_main`] (which never returns). Otherwise we:
+/// [`trill_linux_sandbox::run_main`] (which never returns), and likewise for
+/// `trill-network-proxy`. Otherwise we:
 ///
 /// 1.  Load `.env` values from `~/.trill/.env` before creating any threads.
 /// 2.  Construct a Tokio multi-thread runtime.
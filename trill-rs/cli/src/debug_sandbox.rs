@@ -231,12 +231,17 @@ async fn run_command_under_sandbox(
             let trill_linux_sandbox_exe = config
                 .trill_linux_sandbox_exe
                 .expect("trill-linux-sandbox executable not found");
+            let deny_read = config
+                .sandbox_deny_read
+                .resolve_targets(sandbox_policy_cwd.as_path())?;
             spawn_command_under_linux_sandbox(
                 trill_linux_sandbox_exe,
                 command,
                 cwd,
                 config.sandbox_policy.get(),
                 sandbox_policy_cwd.as_path(),
+                &deny_read,
+                None,
                 stdio_policy,
                 env,
             )
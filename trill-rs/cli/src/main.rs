@@ -694,7 +694,7 @@ async fn cli_main(trill_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
                 // Honor `--search` via the canonical web_search mode.
                 if interactive.web_search {
                     cli_kv_overrides.push((
-                        "web_search".to_string(),
+                        "web_search.mode".to_string(),
                         toml::Value::String("live".to_string()),
                     ));
                 }
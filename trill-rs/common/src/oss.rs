@@ -3,7 +3,9 @@
 use trill_core::LMSTUDIO_OSS_PROVIDER_ID;
 use trill_core::OLLAMA_CHAT_PROVIDER_ID;
 use trill_core::OLLAMA_OSS_PROVIDER_ID;
+use trill_core::OPENAI_COMPATIBLE_OSS_PROVIDER_IDS;
 use trill_core::WireApi;
+use trill_core::built_in_model_providers;
 use trill_core::config::Config;
 use trill_core::protocol::DeprecationNoticeEvent;
 use std::io;
@@ -47,6 +49,25 @@ pub async fn ollama_chat_deprecation_notice(
     Ok(None)
 }
 
+/// Switches a built-in OpenAI-compatible local provider to the Responses API
+/// when the running server supports it. Providers redefined in config.toml
+/// keep their configured `wire_api`.
+pub async fn detect_local_server_wire_api(config: &mut Config) -> io::Result<()> {
+    let provider_id = config.model_provider_id.as_str();
+    if !OPENAI_COMPATIBLE_OSS_PROVIDER_IDS.contains(&provider_id)
+        || built_in_model_providers().get(provider_id) != Some(&config.model_provider)
+    {
+        return Ok(());
+    }
+
+    if let Some(wire_api) =
+        trill_local_server::detect_wire_api(provider_id, &config.model_provider).await?
+    {
+        config.model_provider.wire_api = wire_api;
+    }
+    Ok(())
+}
+
 /// Ensures the specified OSS provider is ready (models downloaded, service reachable).
 pub async fn ensure_oss_provider_ready(
     provider_id: &str,
@@ -63,6 +84,11 @@ pub async fn ensure_oss_provider_ready(
                 .await
                 .map_err(|e| std::io::Error::other(format!("OSS setup failed: {e}")))?;
         }
+        _ if OPENAI_COMPATIBLE_OSS_PROVIDER_IDS.contains(&provider_id) => {
+            trill_local_server::ensure_oss_ready(provider_id, config)
+                .await
+                .map_err(|e| std::io::Error::other(format!("OSS setup failed: {e}")))?;
+        }
         _ => {
             // Unknown provider, skip setup
         }
@@ -86,6 +112,13 @@ mod tests {
         assert_eq!(result, Some(trill_ollama::DEFAULT_OSS_MODEL));
     }
 
+    #[test]
+    fn test_get_default_model_for_provider_local_server() {
+        // These servers serve whatever model they were started with.
+        let result = get_default_model_for_oss_provider(trill_core::LLAMACPP_OSS_PROVIDER_ID);
+        assert_eq!(result, None);
+    }
+
     #[test]
     fn test_get_default_model_for_provider_unknown() {
         let result = get_default_model_for_oss_provider("unknown-provider");
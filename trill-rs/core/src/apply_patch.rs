@@ -1,12 +1,17 @@
+use crate::trill::Session;
 use crate::trill::TurnContext;
+use crate::exec_policy::ToolRuleAmendment;
 use crate::function_tool::FunctionCallError;
 use crate::protocol::FileChange;
 use crate::safety::SafetyCheck;
 use crate::safety::assess_patch_safety;
+use crate::tools::orchestrator::ToolOrchestrator;
 use crate::tools::sandboxing::ExecApprovalRequirement;
 use trill_apply_patch::ApplyPatchAction;
 use trill_apply_patch::ApplyPatchFileChange;
+use trill_execpolicy::FileOp;
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
 
 pub const CODEX_APPLY_PATCH_ARG1: &str = "--trill-run-as-apply-patch";
@@ -34,9 +39,61 @@ pub(crate) struct ApplyPatchExec {
 }
 
 pub(crate) async fn apply_patch(
+    session: &Session,
     turn_context: &TurnContext,
+    call_id: &str,
     action: ApplyPatchAction,
 ) -> InternalApplyPatchInvocation {
+    // A matching `file_rule` takes precedence over the sandbox-based safety assessment, except
+    // that `allow` rules only count when they cover every changed path.
+    if let Some(requirement) = session.services.exec_policy.file_changes_requirement(
+        file_ops_for_action(&action),
+        &action.cwd,
+        turn_context.approval_policy,
+    ) {
+        return match requirement {
+            ExecApprovalRequirement::Forbidden { reason } => {
+                InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(
+                    format!("patch rejected: {reason}"),
+                )))
+            }
+            // Asked here rather than by the patch approval prompt so that "Always allow" can
+            // save `file_rule`s for the changed paths.
+            requirement @ ExecApprovalRequirement::NeedsApproval { .. } => {
+                let amendment = ToolRuleAmendment::for_file_changes(file_ops_for_action(&action));
+                match ToolOrchestrator::enforce_policy(
+                    session,
+                    turn_context,
+                    call_id,
+                    "apply_patch",
+                    requirement,
+                    Some(amendment),
+                )
+                .await
+                {
+                    Ok(()) => InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
+                        action,
+                        auto_approved: false,
+                        exec_approval_requirement: ExecApprovalRequirement::Skip {
+                            bypass_sandbox: false,
+                            proposed_execpolicy_amendment: None,
+                        },
+                    }),
+                    Err(reason) => InternalApplyPatchInvocation::Output(Err(
+                        FunctionCallError::RespondToModel(format!("patch rejected: {reason}")),
+                    )),
+                }
+            }
+            requirement @ ExecApprovalRequirement::Skip { .. } => {
+                InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
+                    action,
+                    auto_approved: true,
+                    exec_approval_requirement: requirement,
+                })
+            }
+        };
+    }
+
     match assess_patch_safety(
         &action,
         turn_context.approval_policy,
@@ -74,6 +131,25 @@ pub(crate) async fn apply_patch(
     }
 }
 
+/// The file operations `file_rule`s are checked against: a move updates the source and adds
+/// the destination.
+fn file_ops_for_action(action: &ApplyPatchAction) -> Vec<(&Path, FileOp)> {
+    let mut ops = Vec::new();
+    for (path, change) in action.changes() {
+        match change {
+            ApplyPatchFileChange::Add { .. } => ops.push((path.as_path(), FileOp::Add)),
+            ApplyPatchFileChange::Delete { .. } => ops.push((path.as_path(), FileOp::Delete)),
+            ApplyPatchFileChange::Update { move_path, .. } => {
+                ops.push((path.as_path(), FileOp::Update));
+                if let Some(move_path) = move_path {
+                    ops.push((move_path.as_path(), FileOp::Add));
+                }
+            }
+        }
+    }
+    ops
+}
+
 pub(crate) fn convert_apply_patch_to_protocol(
     action: &ApplyPatchAction,
 ) -> HashMap<PathBuf, FileChange> {
@@ -124,4 +200,27 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn file_ops_treat_moves_as_update_and_add() {
+        let tmp = tempdir().expect("tmp");
+        std::fs::write(tmp.path().join("a.txt"), "old\n").expect("write a.txt");
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n*** Move to: b.txt\n@@\n-old\n+new\n*** End Patch";
+        let command = vec!["apply_patch".to_string(), patch.to_string()];
+        let trill_apply_patch::MaybeApplyPatchVerified::Body(action) =
+            trill_apply_patch::maybe_parse_apply_patch_verified(&command, tmp.path())
+        else {
+            panic!("expected a verified patch");
+        };
+
+        let source = tmp.path().join("a.txt");
+        let destination = tmp.path().join("b.txt");
+        assert_eq!(
+            file_ops_for_action(&action),
+            vec![
+                (source.as_path(), FileOp::Update),
+                (destination.as_path(), FileOp::Add),
+            ]
+        );
+    }
 }
@@ -19,6 +19,7 @@ use trill_api::ResponsesClient as ApiResponsesClient;
 use trill_api::ResponsesOptions as ApiResponsesOptions;
 use trill_api::ResponsesWebsocketClient as ApiWebSocketResponsesClient;
 use trill_api::ResponsesWebsocketConnection as ApiWebSocketConnection;
+use trill_api::SamplingParams as ApiSamplingParams;
 use trill_api::SseTelemetry;
 use trill_api::TransportError;
 use trill_api::build_conversation_headers;
@@ -55,6 +56,7 @@ use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
 use crate::config::Config;
+use crate::config::types::SamplingParams;
 use crate::default_client::build_reqwest_client;
 use crate::error::CodexErr;
 use crate::error::Result;
@@ -380,6 +382,7 @@ impl ModelClientSession {
             extra_headers: build_responses_headers(&self.state.config, Some(&self.turn_state)),
             compression,
             turn_state: Some(Arc::clone(&self.turn_state)),
+            sampling: build_api_sampling(&self.state.config.model_sampling),
         }
     }
 
@@ -415,6 +418,7 @@ impl ModelClientSession {
             prompt_cache_key,
             text,
             store_override,
+            sampling,
             ..
         } = options;
 
@@ -432,6 +436,7 @@ impl ModelClientSession {
             include: include.clone(),
             prompt_cache_key: prompt_cache_key.clone(),
             text: text.clone(),
+            sampling: sampling.body_fields(&trill_api::WireApi::Responses),
         };
 
         ResponsesWsRequest::ResponseCreate(payload)
@@ -481,14 +486,9 @@ impl ModelClientSession {
     /// Streams a turn via the OpenAI Chat Completions API.
     ///
     /// This path is only used when the provider is configured with
-    /// `WireApi::Chat`; it does not support `output_schema` today.
+    /// `WireApi::Chat`. `output_schema` is encoded as the provider's
+    /// `structured_output` setting asks.
     async fn stream_chat_completions(&self, prompt: &Prompt) -> Result<ApiResponseStream> {
-        if prompt.output_schema.is_some() {
-            return Err(CodexErr::UnsupportedOperation(
-                "output_schema is not supported for Chat Completions API".to_string(),
-            ));
-        }
-
         let auth_manager = self.state.auth_manager.clone();
         let instructions = prompt.base_instructions.text.clone();
         let tools_json = create_tools_json_for_chat_completions_api(&prompt.tools)?;
@@ -512,7 +512,12 @@ impl ModelClientSession {
             let transport = ReqwestTransport::new(build_reqwest_client());
             let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
             let client = ApiChatClient::new(transport, api_provider, api_auth)
-                .with_telemetry(Some(request_telemetry), Some(sse_telemetry));
+                .with_telemetry(Some(request_telemetry), Some(sse_telemetry))
+                .with_reasoning_tags(self.state.provider.api_reasoning_tags())
+                .with_reasoning_field(self.state.provider.api_reasoning_field())
+                .with_structured_output(self.state.provider.api_structured_output())
+                .with_capabilities(self.state.provider.api_chat_capabilities())
+                .with_tool_call_format(self.state.provider.api_tool_call_format());
 
             let stream_result = client
                 .stream_prompt(
@@ -520,6 +525,7 @@ impl ModelClientSession {
                     &api_prompt,
                     Some(conversation_id.clone()),
                     Some(session_source.clone()),
+                    build_api_sampling(&self.state.config.model_sampling),
                 )
                 .await;
 
@@ -672,6 +678,25 @@ fn build_api_prompt(prompt: &Prompt, instructions: String, tools_json: Vec<Value
     }
 }
 
+/// Converts the configured sampling parameters into the `trill-api` shape.
+fn build_api_sampling(sampling: &SamplingParams) -> ApiSamplingParams {
+    ApiSamplingParams {
+        temperature: sampling.temperature,
+        top_p: sampling.top_p,
+        top_k: sampling.top_k,
+        min_p: sampling.min_p,
+        repeat_penalty: sampling.repeat_penalty,
+        seed: sampling.seed,
+        max_tokens: sampling.max_tokens,
+        stop: sampling.stop.clone().unwrap_or_default(),
+        extra_body: sampling
+            .extra_body
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+    }
+}
+
 fn experimental_feature_headers(config: &Config) -> ApiHeaderMap {
     let enabled = FEATURES
         .iter()
@@ -3,6 +3,9 @@ use crate::config::edit::ConfigEdit;
 use crate::config::edit::ConfigEditsBuilder;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
 use crate::config::types::History;
+use crate::config::types::HooksToml;
+use crate::config::types::McpSamplingConfig;
+use crate::config::types::McpSamplingToml;
 use crate::config::types::McpServerConfig;
 use crate::config::types::McpServerDisabledReason;
 use crate::config::types::McpServerTransportConfig;
@@ -11,13 +14,20 @@ use crate::config::types::NotificationMethod;
 use crate::config::types::Notifications;
 use crate::config::types::OtelConfig;
 use crate::config::types::OtelConfigToml;
+use crate::config::types::SamplingParams;
 use crate::config::types::OtelExporterKind;
 use crate::config::types::SandboxWorkspaceWrite;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
 use crate::config::types::SkillsConfig;
+use crate::config::types::ToolLoopDetection;
+use crate::config::types::ToolLoopDetectionToml;
 use crate::config::types::Tui;
 use crate::config::types::UriBasedFileOpener;
+use crate::config::types::WebSearchBackendConfig;
+use crate::config::types::WebSearchBackendKind;
+use crate::config::types::WebSearchCacheConfig;
+use crate::config::types::WebSearchToml;
 use crate::config_loader::CloudRequirementsLoader;
 use crate::config_loader::ConfigLayerStack;
 use crate::config_loader::ConfigRequirements;
@@ -36,14 +46,18 @@ use crate::model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::OLLAMA_CHAT_PROVIDER_ID;
 use crate::model_provider_info::OLLAMA_OSS_PROVIDER_ID;
+use crate::model_provider_info::OPENAI_COMPATIBLE_OSS_PROVIDER_IDS;
 use crate::model_provider_info::built_in_model_providers;
 use crate::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
 use crate::project_doc::LOCAL_PROJECT_DOC_FILENAME;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
+use crate::sandboxing::DenyRead;
 use crate::windows_sandbox::WindowsSandboxLevelExt;
 use trill_app_server_protocol::Tools;
 use trill_app_server_protocol::UserSavedConfig;
+use trill_network_policy::DomainPolicy;
+use trill_network_policy::NetworkPolicy;
 use trill_protocol::config_types::AltScreenMode;
 use trill_protocol::config_types::ForcedLoginMethod;
 use trill_protocol::config_types::ModeKind;
@@ -94,9 +108,18 @@ pub use trill_git::GhostSnapshotConfig;
 /// the context window.
 pub(crate) const PROJECT_DOC_MAX_BYTES: usize = 32 * 1024; // 32 KiB
 pub(crate) const DEFAULT_AGENT_MAX_THREADS: Option<usize> = Some(6);
+pub(crate) const DEFAULT_MAX_MALFORMED_TOOL_CALLS: u32 = 3;
 
 pub const CONFIG_TOML_FILE: &str = "config.toml";
 
+/// Directory under `CODEX_HOME` where the network proxy keeps its MITM CA.
+pub const NETWORK_PROXY_DIR: &str = "network-proxy";
+
+/// File name of the network proxy's MITM CA key. With it, a command could
+/// impersonate any host to clients that trust the CA, so it is always part of
+/// [`Config::sandbox_deny_read`].
+pub const MITM_CA_KEY_FILE: &str = "mitm-ca-key.pem";
+
 #[cfg(test)]
 pub(crate) fn test_config() -> Config {
     let trill_home = tempdir().expect("create temp dir");
@@ -158,6 +181,14 @@ pub struct Config {
     /// was coerced to read-only because native auto mode is unsupported.
     pub forced_auto_mode_downgraded_on_windows: bool,
 
+    /// Paths hidden from the Linux sandbox and from the file tools
+    /// (`read_file`, `list_dir`, `grep_files`).
+    pub sandbox_deny_read: DenyRead,
+
+    /// Route network access of sandboxed shell commands through a
+    /// per-command network proxy that asks before reaching new hosts.
+    pub sandbox_network_proxy: bool,
+
     pub shell_environment_policy: ShellEnvironmentPolicy,
 
     /// When `true`, `AgentReasoning` events emitted by the backend will be
@@ -203,6 +234,11 @@ pub struct Config {
     /// If unset the feature is disabled.
     pub notify: Option<Vec<String>>,
 
+    /// `[hooks]`: commands run at session start, prompt submission, around
+    /// tool calls, at turn completion and before compaction. Matchers have
+    /// been checked to compile.
+    pub hooks: HooksToml,
+
     /// TUI notifications preference. When set, the TUI will send terminal notifications on
     /// approvals and turn completions when not focused.
     pub tui_notifications: Notifications,
@@ -267,6 +303,17 @@ pub struct Config {
     /// Token budget applied when storing tool/function outputs in the context manager.
     pub tool_output_token_limit: Option<usize>,
 
+    /// Number of consecutive tool calls with unparseable arguments tolerated in a
+    /// turn before the turn is stopped.
+    pub max_malformed_tool_calls: u32,
+
+    /// Global `[tool_loop_detection]` thresholds; see
+    /// [`Config::tool_loop_detection`] for the per-model result.
+    pub tool_loop_detection: ToolLoopDetectionToml,
+
+    /// How `sampling/createMessage` requests from MCP servers are answered.
+    pub mcp_sampling: McpSamplingConfig,
+
     /// Maximum number of agent threads that can be open concurrently.
     pub agent_max_threads: Option<usize>,
 
@@ -306,6 +353,10 @@ pub struct Config {
     /// Optional verbosity control for GPT-5 models (Responses API `text.verbosity`).
     pub model_verbosity: Option<Verbosity>,
 
+    /// Sampling parameters for model requests: the provider's `sampling`
+    /// table with the active profile's overrides applied.
+    pub model_sampling: SamplingParams,
+
     /// Base URL for requests to ChatGPT (as opposed to the OpenAI API).
     pub chatgpt_base_url: String,
 
@@ -326,6 +377,16 @@ pub struct Config {
     /// URL for the local SearXNG instance used for web searches.
     pub searxng_url: String,
 
+    /// Enabled backends from `[web_search.backends]`. When empty, the web
+    /// search tool queries the SearXNG instance at `searxng_url`.
+    pub web_search_backends: BTreeMap<String, WebSearchBackendConfig>,
+
+    /// On-disk cache used by the web search tool (`[web_search.cache]`).
+    pub web_search_cache: WebSearchCacheConfig,
+
+    /// Hosts the web search tool may fetch pages from (`[web_search.policy]`).
+    pub web_search_policy: NetworkPolicy,
+
     /// If set to `true`, used only the experimental unified exec tool.
     pub use_experimental_unified_exec_tool: bool,
 
@@ -767,11 +828,13 @@ pub fn set_default_oss_provider(trill_home: &Path, provider: &str) -> std::io::R
         LMSTUDIO_OSS_PROVIDER_ID | OLLAMA_OSS_PROVIDER_ID | OLLAMA_CHAT_PROVIDER_ID => {
             // Valid provider, continue
         }
+        _ if OPENAI_COMPATIBLE_OSS_PROVIDER_IDS.contains(&provider) => {}
         _ => {
+            let local_servers = OPENAI_COMPATIBLE_OSS_PROVIDER_IDS.join(", ");
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
                 format!(
-                    "Invalid OSS provider '{provider}'. Must be one of: {LMSTUDIO_OSS_PROVIDER_ID}, {OLLAMA_OSS_PROVIDER_ID}, {OLLAMA_CHAT_PROVIDER_ID}"
+                    "Invalid OSS provider '{provider}'. Must be one of: {LMSTUDIO_OSS_PROVIDER_ID}, {OLLAMA_OSS_PROVIDER_ID}, {OLLAMA_CHAT_PROVIDER_ID}, {local_servers}"
                 ),
             ));
         }
@@ -824,10 +887,23 @@ pub struct ConfigToml {
     /// Sandbox configuration to apply if `sandbox` is `WorkspaceWrite`.
     pub sandbox_workspace_write: Option<SandboxWorkspaceWrite>,
 
+    /// Paths and globs the agent must not read. Enforced by the Linux sandbox
+    /// and by the file tools regardless of `sandbox_mode`.
+    pub sandbox_deny_read: Option<Vec<String>>,
+
+    /// When the sandbox blocks network access, let shell commands reach hosts
+    /// allowed by `[network_proxy.policy]` through a proxy, and ask before
+    /// connecting to any other host. Linux only.
+    pub sandbox_network_proxy: Option<bool>,
+
     /// Optional external command to spawn for end-user notifications.
     #[serde(default)]
     pub notify: Option<Vec<String>>,
 
+    /// Commands run at points in the session lifecycle, with the event as
+    /// JSON on stdin.
+    pub hooks: Option<HooksToml>,
+
     /// System instructions.
     pub instructions: Option<String>,
 
@@ -890,6 +966,17 @@ pub struct ConfigToml {
     /// Token budget applied when storing tool/function outputs in the context manager.
     pub tool_output_token_limit: Option<usize>,
 
+    /// Number of consecutive tool calls with unparseable arguments tolerated in a
+    /// turn before the turn is stopped. Defaults to 3.
+    pub max_malformed_tool_calls: Option<u32>,
+
+    /// Thresholds for stopping a model that keeps repeating the same tool
+    /// calls.
+    pub tool_loop_detection: Option<ToolLoopDetectionToml>,
+
+    /// Settings for MCP servers that ask to sample the session's model.
+    pub mcp_sampling: Option<McpSamplingToml>,
+
     /// Profile to use from the `profiles` map.
     pub profile: Option<String>,
 
@@ -933,8 +1020,9 @@ pub struct ConfigToml {
 
     pub projects: Option<HashMap<String, ProjectConfig>>,
 
-    /// Controls the web search tool mode: disabled, cached, or live.
-    pub web_search: Option<WebSearchMode>,
+    /// Controls the web search tool: either a mode (disabled, cached, or live)
+    /// or a `[web_search]` table with a `mode` and search `backends`.
+    pub web_search: Option<WebSearchToml>,
 
     /// URL for the local SearXNG instance used for web searches.
     /// Defaults to "http://127.0.0.1:8080" if not set.
@@ -1059,6 +1147,18 @@ pub struct ModelSettings {
     /// Token usage threshold triggering auto-compaction of conversation history
     /// for this specific model. Overrides the global `model_auto_compact_token_limit`.
     pub auto_compact_token_limit: Option<i64>,
+
+    /// Tool loop detection thresholds for this specific model. Fields set here
+    /// override the global `[tool_loop_detection]` table.
+    pub tool_loop_detection: Option<ToolLoopDetectionToml>,
+
+    /// Seconds of inactivity after which LM Studio unloads this model when
+    /// `--oss` loaded it.
+    pub ttl_sec: Option<u64>,
+
+    /// Fraction of this model LM Studio offloads to the GPU when `--oss`
+    /// loads it, from 0.0 to 1.0.
+    pub gpu_offload: Option<f64>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
@@ -1267,7 +1367,11 @@ fn resolve_web_search_mode(
     config_profile: &ConfigProfile,
     features: &Features,
 ) -> Option<WebSearchMode> {
-    if let Some(mode) = config_profile.web_search.or(config_toml.web_search) {
+    let config_mode = config_toml
+        .web_search
+        .as_ref()
+        .and_then(WebSearchToml::mode);
+    if let Some(mode) = config_profile.web_search.or(config_mode) {
         return Some(mode);
     }
     if features.enabled(Feature::WebSearchCached) {
@@ -1279,6 +1383,92 @@ fn resolve_web_search_mode(
     None
 }
 
+/// Resolves `[web_search.policy]`, rejecting domain patterns that do not
+/// compile.
+fn resolve_web_search_policy(config_toml: &ConfigToml) -> std::io::Result<NetworkPolicy> {
+    let policy = config_toml
+        .web_search
+        .as_ref()
+        .and_then(WebSearchToml::policy)
+        .cloned()
+        .unwrap_or_default()
+        .to_network_policy();
+    DomainPolicy::new(policy.clone()).map_err(|err| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("invalid web_search.policy: {err:#}"),
+        )
+    })?;
+    Ok(policy)
+}
+
+/// Returns `[hooks]`, rejecting hooks without a command and matchers that
+/// do not compile.
+fn resolve_hooks(config_toml: &ConfigToml) -> std::io::Result<HooksToml> {
+    let hooks = config_toml.hooks.clone().unwrap_or_default();
+    let events = [
+        ("session_start", &hooks.session_start),
+        ("user_prompt_submit", &hooks.user_prompt_submit),
+        ("pre_tool_use", &hooks.pre_tool_use),
+        ("post_tool_use", &hooks.post_tool_use),
+        ("turn_complete", &hooks.turn_complete),
+        ("pre_compact", &hooks.pre_compact),
+    ];
+    for (event, commands) in events {
+        for (idx, hook) in commands.iter().enumerate() {
+            if hook.command.first().is_none_or(String::is_empty) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("hooks.{event}[{idx}].command must name a program"),
+                ));
+            }
+            if let Some(matcher) = &hook.matcher
+                && let Err(err) = crate::hooks::compile_matcher(matcher)
+            {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("hooks.{event}[{idx}].matcher is not a valid regex: {err}"),
+                ));
+            }
+        }
+    }
+    Ok(hooks)
+}
+
+/// Collects the enabled `[web_search.backends]` entries, rejecting entries
+/// that cannot be queried.
+fn resolve_web_search_backends(
+    config_toml: &ConfigToml,
+) -> std::io::Result<BTreeMap<String, WebSearchBackendConfig>> {
+    let Some(backends) = config_toml
+        .web_search
+        .as_ref()
+        .and_then(WebSearchToml::backends)
+    else {
+        return Ok(BTreeMap::new());
+    };
+    let mut enabled = BTreeMap::new();
+    for (name, backend) in backends {
+        if !backend.enabled {
+            continue;
+        }
+        if let Err(err) = url::Url::parse(&backend.url) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("web_search.backends.{name}.url is not a valid URL: {err}"),
+            ));
+        }
+        if backend.kind == WebSearchBackendKind::Elasticsearch && backend.index.is_none() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("web_search.backends.{name}.index is required for elasticsearch backends"),
+            ));
+        }
+        enabled.insert(name.clone(), backend.clone());
+    }
+    Ok(enabled)
+}
+
 pub(crate) fn resolve_web_search_mode_for_turn(
     explicit_mode: Option<WebSearchMode>,
     is_azure_responses_endpoint: bool,
@@ -1419,6 +1609,13 @@ impl Config {
                 }
             });
         let web_search_mode = resolve_web_search_mode(&cfg, &config_profile, &features);
+        let web_search_backends = resolve_web_search_backends(&cfg)?;
+        let web_search_cache = WebSearchCacheConfig::new(
+            &trill_home,
+            cfg.web_search.as_ref().and_then(WebSearchToml::cache),
+        );
+        let web_search_policy = resolve_web_search_policy(&cfg)?;
+        let hooks = resolve_hooks(&cfg)?;
         // TODO(dylan): We should be able to leverage ConfigLayerStack so that
         // we can reliably check this at every config level.
         let did_user_set_custom_approval_policy_or_sandbox_mode = approval_policy_override
@@ -1449,6 +1646,11 @@ impl Config {
                 )
             })?
             .clone();
+        let provider_sampling = model_provider.sampling.clone().unwrap_or_default();
+        let model_sampling = match &config_profile.sampling {
+            Some(overrides) => provider_sampling.merged_with(overrides),
+            None => provider_sampling,
+        };
 
         let shell_environment_policy = cfg.shell_environment_policy.into();
 
@@ -1580,8 +1782,11 @@ impl Config {
             enforce_residency,
             did_user_set_custom_approval_policy_or_sandbox_mode,
             forced_auto_mode_downgraded_on_windows,
+            sandbox_deny_read: sandbox_deny_read(cfg.sandbox_deny_read, &trill_home)?,
+            sandbox_network_proxy: cfg.sandbox_network_proxy.unwrap_or(false),
             shell_environment_policy,
             notify: cfg.notify,
+            hooks,
             user_instructions,
             base_instructions,
             model_personality,
@@ -1611,6 +1816,11 @@ impl Config {
                 })
                 .collect(),
             tool_output_token_limit: cfg.tool_output_token_limit,
+            max_malformed_tool_calls: cfg
+                .max_malformed_tool_calls
+                .unwrap_or(DEFAULT_MAX_MALFORMED_TOOL_CALLS),
+            tool_loop_detection: cfg.tool_loop_detection.unwrap_or_default(),
+            mcp_sampling: cfg.mcp_sampling.unwrap_or_default().into(),
             agent_max_threads,
             trill_home,
             config_layer_stack,
@@ -1633,6 +1843,7 @@ impl Config {
                 .unwrap_or_default(),
             model_supports_reasoning_summaries: cfg.model_supports_reasoning_summaries,
             model_verbosity: config_profile.model_verbosity.or(cfg.model_verbosity),
+            model_sampling,
             chatgpt_base_url: config_profile
                 .chatgpt_base_url
                 .or(cfg.chatgpt_base_url)
@@ -1645,6 +1856,9 @@ impl Config {
                 .searxng_url
                 .clone()
                 .unwrap_or_else(|| "http://127.0.0.1:8080".to_string()),
+            web_search_backends,
+            web_search_cache,
+            web_search_policy,
             use_experimental_unified_exec_tool,
             ghost_snapshot,
             features,
@@ -1749,6 +1963,34 @@ impl Config {
         }
     }
 
+    /// Tool loop detection thresholds for `model`: its
+    /// `[model_settings."<id>".tool_loop_detection]` over the global table.
+    pub fn tool_loop_detection(&self, model: &str) -> ToolLoopDetection {
+        let merged = match self
+            .model_settings
+            .get(model)
+            .and_then(|settings| settings.tool_loop_detection.as_ref())
+        {
+            Some(overrides) => self.tool_loop_detection.merged_with(overrides),
+            None => self.tool_loop_detection.clone(),
+        };
+        ToolLoopDetection::from(&merged)
+    }
+
+    /// Backends queried by the web search tool: the enabled
+    /// `[web_search.backends]`, or the SearXNG instance at `searxng_url` when
+    /// none are configured.
+    pub fn effective_web_search_backends(&self) -> BTreeMap<String, WebSearchBackendConfig> {
+        if self.web_search_backends.is_empty() {
+            BTreeMap::from([(
+                "searxng".to_string(),
+                WebSearchBackendConfig::searxng(self.searxng_url.clone()),
+            )])
+        } else {
+            self.web_search_backends.clone()
+        }
+    }
+
     pub fn set_windows_sandbox_enabled(&mut self, value: bool) {
         if value {
             self.features.enable(Feature::WindowsSandbox);
@@ -1792,6 +2034,19 @@ fn toml_uses_deprecated_instructions_file(value: &TomlValue) -> bool {
     })
 }
 
+/// The configured `sandbox_deny_read` patterns plus the network proxy's CA key,
+/// rejecting entries that are bare names rather than paths.
+fn sandbox_deny_read(
+    patterns: Option<Vec<String>>,
+    trill_home: &Path,
+) -> std::io::Result<DenyRead> {
+    let mut patterns = patterns.unwrap_or_default();
+    let ca_key = trill_home.join(NETWORK_PROXY_DIR).join(MITM_CA_KEY_FILE);
+    patterns.push(ca_key.to_string_lossy().into_owned());
+    DenyRead::new(patterns)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))
+}
+
 /// Returns the path to the Codex configuration directory, which can be
 /// specified by the `CODEX_HOME` environment variable. If not set, defaults to
 /// `~/.trill`.
@@ -1822,6 +2077,7 @@ mod tests {
     use crate::config::types::McpServerTransportConfig;
     use crate::config::types::NotificationMethod;
     use crate::config::types::Notifications;
+    use crate::config::types::HookCommandToml;
     use crate::config_loader::RequirementSource;
     use crate::features::Feature;
 
@@ -2364,7 +2620,7 @@ trust_level = "trusted"
     #[test]
     fn web_search_mode_disabled_overrides_legacy_request() {
         let cfg = ConfigToml {
-            web_search: Some(WebSearchMode::Disabled),
+            web_search: Some(WebSearchMode::Disabled.into()),
             ..Default::default()
         };
         let profile = ConfigProfile::default();
@@ -2377,6 +2633,277 @@ trust_level = "trusted"
         );
     }
 
+    #[test]
+    fn web_search_table_configures_mode_and_backends() -> std::io::Result<()> {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[web_search]
+mode = "live"
+
+[web_search.backends.searx]
+type = "searxng"
+url = "http://127.0.0.1:8080"
+engines = ["duckduckgo", "wikipedia"]
+time_range = "month"
+safesearch = "moderate"
+
+[web_search.backends.docs]
+type = "elasticsearch"
+url = "http://127.0.0.1:9200"
+index = "docs"
+
+[web_search.backends.peer]
+type = "yacy"
+url = "http://127.0.0.1:8090"
+enabled = false
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        assert_eq!(
+            resolve_web_search_mode(&cfg, &ConfigProfile::default(), &Features::with_defaults()),
+            Some(WebSearchMode::Live)
+        );
+        let backends = resolve_web_search_backends(&cfg)?;
+        assert_eq!(backends.keys().collect::<Vec<_>>(), vec!["docs", "searx"]);
+        let searx = &backends["searx"];
+        assert_eq!(searx.kind, WebSearchBackendKind::Searxng);
+        assert_eq!(searx.engines, vec!["duckduckgo", "wikipedia"]);
+        assert_eq!(
+            searx.time_range,
+            Some(crate::config::types::WebSearchTimeRange::Month)
+        );
+
+        let bare = toml::from_str::<ConfigToml>(r#"web_search = "cached""#)
+            .expect("TOML deserialization should succeed");
+        assert_eq!(bare.web_search, Some(WebSearchMode::Cached.into()));
+        assert_eq!(resolve_web_search_backends(&bare)?, BTreeMap::new());
+        Ok(())
+    }
+
+    #[test]
+    fn web_search_cache_applies_defaults_and_overrides() {
+        let trill_home = Path::new("/home/user/.trill");
+        let defaults = WebSearchCacheConfig::new(trill_home, None);
+        assert_eq!(
+            defaults,
+            WebSearchCacheConfig {
+                dir: trill_home.join("cache").join("web"),
+                ttl: crate::config::types::DEFAULT_WEB_CACHE_TTL,
+                max_bytes: 256 * 1024 * 1024,
+                offline: false,
+            }
+        );
+
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[web_search]
+mode = "cached"
+
+[web_search.cache]
+ttl_sec = 600
+max_size_mb = 1
+offline = true
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let cache = WebSearchCacheConfig::new(
+            trill_home,
+            cfg.web_search.as_ref().and_then(WebSearchToml::cache),
+        );
+        assert_eq!(cache.ttl, Duration::from_secs(600));
+        assert_eq!(cache.max_bytes, 1024 * 1024);
+        assert!(cache.offline);
+    }
+
+    #[test]
+    fn profile_sampling_overrides_provider_sampling() -> std::io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+model_provider = "llamacpp"
+profile = "coder"
+
+[model_providers.llamacpp]
+name = "llama.cpp"
+base_url = "http://localhost:8080/v1"
+
+[model_providers.llamacpp.sampling]
+temperature = 0.7
+top_k = 20
+stop = ["<|im_end|>"]
+extra_body = { cache_prompt = true }
+
+[profiles.coder.sampling]
+temperature = 0.2
+extra_body = { grammar = "root ::= [a-z]+" }
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.model_sampling,
+            SamplingParams {
+                temperature: Some(0.2),
+                top_k: Some(20),
+                stop: Some(vec!["<|im_end|>".to_string()]),
+                extra_body: BTreeMap::from([
+                    ("cache_prompt".to_string(), serde_json::Value::Bool(true)),
+                    (
+                        "grammar".to_string(),
+                        serde_json::Value::from("root ::= [a-z]+"),
+                    ),
+                ]),
+                ..SamplingParams::default()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn model_settings_override_tool_loop_detection() -> std::io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[tool_loop_detection]
+repeats = 4
+max_warnings = 2
+
+[model_settings."qwen2.5-coder".tool_loop_detection]
+repeats = 2
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.tool_loop_detection("qwen2.5-coder"),
+            ToolLoopDetection {
+                enabled: true,
+                repeats: 2,
+                max_cycle_length: 3,
+                max_warnings: 2,
+            }
+        );
+        assert_eq!(config.tool_loop_detection("other").repeats, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn hooks_are_parsed_and_matchers_validated() -> std::io::Result<()> {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[[hooks.pre_tool_use]]
+command = ["python3", "guard.py"]
+matcher = "shell|apply_patch"
+timeout_sec = 5
+
+[[hooks.turn_complete]]
+command = ["notify.sh"]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let hooks = resolve_hooks(&cfg)?;
+        assert_eq!(
+            hooks.pre_tool_use,
+            vec![HookCommandToml {
+                command: vec!["python3".to_string(), "guard.py".to_string()],
+                matcher: Some("shell|apply_patch".to_string()),
+                timeout_sec: Some(5),
+            }]
+        );
+        assert_eq!(hooks.turn_complete.len(), 1);
+        assert!(hooks.session_start.is_empty());
+
+        let invalid_matcher = toml::from_str::<ConfigToml>(
+            r#"
+[[hooks.post_tool_use]]
+command = ["log.sh"]
+matcher = "(shell"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        assert!(resolve_hooks(&invalid_matcher).is_err());
+
+        let empty_command = toml::from_str::<ConfigToml>(
+            r#"
+[[hooks.session_start]]
+command = []
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        assert!(resolve_hooks(&empty_command).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn web_search_policy_defaults_to_public_hosts() -> std::io::Result<()> {
+        let bare = toml::from_str::<ConfigToml>(r#"web_search = "live""#)
+            .expect("TOML deserialization should succeed");
+        assert_eq!(
+            resolve_web_search_policy(&bare)?,
+            NetworkPolicy {
+                allowed_domains: vec!["*".to_string()],
+                ..NetworkPolicy::default()
+            }
+        );
+
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[web_search.policy]
+allowed_domains = ["**.rust-lang.org", "docs.rs"]
+denied_domains = ["play.rust-lang.org"]
+allow_local_binding = true
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        assert_eq!(
+            resolve_web_search_policy(&cfg)?,
+            NetworkPolicy {
+                allowed_domains: vec!["**.rust-lang.org".to_string(), "docs.rs".to_string()],
+                denied_domains: vec!["play.rust-lang.org".to_string()],
+                allow_unix_sockets: Vec::new(),
+                allow_local_binding: true,
+            }
+        );
+
+        let invalid = toml::from_str::<ConfigToml>(
+            r#"
+[web_search.policy]
+denied_domains = ["[bad"]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        assert!(resolve_web_search_policy(&invalid).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn web_search_elasticsearch_backend_requires_index() {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[web_search.backends.docs]
+type = "elasticsearch"
+url = "http://127.0.0.1:9200"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let err = resolve_web_search_backends(&cfg).expect_err("missing index should fail");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
     #[test]
     fn web_search_mode_for_turn_defaults_to_cached_when_unset() {
         let mode = resolve_web_search_mode_for_turn(None, false, &SandboxPolicy::ReadOnly);
@@ -3754,6 +4281,11 @@ model_verbosity = "high"
             stream_idle_timeout_ms: Some(300_000),
             requires_openai_auth: false,
             supports_websockets: false,
+            reasoning_tags: None,
+            sampling: None,
+            structured_output: None,
+            chat_capabilities: None,
+            tool_call_format: None,
         };
         let model_provider_map = {
             let mut model_provider_map = built_in_model_providers();
@@ -3819,9 +4351,12 @@ model_verbosity = "high"
                 enforce_residency: Constrained::allow_any(None),
                 did_user_set_custom_approval_policy_or_sandbox_mode: true,
                 forced_auto_mode_downgraded_on_windows: false,
+                sandbox_deny_read: sandbox_deny_read(None, &fixture.trill_home())?,
+                sandbox_network_proxy: false,
                 shell_environment_policy: ShellEnvironmentPolicy::default(),
                 user_instructions: None,
                 notify: None,
+                hooks: HooksToml::default(),
                 cwd: fixture.cwd(),
                 cli_auth_credentials_store_mode: Default::default(),
                 mcp_servers: Constrained::allow_any(HashMap::new()),
@@ -3831,6 +4366,9 @@ model_verbosity = "high"
                 project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
                 project_doc_fallback_filenames: Vec::new(),
                 tool_output_token_limit: None,
+                max_malformed_tool_calls: DEFAULT_MAX_MALFORMED_TOOL_CALLS,
+                tool_loop_detection: ToolLoopDetectionToml::default(),
+                mcp_sampling: McpSamplingConfig::default(),
                 agent_max_threads: DEFAULT_AGENT_MAX_THREADS,
                 trill_home: fixture.trill_home(),
                 config_layer_stack: Default::default(),
@@ -3845,6 +4383,7 @@ model_verbosity = "high"
                 model_supports_reasoning_summaries: None,
                 model_verbosity: None,
                 model_personality: None,
+                model_sampling: SamplingParams::default(),
                 chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
                 base_instructions: None,
                 developer_instructions: None,
@@ -3854,6 +4393,12 @@ model_verbosity = "high"
                 include_apply_patch_tool: false,
                 web_search_mode: None,
                 searxng_url: "http://127.0.0.1:8080".to_string(),
+                web_search_backends: BTreeMap::new(),
+                web_search_cache: WebSearchCacheConfig::new(&fixture.trill_home(), None),
+                web_search_policy: NetworkPolicy {
+                    allowed_domains: vec!["*".to_string()],
+                    ..NetworkPolicy::default()
+                },
                 use_experimental_unified_exec_tool: false,
                 ghost_snapshot: GhostSnapshotConfig::default(),
                 features: Features::with_defaults(),
@@ -3906,9 +4451,12 @@ model_verbosity = "high"
             enforce_residency: Constrained::allow_any(None),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
+            sandbox_deny_read: sandbox_deny_read(None, &fixture.trill_home())?,
+            sandbox_network_proxy: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: HooksToml::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: Constrained::allow_any(HashMap::new()),
@@ -3918,6 +4466,9 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            max_malformed_tool_calls: DEFAULT_MAX_MALFORMED_TOOL_CALLS,
+            tool_loop_detection: ToolLoopDetectionToml::default(),
+            mcp_sampling: McpSamplingConfig::default(),
             agent_max_threads: DEFAULT_AGENT_MAX_THREADS,
             trill_home: fixture.trill_home(),
             config_layer_stack: Default::default(),
@@ -3932,6 +4483,7 @@ model_verbosity = "high"
             model_supports_reasoning_summaries: None,
             model_verbosity: None,
             model_personality: None,
+            model_sampling: SamplingParams::default(),
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
             base_instructions: None,
             developer_instructions: None,
@@ -3940,6 +4492,13 @@ model_verbosity = "high"
             forced_login_method: None,
             include_apply_patch_tool: false,
             web_search_mode: None,
+            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
+            web_search_cache: WebSearchCacheConfig::new(&fixture.trill_home(), None),
+            web_search_policy: NetworkPolicy {
+                allowed_domains: vec!["*".to_string()],
+                ..NetworkPolicy::default()
+            },
             use_experimental_unified_exec_tool: false,
             ghost_snapshot: GhostSnapshotConfig::default(),
             features: Features::with_defaults(),
@@ -4007,9 +4566,12 @@ model_verbosity = "high"
             enforce_residency: Constrained::allow_any(None),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
+            sandbox_deny_read: sandbox_deny_read(None, &fixture.trill_home())?,
+            sandbox_network_proxy: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: HooksToml::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: Constrained::allow_any(HashMap::new()),
@@ -4019,6 +4581,9 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            max_malformed_tool_calls: DEFAULT_MAX_MALFORMED_TOOL_CALLS,
+            tool_loop_detection: ToolLoopDetectionToml::default(),
+            mcp_sampling: McpSamplingConfig::default(),
             agent_max_threads: DEFAULT_AGENT_MAX_THREADS,
             trill_home: fixture.trill_home(),
             config_layer_stack: Default::default(),
@@ -4033,6 +4598,7 @@ model_verbosity = "high"
             model_supports_reasoning_summaries: None,
             model_verbosity: None,
             model_personality: None,
+            model_sampling: SamplingParams::default(),
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
             base_instructions: None,
             developer_instructions: None,
@@ -4041,6 +4607,13 @@ model_verbosity = "high"
             forced_login_method: None,
             include_apply_patch_tool: false,
             web_search_mode: None,
+            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
+            web_search_cache: WebSearchCacheConfig::new(&fixture.trill_home(), None),
+            web_search_policy: NetworkPolicy {
+                allowed_domains: vec!["*".to_string()],
+                ..NetworkPolicy::default()
+            },
             use_experimental_unified_exec_tool: false,
             ghost_snapshot: GhostSnapshotConfig::default(),
             features: Features::with_defaults(),
@@ -4094,9 +4667,12 @@ model_verbosity = "high"
             enforce_residency: Constrained::allow_any(None),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
+            sandbox_deny_read: sandbox_deny_read(None, &fixture.trill_home())?,
+            sandbox_network_proxy: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: HooksToml::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: Constrained::allow_any(HashMap::new()),
@@ -4106,6 +4682,9 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            max_malformed_tool_calls: DEFAULT_MAX_MALFORMED_TOOL_CALLS,
+            tool_loop_detection: ToolLoopDetectionToml::default(),
+            mcp_sampling: McpSamplingConfig::default(),
             agent_max_threads: DEFAULT_AGENT_MAX_THREADS,
             trill_home: fixture.trill_home(),
             config_layer_stack: Default::default(),
@@ -4120,6 +4699,7 @@ model_verbosity = "high"
             model_supports_reasoning_summaries: None,
             model_verbosity: Some(Verbosity::High),
             model_personality: None,
+            model_sampling: SamplingParams::default(),
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
             base_instructions: None,
             developer_instructions: None,
@@ -4128,6 +4708,13 @@ model_verbosity = "high"
             forced_login_method: None,
             include_apply_patch_tool: false,
             web_search_mode: None,
+            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
+            web_search_cache: WebSearchCacheConfig::new(&fixture.trill_home(), None),
+            web_search_policy: NetworkPolicy {
+                allowed_domains: vec!["*".to_string()],
+                ..NetworkPolicy::default()
+            },
             use_experimental_unified_exec_tool: false,
             ghost_snapshot: GhostSnapshotConfig::default(),
             features: Features::with_defaults(),
@@ -4,6 +4,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::config::types::Personality;
+use crate::config::types::SamplingParams;
 use crate::protocol::AskForApproval;
 use trill_protocol::config_types::ReasoningSummary;
 use trill_protocol::config_types::SandboxMode;
@@ -26,6 +27,8 @@ pub struct ConfigProfile {
     pub model_reasoning_summary: Option<ReasoningSummary>,
     pub model_verbosity: Option<Verbosity>,
     pub model_personality: Option<Personality>,
+    /// Overrides for the provider's `sampling` table.
+    pub sampling: Option<SamplingParams>,
     pub chatgpt_base_url: Option<String>,
     /// Optional path to a file containing model instructions.
     pub model_instructions_file: Option<AbsolutePathBuf>,
@@ -8,10 +8,12 @@ pub use trill_protocol::config_types::AltScreenMode;
 pub use trill_protocol::config_types::ModeKind;
 pub use trill_protocol::config_types::Personality;
 pub use trill_protocol::config_types::WebSearchMode;
+use trill_network_policy::NetworkPolicy;
 use trill_utils_absolute_path::AbsolutePathBuf;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::fmt;
+use std::path::Path;
 use std::path::PathBuf;
 use std::time::Duration;
 use wildmatch::WildMatchPattern;
@@ -415,6 +417,504 @@ impl Default for OtelConfig {
     }
 }
 
+// ===== Sampling configuration =====
+
+/// Sampling parameters sent with each model request, set under
+/// `[model_providers.<id>.sampling]` and overridden per profile with
+/// `[profiles.<name>.sampling]`. Unset fields are not sent.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SamplingParams {
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub top_k: Option<u64>,
+    pub min_p: Option<f64>,
+    pub repeat_penalty: Option<f64>,
+    pub seed: Option<i64>,
+    /// Maximum tokens to generate. Sent as `max_output_tokens` on the
+    /// Responses API.
+    pub max_tokens: Option<u64>,
+    pub stop: Option<Vec<String>>,
+    /// Provider-specific fields added to the request body as-is, e.g.
+    /// llama.cpp `grammar` or vLLM `guided_json`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra_body: BTreeMap<String, serde_json::Value>,
+}
+
+impl SamplingParams {
+    /// Returns `self` with every field set in `overrides` replaced.
+    /// `extra_body` entries are merged key by key.
+    pub fn merged_with(&self, overrides: &SamplingParams) -> SamplingParams {
+        let mut extra_body = self.extra_body.clone();
+        extra_body.extend(overrides.extra_body.clone());
+        SamplingParams {
+            temperature: overrides.temperature.or(self.temperature),
+            top_p: overrides.top_p.or(self.top_p),
+            top_k: overrides.top_k.or(self.top_k),
+            min_p: overrides.min_p.or(self.min_p),
+            repeat_penalty: overrides.repeat_penalty.or(self.repeat_penalty),
+            seed: overrides.seed.or(self.seed),
+            max_tokens: overrides.max_tokens.or(self.max_tokens),
+            stop: overrides.stop.clone().or_else(|| self.stop.clone()),
+            extra_body,
+        }
+    }
+}
+
+// ===== Tool loop detection =====
+
+/// `[tool_loop_detection]` in config.toml, overridden per model under
+/// `[model_settings."<id>".tool_loop_detection]`. Unset fields fall back to
+/// the global table, then to the defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ToolLoopDetectionToml {
+    /// Set to `false` to let the model repeat tool calls freely.
+    pub enabled: Option<bool>,
+
+    /// Times the same call, or the same cycle of calls, must occur in a row
+    /// to count as a loop. Defaults to 3.
+    pub repeats: Option<u32>,
+
+    /// Longest cycle of distinct calls to look for, e.g. 2 for a model
+    /// alternating between two calls. Defaults to 3.
+    pub max_cycle_length: Option<u32>,
+
+    /// Corrective messages sent to the model before the turn is ended.
+    /// Defaults to 1.
+    pub max_warnings: Option<u32>,
+}
+
+impl ToolLoopDetectionToml {
+    /// Returns `self` with every field set in `overrides` replaced.
+    pub fn merged_with(&self, overrides: &ToolLoopDetectionToml) -> ToolLoopDetectionToml {
+        ToolLoopDetectionToml {
+            enabled: overrides.enabled.or(self.enabled),
+            repeats: overrides.repeats.or(self.repeats),
+            max_cycle_length: overrides.max_cycle_length.or(self.max_cycle_length),
+            max_warnings: overrides.max_warnings.or(self.max_warnings),
+        }
+    }
+}
+
+/// Resolved tool loop detection thresholds for one model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToolLoopDetection {
+    pub enabled: bool,
+    pub repeats: usize,
+    pub max_cycle_length: usize,
+    pub max_warnings: usize,
+}
+
+impl From<&ToolLoopDetectionToml> for ToolLoopDetection {
+    fn from(toml: &ToolLoopDetectionToml) -> Self {
+        let count = |value: Option<u32>, default: usize| {
+            value.map_or(default, |value| {
+                usize::try_from(value).unwrap_or(usize::MAX)
+            })
+        };
+        Self {
+            enabled: toml.enabled.unwrap_or(true),
+            repeats: count(toml.repeats, 3).max(2),
+            max_cycle_length: count(toml.max_cycle_length, 3).max(1),
+            max_warnings: count(toml.max_warnings, 1),
+        }
+    }
+}
+
+// ===== MCP sampling =====
+
+/// Default cap on `maxTokens` for one MCP sampling request.
+pub const DEFAULT_MCP_SAMPLING_MAX_TOKENS: u32 = 4096;
+
+/// `[mcp_sampling]` in config.toml: how `sampling/createMessage` requests
+/// from MCP servers are answered.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct McpSamplingToml {
+    /// Set to `false` to stop advertising the sampling capability to MCP
+    /// servers.
+    pub enabled: Option<bool>,
+
+    /// Upper bound on the `maxTokens` a server may request. Defaults to 4096.
+    pub max_tokens: Option<u32>,
+
+    /// Maps model hints sent by servers to configured models, e.g.
+    /// `claude = "qwen3-coder-30b"`. A key matches any hint that contains it.
+    #[serde(default)]
+    pub models: BTreeMap<String, String>,
+}
+
+/// Resolved `[mcp_sampling]` settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct McpSamplingConfig {
+    pub enabled: bool,
+    pub max_tokens: u32,
+    pub models: BTreeMap<String, String>,
+}
+
+impl Default for McpSamplingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_tokens: DEFAULT_MCP_SAMPLING_MAX_TOKENS,
+            models: BTreeMap::new(),
+        }
+    }
+}
+
+impl From<McpSamplingToml> for McpSamplingConfig {
+    fn from(toml: McpSamplingToml) -> Self {
+        Self {
+            enabled: toml.enabled.unwrap_or(true),
+            max_tokens: toml
+                .max_tokens
+                .unwrap_or(DEFAULT_MCP_SAMPLING_MAX_TOKENS)
+                .max(1),
+            models: toml.models,
+        }
+    }
+}
+
+// ===== Hooks =====
+
+/// Seconds a hook may run before it is killed, unless it sets `timeout_sec`.
+pub const DEFAULT_HOOK_TIMEOUT_SEC: u64 = 60;
+
+/// `[hooks]` in config.toml: commands run at points in a session's
+/// lifecycle. Each event takes a list of hooks, run in order.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct HooksToml {
+    /// Run once when a session starts or is resumed.
+    #[serde(default)]
+    pub session_start: Vec<HookCommandToml>,
+
+    /// Run before a user message starts a turn. Hooks may add context for
+    /// the model.
+    #[serde(default)]
+    pub user_prompt_submit: Vec<HookCommandToml>,
+
+    /// Run before a tool call. Hooks may block the call or rewrite its
+    /// arguments.
+    #[serde(default)]
+    pub pre_tool_use: Vec<HookCommandToml>,
+
+    /// Run after a tool call with its output.
+    #[serde(default)]
+    pub post_tool_use: Vec<HookCommandToml>,
+
+    /// Run when the agent finishes a turn.
+    #[serde(default)]
+    pub turn_complete: Vec<HookCommandToml>,
+
+    /// Run before the conversation history is compacted.
+    #[serde(default)]
+    pub pre_compact: Vec<HookCommandToml>,
+}
+
+/// One hook command. The event is written to its stdin as JSON.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct HookCommandToml {
+    /// Program and arguments, e.g. `["python3", "/path/to/hook.py"]`.
+    pub command: Vec<String>,
+
+    /// Regex that must match the whole tool name, e.g. `"shell|apply_patch"`.
+    /// Only used by `pre_tool_use` and `post_tool_use`; without it the hook
+    /// runs for every tool.
+    pub matcher: Option<String>,
+
+    /// Seconds to wait before the hook is killed. Defaults to 60.
+    pub timeout_sec: Option<u64>,
+}
+
+// ===== Web search configuration =====
+
+/// `web_search` in config.toml: either a bare mode (`web_search = "live"`) or a
+/// `[web_search]` table that also configures search backends.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(untagged)]
+pub enum WebSearchToml {
+    Mode(WebSearchMode),
+    Table(WebSearchConfigToml),
+}
+
+impl WebSearchToml {
+    pub fn mode(&self) -> Option<WebSearchMode> {
+        match self {
+            WebSearchToml::Mode(mode) => Some(*mode),
+            WebSearchToml::Table(table) => table.mode,
+        }
+    }
+
+    pub fn backends(&self) -> Option<&BTreeMap<String, WebSearchBackendConfig>> {
+        match self {
+            WebSearchToml::Mode(_) => None,
+            WebSearchToml::Table(table) => Some(&table.backends),
+        }
+    }
+
+    pub fn cache(&self) -> Option<&WebSearchCacheToml> {
+        match self {
+            WebSearchToml::Mode(_) => None,
+            WebSearchToml::Table(table) => table.cache.as_ref(),
+        }
+    }
+
+    pub fn policy(&self) -> Option<&WebSearchPolicyToml> {
+        match self {
+            WebSearchToml::Mode(_) => None,
+            WebSearchToml::Table(table) => table.policy.as_ref(),
+        }
+    }
+}
+
+impl From<WebSearchMode> for WebSearchToml {
+    fn from(mode: WebSearchMode) -> Self {
+        WebSearchToml::Mode(mode)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct WebSearchConfigToml {
+    /// Controls the web search tool mode: disabled, cached, or live.
+    pub mode: Option<WebSearchMode>,
+
+    /// Search backends keyed by a name of your choosing. Every enabled backend
+    /// is queried and the results are merged. When empty, the SearXNG instance
+    /// at `searxng_url` is used.
+    #[serde(default)]
+    pub backends: BTreeMap<String, WebSearchBackendConfig>,
+
+    /// On-disk cache of search results and fetched pages.
+    pub cache: Option<WebSearchCacheToml>,
+
+    /// Hosts `open_page` and `find_in_page` may fetch.
+    pub policy: Option<WebSearchPolicyToml>,
+}
+
+/// `[web_search.policy]`: which hosts pages may be fetched from. Patterns and
+/// precedence follow `[network_proxy.policy]`: denied domains always win, and
+/// loopback, link-local and private addresses are refused (on every redirect
+/// hop, and for hostnames that resolve to them) unless `allow_local_binding`
+/// is set or the address is listed exactly in `allowed_domains`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct WebSearchPolicyToml {
+    /// Domain patterns pages may be fetched from (`example.com`,
+    /// `*.example.com`, `**.example.com`). Defaults to `["*"]`, any public
+    /// host.
+    pub allowed_domains: Option<Vec<String>>,
+
+    /// Domain patterns that are never fetched.
+    #[serde(default)]
+    pub denied_domains: Vec<String>,
+
+    /// Allow fetching from loopback and private network addresses.
+    pub allow_local_binding: Option<bool>,
+}
+
+impl WebSearchPolicyToml {
+    /// Resolves the policy, allowing any public host when no allowlist is set.
+    pub fn to_network_policy(&self) -> NetworkPolicy {
+        NetworkPolicy {
+            allowed_domains: self
+                .allowed_domains
+                .clone()
+                .unwrap_or_else(|| vec!["*".to_string()]),
+            denied_domains: self.denied_domains.clone(),
+            allow_unix_sockets: Vec::new(),
+            allow_local_binding: self.allow_local_binding.unwrap_or(false),
+        }
+    }
+}
+
+/// `[web_search.cache]`: the on-disk cache under `~/.trill/cache/web`.
+///
+/// In `cached` mode, searches and page fetches are served from the cache while
+/// fresh and revalidated with `ETag`/`Last-Modified` once stale. In `live`
+/// mode the cache is only written, so later cached or offline sessions can
+/// replay it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct WebSearchCacheToml {
+    /// Seconds an entry is served without revalidation. Defaults to one day.
+    pub ttl_sec: Option<u64>,
+
+    /// Size cap in megabytes; least recently used entries are evicted beyond
+    /// it. Set to `0` to disable the cache. Defaults to 256.
+    pub max_size_mb: Option<u64>,
+
+    /// Serve only from the cache, regardless of age, and never touch the
+    /// network.
+    pub offline: Option<bool>,
+}
+
+/// Default lifetime of a web cache entry before it is revalidated.
+pub const DEFAULT_WEB_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Default size cap of the web cache, in megabytes.
+pub const DEFAULT_WEB_CACHE_MAX_SIZE_MB: u64 = 256;
+
+/// Resolved `[web_search.cache]` settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebSearchCacheConfig {
+    pub dir: PathBuf,
+    pub ttl: Duration,
+    pub max_bytes: u64,
+    pub offline: bool,
+}
+
+impl WebSearchCacheConfig {
+    /// Applies defaults to `toml`, storing entries under
+    /// `<trill_home>/cache/web`.
+    pub fn new(trill_home: &Path, toml: Option<&WebSearchCacheToml>) -> Self {
+        let toml = toml.cloned().unwrap_or_default();
+        Self {
+            dir: trill_home.join("cache").join("web"),
+            ttl: toml
+                .ttl_sec
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_WEB_CACHE_TTL),
+            max_bytes: toml
+                .max_size_mb
+                .unwrap_or(DEFAULT_WEB_CACHE_MAX_SIZE_MB)
+                .saturating_mul(1024 * 1024),
+            offline: toml.offline.unwrap_or(false),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WebSearchBackendKind {
+    /// SearXNG metasearch (`/search?format=json`).
+    Searxng,
+    /// YaCy peer-to-peer search (`/yacysearch.json`).
+    Yacy,
+    /// An Elasticsearch (or OpenSearch) index queried with `_search`.
+    Elasticsearch,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WebSearchTimeRange {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WebSearchSafeSearch {
+    Off,
+    Moderate,
+    Strict,
+}
+
+/// A single search backend under `[web_search.backends.<name>]`.
+///
+/// Options a backend cannot express are ignored with a warning; the
+/// `elasticsearch`-only settings are listed last.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct WebSearchBackendConfig {
+    #[serde(rename = "type")]
+    pub kind: WebSearchBackendKind,
+
+    /// Base URL of the backend, e.g. `http://127.0.0.1:8080`.
+    pub url: String,
+
+    /// Set to `false` to keep the backend configured but unused.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// Upstream engines to query (SearXNG).
+    #[serde(default)]
+    pub engines: Vec<String>,
+
+    /// Result categories (SearXNG categories, or values of `category_field`
+    /// for Elasticsearch).
+    #[serde(default)]
+    pub categories: Vec<String>,
+
+    /// Preferred result language, e.g. `en` or `de-CH`.
+    pub language: Option<String>,
+
+    /// Only return results published within this window.
+    pub time_range: Option<WebSearchTimeRange>,
+
+    /// Safe search level (SearXNG).
+    pub safesearch: Option<WebSearchSafeSearch>,
+
+    /// Maximum number of results to request from this backend.
+    pub max_results: Option<usize>,
+
+    /// Request timeout in seconds.
+    pub timeout_sec: Option<u64>,
+
+    /// Index (or comma-separated indices / alias) to search (Elasticsearch).
+    pub index: Option<String>,
+
+    /// Fields passed to `multi_match`; defaults to the title and content
+    /// fields (Elasticsearch).
+    #[serde(default)]
+    pub fields: Vec<String>,
+
+    /// Document field holding the title; defaults to `title` (Elasticsearch).
+    pub title_field: Option<String>,
+
+    /// Document field holding the URL; defaults to `url` (Elasticsearch).
+    pub url_field: Option<String>,
+
+    /// Document field holding the body text; defaults to `content`
+    /// (Elasticsearch).
+    pub content_field: Option<String>,
+
+    /// Date field used for `time_range` filtering (Elasticsearch).
+    pub date_field: Option<String>,
+
+    /// Keyword field used for `language` filtering (Elasticsearch).
+    pub language_field: Option<String>,
+
+    /// Keyword field used for `categories` filtering (Elasticsearch).
+    pub category_field: Option<String>,
+
+    /// Environment variable holding an API key, sent as
+    /// `Authorization: ApiKey <key>` (Elasticsearch).
+    pub env_key: Option<String>,
+}
+
+impl WebSearchBackendConfig {
+    /// A SearXNG backend with default options.
+    pub fn searxng(url: impl Into<String>) -> Self {
+        Self {
+            kind: WebSearchBackendKind::Searxng,
+            url: url.into(),
+            enabled: true,
+            engines: Vec::new(),
+            categories: Vec::new(),
+            language: None,
+            time_range: None,
+            safesearch: None,
+            max_results: None,
+            timeout_sec: None,
+            index: None,
+            fields: Vec::new(),
+            title_field: None,
+            url_field: None,
+            content_field: None,
+            date_field: None,
+            language_field: None,
+            category_field: None,
+            env_key: None,
+        }
+    }
+}
+
 #[derive(Serialize, Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum Notifications {
@@ -26,6 +26,7 @@ use crate::protocol::ExecCommandOutputDeltaEvent;
 use crate::protocol::ExecOutputStream;
 use crate::protocol::SandboxPolicy;
 use crate::sandboxing::CommandSpec;
+use crate::sandboxing::DenyRead;
 use crate::sandboxing::ExecEnv;
 use crate::sandboxing::SandboxManager;
 use crate::sandboxing::SandboxPermissions;
@@ -189,6 +190,8 @@ pub async fn process_exec_tool_call(
             sandbox_policy,
             sandbox_type,
             sandbox_cwd,
+            &DenyRead::default(),
+            None,
             trill_linux_sandbox_exe.as_ref(),
             windows_sandbox_level,
         )
@@ -479,6 +482,9 @@ pub(crate) mod errors {
                 SandboxTransformError::SeatbeltUnavailable => CodexErr::UnsupportedOperation(
                     "seatbelt sandbox is only available on macOS".to_string(),
                 ),
+                SandboxTransformError::DenyReadScan(err) => {
+                    CodexErr::UnsupportedOperation(err.to_string())
+                }
             }
         }
     }
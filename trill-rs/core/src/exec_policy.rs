@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::io::ErrorKind;
 use std::path::Path;
 use std::path::PathBuf;
@@ -13,10 +14,16 @@ use trill_execpolicy::AmendError;
 use trill_execpolicy::Decision;
 use trill_execpolicy::Error as ExecPolicyRuleError;
 use trill_execpolicy::Evaluation;
+use trill_execpolicy::FileOp;
+use trill_execpolicy::MatchContext;
 use trill_execpolicy::Policy;
 use trill_execpolicy::PolicyParser;
 use trill_execpolicy::RuleMatch;
+use trill_execpolicy::ToolRule;
+use trill_execpolicy::blocking_append_allow_file_rule;
+use trill_execpolicy::blocking_append_allow_mcp_tool_rule;
 use trill_execpolicy::blocking_append_allow_prefix_rule;
+use trill_execpolicy::blocking_append_allow_url_rule;
 use trill_protocol::approvals::ExecPolicyAmendment;
 use trill_protocol::protocol::AskForApproval;
 use trill_protocol::protocol::SandboxPolicy;
@@ -39,7 +46,9 @@ const DEFAULT_POLICY_FILE: &str = "default.rules";
 
 fn is_policy_match(rule_match: &RuleMatch) -> bool {
     match rule_match {
-        RuleMatch::PrefixRuleMatch { .. } => true,
+        RuleMatch::PrefixRuleMatch { .. }
+        | RuleMatch::ArgRuleMatch { .. }
+        | RuleMatch::ToolRuleMatch { .. } => true,
         RuleMatch::HeuristicsRuleMatch { .. } => false,
     }
 }
@@ -94,6 +103,95 @@ pub(crate) struct ExecApprovalRequest<'a> {
     pub(crate) sandbox_policy: &'a SandboxPolicy,
     pub(crate) sandbox_permissions: SandboxPermissions,
     pub(crate) prefix_rule: Option<Vec<String>>,
+    /// Cwd, writable roots and environment used by `path_rule` and `env` conditions.
+    pub(crate) match_context: MatchContext,
+}
+
+/// Rules saved when the user answers "Always allow" to a tool rule prompt. Each names its
+/// target exactly, so it settles the `prompt` rule that asked without widening it.
+#[derive(Clone, Debug)]
+pub(crate) enum ToolRuleAmendment {
+    McpTool {
+        server: String,
+        tool: String,
+    },
+    Url {
+        host: String,
+    },
+    /// Absolute paths of the changed files, each with the operations approved for it.
+    Files {
+        changes: Vec<(PathBuf, Vec<FileOp>)>,
+    },
+}
+
+impl ToolRuleAmendment {
+    /// The amendment for approving `changes`, merging operations on the same path.
+    pub(crate) fn for_file_changes<'a>(
+        changes: impl IntoIterator<Item = (&'a Path, FileOp)>,
+    ) -> Self {
+        let mut merged: Vec<(PathBuf, Vec<FileOp>)> = Vec::new();
+        for (path, op) in changes {
+            match merged
+                .iter_mut()
+                .find(|(merged_path, _)| merged_path == path)
+            {
+                Some((_, ops)) if !ops.contains(&op) => ops.push(op),
+                Some(_) => {}
+                None => merged.push((path.to_path_buf(), vec![op])),
+            }
+        }
+        Self::Files { changes: merged }
+    }
+
+    fn rules(&self) -> Result<Vec<ToolRule>, ExecPolicyRuleError> {
+        match self {
+            Self::McpTool { server, tool } => Ok(vec![ToolRule::mcp_tool(
+                server,
+                tool,
+                Decision::Allow,
+                None,
+            )?]),
+            Self::Url { host } => Ok(vec![ToolRule::url(host, Decision::Allow, None)?]),
+            Self::Files { changes } => changes
+                .iter()
+                .map(|(path, ops)| {
+                    ToolRule::file(&path.to_string_lossy(), ops.clone(), Decision::Allow, None)
+                })
+                .collect(),
+        }
+    }
+
+    fn blocking_append(&self, policy_path: &Path) -> Result<(), AmendError> {
+        match self {
+            Self::McpTool { server, tool } => {
+                blocking_append_allow_mcp_tool_rule(policy_path, server, tool)
+            }
+            Self::Url { host } => blocking_append_allow_url_rule(policy_path, host),
+            Self::Files { changes } => changes.iter().try_for_each(|(path, ops)| {
+                blocking_append_allow_file_rule(policy_path, &path.to_string_lossy(), ops)
+            }),
+        }
+    }
+}
+
+/// Builds the context execpolicy rules see for a command run in `cwd` with `env`: the
+/// workspace is `cwd` plus the sandbox's writable roots.
+pub(crate) fn exec_policy_match_context(
+    cwd: &Path,
+    sandbox_policy: &SandboxPolicy,
+    env: &HashMap<String, String>,
+) -> MatchContext {
+    MatchContext {
+        cwd: Some(cwd.to_path_buf()),
+        writable_roots: sandbox_policy
+            .get_writable_roots_with_cwd(cwd)
+            .into_iter()
+            .map(|writable_root| writable_root.root.into_path_buf())
+            .collect(),
+        env: Some(env.clone()),
+        cwd_may_change: false,
+        assume_env: false,
+    }
 }
 
 impl ExecPolicyManager {
@@ -130,10 +228,18 @@ impl ExecPolicyManager {
             sandbox_policy,
             sandbox_permissions,
             prefix_rule,
+            mut match_context,
         } = req;
         let exec_policy = self.current();
         let commands =
             parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
+        // Relative paths only mean what `cwd` says for the first command of a script that never
+        // changes directory.
+        match_context.cwd_may_change = commands.len() > 1
+            || commands.iter().any(|cmd| {
+                cmd.first()
+                    .is_some_and(|program| matches!(program.as_str(), "cd" | "pushd" | "popd"))
+            });
         let exec_policy_fallback = |cmd: &[String]| {
             render_decision_for_unmatched_command(
                 approval_policy,
@@ -142,7 +248,11 @@ impl ExecPolicyManager {
                 sandbox_permissions,
             )
         };
-        let evaluation = exec_policy.check_multiple(commands.iter(), &exec_policy_fallback);
+        let evaluation = exec_policy.check_multiple_with_context(
+            commands.iter(),
+            &match_context,
+            &exec_policy_fallback,
+        );
 
         let requested_amendment = derive_requested_execpolicy_amendment(
             features,
@@ -175,9 +285,13 @@ impl ExecPolicyManager {
                 }
             }
             Decision::Allow => ExecApprovalRequirement::Skip {
-                // Bypass sandbox if execpolicy allows the command
+                // Bypass sandbox if a prefix rule allows the command. Argument and path rules
+                // only vouch for the arguments they inspect, so they keep the sandbox.
                 bypass_sandbox: evaluation.matched_rules.iter().any(|rule_match| {
                     is_policy_match(rule_match) && rule_match.decision() == Decision::Allow
+                }) && !evaluation.matched_rules.iter().any(|rule_match| {
+                    matches!(rule_match, RuleMatch::ArgRuleMatch { .. })
+                        && rule_match.decision() == Decision::Allow
                 }),
                 proposed_execpolicy_amendment: if features.enabled(Feature::ExecPolicy) {
                     try_derive_execpolicy_amendment_for_allow_rules(&evaluation.matched_rules)
@@ -212,6 +326,80 @@ impl ExecPolicyManager {
         self.policy.store(Arc::new(updated_policy));
         Ok(())
     }
+
+    /// Requirement for calling MCP tool `tool` on `server`, or `None` when no `mcp_tool_rule`
+    /// matches and the call keeps its usual approval flow.
+    pub(crate) fn mcp_tool_requirement(
+        &self,
+        server: &str,
+        tool: &str,
+        approval_policy: AskForApproval,
+    ) -> Option<ExecApprovalRequirement> {
+        let evaluation = self.current().check_mcp_tool(server, tool)?;
+        Some(tool_rule_requirement(&evaluation, approval_policy))
+    }
+
+    /// Requirement for fetching a page from `host`, or `None` when no `url_rule` matches.
+    pub(crate) fn url_requirement(
+        &self,
+        host: &str,
+        approval_policy: AskForApproval,
+    ) -> Option<ExecApprovalRequirement> {
+        let evaluation = self.current().check_url_host(host)?;
+        Some(tool_rule_requirement(&evaluation, approval_policy))
+    }
+
+    /// Requirement for applying file changes under `cwd`, or `None` when no `file_rule` matches
+    /// any of them. A `forbidden` or `prompt` rule for any change applies to the whole set, but
+    /// `allow` rules only skip approval when every change matches one; otherwise the changes keep
+    /// their usual approval flow.
+    pub(crate) fn file_changes_requirement<'a>(
+        &self,
+        changes: impl IntoIterator<Item = (&'a Path, FileOp)>,
+        cwd: &Path,
+        approval_policy: AskForApproval,
+    ) -> Option<ExecApprovalRequirement> {
+        let changes: Vec<(&Path, FileOp)> = changes.into_iter().collect();
+        let policy = self.current();
+        let evaluation = policy.check_file_changes(changes.iter().copied(), cwd)?;
+        if evaluation.decision == Decision::Allow
+            && !changes
+                .iter()
+                .all(|change| policy.check_file_changes([*change], cwd).is_some())
+        {
+            return None;
+        }
+        Some(tool_rule_requirement(&evaluation, approval_policy))
+    }
+
+    /// Persists the exact `allow` tool rules of `amendment` and adds them to the in-memory
+    /// policy. Being exact, they settle `prompt` rules for the same targets.
+    pub(crate) async fn append_tool_rule_amendment_and_update(
+        &self,
+        trill_home: &Path,
+        amendment: &ToolRuleAmendment,
+    ) -> Result<(), ExecPolicyUpdateError> {
+        let rules = amendment.rules()?;
+        let policy_path = default_policy_path(trill_home);
+        spawn_blocking({
+            let policy_path = policy_path.clone();
+            let amendment = amendment.clone();
+            move || amendment.blocking_append(&policy_path)
+        })
+        .await
+        .map_err(|source| ExecPolicyUpdateError::JoinBlockingTask { source })?
+        .map_err(|source| ExecPolicyUpdateError::AppendRule {
+            path: policy_path,
+            source,
+        })?;
+
+        let mut updated_policy = self.current().as_ref().clone();
+        for rule in rules {
+            updated_policy.add_tool_rule(rule);
+        }
+        self.policy.store(Arc::new(updated_policy));
+        Ok(())
+    }
 }
 
 impl Default for ExecPolicyManager {
@@ -277,21 +465,59 @@ pub async fn load_exec_policy(config_stack: &ConfigLayerStack) -> Result<Policy,
             })?;
     }
 
-    let policy = parser.build();
+    let mut policy = parser.build();
     tracing::debug!("loaded rules from {} files", policy_paths.len());
 
     let Some(requirements_policy) = config_stack.requirements().exec_policy.as_deref() else {
         return Ok(policy);
     };
 
-    let mut combined_rules = policy.rules().clone();
-    for (program, rules) in requirements_policy.as_ref().rules().iter_all() {
-        for rule in rules {
-            combined_rules.insert(program.clone(), rule.clone());
+    policy.extend(requirements_policy.as_ref());
+    Ok(policy)
+}
+
+/// Maps a tool rule evaluation onto the approval flow. Unlike commands, tool calls matched by
+/// an `allow` rule still run under the sandbox.
+fn tool_rule_requirement(
+    evaluation: &Evaluation,
+    approval_policy: AskForApproval,
+) -> ExecApprovalRequirement {
+    match evaluation.decision {
+        Decision::Forbidden => ExecApprovalRequirement::Forbidden {
+            reason: derive_tool_rule_reason(evaluation, "rejected"),
+        },
+        Decision::Prompt if matches!(approval_policy, AskForApproval::Never) => {
+            ExecApprovalRequirement::Forbidden {
+                reason: PROMPT_CONFLICT_REASON.to_string(),
+            }
         }
+        Decision::Prompt => ExecApprovalRequirement::NeedsApproval {
+            reason: Some(derive_tool_rule_reason(evaluation, "requires approval")),
+            proposed_execpolicy_amendment: None,
+        },
+        Decision::Allow => ExecApprovalRequirement::Skip {
+            bypass_sandbox: false,
+            proposed_execpolicy_amendment: None,
+        },
     }
+}
 
-    Ok(Policy::new(combined_rules))
+/// Explains a tool rule decision using the first rule that produced it, e.g.
+/// "mcp tool `github/delete_repo` rejected: use the web UI".
+fn derive_tool_rule_reason(evaluation: &Evaluation, verdict: &str) -> String {
+    let rule_match = evaluation
+        .matched_rules
+        .iter()
+        .find(|rule_match| rule_match.decision() == evaluation.decision);
+    match rule_match {
+        Some(RuleMatch::ToolRuleMatch {
+            target,
+            justification: Some(justification),
+            ..
+        }) => format!("{target} {verdict}: {justification}"),
+        Some(RuleMatch::ToolRuleMatch { target, .. }) => format!("{target} {verdict} by policy"),
+        _ => format!("{verdict} by policy"),
+    }
 }
 
 /// If a command is not matched by any execpolicy rule, derive a [`Decision`].
@@ -445,14 +671,11 @@ fn derive_prompt_reason(command_args: &[String], evaluation: &Evaluation) -> Opt
     let most_specific_prompt = evaluation
         .matched_rules
         .iter()
-        .filter_map(|rule_match| match rule_match {
-            RuleMatch::PrefixRuleMatch {
-                matched_prefix,
-                decision: Decision::Prompt,
-                justification,
-                ..
-            } => Some((matched_prefix.len(), justification.as_deref())),
-            _ => None,
+        .filter(|rule_match| rule_match.decision() == Decision::Prompt)
+        .filter_map(|rule_match| {
+            rule_match
+                .matched_prefix()
+                .map(|matched_prefix| (matched_prefix.len(), rule_match.justification()))
         })
         .max_by_key(|(matched_prefix_len, _)| *matched_prefix_len);
 
@@ -480,14 +703,11 @@ fn derive_forbidden_reason(command_args: &[String], evaluation: &Evaluation) ->
     let most_specific_forbidden = evaluation
         .matched_rules
         .iter()
-        .filter_map(|rule_match| match rule_match {
-            RuleMatch::PrefixRuleMatch {
-                matched_prefix,
-                decision: Decision::Forbidden,
-                justification,
-                ..
-            } => Some((matched_prefix, justification.as_deref())),
-            _ => None,
+        .filter(|rule_match| rule_match.decision() == Decision::Forbidden)
+        .filter_map(|rule_match| {
+            rule_match
+                .matched_prefix()
+                .map(|matched_prefix| (matched_prefix, rule_match.justification()))
         })
         .max_by_key(|(matched_prefix, _)| matched_prefix.len());
 
@@ -819,6 +1039,7 @@ prefix_rule(pattern=["rm"], decision="forbidden")
                 sandbox_policy: &SandboxPolicy::DangerFullAccess,
                 sandbox_permissions: SandboxPermissions::UseDefault,
                 prefix_rule: None,
+                match_context: MatchContext::default(),
             })
             .await;
 
@@ -830,6 +1051,74 @@ prefix_rule(pattern=["rm"], decision="forbidden")
         );
     }
 
+    #[tokio::test]
+    async fn path_rule_uses_command_cwd_and_writable_roots() {
+        let policy_src = r#"
+path_rule(
+    pattern=["rm"],
+    within=["workspace"],
+    outside=True,
+    decision="forbidden",
+    justification="only delete files inside the workspace",
+)
+"#;
+        let mut parser = PolicyParser::new();
+        parser
+            .parse("test.rules", policy_src)
+            .expect("parse policy");
+        let manager = ExecPolicyManager::new(Arc::new(parser.build()));
+        let sandbox_policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![AbsolutePathBuf::from_absolute_path("/scratch").expect("abs")],
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        };
+        let match_context =
+            exec_policy_match_context(Path::new("/repo"), &sandbox_policy, &HashMap::new());
+
+        let inside = vec![
+            "rm".to_string(),
+            "-rf".to_string(),
+            "target".to_string(),
+            "/scratch/out".to_string(),
+        ];
+        let requirement = manager
+            .create_exec_approval_requirement_for_command(ExecApprovalRequest {
+                features: &Features::with_defaults(),
+                command: &inside,
+                approval_policy: AskForApproval::OnRequest,
+                sandbox_policy: &sandbox_policy,
+                sandbox_permissions: SandboxPermissions::UseDefault,
+                prefix_rule: None,
+                match_context: match_context.clone(),
+            })
+            .await;
+        assert!(
+            !matches!(requirement, ExecApprovalRequirement::Forbidden { .. }),
+            "paths inside the workspace should not be forbidden: {requirement:?}"
+        );
+
+        let outside = vec!["rm".to_string(), "../other".to_string()];
+        let requirement = manager
+            .create_exec_approval_requirement_for_command(ExecApprovalRequest {
+                features: &Features::with_defaults(),
+                command: &outside,
+                approval_policy: AskForApproval::OnRequest,
+                sandbox_policy: &sandbox_policy,
+                sandbox_permissions: SandboxPermissions::UseDefault,
+                prefix_rule: None,
+                match_context,
+            })
+            .await;
+        assert_eq!(
+            requirement,
+            ExecApprovalRequirement::Forbidden {
+                reason: "`rm ../other` rejected: only delete files inside the workspace"
+                    .to_string()
+            }
+        );
+    }
+
     #[tokio::test]
     async fn justification_is_included_in_forbidden_exec_approval_requirement() {
         let policy_src = r#"
@@ -858,6 +1147,7 @@ prefix_rule(
                 sandbox_policy: &SandboxPolicy::DangerFullAccess,
                 sandbox_permissions: SandboxPermissions::UseDefault,
                 prefix_rule: None,
+                match_context: MatchContext::default(),
             })
             .await;
 
@@ -888,6 +1178,7 @@ prefix_rule(
                 sandbox_policy: &SandboxPolicy::DangerFullAccess,
                 sandbox_permissions: SandboxPermissions::UseDefault,
                 prefix_rule: None,
+                match_context: MatchContext::default(),
             })
             .await;
 
@@ -919,6 +1210,7 @@ prefix_rule(
                 sandbox_policy: &SandboxPolicy::DangerFullAccess,
                 sandbox_permissions: SandboxPermissions::UseDefault,
                 prefix_rule: None,
+                match_context: MatchContext::default(),
             })
             .await;
 
@@ -943,6 +1235,7 @@ prefix_rule(
                 sandbox_policy: &SandboxPolicy::ReadOnly,
                 sandbox_permissions: SandboxPermissions::UseDefault,
                 prefix_rule: None,
+                match_context: MatchContext::default(),
             })
             .await;
 
@@ -974,6 +1267,7 @@ prefix_rule(
                 sandbox_policy: &SandboxPolicy::ReadOnly,
                 sandbox_permissions: SandboxPermissions::RequireEscalated,
                 prefix_rule: Some(vec!["cargo".to_string(), "install".to_string()]),
+                match_context: MatchContext::default(),
             })
             .await;
 
@@ -1012,6 +1306,7 @@ prefix_rule(
                     sandbox_policy: &SandboxPolicy::DangerFullAccess,
                     sandbox_permissions: SandboxPermissions::UseDefault,
                     prefix_rule: None,
+                    match_context: MatchContext::default(),
                 })
                 .await,
             ExecApprovalRequirement::NeedsApproval {
@@ -1056,6 +1351,242 @@ prefix_rule(
         );
     }
 
+    #[tokio::test]
+    async fn append_mcp_tool_amendment_updates_policy_and_file() {
+        let trill_home = tempdir().expect("create temp dir");
+        let manager = ExecPolicyManager::default();
+
+        manager
+            .append_tool_rule_amendment_and_update(
+                trill_home.path(),
+                &ToolRuleAmendment::McpTool {
+                    server: "github".to_string(),
+                    tool: "create_issue".to_string(),
+                },
+            )
+            .await
+            .expect("update policy");
+
+        assert_eq!(
+            Some(ExecApprovalRequirement::Skip {
+                bypass_sandbox: false,
+                proposed_execpolicy_amendment: None,
+            }),
+            manager.mcp_tool_requirement("github", "create_issue", AskForApproval::OnRequest)
+        );
+        let contents = fs::read_to_string(default_policy_path(trill_home.path()))
+            .expect("policy file should have been created");
+        assert_eq!(
+            contents,
+            r#"mcp_tool_rule(server="github", tool="create_issue", decision="allow")
+"#
+        );
+    }
+
+    #[tokio::test]
+    async fn url_and_file_amendments_settle_prompt_rules() {
+        let trill_home = tempdir().expect("create temp dir");
+        let policy_src = r#"
+url_rule(domain_glob="*.internal.example.com", decision="prompt")
+file_rule(path_glob="**/*.lock", ops=["update", "delete"], decision="prompt")
+"#;
+        let mut parser = PolicyParser::new();
+        parser
+            .parse("test.rules", policy_src)
+            .expect("parse policy");
+        let manager = ExecPolicyManager::new(Arc::new(parser.build()));
+        let cwd = Path::new("/workspace");
+        let lockfile = cwd.join("Cargo.lock");
+        let other_lockfile = cwd.join("web/package.lock");
+
+        manager
+            .append_tool_rule_amendment_and_update(
+                trill_home.path(),
+                &ToolRuleAmendment::Url {
+                    host: "wiki.internal.example.com".to_string(),
+                },
+            )
+            .await
+            .expect("update policy");
+        manager
+            .append_tool_rule_amendment_and_update(
+                trill_home.path(),
+                &ToolRuleAmendment::for_file_changes([
+                    (lockfile.as_path(), FileOp::Update),
+                    (lockfile.as_path(), FileOp::Update),
+                ]),
+            )
+            .await
+            .expect("update policy");
+
+        let skip = Some(ExecApprovalRequirement::Skip {
+            bypass_sandbox: false,
+            proposed_execpolicy_amendment: None,
+        });
+        assert_eq!(
+            skip,
+            manager.url_requirement("wiki.internal.example.com", AskForApproval::OnRequest)
+        );
+        assert!(matches!(
+            manager.url_requirement("git.internal.example.com", AskForApproval::OnRequest),
+            Some(ExecApprovalRequirement::NeedsApproval { .. })
+        ));
+        assert_eq!(
+            skip,
+            manager.file_changes_requirement(
+                [(lockfile.as_path(), FileOp::Update)],
+                cwd,
+                AskForApproval::OnRequest,
+            )
+        );
+        assert!(matches!(
+            manager.file_changes_requirement(
+                [(lockfile.as_path(), FileOp::Delete)],
+                cwd,
+                AskForApproval::OnRequest,
+            ),
+            Some(ExecApprovalRequirement::NeedsApproval { .. })
+        ));
+        assert!(matches!(
+            manager.file_changes_requirement(
+                [
+                    (lockfile.as_path(), FileOp::Update),
+                    (other_lockfile.as_path(), FileOp::Update),
+                ],
+                cwd,
+                AskForApproval::OnRequest,
+            ),
+            Some(ExecApprovalRequirement::NeedsApproval { .. })
+        ));
+
+        let contents = fs::read_to_string(default_policy_path(trill_home.path()))
+            .expect("policy file should have been created");
+        assert_eq!(
+            contents,
+            r#"url_rule(domain_glob="wiki.internal.example.com", decision="allow")
+file_rule(path_glob="/workspace/Cargo.lock", ops=["update"], decision="allow")
+"#
+        );
+    }
+
+    #[test]
+    fn tool_rules_map_to_approval_requirements() {
+        let policy_src = r#"
+mcp_tool_rule(server="github", tool="delete_*", decision="forbidden", justification="use the web UI")
+url_rule(domain_glob="*.internal.example.com", decision="prompt")
+file_rule(path_glob="**/*.lock", ops=["update"], decision="prompt")
+"#;
+        let mut parser = PolicyParser::new();
+        parser
+            .parse("test.rules", policy_src)
+            .expect("parse policy");
+        let manager = ExecPolicyManager::new(Arc::new(parser.build()));
+
+        assert_eq!(
+            Some(ExecApprovalRequirement::Forbidden {
+                reason: "mcp tool `github/delete_repo` rejected: use the web UI".to_string(),
+            }),
+            manager.mcp_tool_requirement("github", "delete_repo", AskForApproval::OnRequest)
+        );
+        assert_eq!(
+            None,
+            manager.mcp_tool_requirement("github", "create_issue", AskForApproval::OnRequest)
+        );
+        assert_eq!(
+            Some(ExecApprovalRequirement::NeedsApproval {
+                reason: Some(
+                    "url host `wiki.internal.example.com` requires approval by policy".to_string()
+                ),
+                proposed_execpolicy_amendment: None,
+            }),
+            manager.url_requirement("wiki.internal.example.com", AskForApproval::OnRequest)
+        );
+        assert_eq!(
+            Some(ExecApprovalRequirement::Forbidden {
+                reason: PROMPT_CONFLICT_REASON.to_string(),
+            }),
+            manager.url_requirement("wiki.internal.example.com", AskForApproval::Never)
+        );
+
+        let cwd = Path::new("/workspace");
+        let lockfile = cwd.join("Cargo.lock");
+        assert_eq!(
+            Some(ExecApprovalRequirement::NeedsApproval {
+                reason: Some("file update `Cargo.lock` requires approval by policy".to_string()),
+                proposed_execpolicy_amendment: None,
+            }),
+            manager.file_changes_requirement(
+                [(lockfile.as_path(), FileOp::Update)],
+                cwd,
+                AskForApproval::OnRequest
+            )
+        );
+        assert_eq!(
+            None,
+            manager.file_changes_requirement(
+                [(lockfile.as_path(), FileOp::Delete)],
+                cwd,
+                AskForApproval::OnRequest
+            )
+        );
+    }
+
+    #[test]
+    fn file_allow_rules_must_cover_every_change() {
+        let policy_src = r#"
+file_rule(path_glob="docs/**", ops=["add", "update"], decision="allow")
+file_rule(path_glob="docs/private/**", ops=["update"], decision="forbidden")
+"#;
+        let mut parser = PolicyParser::new();
+        parser
+            .parse("test.rules", policy_src)
+            .expect("parse policy");
+        let manager = ExecPolicyManager::new(Arc::new(parser.build()));
+        let cwd = Path::new("/workspace");
+        let guide = cwd.join("docs/guide.md");
+        let index = cwd.join("docs/index.md");
+        let main = cwd.join("src/main.rs");
+        let private = cwd.join("docs/private/keys.md");
+
+        assert_eq!(
+            Some(ExecApprovalRequirement::Skip {
+                bypass_sandbox: false,
+                proposed_execpolicy_amendment: None,
+            }),
+            manager.file_changes_requirement(
+                [
+                    (guide.as_path(), FileOp::Update),
+                    (index.as_path(), FileOp::Add)
+                ],
+                cwd,
+                AskForApproval::OnRequest
+            )
+        );
+        // `src/main.rs` is not covered by an allow rule, so the patch is assessed as usual.
+        assert_eq!(
+            None,
+            manager.file_changes_requirement(
+                [
+                    (guide.as_path(), FileOp::Update),
+                    (main.as_path(), FileOp::Update)
+                ],
+                cwd,
+                AskForApproval::OnRequest
+            )
+        );
+        assert!(matches!(
+            manager.file_changes_requirement(
+                [
+                    (guide.as_path(), FileOp::Update),
+                    (private.as_path(), FileOp::Update)
+                ],
+                cwd,
+                AskForApproval::OnRequest
+            ),
+            Some(ExecApprovalRequirement::Forbidden { .. })
+        ));
+    }
+
     #[tokio::test]
     async fn append_execpolicy_amendment_rejects_empty_prefix() {
         let trill_home = tempdir().expect("create temp dir");
@@ -1087,6 +1618,7 @@ prefix_rule(
                 sandbox_policy: &SandboxPolicy::ReadOnly,
                 sandbox_permissions: SandboxPermissions::UseDefault,
                 prefix_rule: None,
+                match_context: MatchContext::default(),
             })
             .await;
 
@@ -1115,6 +1647,7 @@ prefix_rule(
                 sandbox_policy: &SandboxPolicy::ReadOnly,
                 sandbox_permissions: SandboxPermissions::UseDefault,
                 prefix_rule: None,
+                match_context: MatchContext::default(),
             })
             .await;
 
@@ -1146,6 +1679,7 @@ prefix_rule(
                 sandbox_policy: &SandboxPolicy::DangerFullAccess,
                 sandbox_permissions: SandboxPermissions::UseDefault,
                 prefix_rule: None,
+                match_context: MatchContext::default(),
             })
             .await;
 
@@ -1174,6 +1708,7 @@ prefix_rule(
                 sandbox_policy: &SandboxPolicy::ReadOnly,
                 sandbox_permissions: SandboxPermissions::UseDefault,
                 prefix_rule: None,
+                match_context: MatchContext::default(),
             })
             .await;
 
@@ -1213,6 +1748,7 @@ prefix_rule(
                     sandbox_policy: &SandboxPolicy::ReadOnly,
                     sandbox_permissions: SandboxPermissions::UseDefault,
                     prefix_rule: None,
+                    match_context: MatchContext::default(),
                 })
                 .await,
             ExecApprovalRequirement::NeedsApproval {
@@ -1237,6 +1773,7 @@ prefix_rule(
                 sandbox_policy: &SandboxPolicy::ReadOnly,
                 sandbox_permissions: SandboxPermissions::UseDefault,
                 prefix_rule: None,
+                match_context: MatchContext::default(),
             })
             .await;
 
@@ -1268,6 +1805,7 @@ prefix_rule(
                 sandbox_policy: &SandboxPolicy::ReadOnly,
                 sandbox_permissions: SandboxPermissions::UseDefault,
                 prefix_rule: None,
+                match_context: MatchContext::default(),
             })
             .await;
 
@@ -1280,6 +1818,58 @@ prefix_rule(
         );
     }
 
+    #[tokio::test]
+    async fn path_rule_allow_keeps_the_sandbox() {
+        let policy_src = r#"path_rule(pattern=["rm"], within=["workspace"], decision="allow")"#;
+        let mut parser = PolicyParser::new();
+        parser
+            .parse("test.rules", policy_src)
+            .expect("parse policy");
+        let manager = ExecPolicyManager::new(Arc::new(parser.build()));
+        let sandbox_policy = SandboxPolicy::ReadOnly;
+        let match_context =
+            exec_policy_match_context(Path::new("/repo"), &sandbox_policy, &HashMap::new());
+
+        let requirement = manager
+            .create_exec_approval_requirement_for_command(ExecApprovalRequest {
+                features: &Features::with_defaults(),
+                command: &vec_str(&["rm", "-rf", "target"]),
+                approval_policy: AskForApproval::OnRequest,
+                sandbox_policy: &sandbox_policy,
+                sandbox_permissions: SandboxPermissions::UseDefault,
+                prefix_rule: None,
+                match_context: match_context.clone(),
+            })
+            .await;
+        assert!(
+            matches!(
+                requirement,
+                ExecApprovalRequirement::Skip {
+                    bypass_sandbox: false,
+                    ..
+                }
+            ),
+            "path rules must not lift the sandbox: {requirement:?}"
+        );
+
+        // After `cd /`, `etc` is no longer known to be inside /repo.
+        let requirement = manager
+            .create_exec_approval_requirement_for_command(ExecApprovalRequest {
+                features: &Features::with_defaults(),
+                command: &vec_str(&["bash", "-lc", "cd / && rm -rf etc"]),
+                approval_policy: AskForApproval::OnRequest,
+                sandbox_policy: &sandbox_policy,
+                sandbox_permissions: SandboxPermissions::UseDefault,
+                prefix_rule: None,
+                match_context,
+            })
+            .await;
+        assert!(
+            !matches!(requirement, ExecApprovalRequirement::Skip { .. }),
+            "relative paths after `cd` must not be auto-approved: {requirement:?}"
+        );
+    }
+
     fn vec_str(items: &[&str]) -> Vec<String> {
         items.iter().map(std::string::ToString::to_string).collect()
     }
@@ -1336,6 +1926,7 @@ prefix_rule(
                     sandbox_policy: &SandboxPolicy::ReadOnly,
                     sandbox_permissions: permissions,
                     prefix_rule: None,
+                    match_context: MatchContext::default(),
                 })
                 .await,
             "{pwsh_approval_reason}"
@@ -1360,6 +1951,7 @@ prefix_rule(
                     sandbox_policy: &SandboxPolicy::ReadOnly,
                     sandbox_permissions: permissions,
                     prefix_rule: None,
+                    match_context: MatchContext::default(),
                 })
                 .await,
             r#"On all platforms, a forbidden command should require approval
@@ -1380,6 +1972,7 @@ prefix_rule(
                     sandbox_policy: &SandboxPolicy::ReadOnly,
                     sandbox_permissions: permissions,
                     prefix_rule: None,
+                    match_context: MatchContext::default(),
                 })
                 .await,
             r#"On all platforms, a forbidden command should require approval
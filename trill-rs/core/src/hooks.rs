@@ -0,0 +1,831 @@
+//! Lifecycle hooks: user commands configured under `[hooks]`.
+//!
+//! Every hook receives a JSON description of the event on stdin and runs in
+//! the turn's working directory. A hook that prints a JSON object on stdout
+//! can steer the session:
+//!
+//! - `pre_tool_use` hooks block the call with `{"decision": "block",
+//!   "reason": "..."}` (or by exiting with code 2, using stderr as the reason)
+//!   and replace its arguments with `{"tool_input": ...}`.
+//! - `user_prompt_submit` hooks add context for the model with
+//!   `{"additional_context": "..."}`. Plain-text stdout is used as context
+//!   too.
+//!
+//! Anything else a hook prints is informational. Hooks that fail or time out
+//! never stop the session; every run is reported with
+//! [`EventMsg::HookCompleted`], which is also recorded in the rollout.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use regex_lite::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::warn;
+use trill_protocol::models::DeveloperInstructions;
+use trill_protocol::models::ResponseInputItem;
+use trill_protocol::models::ResponseItem;
+use trill_protocol::models::ShellToolCallParams;
+use trill_protocol::protocol::EventMsg;
+use trill_protocol::protocol::HookCompletedEvent;
+use trill_protocol::protocol::HookEventName;
+use trill_protocol::protocol::HookOutcome;
+use trill_protocol::user_input::UserInput;
+
+use crate::config::types::DEFAULT_HOOK_TIMEOUT_SEC;
+use crate::config::types::HookCommandToml;
+use crate::config::types::HooksToml;
+use crate::tools::context::ToolPayload;
+use crate::trill::Session;
+use crate::trill::TurnContext;
+
+/// Exit code with which a `pre_tool_use` hook blocks the tool call.
+const BLOCK_EXIT_CODE: i32 = 2;
+
+/// Compiles a hook `matcher`. The pattern must match the whole tool name.
+pub(crate) fn compile_matcher(matcher: &str) -> Result<Regex, regex_lite::Error> {
+    Regex::new(&format!("^(?:{matcher})$"))
+}
+
+/// What started the session, as reported to `session_start` hooks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SessionStartSource {
+    New,
+    Resumed,
+    Forked,
+}
+
+/// What started a compaction, as reported to `pre_compact` hooks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CompactTrigger {
+    /// The context window filled up.
+    Auto,
+    /// The user asked for it.
+    Manual,
+}
+
+/// Event-specific part of the JSON written to a hook's stdin.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "hook_event_name", rename_all = "snake_case")]
+enum HookInput {
+    SessionStart {
+        source: SessionStartSource,
+    },
+    UserPromptSubmit {
+        prompt: String,
+    },
+    PreToolUse {
+        tool_name: String,
+        call_id: String,
+        tool_input: Value,
+    },
+    PostToolUse {
+        tool_name: String,
+        call_id: String,
+        tool_input: Value,
+        tool_output: String,
+        success: Option<bool>,
+    },
+    TurnComplete {
+        input_messages: Vec<String>,
+        last_assistant_message: Option<String>,
+    },
+    PreCompact {
+        trigger: CompactTrigger,
+    },
+}
+
+impl HookInput {
+    fn event_name(&self) -> HookEventName {
+        match self {
+            HookInput::SessionStart { .. } => HookEventName::SessionStart,
+            HookInput::UserPromptSubmit { .. } => HookEventName::UserPromptSubmit,
+            HookInput::PreToolUse { .. } => HookEventName::PreToolUse,
+            HookInput::PostToolUse { .. } => HookEventName::PostToolUse,
+            HookInput::TurnComplete { .. } => HookEventName::TurnComplete,
+            HookInput::PreCompact { .. } => HookEventName::PreCompact,
+        }
+    }
+
+    fn tool_name(&self) -> Option<&str> {
+        match self {
+            HookInput::PreToolUse { tool_name, .. } | HookInput::PostToolUse { tool_name, .. } => {
+                Some(tool_name)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct HookRequest<'a> {
+    session_id: String,
+    turn_id: &'a str,
+    cwd: &'a Path,
+    #[serde(flatten)]
+    input: &'a HookInput,
+}
+
+/// JSON object a hook may print on stdout.
+#[derive(Debug, Default, Deserialize)]
+struct HookResponse {
+    #[serde(default)]
+    decision: Option<HookDecision>,
+    #[serde(default)]
+    reason: Option<String>,
+    #[serde(default)]
+    tool_input: Option<Value>,
+    #[serde(default)]
+    additional_context: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum HookDecision {
+    Approve,
+    Block,
+}
+
+#[derive(Debug)]
+struct Hook {
+    event: HookEventName,
+    command: Vec<String>,
+    matcher: Option<Regex>,
+    timeout: Duration,
+}
+
+#[derive(Debug, Default)]
+struct HookExecution {
+    exit_code: Option<i32>,
+    stdout: String,
+    stderr: String,
+    /// Set when the hook could not be started or timed out.
+    error: Option<String>,
+}
+
+impl Hook {
+    fn new(event: HookEventName, config: &HookCommandToml) -> Option<Self> {
+        if config.command.first().is_none_or(String::is_empty) {
+            return None;
+        }
+        let matcher = match config.matcher.as_deref().map(compile_matcher) {
+            None => None,
+            Some(Ok(matcher)) => Some(matcher),
+            Some(Err(err)) => {
+                warn!(
+                    "ignoring hook {:?} with invalid matcher: {err}",
+                    config.command
+                );
+                return None;
+            }
+        };
+        Some(Self {
+            event,
+            command: config.command.clone(),
+            matcher,
+            timeout: Duration::from_secs(config.timeout_sec.unwrap_or(DEFAULT_HOOK_TIMEOUT_SEC)),
+        })
+    }
+
+    fn matches(&self, event: HookEventName, tool_name: Option<&str>) -> bool {
+        self.event == event
+            && match (&self.matcher, tool_name) {
+                (Some(matcher), Some(tool_name)) => matcher.is_match(tool_name),
+                _ => true,
+            }
+    }
+
+    async fn run(&self, cwd: &Path, stdin: &[u8]) -> HookExecution {
+        let mut command = Command::new(&self.command[0]);
+        command
+            .args(&self.command[1..])
+            .current_dir(cwd)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(err) => {
+                return HookExecution {
+                    error: Some(format!("failed to start `{}`: {err}", self.command[0])),
+                    ..Default::default()
+                };
+            }
+        };
+        if let Some(mut child_stdin) = child.stdin.take() {
+            // Written from a task so a hook that never reads its input cannot
+            // block past its timeout; the pipe is closed once the write ends.
+            let stdin = stdin.to_vec();
+            let program = self.command[0].clone();
+            tokio::spawn(async move {
+                // Hooks are free to ignore their input, so a closed pipe is fine.
+                if let Err(err) = child_stdin.write_all(&stdin).await
+                    && err.kind() != std::io::ErrorKind::BrokenPipe
+                {
+                    warn!("failed to write hook input to `{program}`: {err}");
+                }
+            });
+        }
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let finished = tokio::time::timeout(self.timeout, async {
+            tokio::try_join!(child.wait(), read_pipe(stdout), read_pipe(stderr))
+        })
+        .await;
+        match finished {
+            Ok(Ok((status, stdout, stderr))) => HookExecution {
+                exit_code: status.code(),
+                stdout: String::from_utf8_lossy(&stdout).into_owned(),
+                stderr: String::from_utf8_lossy(&stderr).into_owned(),
+                error: status
+                    .code()
+                    .is_none()
+                    .then(|| "hook was terminated by a signal".to_string()),
+            },
+            Ok(Err(err)) => HookExecution {
+                error: Some(format!("failed to wait for `{}`: {err}", self.command[0])),
+                ..Default::default()
+            },
+            Err(_) => {
+                if let Err(err) = child.kill().await {
+                    warn!("failed to kill hook `{}`: {err}", self.command[0]);
+                }
+                HookExecution {
+                    error: Some(format!(
+                        "hook timed out after {}s",
+                        self.timeout.as_secs_f32()
+                    )),
+                    ..Default::default()
+                }
+            }
+        }
+    }
+}
+
+async fn read_pipe(pipe: Option<impl AsyncRead + Unpin>) -> std::io::Result<Vec<u8>> {
+    let mut output = Vec::new();
+    if let Some(mut pipe) = pipe {
+        pipe.read_to_end(&mut output).await?;
+    }
+    Ok(output)
+}
+
+/// Interprets a finished hook run.
+fn interpret(event: HookEventName, execution: &HookExecution) -> (HookOutcome, HookResponse) {
+    if let Some(message) = &execution.error {
+        return (
+            HookOutcome::Failed {
+                message: message.clone(),
+            },
+            HookResponse::default(),
+        );
+    }
+    match execution.exit_code {
+        Some(0) => {}
+        Some(BLOCK_EXIT_CODE) if event == HookEventName::PreToolUse => {
+            let reason = execution.stderr.trim();
+            let reason = if reason.is_empty() {
+                "blocked by hook".to_string()
+            } else {
+                reason.to_string()
+            };
+            return (HookOutcome::Blocked { reason }, HookResponse::default());
+        }
+        code => {
+            let code = code.map_or_else(|| "unknown".to_string(), |code| code.to_string());
+            return (
+                HookOutcome::Failed {
+                    message: format!("hook exited with code {code}"),
+                },
+                HookResponse::default(),
+            );
+        }
+    }
+
+    let stdout = execution.stdout.trim();
+    let response = match serde_json::from_str::<HookResponse>(stdout) {
+        Ok(response) => response,
+        Err(_) if event == HookEventName::UserPromptSubmit && !stdout.is_empty() => HookResponse {
+            additional_context: Some(stdout.to_string()),
+            ..Default::default()
+        },
+        Err(_) => HookResponse::default(),
+    };
+    if event == HookEventName::PreToolUse && response.decision == Some(HookDecision::Block) {
+        let reason = response
+            .reason
+            .clone()
+            .unwrap_or_else(|| "blocked by hook".to_string());
+        return (HookOutcome::Blocked { reason }, response);
+    }
+    (HookOutcome::Success, response)
+}
+
+/// Hooks from `[hooks]`, ready to run.
+#[derive(Debug, Default)]
+pub(crate) struct HookRunner {
+    hooks: Vec<Hook>,
+}
+
+impl HookRunner {
+    pub(crate) fn new(config: &HooksToml) -> Self {
+        let events = [
+            (HookEventName::SessionStart, &config.session_start),
+            (HookEventName::UserPromptSubmit, &config.user_prompt_submit),
+            (HookEventName::PreToolUse, &config.pre_tool_use),
+            (HookEventName::PostToolUse, &config.post_tool_use),
+            (HookEventName::TurnComplete, &config.turn_complete),
+            (HookEventName::PreCompact, &config.pre_compact),
+        ];
+        let hooks = events
+            .into_iter()
+            .flat_map(|(event, commands)| {
+                commands
+                    .iter()
+                    .filter_map(move |command| Hook::new(event, command))
+            })
+            .collect();
+        Self { hooks }
+    }
+
+    fn has_hooks(&self, event: HookEventName) -> bool {
+        self.hooks.iter().any(|hook| hook.event == event)
+    }
+
+    /// Runs the hooks for `input` one after another, reporting each run.
+    /// `on_response` sees every successful response in order and may update
+    /// `input` for the hooks that follow; returning `Some` stops early.
+    async fn run<T>(
+        &self,
+        sess: &Session,
+        turn_context: &TurnContext,
+        mut input: HookInput,
+        mut on_response: impl FnMut(&mut HookInput, HookOutcome, HookResponse) -> Option<T>,
+    ) -> Option<T> {
+        let event = input.event_name();
+        let tool_name = input.tool_name().map(str::to_string);
+        for hook in self
+            .hooks
+            .iter()
+            .filter(|hook| hook.matches(event, tool_name.as_deref()))
+        {
+            let request = HookRequest {
+                session_id: sess.conversation_id.to_string(),
+                turn_id: &turn_context.sub_id,
+                cwd: &turn_context.cwd,
+                input: &input,
+            };
+            let stdin = match serde_json::to_vec(&request) {
+                Ok(stdin) => stdin,
+                Err(err) => {
+                    warn!("failed to serialize hook input: {err}");
+                    return None;
+                }
+            };
+            let execution = hook.run(&turn_context.cwd, &stdin).await;
+            let (outcome, response) = interpret(event, &execution);
+            if let HookOutcome::Failed { message } = &outcome {
+                warn!("hook {:?} failed: {message}", hook.command);
+            }
+            sess.send_event(
+                turn_context,
+                EventMsg::HookCompleted(HookCompletedEvent {
+                    hook_event_name: event,
+                    command: hook.command.clone(),
+                    tool_name: tool_name.clone(),
+                    exit_code: execution.exit_code,
+                    stdout: execution.stdout,
+                    stderr: execution.stderr,
+                    outcome: outcome.clone(),
+                }),
+            )
+            .await;
+            if let Some(result) = on_response(&mut input, outcome, response) {
+                return Some(result);
+            }
+        }
+        None
+    }
+}
+
+pub(crate) async fn run_session_start(
+    sess: &Session,
+    turn_context: &TurnContext,
+    source: SessionStartSource,
+) {
+    let runner = &sess.services.hooks;
+    if !runner.has_hooks(HookEventName::SessionStart) {
+        return;
+    }
+    let input = HookInput::SessionStart { source };
+    runner
+        .run(sess, turn_context, input, |_, _, _| None::<()>)
+        .await;
+}
+
+/// Runs `user_prompt_submit` hooks and returns the context they added, as
+/// developer messages to record after the user's message.
+pub(crate) async fn run_user_prompt_submit(
+    sess: &Session,
+    turn_context: &TurnContext,
+    input: &[UserInput],
+) -> Vec<ResponseItem> {
+    let runner = &sess.services.hooks;
+    if !runner.has_hooks(HookEventName::UserPromptSubmit) {
+        return Vec::new();
+    }
+    let prompt = input
+        .iter()
+        .filter_map(|item| match item {
+            UserInput::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut context: Vec<ResponseItem> = Vec::new();
+    runner
+        .run(
+            sess,
+            turn_context,
+            HookInput::UserPromptSubmit { prompt },
+            |_, _, response| {
+                if let Some(text) = response.additional_context
+                    && !text.trim().is_empty()
+                {
+                    context.push(DeveloperInstructions::new(text).into());
+                }
+                None::<()>
+            },
+        )
+        .await;
+    context
+}
+
+/// Runs `pre_tool_use` hooks for a call, applying any rewritten arguments to
+/// `payload`. Returns the reason when a hook blocked the call.
+pub(crate) async fn run_pre_tool_use(
+    sess: &Session,
+    turn_context: &TurnContext,
+    tool_name: &str,
+    call_id: &str,
+    payload: &mut ToolPayload,
+) -> Result<(), String> {
+    let runner = &sess.services.hooks;
+    if !runner.has_hooks(HookEventName::PreToolUse) {
+        return Ok(());
+    }
+    let input = HookInput::PreToolUse {
+        tool_name: tool_name.to_string(),
+        call_id: call_id.to_string(),
+        tool_input: tool_input(payload),
+    };
+    let blocked = runner
+        .run(sess, turn_context, input, |input, outcome, response| {
+            if let HookOutcome::Blocked { reason } = outcome {
+                return Some(reason);
+            }
+            let (Some(rewritten), HookInput::PreToolUse { tool_input, .. }) =
+                (response.tool_input, input)
+            else {
+                return None;
+            };
+            match rewrite_tool_input(payload, rewritten.clone()) {
+                Ok(()) => *tool_input = rewritten,
+                Err(err) => warn!("ignoring tool_input from pre_tool_use hook: {err}"),
+            }
+            None
+        })
+        .await;
+    match blocked {
+        Some(reason) => Err(reason),
+        None => Ok(()),
+    }
+}
+
+pub(crate) async fn run_post_tool_use(
+    sess: &Session,
+    turn_context: &TurnContext,
+    tool_name: &str,
+    call_id: &str,
+    payload: &ToolPayload,
+    response: &ResponseInputItem,
+) {
+    let runner = &sess.services.hooks;
+    if !runner.has_hooks(HookEventName::PostToolUse) {
+        return;
+    }
+    let (tool_output, success) = tool_output(response);
+    let input = HookInput::PostToolUse {
+        tool_name: tool_name.to_string(),
+        call_id: call_id.to_string(),
+        tool_input: tool_input(payload),
+        tool_output,
+        success,
+    };
+    runner
+        .run(sess, turn_context, input, |_, _, _| None::<()>)
+        .await;
+}
+
+pub(crate) async fn run_turn_complete(
+    sess: &Session,
+    turn_context: &TurnContext,
+    input_messages: Vec<String>,
+    last_assistant_message: Option<String>,
+) {
+    let runner = &sess.services.hooks;
+    if !runner.has_hooks(HookEventName::TurnComplete) {
+        return;
+    }
+    let input = HookInput::TurnComplete {
+        input_messages,
+        last_assistant_message,
+    };
+    runner
+        .run(sess, turn_context, input, |_, _, _| None::<()>)
+        .await;
+}
+
+pub(crate) async fn run_pre_compact(
+    sess: &Session,
+    turn_context: &TurnContext,
+    trigger: CompactTrigger,
+) {
+    let runner = &sess.services.hooks;
+    if !runner.has_hooks(HookEventName::PreCompact) {
+        return;
+    }
+    let input = HookInput::PreCompact { trigger };
+    runner
+        .run(sess, turn_context, input, |_, _, _| None::<()>)
+        .await;
+}
+
+/// Tool arguments as hooks see them: parsed JSON where the tool takes JSON,
+/// a string otherwise.
+fn tool_input(payload: &ToolPayload) -> Value {
+    match payload {
+        ToolPayload::Function { arguments } => parse_or_string(arguments),
+        ToolPayload::Mcp { raw_arguments, .. } => parse_or_string(raw_arguments),
+        ToolPayload::Custom { input } => Value::String(input.clone()),
+        ToolPayload::LocalShell { params } => serde_json::json!({
+            "command": params.command,
+            "workdir": params.workdir,
+            "timeout_ms": params.timeout_ms,
+        }),
+    }
+}
+
+fn parse_or_string(arguments: &str) -> Value {
+    serde_json::from_str(arguments).unwrap_or_else(|_| Value::String(arguments.to_string()))
+}
+
+fn rewrite_tool_input(payload: &mut ToolPayload, tool_input: Value) -> Result<(), String> {
+    match payload {
+        ToolPayload::Function { arguments } => {
+            *arguments = tool_input.to_string();
+        }
+        ToolPayload::Mcp { raw_arguments, .. } => {
+            *raw_arguments = tool_input.to_string();
+        }
+        ToolPayload::Custom { input } => match tool_input {
+            Value::String(text) => *input = text,
+            other => return Err(format!("expected a string for a custom tool, got {other}")),
+        },
+        ToolPayload::LocalShell { params } => {
+            let mut rewritten: ShellToolCallParams =
+                serde_json::from_value(tool_input).map_err(|err| err.to_string())?;
+            rewritten.sandbox_permissions = params.sandbox_permissions;
+            *params = rewritten;
+        }
+    }
+    Ok(())
+}
+
+fn tool_output(response: &ResponseInputItem) -> (String, Option<bool>) {
+    match response {
+        ResponseInputItem::FunctionCallOutput { output, .. } => {
+            (output.content.clone(), output.success)
+        }
+        ResponseInputItem::CustomToolCallOutput { output, .. } => (output.clone(), None),
+        ResponseInputItem::McpToolCallOutput { result, .. } => match result {
+            Ok(result) => (
+                serde_json::to_string(result).unwrap_or_default(),
+                Some(!result.is_error.unwrap_or(false)),
+            ),
+            Err(err) => (err.clone(), Some(false)),
+        },
+        ResponseInputItem::Message { .. } => (String::new(), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn hook(command: &[&str], matcher: Option<&str>, timeout_sec: u64) -> Hook {
+        Hook::new(
+            HookEventName::PreToolUse,
+            &HookCommandToml {
+                command: command.iter().map(ToString::to_string).collect(),
+                matcher: matcher.map(str::to_string),
+                timeout_sec: Some(timeout_sec),
+            },
+        )
+        .expect("valid hook")
+    }
+
+    fn exited(code: i32, stdout: &str, stderr: &str) -> HookExecution {
+        HookExecution {
+            exit_code: Some(code),
+            stdout: stdout.to_string(),
+            stderr: stderr.to_string(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn matcher_must_match_whole_tool_name() {
+        let hook = hook(&["true"], Some("shell|apply_patch"), 1);
+        assert!(hook.matches(HookEventName::PreToolUse, Some("shell")));
+        assert!(hook.matches(HookEventName::PreToolUse, Some("apply_patch")));
+        assert!(!hook.matches(HookEventName::PreToolUse, Some("shell_command")));
+        assert!(!hook.matches(HookEventName::PostToolUse, Some("shell")));
+    }
+
+    #[test]
+    fn pre_tool_use_blocks_on_decision_or_exit_code() {
+        let (outcome, _) = interpret(
+            HookEventName::PreToolUse,
+            &exited(0, r#"{"decision": "block", "reason": "no rm"}"#, ""),
+        );
+        assert_eq!(
+            outcome,
+            HookOutcome::Blocked {
+                reason: "no rm".to_string()
+            }
+        );
+
+        let (outcome, _) = interpret(HookEventName::PreToolUse, &exited(2, "", "denied\n"));
+        assert_eq!(
+            outcome,
+            HookOutcome::Blocked {
+                reason: "denied".to_string()
+            }
+        );
+
+        // Only `pre_tool_use` hooks can block.
+        let (outcome, _) = interpret(HookEventName::PostToolUse, &exited(2, "", "denied"));
+        assert_eq!(
+            outcome,
+            HookOutcome::Failed {
+                message: "hook exited with code 2".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn user_prompt_submit_takes_json_or_plain_text_context() {
+        let (outcome, response) = interpret(
+            HookEventName::UserPromptSubmit,
+            &exited(0, r#"{"additional_context": "branch: main"}"#, ""),
+        );
+        assert_eq!(outcome, HookOutcome::Success);
+        assert_eq!(response.additional_context.as_deref(), Some("branch: main"));
+
+        let (_, response) = interpret(
+            HookEventName::UserPromptSubmit,
+            &exited(0, "branch: main\n", ""),
+        );
+        assert_eq!(response.additional_context.as_deref(), Some("branch: main"));
+
+        let (_, response) = interpret(HookEventName::TurnComplete, &exited(0, "done\n", ""));
+        assert_eq!(response.additional_context, None);
+    }
+
+    #[test]
+    fn rewrites_function_and_local_shell_arguments() {
+        let mut payload = ToolPayload::Function {
+            arguments: r#"{"path": "a.txt"}"#.to_string(),
+        };
+        rewrite_tool_input(&mut payload, serde_json::json!({"path": "b.txt"}))
+            .expect("rewrite function arguments");
+        assert_eq!(tool_input(&payload), serde_json::json!({"path": "b.txt"}));
+
+        let mut payload = ToolPayload::LocalShell {
+            params: ShellToolCallParams {
+                command: vec!["rm".to_string(), "-rf".to_string(), "build".to_string()],
+                workdir: None,
+                timeout_ms: None,
+                sandbox_permissions: None,
+                prefix_rule: None,
+                justification: None,
+            },
+        };
+        rewrite_tool_input(
+            &mut payload,
+            serde_json::json!({"command": ["echo", "skipped"]}),
+        )
+        .expect("rewrite shell params");
+        assert_eq!(
+            tool_input(&payload),
+            serde_json::json!({"command": ["echo", "skipped"], "workdir": null, "timeout_ms": null})
+        );
+
+        let mut payload = ToolPayload::Custom {
+            input: "patch".to_string(),
+        };
+        assert!(rewrite_tool_input(&mut payload, serde_json::json!({"x": 1})).is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn runs_command_with_input_on_stdin() {
+        let cwd = std::env::temp_dir();
+        let hook = hook(&["sh", "-c", "cat; echo oops >&2"], None, 10);
+        let execution = hook
+            .run(&cwd, br#"{"hook_event_name":"pre_tool_use"}"#)
+            .await;
+        assert_eq!(execution.exit_code, Some(0));
+        assert_eq!(execution.stdout, r#"{"hook_event_name":"pre_tool_use"}"#);
+        assert_eq!(execution.stderr, "oops\n");
+        assert_eq!(execution.error, None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn kills_hooks_that_time_out() {
+        let cwd = std::env::temp_dir();
+        let mut hook = hook(&["sleep", "5"], None, 1);
+        hook.timeout = Duration::from_millis(50);
+        let execution = hook.run(&cwd, b"{}").await;
+        let (outcome, _) = interpret(HookEventName::PreToolUse, &execution);
+        assert!(matches!(outcome, HookOutcome::Failed { .. }));
+        assert_eq!(execution.exit_code, None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn times_out_hooks_that_never_read_their_input() {
+        let cwd = std::env::temp_dir();
+        let pid_file = tempfile::NamedTempFile::new().expect("pid file");
+        let script = format!("echo $$ > {}; exec sleep 30", pid_file.path().display());
+        let mut hook = hook(&["sh", "-c", &script], None, 1);
+        hook.timeout = Duration::from_millis(200);
+        // Far more than a pipe buffer holds, so writing it blocks until the
+        // hook is gone.
+        let stdin = vec![b' '; 4 * 1024 * 1024];
+
+        let started = std::time::Instant::now();
+        let execution = hook.run(&cwd, &stdin).await;
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(execution.exit_code, None);
+        assert!(
+            execution
+                .error
+                .as_deref()
+                .is_some_and(|error| error.starts_with("hook timed out"))
+        );
+
+        let pid = std::fs::read_to_string(pid_file.path()).expect("read pid");
+        let alive = std::process::Command::new("kill")
+            .args(["-0", pid.trim()])
+            .stderr(Stdio::null())
+            .status()
+            .expect("run kill");
+        assert!(!alive.success(), "hook process {pid} is still running");
+    }
+
+    #[test]
+    fn request_flattens_event_fields() {
+        let input = HookInput::PreCompact {
+            trigger: CompactTrigger::Auto,
+        };
+        let request = HookRequest {
+            session_id: "session".to_string(),
+            turn_id: "turn",
+            cwd: Path::new("/repo"),
+            input: &input,
+        };
+        assert_eq!(
+            serde_json::to_value(&request).expect("serialize request"),
+            serde_json::json!({
+                "session_id": "session",
+                "turn_id": "turn",
+                "cwd": "/repo",
+                "hook_event_name": "pre_compact",
+                "trigger": "auto",
+            })
+        );
+    }
+}
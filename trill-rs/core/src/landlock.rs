@@ -12,20 +12,31 @@ use tokio::process::Child;
 /// Unlike macOS Seatbelt where we directly embed the policy text, the Linux
 /// helper accepts a list of `--sandbox-permission`/`-s` flags mirroring the
 /// public CLI. We convert the internal [`SandboxPolicy`] representation into
-/// the equivalent CLI options.
+/// the equivalent CLI options. `deny_read` lists existing paths the helper
+/// hides from the command, and `network_proxy_port` puts it in a network
+/// namespace whose only way out is the sandbox network proxy.
+#[allow(clippy::too_many_arguments)]
 pub async fn spawn_command_under_linux_sandbox<P>(
     trill_linux_sandbox_exe: P,
     command: Vec<String>,
     command_cwd: PathBuf,
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
+    deny_read: &[PathBuf],
+    network_proxy_port: Option<u16>,
     stdio_policy: StdioPolicy,
     env: HashMap<String, String>,
 ) -> std::io::Result<Child>
 where
     P: AsRef<Path>,
 {
-    let args = create_linux_sandbox_command_args(command, sandbox_policy, sandbox_policy_cwd);
+    let args = create_linux_sandbox_command_args(
+        command,
+        sandbox_policy,
+        sandbox_policy_cwd,
+        deny_read,
+        network_proxy_port,
+    );
     let arg0 = Some("trill-linux-sandbox");
     spawn_child_async(
         trill_linux_sandbox_exe.as_ref().to_path_buf(),
@@ -44,6 +55,8 @@ pub(crate) fn create_linux_sandbox_command_args(
     command: Vec<String>,
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
+    deny_read: &[PathBuf],
+    network_proxy_port: Option<u16>,
 ) -> Vec<String> {
     #[expect(clippy::expect_used)]
     let sandbox_policy_cwd = sandbox_policy_cwd
@@ -60,10 +73,20 @@ pub(crate) fn create_linux_sandbox_command_args(
         sandbox_policy_cwd,
         "--sandbox-policy".to_string(),
         sandbox_policy_json,
+    ];
+    for path in deny_read {
+        linux_cmd.push("--deny-read".to_string());
+        linux_cmd.push(path.to_string_lossy().to_string());
+    }
+    if let Some(port) = network_proxy_port {
+        linux_cmd.push("--network-proxy-port".to_string());
+        linux_cmd.push(port.to_string());
+    }
+    linux_cmd.extend([
         // Separator so that command arguments starting with `-` are not parsed as
         // options of the helper itself.
         "--".to_string(),
-    ];
+    ]);
 
     // Append the original tool command.
     linux_cmd.extend(command);
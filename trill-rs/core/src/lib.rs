@@ -33,6 +33,7 @@ mod exec_policy;
 pub mod features;
 mod flags;
 pub mod git_info;
+mod hooks;
 pub mod instructions;
 pub mod landlock;
 pub mod mcp;
@@ -53,19 +54,30 @@ mod proposed_plan_parser;
 pub mod sandboxing;
 mod session_prefix;
 mod stream_events_utils;
+mod structured_output;
 mod tagged_block_parser;
 mod text_encoding;
+mod tool_loop;
 pub mod token_data;
 mod truncate;
 mod unified_exec;
 pub mod windows_sandbox;
 pub use model_provider_info::CHAT_WIRE_API_DEPRECATION_SUMMARY;
+pub use model_provider_info::DEFAULT_KOBOLDCPP_PORT;
+pub use model_provider_info::DEFAULT_LLAMACPP_PORT;
 pub use model_provider_info::DEFAULT_LMSTUDIO_PORT;
 pub use model_provider_info::DEFAULT_OLLAMA_PORT;
+pub use model_provider_info::DEFAULT_TABBYAPI_PORT;
+pub use model_provider_info::DEFAULT_VLLM_PORT;
+pub use model_provider_info::KOBOLDCPP_OSS_PROVIDER_ID;
+pub use model_provider_info::LLAMACPP_OSS_PROVIDER_ID;
 pub use model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
 pub use model_provider_info::ModelProviderInfo;
 pub use model_provider_info::OLLAMA_CHAT_PROVIDER_ID;
 pub use model_provider_info::OLLAMA_OSS_PROVIDER_ID;
+pub use model_provider_info::OPENAI_COMPATIBLE_OSS_PROVIDER_IDS;
+pub use model_provider_info::TABBYAPI_OSS_PROVIDER_ID;
+pub use model_provider_info::VLLM_OSS_PROVIDER_ID;
 pub use model_provider_info::WireApi;
 pub use model_provider_info::built_in_model_providers;
 pub use model_provider_info::create_oss_provider_with_base_url;
@@ -0,0 +1,160 @@
+//! Turns the messages of an expanded MCP prompt into conversation input.
+//!
+//! `prompts/get` returns a list of role-tagged messages. Everything up to the
+//! last assistant message is recorded in history as-is, so the model sees the
+//! exchange the prompt describes; the trailing user messages become the input
+//! of the turn that follows.
+
+use mcp_types::ContentBlock;
+use mcp_types::EmbeddedResourceResource;
+use mcp_types::ImageContent;
+use mcp_types::PromptMessage;
+use mcp_types::Role;
+use trill_protocol::models::ContentItem;
+use trill_protocol::models::ResponseItem;
+use trill_protocol::user_input::UserInput;
+
+/// Splits prompt messages into the items recorded ahead of the turn and the
+/// user input that starts it. Content without a text or image form (audio,
+/// resource links, binary resources) is dropped.
+pub(crate) fn prompt_turn_input(
+    mut messages: Vec<PromptMessage>,
+) -> (Vec<ResponseItem>, Vec<UserInput>) {
+    let split = messages
+        .iter()
+        .rposition(|message| matches!(message.role, Role::Assistant))
+        .map_or(0, |idx| idx + 1);
+    let trailing = messages.split_off(split);
+
+    let history = messages.into_iter().filter_map(response_item).collect();
+    let input = trailing
+        .into_iter()
+        .filter_map(|message| match message.content {
+            ContentBlock::ImageContent(image) => Some(UserInput::Image {
+                image_url: image_url(&image),
+            }),
+            content => text(content).map(|text| UserInput::Text {
+                text,
+                text_elements: Vec::new(),
+            }),
+        })
+        .collect();
+    (history, input)
+}
+
+fn response_item(message: PromptMessage) -> Option<ResponseItem> {
+    let (role, content) = match (message.role, message.content) {
+        (Role::User, ContentBlock::ImageContent(image)) => (
+            "user",
+            ContentItem::InputImage {
+                image_url: image_url(&image),
+            },
+        ),
+        (Role::User, content) => (
+            "user",
+            ContentItem::InputText {
+                text: text(content)?,
+            },
+        ),
+        (Role::Assistant, content) => (
+            "assistant",
+            ContentItem::OutputText {
+                text: text(content)?,
+            },
+        ),
+    };
+    Some(ResponseItem::Message {
+        id: None,
+        role: role.to_string(),
+        content: vec![content],
+        end_turn: None,
+    })
+}
+
+fn text(content: ContentBlock) -> Option<String> {
+    match content {
+        ContentBlock::TextContent(text) => Some(text.text),
+        ContentBlock::EmbeddedResource(resource) => match resource.resource {
+            EmbeddedResourceResource::TextResourceContents(contents) => Some(contents.text),
+            EmbeddedResourceResource::BlobResourceContents(_) => None,
+        },
+        ContentBlock::ImageContent(_)
+        | ContentBlock::AudioContent(_)
+        | ContentBlock::ResourceLink(_) => None,
+    }
+}
+
+fn image_url(image: &ImageContent) -> String {
+    format!("data:{};base64,{}", image.mime_type, image.data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::TextContent;
+    use pretty_assertions::assert_eq;
+
+    fn message(role: Role, text: &str) -> PromptMessage {
+        PromptMessage {
+            content: ContentBlock::TextContent(TextContent {
+                annotations: None,
+                text: text.to_string(),
+                r#type: "text".to_string(),
+            }),
+            role,
+        }
+    }
+
+    #[test]
+    fn prompt_messages_keep_their_roles() {
+        let (history, input) = prompt_turn_input(vec![
+            message(Role::User, "review this diff"),
+            message(Role::Assistant, "which file first?"),
+            message(Role::User, "start with lib.rs"),
+            message(Role::User, "then main.rs"),
+        ]);
+
+        assert_eq!(
+            history,
+            vec![
+                ResponseItem::Message {
+                    id: None,
+                    role: "user".to_string(),
+                    content: vec![ContentItem::InputText {
+                        text: "review this diff".to_string(),
+                    }],
+                    end_turn: None,
+                },
+                ResponseItem::Message {
+                    id: None,
+                    role: "assistant".to_string(),
+                    content: vec![ContentItem::OutputText {
+                        text: "which file first?".to_string(),
+                    }],
+                    end_turn: None,
+                },
+            ]
+        );
+        assert_eq!(
+            input,
+            vec![
+                UserInput::Text {
+                    text: "start with lib.rs".to_string(),
+                    text_elements: Vec::new(),
+                },
+                UserInput::Text {
+                    text: "then main.rs".to_string(),
+                    text_elements: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn prompt_ending_with_assistant_message_has_no_input() {
+        let (history, input) = prompt_turn_input(vec![message(Role::Assistant, "hello")]);
+
+        assert_eq!(history.len(), 1);
+        assert_eq!(input, Vec::new());
+    }
+}
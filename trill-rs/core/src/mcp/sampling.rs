@@ -0,0 +1,556 @@
+//! Answers `sampling/createMessage` requests from MCP servers with the
+//! session's own provider.
+//!
+//! MCP clients push each request onto a channel owned by the session. A
+//! worker asks the user for approval, maps the server's model hints to a
+//! configured model, caps `maxTokens`, and streams the reply through a
+//! [`ModelClient`]. Every step is reported as an
+//! [`EventMsg::McpSamplingProgress`] so it lands in the rollout.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::Weak;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use anyhow::bail;
+use async_channel::Receiver;
+use async_channel::Sender;
+use futures::FutureExt;
+use futures::StreamExt;
+use mcp_types::CreateMessageResultContent;
+use mcp_types::ModelPreferences;
+use mcp_types::RequestId;
+use mcp_types::Role;
+use mcp_types::SamplingMessage;
+use mcp_types::SamplingMessageContent;
+use mcp_types::TextContent;
+use tokio::sync::Mutex;
+use tokio::sync::oneshot;
+use tracing::warn;
+use trill_protocol::approvals::McpSamplingMessage;
+use trill_protocol::approvals::McpSamplingRequestEvent;
+use trill_protocol::models::BaseInstructions;
+use trill_protocol::models::ContentItem;
+use trill_protocol::models::ResponseItem;
+use trill_protocol::protocol::EventMsg;
+use trill_protocol::protocol::McpSamplingProgressEvent;
+use trill_protocol::protocol::ReviewDecision;
+use trill_rmcp_client::Sampling;
+use trill_rmcp_client::SamplingResponse;
+use trill_rmcp_client::SendSampling;
+
+use crate::client::ModelClient;
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::config::Config;
+use crate::trill::Session;
+use crate::trill::TurnContext;
+
+/// A sampling request from one MCP server, waiting to be answered.
+pub(crate) struct McpSamplingRequest {
+    server_name: String,
+    id: RequestId,
+    params: Sampling,
+    responder: oneshot::Sender<Result<SamplingResponse>>,
+}
+
+/// Builds the callback an MCP client uses to forward `sampling/createMessage`
+/// requests to the session.
+pub(crate) fn make_sampling_sender(
+    server_name: String,
+    requests: Sender<McpSamplingRequest>,
+) -> SendSampling {
+    Box::new(move |id, params| {
+        let requests = requests.clone();
+        let server_name = server_name.clone();
+        async move {
+            let (responder, response) = oneshot::channel();
+            requests
+                .send(McpSamplingRequest {
+                    server_name,
+                    id,
+                    params,
+                    responder,
+                })
+                .await
+                .map_err(|_| anyhow!("session is no longer accepting sampling requests"))?;
+            response
+                .await
+                .context("sampling request dropped before it was answered")?
+        }
+        .boxed()
+    })
+}
+
+type ApprovalMap = HashMap<(String, RequestId), oneshot::Sender<ReviewDecision>>;
+
+/// Session-side state for MCP sampling.
+pub(crate) struct McpSamplingManager {
+    requests: Sender<McpSamplingRequest>,
+    pending_approvals: Mutex<ApprovalMap>,
+    /// Servers whose requests were approved for the rest of the session.
+    approved_servers: Mutex<HashSet<String>>,
+}
+
+impl McpSamplingManager {
+    pub(crate) fn new() -> (Self, Receiver<McpSamplingRequest>) {
+        let (requests, rx) = async_channel::unbounded();
+        let manager = Self {
+            requests,
+            pending_approvals: Mutex::new(HashMap::new()),
+            approved_servers: Mutex::new(HashSet::new()),
+        };
+        (manager, rx)
+    }
+
+    /// Channel for MCP clients, or `None` when sampling is disabled and the
+    /// capability should not be advertised.
+    pub(crate) fn requests(&self, config: &Config) -> Option<Sender<McpSamplingRequest>> {
+        config.mcp_sampling.enabled.then(|| self.requests.clone())
+    }
+
+    pub(crate) async fn resolve(
+        &self,
+        server_name: String,
+        id: RequestId,
+        decision: ReviewDecision,
+    ) -> Result<()> {
+        self.pending_approvals
+            .lock()
+            .await
+            .remove(&(server_name, id))
+            .ok_or_else(|| anyhow!("sampling request not found"))?
+            .send(decision)
+            .map_err(|_| anyhow!("sampling request is no longer waiting for approval"))
+    }
+}
+
+/// Answers sampling requests until the session is dropped.
+pub(crate) fn spawn_sampling_worker(session: Weak<Session>, rx: Receiver<McpSamplingRequest>) {
+    tokio::spawn(async move {
+        while let Ok(request) = rx.recv().await {
+            let Some(sess) = session.upgrade() else {
+                break;
+            };
+            tokio::spawn(handle_request(sess, request));
+        }
+    });
+}
+
+async fn handle_request(sess: Arc<Session>, request: McpSamplingRequest) {
+    let McpSamplingRequest {
+        server_name,
+        id,
+        params,
+        responder,
+    } = request;
+    let turn_context = sess.new_default_turn().await;
+    let result = sample(&sess, &turn_context, &server_name, &id, params).await;
+    if let Err(err) = &result {
+        send_progress(
+            &sess,
+            &turn_context,
+            &server_name,
+            &id,
+            format!("sampling failed: {err:#}"),
+        )
+        .await;
+    }
+    if responder.send(result).is_err() {
+        warn!("MCP server {server_name} stopped waiting for sampling request {id:?}");
+    }
+}
+
+async fn sample(
+    sess: &Session,
+    turn_context: &TurnContext,
+    server_name: &str,
+    id: &RequestId,
+    params: Sampling,
+) -> Result<SamplingResponse> {
+    let config = turn_context.client.config();
+    let model = resolve_model(
+        &config,
+        &turn_context.client.get_model(),
+        params.model_preferences.as_ref(),
+    );
+    let max_tokens = capped_max_tokens(params.max_tokens, config.mcp_sampling.max_tokens);
+    let input = params
+        .messages
+        .iter()
+        .map(response_item)
+        .collect::<Result<Vec<_>>>()?;
+
+    let decision = request_approval(
+        sess,
+        turn_context,
+        McpSamplingRequestEvent {
+            server_name: server_name.to_string(),
+            id: id.clone(),
+            model: model.clone(),
+            max_tokens,
+            system_prompt: params.system_prompt.clone(),
+            messages: params.messages.iter().map(message_preview).collect(),
+        },
+    )
+    .await;
+    match decision {
+        ReviewDecision::Approved
+        | ReviewDecision::ApprovedForSession
+        | ReviewDecision::ApprovedExecpolicyAmendment { .. } => {}
+        ReviewDecision::Denied | ReviewDecision::Abort => {
+            bail!("the user declined the sampling request")
+        }
+    }
+
+    send_progress(
+        sess,
+        turn_context,
+        server_name,
+        id,
+        format!("sampling `{model}` (max {max_tokens} tokens)"),
+    )
+    .await;
+
+    let mut request_config = (*config).clone();
+    request_config.model = Some(model.clone());
+    request_config.model_sampling.max_tokens = Some(u64::from(max_tokens));
+    if params.temperature.is_some() {
+        request_config.model_sampling.temperature = params.temperature;
+    }
+    if let Some(stop) = params.stop_sequences.filter(|stop| !stop.is_empty()) {
+        request_config.model_sampling.stop = Some(stop);
+    }
+    let model_info = sess
+        .services
+        .models_manager
+        .get_model_info(&model, &request_config)
+        .await;
+    let otel_manager = turn_context
+        .client
+        .get_otel_manager()
+        .with_model(model.as_str(), model_info.slug.as_str());
+    let client = ModelClient::new(
+        Arc::new(request_config),
+        turn_context.client.get_auth_manager(),
+        model_info,
+        otel_manager,
+        turn_context.client.get_provider(),
+        turn_context.client.get_reasoning_effort(),
+        turn_context.client.get_reasoning_summary(),
+        sess.conversation_id,
+        turn_context.client.get_session_source(),
+        turn_context.client.transport_manager(),
+    );
+    let prompt = Prompt {
+        input,
+        base_instructions: BaseInstructions {
+            text: params.system_prompt.unwrap_or_default(),
+        },
+        ..Default::default()
+    };
+
+    let (text, output_tokens) = stream_reply(&client, &prompt).await?;
+    let stop_reason = if output_tokens.is_some_and(|tokens| tokens >= i64::from(max_tokens)) {
+        "maxTokens"
+    } else {
+        "endTurn"
+    };
+    send_progress(
+        sess,
+        turn_context,
+        server_name,
+        id,
+        format!("`{model}` replied ({stop_reason}):\n{text}"),
+    )
+    .await;
+
+    Ok(SamplingResponse {
+        content: CreateMessageResultContent::TextContent(TextContent {
+            annotations: None,
+            text,
+            r#type: "text".to_string(),
+        }),
+        model,
+        role: Role::Assistant,
+        stop_reason: Some(stop_reason.to_string()),
+    })
+}
+
+async fn request_approval(
+    sess: &Session,
+    turn_context: &TurnContext,
+    event: McpSamplingRequestEvent,
+) -> ReviewDecision {
+    let manager = &sess.services.mcp_sampling;
+    if manager
+        .approved_servers
+        .lock()
+        .await
+        .contains(&event.server_name)
+    {
+        return ReviewDecision::Approved;
+    }
+
+    let server_name = event.server_name.clone();
+    let (tx, rx) = oneshot::channel();
+    manager
+        .pending_approvals
+        .lock()
+        .await
+        .insert((server_name.clone(), event.id.clone()), tx);
+    sess.send_event(turn_context, EventMsg::McpSamplingRequest(event))
+        .await;
+    let decision = rx.await.unwrap_or(ReviewDecision::Abort);
+    if decision == ReviewDecision::ApprovedForSession {
+        manager.approved_servers.lock().await.insert(server_name);
+    }
+    decision
+}
+
+async fn send_progress(
+    sess: &Session,
+    turn_context: &TurnContext,
+    server_name: &str,
+    id: &RequestId,
+    message: String,
+) {
+    sess.send_event(
+        turn_context,
+        EventMsg::McpSamplingProgress(McpSamplingProgressEvent {
+            server_name: server_name.to_string(),
+            id: id.clone(),
+            message,
+        }),
+    )
+    .await;
+}
+
+/// Streams `prompt` and returns the assistant text with the output token
+/// count, when the provider reported one.
+async fn stream_reply(client: &ModelClient, prompt: &Prompt) -> Result<(String, Option<i64>)> {
+    let mut session = client.new_session();
+    let mut stream = session.stream(prompt).await?;
+    let mut text = String::new();
+    while let Some(event) = stream.next().await {
+        match event? {
+            ResponseEvent::OutputItemDone(ResponseItem::Message { role, content, .. })
+                if role == "assistant" =>
+            {
+                for item in content {
+                    if let ContentItem::OutputText { text: chunk } = item {
+                        text.push_str(&chunk);
+                    }
+                }
+            }
+            ResponseEvent::Completed { token_usage, .. } => {
+                return Ok((text, token_usage.map(|usage| usage.output_tokens)));
+            }
+            _ => {}
+        }
+    }
+    bail!("stream closed before the sampling response completed")
+}
+
+/// Picks the model for a request: the first hint that matches a
+/// `[mcp_sampling.models]` key or names a configured model, else the
+/// session model. Hints match as case-insensitive substrings, per the MCP
+/// spec.
+fn resolve_model(
+    config: &Config,
+    session_model: &str,
+    preferences: Option<&ModelPreferences>,
+) -> String {
+    let hints = preferences
+        .and_then(|preferences| preferences.hints.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|hint| hint.name.as_deref())
+        .map(str::to_lowercase)
+        .filter(|hint| !hint.is_empty());
+
+    let mut configured = vec![session_model.to_string()];
+    configured.extend(config.review_model.clone());
+    let mut model_settings = config.model_settings.keys().cloned().collect::<Vec<_>>();
+    model_settings.sort();
+    configured.extend(model_settings);
+
+    for hint in hints {
+        let alias = config
+            .mcp_sampling
+            .models
+            .iter()
+            .filter(|(key, _)| hint.contains(&key.to_lowercase()))
+            .max_by_key(|(key, _)| key.len());
+        if let Some((_, model)) = alias {
+            return model.clone();
+        }
+        if let Some(model) = configured
+            .iter()
+            .find(|model| model.to_lowercase().contains(&hint))
+        {
+            return model.clone();
+        }
+    }
+    session_model.to_string()
+}
+
+fn capped_max_tokens(requested: i64, cap: u32) -> u32 {
+    u32::try_from(requested).unwrap_or(0).clamp(1, cap)
+}
+
+fn response_item(message: &SamplingMessage) -> Result<ResponseItem> {
+    let (role, content) = match (&message.role, &message.content) {
+        (Role::User, SamplingMessageContent::TextContent(content)) => (
+            "user",
+            ContentItem::InputText {
+                text: content.text.clone(),
+            },
+        ),
+        (Role::Assistant, SamplingMessageContent::TextContent(content)) => (
+            "assistant",
+            ContentItem::OutputText {
+                text: content.text.clone(),
+            },
+        ),
+        (Role::User, SamplingMessageContent::ImageContent(image)) => (
+            "user",
+            ContentItem::InputImage {
+                image_url: format!("data:{};base64,{}", image.mime_type, image.data),
+            },
+        ),
+        (Role::Assistant, SamplingMessageContent::ImageContent(_)) => {
+            bail!("assistant image messages are not supported")
+        }
+        (_, SamplingMessageContent::AudioContent(_)) => {
+            bail!("audio messages are not supported")
+        }
+    };
+    Ok(ResponseItem::Message {
+        id: None,
+        role: role.to_string(),
+        content: vec![content],
+        end_turn: None,
+    })
+}
+
+fn message_preview(message: &SamplingMessage) -> McpSamplingMessage {
+    let text = match &message.content {
+        SamplingMessageContent::TextContent(content) => content.text.clone(),
+        SamplingMessageContent::ImageContent(image) => format!("[image: {}]", image.mime_type),
+        SamplingMessageContent::AudioContent(audio) => format!("[audio: {}]", audio.mime_type),
+    };
+    McpSamplingMessage {
+        role: message.role.clone(),
+        text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigBuilder;
+    use mcp_types::ModelHint;
+    use pretty_assertions::assert_eq;
+
+    fn preferences(hints: &[&str]) -> ModelPreferences {
+        ModelPreferences {
+            cost_priority: None,
+            hints: Some(
+                hints
+                    .iter()
+                    .map(|name| ModelHint {
+                        name: Some((*name).to_string()),
+                    })
+                    .collect(),
+            ),
+            intelligence_priority: None,
+            speed_priority: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn hints_map_to_aliases_then_configured_models() -> std::io::Result<()> {
+        let trill_home = tempfile::TempDir::new()?;
+        std::fs::write(
+            trill_home.path().join("config.toml"),
+            r#"
+review_model = "qwen3-coder-30b"
+
+[model_settings."gemma-3-12b"]
+context_window = 32000
+
+[mcp_sampling.models]
+claude = "gemma-3-12b"
+claude-3-haiku = "qwen3-4b"
+"#,
+        )?;
+        let config = ConfigBuilder::default()
+            .trill_home(trill_home.path().to_path_buf())
+            .build()
+            .await?;
+
+        let resolve =
+            |hints: &[&str]| resolve_model(&config, "llama-3.1-8b", Some(&preferences(hints)));
+        assert_eq!(resolve(&["claude-3-sonnet"]), "gemma-3-12b");
+        assert_eq!(resolve(&["claude-3-haiku-20240307"]), "qwen3-4b");
+        assert_eq!(resolve(&["gpt-4o", "Qwen3-Coder"]), "qwen3-coder-30b");
+        assert_eq!(resolve(&["gemma"]), "gemma-3-12b");
+        assert_eq!(resolve(&["gpt-4o"]), "llama-3.1-8b");
+        assert_eq!(resolve_model(&config, "llama-3.1-8b", None), "llama-3.1-8b");
+        Ok(())
+    }
+
+    #[test]
+    fn max_tokens_are_capped() {
+        assert_eq!(capped_max_tokens(100, 4096), 100);
+        assert_eq!(capped_max_tokens(100_000, 4096), 4096);
+        assert_eq!(capped_max_tokens(-1, 4096), 1);
+    }
+
+    #[test]
+    fn messages_convert_to_response_items() {
+        let message =
+            |role: Role, content: SamplingMessageContent| SamplingMessage { content, role };
+        let text = |text: &str| {
+            SamplingMessageContent::TextContent(TextContent {
+                annotations: None,
+                text: text.to_string(),
+                r#type: "text".to_string(),
+            })
+        };
+
+        assert_eq!(
+            response_item(&message(Role::Assistant, text("earlier reply"))).expect("text"),
+            ResponseItem::Message {
+                id: None,
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText {
+                    text: "earlier reply".to_string(),
+                }],
+                end_turn: None,
+            }
+        );
+
+        let audio = message(
+            Role::User,
+            SamplingMessageContent::AudioContent(mcp_types::AudioContent {
+                annotations: None,
+                data: String::new(),
+                mime_type: "audio/wav".to_string(),
+                r#type: "audio".to_string(),
+            }),
+        );
+        assert!(response_item(&audio).is_err());
+        assert_eq!(
+            message_preview(&audio),
+            McpSamplingMessage {
+                role: Role::User,
+                text: "[audio: audio/wav]".to_string(),
+            }
+        );
+    }
+}
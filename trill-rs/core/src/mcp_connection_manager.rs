@@ -10,12 +10,15 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::env;
 use std::ffi::OsString;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
 use crate::mcp::CODEX_APPS_MCP_SERVER_NAME;
 use crate::mcp::auth::McpAuthStatusEntry;
+use crate::mcp::sampling::McpSamplingRequest;
+use crate::mcp::sampling::make_sampling_sender;
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
@@ -30,6 +33,7 @@ use trill_protocol::protocol::McpStartupFailure;
 use trill_protocol::protocol::McpStartupStatus;
 use trill_protocol::protocol::McpStartupUpdateEvent;
 use trill_protocol::protocol::SandboxPolicy;
+use trill_utils_absolute_path::AbsolutePathBuf;
 use trill_rmcp_client::ElicitationResponse;
 use trill_rmcp_client::OAuthCredentialsStoreMode;
 use trill_rmcp_client::RmcpClient;
@@ -38,16 +42,22 @@ use futures::future::BoxFuture;
 use futures::future::FutureExt;
 use futures::future::Shared;
 use mcp_types::ClientCapabilities;
+use mcp_types::ClientCapabilitiesRoots;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
 use mcp_types::Implementation;
+use mcp_types::ListPromptsRequestParams;
 use mcp_types::ListResourceTemplatesRequestParams;
 use mcp_types::ListResourceTemplatesResult;
 use mcp_types::ListResourcesRequestParams;
 use mcp_types::ListResourcesResult;
+use mcp_types::Prompt;
 use mcp_types::ReadResourceRequestParams;
 use mcp_types::ReadResourceResult;
 use mcp_types::RequestId;
 use mcp_types::Resource;
 use mcp_types::ResourceTemplate;
+use mcp_types::Root;
 use mcp_types::Tool;
 
 use serde::Deserialize;
@@ -61,6 +71,7 @@ use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
 use tracing::instrument;
 use tracing::warn;
+use url::Url;
 
 use crate::trill::INITIAL_SUBMIT_ID;
 use crate::config::types::McpServerConfig;
@@ -218,11 +229,14 @@ struct ManagedClient {
     tool_filter: ToolFilter,
     tool_timeout: Option<Duration>,
     server_supports_sandbox_state_capability: bool,
+    server_supports_prompts: bool,
 }
 
 impl ManagedClient {
     /// Returns once the server has ack'd the sandbox state update.
     async fn notify_sandbox_state_change(&self, sandbox_state: &SandboxState) -> Result<()> {
+        let roots = workspace_roots(&sandbox_state.sandbox_cwd, &sandbox_state.sandbox_policy);
+        self.client.set_roots(roots).await?;
         if !self.server_supports_sandbox_state_capability {
             return Ok(());
         }
@@ -244,6 +258,7 @@ struct AsyncManagedClient {
 }
 
 impl AsyncManagedClient {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         server_name: String,
         config: McpServerConfig,
@@ -251,6 +266,8 @@ impl AsyncManagedClient {
         cancel_token: CancellationToken,
         tx_event: Sender<Event>,
         elicitation_requests: ElicitationRequestManager,
+        sampling_requests: Option<Sender<McpSamplingRequest>>,
+        roots: Vec<Root>,
     ) -> Self {
         let tool_filter = ToolFilter::from_config(&config);
         let fut = async move {
@@ -260,6 +277,7 @@ impl AsyncManagedClient {
 
             let client =
                 Arc::new(make_rmcp_client(&server_name, config.transport, store_mode).await?);
+            client.set_roots(roots).await?;
             match start_server_task(
                 server_name,
                 client,
@@ -268,6 +286,7 @@ impl AsyncManagedClient {
                 tool_filter,
                 tx_event,
                 elicitation_requests,
+                sampling_requests,
             )
             .or_cancel(&cancel_token)
             .await
@@ -291,6 +310,36 @@ impl AsyncManagedClient {
     }
 }
 
+/// Roots advertised to MCP servers. The session cwd is a root in every
+/// sandbox mode; a workspace-write sandbox adds its extra writable roots
+/// (including `--add-dir`).
+pub(crate) fn workspace_roots(cwd: &Path, sandbox_policy: &SandboxPolicy) -> Vec<Root> {
+    let extra_roots: &[AbsolutePathBuf] = match sandbox_policy {
+        SandboxPolicy::WorkspaceWrite { writable_roots, .. } => writable_roots,
+        SandboxPolicy::DangerFullAccess
+        | SandboxPolicy::ReadOnly
+        | SandboxPolicy::ExternalSandbox { .. } => &[],
+    };
+    let mut dirs = vec![cwd.to_path_buf()];
+    for root in extra_roots {
+        let dir = root.to_path_buf();
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs.into_iter()
+        .filter_map(|dir| {
+            let uri = Url::from_file_path(&dir).ok()?;
+            Some(Root {
+                name: dir
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned()),
+                uri: uri.to_string(),
+            })
+        })
+        .collect()
+}
+
 pub const MCP_SANDBOX_STATE_CAPABILITY: &str = "codex/sandbox-state";
 
 /// Custom MCP request to push sandbox state updates.
@@ -313,6 +362,7 @@ pub(crate) struct McpConnectionManager {
 }
 
 impl McpConnectionManager {
+    #[allow(clippy::too_many_arguments)]
     pub async fn initialize(
         &mut self,
         mcp_servers: &HashMap<String, McpServerConfig>,
@@ -321,6 +371,7 @@ impl McpConnectionManager {
         tx_event: Sender<Event>,
         cancel_token: CancellationToken,
         initial_sandbox_state: SandboxState,
+        sampling_requests: Option<Sender<McpSamplingRequest>>,
     ) {
         if cancel_token.is_cancelled() {
             return;
@@ -328,6 +379,10 @@ impl McpConnectionManager {
         let mut clients = HashMap::new();
         let mut join_set = JoinSet::new();
         let elicitation_requests = ElicitationRequestManager::default();
+        let roots = workspace_roots(
+            &initial_sandbox_state.sandbox_cwd,
+            &initial_sandbox_state.sandbox_policy,
+        );
         let mcp_servers = mcp_servers.clone();
         for (server_name, cfg) in mcp_servers.into_iter().filter(|(_, cfg)| cfg.enabled) {
             let cancel_token = cancel_token.child_token();
@@ -346,6 +401,8 @@ impl McpConnectionManager {
                 cancel_token.clone(),
                 tx_event.clone(),
                 elicitation_requests.clone(),
+                sampling_requests.clone(),
+                roots.clone(),
             );
             clients.insert(server_name.clone(), async_managed_client.clone());
             let tx_event = tx_event.clone();
@@ -630,6 +687,89 @@ impl McpConnectionManager {
             .with_context(|| format!("tool call failed for `{server}/{tool}`"))
     }
 
+    /// Returns the prompts of every server that advertises the `prompts`
+    /// capability, keyed by server name.
+    pub async fn list_all_prompts(&self) -> HashMap<String, Vec<Prompt>> {
+        let mut join_set = JoinSet::new();
+
+        for (server_name, async_managed_client) in &self.clients {
+            let server_name = server_name.clone();
+            let Ok(managed_client) = async_managed_client.client().await else {
+                continue;
+            };
+            if !managed_client.server_supports_prompts {
+                continue;
+            }
+            let timeout = managed_client.tool_timeout;
+            let client = managed_client.client.clone();
+
+            join_set.spawn(async move {
+                let mut collected: Vec<Prompt> = Vec::new();
+                let mut cursor: Option<String> = None;
+
+                loop {
+                    let params = cursor.as_ref().map(|next| ListPromptsRequestParams {
+                        cursor: Some(next.clone()),
+                    });
+                    let response = match client.list_prompts(params, timeout).await {
+                        Ok(result) => result,
+                        Err(err) => return (server_name, Err(err)),
+                    };
+
+                    collected.extend(response.prompts);
+
+                    match response.next_cursor {
+                        Some(next) => {
+                            if cursor.as_ref() == Some(&next) {
+                                return (
+                                    server_name,
+                                    Err(anyhow!("prompts/list returned duplicate cursor")),
+                                );
+                            }
+                            cursor = Some(next);
+                        }
+                        None => return (server_name, Ok(collected)),
+                    }
+                }
+            });
+        }
+
+        let mut aggregated: HashMap<String, Vec<Prompt>> = HashMap::new();
+
+        while let Some(join_res) = join_set.join_next().await {
+            match join_res {
+                Ok((server_name, Ok(prompts))) => {
+                    aggregated.insert(server_name, prompts);
+                }
+                Ok((server_name, Err(err))) => {
+                    warn!("Failed to list prompts for MCP server '{server_name}': {err:#}");
+                }
+                Err(err) => {
+                    warn!("Task panic when listing prompts for MCP server: {err:#}");
+                }
+            }
+        }
+
+        aggregated
+    }
+
+    /// Fetch a prompt, with its arguments filled in, from the specified server.
+    pub async fn get_prompt(
+        &self,
+        server: &str,
+        params: GetPromptRequestParams,
+    ) -> Result<GetPromptResult> {
+        let managed = self.client_by_name(server).await?;
+        let client = managed.client.clone();
+        let timeout = managed.tool_timeout;
+        let name = params.name.clone();
+
+        client
+            .get_prompt(params, timeout)
+            .await
+            .with_context(|| format!("prompts/get failed for `{server}` ({name})"))
+    }
+
     /// List resources from the specified server.
     pub async fn list_resources(
         &self,
@@ -840,6 +980,7 @@ impl From<anyhow::Error> for StartupOutcomeError {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn start_server_task(
     server_name: String,
     client: Arc<RmcpClient>,
@@ -848,12 +989,17 @@ async fn start_server_task(
     tool_filter: ToolFilter,
     tx_event: Sender<Event>,
     elicitation_requests: ElicitationRequestManager,
+    sampling_requests: Option<Sender<McpSamplingRequest>>,
 ) -> Result<ManagedClient, StartupOutcomeError> {
     let params = mcp_types::InitializeRequestParams {
         capabilities: ClientCapabilities {
             experimental: None,
-            roots: None,
-            sampling: None,
+            // Roots follow the session cwd and extra writable roots.
+            roots: Some(ClientCapabilitiesRoots {
+                list_changed: Some(true),
+            }),
+            // Only advertised when the session can answer sampling requests.
+            sampling: sampling_requests.as_ref().map(|_| json!({})),
             // https://modelcontextprotocol.io/specification/2025-06-18/client/elicitation#capabilities
             // indicates this should be an empty object.
             elicitation: Some(json!({})),
@@ -871,9 +1017,11 @@ async fn start_server_task(
     };
 
     let send_elicitation = elicitation_requests.make_sender(server_name.clone(), tx_event);
+    let send_sampling =
+        sampling_requests.map(|requests| make_sampling_sender(server_name.clone(), requests));
 
     let initialize_result = client
-        .initialize(params, startup_timeout, send_elicitation)
+        .initialize(params, startup_timeout, send_elicitation, send_sampling)
         .await
         .map_err(StartupOutcomeError::from)?;
 
@@ -887,6 +1035,7 @@ async fn start_server_task(
         .as_ref()
         .and_then(|exp| exp.get(MCP_SANDBOX_STATE_CAPABILITY))
         .is_some();
+    let server_supports_prompts = initialize_result.capabilities.prompts.is_some();
 
     let managed = ManagedClient {
         client: Arc::clone(&client),
@@ -894,6 +1043,7 @@ async fn start_server_task(
         tool_timeout: Some(tool_timeout),
         tool_filter,
         server_supports_sandbox_state_capability,
+        server_supports_prompts,
     };
 
     Ok(managed)
@@ -1047,6 +1197,7 @@ mod mcp_init_error_display_tests {}
 mod tests {
     use super::*;
     use trill_protocol::protocol::McpAuthStatus;
+    use trill_protocol::protocol::NetworkAccess;
     use mcp_types::ToolInputSchema;
     use std::collections::HashSet;
 
@@ -1311,4 +1462,46 @@ mod tests {
             display
         );
     }
+
+    #[test]
+    fn workspace_roots_lists_cwd_in_every_sandbox_mode() {
+        let cwd = std::env::temp_dir().join("project");
+        let extra = std::env::temp_dir().join("shared");
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![
+                AbsolutePathBuf::from_absolute_path(&extra).expect("absolute path"),
+                AbsolutePathBuf::from_absolute_path(&cwd).expect("absolute path"),
+            ],
+            network_access: false,
+            exclude_tmpdir_env_var: false,
+            exclude_slash_tmp: false,
+        };
+
+        let roots = workspace_roots(&cwd, &policy);
+
+        let expected = vec![
+            Root {
+                name: Some("project".to_string()),
+                uri: Url::from_file_path(&cwd).expect("file url").to_string(),
+            },
+            Root {
+                name: Some("shared".to_string()),
+                uri: Url::from_file_path(&extra).expect("file url").to_string(),
+            },
+        ];
+        assert_eq!(expected, roots);
+        for policy in [
+            SandboxPolicy::ReadOnly,
+            SandboxPolicy::DangerFullAccess,
+            SandboxPolicy::ExternalSandbox {
+                network_access: NetworkAccess::Restricted,
+            },
+        ] {
+            assert_eq!(
+                vec![expected[0].clone()],
+                workspace_roots(&cwd, &policy),
+                "cwd is a root under {policy:?}"
+            );
+        }
+    }
 }
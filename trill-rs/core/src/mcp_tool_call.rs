@@ -5,11 +5,15 @@ use tracing::error;
 
 use crate::trill::Session;
 use crate::trill::TurnContext;
+use crate::features::Feature;
+use crate::exec_policy::ToolRuleAmendment;
 use crate::mcp::CODEX_APPS_MCP_SERVER_NAME;
 use crate::protocol::EventMsg;
 use crate::protocol::McpInvocation;
 use crate::protocol::McpToolCallBeginEvent;
 use crate::protocol::McpToolCallEndEvent;
+use crate::protocol::WarningEvent;
+use crate::tools::orchestrator::ToolOrchestrator;
 use trill_protocol::models::FunctionCallOutputPayload;
 use trill_protocol::models::ResponseInputItem;
 use trill_protocol::protocol::AskForApproval;
@@ -58,12 +62,58 @@ pub(crate) async fn handle_mcp_tool_call(
         arguments: arguments_value.clone(),
     };
 
-    if let Some(decision) =
-        maybe_request_mcp_tool_approval(sess.as_ref(), turn_context, &call_id, &server, &tool_name)
+    // An `mcp_tool_rule` match replaces the app approval prompt below.
+    let decision = match sess.services.exec_policy.mcp_tool_requirement(
+        &server,
+        &tool_name,
+        turn_context.approval_policy,
+    ) {
+        Some(requirement) => Some(
+            match ToolOrchestrator::enforce_policy(
+                sess.as_ref(),
+                turn_context,
+                &call_id,
+                &tool_name,
+                requirement,
+                Some(ToolRuleAmendment::McpTool {
+                    server: server.clone(),
+                    tool: tool_name.clone(),
+                }),
+            )
             .await
-    {
+            {
+                Ok(()) => McpToolApprovalDecision::Accept,
+                Err(reason) => McpToolApprovalDecision::PolicyRejected(reason),
+            },
+        ),
+        None => {
+            maybe_request_mcp_tool_approval(
+                sess.as_ref(),
+                turn_context,
+                &call_id,
+                &server,
+                &tool_name,
+            )
+            .await
+        }
+    };
+
+    if let Some(decision) = decision {
+        if matches!(decision, McpToolApprovalDecision::AlwaysAllow)
+            && let Err(err) = sess
+                .persist_tool_rule_amendment(&ToolRuleAmendment::McpTool {
+                    server: server.clone(),
+                    tool: tool_name.clone(),
+                })
+                .await
+        {
+            let message = format!("Failed to remember approval for {server}/{tool_name}: {err}");
+            tracing::warn!("{message}");
+            sess.send_event(turn_context, EventMsg::Warning(WarningEvent { message }))
+                .await;
+        }
         let result = match decision {
-            McpToolApprovalDecision::Accept => {
+            McpToolApprovalDecision::Accept | McpToolApprovalDecision::AlwaysAllow => {
                 let tool_call_begin_event = EventMsg::McpToolCallBegin(McpToolCallBeginEvent {
                     call_id: call_id.clone(),
                     invocation: invocation.clone(),
@@ -115,6 +165,17 @@ pub(crate) async fn handle_mcp_tool_call(
                 )
                 .await
             }
+            McpToolApprovalDecision::PolicyRejected(reason) => {
+                let message = format!("MCP tool call rejected: {reason}");
+                notify_mcp_tool_call_skip(
+                    sess.as_ref(),
+                    turn_context,
+                    &call_id,
+                    invocation,
+                    message,
+                )
+                .await
+            }
         };
 
         let status = if result.is_ok() { "ok" } else { "error" };
@@ -163,11 +224,14 @@ async fn notify_mcp_tool_call_event(sess: &Session, turn_context: &TurnContext,
     sess.send_event(turn_context, event).await;
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum McpToolApprovalDecision {
     Accept,
+    /// Accept and persist an `mcp_tool_rule` so the tool no longer prompts.
+    AlwaysAllow,
     Decline,
     Cancel,
+    PolicyRejected(String),
 }
 
 struct McpToolApprovalMetadata {
@@ -178,6 +242,7 @@ struct McpToolApprovalMetadata {
 
 const MCP_TOOL_APPROVAL_QUESTION_ID_PREFIX: &str = "mcp_tool_call_approval";
 const MCP_TOOL_APPROVAL_ACCEPT: &str = "Accept";
+const MCP_TOOL_APPROVAL_ALWAYS_ALLOW: &str = "Always allow";
 const MCP_TOOL_APPROVAL_DECLINE: &str = "Decline";
 const MCP_TOOL_APPROVAL_CANCEL: &str = "Cancel";
 
@@ -207,6 +272,7 @@ async fn maybe_request_mcp_tool_approval(
         metadata.tool_title.as_deref(),
         metadata.connector_name.as_deref(),
         &metadata.annotations,
+        sess.features().enabled(Feature::ExecPolicy),
     );
     let args = RequestUserInputArgs {
         questions: vec![question],
@@ -260,6 +326,7 @@ fn build_mcp_tool_approval_question(
     tool_title: Option<&str>,
     connector_name: Option<&str>,
     annotations: &ToolAnnotations,
+    offer_always_allow: bool,
 ) -> RequestUserInputQuestion {
     let destructive = annotations.destructive_hint == Some(true);
     let open_world = annotations.open_world_hint == Some(true);
@@ -278,26 +345,34 @@ fn build_mcp_tool_approval_question(
         "{app_label} wants to run the tool \"{tool_label}\", which {reason}. Allow this action?"
     );
 
+    let mut options = vec![RequestUserInputQuestionOption {
+        label: MCP_TOOL_APPROVAL_ACCEPT.to_string(),
+        description: "Run the tool and continue.".to_string(),
+    }];
+    if offer_always_allow {
+        options.push(RequestUserInputQuestionOption {
+            label: MCP_TOOL_APPROVAL_ALWAYS_ALLOW.to_string(),
+            description: "Run the tool and add a rule so it is not asked about again.".to_string(),
+        });
+    }
+    options.extend([
+        RequestUserInputQuestionOption {
+            label: MCP_TOOL_APPROVAL_DECLINE.to_string(),
+            description: "Decline this tool call and continue.".to_string(),
+        },
+        RequestUserInputQuestionOption {
+            label: MCP_TOOL_APPROVAL_CANCEL.to_string(),
+            description: "Cancel this tool call".to_string(),
+        },
+    ]);
+
     RequestUserInputQuestion {
         id: question_id,
         header: "Approve app tool call?".to_string(),
         question,
         is_other: false,
         is_secret: false,
-        options: Some(vec![
-            RequestUserInputQuestionOption {
-                label: MCP_TOOL_APPROVAL_ACCEPT.to_string(),
-                description: "Run the tool and continue.".to_string(),
-            },
-            RequestUserInputQuestionOption {
-                label: MCP_TOOL_APPROVAL_DECLINE.to_string(),
-                description: "Decline this tool call and continue.".to_string(),
-            },
-            RequestUserInputQuestionOption {
-                label: MCP_TOOL_APPROVAL_CANCEL.to_string(),
-                description: "Cancel this tool call".to_string(),
-            },
-        ]),
+        options: Some(options),
     }
 }
 
@@ -316,6 +391,11 @@ fn parse_mcp_tool_approval_response(
         return McpToolApprovalDecision::Cancel;
     };
     if answers
+        .iter()
+        .any(|answer| answer == MCP_TOOL_APPROVAL_ALWAYS_ALLOW)
+    {
+        McpToolApprovalDecision::AlwaysAllow
+    } else if answers
         .iter()
         .any(|answer| answer == MCP_TOOL_APPROVAL_ACCEPT)
     {
@@ -362,6 +442,8 @@ async fn notify_mcp_tool_call_skip(
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use trill_protocol::request_user_input::RequestUserInputAnswer;
 
     fn annotations(
         read_only: Option<bool>,
@@ -394,4 +476,46 @@ mod tests {
         let annotations = annotations(Some(true), Some(true), Some(true));
         assert_eq!(requires_mcp_tool_approval(&annotations), false);
     }
+
+    #[test]
+    fn always_allow_answer_is_parsed() {
+        let question_id = "mcp_tool_call_approval_call-1";
+        let response = RequestUserInputResponse {
+            answers: HashMap::from([(
+                question_id.to_string(),
+                RequestUserInputAnswer {
+                    answers: vec![MCP_TOOL_APPROVAL_ALWAYS_ALLOW.to_string()],
+                },
+            )]),
+        };
+        assert_eq!(
+            parse_mcp_tool_approval_response(Some(response), question_id),
+            McpToolApprovalDecision::AlwaysAllow
+        );
+    }
+
+    #[test]
+    fn always_allow_is_only_offered_when_rules_are_enabled() {
+        let annotations = annotations(Some(false), Some(true), None);
+        let labels = |offer_always_allow| {
+            build_mcp_tool_approval_question(
+                "id".to_string(),
+                "create_issue",
+                None,
+                None,
+                &annotations,
+                offer_always_allow,
+            )
+            .options
+            .unwrap_or_default()
+            .into_iter()
+            .map(|option| option.label)
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            labels(true),
+            vec!["Accept", "Always allow", "Decline", "Cancel"]
+        );
+        assert_eq!(labels(false), vec!["Accept", "Decline", "Cancel"]);
+    }
 }
@@ -6,8 +6,13 @@
 //!      key. These override or extend the defaults at runtime.
 
 use crate::auth::AuthMode;
+use crate::config::types::SamplingParams;
 use crate::error::EnvVarError;
 use trill_api::Provider as ApiProvider;
+use trill_api::ChatCapabilities as ApiChatCapabilities;
+use trill_api::ReasoningTags as ApiReasoningTags;
+use trill_api::StructuredOutput as ApiStructuredOutput;
+use trill_api::ToolCallFormat as ApiToolCallFormat;
 use trill_api::WireApi as ApiWireApi;
 use trill_api::is_azure_responses_wire_base_url;
 use trill_api::provider::RetryConfig as ApiRetryConfig;
@@ -106,6 +111,119 @@ pub struct ModelProviderInfo {
     /// Whether this provider supports the Responses API WebSocket transport.
     #[serde(default)]
     pub supports_websockets: bool,
+
+    /// Tags around reasoning that models write inline in Chat Completions
+    /// message content, such as `<think>`...`</think>` from DeepSeek-R1
+    /// distills or Qwen3. Text between them is shown and stored as reasoning
+    /// instead of assistant output. Only used with `wire_api = "chat"`.
+    #[serde(default)]
+    pub reasoning_tags: Option<ReasoningTags>,
+
+    /// Sampling parameters (`temperature`, `top_p`, `max_tokens`, ...) and
+    /// provider-specific `extra_body` fields sent with every request.
+    #[serde(default)]
+    pub sampling: Option<SamplingParams>,
+
+    /// How `--output-schema` is sent on `wire_api = "chat"`. Defaults to
+    /// `json_schema` (OpenAI `response_format`).
+    #[serde(default)]
+    pub structured_output: Option<StructuredOutput>,
+
+    /// Optional Chat Completions features the server understands. Defaults to
+    /// none of them, with reasoning read from the `reasoning` delta field.
+    #[serde(default)]
+    pub chat_capabilities: Option<ChatCapabilities>,
+
+    /// How tools are called on `wire_api = "chat"`. Models without a
+    /// tool-call chat template can be given a text protocol instead of the
+    /// `tools` field. Defaults to `native`.
+    #[serde(default)]
+    pub tool_call_format: Option<ToolCallFormat>,
+}
+
+/// How tool calls are exchanged with a Chat Completions provider.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallFormat {
+    /// The server's `tools` field and `tool_calls` deltas.
+    #[default]
+    Native,
+    /// `<tool_call>{"name": ..., "arguments": ...}</tool_call>` in the
+    /// message text (Hermes, Qwen).
+    Hermes,
+    /// `[TOOL_CALLS] [{"name": ..., "arguments": ...}]` (Mistral).
+    Mistral,
+    /// A fenced `tool_call` code block holding the call as JSON.
+    FencedJson,
+}
+
+/// Request fields and response quirks of a Chat Completions server.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ChatCapabilities {
+    /// Send `tool_choice: "auto"` alongside tools.
+    #[serde(default)]
+    pub tool_choice: bool,
+    /// Send `parallel_tool_calls` alongside tools.
+    #[serde(default)]
+    pub parallel_tool_calls: bool,
+    /// Delta field carrying reasoning text, e.g. `reasoning_content` on
+    /// llama.cpp and vLLM. Defaults to `reasoning`.
+    #[serde(default)]
+    pub reasoning_field: Option<String>,
+}
+
+/// Constrained decoding supported by a Chat Completions provider.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StructuredOutput {
+    /// `response_format: {type: "json_schema", ...}`, as supported by OpenAI,
+    /// LM Studio, vLLM and llama.cpp.
+    #[default]
+    JsonSchema,
+    /// Ollama's `format` field.
+    OllamaFormat,
+    /// A llama.cpp GBNF `grammar` generated from the schema.
+    Grammar,
+    /// No constrained decoding. The final message is validated against the
+    /// schema instead, and the model is asked to fix it if it does not match.
+    None,
+}
+
+/// Delimiters for inline reasoning, `<think>` and `</think>` by default.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ReasoningTags {
+    #[serde(default = "default_reasoning_open_tag")]
+    pub open: String,
+    #[serde(default = "default_reasoning_close_tag")]
+    pub close: String,
+}
+
+impl Default for ReasoningTags {
+    fn default() -> Self {
+        Self {
+            open: default_reasoning_open_tag(),
+            close: default_reasoning_close_tag(),
+        }
+    }
+}
+
+impl From<&ReasoningTags> for ApiReasoningTags {
+    fn from(tags: &ReasoningTags) -> Self {
+        Self {
+            open: tags.open.clone(),
+            close: tags.close.clone(),
+        }
+    }
+}
+
+fn default_reasoning_open_tag() -> String {
+    "<think>".to_string()
+}
+
+fn default_reasoning_close_tag() -> String {
+    "</think>".to_string()
 }
 
 impl ModelProviderInfo {
@@ -171,6 +289,44 @@ impl ModelProviderInfo {
         })
     }
 
+    pub(crate) fn api_structured_output(&self) -> ApiStructuredOutput {
+        match self.structured_output.unwrap_or_default() {
+            StructuredOutput::JsonSchema => ApiStructuredOutput::JsonSchema,
+            StructuredOutput::OllamaFormat => ApiStructuredOutput::OllamaFormat,
+            StructuredOutput::Grammar => ApiStructuredOutput::Grammar,
+            StructuredOutput::None => ApiStructuredOutput::None,
+        }
+    }
+
+    pub(crate) fn api_chat_capabilities(&self) -> ApiChatCapabilities {
+        let capabilities = self.chat_capabilities.clone().unwrap_or_default();
+        ApiChatCapabilities {
+            tool_choice: capabilities.tool_choice,
+            parallel_tool_calls: capabilities.parallel_tool_calls,
+        }
+    }
+
+    /// Delta field to read Chat Completions reasoning from, if not the default.
+    pub(crate) fn api_reasoning_field(&self) -> Option<String> {
+        self.chat_capabilities
+            .as_ref()
+            .and_then(|capabilities| capabilities.reasoning_field.clone())
+    }
+
+    pub(crate) fn api_tool_call_format(&self) -> ApiToolCallFormat {
+        match self.tool_call_format.unwrap_or_default() {
+            ToolCallFormat::Native => ApiToolCallFormat::Native,
+            ToolCallFormat::Hermes => ApiToolCallFormat::Hermes,
+            ToolCallFormat::Mistral => ApiToolCallFormat::Mistral,
+            ToolCallFormat::FencedJson => ApiToolCallFormat::FencedJson,
+        }
+    }
+
+    /// Reasoning tags to split out of Chat Completions content, if any.
+    pub(crate) fn api_reasoning_tags(&self) -> Option<ApiReasoningTags> {
+        self.reasoning_tags.as_ref().map(ApiReasoningTags::from)
+    }
+
     pub(crate) fn is_azure_responses_endpoint(&self) -> bool {
         let wire = match self.wire_api {
             WireApi::Responses => ApiWireApi::Responses,
@@ -264,6 +420,11 @@ impl ModelProviderInfo {
             stream_idle_timeout_ms: None,
             requires_openai_auth: true,
             supports_websockets: true,
+            reasoning_tags: None,
+            sampling: None,
+            structured_output: None,
+            chat_capabilities: None,
+            tool_call_format: None,
         }
     }
 
@@ -274,10 +435,27 @@ impl ModelProviderInfo {
 
 pub const DEFAULT_LMSTUDIO_PORT: u16 = 1234;
 pub const DEFAULT_OLLAMA_PORT: u16 = 11434;
+pub const DEFAULT_LLAMACPP_PORT: u16 = 8080;
+pub const DEFAULT_VLLM_PORT: u16 = 8000;
+pub const DEFAULT_TABBYAPI_PORT: u16 = 5000;
+pub const DEFAULT_KOBOLDCPP_PORT: u16 = 5001;
 
 pub const LMSTUDIO_OSS_PROVIDER_ID: &str = "lmstudio";
 pub const OLLAMA_OSS_PROVIDER_ID: &str = "ollama";
 pub const OLLAMA_CHAT_PROVIDER_ID: &str = "ollama-chat";
+pub const LLAMACPP_OSS_PROVIDER_ID: &str = "llamacpp";
+pub const VLLM_OSS_PROVIDER_ID: &str = "vllm";
+pub const TABBYAPI_OSS_PROVIDER_ID: &str = "tabbyapi";
+pub const KOBOLDCPP_OSS_PROVIDER_ID: &str = "koboldcpp";
+
+/// Built-in providers for generic OpenAI-compatible local servers, which are
+/// probed and listed through `/v1/models` rather than a native API.
+pub const OPENAI_COMPATIBLE_OSS_PROVIDER_IDS: [&str; 4] = [
+    LLAMACPP_OSS_PROVIDER_ID,
+    VLLM_OSS_PROVIDER_ID,
+    TABBYAPI_OSS_PROVIDER_ID,
+    KOBOLDCPP_OSS_PROVIDER_ID,
+];
 
 /// Built-in default provider list.
 pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
@@ -295,12 +473,60 @@ pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
         ),
         (
             OLLAMA_CHAT_PROVIDER_ID,
-            create_oss_provider(DEFAULT_OLLAMA_PORT, WireApi::Chat),
+            P {
+                structured_output: Some(StructuredOutput::OllamaFormat),
+                ..create_oss_provider(DEFAULT_OLLAMA_PORT, WireApi::Chat)
+            },
         ),
         (
             LMSTUDIO_OSS_PROVIDER_ID,
             create_oss_provider(DEFAULT_LMSTUDIO_PORT, WireApi::Responses),
         ),
+        (
+            LLAMACPP_OSS_PROVIDER_ID,
+            create_local_server_provider(
+                "llama.cpp",
+                DEFAULT_LLAMACPP_PORT,
+                ChatCapabilities {
+                    tool_choice: true,
+                    parallel_tool_calls: true,
+                    reasoning_field: Some("reasoning_content".to_string()),
+                },
+            ),
+        ),
+        (
+            VLLM_OSS_PROVIDER_ID,
+            // `tool_choice: "auto"` is rejected unless vLLM was started with
+            // `--enable-auto-tool-choice`, and `parallel_tool_calls` is ignored.
+            create_local_server_provider(
+                "vLLM",
+                DEFAULT_VLLM_PORT,
+                ChatCapabilities {
+                    reasoning_field: Some("reasoning_content".to_string()),
+                    ..Default::default()
+                },
+            ),
+        ),
+        (
+            TABBYAPI_OSS_PROVIDER_ID,
+            create_local_server_provider(
+                "TabbyAPI",
+                DEFAULT_TABBYAPI_PORT,
+                ChatCapabilities {
+                    reasoning_field: Some("reasoning_content".to_string()),
+                    ..Default::default()
+                },
+            ),
+        ),
+        (
+            KOBOLDCPP_OSS_PROVIDER_ID,
+            // KoboldCpp has no reasoning field; `<think>` tags cover it.
+            create_local_server_provider(
+                "KoboldCpp",
+                DEFAULT_KOBOLDCPP_PORT,
+                ChatCapabilities::default(),
+            ),
+        ),
     ]
     .into_iter()
     .map(|(k, v)| (k.to_string(), v))
@@ -327,6 +553,21 @@ pub fn create_oss_provider(default_provider_port: u16, wire_api: WireApi) -> Mod
     create_oss_provider_with_base_url(&codex_oss_base_url, wire_api)
 }
 
+/// Chat Completions provider for an OpenAI-compatible local server. The wire
+/// API is switched to Responses at startup when the server turns out to
+/// support it.
+fn create_local_server_provider(
+    name: &str,
+    default_provider_port: u16,
+    capabilities: ChatCapabilities,
+) -> ModelProviderInfo {
+    ModelProviderInfo {
+        name: name.into(),
+        chat_capabilities: Some(capabilities),
+        ..create_oss_provider(default_provider_port, WireApi::Chat)
+    }
+}
+
 pub fn create_oss_provider_with_base_url(base_url: &str, wire_api: WireApi) -> ModelProviderInfo {
     ModelProviderInfo {
         name: "gpt-oss".into(),
@@ -343,6 +584,11 @@ pub fn create_oss_provider_with_base_url(base_url: &str, wire_api: WireApi) -> M
         stream_idle_timeout_ms: None,
         requires_openai_auth: false,
         supports_websockets: false,
+        reasoning_tags: Some(ReasoningTags::default()),
+        sampling: None,
+        structured_output: None,
+        chat_capabilities: None,
+        tool_call_format: None,
     }
 }
 
@@ -372,6 +618,11 @@ base_url = "http://localhost:11434/v1"
             stream_idle_timeout_ms: None,
             requires_openai_auth: false,
             supports_websockets: false,
+            reasoning_tags: None,
+            sampling: None,
+            structured_output: None,
+            chat_capabilities: None,
+            tool_call_format: None,
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -403,6 +654,11 @@ query_params = { api-version = "2025-04-01-preview" }
             stream_idle_timeout_ms: None,
             requires_openai_auth: false,
             supports_websockets: false,
+            reasoning_tags: None,
+            sampling: None,
+            structured_output: None,
+            chat_capabilities: None,
+            tool_call_format: None,
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -437,9 +693,38 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             stream_idle_timeout_ms: None,
             requires_openai_auth: false,
             supports_websockets: false,
+            reasoning_tags: None,
+            sampling: None,
+            structured_output: None,
+            chat_capabilities: None,
+            tool_call_format: None,
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
         assert_eq!(expected_provider, provider);
     }
+
+    #[test]
+    fn test_deserialize_reasoning_tags() {
+        let provider_toml = r#"
+name = "LM Studio"
+base_url = "http://localhost:1234/v1"
+reasoning_tags = {}
+        "#;
+        let provider: ModelProviderInfo = toml::from_str(provider_toml).unwrap();
+        assert_eq!(provider.reasoning_tags, Some(ReasoningTags::default()));
+
+        let provider_toml = r#"
+name = "Sky-T1"
+reasoning_tags = { open = "<|begin_of_thought|>", close = "<|end_of_thought|>" }
+        "#;
+        let provider: ModelProviderInfo = toml::from_str(provider_toml).unwrap();
+        assert_eq!(
+            provider.api_reasoning_tags(),
+            Some(ApiReasoningTags {
+                open: "<|begin_of_thought|>".to_string(),
+                close: "<|end_of_thought|>".to_string(),
+            })
+        );
+    }
 }
@@ -0,0 +1,514 @@
+//! Discovers context windows and capabilities of models on local servers.
+//!
+//! OpenAI-compatible `/v1/models` listings only carry model ids, so each
+//! server's native API is queried for the rest: LM Studio `/api/v1/models`
+//! (`/api/v0/models` on older builds), Ollama `/api/show` and llama.cpp
+//! `/props`. Failures are logged and leave
+//! the metadata empty; the caller falls back to config and defaults.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures::future::join_all;
+use serde_json::Value;
+use serde_json::json;
+use tokio::time::timeout;
+use tracing::debug;
+
+use crate::model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
+use crate::model_provider_info::OLLAMA_CHAT_PROVIDER_ID;
+use crate::model_provider_info::OLLAMA_OSS_PROVIDER_ID;
+
+const METADATA_TIMEOUT: Duration = Duration::from_secs(5);
+/// Context Ollama runs a model with when neither the model's `num_ctx` nor
+/// the request sets one. A server started with `OLLAMA_CONTEXT_LENGTH` may use
+/// more, which only makes this estimate conservative.
+const OLLAMA_DEFAULT_NUM_CTX: i64 = 4096;
+
+/// Native API flavor of a local model server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LocalServerKind {
+    LmStudio,
+    Ollama,
+    /// llama.cpp `llama-server`; also the probe used for other local servers,
+    /// which simply yields no metadata when `/props` is missing.
+    LlamaCpp,
+}
+
+impl LocalServerKind {
+    pub(crate) fn for_provider(provider_id: &str) -> Self {
+        match provider_id {
+            LMSTUDIO_OSS_PROVIDER_ID => Self::LmStudio,
+            OLLAMA_OSS_PROVIDER_ID | OLLAMA_CHAT_PROVIDER_ID => Self::Ollama,
+            _ => Self::LlamaCpp,
+        }
+    }
+}
+
+/// What a local server reports about one model. `None` means unknown.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct LocalModelMetadata {
+    /// Context length the model runs with: the loaded length in LM Studio,
+    /// `num_ctx` (or Ollama's default) in Ollama, `n_ctx` in llama.cpp.
+    pub(crate) context_length: Option<i64>,
+    /// Largest context the model was trained for.
+    pub(crate) max_context_length: Option<i64>,
+    pub(crate) supports_tools: Option<bool>,
+    pub(crate) supports_parallel_tool_calls: Option<bool>,
+    /// False for embedding models, which cannot serve chat turns.
+    pub(crate) is_chat_model: bool,
+}
+
+impl LocalModelMetadata {
+    /// Context window to budget for: the running length when known, otherwise
+    /// the trained maximum.
+    pub(crate) fn context_window(&self) -> Option<i64> {
+        self.context_length.or(self.max_context_length)
+    }
+}
+
+/// Fetches metadata for `model_ids`, keyed by model id. Models the server
+/// did not describe are missing from the map.
+pub(crate) async fn fetch_local_model_metadata(
+    client: &reqwest::Client,
+    kind: LocalServerKind,
+    base_url: &str,
+    model_ids: &[String],
+) -> HashMap<String, LocalModelMetadata> {
+    let root = server_root(base_url);
+    match kind {
+        LocalServerKind::LmStudio => {
+            let body = match get_json(client, &format!("{root}/api/v1/models")).await {
+                Some(body) => Some(body),
+                None => get_json(client, &format!("{root}/api/v0/models")).await,
+            };
+            body.map(|body| parse_lmstudio_models(&body))
+                .unwrap_or_default()
+        }
+        LocalServerKind::Ollama => {
+            let show_url = format!("{root}/api/show");
+            let url = show_url.as_str();
+            let requests = model_ids.iter().map(|id| {
+                let request = client.post(url).json(&json!({ "model": id }));
+                async move { (id.clone(), send_json(request, url).await) }
+            });
+            join_all(requests)
+                .await
+                .into_iter()
+                .filter_map(|(id, body)| Some((id, parse_ollama_show(&body?))))
+                .collect()
+        }
+        LocalServerKind::LlamaCpp => {
+            // llama-server hosts a single model, so its props apply to every id.
+            let Some(metadata) = get_json(client, &format!("{root}/props"))
+                .await
+                .map(|body| parse_llamacpp_props(&body))
+            else {
+                return HashMap::new();
+            };
+            model_ids
+                .iter()
+                .map(|id| (id.clone(), metadata.clone()))
+                .collect()
+        }
+    }
+}
+
+async fn get_json(client: &reqwest::Client, url: &str) -> Option<Value> {
+    send_json(client.get(url), url).await
+}
+
+async fn send_json(request: reqwest::RequestBuilder, url: &str) -> Option<Value> {
+    let response = match timeout(METADATA_TIMEOUT, request.send()).await {
+        Ok(Ok(response)) if response.status().is_success() => response,
+        Ok(Ok(response)) => {
+            debug!(
+                "model metadata request to {url} failed: HTTP {}",
+                response.status()
+            );
+            return None;
+        }
+        Ok(Err(err)) => {
+            debug!("model metadata request to {url} failed: {err}");
+            return None;
+        }
+        Err(_) => {
+            debug!("model metadata request to {url} timed out");
+            return None;
+        }
+    };
+    response
+        .json()
+        .await
+        .inspect_err(|err| debug!("invalid model metadata from {url}: {err}"))
+        .ok()
+}
+
+/// Parses LM Studio's `/api/v1/models` listing, or the `/api/v0/models` one
+/// of older builds.
+fn parse_lmstudio_models(body: &Value) -> HashMap<String, LocalModelMetadata> {
+    if let Some(models) = body["models"].as_array() {
+        return parse_lmstudio_v1_models(models);
+    }
+    let Some(models) = body["data"].as_array() else {
+        return HashMap::new();
+    };
+    models
+        .iter()
+        .filter_map(|model| {
+            let id = model["id"].as_str()?;
+            let capabilities = string_list(&model["capabilities"]);
+            let metadata = LocalModelMetadata {
+                context_length: model["loaded_context_length"].as_i64(),
+                max_context_length: model["max_context_length"].as_i64(),
+                // Older LM Studio builds do not report capabilities at all.
+                supports_tools: capabilities
+                    .as_ref()
+                    .map(|caps| caps.iter().any(|cap| cap == "tool_use")),
+                supports_parallel_tool_calls: None,
+                is_chat_model: matches!(model["type"].as_str(), None | Some("llm" | "vlm")),
+            };
+            Some((id.to_string(), metadata))
+        })
+        .collect()
+}
+
+/// Parses the `/api/v1/models` entries. A model is served under its key and
+/// under the id of each loaded instance, which may run with its own context
+/// length.
+fn parse_lmstudio_v1_models(models: &[Value]) -> HashMap<String, LocalModelMetadata> {
+    let mut metadata = HashMap::new();
+    for model in models {
+        let Some(key) = model["key"].as_str() else {
+            continue;
+        };
+        let instances = model["loaded_instances"]
+            .as_array()
+            .map_or(&[][..], Vec::as_slice);
+        let base = LocalModelMetadata {
+            context_length: None,
+            max_context_length: model["max_context_length"].as_i64(),
+            supports_tools: model["capabilities"]["trained_for_tool_use"].as_bool(),
+            supports_parallel_tool_calls: None,
+            is_chat_model: matches!(model["type"].as_str(), None | Some("llm")),
+        };
+        for instance in instances {
+            if let Some(id) = instance["id"].as_str() {
+                let instance_metadata = LocalModelMetadata {
+                    context_length: instance["config"]["context_length"].as_i64(),
+                    ..base.clone()
+                };
+                metadata.insert(id.to_string(), instance_metadata);
+            }
+        }
+        let key_metadata = LocalModelMetadata {
+            context_length: instances
+                .first()
+                .and_then(|instance| instance["config"]["context_length"].as_i64()),
+            ..base
+        };
+        metadata.entry(key.to_string()).or_insert(key_metadata);
+    }
+    metadata
+}
+
+/// Parses Ollama's `/api/show` response for one model.
+fn parse_ollama_show(body: &Value) -> LocalModelMetadata {
+    let num_ctx = body["parameters"].as_str().and_then(|parameters| {
+        parameters.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some("num_ctx"), Some(value)) => value.parse().ok(),
+                _ => None,
+            }
+        })
+    });
+    // The trained length is keyed by architecture, e.g. `qwen2.context_length`.
+    let max_context_length = body["model_info"].as_object().and_then(|info| {
+        info.iter()
+            .find(|(key, _)| key.ends_with(".context_length"))
+            .and_then(|(_, value)| value.as_i64())
+    });
+    let capabilities = string_list(&body["capabilities"]);
+    // Servers predating `capabilities` only expose the chat template.
+    let supports_tools = match &capabilities {
+        Some(caps) => Some(caps.iter().any(|cap| cap == "tools")),
+        None => body["template"]
+            .as_str()
+            .map(|template| template.contains(".Tools")),
+    };
+    let is_chat_model = capabilities
+        .as_ref()
+        .is_none_or(|caps| caps.iter().any(|cap| cap == "completion"));
+    // Without `num_ctx` Ollama does not run the trained length but its own
+    // default.
+    let context_length = num_ctx.or_else(|| {
+        Some(max_context_length.map_or(OLLAMA_DEFAULT_NUM_CTX, |max| {
+            max.min(OLLAMA_DEFAULT_NUM_CTX)
+        }))
+    });
+    LocalModelMetadata {
+        context_length,
+        max_context_length,
+        supports_tools,
+        supports_parallel_tool_calls: None,
+        is_chat_model,
+    }
+}
+
+/// Parses llama.cpp's `/props` response.
+fn parse_llamacpp_props(body: &Value) -> LocalModelMetadata {
+    let caps = &body["chat_template_caps"];
+    LocalModelMetadata {
+        context_length: body["default_generation_settings"]["n_ctx"]
+            .as_i64()
+            .or_else(|| body["n_ctx"].as_i64()),
+        max_context_length: None,
+        supports_tools: caps["supports_tools"].as_bool(),
+        supports_parallel_tool_calls: caps["supports_parallel_tool_calls"].as_bool(),
+        is_chat_model: true,
+    }
+}
+
+fn string_list(value: &Value) -> Option<Vec<String>> {
+    value.as_array().map(|items| {
+        items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect()
+    })
+}
+
+/// Strips the OpenAI-compatible `/v1` suffix to reach the native API root.
+fn server_root(base_url: &str) -> String {
+    let trimmed = base_url.trim_end_matches('/');
+    trimmed
+        .strip_suffix("/v1")
+        .unwrap_or(trimmed)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::body_json;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+
+    #[test]
+    fn parses_lmstudio_models() {
+        let body = json!({
+            "object": "list",
+            "data": [
+                {
+                    "id": "qwen2.5-coder-14b-instruct",
+                    "type": "llm",
+                    "state": "loaded",
+                    "max_context_length": 32768,
+                    "loaded_context_length": 16384,
+                    "capabilities": ["tool_use"]
+                },
+                {
+                    "id": "text-embedding-nomic-embed-text-v1.5",
+                    "type": "embeddings",
+                    "state": "not-loaded",
+                    "max_context_length": 2048
+                },
+                {
+                    "id": "gemma-3-4b",
+                    "type": "vlm",
+                    "state": "not-loaded",
+                    "max_context_length": 131072,
+                    "capabilities": []
+                }
+            ]
+        });
+
+        let models = parse_lmstudio_models(&body);
+
+        assert_eq!(
+            models["qwen2.5-coder-14b-instruct"],
+            LocalModelMetadata {
+                context_length: Some(16384),
+                max_context_length: Some(32768),
+                supports_tools: Some(true),
+                supports_parallel_tool_calls: None,
+                is_chat_model: true,
+            }
+        );
+        assert!(!models["text-embedding-nomic-embed-text-v1.5"].is_chat_model);
+        assert_eq!(models["gemma-3-4b"].context_window(), Some(131072));
+        assert_eq!(models["gemma-3-4b"].supports_tools, Some(false));
+    }
+
+    #[test]
+    fn parses_lmstudio_v1_models() {
+        let body = json!({
+            "models": [
+                {
+                    "type": "llm",
+                    "key": "qwen/qwen3-8b",
+                    "max_context_length": 32768,
+                    "capabilities": { "vision": false, "trained_for_tool_use": true },
+                    "loaded_instances": [
+                        { "id": "qwen/qwen3-8b", "config": { "context_length": 8192 } },
+                        { "id": "qwen/qwen3-8b:2", "config": { "context_length": 16384 } }
+                    ]
+                },
+                {
+                    "type": "embedding",
+                    "key": "text-embedding-nomic-embed-text-v1.5",
+                    "loaded_instances": []
+                }
+            ]
+        });
+
+        let models = parse_lmstudio_models(&body);
+
+        assert_eq!(
+            models["qwen/qwen3-8b"],
+            LocalModelMetadata {
+                context_length: Some(8192),
+                max_context_length: Some(32768),
+                supports_tools: Some(true),
+                supports_parallel_tool_calls: None,
+                is_chat_model: true,
+            }
+        );
+        assert_eq!(models["qwen/qwen3-8b:2"].context_window(), Some(16384));
+        assert!(!models["text-embedding-nomic-embed-text-v1.5"].is_chat_model);
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_lmstudio_v0_models() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{ "id": "gemma-3-4b", "type": "vlm", "max_context_length": 131072 }]
+            })))
+            .mount(&server)
+            .await;
+
+        let metadata = fetch_local_model_metadata(
+            &reqwest::Client::new(),
+            LocalServerKind::LmStudio,
+            &format!("{}/v1", server.uri()),
+            &["gemma-3-4b".to_string()],
+        )
+        .await;
+
+        assert_eq!(metadata["gemma-3-4b"].context_window(), Some(131072));
+    }
+
+    #[test]
+    fn parses_ollama_show() {
+        let body = json!({
+            "parameters": "stop \"<|im_end|>\"\nnum_ctx 16384\ntemperature 0.7",
+            "template": "{{- if .Tools }}...{{ end }}",
+            "model_info": {
+                "general.architecture": "qwen2",
+                "qwen2.context_length": 32768
+            },
+            "capabilities": ["completion", "tools"]
+        });
+        assert_eq!(
+            parse_ollama_show(&body),
+            LocalModelMetadata {
+                context_length: Some(16384),
+                max_context_length: Some(32768),
+                supports_tools: Some(true),
+                supports_parallel_tool_calls: None,
+                is_chat_model: true,
+            }
+        );
+
+        // Without `num_ctx` the model runs with Ollama's default context.
+        let legacy = json!({
+            "template": "{{ .Prompt }}",
+            "model_info": { "llama.context_length": 8192 }
+        });
+        let metadata = parse_ollama_show(&legacy);
+        assert_eq!(metadata.context_window(), Some(OLLAMA_DEFAULT_NUM_CTX));
+        assert_eq!(metadata.max_context_length, Some(8192));
+        assert_eq!(metadata.supports_tools, Some(false));
+
+        let small = json!({ "model_info": { "llama.context_length": 2048 } });
+        assert_eq!(parse_ollama_show(&small).context_window(), Some(2048));
+
+        let embedding = json!({ "capabilities": ["embedding"] });
+        assert!(!parse_ollama_show(&embedding).is_chat_model);
+    }
+
+    #[test]
+    fn parses_llamacpp_props() {
+        let body = json!({
+            "default_generation_settings": { "n_ctx": 8192, "temperature": 0.8 },
+            "total_slots": 1,
+            "model_path": "/models/Qwen3-8B-Q4_K_M.gguf",
+            "chat_template_caps": {
+                "supports_tools": true,
+                "supports_parallel_tool_calls": true
+            }
+        });
+        assert_eq!(
+            parse_llamacpp_props(&body),
+            LocalModelMetadata {
+                context_length: Some(8192),
+                max_context_length: None,
+                supports_tools: Some(true),
+                supports_parallel_tool_calls: Some(true),
+                is_chat_model: true,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn queries_ollama_show_for_each_model() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/show"))
+            .and(body_json(json!({ "model": "qwen3:8b" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "parameters": "num_ctx 12288",
+                "capabilities": ["completion", "tools", "thinking"]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/show"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let metadata = fetch_local_model_metadata(
+            &reqwest::Client::new(),
+            LocalServerKind::Ollama,
+            &format!("{}/v1", server.uri()),
+            &["qwen3:8b".to_string(), "missing:latest".to_string()],
+        )
+        .await;
+
+        assert_eq!(metadata.len(), 1);
+        assert_eq!(metadata["qwen3:8b"].context_window(), Some(12288));
+        assert_eq!(metadata["qwen3:8b"].supports_tools, Some(true));
+    }
+
+    #[test]
+    fn server_root_strips_openai_suffix() {
+        assert_eq!(
+            server_root("http://localhost:1234/v1/"),
+            "http://localhost:1234"
+        );
+        assert_eq!(
+            server_root("http://localhost:8080"),
+            "http://localhost:8080"
+        );
+    }
+}
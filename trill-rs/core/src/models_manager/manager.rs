@@ -12,7 +12,11 @@ use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
 use crate::model_provider_info::OLLAMA_CHAT_PROVIDER_ID;
 use crate::model_provider_info::OLLAMA_OSS_PROVIDER_ID;
+use crate::model_provider_info::OPENAI_COMPATIBLE_OSS_PROVIDER_IDS;
 use crate::models_manager::collaboration_mode_presets::builtin_collaboration_mode_presets;
+use crate::models_manager::local_metadata::LocalModelMetadata;
+use crate::models_manager::local_metadata::LocalServerKind;
+use crate::models_manager::local_metadata::fetch_local_model_metadata;
 use crate::models_manager::model_info;
 use crate::models_manager::model_presets::builtin_model_presets;
 use trill_api::ModelsClient;
@@ -76,7 +80,7 @@ impl ModelsManager {
         auth_manager: Arc<AuthManager>,
         model_provider_id: String,
     ) -> Self {
-        let cache_path = trill_home.join(MODEL_CACHE_FILE);
+        let cache_path = trill_home.join(Self::cache_file_name(&model_provider_id));
         let cache_manager = ModelsCacheManager::new(cache_path, DEFAULT_MODEL_CACHE_TTL);
         Self {
             local_models: builtin_model_presets(auth_manager.get_internal_auth_mode()),
@@ -89,12 +93,19 @@ impl ModelsManager {
         }
     }
 
+    /// OSS providers keep their discovered models in a cache of their own so
+    /// they never mix with OpenAI's model list.
+    fn cache_file_name(model_provider_id: &str) -> String {
+        if is_oss_provider_id(model_provider_id) {
+            format!("models_cache_{model_provider_id}.json")
+        } else {
+            MODEL_CACHE_FILE.to_string()
+        }
+    }
+
     /// Check if the current provider is an OSS provider (LM Studio, Ollama, etc.).
     fn is_oss_provider(&self) -> bool {
-        matches!(
-            self.model_provider_id.as_str(),
-            LMSTUDIO_OSS_PROVIDER_ID | OLLAMA_OSS_PROVIDER_ID | OLLAMA_CHAT_PROVIDER_ID
-        )
+        is_oss_provider_id(&self.model_provider_id)
     }
 
     /// List all available models, refreshing according to the specified strategy.
@@ -170,6 +181,10 @@ impl ModelsManager {
             .find(|m| m.slug == model);
         let model = if let Some(remote) = remote {
             remote
+        } else if self.is_oss_provider() {
+            // A local model the server did not list (e.g. not downloaded yet)
+            // must not inherit the defaults of hosted models.
+            self.create_oss_model_info(model, config, None)
         } else {
             model_info::find_model_info_for_slug(model)
         };
@@ -240,26 +255,40 @@ impl ModelsManager {
     ) -> CoreResult<()> {
         match refresh_strategy {
             RefreshStrategy::Offline => {
-                // For offline, just use what we have cached in memory
-                Ok(())
+                self.try_load_oss_cache().await;
+                return Ok(());
             }
-            RefreshStrategy::OnlineIfUncached | RefreshStrategy::Online => {
-                // Fetch models from the OSS provider
-                match self.fetch_oss_models(config).await {
-                    Ok(models) => {
-                        *self.remote_models.write().await = models;
-                        Ok(())
-                    }
-                    Err(e) => {
-                        warn!("Failed to fetch models from OSS provider: {e}");
-                        // Don't treat this as fatal - we can still work with cached/default models
-                        Ok(())
-                    }
+            RefreshStrategy::OnlineIfUncached => {
+                if self.try_load_oss_cache().await {
+                    return Ok(());
                 }
             }
+            RefreshStrategy::Online => {}
+        }
+        // Fetch models from the OSS provider
+        match self.fetch_oss_models(config).await {
+            Ok(models) => {
+                self.cache_manager.persist_cache(&models, None).await;
+                *self.remote_models.write().await = models;
+                Ok(())
+            }
+            Err(e) => {
+                warn!("Failed to fetch models from OSS provider: {e}");
+                // Don't treat this as fatal - we can still work with cached/default models
+                Ok(())
+            }
         }
     }
 
+    /// Replace the model list with the OSS provider's cached models if fresh.
+    async fn try_load_oss_cache(&self) -> bool {
+        let Some(cache) = self.cache_manager.load_fresh().await else {
+            return false;
+        };
+        *self.remote_models.write().await = cache.models;
+        true
+    }
+
     /// Fetch models from an OSS provider (LM Studio or Ollama).
     async fn fetch_oss_models(&self, config: &Config) -> CoreResult<Vec<ModelInfo>> {
         let provider = config
@@ -310,23 +339,34 @@ impl ModelsManager {
             .map(String::from)
             .collect();
 
+        let server = LocalServerKind::for_provider(&self.model_provider_id);
+        let metadata = fetch_local_model_metadata(&client, server, base_url, &model_ids).await;
+
         let models: Vec<ModelInfo> = model_ids
             .into_iter()
-            .map(|id| self.create_oss_model_info(&id, config))
+            .filter(|id| metadata.get(id).is_none_or(|m| m.is_chat_model))
+            .map(|id| self.create_oss_model_info(&id, config, metadata.get(&id)))
             .collect();
 
         Ok(models)
     }
 
-    /// Create a ModelInfo for an OSS model.
-    fn create_oss_model_info(&self, model_id: &str, config: &Config) -> ModelInfo {
+    /// Create a ModelInfo for an OSS model, using what the server reported
+    /// about it where the config is silent.
+    fn create_oss_model_info(
+        &self,
+        model_id: &str,
+        config: &Config,
+        metadata: Option<&LocalModelMetadata>,
+    ) -> ModelInfo {
         // Check for per-model settings in config
         let model_settings = config.model_settings.get(model_id);
 
-        // Determine context window: per-model setting > global setting > default
+        // Determine context window: per-model setting > global setting > server > default
         let context_window = model_settings
             .and_then(|s| s.context_window)
             .or(config.model_context_window)
+            .or_else(|| metadata.and_then(LocalModelMetadata::context_window))
             .unwrap_or(DEFAULT_OSS_CONTEXT_WINDOW);
 
         // Determine auto-compact token limit: per-model setting > global setting > 90% of context
@@ -342,10 +382,20 @@ impl ModelsManager {
             .unwrap_or(model_id)
             .to_string();
 
+        let supports_tools = metadata.and_then(|m| m.supports_tools);
+        let description = if supports_tools == Some(false) {
+            format!(
+                "Local model via {} (no tool calling support reported)",
+                self.model_provider_id
+            )
+        } else {
+            format!("Local model via {}", self.model_provider_id)
+        };
+
         ModelInfo {
             slug: model_id.to_string(),
             display_name,
-            description: Some(format!("Local model via {}", self.model_provider_id)),
+            description: Some(description),
             default_reasoning_level: None,
             supported_reasoning_levels: Vec::new(),
             shell_type: ConfigShellToolType::Default,
@@ -360,7 +410,9 @@ impl ModelsManager {
             default_verbosity: None,
             apply_patch_tool_type: None,
             truncation_policy: TruncationPolicyConfig::bytes(10_000),
-            supports_parallel_tool_calls: false,
+            supports_parallel_tool_calls: metadata
+                .and_then(|m| m.supports_parallel_tool_calls)
+                .unwrap_or(false),
             context_window: Some(context_window),
             auto_compact_token_limit: Some(auto_compact_token_limit),
             effective_context_window_percent: 95,
@@ -525,6 +577,13 @@ impl ModelsManager {
     }
 }
 
+fn is_oss_provider_id(model_provider_id: &str) -> bool {
+    matches!(
+        model_provider_id,
+        LMSTUDIO_OSS_PROVIDER_ID | OLLAMA_OSS_PROVIDER_ID | OLLAMA_CHAT_PROVIDER_ID
+    ) || OPENAI_COMPATIBLE_OSS_PROVIDER_IDS.contains(&model_provider_id)
+}
+
 /// Convert a client version string to a whole version string (e.g. "1.2.3-alpha.4" -> "1.2.3")
 fn format_client_version_to_whole() -> String {
     format!(
@@ -549,7 +608,11 @@ mod tests {
     use pretty_assertions::assert_eq;
     use serde_json::json;
     use tempfile::tempdir;
+    use wiremock::Mock;
     use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
 
     fn remote_model(slug: &str, display: &str, priority: i32) -> ModelInfo {
         remote_model_with_visibility(slug, display, priority, "list")
@@ -612,6 +675,11 @@ mod tests {
             stream_idle_timeout_ms: Some(5_000),
             requires_openai_auth: false,
             supports_websockets: false,
+            reasoning_tags: None,
+            sampling: None,
+            structured_output: None,
+            chat_capabilities: None,
+            tool_call_format: None,
         }
     }
 
@@ -857,6 +925,98 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn refresh_oss_models_uses_server_metadata_and_cache() {
+        let server = MockServer::start().await;
+        let models_mock = Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    { "id": "qwen2.5-coder-14b-instruct" },
+                    { "id": "text-embedding-nomic-embed-text-v1.5" }
+                ]
+            })))
+            .expect(1)
+            .mount_as_scoped(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {
+                        "id": "qwen2.5-coder-14b-instruct",
+                        "type": "llm",
+                        "max_context_length": 32768,
+                        "loaded_context_length": 16384,
+                        "capabilities": ["tool_use"]
+                    },
+                    {
+                        "id": "text-embedding-nomic-embed-text-v1.5",
+                        "type": "embeddings",
+                        "max_context_length": 2048
+                    }
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let trill_home = tempdir().expect("temp dir");
+        let mut config = ConfigBuilder::default()
+            .trill_home(trill_home.path().to_path_buf())
+            .build()
+            .await
+            .expect("load default test config");
+        config
+            .model_providers
+            .get_mut(LMSTUDIO_OSS_PROVIDER_ID)
+            .expect("built-in lmstudio provider")
+            .base_url = Some(format!("{}/v1", server.uri()));
+        let auth_manager =
+            AuthManager::from_auth_for_testing(CodexAuth::from_api_key("Test API Key"));
+        let manager = ModelsManager::new(
+            trill_home.path().to_path_buf(),
+            Arc::clone(&auth_manager),
+            LMSTUDIO_OSS_PROVIDER_ID.to_string(),
+        );
+
+        let available = manager
+            .list_models(&config, RefreshStrategy::OnlineIfUncached)
+            .await;
+        assert_eq!(
+            available
+                .iter()
+                .map(|preset| preset.model.as_str())
+                .collect::<Vec<_>>(),
+            vec!["qwen2.5-coder-14b-instruct"]
+        );
+        let info = manager
+            .get_model_info("qwen2.5-coder-14b-instruct", &config)
+            .await;
+        assert_eq!(info.context_window, Some(16384));
+        assert_eq!(info.auto_compact_token_limit, Some(16384 * 90 / 100));
+
+        let unlisted = manager.get_model_info("not-downloaded", &config).await;
+        assert_eq!(unlisted.context_window, Some(DEFAULT_OSS_CONTEXT_WINDOW));
+
+        // A fresh manager is served from the provider's own cache file.
+        let cached = ModelsManager::new(
+            trill_home.path().to_path_buf(),
+            auth_manager,
+            LMSTUDIO_OSS_PROVIDER_ID.to_string(),
+        );
+        cached
+            .refresh_available_models(&config, RefreshStrategy::OnlineIfUncached)
+            .await
+            .expect("cached refresh succeeds");
+        let info = cached
+            .get_model_info("qwen2.5-coder-14b-instruct", &config)
+            .await;
+        assert_eq!(info.context_window, Some(16384));
+        let cache_file = trill_home.path().join("models_cache_lmstudio.json");
+        assert!(cache_file.exists());
+        drop(models_mock);
+    }
+
     #[test]
     fn build_available_models_picks_default_after_hiding_hidden_models() {
         let trill_home = tempdir().expect("temp dir");
@@ -0,0 +1,304 @@
+use std::collections::VecDeque;
+use std::path::Path;
+use std::path::PathBuf;
+
+use trill_utils_absolute_path::AbsolutePathBuf;
+use wildmatch::WildMatch;
+
+/// Upper bound on directory entries visited beneath each glob's literal
+/// prefix while expanding it before a sandboxed command starts, so a glob
+/// over a huge tree does not stall every command.
+const MAX_SCAN_ENTRIES: usize = 50_000;
+
+/// A scan for denied paths gave up before covering `root`. The command must
+/// not run, since a denied file beyond the limit would stay readable.
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "sandbox_deny_read: gave up scanning {} for denied paths after {limit} entries; \
+     replace the glob with the paths it should match",
+    root.display()
+)]
+pub struct DenyReadScanError {
+    root: PathBuf,
+    limit: usize,
+}
+
+/// A `sandbox_deny_read` entry that is a bare name rather than a path.
+/// Enforcing it would mean searching every readable directory before each
+/// command, so it is rejected instead.
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "sandbox_deny_read: `{pattern}` is a bare name, not a path; list the paths it should \
+     cover instead, such as `./{pattern}`"
+)]
+pub struct DenyReadPatternError {
+    pattern: String,
+}
+
+/// Paths the agent must not read, configured with `sandbox_deny_read`.
+///
+/// Each entry is a path: `~` is expanded and relative paths are resolved
+/// against the cwd. The path and everything beneath it are denied. `*` and
+/// `?` match any characters, including `/`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DenyRead {
+    patterns: Vec<String>,
+}
+
+impl DenyRead {
+    /// Fails on entries without `/` (for example `.env` or `*.pem`), which
+    /// name no location.
+    pub fn new(patterns: Vec<String>) -> Result<Self, DenyReadPatternError> {
+        let mut checked = Vec::with_capacity(patterns.len());
+        for pattern in patterns {
+            let pattern = pattern.trim();
+            if pattern.is_empty() {
+                continue;
+            }
+            if is_name_pattern(pattern) {
+                return Err(DenyReadPatternError {
+                    pattern: pattern.to_string(),
+                });
+            }
+            checked.push(pattern.to_string());
+        }
+        Ok(Self { patterns: checked })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Whether `path` (absolute, or relative to `cwd`) is denied. Both the
+    /// normalized path and, when it exists, its canonical form are checked so
+    /// symlinks cannot be used to reach a denied file.
+    pub fn is_denied(&self, path: &Path, cwd: &Path) -> bool {
+        if self.patterns.is_empty() {
+            return false;
+        }
+        let Ok(path) = AbsolutePathBuf::resolve_path_against_base(path, cwd) else {
+            return false;
+        };
+        self.matches(path.as_path(), cwd)
+            || std::fs::canonicalize(path.as_path())
+                .is_ok_and(|canonical| self.matches(&canonical, cwd))
+    }
+
+    /// Existing paths the sandbox must hide from a command running in `cwd`.
+    /// Globs are expanded by scanning their longest literal prefix, so files
+    /// created after the command starts are not hidden from it.
+    pub fn resolve_targets(&self, cwd: &Path) -> Result<Vec<PathBuf>, DenyReadScanError> {
+        self.resolve_targets_with_limit(cwd, MAX_SCAN_ENTRIES)
+    }
+
+    fn resolve_targets_with_limit(
+        &self,
+        cwd: &Path,
+        limit: usize,
+    ) -> Result<Vec<PathBuf>, DenyReadScanError> {
+        let mut targets = Vec::new();
+        let mut scan_roots = Vec::new();
+        for pattern in &self.patterns {
+            let Some(path) = resolve_pattern(pattern, cwd) else {
+                continue;
+            };
+            if has_glob(&path) {
+                scan_roots.push(literal_prefix(&path));
+            } else if path.exists() && !targets.contains(&path) {
+                targets.push(path);
+            }
+        }
+        // Parents sort before their children, so nested roots are dropped.
+        scan_roots.sort();
+        let mut outer_roots: Vec<PathBuf> = Vec::new();
+        for root in scan_roots {
+            if !outer_roots.iter().any(|outer| root.starts_with(outer)) {
+                outer_roots.push(root);
+            }
+        }
+
+        for root in outer_roots {
+            self.scan(&root, cwd, &mut targets, limit)?;
+        }
+        Ok(targets)
+    }
+
+    /// Breadth-first walk of `root` collecting denied entries. Denied
+    /// directories are not descended into; symlinks are not followed.
+    fn scan(
+        &self,
+        root: &Path,
+        cwd: &Path,
+        targets: &mut Vec<PathBuf>,
+        limit: usize,
+    ) -> Result<(), DenyReadScanError> {
+        let mut budget = limit;
+        let mut queue = VecDeque::from([root.to_path_buf()]);
+        while let Some(dir) = queue.pop_front() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                if budget == 0 {
+                    return Err(DenyReadScanError {
+                        root: root.to_path_buf(),
+                        limit,
+                    });
+                }
+                budget -= 1;
+                let path = entry.path();
+                if self.matches(&path, cwd) {
+                    if !targets.contains(&path) {
+                        targets.push(path);
+                    }
+                } else if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                    queue.push_back(path);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn matches(&self, path: &Path, cwd: &Path) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| pattern_matches(pattern, path, cwd))
+    }
+}
+
+fn pattern_matches(pattern: &str, path: &Path, cwd: &Path) -> bool {
+    let Some(denied) = resolve_pattern(pattern, cwd) else {
+        return false;
+    };
+    if has_glob(&denied) {
+        let matcher = WildMatch::new(&denied.to_string_lossy());
+        path.ancestors()
+            .any(|ancestor| matcher.matches(&ancestor.to_string_lossy()))
+    } else {
+        path.starts_with(&denied)
+    }
+}
+
+fn is_name_pattern(pattern: &str) -> bool {
+    !pattern.contains('/') && !pattern.starts_with('~') && !Path::new(pattern).is_absolute()
+}
+
+fn resolve_pattern(pattern: &str, cwd: &Path) -> Option<PathBuf> {
+    AbsolutePathBuf::resolve_path_against_base(pattern, cwd)
+        .ok()
+        .map(AbsolutePathBuf::into_path_buf)
+}
+
+fn has_glob(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?'])
+}
+
+/// The leading components of `path` that contain no glob characters.
+fn literal_prefix(path: &Path) -> PathBuf {
+    path.components()
+        .take_while(|component| !has_glob(Path::new(component.as_os_str())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn deny_read(patterns: &[&str]) -> DenyRead {
+        DenyRead::new(patterns.iter().map(ToString::to_string).collect()).expect("valid patterns")
+    }
+
+    #[test]
+    fn matches_paths_and_globs() {
+        let deny_read = deny_read(&[
+            "./.env",
+            "./*.pem",
+            "secrets/",
+            "/etc/ssl/private/*.key",
+            " ",
+        ]);
+        let cwd = Path::new("/workspace");
+
+        assert!(deny_read.is_denied(Path::new("/workspace/.env"), cwd));
+        assert!(deny_read.is_denied(Path::new(".env"), cwd));
+        assert!(deny_read.is_denied(Path::new("/workspace/certs/server.pem"), cwd));
+        assert!(deny_read.is_denied(Path::new("/workspace/secrets/db/password"), cwd));
+        assert!(deny_read.is_denied(Path::new("/etc/ssl/private/host.key"), cwd));
+        assert!(deny_read.is_denied(Path::new("/workspace/src/../.env"), cwd));
+        assert!(!deny_read.is_denied(Path::new("/workspace/app/.env"), cwd));
+        assert!(!deny_read.is_denied(Path::new("/workspace/.env.example"), cwd));
+        assert!(!deny_read.is_denied(Path::new("/workspace/src/main.rs"), cwd));
+        assert!(!deny_read.is_denied(Path::new("/etc/ssl/certs/host.crt"), cwd));
+        assert!(!DenyRead::default().is_denied(Path::new("/workspace/.env"), cwd));
+    }
+
+    #[test]
+    fn rejects_bare_names() {
+        for pattern in [".env", "*.pem", "secrets"] {
+            let err = DenyRead::new(vec![pattern.to_string()]).expect_err("bare name");
+            assert_eq!(err.pattern, pattern);
+        }
+        assert!(DenyRead::new(vec!["~".to_string(), "/".to_string()]).is_ok());
+    }
+
+    #[test]
+    fn relative_paths_resolve_against_cwd() {
+        let deny_read = deny_read(&["config/credentials"]);
+        let cwd = Path::new("/workspace");
+
+        assert!(deny_read.is_denied(Path::new("/workspace/config/credentials/prod.json"), cwd));
+        assert!(!deny_read.is_denied(Path::new("/other/config/credentials"), cwd));
+    }
+
+    #[test]
+    fn resolve_targets_expands_globs() {
+        let tmp = tempdir().expect("tempdir");
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("app/node_modules")).expect("create dirs");
+        std::fs::create_dir_all(root.join("keys/prod")).expect("create dirs");
+        std::fs::write(root.join(".env"), "TOKEN=1").expect("write .env");
+        std::fs::write(root.join("app/.env"), "TOKEN=2").expect("write app/.env");
+        std::fs::write(root.join("app/main.rs"), "").expect("write main.rs");
+        std::fs::write(root.join("keys/prod/id.key"), "").expect("write key");
+
+        let node_modules = root.join("app/node_modules").display().to_string();
+        let deny_read = deny_read(&[
+            "./.env",
+            "app/*.env",
+            "keys/*/id.key",
+            "./missing",
+            &node_modules,
+        ]);
+        let mut targets = deny_read.resolve_targets(root).expect("resolve");
+        targets.sort();
+
+        assert_eq!(
+            targets,
+            vec![
+                root.join(".env"),
+                root.join("app/.env"),
+                root.join("app/node_modules"),
+                root.join("keys/prod/id.key"),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_targets_fails_closed_when_the_scan_limit_is_hit() {
+        let tmp = tempdir().expect("tempdir");
+        let root = tmp.path();
+        for name in ["a", "b", "c"] {
+            std::fs::write(root.join(name), "").expect("write file");
+        }
+        let deny_read = deny_read(&["./*.env"]);
+
+        let err = deny_read
+            .resolve_targets_with_limit(root, 2)
+            .expect_err("scan should stop at the limit");
+        assert_eq!(err.root, root);
+        assert_eq!(err.limit, 2);
+        assert!(deny_read.resolve_targets_with_limit(root, 3).is_ok());
+    }
+}
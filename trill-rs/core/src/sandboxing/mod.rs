@@ -6,6 +6,15 @@ sandbox placement and transformation of portable CommandSpec into a
 ready‑to‑spawn environment.
 */
 
+mod deny_read;
+mod network_proxy;
+
+pub use deny_read::DenyRead;
+pub use deny_read::DenyReadPatternError;
+pub use deny_read::DenyReadScanError;
+pub(crate) use network_proxy::NetworkProxyEndpoint;
+pub(crate) use network_proxy::SandboxNetworkProxy;
+
 use crate::exec::ExecExpiration;
 use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
@@ -64,6 +73,8 @@ pub(crate) enum SandboxTransformError {
     #[cfg(not(target_os = "macos"))]
     #[error("seatbelt sandbox is only available on macOS")]
     SeatbeltUnavailable,
+    #[error(transparent)]
+    DenyReadScan(#[from] DenyReadScanError),
 }
 
 #[derive(Default)]
@@ -102,12 +113,15 @@ impl SandboxManager {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn transform(
         &self,
         mut spec: CommandSpec,
         policy: &SandboxPolicy,
         sandbox: SandboxType,
         sandbox_policy_cwd: &Path,
+        deny_read: &DenyRead,
+        network_proxy: Option<&NetworkProxyEndpoint>,
         trill_linux_sandbox_exe: Option<&PathBuf>,
         windows_sandbox_level: WindowsSandboxLevel,
     ) -> Result<ExecEnv, SandboxTransformError> {
@@ -141,14 +155,23 @@ impl SandboxManager {
             SandboxType::LinuxSeccomp => {
                 let exe = trill_linux_sandbox_exe
                     .ok_or(SandboxTransformError::MissingLinuxSandboxExecutable)?;
-                let mut args =
-                    create_linux_sandbox_command_args(command.clone(), policy, sandbox_policy_cwd);
+                let deny_read = deny_read.resolve_targets(sandbox_policy_cwd)?;
+                let mut args = create_linux_sandbox_command_args(
+                    command.clone(),
+                    policy,
+                    sandbox_policy_cwd,
+                    &deny_read,
+                    network_proxy.map(|endpoint| endpoint.port),
+                );
                 let mut full_command = Vec::with_capacity(1 + args.len());
                 full_command.push(exe.to_string_lossy().to_string());
                 full_command.append(&mut args);
+                let proxy_env = network_proxy
+                    .map(network_proxy::proxy_env)
+                    .unwrap_or_default();
                 (
                     full_command,
-                    HashMap::new(),
+                    proxy_env,
                     Some("trill-linux-sandbox".to_string()),
                 )
             }
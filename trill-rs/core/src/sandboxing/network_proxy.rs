@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::future::Future;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use futures::StreamExt;
+use futures::stream::FuturesUnordered;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::io::Lines;
+use tokio::process::Child;
+use tokio::process::ChildStdin;
+use tokio::process::ChildStdout;
+use tokio::process::Command;
+use tracing::warn;
+use trill_network_policy::sandbox::SandboxProxyDecision;
+use trill_network_policy::sandbox::SandboxProxyEvent;
+
+#[cfg(unix)]
+const NETWORK_PROXY_ARG0: &str = "trill-network-proxy";
+const READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A `trill-network-proxy --sandbox` child serving a single sandboxed command.
+///
+/// The command may only connect to the proxy's loopback port. Hosts outside
+/// the `[network_proxy.policy]` allowlist are reported back here and held
+/// until [`SandboxNetworkProxy::drive`] answers them. Dropping the value kills
+/// the proxy.
+pub(crate) struct SandboxNetworkProxy {
+    // Held so the proxy is killed when the command is done.
+    _child: Child,
+    stdin: ChildStdin,
+    events: Lines<BufReader<ChildStdout>>,
+    endpoint: NetworkProxyEndpoint,
+    allowed_hosts: HashSet<(String, u16)>,
+}
+
+/// Where a sandboxed command reaches the proxy.
+#[derive(Clone, Debug)]
+pub(crate) struct NetworkProxyEndpoint {
+    pub(crate) port: u16,
+    /// CA certificate to trust when the proxy intercepts HTTPS.
+    pub(crate) ca_cert: Option<PathBuf>,
+}
+
+impl SandboxNetworkProxy {
+    /// Starts the proxy through the arg0 alias of `trill_linux_sandbox_exe`
+    /// and waits until it accepts connections.
+    pub(crate) async fn start(trill_linux_sandbox_exe: &Path) -> io::Result<Self> {
+        let mut cmd = Command::new(trill_linux_sandbox_exe);
+        #[cfg(unix)]
+        cmd.arg0(NETWORK_PROXY_ARG0);
+        let mut child = cmd
+            .arg("--sandbox")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(io::Error::other("network proxy stdio was not captured"));
+        };
+        let mut events = BufReader::new(stdout).lines();
+
+        let ready = async {
+            while let Some(line) = events.next_line().await? {
+                if let Ok(SandboxProxyEvent::Ready { http_addr, ca_cert }) =
+                    serde_json::from_str(&line)
+                {
+                    return Ok(NetworkProxyEndpoint {
+                        port: http_addr.port(),
+                        ca_cert,
+                    });
+                }
+            }
+            Err(io::Error::other("network proxy exited before it was ready"))
+        };
+        let endpoint = tokio::time::timeout(READY_TIMEOUT, ready)
+            .await
+            .map_err(|_| {
+                io::Error::new(io::ErrorKind::TimedOut, "network proxy did not start")
+            })??;
+
+        Ok(Self {
+            _child: child,
+            stdin,
+            events,
+            endpoint,
+            allowed_hosts: HashSet::new(),
+        })
+    }
+
+    pub(crate) fn endpoint(&self) -> &NetworkProxyEndpoint {
+        &self.endpoint
+    }
+
+    /// Runs `fut` to completion while answering the proxy's questions with
+    /// `decide(host, port)`. A host and port allowed once stay allowed for
+    /// the rest of the command. `fut` keeps running while decisions are
+    /// pending, and connections to a target that is already being decided
+    /// wait for that one answer instead of asking again.
+    pub(crate) async fn drive<T, D, DFut>(
+        &mut self,
+        fut: impl Future<Output = T>,
+        mut decide: D,
+    ) -> T
+    where
+        D: FnMut(String, u16) -> DFut,
+        DFut: Future<Output = bool>,
+    {
+        tokio::pin!(fut);
+        let mut proxy_open = true;
+        let mut waiting: HashMap<(String, u16), Vec<u64>> = HashMap::new();
+        let mut decisions = FuturesUnordered::new();
+        loop {
+            tokio::select! {
+                out = &mut fut => return out,
+                Some((target, allow)) = decisions.next(), if !decisions.is_empty() => {
+                    let ids = waiting.remove(&target).unwrap_or_default();
+                    if allow {
+                        self.allowed_hosts.insert(target);
+                    }
+                    for id in ids {
+                        if proxy_open
+                            && let Err(err) = self.answer(SandboxProxyDecision { id, allow }).await
+                        {
+                            warn!("failed to answer the sandbox network proxy: {err}");
+                            proxy_open = false;
+                        }
+                    }
+                }
+                line = self.events.next_line(), if proxy_open => {
+                    let Ok(Some(line)) = line else {
+                        proxy_open = false;
+                        continue;
+                    };
+                    let Ok(SandboxProxyEvent::Decide { id, host, port }) =
+                        serde_json::from_str(&line)
+                    else {
+                        continue;
+                    };
+                    let target = (host, port);
+                    if !self.allowed_hosts.contains(&target) {
+                        let ids = waiting.entry(target.clone()).or_default();
+                        if ids.is_empty() {
+                            let decision = decide(target.0.clone(), port);
+                            decisions.push(async move { (target, decision.await) });
+                        }
+                        ids.push(id);
+                        continue;
+                    }
+                    if let Err(err) = self.answer(SandboxProxyDecision { id, allow: true }).await {
+                        warn!("failed to answer the sandbox network proxy: {err}");
+                        proxy_open = false;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn answer(&mut self, decision: SandboxProxyDecision) -> io::Result<()> {
+        let mut line = serde_json::to_string(&decision).map_err(io::Error::other)?;
+        line.push('\n');
+        self.stdin.write_all(line.as_bytes()).await?;
+        self.stdin.flush().await
+    }
+}
+
+/// Environment that points HTTP clients inside the sandbox at the proxy and,
+/// when it intercepts HTTPS, at the CA it signs certificates with.
+pub(crate) fn proxy_env(endpoint: &NetworkProxyEndpoint) -> HashMap<String, String> {
+    let url = format!("http://127.0.0.1:{}", endpoint.port);
+    let mut env: HashMap<String, String> = [
+        "HTTP_PROXY",
+        "HTTPS_PROXY",
+        "ALL_PROXY",
+        "http_proxy",
+        "https_proxy",
+        "all_proxy",
+    ]
+    .into_iter()
+    .map(|key| (key.to_string(), url.clone()))
+    .collect();
+    if let Some(ca_cert) = &endpoint.ca_cert {
+        let ca_cert = ca_cert.to_string_lossy();
+        for key in ["SSL_CERT_FILE", "NODE_EXTRA_CA_CERTS", "REQUESTS_CA_BUNDLE"] {
+            env.insert(key.to_string(), ca_cert.to_string());
+        }
+    }
+    env
+}
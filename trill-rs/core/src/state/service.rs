@@ -4,10 +4,15 @@ use crate::AuthManager;
 use crate::RolloutRecorder;
 use crate::agent::AgentControl;
 use crate::exec_policy::ExecPolicyManager;
+use crate::hooks::HookRunner;
+use crate::mcp::sampling::McpSamplingManager;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::models_manager::manager::ModelsManager;
 use crate::skills::SkillsManager;
 use crate::state_db::StateDbHandle;
+use crate::tools::handlers::WebCache;
+use crate::tools::handlers::WebFetcher;
+use crate::tools::handlers::WebPageCache;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::transport_manager::TransportManager;
 use crate::unified_exec::UnifiedExecProcessManager;
@@ -20,8 +25,10 @@ use tokio_util::sync::CancellationToken;
 pub(crate) struct SessionServices {
     pub(crate) mcp_connection_manager: Arc<RwLock<McpConnectionManager>>,
     pub(crate) mcp_startup_cancellation_token: Mutex<CancellationToken>,
+    pub(crate) mcp_sampling: McpSamplingManager,
     pub(crate) unified_exec_manager: UnifiedExecProcessManager,
     pub(crate) notifier: UserNotifier,
+    pub(crate) hooks: HookRunner,
     pub(crate) rollout: Mutex<Option<RolloutRecorder>>,
     pub(crate) user_shell: Arc<crate::shell::Shell>,
     pub(crate) show_raw_agent_reasoning: bool,
@@ -34,4 +41,7 @@ pub(crate) struct SessionServices {
     pub(crate) agent_control: AgentControl,
     pub(crate) state_db: Option<StateDbHandle>,
     pub(crate) transport_manager: TransportManager,
+    pub(crate) web_page_cache: WebPageCache,
+    pub(crate) web_cache: WebCache,
+    pub(crate) web_fetcher: WebFetcher,
 }
@@ -1,8 +1,10 @@
 //! Session-wide mutable state.
 
+use trill_git::GhostCommit;
 use trill_protocol::models::ResponseItem;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::PathBuf;
 
 use crate::trill::SessionConfiguration;
 use crate::context_manager::ContextManager;
@@ -24,6 +26,19 @@ pub(crate) struct SessionState {
     /// TODO(owen): This is a temporary solution to avoid updating a thread's updated_at
     /// timestamp when resuming a session. Remove this once SQLite is in place.
     pub(crate) initial_context_seeded: bool,
+    /// Undos that can be reapplied, most recent last. Cleared when a new turn starts.
+    pub(crate) redo_stack: Vec<UndoneChange>,
+}
+
+/// Working tree and conversation state discarded by an undo.
+#[derive(Debug, Clone)]
+pub(crate) struct UndoneChange {
+    /// Snapshot of the working tree taken right before the undo.
+    pub(crate) ghost_commit: GhostCommit,
+    /// History items dropped by the undo; empty for per-file undos.
+    pub(crate) items: Vec<ResponseItem>,
+    /// Files the undo was limited to; empty when the whole tree was restored.
+    pub(crate) paths: Vec<PathBuf>,
 }
 
 impl SessionState {
@@ -38,6 +53,7 @@ impl SessionState {
             dependency_env: HashMap::new(),
             mcp_dependency_prompted: HashSet::new(),
             initial_context_seeded: false,
+            redo_stack: Vec::new(),
         }
     }
 
@@ -125,6 +141,22 @@ impl SessionState {
     pub(crate) fn dependency_env(&self) -> HashMap<String, String> {
         self.dependency_env.clone()
     }
+
+    pub(crate) fn push_redo(&mut self, change: UndoneChange) {
+        self.redo_stack.push(change);
+    }
+
+    pub(crate) fn pop_redo(&mut self) -> Option<UndoneChange> {
+        self.redo_stack.pop()
+    }
+
+    pub(crate) fn redo_available(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub(crate) fn clear_redo(&mut self) {
+        self.redo_stack.clear();
+    }
 }
 
 // Sometimes new snapshots don't include credits or plan information.
@@ -70,7 +70,7 @@ impl ActiveTurn {
 #[derive(Default)]
 pub(crate) struct TurnState {
     pending_approvals: HashMap<String, oneshot::Sender<ReviewDecision>>,
-    pending_user_input: HashMap<String, oneshot::Sender<RequestUserInputResponse>>,
+    pending_user_input: IndexMap<String, oneshot::Sender<RequestUserInputResponse>>,
     pending_dynamic_tools: HashMap<String, oneshot::Sender<DynamicToolResponse>>,
     pending_input: Vec<ResponseInputItem>,
 }
@@ -98,19 +98,35 @@ impl TurnState {
         self.pending_input.clear();
     }
 
+    /// Registers a user input request under `call_id` and returns its key,
+    /// which gets a `#n` suffix when `call_id` already has a pending request.
     pub(crate) fn insert_pending_user_input(
         &mut self,
-        key: String,
+        call_id: String,
         tx: oneshot::Sender<RequestUserInputResponse>,
-    ) -> Option<oneshot::Sender<RequestUserInputResponse>> {
-        self.pending_user_input.insert(key, tx)
+    ) -> String {
+        let mut key = call_id.clone();
+        let mut n = 1;
+        while self.pending_user_input.contains_key(&key) {
+            n += 1;
+            key = format!("{call_id}#{n}");
+        }
+        self.pending_user_input.insert(key.clone(), tx);
+        key
     }
 
+    /// Removes the request with `key`, or the oldest one when `key` is `None`.
     pub(crate) fn remove_pending_user_input(
         &mut self,
-        key: &str,
+        key: Option<&str>,
     ) -> Option<oneshot::Sender<RequestUserInputResponse>> {
-        self.pending_user_input.remove(key)
+        match key {
+            Some(key) => self.pending_user_input.shift_remove(key),
+            None => self
+                .pending_user_input
+                .shift_remove_index(0)
+                .map(|(_, tx)| tx),
+        }
     }
 
     pub(crate) fn insert_pending_dynamic_tool(
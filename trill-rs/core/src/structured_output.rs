@@ -0,0 +1,195 @@
+//! Client-side enforcement of `--output-schema` for Chat Completions
+//! providers that cannot constrain decoding.
+//!
+//! When the provider's `structured_output` setting does not enforce the
+//! schema, the final message of the turn is validated instead. A message that
+//! does not match is answered with a corrective prompt, up to
+//! [`MAX_OUTPUT_SCHEMA_REPAIRS`] times per turn. The prompt is only sent with
+//! the next request and never recorded, so it does not show up in the
+//! conversation history or in later turns.
+
+use serde_json::Value;
+use trill_protocol::models::ContentItem;
+use trill_protocol::models::ResponseItem;
+use trill_protocol::protocol::EventMsg;
+use trill_protocol::protocol::WarningEvent;
+
+use crate::model_provider_info::ModelProviderInfo;
+use crate::model_provider_info::WireApi;
+use crate::trill::Session;
+use crate::trill::TurnContext;
+
+/// Corrective prompts sent per turn before the reply is accepted as is.
+pub(crate) const MAX_OUTPUT_SCHEMA_REPAIRS: usize = 2;
+/// Schema violations listed in one corrective prompt.
+const MAX_REPORTED_ERRORS: usize = 5;
+
+/// Whether the final message must be checked against `schema` here because
+/// the provider does not enforce it on a request that did or did not offer
+/// tools.
+pub(crate) fn needs_output_validation(
+    provider: &ModelProviderInfo,
+    schema: &Value,
+    with_tools: bool,
+) -> bool {
+    provider.wire_api == WireApi::Chat
+        && !provider
+            .api_structured_output()
+            .enforces(schema, with_tools)
+}
+
+/// Checks that `message` is a JSON document matching `schema` and describes
+/// what is wrong otherwise. A schema the validator cannot compile is not
+/// enforced.
+pub(crate) fn validate_output(schema: &Value, message: &str) -> Result<(), String> {
+    let validator = match jsonschema::validator_for(schema) {
+        Ok(validator) => validator,
+        Err(err) => {
+            tracing::warn!("cannot validate final output against invalid schema: {err}");
+            return Ok(());
+        }
+    };
+    let value: Value = serde_json::from_str(message.trim())
+        .map_err(|err| format!("The reply is not valid JSON: {err}."))?;
+    let errors: Vec<String> = validator
+        .iter_errors(&value)
+        .take(MAX_REPORTED_ERRORS)
+        .map(|err| {
+            let path = err.instance_path().to_string();
+            if path.is_empty() {
+                format!("- {err}")
+            } else {
+                format!("- at `{path}`: {err}")
+            }
+        })
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "The reply does not match the output schema:\n{}",
+            errors.join("\n")
+        ))
+    }
+}
+
+fn repair_prompt(problem: &str, schema: &Value) -> String {
+    format!(
+        "{problem}\n\nRespond again with only a JSON document that matches this schema, \
+         without code fences or other text:\n{schema}"
+    )
+}
+
+/// Validates the turn's final message, produced by a request that did or did
+/// not offer tools, when the provider cannot enforce the output schema.
+/// Returns the corrective prompt to append to the next request if the turn
+/// should sample again.
+pub(crate) async fn request_output_repair(
+    sess: &Session,
+    turn_context: &TurnContext,
+    last_agent_message: Option<&str>,
+    with_tools: bool,
+    repairs: &mut usize,
+) -> Option<ResponseItem> {
+    let schema = turn_context.final_output_json_schema.as_ref()?;
+    if !needs_output_validation(&turn_context.client.get_provider(), schema, with_tools) {
+        return None;
+    }
+    let problem = validate_output(schema, last_agent_message.unwrap_or_default()).err()?;
+    if *repairs >= MAX_OUTPUT_SCHEMA_REPAIRS {
+        let message = format!(
+            "Final message still does not match the output schema after \
+             {MAX_OUTPUT_SCHEMA_REPAIRS} retries. {problem}"
+        );
+        sess.send_event(turn_context, EventMsg::Warning(WarningEvent { message }))
+            .await;
+        return None;
+    }
+    *repairs += 1;
+
+    let message = format!(
+        "Final message does not match the output schema; asking the model to fix it \
+         (attempt {repairs}/{MAX_OUTPUT_SCHEMA_REPAIRS})."
+    );
+    sess.send_event(turn_context, EventMsg::Warning(WarningEvent { message }))
+        .await;
+    Some(ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: repair_prompt(&problem, schema),
+        }],
+        end_turn: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_provider_info::StructuredOutput;
+    use crate::model_provider_info::create_oss_provider_with_base_url;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "status": { "enum": ["ok", "failed"] },
+                "files": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["status", "files"],
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn accepts_matching_output() {
+        assert_eq!(
+            validate_output(&schema(), "  {\"status\": \"ok\", \"files\": []}\n"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn reports_parse_errors_and_schema_violations() {
+        let not_json = validate_output(&schema(), "```json\n{}\n```").expect_err("fenced");
+        assert!(
+            not_json.starts_with("The reply is not valid JSON"),
+            "{not_json}"
+        );
+
+        let mismatch =
+            validate_output(&schema(), r#"{"status": "done", "files": [1]}"#).expect_err("invalid");
+        assert!(
+            mismatch.starts_with("The reply does not match the output schema:"),
+            "{mismatch}"
+        );
+        assert!(mismatch.contains("at `/status`"), "{mismatch}");
+        assert!(mismatch.contains("at `/files/0`"), "{mismatch}");
+    }
+
+    #[test]
+    fn validates_only_without_constrained_decoding() {
+        let mut provider =
+            create_oss_provider_with_base_url("http://localhost:8080/v1", WireApi::Chat);
+        assert!(!needs_output_validation(&provider, &schema(), true));
+
+        provider.structured_output = Some(StructuredOutput::None);
+        assert!(needs_output_validation(&provider, &schema(), false));
+
+        provider.structured_output = Some(StructuredOutput::Grammar);
+        assert!(!needs_output_validation(&provider, &schema(), false));
+        assert!(needs_output_validation(
+            &provider,
+            &json!({ "allOf": [{ "type": "object" }] }),
+            false
+        ));
+        // The grammar is not sent alongside tools.
+        assert!(needs_output_validation(&provider, &schema(), true));
+
+        provider.wire_api = WireApi::Responses;
+        provider.structured_output = Some(StructuredOutput::None);
+        assert!(!needs_output_validation(&provider, &schema(), true));
+    }
+}
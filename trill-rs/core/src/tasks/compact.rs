@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use super::SessionTask;
 use super::SessionTaskContext;
+use crate::hooks::CompactTrigger;
 use crate::trill::TurnContext;
 use crate::state::TaskKind;
 use async_trait::async_trait;
@@ -25,6 +26,7 @@ impl SessionTask for CompactTask {
         _cancellation_token: CancellationToken,
     ) -> Option<String> {
         let session = session.clone_session();
+        crate::hooks::run_pre_compact(&session, &ctx, CompactTrigger::Manual).await;
         if crate::compact::should_use_remote_compact_task(
             session.as_ref(),
             &ctx.client.get_provider(),
@@ -39,7 +39,11 @@ pub(crate) use compact::CompactTask;
 pub(crate) use ghost_snapshot::GhostSnapshotTask;
 pub(crate) use regular::RegularTask;
 pub(crate) use review::ReviewTask;
+pub(crate) use undo::UndoTarget;
 pub(crate) use undo::UndoTask;
+pub(crate) use undo::list_undo_snapshots;
+pub(crate) use undo::redo;
+pub(crate) use undo::undo_turns;
 pub(crate) use user_shell::UserShellCommandTask;
 
 const GRACEFULL_INTERRUPTION_TIMEOUT_MS: u64 = 100;
@@ -1,14 +1,28 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::compact::content_items_to_text;
+use crate::context_manager::is_user_turn_boundary;
+use crate::trill::Session;
 use crate::trill::TurnContext;
 use crate::protocol::EventMsg;
+use crate::protocol::ThreadRolledBackEvent;
 use crate::protocol::UndoCompletedEvent;
+use crate::protocol::UndoSnapshot;
+use crate::protocol::UndoSnapshotsResponseEvent;
 use crate::protocol::UndoStartedEvent;
 use crate::state::TaskKind;
+use crate::state::UndoneChange;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
 use async_trait::async_trait;
+use trill_git::CreateGhostCommitOptions;
+use trill_git::GhostCommit;
+use trill_git::GitToolingError;
 use trill_git::RestoreGhostCommitOptions;
+use trill_git::create_ghost_commit;
+use trill_git::ghost_commit_changed_paths;
+use trill_git::restore_ghost_commit_paths;
 use trill_git::restore_ghost_commit_with_options;
 use trill_protocol::models::ResponseItem;
 use trill_protocol::user_input::UserInput;
@@ -17,11 +31,31 @@ use tracing::error;
 use tracing::info;
 use tracing::warn;
 
-pub(crate) struct UndoTask;
+/// What an [`UndoTask`] should restore.
+#[derive(Debug, Clone)]
+pub(crate) enum UndoTarget {
+    /// Restore the most recent ghost snapshot and consume it, keeping the
+    /// conversation as is.
+    Latest,
+    /// Restore the snapshot taken before the last `num_turns` turns. When
+    /// `paths` is empty the whole tree is restored and the turns are dropped
+    /// from the conversation; otherwise only `paths` are restored.
+    Turns { num_turns: u32, paths: Vec<PathBuf> },
+    /// Reapply the most recent undo.
+    Redo,
+}
+
+pub(crate) struct UndoTask {
+    target: UndoTarget,
+}
 
 impl UndoTask {
     pub(crate) fn new() -> Self {
-        Self
+        Self::with_target(UndoTarget::Latest)
+    }
+
+    pub(crate) fn with_target(target: UndoTarget) -> Self {
+        Self { target }
     }
 }
 
@@ -44,10 +78,14 @@ impl SessionTask for UndoTask {
             .otel_manager
             .counter("codex.task.undo", 1, &[]);
         let sess = session.clone_session();
+        let started_message = match self.target {
+            UndoTarget::Redo => "Redo in progress...",
+            UndoTarget::Latest | UndoTarget::Turns { .. } => "Undo in progress...",
+        };
         sess.send_event(
             ctx.as_ref(),
             EventMsg::UndoStarted(UndoStartedEvent {
-                message: Some("Undo in progress...".to_string()),
+                message: Some(started_message.to_string()),
             }),
         )
         .await;
@@ -64,63 +102,318 @@ impl SessionTask for UndoTask {
             return None;
         }
 
-        let history = sess.clone_history().await;
-        let mut items = history.raw_items().to_vec();
-        let mut completed = UndoCompletedEvent {
-            success: false,
-            message: None,
+        let result = match &self.target {
+            UndoTarget::Latest => undo_latest(&sess, ctx.as_ref()).await,
+            UndoTarget::Turns { num_turns, paths } => {
+                undo_turns(&sess, ctx.as_ref(), *num_turns, paths.clone()).await
+            }
+            UndoTarget::Redo => redo(&sess, ctx.as_ref()).await,
         };
-
-        let Some((idx, ghost_commit)) =
-            items
-                .iter()
-                .enumerate()
-                .rev()
-                .find_map(|(idx, item)| match item {
-                    ResponseItem::GhostSnapshot { ghost_commit } => {
-                        Some((idx, ghost_commit.clone()))
-                    }
-                    _ => None,
-                })
-        else {
-            completed.message = Some("No ghost snapshot available to undo.".to_string());
-            sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
-                .await;
-            return None;
+        let completed = match result {
+            Ok(message) => UndoCompletedEvent {
+                success: true,
+                message: Some(message),
+            },
+            Err(message) => UndoCompletedEvent {
+                success: false,
+                message: Some(message),
+            },
         };
 
-        let commit_id = ghost_commit.id().to_string();
-        let repo_path = ctx.cwd.clone();
-        let ghost_snapshot = ctx.ghost_snapshot.clone();
-        let restore_result = tokio::task::spawn_blocking(move || {
-            let options = RestoreGhostCommitOptions::new(&repo_path).ghost_snapshot(ghost_snapshot);
-            restore_ghost_commit_with_options(&options, &ghost_commit)
-        })
+        sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+            .await;
+        None
+    }
+}
+
+async fn undo_latest(sess: &Session, ctx: &TurnContext) -> Result<String, String> {
+    let history = sess.clone_history().await;
+    let mut items = history.raw_items().to_vec();
+
+    let Some((idx, ghost_commit)) =
+        items
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(idx, item)| match item {
+                ResponseItem::GhostSnapshot { ghost_commit } => Some((idx, ghost_commit.clone())),
+                _ => None,
+            })
+    else {
+        return Err("No ghost snapshot available to undo.".to_string());
+    };
+
+    let commit_id = ghost_commit.id().to_string();
+    let repo_path = ctx.cwd.clone();
+    let ghost_snapshot = ctx.ghost_snapshot.clone();
+    let restore_result = tokio::task::spawn_blocking(move || {
+        let options = RestoreGhostCommitOptions::new(&repo_path).ghost_snapshot(ghost_snapshot);
+        restore_ghost_commit_with_options(&options, &ghost_commit)
+    })
+    .await;
+
+    match restore_result {
+        Ok(Ok(())) => {
+            items.remove(idx);
+            sess.replace_history(items).await;
+            info!(commit_id = commit_id, "Undo restored ghost snapshot");
+            Ok(format!("Undo restored snapshot {}.", short_id(&commit_id)))
+        }
+        Ok(Err(err)) => {
+            let message = format!("Failed to restore snapshot {commit_id}: {err}");
+            warn!("{message}");
+            Err(message)
+        }
+        Err(err) => {
+            let message = format!("Failed to restore snapshot {commit_id}: {err}");
+            error!("{message}");
+            Err(message)
+        }
+    }
+}
+
+/// Restores the snapshot taken before the last `num_turns` turns.
+///
+/// A full restore also drops those turns from the conversation. Either way the
+/// current working tree is captured first so the undo can be redone.
+pub(crate) async fn undo_turns(
+    sess: &Session,
+    ctx: &TurnContext,
+    num_turns: u32,
+    paths: Vec<PathBuf>,
+) -> Result<String, String> {
+    if num_turns == 0 {
+        return Err("num_turns must be >= 1".to_string());
+    }
+    let paths = paths
+        .into_iter()
+        .map(|path| session_relative_path(ctx, path))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let history = sess.clone_history().await;
+    let items = history.raw_items().to_vec();
+    let turns = user_turns(&items);
+    let total_turns = turns.len();
+    let Some(idx) = total_turns.checked_sub(usize::try_from(num_turns).unwrap_or(usize::MAX))
+    else {
+        return Err(format!(
+            "Cannot undo {num_turns} turns; only {total_turns} available."
+        ));
+    };
+    let Some(ghost_commit) = turns[idx].ghost_commit.clone() else {
+        return Err("No ghost snapshot available for that turn.".to_string());
+    };
+
+    let current = capture_working_tree(ctx).await?;
+    restore(ctx, ghost_commit.clone(), paths.clone()).await?;
+    let commit_id = ghost_commit.id().to_string();
+    info!(
+        commit_id = commit_id,
+        num_turns, "Undo restored ghost snapshot"
+    );
+
+    let (dropped, message) = if paths.is_empty() {
+        let mut history = history;
+        history.drop_last_n_user_turns(num_turns);
+        let kept = history.raw_items().to_vec();
+        let dropped = items[kept.len()..].to_vec();
+        sess.replace_history(kept).await;
+        sess.recompute_token_usage(ctx).await;
+        sess.send_event(
+            ctx,
+            EventMsg::ThreadRolledBack(ThreadRolledBackEvent { num_turns }),
+        )
         .await;
+        let turns = if num_turns == 1 { "turn" } else { "turns" };
+        (
+            dropped,
+            format!(
+                "Undid {num_turns} {turns} by restoring snapshot {}.",
+                short_id(&commit_id)
+            ),
+        )
+    } else {
+        let files = if paths.len() == 1 { "file" } else { "files" };
+        (
+            Vec::new(),
+            format!(
+                "Restored {} {files} from snapshot {}.",
+                paths.len(),
+                short_id(&commit_id)
+            ),
+        )
+    };
 
-        match restore_result {
-            Ok(Ok(())) => {
-                items.remove(idx);
-                sess.replace_history(items).await;
-                let short_id: String = commit_id.chars().take(7).collect();
-                info!(commit_id = commit_id, "Undo restored ghost snapshot");
-                completed.success = true;
-                completed.message = Some(format!("Undo restored snapshot {short_id}."));
-            }
-            Ok(Err(err)) => {
-                let message = format!("Failed to restore snapshot {commit_id}: {err}");
-                warn!("{message}");
-                completed.message = Some(message);
-            }
-            Err(err) => {
-                let message = format!("Failed to restore snapshot {commit_id}: {err}");
-                error!("{message}");
-                completed.message = Some(message);
-            }
+    sess.push_redo(UndoneChange {
+        ghost_commit: current,
+        items: dropped,
+        paths,
+    })
+    .await;
+    Ok(message)
+}
+
+/// Reapplies the most recent undo, restoring the working tree captured before
+/// it and any turns it dropped.
+pub(crate) async fn redo(sess: &Session, ctx: &TurnContext) -> Result<String, String> {
+    let Some(change) = sess.pop_redo().await else {
+        return Err("Nothing to redo.".to_string());
+    };
+
+    if let Err(message) = restore(ctx, change.ghost_commit.clone(), change.paths.clone()).await {
+        sess.push_redo(change).await;
+        return Err(message);
+    }
+    if !change.items.is_empty() {
+        sess.record_conversation_items(ctx, &change.items).await;
+    }
+    info!(
+        commit_id = change.ghost_commit.id(),
+        "Redo restored ghost snapshot"
+    );
+    Ok(format!(
+        "Redo restored snapshot {}.",
+        short_id(change.ghost_commit.id())
+    ))
+}
+
+/// Lists the turns that can be undone, newest first, with the files each one
+/// changed.
+pub(crate) async fn list_undo_snapshots(
+    sess: &Session,
+    ctx: &TurnContext,
+) -> Result<UndoSnapshotsResponseEvent, String> {
+    let history = sess.clone_history().await;
+    let turns = user_turns(history.raw_items());
+    let total_turns = turns.len();
+    let redo_available = sess.redo_available().await;
+    if turns.iter().all(|turn| turn.ghost_commit.is_none()) {
+        return Ok(UndoSnapshotsResponseEvent {
+            snapshots: Vec::new(),
+            redo_available,
+        });
+    }
+
+    // Each turn changed whatever differs between its snapshot and the next
+    // one; the latest turn is compared with the current working tree.
+    let mut next_commit_id = capture_working_tree(ctx).await?.id().to_string();
+    let mut snapshots = Vec::new();
+    for (idx, turn) in turns.into_iter().enumerate().rev() {
+        let Some(ghost_commit) = turn.ghost_commit else {
+            continue;
+        };
+        let repo_path = ctx.cwd.clone();
+        let from = ghost_commit.id().to_string();
+        let to = next_commit_id.clone();
+        let changed_files = run_git(move || ghost_commit_changed_paths(&repo_path, &from, &to))
+            .await
+            .map_err(|err| format!("Failed to list changed files: {err}"))?;
+        next_commit_id = ghost_commit.id().to_string();
+        snapshots.push(UndoSnapshot {
+            num_turns: u32::try_from(total_turns - idx).unwrap_or(u32::MAX),
+            commit_id: ghost_commit.id().to_string(),
+            user_message: turn.user_message,
+            changed_files,
+        });
+    }
+
+    Ok(UndoSnapshotsResponseEvent {
+        snapshots,
+        redo_available,
+    })
+}
+
+struct UserTurn {
+    user_message: String,
+    ghost_commit: Option<GhostCommit>,
+}
+
+/// Splits history into user turns, oldest first, pairing each with the ghost
+/// snapshot recorded while it ran.
+fn user_turns(items: &[ResponseItem]) -> Vec<UserTurn> {
+    let mut turns: Vec<UserTurn> = Vec::new();
+    for item in items {
+        if is_user_turn_boundary(item) {
+            let user_message = match item {
+                ResponseItem::Message { content, .. } => {
+                    content_items_to_text(content).unwrap_or_default()
+                }
+                _ => String::new(),
+            };
+            turns.push(UserTurn {
+                user_message,
+                ghost_commit: None,
+            });
+        } else if let ResponseItem::GhostSnapshot { ghost_commit } = item
+            && let Some(turn) = turns.last_mut()
+            && turn.ghost_commit.is_none()
+        {
+            turn.ghost_commit = Some(ghost_commit.clone());
         }
+    }
+    turns
+}
 
-        sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
-            .await;
-        None
+fn session_relative_path(ctx: &TurnContext, path: PathBuf) -> Result<PathBuf, String> {
+    if path.is_relative() {
+        return Ok(path);
     }
+    path.strip_prefix(&ctx.cwd)
+        .map(std::path::Path::to_path_buf)
+        .map_err(|_| format!("{} is outside {}", path.display(), ctx.cwd.display()))
+}
+
+async fn capture_working_tree(ctx: &TurnContext) -> Result<GhostCommit, String> {
+    let repo_path = ctx.cwd.clone();
+    let ghost_snapshot = ctx.ghost_snapshot.clone();
+    run_git(move || {
+        let options = CreateGhostCommitOptions::new(&repo_path).ghost_snapshot(ghost_snapshot);
+        create_ghost_commit(&options)
+    })
+    .await
+    .map_err(|err| format!("Failed to snapshot the working tree: {err}"))
+}
+
+async fn restore(
+    ctx: &TurnContext,
+    ghost_commit: GhostCommit,
+    paths: Vec<PathBuf>,
+) -> Result<(), String> {
+    let commit_id = ghost_commit.id().to_string();
+    let repo_path = ctx.cwd.clone();
+    let ghost_snapshot = ctx.ghost_snapshot.clone();
+    run_git(move || {
+        let options = RestoreGhostCommitOptions::new(&repo_path).ghost_snapshot(ghost_snapshot);
+        if paths.is_empty() {
+            restore_ghost_commit_with_options(&options, &ghost_commit)
+        } else {
+            restore_ghost_commit_paths(&options, &ghost_commit, &paths)
+        }
+    })
+    .await
+    .map_err(|err| {
+        let message = format!("Failed to restore snapshot {commit_id}: {err}");
+        warn!("{message}");
+        message
+    })
+}
+
+async fn run_git<T, F>(f: F) -> Result<T, String>
+where
+    F: FnOnce() -> Result<T, GitToolingError> + Send + 'static,
+    T: Send + 'static,
+{
+    // Git tooling blocks, so it runs in the dedicated blocking pool.
+    match tokio::task::spawn_blocking(f).await {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(err)) => Err(err.to_string()),
+        Err(err) => {
+            error!("git task panicked: {err}");
+            Err(err.to_string())
+        }
+    }
+}
+
+fn short_id(commit_id: &str) -> String {
+    commit_id.chars().take(7).collect()
 }
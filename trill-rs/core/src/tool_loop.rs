@@ -0,0 +1,414 @@
+//! Detection of tool-call loops, where a model keeps making the same call
+//! (or cycles through the same few calls) and keeps getting the same result.
+//!
+//! Each call is fingerprinted from the tool name, its arguments with object
+//! keys sorted, and its output with timing details removed. When the latest
+//! calls are a cycle of up to `max_cycle_length` calls repeated `repeats`
+//! times in a row, the model is first told to change approach. Once
+//! `max_warnings` such messages have been sent, the next loop ends the turn
+//! with [`EventMsg::ToolCallLoopDetected`].
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+use serde_json::Value;
+use trill_protocol::models::DeveloperInstructions;
+use trill_protocol::models::ResponseItem;
+use trill_protocol::protocol::EventMsg;
+use trill_protocol::protocol::ToolCallLoopDetectedEvent;
+use trill_protocol::protocol::WarningEvent;
+
+use crate::config::types::ToolLoopDetection;
+use crate::trill::Session;
+use crate::trill::TurnContext;
+
+/// Output lines that change between otherwise identical runs.
+const VOLATILE_OUTPUT_PREFIXES: [&str; 2] = ["Wall time:", "Chunk ID:"];
+/// Output JSON keys that change between otherwise identical runs.
+const VOLATILE_OUTPUT_KEYS: [&str; 1] = ["duration_seconds"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Fingerprint {
+    tool_name: String,
+    hash: u64,
+}
+
+/// Calls repeated back to back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ToolLoop {
+    /// Tools in one cycle, in call order.
+    pub(crate) tool_names: Vec<String>,
+    /// Times the cycle occurred in a row.
+    pub(crate) repeats: usize,
+}
+
+impl ToolLoop {
+    fn describe(&self) -> String {
+        match self.tool_names.as_slice() {
+            [tool_name] => format!("the same `{tool_name}` call"),
+            tool_names => format!(
+                "the same sequence of calls ({})",
+                tool_names
+                    .iter()
+                    .map(|name| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            ),
+        }
+    }
+
+    fn corrective_prompt(&self) -> String {
+        format!(
+            "You have made {} {} times in a row with the same arguments and got the same \
+             result each time. Repeating it will not produce anything new. Use the results \
+             you already have, try a different approach, or stop and explain what is blocking \
+             you.",
+            self.describe(),
+            self.repeats
+        )
+    }
+}
+
+pub(crate) struct ToolLoopDetector {
+    settings: ToolLoopDetection,
+    seen: HashSet<String>,
+    calls: Vec<Fingerprint>,
+    warnings: usize,
+}
+
+impl ToolLoopDetector {
+    /// Creates a detector that ignores the calls already in `history`.
+    pub(crate) fn new(settings: ToolLoopDetection, history: &[ResponseItem]) -> Self {
+        let seen = history
+            .iter()
+            .filter_map(|item| call_of(item).map(|(call_id, _, _)| call_id))
+            .collect();
+        Self {
+            settings,
+            seen,
+            calls: Vec::new(),
+            warnings: 0,
+        }
+    }
+
+    /// Fingerprints the completed calls in `history` that were not seen yet
+    /// and reports a loop ending at the latest one.
+    pub(crate) fn observe(&mut self, history: &[ResponseItem]) -> Option<ToolLoop> {
+        if !self.settings.enabled {
+            return None;
+        }
+        let outputs: HashMap<&str, String> = history.iter().filter_map(output_of).collect();
+        let mut added = false;
+        for item in history {
+            let Some((call_id, tool_name, arguments)) = call_of(item) else {
+                continue;
+            };
+            let Some(output) = outputs.get(call_id.as_str()) else {
+                continue;
+            };
+            if !self.seen.insert(call_id) {
+                continue;
+            }
+            let mut hasher = DefaultHasher::new();
+            tool_name.hash(&mut hasher);
+            normalize_arguments(&arguments).hash(&mut hasher);
+            normalize_output(output).hash(&mut hasher);
+            self.calls.push(Fingerprint {
+                tool_name,
+                hash: hasher.finish(),
+            });
+            added = true;
+        }
+        if added { self.find_loop() } else { None }
+    }
+
+    fn find_loop(&self) -> Option<ToolLoop> {
+        let repeats = self.settings.repeats;
+        (1..=self.settings.max_cycle_length).find_map(|cycle| {
+            let window = cycle.checked_mul(repeats)?;
+            let start = self.calls.len().checked_sub(window)?;
+            let tail = &self.calls[start..];
+            let repeating = (cycle..window).all(|i| tail[i] == tail[i - cycle]);
+            repeating.then(|| ToolLoop {
+                tool_names: tail[..cycle]
+                    .iter()
+                    .map(|call| call.tool_name.clone())
+                    .collect(),
+                repeats,
+            })
+        })
+    }
+
+    /// Records that the model was warned about a loop. Returns `false` once
+    /// no warnings are left and the turn should end instead.
+    fn warn(&mut self) -> bool {
+        if self.warnings >= self.settings.max_warnings {
+            return false;
+        }
+        self.warnings += 1;
+        // Give the model a fresh window to show it changed approach.
+        self.calls.clear();
+        true
+    }
+}
+
+/// Looks for a tool-call loop after a sampling request. A first loop is
+/// answered with a corrective developer message; returns `true` when the
+/// turn should end because the model kept looping.
+pub(crate) async fn check_tool_loop(
+    sess: &Session,
+    turn_context: &TurnContext,
+    detector: &mut ToolLoopDetector,
+) -> bool {
+    if !detector.settings.enabled {
+        return false;
+    }
+    let history = sess.clone_history().await;
+    let Some(found) = detector.observe(history.raw_items()) else {
+        return false;
+    };
+
+    if detector.warn() {
+        let message = format!(
+            "The model repeated {} {} times in a row; asking it to change approach.",
+            found.describe(),
+            found.repeats
+        );
+        sess.send_event(turn_context, EventMsg::Warning(WarningEvent { message }))
+            .await;
+        let prompt: ResponseItem = DeveloperInstructions::new(found.corrective_prompt()).into();
+        sess.record_conversation_items(turn_context, &[prompt])
+            .await;
+        return false;
+    }
+
+    let message = format!(
+        "Stopped the turn: the model kept repeating {} ({} times in a row).",
+        found.describe(),
+        found.repeats
+    );
+    let event = ToolCallLoopDetectedEvent {
+        message,
+        tool_names: found.tool_names,
+        repeats: u32::try_from(found.repeats).unwrap_or(u32::MAX),
+    };
+    sess.send_event(turn_context, EventMsg::ToolCallLoopDetected(event))
+        .await;
+    true
+}
+
+/// Call id, tool name and arguments of a tool call item.
+fn call_of(item: &ResponseItem) -> Option<(String, String, String)> {
+    match item {
+        ResponseItem::FunctionCall {
+            name,
+            arguments,
+            call_id,
+            ..
+        } => Some((call_id.clone(), name.clone(), arguments.clone())),
+        ResponseItem::CustomToolCall {
+            name,
+            input,
+            call_id,
+            ..
+        } => Some((call_id.clone(), name.clone(), input.clone())),
+        ResponseItem::LocalShellCall {
+            id,
+            call_id,
+            action,
+            ..
+        } => Some((
+            call_id.clone().or_else(|| id.clone())?,
+            "local_shell".to_string(),
+            serde_json::to_string(action).ok()?,
+        )),
+        _ => None,
+    }
+}
+
+fn output_of(item: &ResponseItem) -> Option<(&str, String)> {
+    match item {
+        ResponseItem::FunctionCallOutput { call_id, output } => {
+            let mut text = output.content.clone();
+            if let Some(items) = &output.content_items
+                && let Ok(items) = serde_json::to_string(items)
+            {
+                text.push_str(&items);
+            }
+            Some((call_id.as_str(), text))
+        }
+        ResponseItem::CustomToolCallOutput { call_id, output } => {
+            Some((call_id.as_str(), output.clone()))
+        }
+        _ => None,
+    }
+}
+
+fn normalize_arguments(arguments: &str) -> String {
+    match serde_json::from_str::<Value>(arguments) {
+        Ok(value) => canonical(value, &[]).to_string(),
+        Err(_) => arguments.trim().to_string(),
+    }
+}
+
+fn normalize_output(output: &str) -> String {
+    if let Ok(value) = serde_json::from_str::<Value>(output) {
+        return canonical(value, &VOLATILE_OUTPUT_KEYS).to_string();
+    }
+    output
+        .lines()
+        .filter(|line| {
+            !VOLATILE_OUTPUT_PREFIXES
+                .iter()
+                .any(|prefix| line.starts_with(prefix))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `value` with object keys sorted and `skip_keys` removed at any depth.
+fn canonical(value: Value, skip_keys: &[&str]) -> Value {
+    match value {
+        Value::Object(map) => {
+            let sorted: BTreeMap<String, Value> = map
+                .into_iter()
+                .filter(|(key, _)| !skip_keys.contains(&key.as_str()))
+                .map(|(key, value)| (key, canonical(value, skip_keys)))
+                .collect();
+            Value::Object(sorted.into_iter().collect())
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| canonical(item, skip_keys))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use trill_protocol::models::FunctionCallOutputPayload;
+
+    fn settings() -> ToolLoopDetection {
+        ToolLoopDetection {
+            enabled: true,
+            repeats: 3,
+            max_cycle_length: 3,
+            max_warnings: 1,
+        }
+    }
+
+    fn call(history: &mut Vec<ResponseItem>, name: &str, arguments: &str, output: &str) {
+        let call_id = format!("call-{}", history.len());
+        history.push(ResponseItem::FunctionCall {
+            id: None,
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+            call_id: call_id.clone(),
+        });
+        history.push(ResponseItem::FunctionCallOutput {
+            call_id,
+            output: FunctionCallOutputPayload {
+                content: output.to_string(),
+                ..Default::default()
+            },
+        });
+    }
+
+    #[test]
+    fn detects_identical_calls_regardless_of_key_order_and_timing() {
+        let mut history = Vec::new();
+        let mut detector = ToolLoopDetector::new(settings(), &history);
+
+        call(
+            &mut history,
+            "shell",
+            r#"{"command": ["ls"], "workdir": "."}"#,
+            "Exit code: 0\nWall time: 0.1 seconds\nOutput:\na.txt",
+        );
+        call(
+            &mut history,
+            "shell",
+            r#"{"workdir": ".", "command": ["ls"]}"#,
+            "Exit code: 0\nWall time: 0.3 seconds\nOutput:\na.txt",
+        );
+        assert_eq!(detector.observe(&history), None);
+
+        call(
+            &mut history,
+            "shell",
+            r#"{"command":["ls"],"workdir":"."}"#,
+            r#"Exit code: 0
+Wall time: 0.2 seconds
+Output:
+a.txt"#,
+        );
+        assert_eq!(
+            detector.observe(&history),
+            Some(ToolLoop {
+                tool_names: vec!["shell".to_string()],
+                repeats: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn detects_short_cycles_but_not_changing_output() {
+        let mut history = Vec::new();
+        let mut detector = ToolLoopDetector::new(settings(), &history);
+        for _ in 0..3 {
+            call(&mut history, "read_file", r#"{"path": "a"}"#, "a");
+            call(&mut history, "grep_files", r#"{"pattern": "x"}"#, "a");
+        }
+        assert_eq!(
+            detector.observe(&history),
+            Some(ToolLoop {
+                tool_names: vec!["read_file".to_string(), "grep_files".to_string()],
+                repeats: 3,
+            })
+        );
+
+        let mut history = Vec::new();
+        let mut detector = ToolLoopDetector::new(settings(), &history);
+        for attempt in 0..4 {
+            call(
+                &mut history,
+                "shell",
+                r#"{"command": ["cargo", "build"]}"#,
+                &format!("{attempt} errors"),
+            );
+        }
+        assert_eq!(detector.observe(&history), None);
+    }
+
+    #[test]
+    fn ignores_earlier_calls_and_escalates_after_warnings() {
+        let mut history = Vec::new();
+        for _ in 0..3 {
+            call(&mut history, "list_dir", r#"{"dir_path": "/"}"#, "bin");
+        }
+        let mut detector = ToolLoopDetector::new(settings(), &history);
+        assert_eq!(detector.observe(&history), None);
+
+        for _ in 0..3 {
+            call(&mut history, "list_dir", r#"{"dir_path": "/"}"#, "bin");
+        }
+        assert!(detector.observe(&history).is_some());
+        assert!(detector.warn());
+
+        call(&mut history, "list_dir", r#"{"dir_path": "/"}"#, "bin");
+        assert_eq!(detector.observe(&history), None);
+        call(&mut history, "list_dir", r#"{"dir_path": "/"}"#, "bin");
+        call(&mut history, "list_dir", r#"{"dir_path": "/"}"#, "bin");
+        assert!(detector.observe(&history).is_some());
+        assert!(!detector.warn());
+    }
+}
@@ -7,6 +7,7 @@ use tokio::process::Command;
 use tokio::time::timeout;
 
 use crate::function_tool::FunctionCallError;
+use crate::sandboxing::DenyRead;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -73,6 +74,13 @@ impl ToolHandler for GrepFilesHandler {
 
         verify_path_exists(&search_path).await?;
 
+        if turn.sandbox_deny_read.is_denied(&search_path, &turn.cwd) {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "access to `{}` is denied by sandbox_deny_read",
+                search_path.display()
+            )));
+        }
+
         let include = args.include.as_deref().map(str::trim).and_then(|val| {
             if val.is_empty() {
                 None
@@ -81,8 +89,15 @@ impl ToolHandler for GrepFilesHandler {
             }
         });
 
-        let search_results =
-            run_rg_search(pattern, include.as_deref(), &search_path, limit, &turn.cwd).await?;
+        let search_results = run_rg_search(
+            pattern,
+            include.as_deref(),
+            &search_path,
+            limit,
+            &turn.sandbox_deny_read,
+            &turn.cwd,
+        )
+        .await?;
 
         if search_results.is_empty() {
             Ok(ToolOutput::Function {
@@ -112,6 +127,7 @@ async fn run_rg_search(
     include: Option<&str>,
     search_path: &Path,
     limit: usize,
+    deny_read: &DenyRead,
     cwd: &Path,
 ) -> Result<Vec<String>, FunctionCallError> {
     let mut command = Command::new("rg");
@@ -141,7 +157,7 @@ async fn run_rg_search(
         })?;
 
     match output.status.code() {
-        Some(0) => Ok(parse_results(&output.stdout, limit)),
+        Some(0) => Ok(parse_results(&output.stdout, limit, deny_read, cwd)),
         Some(1) => Ok(Vec::new()),
         _ => {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -152,14 +168,15 @@ async fn run_rg_search(
     }
 }
 
-fn parse_results(stdout: &[u8], limit: usize) -> Vec<String> {
+/// Collects up to `limit` matching paths, skipping any hidden by `deny_read`.
+fn parse_results(stdout: &[u8], limit: usize, deny_read: &DenyRead, cwd: &Path) -> Vec<String> {
     let mut results = Vec::new();
     for line in stdout.split(|byte| *byte == b'\n') {
         if line.is_empty() {
             continue;
         }
         if let Ok(text) = std::str::from_utf8(line) {
-            if text.is_empty() {
+            if text.is_empty() || deny_read.is_denied(Path::new(text), cwd) {
                 continue;
             }
             results.push(text.to_string());
@@ -180,7 +197,7 @@ mod tests {
     #[test]
     fn parses_basic_results() {
         let stdout = b"/tmp/file_a.rs\n/tmp/file_b.rs\n";
-        let parsed = parse_results(stdout, 10);
+        let parsed = parse_results(stdout, 10, &DenyRead::default(), Path::new("/"));
         assert_eq!(
             parsed,
             vec!["/tmp/file_a.rs".to_string(), "/tmp/file_b.rs".to_string()]
@@ -190,13 +207,25 @@ mod tests {
     #[test]
     fn parse_truncates_after_limit() {
         let stdout = b"/tmp/file_a.rs\n/tmp/file_b.rs\n/tmp/file_c.rs\n";
-        let parsed = parse_results(stdout, 2);
+        let parsed = parse_results(stdout, 2, &DenyRead::default(), Path::new("/"));
         assert_eq!(
             parsed,
             vec!["/tmp/file_a.rs".to_string(), "/tmp/file_b.rs".to_string()]
         );
     }
 
+    #[test]
+    fn parse_skips_denied_paths_before_limit() {
+        let stdout = b"src/.env\nsrc/main.rs\nsecrets/key.pem\nsrc/lib.rs\n";
+        let deny_read = DenyRead::new(vec!["src/.env".to_string(), "secrets/".to_string()])
+            .expect("valid patterns");
+        let parsed = parse_results(stdout, 2, &deny_read, Path::new("/workspace"));
+        assert_eq!(
+            parsed,
+            vec!["src/main.rs".to_string(), "src/lib.rs".to_string()]
+        );
+    }
+
     #[tokio::test]
     async fn run_search_returns_results() -> anyhow::Result<()> {
         if !rg_available() {
@@ -208,7 +237,7 @@ mod tests {
         std::fs::write(dir.join("match_two.txt"), "alpha delta").unwrap();
         std::fs::write(dir.join("other.txt"), "omega").unwrap();
 
-        let results = run_rg_search("alpha", None, dir, 10, dir).await?;
+        let results = run_rg_search("alpha", None, dir, 10, &DenyRead::default(), dir).await?;
         assert_eq!(results.len(), 2);
         assert!(results.iter().any(|path| path.ends_with("match_one.txt")));
         assert!(results.iter().any(|path| path.ends_with("match_two.txt")));
@@ -225,7 +254,8 @@ mod tests {
         std::fs::write(dir.join("match_one.rs"), "alpha beta gamma").unwrap();
         std::fs::write(dir.join("match_two.txt"), "alpha delta").unwrap();
 
-        let results = run_rg_search("alpha", Some("*.rs"), dir, 10, dir).await?;
+        let results =
+            run_rg_search("alpha", Some("*.rs"), dir, 10, &DenyRead::default(), dir).await?;
         assert_eq!(results.len(), 1);
         assert!(results.iter().all(|path| path.ends_with("match_one.rs")));
         Ok(())
@@ -242,7 +272,7 @@ mod tests {
         std::fs::write(dir.join("two.txt"), "alpha two").unwrap();
         std::fs::write(dir.join("three.txt"), "alpha three").unwrap();
 
-        let results = run_rg_search("alpha", None, dir, 2, dir).await?;
+        let results = run_rg_search("alpha", None, dir, 2, &DenyRead::default(), dir).await?;
         assert_eq!(results.len(), 2);
         Ok(())
     }
@@ -256,7 +286,7 @@ mod tests {
         let dir = temp.path();
         std::fs::write(dir.join("one.txt"), "omega").unwrap();
 
-        let results = run_rg_search("alpha", None, dir, 5, dir).await?;
+        let results = run_rg_search("alpha", None, dir, 5, &DenyRead::default(), dir).await?;
         assert!(results.is_empty());
         Ok(())
     }
@@ -10,6 +10,7 @@ use serde::Deserialize;
 use tokio::fs;
 
 use crate::function_tool::FunctionCallError;
+use crate::sandboxing::DenyRead;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -52,7 +53,7 @@ impl ToolHandler for ListDirHandler {
     }
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation { payload, turn, .. } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -97,7 +98,14 @@ impl ToolHandler for ListDirHandler {
             ));
         }
 
-        let entries = list_dir_slice(&path, offset, limit, depth).await?;
+        let deny_read = &turn.sandbox_deny_read;
+        if deny_read.is_denied(&path, &turn.cwd) {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "access to {dir_path} is denied by sandbox_deny_read"
+            )));
+        }
+
+        let entries = list_dir_slice(&path, offset, limit, depth, deny_read, &turn.cwd).await?;
         let mut output = Vec::with_capacity(entries.len() + 1);
         output.push(format!("Absolute path: {}", path.display()));
         output.extend(entries);
@@ -114,9 +122,11 @@ async fn list_dir_slice(
     offset: usize,
     limit: usize,
     depth: usize,
+    deny_read: &DenyRead,
+    cwd: &Path,
 ) -> Result<Vec<String>, FunctionCallError> {
     let mut entries = Vec::new();
-    collect_entries(path, Path::new(""), depth, &mut entries).await?;
+    collect_entries(path, Path::new(""), depth, deny_read, cwd, &mut entries).await?;
 
     if entries.is_empty() {
         return Ok(Vec::new());
@@ -152,6 +162,8 @@ async fn collect_entries(
     dir_path: &Path,
     relative_prefix: &Path,
     depth: usize,
+    deny_read: &DenyRead,
+    cwd: &Path,
     entries: &mut Vec<DirEntry>,
 ) -> Result<(), FunctionCallError> {
     let mut queue = VecDeque::new();
@@ -167,6 +179,10 @@ async fn collect_entries(
         while let Some(entry) = read_dir.next_entry().await.map_err(|err| {
             FunctionCallError::RespondToModel(format!("failed to read directory: {err}"))
         })? {
+            // Denied entries are omitted entirely so their names do not leak.
+            if deny_read.is_denied(&entry.path(), cwd) {
+                continue;
+            }
             let file_type = entry.file_type().await.map_err(|err| {
                 FunctionCallError::RespondToModel(format!("failed to inspect entry: {err}"))
             })?;
@@ -306,7 +322,7 @@ mod tests {
             symlink(dir_path.join("entry.txt"), &link_path).expect("create symlink");
         }
 
-        let entries = list_dir_slice(dir_path, 1, 20, 3)
+        let entries = list_dir_slice(dir_path, 1, 20, 3, &DenyRead::default(), dir_path)
             .await
             .expect("list directory");
 
@@ -340,7 +356,7 @@ mod tests {
             .await
             .expect("create sub dir");
 
-        let err = list_dir_slice(dir_path, 10, 1, 2)
+        let err = list_dir_slice(dir_path, 10, 1, 2, &DenyRead::default(), dir_path)
             .await
             .expect_err("offset exceeds entries");
         assert_eq!(
@@ -367,7 +383,7 @@ mod tests {
             .await
             .expect("write deeper");
 
-        let entries_depth_one = list_dir_slice(dir_path, 1, 10, 1)
+        let entries_depth_one = list_dir_slice(dir_path, 1, 10, 1, &DenyRead::default(), dir_path)
             .await
             .expect("list depth 1");
         assert_eq!(
@@ -375,7 +391,7 @@ mod tests {
             vec!["nested/".to_string(), "root.txt".to_string(),]
         );
 
-        let entries_depth_two = list_dir_slice(dir_path, 1, 20, 2)
+        let entries_depth_two = list_dir_slice(dir_path, 1, 20, 2, &DenyRead::default(), dir_path)
             .await
             .expect("list depth 2");
         assert_eq!(
@@ -388,9 +404,10 @@ mod tests {
             ]
         );
 
-        let entries_depth_three = list_dir_slice(dir_path, 1, 30, 3)
-            .await
-            .expect("list depth 3");
+        let entries_depth_three =
+            list_dir_slice(dir_path, 1, 30, 3, &DenyRead::default(), dir_path)
+                .await
+                .expect("list depth 3");
         assert_eq!(
             entries_depth_three,
             vec![
@@ -420,7 +437,7 @@ mod tests {
             .await
             .expect("write b child");
 
-        let first_page = list_dir_slice(dir_path, 1, 2, 2)
+        let first_page = list_dir_slice(dir_path, 1, 2, 2, &DenyRead::default(), dir_path)
             .await
             .expect("list page one");
         assert_eq!(
@@ -432,7 +449,7 @@ mod tests {
             ]
         );
 
-        let second_page = list_dir_slice(dir_path, 3, 2, 2)
+        let second_page = list_dir_slice(dir_path, 3, 2, 2, &DenyRead::default(), dir_path)
             .await
             .expect("list page two");
         assert_eq!(
@@ -455,7 +472,7 @@ mod tests {
             .await
             .expect("write gamma");
 
-        let entries = list_dir_slice(dir_path, 2, usize::MAX, 1)
+        let entries = list_dir_slice(dir_path, 2, usize::MAX, 1, &DenyRead::default(), dir_path)
             .await
             .expect("list without overflow");
         assert_eq!(
@@ -476,7 +493,7 @@ mod tests {
                 .expect("write file");
         }
 
-        let entries = list_dir_slice(dir_path, 1, 25, 1)
+        let entries = list_dir_slice(dir_path, 1, 25, 1, &DenyRead::default(), dir_path)
             .await
             .expect("list directory");
         assert_eq!(entries.len(), 26);
@@ -486,6 +503,26 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn omits_denied_entries() -> anyhow::Result<()> {
+        let temp = tempdir()?;
+        let dir_path = temp.path();
+        let secrets = dir_path.join("secrets");
+        tokio::fs::create_dir(&secrets).await?;
+        tokio::fs::write(secrets.join("token"), b"token").await?;
+        tokio::fs::write(dir_path.join(".env"), b"TOKEN=1").await?;
+        tokio::fs::write(dir_path.join("main.rs"), b"fn main() {}").await?;
+
+        let deny_read = DenyRead::new(vec![
+            dir_path.join(".env").display().to_string(),
+            secrets.display().to_string(),
+        ])?;
+        let entries = list_dir_slice(dir_path, 1, 10, 2, &deny_read, dir_path).await?;
+        assert_eq!(entries, vec!["main.rs".to_string()]);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn truncation_respects_sorted_order() -> anyhow::Result<()> {
         let temp = tempdir()?;
@@ -498,7 +535,8 @@ mod tests {
         tokio::fs::write(nested.join("child.txt"), b"child").await?;
         tokio::fs::write(deeper.join("grandchild.txt"), b"deep").await?;
 
-        let entries_depth_three = list_dir_slice(dir_path, 1, 3, 3).await?;
+        let entries_depth_three =
+            list_dir_slice(dir_path, 1, 3, 3, &DenyRead::default(), dir_path).await?;
         assert_eq!(
             entries_depth_three,
             vec![
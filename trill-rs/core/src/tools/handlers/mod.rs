@@ -34,6 +34,9 @@ pub use test_sync::TestSyncHandler;
 pub use unified_exec::UnifiedExecHandler;
 pub use view_image::ViewImageHandler;
 pub use web_search::WebSearchHandler;
+pub(crate) use web_search::WebCache;
+pub(crate) use web_search::WebFetcher;
+pub(crate) use web_search::WebPageCache;
 
 fn parse_arguments<T>(arguments: &str) -> Result<T, FunctionCallError>
 where
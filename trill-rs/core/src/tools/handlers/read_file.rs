@@ -98,7 +98,7 @@ impl ToolHandler for ReadFileHandler {
     }
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation { payload, turn, .. } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -138,6 +138,12 @@ impl ToolHandler for ReadFileHandler {
             ));
         }
 
+        if turn.sandbox_deny_read.is_denied(&path, &turn.cwd) {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "access to {file_path} is denied by sandbox_deny_read"
+            )));
+        }
+
         let collected = match mode {
             ReadMode::Slice => slice::read(&path, offset, limit).await?,
             ReadMode::Indentation => {
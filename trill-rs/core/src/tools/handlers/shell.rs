@@ -7,6 +7,7 @@ use crate::trill::TurnContext;
 use crate::exec::ExecParams;
 use crate::exec_env::create_env;
 use crate::exec_policy::ExecApprovalRequest;
+use crate::exec_policy::exec_policy_match_context;
 use crate::function_tool::FunctionCallError;
 use crate::is_safe_command::is_known_safe_command;
 use crate::protocol::ExecCommandSource;
@@ -290,6 +291,11 @@ impl ShellHandler {
                 sandbox_policy: &turn.sandbox_policy,
                 sandbox_permissions: exec_params.sandbox_permissions,
                 prefix_rule,
+                match_context: exec_policy_match_context(
+                    &exec_params.cwd,
+                    &turn.sandbox_policy,
+                    &exec_params.env,
+                ),
             })
             .await;
 
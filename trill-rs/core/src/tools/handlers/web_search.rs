@@ -1,43 +1,101 @@
+mod backend;
+mod document;
+mod fetch;
+mod find;
+mod html;
+mod page_cache;
+mod pages;
+mod pdf;
+mod web_cache;
+
 use async_trait::async_trait;
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use serde_json::json;
-use std::time::Duration;
+use futures::FutureExt;
+use futures::StreamExt;
+use futures::future::BoxFuture;
+use reqwest::header::ACCEPT;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::header::HeaderMap;
+use reqwest::header::HeaderValue;
+use reqwest::header::IF_MODIFIED_SINCE;
+use reqwest::header::IF_NONE_MATCH;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use trill_network_policy::reasons::blocked_message;
 
+use crate::config::types::WebSearchBackendConfig;
+use crate::config::types::WebSearchTimeRange;
+use crate::exec_policy::ToolRuleAmendment;
 use crate::function_tool::FunctionCallError;
+use crate::protocol::EventMsg;
+use crate::protocol::WarningEvent;
+use crate::trill::Session;
+use crate::trill::TurnContext;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
 use crate::tools::handlers::parse_arguments;
+use crate::tools::orchestrator::ToolOrchestrator;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
+use backend::Infobox;
+use backend::MAX_NUM_RESULTS;
+use backend::RankedList;
+use backend::SearchBackend;
+use backend::SearchOptions;
+use backend::SearchResults;
+use backend::build_backends;
+use backend::merge_results;
+use document::DocumentKind;
+use document::PageDocument;
+use document::convert_document;
+use fetch::CheckHop;
+use fetch::FetchError;
+pub(crate) use fetch::WebFetcher;
+use find::DEFAULT_CONTEXT_LINES;
+use find::MAX_MATCHES;
+use find::MatchBlock;
+use find::compile_pattern;
+use find::find_in_content;
+pub(crate) use page_cache::WebPageCache;
+use pages::page_bytes_for_budget;
+use pages::split_pages;
+use url::Url;
+use web_cache::CachePolicy;
+use web_cache::CachedPage;
+pub(crate) use web_cache::WebCache;
 
 /// Default SearXNG URL - can be overridden via config
 const DEFAULT_SEARXNG_URL: &str = "http://127.0.0.1:8080";
-const DEFAULT_TIMEOUT_SECS: u64 = 30;
 const DEFAULT_NUM_RESULTS: usize = 10;
+/// Backend requests in flight at once when fanning out several queries.
+const MAX_CONCURRENT_SEARCHES: usize = 4;
+/// Upper bound on the size of a fetched page body.
+const MAX_PAGE_BYTES: usize = 10 * 1024 * 1024;
+const PAGE_ACCEPT_HEADER: &str =
+    "text/html,application/xhtml+xml,application/pdf,application/json,text/plain;q=0.9,*/*;q=0.8";
 
 pub struct WebSearchHandler {
-    client: Client,
-    searxng_url: String,
+    backends: Vec<Arc<dyn SearchBackend>>,
 }
 
 impl Default for WebSearchHandler {
     fn default() -> Self {
-        Self::new(DEFAULT_SEARXNG_URL.to_string())
+        Self::new(&BTreeMap::from([(
+            "searxng".to_string(),
+            WebSearchBackendConfig::searxng(DEFAULT_SEARXNG_URL),
+        )]))
     }
 }
 
 impl WebSearchHandler {
-    pub fn new(searxng_url: String) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
-            .build()
-            .expect("Failed to create HTTP client");
-
+    pub fn new(backends: &BTreeMap<String, WebSearchBackendConfig>) -> Self {
         Self {
-            client,
-            searxng_url,
+            backends: build_backends(backends),
         }
     }
 }
@@ -61,32 +119,72 @@ struct WebSearchArgs {
     #[serde(default)]
     pattern: Option<String>,
 
+    /// Treat `pattern` as a regular expression (for FindInPage action)
+    #[serde(default)]
+    regex: Option<bool>,
+
+    /// Lines of context around each match (for FindInPage action)
+    #[serde(default)]
+    context_lines: Option<usize>,
+
+    /// 1-based page to read (for OpenPage action) or page of results (for
+    /// Search action)
+    #[serde(default)]
+    page: Option<usize>,
+
+    /// `next_cursor` from a previous OpenPage response
+    #[serde(default)]
+    cursor: Option<String>,
+
     /// Action type hint (auto-detected if not provided)
     #[serde(default)]
     action: Option<String>,
-}
 
-/// SearXNG search result
-#[derive(Debug, Deserialize)]
-struct SearxngResult {
-    title: Option<String>,
-    url: Option<String>,
-    content: Option<String>,
-    engine: Option<String>,
+    /// Search categories such as `news` or `it` (for Search action)
     #[serde(default)]
-    engines: Vec<String>,
-    score: Option<f64>,
-    category: Option<String>,
-    #[serde(rename = "publishedDate")]
-    published_date: Option<String>,
-}
+    categories: Option<OneOrMany>,
 
-/// SearXNG API response
-#[derive(Debug, Deserialize)]
-struct SearxngResponse {
-    results: Vec<SearxngResult>,
+    /// Search engines to query (for Search action)
     #[serde(default)]
-    query: String,
+    engines: Option<OneOrMany>,
+
+    /// Result language such as `en` or `de-CH` (for Search action)
+    #[serde(default)]
+    language: Option<String>,
+
+    /// Only return results from the last day, week, month or year (for
+    /// Search action)
+    #[serde(default)]
+    time_range: Option<WebSearchTimeRange>,
+
+    /// Results to return per query (for Search action)
+    #[serde(default)]
+    num_results: Option<usize>,
+}
+
+/// A list argument that models sometimes send as a single (possibly
+/// comma-separated) string.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    fn into_vec(self) -> Vec<String> {
+        let items = match self {
+            OneOrMany::One(item) => vec![item],
+            OneOrMany::Many(items) => items,
+        };
+        items
+            .iter()
+            .flat_map(|item| item.split(','))
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
 }
 
 /// Web search result item returned to model
@@ -105,6 +203,12 @@ struct WebSearchResultItem {
     category: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     published_date: Option<String>,
+    /// Backends that returned this result; only set when several are configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backends: Option<Vec<String>>,
+    /// Queries that returned this result; only set for multi-query searches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    matched_queries: Option<Vec<String>>,
 }
 
 /// WebSearchCall response format
@@ -114,9 +218,60 @@ struct WebSearchCallResponse {
     action: WebSearchActionResponse,
     results: Option<Vec<WebSearchResultItem>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_type: Option<DocumentKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     page_content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    matches: Option<Vec<String>>,
+    page: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_pages: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    matches: Option<Vec<MatchBlock>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_matches: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    answers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    infoboxes: Option<Vec<Infobox>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suggestions: Option<Vec<String>>,
+}
+
+/// One backend's answer to one query: `(query, backend name, result)`.
+type SearchResponse = (String, String, Result<SearchResults, String>);
+
+/// Merged output of a search across queries and backends.
+#[derive(Debug, Default)]
+struct SearchOutput {
+    results: Vec<WebSearchResultItem>,
+    answers: Vec<String>,
+    infoboxes: Vec<Infobox>,
+    suggestions: Vec<String>,
+}
+
+impl WebSearchCallResponse {
+    fn completed(action: WebSearchActionResponse) -> Self {
+        Self {
+            status: "completed".to_string(),
+            action,
+            results: None,
+            title: None,
+            content_type: None,
+            page_content: None,
+            page: None,
+            total_pages: None,
+            next_cursor: None,
+            matches: None,
+            total_matches: None,
+            answers: None,
+            infoboxes: None,
+            suggestions: None,
+        }
+    }
 }
 
 /// WebSearchAction in response
@@ -131,6 +286,8 @@ enum WebSearchActionResponse {
     },
     OpenPage {
         url: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        page: Option<usize>,
     },
     FindInPage {
         url: String,
@@ -147,9 +304,9 @@ impl WebSearchHandler {
                 "search" => Ok(WebSearchActionType::Search),
                 "open_page" | "openpage" => Ok(WebSearchActionType::OpenPage),
                 "find_in_page" | "findinpage" => Ok(WebSearchActionType::FindInPage),
-                _ => Err(FunctionCallError::RespondToModel(
-                    format!("Unknown action type: {}", action)
-                )),
+                _ => Err(FunctionCallError::RespondToModel(format!(
+                    "Unknown action type: {action}"
+                ))),
             };
         }
 
@@ -167,102 +324,434 @@ impl WebSearchHandler {
         }
     }
 
-    /// Execute a search via SearXNG
-    async fn execute_search(&self, query: &str) -> Result<Vec<WebSearchResultItem>, FunctionCallError> {
-        let search_url = format!("{}/search", self.searxng_url);
+    /// Run every query against every configured backend, at most
+    /// [`MAX_CONCURRENT_SEARCHES`] requests at a time, and fuse the results.
+    /// Backend failures are tolerated as long as some request succeeds;
+    /// options a backend rejects fail the whole call so the model can
+    /// correct them.
+    async fn execute_search(
+        &self,
+        queries: &[String],
+        options: &SearchOptions,
+        cache: &WebCache,
+        policy: CachePolicy,
+    ) -> Result<SearchOutput, FunctionCallError> {
+        if self.backends.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "No web search backends are configured".to_string(),
+            ));
+        }
 
-        let response = self.client
-            .get(&search_url)
-            .query(&[("q", query), ("format", "json")])
-            .send()
+        let validations = self.backends.iter().map(|backend| async move {
+            (backend.name().to_string(), backend.validate(options).await)
+        });
+        let invalid: Vec<String> = futures::future::join_all(validations)
             .await
-            .map_err(|e| FunctionCallError::RespondToModel(
-                format!("SearXNG request failed: {}", e)
-            ))?;
+            .into_iter()
+            .filter_map(|(name, result)| result.err().map(|err| format!("{name}: {err}")))
+            .collect();
+        if !invalid.is_empty() {
+            return Err(FunctionCallError::RespondToModel(invalid.join("; ")));
+        }
 
-        if !response.status().is_success() {
-            return Err(FunctionCallError::RespondToModel(
-                format!("SearXNG returned error status: {}", response.status())
-            ));
+        let mut searches: Vec<BoxFuture<'static, SearchResponse>> = Vec::new();
+        let mut from_network = Vec::new();
+        for query in queries {
+            for backend in &self.backends {
+                let name = backend.name().to_string();
+                match cache.search_results(&name, query, options, policy) {
+                    Ok(None) => {}
+                    cached => {
+                        let result = cached.map(Option::unwrap_or_default);
+                        searches.push(Box::pin(futures::future::ready((
+                            query.clone(),
+                            name,
+                            result,
+                        ))));
+                        from_network.push(false);
+                        continue;
+                    }
+                }
+                let query = query.clone();
+                let backend = Arc::clone(backend);
+                let options = options.clone();
+                searches.push(Box::pin(async move {
+                    let result = backend.search(&query, &options).await;
+                    (query, name, result)
+                }));
+                from_network.push(true);
+            }
+        }
+        // `buffered` keeps completion order deterministic for the merge.
+        let responses: Vec<SearchResponse> = futures::stream::iter(searches)
+            .buffered(MAX_CONCURRENT_SEARCHES)
+            .collect()
+            .await;
+        for ((query, name, result), fetched) in responses.iter().zip(from_network) {
+            if fetched && let Ok(results) = result {
+                cache.store_search_results(name, query, options, results);
+            }
         }
 
-        let searxng_response: SearxngResponse = response.json().await
-            .map_err(|e| FunctionCallError::RespondToModel(
-                format!("Failed to parse SearXNG response: {}", e)
-            ))?;
+        let mut output = SearchOutput::default();
+        let mut ranked = Vec::new();
+        let mut errors = Vec::new();
+        for (query, name, result) in responses {
+            match result {
+                Ok(results) => {
+                    push_unique(&mut output.answers, results.answers);
+                    push_unique(&mut output.suggestions, results.suggestions);
+                    for infobox in results.infoboxes {
+                        if !output
+                            .infoboxes
+                            .iter()
+                            .any(|existing| existing.title == infobox.title)
+                        {
+                            output.infoboxes.push(infobox);
+                        }
+                    }
+                    ranked.push(RankedList {
+                        backend: name,
+                        query,
+                        hits: results.hits,
+                    });
+                }
+                Err(err) => {
+                    tracing::warn!("web_search backend `{name}` failed for '{query}': {err}");
+                    let error = format!("{name}: {err}");
+                    if !errors.contains(&error) {
+                        errors.push(error);
+                    }
+                }
+            }
+        }
+        if ranked.is_empty() {
+            return Err(FunctionCallError::RespondToModel(errors.join("; ")));
+        }
 
-        let results: Vec<WebSearchResultItem> = searxng_response.results
+        let show_backends = self.backends.len() > 1;
+        let show_queries = queries.len() > 1;
+        let per_query = options.num_results.unwrap_or(DEFAULT_NUM_RESULTS);
+        output.results = merge_results(ranked)
             .into_iter()
-            .take(DEFAULT_NUM_RESULTS)
-            .map(|r| WebSearchResultItem {
-                title: r.title.unwrap_or_default(),
-                url: r.url.unwrap_or_default(),
-                snippet: r.content.unwrap_or_default(),
-                engine: r.engine,
-                engines: if r.engines.is_empty() { None } else { Some(r.engines) },
-                score: r.score,
-                category: r.category,
-                published_date: r.published_date,
+            .take(per_query * queries.len())
+            .map(|merged| {
+                let hit = merged.hit;
+                WebSearchResultItem {
+                    title: hit.title,
+                    url: hit.url,
+                    snippet: hit.snippet,
+                    engine: hit.engines.first().cloned(),
+                    engines: (hit.engines.len() > 1).then_some(hit.engines),
+                    score: hit.score,
+                    category: hit.category,
+                    published_date: hit.published_date,
+                    backends: show_backends.then_some(merged.backends),
+                    matched_queries: show_queries.then_some(merged.queries),
+                }
             })
             .collect();
-
-        Ok(results)
+        Ok(output)
     }
 
-    /// Fetch a page and return its content
-    async fn execute_open_page(&self, url: &str) -> Result<String, FunctionCallError> {
-        let response = self.client
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| FunctionCallError::RespondToModel(
-                format!("Failed to fetch page: {}", e)
-            ))?;
+    /// Fetch a page and convert it to readable text (Markdown for HTML),
+    /// going through the on-disk cache as `policy` allows. The host policy and
+    /// `check_hop` are applied to every URL involved, including those of
+    /// cached pages. Returns the document with the URL that served it.
+    async fn fetch_document(
+        &self,
+        url: &str,
+        fetcher: &WebFetcher,
+        cache: &WebCache,
+        policy: CachePolicy,
+        check_hop: &CheckHop<'_>,
+    ) -> Result<(PageDocument, Url), FetchError> {
+        fetcher.check_url(url, check_hop).await?;
+        let cached = cache.page(url, policy);
+        // The cached body may have been served by a redirect target.
+        if let Some(final_url) = cached.as_ref().and_then(|page| page.final_url.as_deref()) {
+            fetcher.check_url(final_url, check_hop).await?;
+        }
+        match cached {
+            Some(page) if page.fresh => return Ok(convert_cached_page(url, page)?),
+            None if policy == CachePolicy::Offline => {
+                return Err(FetchError::Failed(format!(
+                    "{url} is not in the web cache and the web search cache is offline"
+                )));
+            }
+            _ => {}
+        }
+
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static(PAGE_ACCEPT_HEADER));
+        if let Some(page) = &cached {
+            let validators = [
+                (IF_NONE_MATCH, &page.validators.etag),
+                (IF_MODIFIED_SINCE, &page.validators.last_modified),
+            ];
+            for (name, value) in validators {
+                if let Some(value) = value
+                    .as_deref()
+                    .and_then(|value| HeaderValue::from_str(value).ok())
+                {
+                    headers.insert(name, value);
+                }
+            }
+        }
+        let mut response = fetcher.get(url, headers, check_hop).await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED
+            && let Some(page) = cached
+        {
+            cache.refresh_page(url);
+            return Ok(convert_cached_page(url, page)?);
+        }
 
         if !response.status().is_success() {
-            return Err(FunctionCallError::RespondToModel(
-                format!("Page returned error status: {}", response.status())
-            ));
+            return Err(FetchError::Failed(format!(
+                "Page returned error status: {}",
+                response.status()
+            )));
         }
 
-        let content = response.text().await
-            .map_err(|e| FunctionCallError::RespondToModel(
-                format!("Failed to read page content: {}", e)
-            ))?;
+        let final_url = response.url().clone();
+        let headers = response.headers().clone();
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
 
-        // Truncate very long content
-        const MAX_CONTENT_LENGTH: usize = 50000;
-        if content.len() > MAX_CONTENT_LENGTH {
-            Ok(format!("{}...\n[Content truncated at {} characters]",
-                &content[..MAX_CONTENT_LENGTH], MAX_CONTENT_LENGTH))
-        } else {
-            Ok(content)
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| FetchError::Failed(format!("Failed to read page content: {e}")))?
+        {
+            let remaining = MAX_PAGE_BYTES - body.len();
+            if chunk.len() >= remaining {
+                body.extend_from_slice(&chunk[..remaining]);
+                tracing::debug!("page body for {url} exceeded {MAX_PAGE_BYTES} bytes; truncating");
+                break;
+            }
+            body.extend_from_slice(&chunk);
         }
+
+        cache.store_page(
+            url,
+            final_url.as_str(),
+            content_type.as_deref(),
+            &headers,
+            &body,
+        );
+        let document = convert_document(&final_url, content_type.as_deref(), &body)?;
+        Ok((document, final_url))
     }
 
-    /// Find pattern in page content
-    async fn execute_find_in_page(&self, url: &str, pattern: &str) -> Result<Vec<String>, FunctionCallError> {
-        let content = self.execute_open_page(url).await?;
+    /// Returns the converted document for `url`, fetching it only when the
+    /// session cache has no fresh copy. Session cache hits are checked again,
+    /// along with the URL that served them. Fetches (and cache hits) refused
+    /// by `[web_search.policy]` are recorded by the fetcher and surfaced to
+    /// the user as warnings.
+    async fn load_document(
+        &self,
+        session: &Session,
+        turn: &TurnContext,
+        call_id: &str,
+        policy: CachePolicy,
+        url: &str,
+    ) -> Result<Arc<PageDocument>, FunctionCallError> {
+        let services = &session.services;
+        // `url_rule` applies to every redirect hop and to cached pages. Hosts
+        // already approved for this call are not asked about again.
+        let approved_hosts = Mutex::new(Vec::<String>::new());
+        let approved_hosts = &approved_hosts;
+        let check_url_rule = |hop: &Url| {
+            let hop = hop.clone();
+            async move {
+                let Some(host) = hop.host_str() else {
+                    return Ok(());
+                };
+                if approved_hosts
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .iter()
+                    .any(|approved| approved == host)
+                {
+                    return Ok(());
+                }
+                if let Some(requirement) = services
+                    .exec_policy
+                    .url_requirement(host, turn.approval_policy)
+                {
+                    ToolOrchestrator::enforce_policy(
+                        session,
+                        turn,
+                        call_id,
+                        "web_search",
+                        requirement,
+                        Some(ToolRuleAmendment::Url {
+                            host: host.to_string(),
+                        }),
+                    )
+                    .await
+                    .map_err(|reason| {
+                        FetchError::Failed(format!("Refused to fetch {hop}: {reason}"))
+                    })?;
+                }
+                approved_hosts
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push(host.to_string());
+                Ok(())
+            }
+            .boxed()
+        };
+        let fetcher = &services.web_fetcher;
+        let fetched = match services.web_page_cache.get(url) {
+            Some((document, final_url)) => {
+                // The page may have been served by a redirect target.
+                let checked = async {
+                    fetcher.check_url(url, &check_url_rule).await?;
+                    if final_url.as_str() != url {
+                        fetcher
+                            .check_url(final_url.as_str(), &check_url_rule)
+                            .await?;
+                    }
+                    Ok::<(), FetchError>(())
+                };
+                match checked.await {
+                    Ok(()) => return Ok(document),
+                    Err(err) => Err(err),
+                }
+            }
+            None => {
+                self.fetch_document(url, fetcher, &services.web_cache, policy, &check_url_rule)
+                    .await
+            }
+        };
+        let (document, final_url) = match fetched {
+            Ok((document, final_url)) => (Arc::new(document), final_url),
+            Err(FetchError::Failed(message)) => {
+                return Err(FunctionCallError::RespondToModel(message));
+            }
+            Err(FetchError::Blocked(blocked)) => {
+                // Calls share the fetcher, so this reports every block
+                // recorded since the last report, this one included.
+                for entry in fetcher.drain_blocked() {
+                    let message = format!(
+                        "{} Refused to fetch from {} (reason={}).",
+                        blocked_message(&entry.reason),
+                        entry.host,
+                        entry.reason
+                    );
+                    session
+                        .send_event(turn, EventMsg::Warning(WarningEvent { message }))
+                        .await;
+                }
+                let host = &blocked.host;
+                let reason = &blocked.reason;
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "{} Refused to fetch {url} (host={host}, reason={reason}).",
+                    blocked_message(reason)
+                )));
+            }
+        };
+        services
+            .web_page_cache
+            .insert(url.to_string(), final_url, Arc::clone(&document));
+        Ok(document)
+    }
+}
 
-        let mut matches = Vec::new();
-        let pattern_lower = pattern.to_lowercase();
+/// Trims the requested queries and drops empty and repeated ones.
+fn normalize_queries(queries: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for query in queries {
+        let query = query.trim();
+        if !query.is_empty() && !normalized.iter().any(|seen| seen == query) {
+            normalized.push(query.to_string());
+        }
+    }
+    normalized
+}
 
-        for (i, line) in content.lines().enumerate() {
-            if line.to_lowercase().contains(&pattern_lower) {
-                // Include line number and context
-                matches.push(format!("Line {}: {}", i + 1, line.trim()));
-            }
+/// Converts a page body served from the on-disk cache.
+fn convert_cached_page(url: &str, page: CachedPage) -> Result<(PageDocument, Url), String> {
+    let final_url = page
+        .final_url
+        .as_deref()
+        .and_then(|final_url| Url::parse(final_url).ok())
+        .or_else(|| Url::parse(url).ok())
+        .ok_or_else(|| format!("Invalid URL: {url}"))?;
+    let document = convert_document(&final_url, page.content_type.as_deref(), &page.body)?;
+    Ok((document, final_url))
+}
+
+fn push_unique(target: &mut Vec<String>, items: Vec<String>) {
+    for item in items {
+        if !target.contains(&item) {
+            target.push(item);
         }
+    }
+}
 
-        // Limit number of matches returned
-        const MAX_MATCHES: usize = 50;
-        if matches.len() > MAX_MATCHES {
-            matches.truncate(MAX_MATCHES);
-            matches.push(format!("[... and more matches, showing first {}]", MAX_MATCHES));
+/// Collects the Search action options from the tool call.
+fn search_options(args: &WebSearchArgs) -> Result<SearchOptions, FunctionCallError> {
+    let page = args.page.unwrap_or(1);
+    if page == 0 {
+        return Err(FunctionCallError::RespondToModel(
+            "Result pages are numbered from 1".to_string(),
+        ));
+    }
+    let num_results = match args.num_results {
+        Some(0) => {
+            return Err(FunctionCallError::RespondToModel(
+                "num_results must be at least 1".to_string(),
+            ));
         }
+        Some(num_results) => Some(num_results.min(MAX_NUM_RESULTS)),
+        None => None,
+    };
+    Ok(SearchOptions {
+        categories: args
+            .categories
+            .clone()
+            .map(OneOrMany::into_vec)
+            .unwrap_or_default(),
+        engines: args
+            .engines
+            .clone()
+            .map(OneOrMany::into_vec)
+            .unwrap_or_default(),
+        language: args
+            .language
+            .as_deref()
+            .map(str::trim)
+            .filter(|language| !language.is_empty())
+            .map(str::to_string),
+        time_range: args.time_range,
+        page,
+        num_results,
+    })
+}
 
-        Ok(matches)
+/// Resolves the page requested through `cursor` or `page`, defaulting to the
+/// first page.
+fn requested_page(args: &WebSearchArgs) -> Result<usize, FunctionCallError> {
+    let page = match &args.cursor {
+        Some(cursor) => cursor.trim().parse::<usize>().map_err(|_| {
+            FunctionCallError::RespondToModel(format!(
+                "Invalid cursor `{cursor}`; pass the next_cursor value from a previous open_page response"
+            ))
+        })?,
+        None => args.page.unwrap_or(1),
+    };
+    if page == 0 {
+        return Err(FunctionCallError::RespondToModel(
+            "Pages are numbered from 1".to_string(),
+        ));
     }
+    Ok(page)
 }
 
 #[derive(Debug)]
@@ -280,9 +769,15 @@ impl ToolHandler for WebSearchHandler {
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
         let ToolInvocation {
+            session,
+            turn,
+            call_id,
             payload,
             ..
         } = invocation;
+        let web_cache = &session.services.web_cache;
+        let policy = web_cache.policy(turn.tools_config.web_search_mode);
+        let page_bytes = page_bytes_for_budget(turn.truncation_policy.byte_budget());
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -298,57 +793,72 @@ impl ToolHandler for WebSearchHandler {
 
         let response = match action_type {
             WebSearchActionType::Search => {
-                let queries: Vec<String> = if let Some(queries) = args.queries {
-                    queries
-                } else if let Some(query) = args.query {
-                    vec![query]
-                } else {
+                let options = search_options(&args)?;
+                let queries = normalize_queries(
+                    args.query
+                        .into_iter()
+                        .chain(args.queries.unwrap_or_default())
+                        .collect(),
+                );
+                if queries.is_empty() {
                     return Err(FunctionCallError::RespondToModel(
                         "Search action requires query or queries field".to_string()
                     ));
-                };
-
-                // Execute all queries and combine results
-                let mut all_results = Vec::new();
-                for query in &queries {
-                    match self.execute_search(query).await {
-                        Ok(results) => all_results.extend(results),
-                        Err(e) => {
-                            // Log error but continue with other queries
-                            tracing::warn!("Search query '{}' failed: {:?}", query, e);
-                        }
-                    }
                 }
 
+                let output = self
+                    .execute_search(&queries, &options, web_cache, policy)
+                    .await?;
+
                 let action = WebSearchActionResponse::Search {
                     query: if queries.len() == 1 { Some(queries[0].clone()) } else { None },
                     queries: if queries.len() > 1 { Some(queries) } else { None },
                 };
 
                 WebSearchCallResponse {
-                    status: "completed".to_string(),
-                    action,
-                    results: Some(all_results),
-                    page_content: None,
-                    matches: None,
+                    results: Some(output.results),
+                    page: (options.page > 1).then_some(options.page),
+                    answers: (!output.answers.is_empty()).then_some(output.answers),
+                    infoboxes: (!output.infoboxes.is_empty()).then_some(output.infoboxes),
+                    suggestions: (!output.suggestions.is_empty()).then_some(output.suggestions),
+                    ..WebSearchCallResponse::completed(action)
                 }
             }
 
             WebSearchActionType::OpenPage => {
+                let requested = requested_page(&args)?;
                 let url = args.url.ok_or_else(|| {
                     FunctionCallError::RespondToModel(
                         "OpenPage action requires url field".to_string()
                     )
                 })?;
 
-                let content = self.execute_open_page(&url).await?;
+                let document = self
+                    .load_document(&session, &turn, &call_id, policy, &url)
+                    .await?;
+                let pages = split_pages(&document.content, page_bytes);
+                let total_pages = pages.len().max(1);
+                if requested > total_pages {
+                    return Err(FunctionCallError::RespondToModel(format!(
+                        "Page {requested} is out of range; the document has {total_pages} page(s)"
+                    )));
+                }
+                let page_content = pages
+                    .get(requested - 1)
+                    .map(|range| document.content[range.clone()].to_string())
+                    .unwrap_or_default();
 
                 WebSearchCallResponse {
-                    status: "completed".to_string(),
-                    action: WebSearchActionResponse::OpenPage { url: url.clone() },
-                    results: None,
-                    page_content: Some(content),
-                    matches: None,
+                    title: document.title.clone(),
+                    content_type: Some(document.kind),
+                    page_content: Some(page_content),
+                    page: Some(requested),
+                    total_pages: Some(total_pages),
+                    next_cursor: (requested < total_pages).then(|| (requested + 1).to_string()),
+                    ..WebSearchCallResponse::completed(WebSearchActionResponse::OpenPage {
+                        url,
+                        page: Some(requested),
+                    })
                 }
             }
 
@@ -364,25 +874,43 @@ impl ToolHandler for WebSearchHandler {
                     )
                 })?;
 
-                let matches = self.execute_find_in_page(&url, &pattern).await?;
+                let regex = compile_pattern(&pattern, args.regex.unwrap_or(false))
+                    .map_err(FunctionCallError::RespondToModel)?;
+
+                let document = self
+                    .load_document(&session, &turn, &call_id, policy, &url)
+                    .await?;
+                let pages = split_pages(&document.content, page_bytes);
+                let found = find_in_content(
+                    &document.content,
+                    &pages,
+                    &regex,
+                    args.context_lines.unwrap_or(DEFAULT_CONTEXT_LINES),
+                );
+                if found.total_matches > MAX_MATCHES {
+                    tracing::debug!(
+                        "find_in_page for {url} matched {} lines; returning first {MAX_MATCHES}",
+                        found.total_matches
+                    );
+                }
 
                 WebSearchCallResponse {
-                    status: "completed".to_string(),
-                    action: WebSearchActionResponse::FindInPage {
-                        url: url.clone(),
-                        pattern: pattern.clone()
-                    },
-                    results: None,
-                    page_content: None,
-                    matches: Some(matches),
+                    title: document.title.clone(),
+                    content_type: Some(document.kind),
+                    total_pages: Some(pages.len().max(1)),
+                    matches: Some(found.blocks),
+                    total_matches: Some(found.total_matches),
+                    ..WebSearchCallResponse::completed(WebSearchActionResponse::FindInPage {
+                        url,
+                        pattern,
+                    })
                 }
             }
         };
 
-        let content = serde_json::to_string(&response)
-            .map_err(|e| FunctionCallError::RespondToModel(
-                format!("Failed to serialize response: {}", e)
-            ))?;
+        let content = serde_json::to_string(&response).map_err(|e| {
+            FunctionCallError::RespondToModel(format!("Failed to serialize response: {e}"))
+        })?;
 
         Ok(ToolOutput::Function {
             content,
@@ -395,6 +923,18 @@ impl ToolHandler for WebSearchHandler {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pretty_assertions::assert_eq;
+    use crate::config::types::WebSearchCacheConfig;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+    use trill_network_policy::NetworkPolicy;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::header;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
 
     #[test]
     fn test_determine_action_search() {
@@ -403,6 +943,15 @@ mod tests {
             queries: None,
             url: None,
             pattern: None,
+            regex: None,
+            context_lines: None,
+            page: None,
+            cursor: None,
+            categories: None,
+            engines: None,
+            language: None,
+            time_range: None,
+            num_results: None,
             action: None,
         };
 
@@ -417,6 +966,15 @@ mod tests {
             queries: None,
             url: Some("https://example.com".to_string()),
             pattern: None,
+            regex: None,
+            context_lines: None,
+            page: None,
+            cursor: None,
+            categories: None,
+            engines: None,
+            language: None,
+            time_range: None,
+            num_results: None,
             action: None,
         };
 
@@ -431,6 +989,15 @@ mod tests {
             queries: None,
             url: Some("https://example.com".to_string()),
             pattern: Some("search term".to_string()),
+            regex: None,
+            context_lines: None,
+            page: None,
+            cursor: None,
+            categories: None,
+            engines: None,
+            language: None,
+            time_range: None,
+            num_results: None,
             action: None,
         };
 
@@ -445,6 +1012,15 @@ mod tests {
             queries: None,
             url: Some("https://example.com".to_string()),
             pattern: Some("term".to_string()),
+            regex: None,
+            context_lines: None,
+            page: None,
+            cursor: None,
+            categories: None,
+            engines: None,
+            language: None,
+            time_range: None,
+            num_results: None,
             action: Some("search".to_string()),
         };
 
@@ -452,4 +1028,245 @@ mod tests {
         let action = WebSearchHandler::determine_action(&args).unwrap();
         assert!(matches!(action, WebSearchActionType::Search));
     }
+
+    #[test]
+    fn search_options_accept_strings_or_lists() {
+        let mut args: WebSearchArgs = serde_json::from_str(
+            r#"{"query":"rust","categories":"news, it","engines":["bing"],"language":" de ","time_range":"week","page":2,"num_results":500}"#,
+        )
+        .unwrap();
+
+        let options = search_options(&args).unwrap();
+        assert_eq!(
+            options,
+            SearchOptions {
+                categories: vec!["news".to_string(), "it".to_string()],
+                engines: vec!["bing".to_string()],
+                language: Some("de".to_string()),
+                time_range: Some(WebSearchTimeRange::Week),
+                page: 2,
+                num_results: Some(MAX_NUM_RESULTS),
+            }
+        );
+
+        args.page = Some(0);
+        assert!(search_options(&args).is_err());
+    }
+
+    /// Records the most searches it saw running at once.
+    struct SlowBackend {
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl SearchBackend for SlowBackend {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        async fn search(
+            &self,
+            query: &str,
+            _options: &SearchOptions,
+        ) -> Result<SearchResults, String> {
+            let running = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            let hit = |url: &str| backend::SearchHit {
+                title: url.to_string(),
+                url: url.to_string(),
+                ..Default::default()
+            };
+            Ok(vec![
+                hit(&format!("https://{query}.example/")),
+                hit("https://shared.example/"),
+            ]
+            .into())
+        }
+    }
+
+    #[tokio::test]
+    async fn fans_out_queries_with_bounded_concurrency() {
+        let backend = Arc::new(SlowBackend {
+            in_flight: AtomicUsize::new(0),
+            max_in_flight: AtomicUsize::new(0),
+        });
+        let handler = WebSearchHandler {
+            backends: vec![backend.clone()],
+        };
+        let queries: Vec<String> = ["a", "b", "c", "d", "e", "f"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache = test_cache(dir.path(), Duration::from_secs(60));
+
+        let output = handler
+            .execute_search(
+                &queries,
+                &SearchOptions::default(),
+                &cache,
+                CachePolicy::Live,
+            )
+            .await
+            .unwrap();
+
+        assert!(backend.max_in_flight.load(Ordering::SeqCst) > 1);
+        assert!(backend.max_in_flight.load(Ordering::SeqCst) <= MAX_CONCURRENT_SEARCHES);
+        assert_eq!(output.results[0].url, "https://shared.example/");
+        assert_eq!(output.results[0].matched_queries, Some(queries.clone()));
+        assert_eq!(output.results.len(), 7);
+        assert_eq!(
+            output.results[1].matched_queries,
+            Some(vec!["a".to_string()])
+        );
+    }
+
+    fn any_hop(_: &Url) -> BoxFuture<'static, Result<(), FetchError>> {
+        async { Ok(()) }.boxed()
+    }
+
+    fn test_cache(dir: &std::path::Path, ttl: Duration) -> WebCache {
+        WebCache::new(&WebSearchCacheConfig {
+            dir: dir.to_path_buf(),
+            ttl,
+            max_bytes: 1024 * 1024,
+            offline: false,
+        })
+    }
+
+    #[tokio::test]
+    async fn revalidates_stale_pages_with_etag() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/doc"))
+            .and(header("if-none-match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/doc"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("etag", "\"v1\"")
+                    .insert_header("content-type", "text/plain")
+                    .set_body_string("cached body"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let handler = WebSearchHandler::default();
+        let fetcher = WebFetcher::new(&NetworkPolicy {
+            allowed_domains: vec!["127.0.0.1".to_string()],
+            ..NetworkPolicy::default()
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("{}/doc", server.uri());
+
+        // The first fetch stores the page; with a zero TTL the second one
+        // revalidates and the server answers 304.
+        let stale = test_cache(dir.path(), Duration::ZERO);
+        for _ in 0..2 {
+            let (document, _) = handler
+                .fetch_document(&url, &fetcher, &stale, CachePolicy::Cached, &any_hop)
+                .await
+                .unwrap();
+            assert_eq!(document.content, "cached body");
+        }
+
+        // Fresh entries and offline reads never reach the server.
+        let fresh = test_cache(dir.path(), Duration::from_secs(60));
+        let (document, _) = handler
+            .fetch_document(&url, &fetcher, &fresh, CachePolicy::Cached, &any_hop)
+            .await
+            .unwrap();
+        assert_eq!(document.content, "cached body");
+        let (document, _) = handler
+            .fetch_document(&url, &fetcher, &stale, CachePolicy::Offline, &any_hop)
+            .await
+            .unwrap();
+        assert_eq!(document.content, "cached body");
+        assert!(
+            handler
+                .fetch_document(
+                    &format!("{url}/missing"),
+                    &fetcher,
+                    &stale,
+                    CachePolicy::Offline,
+                    &any_hop
+                )
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn cached_pages_are_checked_against_the_policy() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/doc"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/plain")
+                    .set_body_string("cached body"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let handler = WebSearchHandler::default();
+        let dir = tempfile::tempdir().unwrap();
+        let cache = test_cache(dir.path(), Duration::from_secs(60));
+        let url = format!("{}/doc", server.uri());
+        let allowing = WebFetcher::new(&NetworkPolicy {
+            allowed_domains: vec!["127.0.0.1".to_string()],
+            ..NetworkPolicy::default()
+        });
+        handler
+            .fetch_document(&url, &allowing, &cache, CachePolicy::Cached, &any_hop)
+            .await
+            .unwrap();
+
+        let denying = WebFetcher::new(&NetworkPolicy {
+            allowed_domains: vec!["127.0.0.1".to_string()],
+            denied_domains: vec!["127.0.0.1".to_string()],
+            ..NetworkPolicy::default()
+        });
+        for policy in [CachePolicy::Cached, CachePolicy::Offline] {
+            assert!(matches!(
+                handler
+                    .fetch_document(&url, &denying, &cache, policy, &any_hop)
+                    .await,
+                Err(FetchError::Blocked(_))
+            ));
+        }
+
+        // The caller's check applies to cached pages as well.
+        let refuse_all = |hop: &Url| {
+            let refused = FetchError::Failed(format!("Refused to fetch {hop}"));
+            async move { Err(refused) }.boxed()
+        };
+        assert!(matches!(
+            handler
+                .fetch_document(&url, &allowing, &cache, CachePolicy::Offline, &refuse_all)
+                .await,
+            Err(FetchError::Failed(_))
+        ));
+    }
+
+    #[test]
+    fn normalizes_queries() {
+        assert_eq!(
+            normalize_queries(vec![
+                " rust async ".to_string(),
+                String::new(),
+                "tokio".to_string(),
+                "rust async".to_string(),
+            ]),
+            vec!["rust async".to_string(), "tokio".to_string()]
+        );
+    }
 }
@@ -0,0 +1,444 @@
+//! Search backends behind the `search` action.
+//!
+//! Each `[web_search.backends.<name>]` entry becomes a [`SearchBackend`].
+//! Every query is sent to every backend and the ranked lists are combined
+//! with reciprocal-rank fusion, with duplicates (by normalized URL) folded
+//! together.
+
+mod elasticsearch;
+mod searxng;
+mod yacy;
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use regex_lite::Regex;
+use reqwest::Client;
+use serde::Deserialize;
+use serde::Serialize;
+use url::Url;
+
+use super::html::collapse_whitespace;
+use super::html::decode_entities;
+use crate::config::types::WebSearchBackendConfig;
+use crate::config::types::WebSearchBackendKind;
+use crate::config::types::WebSearchTimeRange;
+use crate::default_client::get_codex_user_agent;
+use elasticsearch::ElasticsearchBackend;
+use searxng::SearxngBackend;
+use yacy::YacyBackend;
+
+const DEFAULT_BACKEND_TIMEOUT_SECS: u64 = 30;
+
+/// Results requested from each backend unless `max_results` is set.
+pub(crate) const DEFAULT_BACKEND_RESULTS: usize = 10;
+
+/// Upper bound on `num_results` requested by the model.
+pub(crate) const MAX_NUM_RESULTS: usize = 50;
+
+/// Reciprocal-rank fusion constant: a hit at 1-based rank `r` in a list
+/// contributes `1 / (RRF_K + r)` to its fused score.
+const RRF_K: f64 = 60.0;
+
+/// Query parameters that only track the visitor and never change the page.
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "mc_cid", "mc_eid", "ref_src", "yclid"];
+
+static HTML_TAG: LazyLock<Regex> = LazyLock::new(|| {
+    #[expect(clippy::unwrap_used)]
+    Regex::new(r"<[^>]*>").unwrap()
+});
+
+/// One result as reported by a backend.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SearchHit {
+    pub(crate) title: String,
+    pub(crate) url: String,
+    pub(crate) snippet: String,
+    pub(crate) engines: Vec<String>,
+    pub(crate) score: Option<f64>,
+    pub(crate) category: Option<String>,
+    pub(crate) published_date: Option<String>,
+}
+
+/// A summary card for the query's main entity (SearXNG infobox).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Infobox {
+    pub(crate) title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) url: Option<String>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub(crate) content: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) attributes: Vec<InfoboxAttribute>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) links: Vec<InfoboxLink>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct InfoboxAttribute {
+    pub(crate) label: String,
+    pub(crate) value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct InfoboxLink {
+    pub(crate) title: String,
+    pub(crate) url: String,
+}
+
+/// Everything a backend returned for one query.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SearchResults {
+    pub(crate) hits: Vec<SearchHit>,
+    pub(crate) answers: Vec<String>,
+    pub(crate) infoboxes: Vec<Infobox>,
+    pub(crate) suggestions: Vec<String>,
+}
+
+impl From<Vec<SearchHit>> for SearchResults {
+    fn from(hits: Vec<SearchHit>) -> Self {
+        Self {
+            hits,
+            ..Default::default()
+        }
+    }
+}
+
+/// Per-call search options chosen by the model. Unset fields fall back to the
+/// backend's configured defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub(crate) struct SearchOptions {
+    pub(crate) categories: Vec<String>,
+    pub(crate) engines: Vec<String>,
+    pub(crate) language: Option<String>,
+    pub(crate) time_range: Option<WebSearchTimeRange>,
+    /// 1-based page of results.
+    pub(crate) page: usize,
+    pub(crate) num_results: Option<usize>,
+}
+
+impl SearchOptions {
+    /// Zero-based offset of the first result on the requested page.
+    pub(crate) fn offset(&self, page_size: usize) -> usize {
+        self.page.saturating_sub(1) * page_size
+    }
+}
+
+#[async_trait]
+pub(crate) trait SearchBackend: Send + Sync {
+    /// Name of the backend from config, reported alongside its results.
+    fn name(&self) -> &str;
+
+    /// Checks model-supplied options against what the backend supports,
+    /// returning a message for the model when they cannot be honored.
+    async fn validate(&self, _options: &SearchOptions) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Runs `query` and returns hits in the backend's ranking order. Errors
+    /// are human-readable and may be shown to the model.
+    async fn search(&self, query: &str, options: &SearchOptions) -> Result<SearchResults, String>;
+}
+
+/// Hits one backend returned for one query, in rank order.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RankedList {
+    pub(crate) backend: String,
+    pub(crate) query: String,
+    pub(crate) hits: Vec<SearchHit>,
+}
+
+/// A hit after merging, with every backend and query that returned it.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MergedHit {
+    pub(crate) hit: SearchHit,
+    pub(crate) backends: Vec<String>,
+    pub(crate) queries: Vec<String>,
+    /// Reciprocal-rank fusion score across all lists.
+    pub(crate) fused_score: f64,
+}
+
+pub(crate) fn build_backends(
+    configs: &BTreeMap<String, WebSearchBackendConfig>,
+) -> Vec<Arc<dyn SearchBackend>> {
+    configs
+        .iter()
+        .filter(|(_, config)| config.enabled)
+        .map(|(name, config)| build_backend(name, config))
+        .collect()
+}
+
+fn build_backend(name: &str, config: &WebSearchBackendConfig) -> Arc<dyn SearchBackend> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(
+            config.timeout_sec.unwrap_or(DEFAULT_BACKEND_TIMEOUT_SECS),
+        ))
+        .user_agent(get_codex_user_agent())
+        .build()
+        .unwrap_or_else(|_| Client::new());
+    let name = name.to_string();
+    match config.kind {
+        WebSearchBackendKind::Searxng => Arc::new(SearxngBackend::new(name, client, config)),
+        WebSearchBackendKind::Yacy => Arc::new(YacyBackend::new(name, client, config)),
+        WebSearchBackendKind::Elasticsearch => {
+            Arc::new(ElasticsearchBackend::new(name, client, config))
+        }
+    }
+}
+
+/// Logs options set on a backend that cannot honor them.
+fn warn_unsupported(name: &str, kind: &str, options: &[(&str, bool)]) {
+    for (option, is_set) in options {
+        if *is_set {
+            tracing::warn!("web_search backend `{name}` ({kind}) ignores `{option}`");
+        }
+    }
+}
+
+/// Fuses ranked lists (one per query and backend) with reciprocal-rank
+/// fusion and folds duplicates together. Hits are visited rank by rank across
+/// lists, so ties keep an interleaved order.
+pub(crate) fn merge_results(lists: Vec<RankedList>) -> Vec<MergedHit> {
+    let mut merged: Vec<MergedHit> = Vec::new();
+    let mut index_by_url: HashMap<String, usize> = HashMap::new();
+    let mut iterators: Vec<_> = lists
+        .into_iter()
+        .map(|list| (list.backend, list.query, list.hits.into_iter()))
+        .collect();
+    let mut rank = 0;
+    loop {
+        rank += 1;
+        let contribution = 1.0 / (RRF_K + rank as f64);
+        let mut progressed = false;
+        for (backend, query, hits) in &mut iterators {
+            let Some(hit) = hits.next() else {
+                continue;
+            };
+            progressed = true;
+            let key = normalize_url(&hit.url);
+            match index_by_url.get(&key) {
+                Some(index) => {
+                    let existing = &mut merged[*index];
+                    existing.fused_score += contribution;
+                    merge_into(existing, backend, query, hit);
+                }
+                None => {
+                    index_by_url.insert(key, merged.len());
+                    merged.push(MergedHit {
+                        hit,
+                        backends: vec![backend.clone()],
+                        queries: vec![query.clone()],
+                        fused_score: contribution,
+                    });
+                }
+            }
+        }
+        if !progressed {
+            break;
+        }
+    }
+    merged.sort_by(|a, b| b.fused_score.total_cmp(&a.fused_score));
+    merged
+}
+
+fn merge_into(existing: &mut MergedHit, backend: &str, query: &str, hit: SearchHit) {
+    if !existing.backends.iter().any(|name| name == backend) {
+        existing.backends.push(backend.to_string());
+    }
+    if !existing.queries.iter().any(|seen| seen == query) {
+        existing.queries.push(query.to_string());
+    }
+    for engine in hit.engines {
+        if !existing.hit.engines.contains(&engine) {
+            existing.hit.engines.push(engine);
+        }
+    }
+    let target = &mut existing.hit;
+    if target.title.is_empty() {
+        target.title = hit.title;
+    }
+    if target.snippet.len() < hit.snippet.len() {
+        target.snippet = hit.snippet;
+    }
+    if target.published_date.is_none() {
+        target.published_date = hit.published_date;
+    }
+    if target.category.is_none() {
+        target.category = hit.category;
+    }
+}
+
+/// Canonical form of a URL used to detect duplicates across backends: the
+/// scheme, a leading `www.`, the fragment, a trailing slash, tracking
+/// parameters and query parameter order are all ignored.
+pub(crate) fn normalize_url(raw: &str) -> String {
+    let Ok(url) = Url::parse(raw.trim()) else {
+        return raw.trim().to_ascii_lowercase();
+    };
+    let host = url.host_str().unwrap_or_default();
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    let port = url
+        .port()
+        .map(|port| format!(":{port}"))
+        .unwrap_or_default();
+    let path = url.path().trim_end_matches('/');
+    let mut params: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| !key.starts_with("utm_") && !TRACKING_PARAMS.contains(&key.as_ref()))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    params.sort();
+    let query = params
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join("&");
+    if query.is_empty() {
+        format!("{host}{port}{path}")
+    } else {
+        format!("{host}{port}{path}?{query}")
+    }
+}
+
+/// Removes markup (such as `<b>` highlighting) from backend-provided text.
+fn strip_html(text: &str) -> String {
+    collapse_whitespace(&decode_entities(&HTML_TAG.replace_all(text, " ")))
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn hit(url: &str, title: &str) -> SearchHit {
+        SearchHit {
+            title: title.to_string(),
+            url: url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn normalizes_equivalent_urls() {
+        let canonical = normalize_url("https://example.com/docs?a=1&b=2");
+        for variant in [
+            "http://www.example.com/docs/?b=2&a=1",
+            "https://EXAMPLE.com/docs?a=1&b=2#section",
+            "https://example.com/docs?utm_source=feed&a=1&b=2&fbclid=x",
+        ] {
+            assert_eq!(normalize_url(variant), canonical, "{variant}");
+        }
+        assert_ne!(
+            normalize_url("https://example.com/docs?a=2"),
+            normalize_url("https://example.com/docs?a=1")
+        );
+        assert_ne!(
+            normalize_url("https://example.com:8443/"),
+            normalize_url("https://example.com/")
+        );
+    }
+
+    fn list(backend: &str, query: &str, hits: Vec<SearchHit>) -> RankedList {
+        RankedList {
+            backend: backend.to_string(),
+            query: query.to_string(),
+            hits,
+        }
+    }
+
+    #[test]
+    fn merge_interleaves_by_rank_and_folds_duplicates() {
+        let mut first = hit("https://a.example/", "A");
+        first.engines = vec!["bing".to_string()];
+        let mut duplicate = hit("http://www.a.example", "A again");
+        duplicate.engines = vec!["bing".to_string(), "index".to_string()];
+        duplicate.snippet = "longer snippet".to_string();
+
+        let merged = merge_results(vec![
+            list("searx", "q", vec![first, hit("https://b.example/", "B")]),
+            list("docs", "q", vec![hit("https://c.example/", "C"), duplicate]),
+        ]);
+
+        let summary: Vec<(&str, Vec<String>)> = merged
+            .iter()
+            .map(|merged| (merged.hit.title.as_str(), merged.backends.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("A", vec!["searx".to_string(), "docs".to_string()]),
+                ("C", vec!["docs".to_string()]),
+                ("B", vec!["searx".to_string()]),
+            ]
+        );
+        assert_eq!(merged[0].hit.engines, vec!["bing", "index"]);
+        assert_eq!(merged[0].hit.snippet, "longer snippet");
+    }
+
+    #[test]
+    fn fuses_ranks_across_queries() {
+        let merged = merge_results(vec![
+            list(
+                "searx",
+                "rust async",
+                vec![
+                    hit("https://a.example/", "A"),
+                    hit("https://b.example/", "B"),
+                    hit("https://c.example/", "C"),
+                ],
+            ),
+            list(
+                "searx",
+                "tokio tutorial",
+                vec![
+                    hit("https://d.example/", "D"),
+                    hit("https://c.example/", "C"),
+                ],
+            ),
+            list(
+                "searx",
+                "async runtime",
+                vec![
+                    hit("https://c.example/", "C"),
+                    hit("https://b.example/", "B"),
+                ],
+            ),
+        ]);
+
+        let summary: Vec<(&str, Vec<&str>)> = merged
+            .iter()
+            .map(|merged| {
+                (
+                    merged.hit.title.as_str(),
+                    merged.queries.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("C", vec!["async runtime", "tokio tutorial", "rust async"]),
+                ("B", vec!["rust async", "async runtime"]),
+                ("A", vec!["rust async"]),
+                ("D", vec!["tokio tutorial"]),
+            ]
+        );
+        let expected = 1.0 / 61.0 + 1.0 / 62.0 + 1.0 / 63.0;
+        assert!((merged[0].fused_score - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn strips_highlight_markup() {
+        assert_eq!(
+            strip_html("The <b>quick</b>&nbsp;brown\n<em>fox</em> &amp; co"),
+            "The quick brown fox & co"
+        );
+    }
+}
@@ -0,0 +1,390 @@
+//! Elasticsearch (or OpenSearch) full-text search over a document index.
+
+use async_trait::async_trait;
+use reqwest::Client;
+use reqwest::header::AUTHORIZATION;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use serde_json::json;
+
+use super::DEFAULT_BACKEND_RESULTS;
+use super::MAX_NUM_RESULTS;
+use super::SearchBackend;
+use super::SearchHit;
+use super::SearchOptions;
+use super::SearchResults;
+use super::strip_html;
+use super::warn_unsupported;
+use crate::config::types::WebSearchBackendConfig;
+use crate::config::types::WebSearchTimeRange;
+
+const DEFAULT_TITLE_FIELD: &str = "title";
+const DEFAULT_URL_FIELD: &str = "url";
+const DEFAULT_CONTENT_FIELD: &str = "content";
+const SNIPPET_FRAGMENT_CHARS: usize = 240;
+
+pub(super) struct ElasticsearchBackend {
+    name: String,
+    client: Client,
+    search_url: String,
+    env_key: Option<String>,
+    fields: Vec<String>,
+    title_field: String,
+    url_field: String,
+    content_field: String,
+    date_field: Option<String>,
+    language_field: Option<String>,
+    category_field: Option<String>,
+    language: Option<String>,
+    time_range: Option<WebSearchTimeRange>,
+    categories: Vec<String>,
+    max_results: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    hits: Hits,
+}
+
+#[derive(Debug, Deserialize)]
+struct Hits {
+    #[serde(default)]
+    hits: Vec<Hit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Hit {
+    #[serde(rename = "_index")]
+    index: Option<String>,
+    #[serde(rename = "_score")]
+    score: Option<f64>,
+    #[serde(rename = "_source", default)]
+    source: JsonValue,
+    #[serde(default)]
+    highlight: JsonValue,
+}
+
+impl ElasticsearchBackend {
+    pub(super) fn new(name: String, client: Client, config: &WebSearchBackendConfig) -> Self {
+        warn_unsupported(
+            &name,
+            "elasticsearch",
+            &[
+                ("engines", !config.engines.is_empty()),
+                ("safesearch", config.safesearch.is_some()),
+                (
+                    "language (without language_field)",
+                    config.language.is_some() && config.language_field.is_none(),
+                ),
+                (
+                    "time_range (without date_field)",
+                    config.time_range.is_some() && config.date_field.is_none(),
+                ),
+                (
+                    "categories (without category_field)",
+                    !config.categories.is_empty() && config.category_field.is_none(),
+                ),
+            ],
+        );
+
+        let title_field = config
+            .title_field
+            .clone()
+            .unwrap_or_else(|| DEFAULT_TITLE_FIELD.to_string());
+        let content_field = config
+            .content_field
+            .clone()
+            .unwrap_or_else(|| DEFAULT_CONTENT_FIELD.to_string());
+        let fields = if config.fields.is_empty() {
+            vec![format!("{title_field}^2"), content_field.clone()]
+        } else {
+            config.fields.clone()
+        };
+
+        let index = config.index.as_deref().unwrap_or("_all");
+        Self {
+            name,
+            client,
+            search_url: format!("{}/{index}/_search", config.url.trim_end_matches('/')),
+            env_key: config.env_key.clone(),
+            fields,
+            title_field,
+            url_field: config
+                .url_field
+                .clone()
+                .unwrap_or_else(|| DEFAULT_URL_FIELD.to_string()),
+            content_field,
+            date_field: config.date_field.clone(),
+            language_field: config.language_field.clone(),
+            category_field: config.category_field.clone(),
+            language: config.language.clone(),
+            time_range: config.time_range,
+            categories: config.categories.clone(),
+            max_results: config.max_results.unwrap_or(DEFAULT_BACKEND_RESULTS),
+        }
+    }
+
+    /// Filters from the tool call, falling back to the configured defaults.
+    /// Options without a mapped field are ignored.
+    fn filters(&self, options: &SearchOptions) -> Vec<JsonValue> {
+        let mut filters = Vec::new();
+        let language = options.language.as_ref().or(self.language.as_ref());
+        if let (Some(field), Some(language)) = (&self.language_field, language) {
+            filters.push(json!({ "term": { field: language } }));
+        }
+        if let (Some(field), Some(time_range)) =
+            (&self.date_field, options.time_range.or(self.time_range))
+        {
+            filters.push(json!({ "range": { field: { "gte": date_math(time_range) } } }));
+        }
+        let categories = if options.categories.is_empty() {
+            &self.categories
+        } else {
+            &options.categories
+        };
+        if let Some(field) = &self.category_field
+            && !categories.is_empty()
+        {
+            filters.push(json!({ "terms": { field: categories } }));
+        }
+        filters
+    }
+
+    fn request_body(&self, query: &str, options: &SearchOptions) -> JsonValue {
+        let size = options
+            .num_results
+            .unwrap_or(self.max_results)
+            .min(MAX_NUM_RESULTS);
+        let mut source_fields = vec![
+            self.title_field.clone(),
+            self.url_field.clone(),
+            self.content_field.clone(),
+        ];
+        source_fields.extend(self.date_field.clone());
+        json!({
+            "from": options.offset(size),
+            "size": size,
+            "query": {
+                "bool": {
+                    "must": [{
+                        "multi_match": {
+                            "query": query,
+                            "fields": self.fields,
+                            "type": "best_fields",
+                        }
+                    }],
+                    "filter": self.filters(options),
+                }
+            },
+            "_source": source_fields,
+            "highlight": {
+                "fields": {
+                    &self.content_field: {
+                        "fragment_size": SNIPPET_FRAGMENT_CHARS,
+                        "number_of_fragments": 1,
+                    }
+                }
+            },
+        })
+    }
+
+    fn to_hit(&self, hit: Hit) -> Option<SearchHit> {
+        let url = field_str(&hit.source, &self.url_field)?;
+        let snippet = hit
+            .highlight
+            .get(&self.content_field)
+            .and_then(|fragments| fragments.get(0))
+            .and_then(JsonValue::as_str)
+            .map(strip_html)
+            .or_else(|| {
+                field_str(&hit.source, &self.content_field)
+                    .map(|content| content.chars().take(SNIPPET_FRAGMENT_CHARS).collect())
+            })
+            .unwrap_or_default();
+        Some(SearchHit {
+            title: field_str(&hit.source, &self.title_field).unwrap_or_default(),
+            url,
+            snippet,
+            engines: hit.index.into_iter().collect(),
+            score: hit.score,
+            category: None,
+            published_date: self
+                .date_field
+                .as_ref()
+                .and_then(|field| field_str(&hit.source, field)),
+        })
+    }
+}
+
+fn date_math(time_range: WebSearchTimeRange) -> &'static str {
+    match time_range {
+        WebSearchTimeRange::Day => "now-1d/d",
+        WebSearchTimeRange::Week => "now-1w/d",
+        WebSearchTimeRange::Month => "now-1M/d",
+        WebSearchTimeRange::Year => "now-1y/d",
+    }
+}
+
+/// Looks up a possibly dotted field path (`meta.title`) in `_source`.
+fn field_str(source: &JsonValue, field: &str) -> Option<String> {
+    let value = source.get(field).or_else(|| {
+        field
+            .split('.')
+            .try_fold(source, |value, segment| value.get(segment))
+    })?;
+    match value {
+        JsonValue::String(text) => Some(text.clone()),
+        JsonValue::Array(values) => values
+            .first()
+            .and_then(JsonValue::as_str)
+            .map(str::to_string),
+        JsonValue::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+#[async_trait]
+impl SearchBackend for ElasticsearchBackend {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn search(&self, query: &str, options: &SearchOptions) -> Result<SearchResults, String> {
+        let mut request = self
+            .client
+            .post(&self.search_url)
+            .json(&self.request_body(query, options));
+        if let Some(env_key) = &self.env_key {
+            let api_key = std::env::var(env_key).map_err(|_| {
+                format!("Elasticsearch API key environment variable `{env_key}` is not set")
+            })?;
+            request = request.header(AUTHORIZATION, format!("ApiKey {api_key}"));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Elasticsearch request failed: {e}"))?;
+
+        if !response.status().is_success() {
+            return Err(format!(
+                "Elasticsearch returned error status: {}",
+                response.status()
+            ));
+        }
+
+        let search_response: SearchResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Elasticsearch response: {e}"))?;
+
+        let hits: Vec<SearchHit> = search_response
+            .hits
+            .hits
+            .into_iter()
+            .filter_map(|hit| self.to_hit(hit))
+            .collect();
+        Ok(hits.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::WebSearchBackendKind;
+    use pretty_assertions::assert_eq;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::body_json;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+
+    #[tokio::test]
+    async fn searches_index_with_filters_and_maps_fields() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/docs/_search"))
+            .and(body_json(json!({
+                "from": 3,
+                "size": 3,
+                "query": {
+                    "bool": {
+                        "must": [{
+                            "multi_match": {
+                                "query": "sandbox",
+                                "fields": ["meta.title^2", "body"],
+                                "type": "best_fields",
+                            }
+                        }],
+                        "filter": [
+                            { "term": { "lang": "en" } },
+                            { "range": { "updated": { "gte": "now-1d/d" } } },
+                        ],
+                    }
+                },
+                "_source": ["meta.title", "link", "body", "updated"],
+                "highlight": {
+                    "fields": {
+                        "body": { "fragment_size": 240, "number_of_fragments": 1 }
+                    }
+                },
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "hits": {
+                    "total": { "value": 2 },
+                    "hits": [
+                        {
+                            "_index": "docs",
+                            "_score": 7.5,
+                            "_source": {
+                                "meta": { "title": "Sandboxing" },
+                                "link": "https://docs.internal/sandbox",
+                                "body": "How the sandbox works",
+                                "updated": "2025-02-01"
+                            },
+                            "highlight": { "body": ["How the <em>sandbox</em> works"] }
+                        },
+                        { "_index": "docs", "_score": 1.0, "_source": { "body": "no link" } }
+                    ]
+                }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = WebSearchBackendConfig {
+            kind: WebSearchBackendKind::Elasticsearch,
+            index: Some("docs".to_string()),
+            title_field: Some("meta.title".to_string()),
+            url_field: Some("link".to_string()),
+            content_field: Some("body".to_string()),
+            date_field: Some("updated".to_string()),
+            language_field: Some("lang".to_string()),
+            language: Some("en".to_string()),
+            time_range: Some(WebSearchTimeRange::Week),
+            max_results: Some(3),
+            ..WebSearchBackendConfig::searxng(server.uri())
+        };
+        let backend = ElasticsearchBackend::new("docs".to_string(), Client::new(), &config);
+
+        let options = SearchOptions {
+            time_range: Some(WebSearchTimeRange::Day),
+            page: 2,
+            ..Default::default()
+        };
+        let results = backend.search("sandbox", &options).await.expect("search");
+        assert_eq!(
+            results.hits,
+            vec![SearchHit {
+                title: "Sandboxing".to_string(),
+                url: "https://docs.internal/sandbox".to_string(),
+                snippet: "How the sandbox works".to_string(),
+                engines: vec!["docs".to_string()],
+                score: Some(7.5),
+                category: None,
+                published_date: Some("2025-02-01".to_string()),
+            }]
+        );
+    }
+}
@@ -0,0 +1,660 @@
+//! SearXNG via its JSON API (`/search?format=json`).
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::sync::OnceCell;
+
+use super::DEFAULT_BACKEND_RESULTS;
+use super::Infobox;
+use super::InfoboxAttribute;
+use super::InfoboxLink;
+use super::MAX_NUM_RESULTS;
+use super::SearchBackend;
+use super::SearchHit;
+use super::SearchOptions;
+use super::SearchResults;
+use super::strip_html;
+use crate::config::types::WebSearchBackendConfig;
+use crate::config::types::WebSearchSafeSearch;
+use crate::config::types::WebSearchTimeRange;
+
+/// Language values SearXNG accepts besides its configured locales.
+const BUILTIN_LANGUAGES: &[&str] = &["all", "auto"];
+
+pub(super) struct SearxngBackend {
+    name: String,
+    client: Client,
+    base_url: String,
+    engines: Vec<String>,
+    categories: Vec<String>,
+    language: Option<String>,
+    time_range: Option<WebSearchTimeRange>,
+    safesearch: Option<WebSearchSafeSearch>,
+    max_results: usize,
+    /// Instance capabilities from `/config`, fetched on first validation.
+    capabilities: OnceCell<Capabilities>,
+}
+
+/// SearXNG search result
+#[derive(Debug, Deserialize)]
+struct SearxngResult {
+    title: Option<String>,
+    url: Option<String>,
+    content: Option<String>,
+    engine: Option<String>,
+    #[serde(default)]
+    engines: Vec<String>,
+    score: Option<f64>,
+    category: Option<String>,
+    #[serde(rename = "publishedDate")]
+    published_date: Option<String>,
+}
+
+/// SearXNG API response
+#[derive(Debug, Deserialize)]
+struct SearxngResponse {
+    results: Vec<SearxngResult>,
+    #[serde(default)]
+    answers: Vec<SearxngAnswer>,
+    #[serde(default)]
+    infoboxes: Vec<SearxngInfobox>,
+    #[serde(default)]
+    suggestions: Vec<String>,
+}
+
+/// Answers are plain strings on older SearXNG releases and objects on newer
+/// ones.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SearxngAnswer {
+    Text(String),
+    Object { answer: String },
+}
+
+#[derive(Debug, Deserialize)]
+struct SearxngInfobox {
+    infobox: Option<String>,
+    id: Option<String>,
+    content: Option<String>,
+    #[serde(default)]
+    urls: Vec<SearxngInfoboxUrl>,
+    #[serde(default)]
+    attributes: Vec<SearxngInfoboxAttribute>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearxngInfoboxUrl {
+    title: Option<String>,
+    url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearxngInfoboxAttribute {
+    label: Option<String>,
+    value: Option<serde_json::Value>,
+}
+
+/// The parts of the `/config` response used to validate options.
+#[derive(Debug, Deserialize)]
+struct SearxngConfig {
+    #[serde(default)]
+    categories: Vec<String>,
+    #[serde(default)]
+    engines: Vec<SearxngEngine>,
+    #[serde(default)]
+    locales: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearxngEngine {
+    name: String,
+    #[serde(default = "default_true")]
+    enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug)]
+struct Capabilities {
+    categories: Vec<String>,
+    engines: Vec<String>,
+    languages: HashSet<String>,
+}
+
+impl From<SearxngConfig> for Capabilities {
+    fn from(config: SearxngConfig) -> Self {
+        let mut languages: HashSet<String> = HashSet::new();
+        for locale in config.locales.keys() {
+            let locale = locale.to_ascii_lowercase();
+            if let Some(primary) = locale.split(['-', '_']).next() {
+                languages.insert(primary.to_string());
+            }
+            languages.insert(locale);
+        }
+        Self {
+            categories: config.categories,
+            engines: config
+                .engines
+                .into_iter()
+                .filter(|engine| engine.enabled)
+                .map(|engine| engine.name)
+                .collect(),
+            languages,
+        }
+    }
+}
+
+impl Capabilities {
+    fn check(&self, options: &SearchOptions) -> Result<(), String> {
+        let unknown_categories = unknown(&options.categories, &self.categories);
+        if !unknown_categories.is_empty() {
+            return Err(format!(
+                "Unknown SearXNG categories: {}. Available categories: {}",
+                unknown_categories.join(", "),
+                self.categories.join(", ")
+            ));
+        }
+        let unknown_engines = unknown(&options.engines, &self.engines);
+        if !unknown_engines.is_empty() {
+            return Err(format!(
+                "Unknown or disabled SearXNG engines: {}. Enabled engines: {}",
+                unknown_engines.join(", "),
+                self.engines.join(", ")
+            ));
+        }
+        if let Some(language) = &options.language {
+            let language = language.to_ascii_lowercase();
+            // An instance without configured locales accepts any language.
+            if !self.languages.is_empty()
+                && !BUILTIN_LANGUAGES.contains(&language.as_str())
+                && !self.languages.contains(&language)
+            {
+                let mut languages: Vec<&str> = self.languages.iter().map(String::as_str).collect();
+                languages.sort_unstable();
+                return Err(format!(
+                    "Unsupported SearXNG language `{language}`. Supported languages: all, auto, {}",
+                    languages.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn unknown<'a>(requested: &'a [String], available: &[String]) -> Vec<&'a str> {
+    requested
+        .iter()
+        .filter(|name| {
+            !available
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(name))
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+impl SearxngBackend {
+    pub(super) fn new(name: String, client: Client, config: &WebSearchBackendConfig) -> Self {
+        Self {
+            name,
+            client,
+            base_url: config.url.trim_end_matches('/').to_string(),
+            engines: config.engines.clone(),
+            categories: config.categories.clone(),
+            language: config.language.clone(),
+            time_range: config.time_range,
+            safesearch: config.safesearch,
+            max_results: config.max_results.unwrap_or(DEFAULT_BACKEND_RESULTS),
+            capabilities: OnceCell::new(),
+        }
+    }
+
+    /// Builds the `/search` parameters. Options from the tool call take
+    /// precedence over the backend's configured defaults.
+    fn query_params(&self, query: &str, options: &SearchOptions) -> Vec<(&'static str, String)> {
+        let mut params = vec![("q", query.to_string()), ("format", "json".to_string())];
+        let engines = if options.engines.is_empty() {
+            &self.engines
+        } else {
+            &options.engines
+        };
+        if !engines.is_empty() {
+            params.push(("engines", engines.join(",")));
+        }
+        let categories = if options.categories.is_empty() {
+            &self.categories
+        } else {
+            &options.categories
+        };
+        if !categories.is_empty() {
+            params.push(("categories", categories.join(",")));
+        }
+        if let Some(language) = options.language.as_ref().or(self.language.as_ref()) {
+            params.push(("language", language.clone()));
+        }
+        if let Some(time_range) = options.time_range.or(self.time_range) {
+            params.push(("time_range", time_range_param(time_range).to_string()));
+        }
+        if let Some(safesearch) = self.safesearch {
+            let level = match safesearch {
+                WebSearchSafeSearch::Off => "0",
+                WebSearchSafeSearch::Moderate => "1",
+                WebSearchSafeSearch::Strict => "2",
+            };
+            params.push(("safesearch", level.to_string()));
+        }
+        if options.page > 1 {
+            params.push(("pageno", options.page.to_string()));
+        }
+        params
+    }
+
+    fn result_limit(&self, options: &SearchOptions) -> usize {
+        options
+            .num_results
+            .unwrap_or(self.max_results)
+            .min(MAX_NUM_RESULTS)
+    }
+
+    async fn fetch_capabilities(&self) -> Result<Capabilities, String> {
+        let response = self
+            .client
+            .get(format!("{}/config", self.base_url))
+            .send()
+            .await
+            .map_err(|e| format!("SearXNG config request failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "SearXNG config returned error status: {}",
+                response.status()
+            ));
+        }
+        let config: SearxngConfig = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse SearXNG config: {e}"))?;
+        Ok(config.into())
+    }
+}
+
+fn time_range_param(time_range: WebSearchTimeRange) -> &'static str {
+    match time_range {
+        WebSearchTimeRange::Day => "day",
+        WebSearchTimeRange::Week => "week",
+        WebSearchTimeRange::Month => "month",
+        WebSearchTimeRange::Year => "year",
+    }
+}
+
+fn to_infobox(infobox: SearxngInfobox) -> Infobox {
+    Infobox {
+        title: infobox.infobox.unwrap_or_default(),
+        url: infobox.id.filter(|id| id.starts_with("http")),
+        content: infobox
+            .content
+            .as_deref()
+            .map(strip_html)
+            .unwrap_or_default(),
+        attributes: infobox
+            .attributes
+            .into_iter()
+            .filter_map(|attribute| {
+                let value = match attribute.value? {
+                    serde_json::Value::String(text) => text,
+                    serde_json::Value::Null => return None,
+                    other => other.to_string(),
+                };
+                Some(InfoboxAttribute {
+                    label: attribute.label?,
+                    value,
+                })
+            })
+            .collect(),
+        links: infobox
+            .urls
+            .into_iter()
+            .filter_map(|link| {
+                Some(InfoboxLink {
+                    title: link.title.unwrap_or_default(),
+                    url: link.url?,
+                })
+            })
+            .collect(),
+    }
+}
+
+#[async_trait]
+impl SearchBackend for SearxngBackend {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn validate(&self, options: &SearchOptions) -> Result<(), String> {
+        if options.categories.is_empty() && options.engines.is_empty() && options.language.is_none()
+        {
+            return Ok(());
+        }
+        match self
+            .capabilities
+            .get_or_try_init(|| self.fetch_capabilities())
+            .await
+        {
+            Ok(capabilities) => capabilities.check(options),
+            Err(err) => {
+                // Instances may hide `/config`; let the search itself decide.
+                tracing::debug!(
+                    "skipping SearXNG option validation for `{}`: {err}",
+                    self.name
+                );
+                Ok(())
+            }
+        }
+    }
+
+    async fn search(&self, query: &str, options: &SearchOptions) -> Result<SearchResults, String> {
+        let response = self
+            .client
+            .get(format!("{}/search", self.base_url))
+            .query(&self.query_params(query, options))
+            .send()
+            .await
+            .map_err(|e| format!("SearXNG request failed: {e}"))?;
+
+        if !response.status().is_success() {
+            return Err(format!(
+                "SearXNG returned error status: {}",
+                response.status()
+            ));
+        }
+
+        let searxng_response: SearxngResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse SearXNG response: {e}"))?;
+
+        let hits = searxng_response
+            .results
+            .into_iter()
+            .filter(|result| result.url.is_some())
+            .take(self.result_limit(options))
+            .map(|result| {
+                let mut engines = result.engines;
+                if engines.is_empty()
+                    && let Some(engine) = result.engine
+                {
+                    engines.push(engine);
+                }
+                SearchHit {
+                    title: result.title.unwrap_or_default(),
+                    url: result.url.unwrap_or_default(),
+                    snippet: result.content.unwrap_or_default(),
+                    engines,
+                    score: result.score,
+                    category: result.category,
+                    published_date: result.published_date,
+                }
+            })
+            .collect();
+        Ok(SearchResults {
+            hits,
+            answers: searxng_response
+                .answers
+                .into_iter()
+                .map(|answer| match answer {
+                    SearxngAnswer::Text(text) | SearxngAnswer::Object { answer: text } => text,
+                })
+                .collect(),
+            infoboxes: searxng_response
+                .infoboxes
+                .into_iter()
+                .map(to_infobox)
+                .collect(),
+            suggestions: searxng_response.suggestions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+
+    #[tokio::test]
+    async fn sends_configured_options_and_parses_results() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search"))
+            .and(query_param("q", "rust async"))
+            .and(query_param("format", "json"))
+            .and(query_param("engines", "duckduckgo,wikipedia"))
+            .and(query_param("categories", "it"))
+            .and(query_param("language", "en"))
+            .and(query_param("time_range", "month"))
+            .and(query_param("safesearch", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "query": "rust async",
+                "results": [
+                    {
+                        "title": "Async Book",
+                        "url": "https://rust-lang.github.io/async-book/",
+                        "content": "Asynchronous programming in Rust",
+                        "engine": "duckduckgo",
+                        "engines": ["duckduckgo", "wikipedia"],
+                        "score": 2.5,
+                        "category": "it",
+                        "publishedDate": "2024-01-01T00:00:00"
+                    },
+                    {"title": "No URL"},
+                    {"title": "Tokio", "url": "https://tokio.rs/", "engine": "duckduckgo"}
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = WebSearchBackendConfig {
+            engines: vec!["duckduckgo".to_string(), "wikipedia".to_string()],
+            categories: vec!["it".to_string()],
+            language: Some("en".to_string()),
+            time_range: Some(WebSearchTimeRange::Month),
+            safesearch: Some(WebSearchSafeSearch::Strict),
+            ..WebSearchBackendConfig::searxng(format!("{}/", server.uri()))
+        };
+        let backend = SearxngBackend::new("searx".to_string(), Client::new(), &config);
+
+        let results = backend
+            .search("rust async", &SearchOptions::default())
+            .await
+            .expect("search");
+        assert_eq!(
+            results.hits,
+            vec![
+                SearchHit {
+                    title: "Async Book".to_string(),
+                    url: "https://rust-lang.github.io/async-book/".to_string(),
+                    snippet: "Asynchronous programming in Rust".to_string(),
+                    engines: vec!["duckduckgo".to_string(), "wikipedia".to_string()],
+                    score: Some(2.5),
+                    category: Some("it".to_string()),
+                    published_date: Some("2024-01-01T00:00:00".to_string()),
+                },
+                SearchHit {
+                    title: "Tokio".to_string(),
+                    url: "https://tokio.rs/".to_string(),
+                    engines: vec!["duckduckgo".to_string()],
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_error_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search"))
+            .respond_with(ResponseTemplate::new(429))
+            .mount(&server)
+            .await;
+
+        let config = WebSearchBackendConfig::searxng(server.uri());
+        let backend = SearxngBackend::new("searx".to_string(), Client::new(), &config);
+
+        assert_eq!(
+            backend.search("anything", &SearchOptions::default()).await,
+            Err("SearXNG returned error status: 429 Too Many Requests".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn call_options_override_config_and_extras_are_parsed() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search"))
+            .and(query_param("categories", "news"))
+            .and(query_param("engines", "bing news"))
+            .and(query_param("language", "de"))
+            .and(query_param("time_range", "day"))
+            .and(query_param("pageno", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "results": [
+                    {"title": "One", "url": "https://one.example/"},
+                    {"title": "Two", "url": "https://two.example/"}
+                ],
+                "answers": ["42", {"answer": "forty-two", "url": null}],
+                "infoboxes": [{
+                    "infobox": "Rust",
+                    "id": "https://en.wikipedia.org/wiki/Rust_(programming_language)",
+                    "content": "A <b>systems</b> language",
+                    "urls": [{"title": "Website", "url": "https://www.rust-lang.org/"}],
+                    "attributes": [
+                        {"label": "First appeared", "value": "2015"},
+                        {"label": "Typing", "value": null}
+                    ],
+                    "engine": "wikipedia"
+                }],
+                "suggestions": ["rust lang"]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = WebSearchBackendConfig {
+            categories: vec!["it".to_string()],
+            language: Some("en".to_string()),
+            ..WebSearchBackendConfig::searxng(server.uri())
+        };
+        let backend = SearxngBackend::new("searx".to_string(), Client::new(), &config);
+        let options = SearchOptions {
+            categories: vec!["news".to_string()],
+            engines: vec!["bing news".to_string()],
+            language: Some("de".to_string()),
+            time_range: Some(WebSearchTimeRange::Day),
+            page: 2,
+            num_results: Some(1),
+        };
+
+        let results = backend.search("rust", &options).await.expect("search");
+        assert_eq!(
+            results,
+            SearchResults {
+                hits: vec![SearchHit {
+                    title: "One".to_string(),
+                    url: "https://one.example/".to_string(),
+                    ..Default::default()
+                }],
+                answers: vec!["42".to_string(), "forty-two".to_string()],
+                infoboxes: vec![Infobox {
+                    title: "Rust".to_string(),
+                    url: Some(
+                        "https://en.wikipedia.org/wiki/Rust_(programming_language)".to_string()
+                    ),
+                    content: "A systems language".to_string(),
+                    attributes: vec![InfoboxAttribute {
+                        label: "First appeared".to_string(),
+                        value: "2015".to_string(),
+                    }],
+                    links: vec![InfoboxLink {
+                        title: "Website".to_string(),
+                        url: "https://www.rust-lang.org/".to_string(),
+                    }],
+                }],
+                suggestions: vec!["rust lang".to_string()],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn validates_options_against_instance_config() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/config"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "categories": ["general", "it", "news"],
+                "engines": [
+                    {"name": "duckduckgo", "enabled": true, "categories": ["general"]},
+                    {"name": "github", "enabled": false, "categories": ["it"]}
+                ],
+                "locales": {"en": "English", "de-CH": "Deutsch (Schweiz)"}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = WebSearchBackendConfig::searxng(server.uri());
+        let backend = SearxngBackend::new("searx".to_string(), Client::new(), &config);
+        let options =
+            |categories: &[&str], engines: &[&str], language: Option<&str>| SearchOptions {
+                categories: categories.iter().map(ToString::to_string).collect(),
+                engines: engines.iter().map(ToString::to_string).collect(),
+                language: language.map(str::to_string),
+                page: 1,
+                ..Default::default()
+            };
+
+        assert_eq!(
+            backend
+                .validate(&options(&["IT"], &["duckduckgo"], Some("de")))
+                .await,
+            Ok(())
+        );
+        assert_eq!(
+            backend.validate(&options(&[], &[], Some("all"))).await,
+            Ok(())
+        );
+        assert_eq!(
+            backend.validate(&options(&["videos"], &[], None)).await,
+            Err(
+                "Unknown SearXNG categories: videos. Available categories: general, it, news"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            backend.validate(&options(&[], &["github"], None)).await,
+            Err(
+                "Unknown or disabled SearXNG engines: github. Enabled engines: duckduckgo"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            backend.validate(&options(&[], &[], Some("fr"))).await,
+            Err(
+                "Unsupported SearXNG language `fr`. Supported languages: all, auto, de, de-ch, en"
+                    .to_string()
+            )
+        );
+    }
+}
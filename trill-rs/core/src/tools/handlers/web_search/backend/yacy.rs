@@ -0,0 +1,231 @@
+//! YaCy via its OpenSearch-style JSON API (`/yacysearch.json`).
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+
+use super::DEFAULT_BACKEND_RESULTS;
+use super::MAX_NUM_RESULTS;
+use super::SearchBackend;
+use super::SearchHit;
+use super::SearchOptions;
+use super::SearchResults;
+use super::strip_html;
+use super::warn_unsupported;
+use crate::config::types::WebSearchBackendConfig;
+
+pub(super) struct YacyBackend {
+    name: String,
+    client: Client,
+    base_url: String,
+    language: Option<String>,
+    max_results: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct YacyResponse {
+    #[serde(default)]
+    channels: Vec<YacyChannel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YacyChannel {
+    #[serde(default)]
+    items: Vec<YacyItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YacyItem {
+    title: Option<String>,
+    link: Option<String>,
+    description: Option<String>,
+    #[serde(rename = "pubDate")]
+    pub_date: Option<String>,
+}
+
+impl YacyBackend {
+    pub(super) fn new(name: String, client: Client, config: &WebSearchBackendConfig) -> Self {
+        warn_unsupported(
+            &name,
+            "yacy",
+            &[
+                ("engines", !config.engines.is_empty()),
+                ("categories", !config.categories.is_empty()),
+                ("time_range", config.time_range.is_some()),
+                ("safesearch", config.safesearch.is_some()),
+            ],
+        );
+        Self {
+            name,
+            client,
+            base_url: config.url.trim_end_matches('/').to_string(),
+            language: config.language.clone(),
+            max_results: config.max_results.unwrap_or(DEFAULT_BACKEND_RESULTS),
+        }
+    }
+}
+
+/// YaCy filters by the primary language subtag (`lr=lang_de`).
+fn primary_subtag(language: &str) -> String {
+    language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or(language)
+        .to_ascii_lowercase()
+}
+
+#[async_trait]
+impl SearchBackend for YacyBackend {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn search(&self, query: &str, options: &SearchOptions) -> Result<SearchResults, String> {
+        let max_results = options
+            .num_results
+            .unwrap_or(self.max_results)
+            .min(MAX_NUM_RESULTS);
+        let mut params = vec![
+            ("query", query.to_string()),
+            ("maximumRecords", max_results.to_string()),
+            ("startRecord", options.offset(max_results).to_string()),
+            ("contentdom", "text".to_string()),
+            ("resource", "global".to_string()),
+        ];
+        let language = options.language.as_ref().or(self.language.as_ref());
+        if let Some(language) = language.filter(|language| *language != "all") {
+            params.push(("lr", format!("lang_{}", primary_subtag(language))));
+        }
+
+        let response = self
+            .client
+            .get(format!("{}/yacysearch.json", self.base_url))
+            .query(&params)
+            .send()
+            .await
+            .map_err(|e| format!("YaCy request failed: {e}"))?;
+
+        if !response.status().is_success() {
+            return Err(format!("YaCy returned error status: {}", response.status()));
+        }
+
+        let yacy_response: YacyResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse YaCy response: {e}"))?;
+
+        let hits: Vec<SearchHit> = yacy_response
+            .channels
+            .into_iter()
+            .flat_map(|channel| channel.items)
+            .filter_map(|item| {
+                Some(SearchHit {
+                    title: strip_html(item.title.as_deref().unwrap_or_default()),
+                    url: item.link?,
+                    snippet: strip_html(item.description.as_deref().unwrap_or_default()),
+                    engines: Vec::new(),
+                    score: None,
+                    category: None,
+                    published_date: item.pub_date,
+                })
+            })
+            .take(max_results)
+            .collect();
+        Ok(hits.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::WebSearchBackendKind;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+    use wiremock::matchers::query_param;
+
+    #[tokio::test]
+    async fn queries_yacy_and_strips_markup() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/yacysearch.json"))
+            .and(query_param("query", "landlock"))
+            .and(query_param("maximumRecords", "5"))
+            .and(query_param("startRecord", "0"))
+            .and(query_param("lr", "lang_de"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "channels": [{
+                    "title": "YaCy P2P-Search for landlock",
+                    "items": [
+                        {
+                            "title": "<b>Landlock</b> LSM",
+                            "link": "https://docs.kernel.org/userspace-api/landlock.html",
+                            "description": "Unprivileged <b>access</b> control",
+                            "pubDate": "Mon, 01 Jan 2024 00:00:00 +0000"
+                        },
+                        {"title": "missing link"}
+                    ]
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = WebSearchBackendConfig {
+            kind: WebSearchBackendKind::Yacy,
+            language: Some("de-CH".to_string()),
+            max_results: Some(5),
+            ..WebSearchBackendConfig::searxng(server.uri())
+        };
+        let backend = YacyBackend::new("peer".to_string(), Client::new(), &config);
+
+        let results = backend
+            .search("landlock", &SearchOptions::default())
+            .await
+            .expect("search");
+        assert_eq!(
+            results.hits,
+            vec![SearchHit {
+                title: "Landlock LSM".to_string(),
+                url: "https://docs.kernel.org/userspace-api/landlock.html".to_string(),
+                snippet: "Unprivileged access control".to_string(),
+                published_date: Some("Mon, 01 Jan 2024 00:00:00 +0000".to_string()),
+                ..Default::default()
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn pages_through_records() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/yacysearch.json"))
+            .and(query_param("maximumRecords", "5"))
+            .and(query_param("startRecord", "10"))
+            .and(query_param("lr", "lang_fr"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"channels": []})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = WebSearchBackendConfig {
+            kind: WebSearchBackendKind::Yacy,
+            language: Some("de".to_string()),
+            ..WebSearchBackendConfig::searxng(server.uri())
+        };
+        let backend = YacyBackend::new("peer".to_string(), Client::new(), &config);
+        let options = SearchOptions {
+            language: Some("fr-FR".to_string()),
+            page: 3,
+            num_results: Some(5),
+            ..Default::default()
+        };
+
+        let results = backend.search("landlock", &options).await.expect("search");
+        assert_eq!(results, SearchResults::default());
+    }
+}
@@ -0,0 +1,253 @@
+//! Turns a fetched HTTP body into model-readable text.
+//!
+//! The media type from `Content-Type` picks the converter (HTML, PDF, JSON or
+//! plain text); when the server sends something generic such as
+//! `application/octet-stream` the body is sniffed instead. Text bodies are
+//! decoded using the declared charset, a BOM, an HTML `<meta charset>` or, as
+//! a last resort, statistical detection.
+
+use std::sync::LazyLock;
+
+use encoding_rs::Encoding;
+use regex_lite::Regex;
+use serde::Serialize;
+use url::Url;
+
+use super::html::html_to_markdown;
+use super::pdf::extract_pdf_text;
+use crate::text_encoding::bytes_to_string_smart;
+
+/// How far into an HTML document we look for a `<meta charset>` declaration.
+const META_CHARSET_PRESCAN_BYTES: usize = 1024;
+
+static META_CHARSET: LazyLock<Regex> = LazyLock::new(|| {
+    #[expect(clippy::unwrap_used)]
+    Regex::new(r#"(?i)<meta[^>]+charset\s*=\s*["']?\s*([A-Za-z0-9_:.\-]+)"#).unwrap()
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum DocumentKind {
+    Html,
+    Pdf,
+    Json,
+    Text,
+}
+
+/// A fetched page converted to text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PageDocument {
+    pub(crate) title: Option<String>,
+    pub(crate) kind: DocumentKind,
+    pub(crate) content: String,
+}
+
+/// Converts a response body into a [`PageDocument`]. Returns an error message
+/// suitable for the model when the body cannot be represented as text.
+pub(crate) fn convert_document(
+    url: &Url,
+    content_type: Option<&str>,
+    body: &[u8],
+) -> Result<PageDocument, String> {
+    let (media_type, charset) = parse_content_type(content_type);
+    let kind = match media_type.as_deref() {
+        Some("text/html" | "application/xhtml+xml") => DocumentKind::Html,
+        Some("application/pdf" | "application/x-pdf") => DocumentKind::Pdf,
+        Some("application/json" | "text/json") => DocumentKind::Json,
+        Some(media) if media.ends_with("+json") => DocumentKind::Json,
+        Some(media)
+            if media.starts_with("text/")
+                || media.ends_with("+xml")
+                || media == "application/xml" =>
+        {
+            DocumentKind::Text
+        }
+        Some(media)
+            if media.starts_with("image/")
+                || media.starts_with("audio/")
+                || media.starts_with("video/")
+                || media.starts_with("font/") =>
+        {
+            return Err(format!("Unsupported content type for reading: {media}"));
+        }
+        _ => sniff_kind(body)?,
+    };
+
+    match kind {
+        DocumentKind::Html => {
+            let text = decode_text(body, charset.as_deref(), true);
+            let converted = html_to_markdown(&text, Some(url));
+            Ok(PageDocument {
+                title: converted.title,
+                kind,
+                content: converted.markdown,
+            })
+        }
+        DocumentKind::Pdf => {
+            let content = extract_pdf_text(body).ok_or_else(|| {
+                "PDF contains no extractable text (it may be scanned or use embedded font encodings)"
+                    .to_string()
+            })?;
+            Ok(PageDocument {
+                title: None,
+                kind,
+                content,
+            })
+        }
+        DocumentKind::Json => {
+            let text = decode_text(body, charset.as_deref(), false);
+            let content = match serde_json::from_str::<serde_json::Value>(&text) {
+                Ok(value) => {
+                    let pretty = serde_json::to_string_pretty(&value).unwrap_or(text);
+                    format!("```json\n{pretty}\n```")
+                }
+                Err(_) => text,
+            };
+            Ok(PageDocument {
+                title: None,
+                kind,
+                content,
+            })
+        }
+        DocumentKind::Text => Ok(PageDocument {
+            title: None,
+            kind,
+            content: decode_text(body, charset.as_deref(), false),
+        }),
+    }
+}
+
+/// Splits a `Content-Type` header into a lowercase media type and an optional
+/// charset parameter.
+fn parse_content_type(content_type: Option<&str>) -> (Option<String>, Option<String>) {
+    let Some(content_type) = content_type else {
+        return (None, None);
+    };
+    let mut parts = content_type.split(';');
+    let media_type = parts
+        .next()
+        .map(|media| media.trim().to_ascii_lowercase())
+        .filter(|media| !media.is_empty() && media != "application/octet-stream");
+    let charset = parts.find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches(['"', '\'']).to_string())
+    });
+    (media_type, charset)
+}
+
+fn sniff_kind(body: &[u8]) -> Result<DocumentKind, String> {
+    if body.starts_with(b"%PDF-") {
+        return Ok(DocumentKind::Pdf);
+    }
+    let head = String::from_utf8_lossy(&body[..body.len().min(512)]).to_ascii_lowercase();
+    let trimmed = head.trim_start_matches('\u{feff}').trim_start();
+    if trimmed.starts_with("<!doctype html")
+        || trimmed.starts_with("<html")
+        || head.contains("<body")
+    {
+        return Ok(DocumentKind::Html);
+    }
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        return Ok(DocumentKind::Json);
+    }
+    if looks_binary(body) {
+        return Err(
+            "Unsupported binary content; the page is not text, HTML, JSON or PDF".to_string(),
+        );
+    }
+    Ok(DocumentKind::Text)
+}
+
+fn looks_binary(body: &[u8]) -> bool {
+    let sample = &body[..body.len().min(1024)];
+    sample.contains(&0)
+}
+
+/// Decodes text using, in order: a BOM, the declared charset, an HTML meta
+/// charset (when `is_html`), and finally UTF-8 validation with statistical
+/// detection as a fallback.
+fn decode_text(body: &[u8], declared_charset: Option<&str>, is_html: bool) -> String {
+    if let Some((encoding, bom_len)) = Encoding::for_bom(body) {
+        let (decoded, _) = encoding.decode_without_bom_handling(&body[bom_len..]);
+        return decoded.into_owned();
+    }
+    let meta_charset = || {
+        let prescan = &body[..body.len().min(META_CHARSET_PRESCAN_BYTES)];
+        let prescan = String::from_utf8_lossy(prescan);
+        META_CHARSET
+            .captures(&prescan)
+            .and_then(|captures| captures.get(1))
+            .map(|charset| charset.as_str().to_string())
+    };
+    let label = declared_charset
+        .map(str::to_string)
+        .or_else(|| if is_html { meta_charset() } else { None });
+    if let Some(encoding) = label.and_then(|label| Encoding::for_label(label.trim().as_bytes())) {
+        let (decoded, _) = encoding.decode_without_bom_handling(body);
+        return decoded.into_owned();
+    }
+    bytes_to_string_smart(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn url() -> Url {
+        Url::parse("https://example.com/").expect("url")
+    }
+
+    #[test]
+    fn decodes_declared_and_meta_charsets() {
+        let latin1 =
+            b"<html><head><meta charset=\"iso-8859-1\"></head><body><p>caf\xe9</p></body></html>";
+        let document = convert_document(&url(), Some("text/html"), latin1).expect("document");
+        assert_eq!(document.content, "café");
+
+        let declared = convert_document(
+            &url(),
+            Some("text/plain; charset=windows-1252"),
+            b"\x93quoted\x94",
+        )
+        .expect("document");
+        assert_eq!(declared.content, "“quoted”");
+    }
+
+    #[test]
+    fn pretty_prints_json() {
+        let document = convert_document(&url(), Some("application/json"), br#"{"a":[1,2]}"#)
+            .expect("document");
+        assert_eq!(document.kind, DocumentKind::Json);
+        assert_eq!(
+            document.content,
+            "```json\n{\n  \"a\": [\n    1,\n    2\n  ]\n}\n```"
+        );
+    }
+
+    #[test]
+    fn sniffs_untyped_bodies() {
+        let html = convert_document(&url(), None, b"<!DOCTYPE html><title>T</title><p>Hi</p>")
+            .expect("document");
+        assert_eq!(html.kind, DocumentKind::Html);
+        assert_eq!(html.title.as_deref(), Some("T"));
+
+        let text = convert_document(&url(), Some("application/octet-stream"), b"plain words")
+            .expect("document");
+        assert_eq!(text.kind, DocumentKind::Text);
+
+        let binary = convert_document(&url(), None, b"\x00\x01\x02");
+        assert!(binary.is_err());
+    }
+
+    #[test]
+    fn rejects_images() {
+        let result = convert_document(&url(), Some("image/png"), b"\x89PNG");
+        assert_eq!(
+            result,
+            Err("Unsupported content type for reading: image/png".to_string())
+        );
+    }
+}
@@ -0,0 +1,369 @@
+//! Page fetches for `open_page` and `find_in_page`, checked against
+//! `[web_search.policy]` with the host rules the network proxy applies.
+//!
+//! Redirects are followed by hand so every hop is checked, against the policy
+//! and the caller's own check, before it is requested. Unless the policy
+//! allows local addresses, hostnames are resolved through
+//! [`PublicAddrResolver`], which drops private addresses so a DNS answer
+//! cannot change between the check and the connect. Refused URLs are recorded
+//! like the network proxy's blocked requests until they are reported.
+
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use reqwest::Client;
+use reqwest::Response;
+use reqwest::dns::Addrs;
+use reqwest::dns::Name;
+use reqwest::dns::Resolve;
+use reqwest::dns::Resolving;
+use reqwest::header::HeaderMap;
+use reqwest::header::LOCATION;
+use reqwest::redirect;
+use trill_network_policy::BlockedRequest;
+use trill_network_policy::BlockedRequestArgs;
+use trill_network_policy::DomainPolicy;
+use trill_network_policy::HostBlockDecision;
+use trill_network_policy::NetworkPolicy;
+use trill_network_policy::policy::Host;
+use trill_network_policy::policy::is_loopback_host;
+use trill_network_policy::policy::is_non_public_ip;
+use url::Url;
+
+use crate::default_client::get_codex_user_agent;
+
+/// Redirect hops followed before a fetch is abandoned.
+const MAX_REDIRECTS: usize = 10;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+/// Recorded as the `client` of blocked fetches.
+const BLOCKED_CLIENT: &str = "web_search";
+/// Blocked fetches kept until they are reported; older ones are dropped.
+const MAX_BLOCKED_EVENTS: usize = 200;
+
+#[derive(Debug)]
+pub(crate) enum FetchError {
+    /// The policy refused the URL or one of its redirects.
+    Blocked(BlockedRequest),
+    Failed(String),
+}
+
+impl From<String> for FetchError {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
+/// An extra check the caller applies to every URL before it is fetched,
+/// such as an execpolicy `url_rule` that may ask the user.
+pub(crate) type CheckHop<'a> =
+    dyn Fn(&Url) -> BoxFuture<'a, Result<(), FetchError>> + Send + Sync + 'a;
+
+pub(crate) struct WebFetcher {
+    client: Client,
+    domains: DomainPolicy,
+    blocked: Mutex<VecDeque<BlockedRequest>>,
+}
+
+impl WebFetcher {
+    pub(crate) fn new(policy: &NetworkPolicy) -> Self {
+        // The policy is validated when the config is loaded; if it still does
+        // not compile, deny everything rather than fetch unchecked.
+        let domains = DomainPolicy::new(policy.clone()).unwrap_or_else(|err| {
+            tracing::warn!("invalid web_search.policy, blocking all page fetches: {err}");
+            DomainPolicy::default()
+        });
+        let mut builder = Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .user_agent(get_codex_user_agent())
+            .redirect(redirect::Policy::none());
+        if !policy.allow_local_binding {
+            builder = builder.dns_resolver(Arc::new(PublicAddrResolver));
+        }
+        let client = builder.build().unwrap_or_else(|_| Client::new());
+        Self {
+            client,
+            domains,
+            blocked: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Returns the fetches blocked since the last call, oldest first.
+    pub(crate) fn drain_blocked(&self) -> Vec<BlockedRequest> {
+        let mut blocked = self.blocked.lock().unwrap_or_else(PoisonError::into_inner);
+        std::mem::take(&mut *blocked).into_iter().collect()
+    }
+
+    fn record_blocked(&self, entry: BlockedRequest) {
+        let (host, reason) = (&entry.host, &entry.reason);
+        tracing::warn!("web_search fetch blocked (host={host}, reason={reason})");
+        let mut blocked = self.blocked.lock().unwrap_or_else(PoisonError::into_inner);
+        blocked.push_back(entry);
+        while blocked.len() > MAX_BLOCKED_EVENTS {
+            blocked.pop_front();
+        }
+    }
+
+    /// Sends a GET for `url` with `headers`, following redirects only to
+    /// hosts the policy allows and `check_hop` accepts.
+    pub(crate) async fn get(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+        check_hop: &CheckHop<'_>,
+    ) -> Result<Response, FetchError> {
+        let mut current = Url::parse(url)
+            .map_err(|err| FetchError::Failed(format!("Invalid URL {url}: {err}")))?;
+        for _ in 0..=MAX_REDIRECTS {
+            self.check(&current).await?;
+            check_hop(&current).await?;
+            let response = self
+                .client
+                .get(current.clone())
+                .headers(headers.clone())
+                .send()
+                .await
+                .map_err(|err| FetchError::Failed(format!("Failed to fetch page: {err}")))?;
+            if !response.status().is_redirection() {
+                return Ok(response);
+            }
+            let Some(location) = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|value| value.to_str().ok())
+            else {
+                return Ok(response);
+            };
+            current = current.join(location).map_err(|err| {
+                FetchError::Failed(format!("Invalid redirect from {current}: {err}"))
+            })?;
+        }
+        Err(FetchError::Failed(format!(
+            "Too many redirects fetching {url} (limit {MAX_REDIRECTS})"
+        )))
+    }
+
+    /// Checks `url` against the policy and `check_hop` without fetching it,
+    /// for pages served from a cache.
+    pub(crate) async fn check_url(
+        &self,
+        url: &str,
+        check_hop: &CheckHop<'_>,
+    ) -> Result<(), FetchError> {
+        let url = Url::parse(url)
+            .map_err(|err| FetchError::Failed(format!("Invalid URL {url}: {err}")))?;
+        self.check(&url).await?;
+        check_hop(&url).await
+    }
+
+    async fn check(&self, url: &Url) -> Result<(), FetchError> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(FetchError::Failed(format!(
+                "Refusing to fetch {url}: only http and https URLs are supported"
+            )));
+        }
+        let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+            return Err(FetchError::Failed(format!(
+                "Refusing to fetch {url}: missing host"
+            )));
+        };
+        match self.domains.host_blocked(host, port).await {
+            HostBlockDecision::Allowed => Ok(()),
+            HostBlockDecision::Blocked(reason) => {
+                let blocked = BlockedRequest::new(BlockedRequestArgs {
+                    host: host.to_string(),
+                    reason: reason.to_string(),
+                    client: Some(BLOCKED_CLIENT.to_string()),
+                    method: Some("GET".to_string()),
+                    mode: None,
+                    protocol: url.scheme().to_string(),
+                });
+                self.record_blocked(blocked.clone());
+                Err(FetchError::Blocked(blocked))
+            }
+        }
+    }
+}
+
+/// Resolves hostnames to their public addresses only.
+///
+/// Loopback names are passed through: they only reach the resolver when the
+/// policy explicitly allowlists them.
+struct PublicAddrResolver;
+
+impl Resolve for PublicAddrResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            let loopback_name = Host::parse(&host).is_ok_and(|host| is_loopback_host(&host));
+            let addrs: Vec<SocketAddr> = addrs
+                .into_iter()
+                .filter(|addr| loopback_name || !is_non_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{host} resolves only to local or private addresses").into());
+            }
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+    use pretty_assertions::assert_eq;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::path;
+
+    fn any_hop(_: &Url) -> BoxFuture<'static, Result<(), FetchError>> {
+        async { Ok(()) }.boxed()
+    }
+
+    fn blocked_reason(result: Result<Response, FetchError>) -> String {
+        match result {
+            Err(FetchError::Blocked(blocked)) => blocked.reason,
+            other => panic!("expected a blocked fetch, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn blocks_private_addresses_by_default() {
+        let fetcher = WebFetcher::new(&NetworkPolicy {
+            allowed_domains: vec!["*".to_string()],
+            ..NetworkPolicy::default()
+        });
+        for url in [
+            "http://127.0.0.1:8080/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://[::1]/",
+            "http://10.0.0.1/admin",
+        ] {
+            assert_eq!(
+                blocked_reason(fetcher.get(url, HeaderMap::new(), &any_hop).await),
+                "not_allowed_local",
+                "{url}"
+            );
+        }
+        assert!(matches!(
+            fetcher
+                .get("file:///etc/passwd", HeaderMap::new(), &any_hop)
+                .await,
+            Err(FetchError::Failed(_))
+        ));
+
+        // Blocked fetches are recorded until they are drained.
+        let recorded: Vec<String> = fetcher
+            .drain_blocked()
+            .into_iter()
+            .map(|blocked| blocked.host)
+            .collect();
+        assert_eq!(
+            recorded,
+            vec!["127.0.0.1", "169.254.169.254", "[::1]", "10.0.0.1"]
+        );
+        assert!(fetcher.drain_blocked().is_empty());
+    }
+
+    #[tokio::test]
+    async fn checks_every_redirect_hop() {
+        let server = MockServer::start().await;
+        Mock::given(path("/start"))
+            .respond_with(ResponseTemplate::new(302).insert_header("location", "/next"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/next"))
+            .respond_with(
+                ResponseTemplate::new(301)
+                    .insert_header("location", "http://169.254.169.254/latest/meta-data/"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/ok"))
+            .respond_with(ResponseTemplate::new(307).insert_header("location", "/done"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/done"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("done"))
+            .mount(&server)
+            .await;
+        // The mock server is allowed explicitly; the metadata address is not.
+        let fetcher = WebFetcher::new(&NetworkPolicy {
+            allowed_domains: vec!["*".to_string(), "127.0.0.1".to_string()],
+            ..NetworkPolicy::default()
+        });
+
+        let blocked = fetcher
+            .get(
+                &format!("{}/start", server.uri()),
+                HeaderMap::new(),
+                &any_hop,
+            )
+            .await;
+        match blocked {
+            Err(FetchError::Blocked(blocked)) => {
+                assert_eq!(blocked.host, "169.254.169.254");
+                assert_eq!(blocked.reason, "not_allowed_local");
+                assert_eq!(blocked.client.as_deref(), Some(BLOCKED_CLIENT));
+            }
+            other => panic!("expected a blocked redirect, got {other:?}"),
+        }
+
+        let response = fetcher
+            .get(&format!("{}/ok", server.uri()), HeaderMap::new(), &any_hop)
+            .await
+            .expect("allowed redirect");
+        assert_eq!(response.url().path(), "/done");
+        assert_eq!(response.text().await.expect("body"), "done");
+
+        // The caller's check also sees every hop, not just the first URL.
+        let refuse_done = |hop: &Url| {
+            let result = if hop.path() == "/done" {
+                Err(FetchError::Failed(format!("Refused to fetch {hop}")))
+            } else {
+                Ok(())
+            };
+            async move { result }.boxed()
+        };
+        assert!(matches!(
+            fetcher
+                .get(&format!("{}/ok", server.uri()), HeaderMap::new(), &refuse_done)
+                .await,
+            Err(FetchError::Failed(message)) if message.ends_with("/done")
+        ));
+    }
+
+    #[tokio::test]
+    async fn denied_domains_win_over_allowlist() {
+        let fetcher = WebFetcher::new(&NetworkPolicy {
+            allowed_domains: vec!["**.example.com".to_string()],
+            denied_domains: vec!["admin.example.com".to_string()],
+            ..NetworkPolicy::default()
+        });
+        assert_eq!(
+            blocked_reason(
+                fetcher
+                    .get("https://admin.example.com/", HeaderMap::new(), &any_hop)
+                    .await
+            ),
+            "denied"
+        );
+        assert_eq!(
+            blocked_reason(
+                fetcher
+                    .get("https://other.test/", HeaderMap::new(), &any_hop)
+                    .await
+            ),
+            "not_allowed"
+        );
+    }
+}
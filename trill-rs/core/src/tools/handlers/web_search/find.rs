@@ -0,0 +1,230 @@
+//! `find_in_page` search over a converted document.
+//!
+//! Output mirrors `grep -n -C`: matching lines are prefixed with `N:`, context
+//! lines with `N-`, and overlapping context windows are merged into a single
+//! block.
+
+use std::ops::Range;
+
+use regex::Regex;
+use regex::RegexBuilder;
+use serde::Serialize;
+
+use super::pages::page_for_offset;
+
+/// Maximum number of matching lines reported.
+pub(crate) const MAX_MATCHES: usize = 50;
+
+/// Default number of context lines around each match.
+pub(crate) const DEFAULT_CONTEXT_LINES: usize = 2;
+
+/// Upper bound on the requested context, to keep responses bounded.
+const MAX_CONTEXT_LINES: usize = 20;
+
+/// Lines longer than this are clipped around the first match.
+const MAX_LINE_CHARS: usize = 400;
+
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// A run of lines containing one or more matches plus surrounding context.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub(crate) struct MatchBlock {
+    /// 1-based line number of the first line in the block.
+    pub(crate) start_line: usize,
+    /// 1-based line number of the last line in the block.
+    pub(crate) end_line: usize,
+    /// 1-based `open_page` page containing the first match in the block.
+    pub(crate) page: usize,
+    pub(crate) text: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct FindResult {
+    pub(crate) blocks: Vec<MatchBlock>,
+    pub(crate) total_matches: usize,
+}
+
+/// Compiles the pattern. Literal patterns are escaped; both forms match
+/// case-insensitively unless the regex disables it with `(?-i)`.
+pub(crate) fn compile_pattern(pattern: &str, is_regex: bool) -> Result<Regex, String> {
+    let source = if is_regex {
+        pattern.to_string()
+    } else {
+        regex::escape(pattern)
+    };
+    RegexBuilder::new(&source)
+        .case_insensitive(true)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|err| format!("Invalid regex pattern: {err}"))
+}
+
+/// Searches `content` line by line. `pages` are the ranges produced by
+/// [`super::pages::split_pages`] and are used to report which page each match
+/// block starts on.
+pub(crate) fn find_in_content(
+    content: &str,
+    pages: &[Range<usize>],
+    regex: &Regex,
+    context_lines: usize,
+) -> FindResult {
+    let context_lines = context_lines.min(MAX_CONTEXT_LINES);
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        lines.push((offset, line.trim_end_matches(['\n', '\r'])));
+        offset += line.len();
+    }
+
+    let mut total_matches = 0;
+    let mut matched: Vec<(usize, usize)> = Vec::new();
+    for (index, (line_offset, line)) in lines.iter().enumerate() {
+        if let Some(found) = regex.find(line) {
+            total_matches += 1;
+            if matched.len() < MAX_MATCHES {
+                matched.push((index, line_offset + found.start()));
+            }
+        }
+    }
+
+    let mut blocks: Vec<MatchBlock> = Vec::new();
+    let mut current: Option<(usize, usize, usize)> = None;
+    for (index, match_offset) in &matched {
+        let start = index.saturating_sub(context_lines);
+        let end = (index + context_lines).min(lines.len().saturating_sub(1));
+        current = match current {
+            Some((block_start, block_end, page)) if start <= block_end + 1 => {
+                Some((block_start, end.max(block_end), page))
+            }
+            Some(previous) => {
+                blocks.push(render_block(&lines, previous, regex));
+                Some((start, end, page_for_offset(pages, *match_offset)))
+            }
+            None => Some((start, end, page_for_offset(pages, *match_offset))),
+        };
+    }
+    if let Some(last) = current {
+        blocks.push(render_block(&lines, last, regex));
+    }
+
+    FindResult {
+        blocks,
+        total_matches,
+    }
+}
+
+fn render_block(
+    lines: &[(usize, &str)],
+    (start, end, page): (usize, usize, usize),
+    regex: &Regex,
+) -> MatchBlock {
+    let text = lines[start..=end]
+        .iter()
+        .enumerate()
+        .map(|(position, (_, line))| {
+            let number = start + position + 1;
+            match regex.find(line) {
+                Some(found) => format!("{number}:{}", clip_line(line, found.start())),
+                None => format!("{number}-{}", clip_line(line, 0)),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    MatchBlock {
+        start_line: start + 1,
+        end_line: end + 1,
+        page,
+        text,
+    }
+}
+
+/// Shortens long lines to a window of `MAX_LINE_CHARS` that includes the byte
+/// offset `focus`.
+fn clip_line(line: &str, focus: usize) -> String {
+    let char_count = line.chars().count();
+    if char_count <= MAX_LINE_CHARS {
+        return line.trim().to_string();
+    }
+    let focus_char = line[..focus].chars().count();
+    let start = focus_char.saturating_sub(MAX_LINE_CHARS / 4);
+    let start = start.min(char_count - MAX_LINE_CHARS);
+    let end = start + MAX_LINE_CHARS;
+    let mut clipped: String = line.chars().skip(start).take(MAX_LINE_CHARS).collect();
+    if start > 0 {
+        clipped.insert(0, '…');
+    }
+    if end < char_count {
+        clipped.push('…');
+    }
+    clipped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::handlers::web_search::pages::split_pages;
+    use pretty_assertions::assert_eq;
+
+    fn find(content: &str, pattern: &str, is_regex: bool, context_lines: usize) -> FindResult {
+        let regex = compile_pattern(pattern, is_regex).expect("pattern");
+        let pages = split_pages(content, content.len().max(1));
+        find_in_content(content, &pages, &regex, context_lines)
+    }
+
+    #[test]
+    fn merges_overlapping_context_windows() {
+        let content = "one\ntwo\nthree apple\nfour\nApple five\nsix\nseven\neight\nnine\napple ten";
+        let result = find(content, "apple", false, 1);
+        assert_eq!(result.total_matches, 3);
+        assert_eq!(
+            result.blocks,
+            vec![
+                MatchBlock {
+                    start_line: 2,
+                    end_line: 6,
+                    page: 1,
+                    text: "2-two\n3:three apple\n4-four\n5:Apple five\n6-six".to_string(),
+                },
+                MatchBlock {
+                    start_line: 9,
+                    end_line: 10,
+                    page: 1,
+                    text: "9-nine\n10:apple ten".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn supports_regex_and_escapes_literals() {
+        let content = "version 1.2.3\nversion 10x20\n";
+        let regex = find(content, r"\d+\.\d+\.\d+", true, 0);
+        assert_eq!(regex.total_matches, 1);
+        assert_eq!(regex.blocks[0].text, "1:version 1.2.3");
+
+        let literal = find(content, "1.2", false, 0);
+        assert_eq!(literal.total_matches, 1);
+
+        assert!(compile_pattern("(unclosed", true).is_err());
+    }
+
+    #[test]
+    fn reports_page_of_each_block() {
+        let content = "alpha\nbeta\ngamma\ndelta\n";
+        let pages = vec![0..11, 11..content.len()];
+        let regex = compile_pattern("delta|alpha", true).expect("pattern");
+        let result = find_in_content(content, &pages, &regex, 0);
+        let pages: Vec<usize> = result.blocks.iter().map(|block| block.page).collect();
+        assert_eq!(pages, vec![1, 2]);
+    }
+
+    #[test]
+    fn clips_long_lines_around_the_match() {
+        let line = format!("{}needle{}", "a".repeat(1_000), "b".repeat(1_000));
+        let result = find(&line, "needle", false, 0);
+        let text = &result.blocks[0].text;
+        assert!(text.starts_with("1:…"));
+        assert!(text.ends_with('…'));
+        assert!(text.contains("needle"));
+    }
+}
@@ -0,0 +1,1336 @@
+//! HTML to Markdown conversion for `web_search` page fetches.
+//!
+//! Pages are parsed with a small, forgiving tokenizer into an arena-backed
+//! tree, boilerplate (scripts, navigation, cookie banners, sidebars) is pruned,
+//! and the subtree that most likely holds the main content is picked using a
+//! readability-style score. That subtree is rendered as Markdown with links,
+//! images, tables and fenced code blocks preserved, so the model spends its
+//! context on the article rather than on markup.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use regex_lite::Regex;
+use url::Url;
+
+/// Minimum amount of text an explicit `<main>`/`<article>` container must hold
+/// before we trust it over the scored candidate.
+const MIN_SEMANTIC_CONTAINER_CHARS: usize = 200;
+
+/// Minimum amount of text for a scored candidate to win over the whole body.
+const MIN_CANDIDATE_CHARS: usize = 250;
+
+/// Minimum length of a paragraph for it to contribute to candidate scores.
+const MIN_PARAGRAPH_CHARS: usize = 25;
+
+/// Deepest element nesting the parser builds. The tree is walked recursively,
+/// so deeper elements are opened as siblings of the innermost one instead.
+const MAX_DEPTH: usize = 256;
+
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements whose content is raw text rather than markup.
+const RAW_TEXT_TAGS: &[&str] = &["script", "style", "textarea", "title", "xmp", "noscript"];
+
+/// Elements that never carry readable content.
+const DROPPED_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "canvas", "iframe", "button", "select",
+    "input", "textarea", "dialog", "object", "embed", "nav", "footer", "aside", "form", "head",
+    "link", "meta",
+];
+
+/// Elements that close an open `<p>` when they start.
+const CLOSES_PARAGRAPH: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "div",
+    "dl",
+    "fieldset",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "blockquote",
+    "body",
+    "center",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "html",
+    "li",
+    "main",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "tr",
+    "ul",
+];
+
+static UNLIKELY_CANDIDATES: LazyLock<Regex> = LazyLock::new(|| {
+    #[expect(clippy::unwrap_used)]
+    Regex::new(
+        r"(?i)-ad-|ad-break|advert|banner|breadcrumb|combx|comment|community|cookie|consent|cover-wrap|disqus|extra|gdpr|header|legends|menu|modal|newsletter|pager|pagination|popup|promo|related|remark|replies|rss|shoutbox|share|sharing|sidebar|skip-link|social|sponsor|subscribe|toolbar|tweet|twitter|widget",
+    )
+    .unwrap()
+});
+
+static MAYBE_CANDIDATES: LazyLock<Regex> = LazyLock::new(|| {
+    #[expect(clippy::unwrap_used)]
+    Regex::new(r"(?i)and|article|body|column|content|main|post|shadow|story|text").unwrap()
+});
+
+static POSITIVE_WEIGHT: LazyLock<Regex> = LazyLock::new(|| {
+    #[expect(clippy::unwrap_used)]
+    Regex::new(
+        r"(?i)article|body|content|entry|hentry|h-entry|main|page|pagination|post|story|text|blog",
+    )
+    .unwrap()
+});
+
+static NEGATIVE_WEIGHT: LazyLock<Regex> = LazyLock::new(|| {
+    #[expect(clippy::unwrap_used)]
+    Regex::new(
+        r"(?i)-ad-|hidden|^hid$| hid$| hid |^hid |banner|combx|comment|com-|contact|footer|footnote|masthead|media|meta|outbrain|promo|related|scroll|share|shoutbox|sidebar|skyscraper|sponsor|shopping|tags|widget",
+    )
+    .unwrap()
+});
+
+static LANGUAGE_CLASS: LazyLock<Regex> = LazyLock::new(|| {
+    #[expect(clippy::unwrap_used)]
+    Regex::new(r"(?:^|\s)(?:language|lang|highlight-source|brush:)-?\s*([A-Za-z0-9_+#.-]+)")
+        .unwrap()
+});
+
+/// Result of converting an HTML page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HtmlConversion {
+    pub(crate) title: Option<String>,
+    pub(crate) markdown: String,
+}
+
+/// Converts an HTML document into readable Markdown, keeping only the main
+/// content of the page. `base_url` is used to resolve relative links.
+pub(crate) fn html_to_markdown(html: &str, base_url: Option<&Url>) -> HtmlConversion {
+    let dom = Dom::parse(html);
+    let title = dom.title();
+    let base_url = dom
+        .base_href()
+        .and_then(|href| match base_url {
+            Some(base) => base.join(&href).ok(),
+            None => Url::parse(&href).ok(),
+        })
+        .or_else(|| base_url.cloned());
+
+    let body = dom.find_first(Dom::ROOT, "body").unwrap_or(Dom::ROOT);
+    let content_root = dom.main_content(body);
+    let renderer = MarkdownRenderer {
+        dom: &dom,
+        base_url: base_url.as_ref(),
+    };
+    let markdown = renderer.render(content_root);
+
+    HtmlConversion { title, markdown }
+}
+
+#[derive(Debug)]
+enum NodeKind {
+    Element {
+        tag: String,
+        attrs: Vec<(String, String)>,
+    },
+    Text(String),
+}
+
+#[derive(Debug)]
+struct Node {
+    kind: NodeKind,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+/// Minimal arena-backed DOM, good enough for real-world tag soup.
+#[derive(Debug)]
+struct Dom {
+    nodes: Vec<Node>,
+}
+
+impl Dom {
+    const ROOT: usize = 0;
+
+    fn parse(html: &str) -> Self {
+        let mut dom = Dom {
+            nodes: vec![Node {
+                kind: NodeKind::Element {
+                    tag: "#document".to_string(),
+                    attrs: Vec::new(),
+                },
+                parent: None,
+                children: Vec::new(),
+            }],
+        };
+        let mut stack = vec![Self::ROOT];
+        let bytes = html.as_bytes();
+        let mut pos = 0;
+        let mut text_start = 0;
+
+        while pos < bytes.len() {
+            if bytes[pos] != b'<' {
+                pos += 1;
+                continue;
+            }
+            let rest = &html[pos..];
+            let next = bytes.get(pos + 1).copied().unwrap_or(b' ');
+            if rest.starts_with("<!--") {
+                dom.push_text(&stack, &html[text_start..pos]);
+                pos = rest
+                    .find("-->")
+                    .map_or(bytes.len(), |end| pos + end + "-->".len());
+                text_start = pos;
+            } else if next == b'!' || next == b'?' {
+                dom.push_text(&stack, &html[text_start..pos]);
+                pos = rest.find('>').map_or(bytes.len(), |end| pos + end + 1);
+                text_start = pos;
+            } else if next == b'/' && bytes.get(pos + 2).is_some_and(u8::is_ascii_alphabetic) {
+                dom.push_text(&stack, &html[text_start..pos]);
+                let end = rest.find('>').map_or(bytes.len(), |end| pos + end + 1);
+                let name = tag_name(&html[pos + 2..end]);
+                dom.close_element(&mut stack, &name);
+                pos = end;
+                text_start = pos;
+            } else if next.is_ascii_alphabetic() {
+                dom.push_text(&stack, &html[text_start..pos]);
+                let (tag, attrs, self_closing, end) = parse_start_tag(html, pos + 1);
+                pos = end;
+                if RAW_TEXT_TAGS.contains(&tag.as_str()) && !self_closing {
+                    let close = find_closing_tag(html, pos, &tag);
+                    let raw = &html[pos..close.0];
+                    let node = dom.open_element(&mut stack, tag, attrs);
+                    dom.append(node, NodeKind::Text(decode_entities(raw)));
+                    stack.pop();
+                    pos = close.1;
+                } else {
+                    let is_void = VOID_TAGS.contains(&tag.as_str());
+                    dom.open_element(&mut stack, tag, attrs);
+                    if is_void || self_closing {
+                        stack.pop();
+                    }
+                }
+                text_start = pos;
+            } else {
+                pos += 1;
+            }
+        }
+        dom.push_text(&stack, &html[text_start..]);
+        dom
+    }
+
+    fn append(&mut self, parent: usize, kind: NodeKind) -> usize {
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            kind,
+            parent: Some(parent),
+            children: Vec::new(),
+        });
+        self.nodes[parent].children.push(index);
+        index
+    }
+
+    fn push_text(&mut self, stack: &[usize], raw: &str) {
+        if raw.is_empty() {
+            return;
+        }
+        let parent = stack.last().copied().unwrap_or(Self::ROOT);
+        self.append(parent, NodeKind::Text(decode_entities(raw)));
+    }
+
+    fn open_element(
+        &mut self,
+        stack: &mut Vec<usize>,
+        tag: String,
+        attrs: Vec<(String, String)>,
+    ) -> usize {
+        self.apply_implied_end_tags(stack, &tag);
+        if stack.len() >= MAX_DEPTH {
+            stack.pop();
+        }
+        let parent = stack.last().copied().unwrap_or(Self::ROOT);
+        let node = self.append(parent, NodeKind::Element { tag, attrs });
+        stack.push(node);
+        node
+    }
+
+    /// Closes elements whose end tag is optional in HTML, e.g. `<li>` when the
+    /// next `<li>` starts.
+    fn apply_implied_end_tags(&self, stack: &mut Vec<usize>, tag: &str) {
+        let (closes, boundaries): (&[&str], &[&str]) = match tag {
+            "li" => (&["li"], &["ul", "ol", "menu"]),
+            "dt" | "dd" => (&["dt", "dd"], &["dl"]),
+            "tr" => (&["tr", "td", "th"], &["table", "tbody", "thead", "tfoot"]),
+            "td" | "th" => (&["td", "th"], &["tr", "table"]),
+            "tbody" | "thead" | "tfoot" => {
+                (&["tbody", "thead", "tfoot", "tr", "td", "th"], &["table"])
+            }
+            "option" => (&["option"], &["select", "datalist"]),
+            _ if CLOSES_PARAGRAPH.contains(&tag) => {
+                (&["p"], &["button", "td", "th", "li", "blockquote"])
+            }
+            _ => return,
+        };
+        let Some(position) = stack.iter().rposition(|&node| {
+            let name = self.tag(node);
+            closes.contains(&name) || boundaries.contains(&name)
+        }) else {
+            return;
+        };
+        if closes.contains(&self.tag(stack[position])) {
+            stack.truncate(position);
+        }
+    }
+
+    fn close_element(&self, stack: &mut Vec<usize>, tag: &str) {
+        if let Some(position) = stack.iter().rposition(|&node| self.tag(node) == tag)
+            && position > 0
+        {
+            stack.truncate(position);
+        }
+    }
+
+    fn tag(&self, node: usize) -> &str {
+        match &self.nodes[node].kind {
+            NodeKind::Element { tag, .. } => tag,
+            NodeKind::Text(_) => "",
+        }
+    }
+
+    fn attr(&self, node: usize, name: &str) -> Option<&str> {
+        match &self.nodes[node].kind {
+            NodeKind::Element { attrs, .. } => attrs
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str()),
+            NodeKind::Text(_) => None,
+        }
+    }
+
+    fn children(&self, node: usize) -> &[usize] {
+        &self.nodes[node].children
+    }
+
+    fn find_first(&self, node: usize, tag: &str) -> Option<usize> {
+        self.children(node).iter().find_map(|&child| {
+            if self.tag(child) == tag {
+                Some(child)
+            } else {
+                self.find_first(child, tag)
+            }
+        })
+    }
+
+    fn find_all(&self, node: usize, predicate: &dyn Fn(usize) -> bool, out: &mut Vec<usize>) {
+        for &child in self.children(node) {
+            if predicate(child) {
+                out.push(child);
+            }
+            self.find_all(child, predicate, out);
+        }
+    }
+
+    fn title(&self) -> Option<String> {
+        let from_title = self
+            .find_first(Self::ROOT, "title")
+            .map(|node| collapse_whitespace(&self.text_content(node)));
+        let from_og = || {
+            let mut metas = Vec::new();
+            self.find_all(Self::ROOT, &|node| self.tag(node) == "meta", &mut metas);
+            metas.into_iter().find_map(|node| {
+                let property = self.attr(node, "property").or(self.attr(node, "name"))?;
+                (property == "og:title")
+                    .then(|| self.attr(node, "content").map(collapse_whitespace))
+                    .flatten()
+            })
+        };
+        from_title
+            .filter(|title| !title.is_empty())
+            .or_else(from_og)
+            .filter(|title| !title.is_empty())
+    }
+
+    fn base_href(&self) -> Option<String> {
+        let base = self.find_first(Self::ROOT, "base")?;
+        self.attr(base, "href").map(str::to_string)
+    }
+
+    fn text_content(&self, node: usize) -> String {
+        let mut out = String::new();
+        self.collect_text(node, &mut out);
+        out
+    }
+
+    fn collect_text(&self, node: usize, out: &mut String) {
+        match &self.nodes[node].kind {
+            NodeKind::Text(text) => out.push_str(text),
+            NodeKind::Element { .. } => {
+                for &child in self.children(node) {
+                    self.collect_text(child, out);
+                }
+            }
+        }
+    }
+
+    /// Length of visible text below `node`, skipping pruned subtrees.
+    fn visible_text_len(&self, node: usize) -> usize {
+        match &self.nodes[node].kind {
+            NodeKind::Text(text) => text.split_whitespace().map(|word| word.len() + 1).sum(),
+            NodeKind::Element { .. } if self.is_pruned(node) => 0,
+            NodeKind::Element { .. } => self
+                .children(node)
+                .iter()
+                .map(|&child| self.visible_text_len(child))
+                .sum(),
+        }
+    }
+
+    fn link_text_len(&self, node: usize) -> usize {
+        if self.is_pruned(node) {
+            return 0;
+        }
+        if self.tag(node) == "a" {
+            return self.visible_text_len(node);
+        }
+        self.children(node)
+            .iter()
+            .map(|&child| self.link_text_len(child))
+            .sum()
+    }
+
+    fn link_density(&self, node: usize) -> f64 {
+        let total = self.visible_text_len(node);
+        if total == 0 {
+            return 0.0;
+        }
+        self.link_text_len(node) as f64 / total as f64
+    }
+
+    fn class_and_id(&self, node: usize) -> String {
+        let class = self.attr(node, "class").unwrap_or_default();
+        let id = self.attr(node, "id").unwrap_or_default();
+        format!("{class} {id}")
+    }
+
+    /// Whether the element should be dropped from the rendered output.
+    fn is_pruned(&self, node: usize) -> bool {
+        let tag = self.tag(node);
+        if DROPPED_TAGS.contains(&tag) {
+            return true;
+        }
+        if self.attr(node, "hidden").is_some()
+            || self.attr(node, "aria-hidden") == Some("true")
+            || self.attr(node, "style").is_some_and(|style| {
+                let style = style.to_ascii_lowercase().replace(' ', "");
+                style.contains("display:none") || style.contains("visibility:hidden")
+            })
+        {
+            return true;
+        }
+        if matches!(
+            self.attr(node, "role"),
+            Some(
+                "navigation"
+                    | "banner"
+                    | "contentinfo"
+                    | "complementary"
+                    | "search"
+                    | "dialog"
+                    | "menu"
+                    | "menubar"
+            )
+        ) {
+            return true;
+        }
+        if matches!(
+            tag,
+            "body" | "html" | "article" | "main" | "#document" | "a"
+        ) {
+            return false;
+        }
+        let class_and_id = self.class_and_id(node);
+        UNLIKELY_CANDIDATES.is_match(&class_and_id)
+            && !MAYBE_CANDIDATES.is_match(&class_and_id)
+            && self.find_first(node, "pre").is_none()
+            && self.find_first(node, "table").is_none()
+    }
+
+    /// Picks the subtree that most likely holds the page's main content.
+    fn main_content(&self, body: usize) -> usize {
+        let body_len = self.visible_text_len(body);
+        let pruned = self.pruned_below(body);
+
+        let mut semantic = Vec::new();
+        self.find_all(
+            body,
+            &|node| {
+                matches!(self.tag(node), "main" | "article")
+                    || self.attr(node, "role") == Some("main")
+            },
+            &mut semantic,
+        );
+        if let Some(best) = semantic
+            .into_iter()
+            .filter(|&node| !pruned[node])
+            .max_by_key(|&node| self.visible_text_len(node))
+            && self.visible_text_len(best) >= MIN_SEMANTIC_CONTAINER_CHARS.min(body_len)
+        {
+            return best;
+        }
+
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+        let mut paragraphs = Vec::new();
+        self.find_all(
+            body,
+            &|node| match self.tag(node) {
+                "p" | "pre" | "td" | "blockquote" => true,
+                "div" => !self
+                    .children(node)
+                    .iter()
+                    .any(|&child| BLOCK_TAGS.contains(&self.tag(child))),
+                _ => false,
+            },
+            &mut paragraphs,
+        );
+        for paragraph in paragraphs {
+            if pruned[paragraph] {
+                continue;
+            }
+            let text = self.text_content(paragraph);
+            let text_len = collapse_whitespace(&text).len();
+            if text_len < MIN_PARAGRAPH_CHARS {
+                continue;
+            }
+            let content_score =
+                1.0 + text.matches(',').count() as f64 + (text_len as f64 / 100.0).min(3.0);
+            let mut ancestor = self.nodes[paragraph].parent;
+            for level in 0..3 {
+                let Some(node) = ancestor else {
+                    break;
+                };
+                let divider = match level {
+                    0 => 1.0,
+                    1 => 2.0,
+                    _ => 6.0,
+                };
+                let score = scores
+                    .entry(node)
+                    .or_insert_with(|| self.initial_score(node));
+                *score += content_score / divider;
+                if node == body {
+                    break;
+                }
+                ancestor = self.nodes[node].parent;
+            }
+        }
+
+        scores
+            .into_iter()
+            .map(|(node, score)| (node, score * (1.0 - self.link_density(node))))
+            .filter(|&(node, _)| self.visible_text_len(node) >= MIN_CANDIDATE_CHARS.min(body_len))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(body, |(node, _)| node)
+    }
+
+    fn initial_score(&self, node: usize) -> f64 {
+        let tag_score = match self.tag(node) {
+            "div" | "article" | "section" | "main" => 5.0,
+            "pre" | "td" | "blockquote" => 3.0,
+            "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+            _ => 0.0,
+        };
+        let class_and_id = self.class_and_id(node);
+        let mut class_score = 0.0;
+        if POSITIVE_WEIGHT.is_match(&class_and_id) {
+            class_score += 25.0;
+        }
+        if NEGATIVE_WEIGHT.is_match(&class_and_id) {
+            class_score -= 25.0;
+        }
+        tag_score + class_score
+    }
+
+    /// For every node below `root`, whether it or an ancestor up to `root` is
+    /// pruned. Parents are created before their children, so one pass in
+    /// arena order sees each parent first.
+    fn pruned_below(&self, root: usize) -> Vec<bool> {
+        let mut pruned = vec![false; self.nodes.len()];
+        for index in root..self.nodes.len() {
+            let parent_pruned = index != root
+                && self.nodes[index]
+                    .parent
+                    .is_some_and(|parent| pruned[parent]);
+            pruned[index] = parent_pruned
+                || (matches!(self.nodes[index].kind, NodeKind::Element { .. })
+                    && self.is_pruned(index));
+        }
+        pruned
+    }
+}
+
+struct MarkdownRenderer<'a> {
+    dom: &'a Dom,
+    base_url: Option<&'a Url>,
+}
+
+impl MarkdownRenderer<'_> {
+    fn render(&self, root: usize) -> String {
+        let mut blocks = Vec::new();
+        self.render_container(root, &mut blocks);
+        let mut markdown = blocks.join("\n\n");
+        markdown.truncate(markdown.trim_end().len());
+        markdown
+    }
+
+    /// Renders the children of a block-level element, grouping runs of inline
+    /// content into paragraphs.
+    fn render_container(&self, node: usize, blocks: &mut Vec<String>) {
+        let mut inline = String::new();
+        for &child in self.dom.children(node) {
+            if self.is_block(child) {
+                push_paragraph(blocks, &inline);
+                inline.clear();
+                self.render_block(child, blocks);
+            } else {
+                self.render_inline(child, &mut inline);
+            }
+        }
+        push_paragraph(blocks, &inline);
+    }
+
+    fn is_block(&self, node: usize) -> bool {
+        matches!(self.dom.nodes[node].kind, NodeKind::Element { .. })
+            && BLOCK_TAGS.contains(&self.dom.tag(node))
+    }
+
+    fn render_block(&self, node: usize, blocks: &mut Vec<String>) {
+        if self.dom.is_pruned(node) {
+            return;
+        }
+        let tag = self.dom.tag(node);
+        match tag {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = usize::from(tag.as_bytes()[1] - b'0');
+                let text = self.inline_text(node).replace('\n', " ");
+                if !text.is_empty() {
+                    blocks.push(format!("{} {text}", "#".repeat(level)));
+                }
+            }
+            "p" | "summary" | "figcaption" | "dt" => {
+                let text = self.inline_text(node);
+                if tag == "dt" && !text.is_empty() {
+                    blocks.push(format!("**{text}**"));
+                } else {
+                    push_paragraph(blocks, &text);
+                }
+            }
+            "pre" => {
+                if let Some(block) = self.render_code_block(node) {
+                    blocks.push(block);
+                }
+            }
+            "ul" | "ol" => {
+                if let Some(list) = self.render_list(node) {
+                    blocks.push(list);
+                }
+            }
+            "blockquote" => {
+                let mut inner = Vec::new();
+                self.render_container(node, &mut inner);
+                if !inner.is_empty() {
+                    blocks.push(prefix_lines(&inner.join("\n\n"), "> ", "> "));
+                }
+            }
+            "table" => self.render_table(node, blocks),
+            "hr" => blocks.push("---".to_string()),
+            "dd" => {
+                let mut inner = Vec::new();
+                self.render_container(node, &mut inner);
+                if !inner.is_empty() {
+                    blocks.push(prefix_lines(&inner.join("\n\n"), ": ", "  "));
+                }
+            }
+            _ => self.render_container(node, blocks),
+        }
+    }
+
+    fn render_code_block(&self, node: usize) -> Option<String> {
+        let code = self.dom.text_content(node);
+        let code = code.trim_matches('\n');
+        if code.trim().is_empty() {
+            return None;
+        }
+        let language = self.code_language(node).unwrap_or_default();
+        let fence = code_fence(code, '`', 3);
+        Some(format!("{fence}{language}\n{code}\n{fence}"))
+    }
+
+    fn code_language(&self, node: usize) -> Option<String> {
+        let mut candidates = vec![node];
+        if let Some(code) = self.dom.find_first(node, "code") {
+            candidates.push(code);
+        }
+        candidates.into_iter().find_map(|candidate| {
+            if let Some(language) = self.dom.attr(candidate, "data-lang") {
+                return Some(language.to_string());
+            }
+            let class = self.dom.attr(candidate, "class")?;
+            LANGUAGE_CLASS
+                .captures(class)
+                .and_then(|captures| captures.get(1))
+                .map(|language| language.as_str().to_string())
+        })
+    }
+
+    fn render_list(&self, node: usize) -> Option<String> {
+        let ordered = self.dom.tag(node) == "ol";
+        let mut number = self
+            .dom
+            .attr(node, "start")
+            .and_then(|start| start.trim().parse::<usize>().ok())
+            .unwrap_or(1);
+        let mut items = Vec::new();
+        for &child in self.dom.children(node) {
+            if self.dom.tag(child) != "li" || self.dom.is_pruned(child) {
+                continue;
+            }
+            let mut inner = Vec::new();
+            self.render_container(child, &mut inner);
+            let content = inner.join("\n");
+            if content.trim().is_empty() {
+                continue;
+            }
+            let marker = if ordered {
+                format!("{number}. ")
+            } else {
+                "- ".to_string()
+            };
+            number += 1;
+            let indent = " ".repeat(marker.len());
+            items.push(prefix_lines(&content, &marker, &indent));
+        }
+        (!items.is_empty()).then(|| items.join("\n"))
+    }
+
+    fn render_table(&self, node: usize, blocks: &mut Vec<String>) {
+        let mut rows = Vec::new();
+        self.dom
+            .find_all(node, &|child| self.dom.tag(child) == "tr", &mut rows);
+        rows.retain(|&row| self.nearest_table(row) == Some(node));
+
+        let cells: Vec<Vec<usize>> = rows
+            .iter()
+            .map(|&row| {
+                self.dom
+                    .children(row)
+                    .iter()
+                    .copied()
+                    .filter(|&cell| matches!(self.dom.tag(cell), "td" | "th"))
+                    .collect()
+            })
+            .collect();
+        let columns = cells.iter().map(Vec::len).max().unwrap_or(0);
+        let has_nested_blocks = cells.iter().flatten().any(|&cell| {
+            let mut nested = Vec::new();
+            self.dom.find_all(
+                cell,
+                &|child| {
+                    matches!(
+                        self.dom.tag(child),
+                        "table" | "pre" | "ul" | "ol" | "p" | "div"
+                    )
+                },
+                &mut nested,
+            );
+            !nested.is_empty()
+        });
+
+        // Layout tables (single column or holding block content) read better
+        // as plain blocks than as a Markdown table.
+        if columns < 2 || has_nested_blocks {
+            for cell in cells.into_iter().flatten() {
+                self.render_container(cell, blocks);
+            }
+            return;
+        }
+
+        let mut lines = Vec::new();
+        for (index, row) in cells.iter().enumerate() {
+            let mut texts: Vec<String> = row
+                .iter()
+                .map(|&cell| {
+                    self.inline_text(cell)
+                        .replace('\n', " ")
+                        .replace('|', "\\|")
+                })
+                .collect();
+            texts.resize(columns, String::new());
+            lines.push(format!("| {} |", texts.join(" | ")));
+            if index == 0 {
+                lines.push(format!("|{}", " --- |".repeat(columns)));
+            }
+        }
+        blocks.push(lines.join("\n"));
+    }
+
+    fn nearest_table(&self, node: usize) -> Option<usize> {
+        let mut current = self.dom.nodes[node].parent;
+        while let Some(index) = current {
+            if self.dom.tag(index) == "table" {
+                return Some(index);
+            }
+            current = self.dom.nodes[index].parent;
+        }
+        None
+    }
+
+    fn inline_text(&self, node: usize) -> String {
+        let mut out = String::new();
+        for &child in self.dom.children(node) {
+            self.render_inline(child, &mut out);
+        }
+        normalize_inline(&out)
+    }
+
+    fn render_inline(&self, node: usize, out: &mut String) {
+        let tag = match &self.dom.nodes[node].kind {
+            NodeKind::Text(text) => {
+                push_collapsed(out, text);
+                return;
+            }
+            NodeKind::Element { tag, .. } => tag.as_str(),
+        };
+        if self.dom.is_pruned(node) {
+            return;
+        }
+        match tag {
+            "br" => out.push('\n'),
+            "img" => {
+                let alt = collapse_whitespace(self.dom.attr(node, "alt").unwrap_or_default());
+                match self.dom.attr(node, "src").and_then(|src| self.resolve(src)) {
+                    Some(src) => out.push_str(&format!("![{alt}]({src})")),
+                    None if !alt.is_empty() => out.push_str(&alt),
+                    None => {}
+                }
+            }
+            "a" => {
+                let text = self.inline_text(node);
+                let href = self
+                    .dom
+                    .attr(node, "href")
+                    .and_then(|href| self.resolve(href));
+                match href {
+                    Some(href) if !text.is_empty() => {
+                        out.push_str(&format!("[{}]({href})", text.replace('\n', " ")));
+                    }
+                    _ => out.push_str(&text),
+                }
+            }
+            "code" | "kbd" | "samp" | "tt" => {
+                let code = collapse_whitespace(&self.dom.text_content(node));
+                if !code.is_empty() {
+                    let fence = code_fence(&code, '`', 1);
+                    let padding = if code.starts_with('`') || code.ends_with('`') {
+                        " "
+                    } else {
+                        ""
+                    };
+                    out.push_str(&format!("{fence}{padding}{code}{padding}{fence}"));
+                }
+            }
+            "strong" | "b" => self.wrap_inline(node, "**", out),
+            "em" | "i" | "cite" => self.wrap_inline(node, "*", out),
+            "del" | "s" | "strike" => self.wrap_inline(node, "~~", out),
+            "pre" => {
+                // `<pre>` nested in inline context; keep it readable as code.
+                if let Some(block) = self.render_code_block(node) {
+                    out.push('\n');
+                    out.push_str(&block);
+                    out.push('\n');
+                }
+            }
+            _ => {
+                let block = BLOCK_TAGS.contains(&tag);
+                if block {
+                    out.push('\n');
+                }
+                for &child in self.dom.children(node) {
+                    self.render_inline(child, out);
+                }
+                if block {
+                    out.push('\n');
+                }
+            }
+        }
+    }
+
+    fn wrap_inline(&self, node: usize, marker: &str, out: &mut String) {
+        let text = self.inline_text(node);
+        if text.is_empty() {
+            return;
+        }
+        out.push_str(&format!("{marker}{text}{marker}"));
+    }
+
+    fn resolve(&self, href: &str) -> Option<String> {
+        let href = href.trim();
+        if href.is_empty() || href.starts_with('#') {
+            return None;
+        }
+        let lowered = href.to_ascii_lowercase();
+        if lowered.starts_with("javascript:") || lowered.starts_with("data:") {
+            return None;
+        }
+        match self.base_url {
+            Some(base) => base.join(href).ok().map(String::from),
+            None => Some(href.to_string()),
+        }
+    }
+}
+
+fn push_paragraph(blocks: &mut Vec<String>, inline: &str) {
+    let text = normalize_inline(inline);
+    if !text.is_empty() {
+        blocks.push(text);
+    }
+}
+
+/// Appends `text` with whitespace runs collapsed to single spaces.
+fn push_collapsed(out: &mut String, text: &str) {
+    for (index, word) in text.split_whitespace().enumerate() {
+        if (index > 0 || text.starts_with(char::is_whitespace))
+            && !out.is_empty()
+            && !out.ends_with([' ', '\n'])
+        {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    if text.ends_with(char::is_whitespace) && !out.is_empty() && !out.ends_with([' ', '\n']) {
+        out.push(' ');
+    }
+}
+
+/// Trims every line and removes runs of blank lines in inline content.
+fn normalize_inline(text: &str) -> String {
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() && lines.last().is_none_or(|last: &String| last.is_empty()) {
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+fn prefix_lines(text: &str, first: &str, rest: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let prefix = if index == 0 { first } else { rest };
+            if line.is_empty() {
+                prefix.trim_end().to_string()
+            } else {
+                format!("{prefix}{line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns a fence made of `marker` that is longer than any run of `marker`
+/// inside `content`.
+fn code_fence(content: &str, marker: char, min_len: usize) -> String {
+    let mut longest = 0;
+    let mut current = 0;
+    for ch in content.chars() {
+        if ch == marker {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    let len = if longest >= min_len {
+        longest + 1
+    } else {
+        min_len
+    };
+    marker.to_string().repeat(len)
+}
+
+pub(crate) fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn tag_name(raw: &str) -> String {
+    raw.trim_start()
+        .split(|ch: char| ch.is_ascii_whitespace() || ch == '/' || ch == '>')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Parses a start tag beginning right after `<`. Returns the tag name,
+/// attributes, whether it was self-closing and the position after `>`.
+fn parse_start_tag(html: &str, start: usize) -> (String, Vec<(String, String)>, bool, usize) {
+    let bytes = html.as_bytes();
+    let mut pos = start;
+    while pos < bytes.len()
+        && !bytes[pos].is_ascii_whitespace()
+        && bytes[pos] != b'>'
+        && bytes[pos] != b'/'
+    {
+        pos += 1;
+    }
+    let tag = html[start..pos].to_ascii_lowercase();
+    let mut attrs = Vec::new();
+    let mut self_closing = false;
+
+    loop {
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if pos >= bytes.len() {
+            return (tag, attrs, self_closing, bytes.len());
+        }
+        match bytes[pos] {
+            b'>' => return (tag, attrs, self_closing, pos + 1),
+            b'/' => {
+                self_closing = bytes.get(pos + 1) == Some(&b'>');
+                pos += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        let name_start = pos;
+        while pos < bytes.len()
+            && !bytes[pos].is_ascii_whitespace()
+            && !matches!(bytes[pos], b'=' | b'>' | b'/')
+        {
+            pos += 1;
+        }
+        let name = html[name_start..pos].to_ascii_lowercase();
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        let mut value = String::new();
+        if bytes.get(pos) == Some(&b'=') {
+            pos += 1;
+            while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            match bytes.get(pos) {
+                Some(&quote @ (b'"' | b'\'')) => {
+                    let value_start = pos + 1;
+                    let value_end = html[value_start..]
+                        .find(quote as char)
+                        .map_or(bytes.len(), |end| value_start + end);
+                    value = decode_entities(&html[value_start..value_end]);
+                    pos = (value_end + 1).min(bytes.len());
+                }
+                Some(_) => {
+                    let value_start = pos;
+                    while pos < bytes.len()
+                        && !bytes[pos].is_ascii_whitespace()
+                        && bytes[pos] != b'>'
+                    {
+                        pos += 1;
+                    }
+                    value = decode_entities(&html[value_start..pos]);
+                }
+                None => {}
+            }
+        }
+        if !name.is_empty() && !attrs.iter().any(|(existing, _)| *existing == name) {
+            attrs.push((name, value));
+        }
+    }
+}
+
+/// Finds the `</tag>` that ends a raw text element. Returns the start of the
+/// closing tag and the position after it.
+fn find_closing_tag(html: &str, from: usize, tag: &str) -> (usize, usize) {
+    let bytes = html.as_bytes();
+    let close_start = html[from..]
+        .match_indices("</")
+        .map(|(offset, _)| from + offset)
+        .find(|&start| {
+            bytes
+                .get(start + 2..start + 2 + tag.len())
+                .is_some_and(|name| name.eq_ignore_ascii_case(tag.as_bytes()))
+        });
+    match close_start {
+        Some(close_start) => {
+            let close_end = html[close_start..]
+                .find('>')
+                .map_or(html.len(), |end| close_start + end + 1);
+            (close_start, close_end)
+        }
+        None => (html.len(), html.len()),
+    }
+}
+
+/// Decodes HTML character references. Unknown named references are kept as-is.
+pub(crate) fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let end = rest[1..]
+            .char_indices()
+            .take(32)
+            .find(|&(_, ch)| ch == ';' || ch == '&' || ch.is_whitespace())
+            .filter(|&(index, ch)| ch == ';' && index > 0)
+            .map(|(index, _)| index + 1);
+        let decoded = end.and_then(|end| decode_entity(&rest[1..end]).map(|ch| (ch, end)));
+        match decoded {
+            Some((decoded, end)) => {
+                out.push_str(&decoded);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(entity: &str) -> Option<String> {
+    if let Some(numeric) = entity.strip_prefix('#') {
+        let code = match numeric.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => numeric.parse::<u32>().ok()?,
+        };
+        return Some(char::from_u32(code).unwrap_or('\u{FFFD}').to_string());
+    }
+    let decoded = match entity {
+        "amp" => "&",
+        "lt" => "<",
+        "gt" => ">",
+        "quot" => "\"",
+        "apos" => "'",
+        "nbsp" => " ",
+        "ensp" | "emsp" | "thinsp" => " ",
+        "shy" | "zwj" | "zwnj" => "",
+        "ndash" => "–",
+        "mdash" => "—",
+        "hellip" => "…",
+        "lsquo" => "‘",
+        "rsquo" => "’",
+        "ldquo" => "“",
+        "rdquo" => "”",
+        "laquo" => "«",
+        "raquo" => "»",
+        "bull" => "•",
+        "middot" => "·",
+        "copy" => "©",
+        "reg" => "®",
+        "trade" => "™",
+        "deg" => "°",
+        "times" => "×",
+        "divide" => "÷",
+        "plusmn" => "±",
+        "euro" => "€",
+        "pound" => "£",
+        "yen" => "¥",
+        "cent" => "¢",
+        "sect" => "§",
+        "para" => "¶",
+        "larr" => "←",
+        "rarr" => "→",
+        "uarr" => "↑",
+        "darr" => "↓",
+        "le" => "≤",
+        "ge" => "≥",
+        "ne" => "≠",
+        _ => return None,
+    };
+    Some(decoded.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn convert(html: &str) -> HtmlConversion {
+        let base = Url::parse("https://example.com/docs/page.html").expect("url");
+        html_to_markdown(html, Some(&base))
+    }
+
+    #[test]
+    fn extracts_main_content_and_drops_boilerplate() {
+        let html = r#"<!doctype html>
+<html><head><title>Async &amp; Await</title>
+<script>var tracking = "<p>not content</p>";</script>
+<style>body { color: red; }</style></head>
+<body>
+<nav><a href="/">Home</a> <a href="/about">About</a></nav>
+<div class="cookie-banner">We use cookies</div>
+<main>
+<h1>Async and await</h1>
+<p>Rust's <code>async</code> functions return futures, which do nothing until they are polled by an executor.</p>
+<p>See the <a href="../book/ch17.html">async chapter</a> for details, including pinning, streams, and cancellation.</p>
+</main>
+<footer>Copyright 2024</footer>
+</body></html>"#;
+
+        let converted = convert(html);
+        assert_eq!(converted.title.as_deref(), Some("Async & Await"));
+        assert_eq!(
+            converted.markdown,
+            "# Async and await\n\n\
+Rust's `async` functions return futures, which do nothing until they are polled by an executor.\n\n\
+See the [async chapter](https://example.com/book/ch17.html) for details, including pinning, streams, and cancellation."
+        );
+    }
+
+    #[test]
+    fn preserves_code_blocks_with_language() {
+        let html = r#"<article><p>Spawn a task like this, it runs on the runtime's worker threads:</p>
+<pre><code class="language-rust">tokio::spawn(async move {
+    println!("hi &lt;there&gt;");
+});</code></pre></article>"#;
+
+        let converted = convert(html);
+        assert_eq!(
+            converted.markdown,
+            "Spawn a task like this, it runs on the runtime's worker threads:\n\n\
+```rust\ntokio::spawn(async move {\n    println!(\"hi <there>\");\n});\n```"
+        );
+    }
+
+    #[test]
+    fn renders_lists_tables_and_quotes() {
+        let html = r#"<body>
+<ul><li>first<li>second <b>bold</b><ol><li>nested</ol></ul>
+<table><tr><th>Name</th><th>Value</th></tr><tr><td>a|b</td><td>1</td></tr></table>
+<blockquote><p>quoted text</p></blockquote>
+</body>"#;
+
+        let converted = convert(html);
+        assert_eq!(
+            converted.markdown,
+            "- first\n- second **bold**\n  1. nested\n\n\
+| Name | Value |\n| --- | --- |\n| a\\|b | 1 |\n\n\
+> quoted text"
+        );
+    }
+
+    #[test]
+    fn scores_content_when_no_semantic_container() {
+        let paragraph =
+            "This paragraph has plenty of words, commas, and sentences so that it scores well.";
+        let html = format!(
+            r#"<body><div id="sidebar"><p>{paragraph}</p></div>
+<div class="menu"><a href="/a">Link one</a> <a href="/b">Link two</a></div>
+<div class="post-body"><p>{paragraph}</p><p>{paragraph}</p><p>{paragraph}</p><p>{paragraph}</p></div></body>"#
+        );
+
+        let converted = convert(&html);
+        assert_eq!(converted.markdown, [paragraph; 4].join("\n\n"));
+    }
+
+    #[test]
+    fn resolves_relative_images_and_skips_data_uris() {
+        let html = r#"<p>Diagram: <img src="img/arch.png" alt="architecture"> <img src="data:image/png;base64,AAAA" alt="inline"></p>"#;
+
+        let converted = convert(html);
+        assert_eq!(
+            converted.markdown,
+            "Diagram: ![architecture](https://example.com/docs/img/arch.png) inline"
+        );
+    }
+
+    #[test]
+    fn flattens_deeply_nested_documents() {
+        let depth = 10_000;
+        let html = format!(
+            "{}{}<p>Deep paragraph with enough words to count as the page content.</p>{}{}",
+            "<div>".repeat(depth),
+            "<span><b>".repeat(depth),
+            "</b></span>".repeat(depth),
+            "</div>".repeat(depth),
+        );
+
+        let converted = convert(&html);
+        assert_eq!(
+            converted.markdown,
+            "Deep paragraph with enough words to count as the page content."
+        );
+    }
+
+    #[test]
+    fn decodes_entities() {
+        assert_eq!(
+            decode_entities("a &lt;b&gt; &#169; &#x263A; &unknown; & c"),
+            "a <b> © ☺ &unknown; & c"
+        );
+    }
+}
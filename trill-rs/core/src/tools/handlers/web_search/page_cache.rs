@@ -0,0 +1,130 @@
+//! Session-scoped cache of converted pages.
+//!
+//! Reading a long document takes several `open_page` calls (one per chunk)
+//! plus `find_in_page` lookups. Keeping the converted document for the life of
+//! the session means those follow-up calls neither refetch nor reconvert it.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use indexmap::IndexMap;
+use url::Url;
+
+use super::document::PageDocument;
+
+/// Maximum number of documents kept per session.
+const MAX_CACHED_PAGES: usize = 32;
+
+/// Documents older than this are fetched again.
+const PAGE_TTL: Duration = Duration::from_secs(15 * 60);
+
+struct CachedPage {
+    document: Arc<PageDocument>,
+    /// Where the page was served from after redirects, so a hit can be
+    /// checked against the policy like a fresh fetch.
+    final_url: Url,
+    fetched_at: Instant,
+}
+
+/// Least-recently-used cache of fetched pages keyed by requested URL.
+#[derive(Default)]
+pub(crate) struct WebPageCache {
+    pages: Mutex<IndexMap<String, CachedPage>>,
+}
+
+impl WebPageCache {
+    /// Returns the document for `url` and the URL that served it.
+    pub(crate) fn get(&self, url: &str) -> Option<(Arc<PageDocument>, Url)> {
+        let mut pages = self
+            .pages
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let page = pages.shift_remove(url)?;
+        if page.fetched_at.elapsed() > PAGE_TTL {
+            return None;
+        }
+        let hit = (Arc::clone(&page.document), page.final_url.clone());
+        // Re-insert at the back so it becomes the most recently used entry.
+        pages.insert(url.to_string(), page);
+        Some(hit)
+    }
+
+    pub(crate) fn insert(&self, url: String, final_url: Url, document: Arc<PageDocument>) {
+        let mut pages = self
+            .pages
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        pages.shift_remove(&url);
+        pages.insert(
+            url,
+            CachedPage {
+                document,
+                final_url,
+                fetched_at: Instant::now(),
+            },
+        );
+        while pages.len() > MAX_CACHED_PAGES {
+            pages.shift_remove_index(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::handlers::web_search::document::DocumentKind;
+    use pretty_assertions::assert_eq;
+
+    fn document(content: &str) -> Arc<PageDocument> {
+        Arc::new(PageDocument {
+            title: None,
+            kind: DocumentKind::Text,
+            content: content.to_string(),
+        })
+    }
+
+    fn insert(cache: &WebPageCache, url: &str, content: &str) {
+        let final_url = Url::parse(url).expect("valid url");
+        cache.insert(url.to_string(), final_url, document(content));
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = WebPageCache::default();
+        for index in 0..MAX_CACHED_PAGES {
+            insert(&cache, &format!("https://example.com/{index}"), "page");
+        }
+        // Touch the oldest entry so the second one becomes the eviction victim.
+        assert!(cache.get("https://example.com/0").is_some());
+        insert(&cache, "https://example.com/new", "new");
+
+        assert!(cache.get("https://example.com/0").is_some());
+        assert!(cache.get("https://example.com/1").is_none());
+        assert_eq!(
+            cache
+                .get("https://example.com/new")
+                .map(|(document, _)| document.content.clone()),
+            Some("new".to_string())
+        );
+    }
+
+    #[test]
+    fn keeps_the_url_that_served_the_page() {
+        let cache = WebPageCache::default();
+        let final_url = Url::parse("https://www.example.com/doc").expect("valid url");
+        cache.insert(
+            "https://example.com/doc".to_string(),
+            final_url.clone(),
+            document("page"),
+        );
+
+        assert_eq!(
+            cache
+                .get("https://example.com/doc")
+                .map(|(_, served_from)| served_from),
+            Some(final_url)
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! Splits converted documents into numbered pages that each fit the turn's
+//! output budget, so long documents can be read chunk by chunk.
+
+use std::ops::Range;
+
+/// Pages never shrink below this many bytes, even under a tiny budget.
+const MIN_PAGE_BYTES: usize = 1_000;
+
+/// Derives the page size from the tool output byte budget, leaving headroom
+/// for the JSON envelope around `page_content`.
+pub(crate) fn page_bytes_for_budget(byte_budget: usize) -> usize {
+    (byte_budget / 10 * 9).max(MIN_PAGE_BYTES)
+}
+
+/// Returns the byte ranges of each page. Pages break at a paragraph boundary
+/// when one falls in the second half of the window, then at a line boundary,
+/// and otherwise at the last char boundary that fits.
+pub(crate) fn split_pages(content: &str, page_bytes: usize) -> Vec<Range<usize>> {
+    let page_bytes = page_bytes.max(1);
+    let mut pages = Vec::new();
+    let mut start = 0;
+    while start < content.len() {
+        if content.len() - start <= page_bytes {
+            pages.push(start..content.len());
+            break;
+        }
+        let hard_end = floor_char_boundary(content, start + page_bytes);
+        let hard_end = if hard_end <= start {
+            // A single char wider than the page; take it whole.
+            ceil_char_boundary(content, start + 1)
+        } else {
+            hard_end
+        };
+        let window = &content[start..hard_end];
+        let min_break = window.len() / 2;
+        let end = window
+            .rfind("\n\n")
+            .filter(|index| *index >= min_break)
+            .map(|index| start + index + 2)
+            .or_else(|| {
+                window
+                    .rfind('\n')
+                    .filter(|index| *index >= min_break)
+                    .map(|index| start + index + 1)
+            })
+            .unwrap_or(hard_end);
+        pages.push(start..end);
+        start = end;
+    }
+    pages
+}
+
+/// Returns the 1-based page containing `offset`.
+pub(crate) fn page_for_offset(pages: &[Range<usize>], offset: usize) -> usize {
+    pages
+        .iter()
+        .position(|range| offset < range.end)
+        .unwrap_or(pages.len().saturating_sub(1))
+        + 1
+}
+
+fn floor_char_boundary(content: &str, index: usize) -> usize {
+    let mut index = index.min(content.len());
+    while !content.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_char_boundary(content: &str, index: usize) -> usize {
+    let mut index = index.min(content.len());
+    while !content.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn pages(content: &str, page_bytes: usize) -> Vec<&str> {
+        split_pages(content, page_bytes)
+            .into_iter()
+            .map(|range| &content[range])
+            .collect()
+    }
+
+    #[test]
+    fn prefers_paragraph_then_line_breaks() {
+        let content = "first paragraph\n\nsecond para\nstill second\nthird line";
+        assert_eq!(
+            pages(content, 30),
+            vec![
+                "first paragraph\n\n",
+                "second para\nstill second\n",
+                "third line"
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_long_lines_on_char_boundaries() {
+        let content = "é".repeat(5);
+        assert_eq!(pages(&content, 3), vec!["é", "é", "é", "é", "é"]);
+        assert_eq!(pages("", 10), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn maps_offsets_to_pages() {
+        let ranges = split_pages("aaaa\nbbbb\ncccc", 5);
+        assert_eq!(ranges, vec![0..5, 5..10, 10..14]);
+        assert_eq!(page_for_offset(&ranges, 0), 1);
+        assert_eq!(page_for_offset(&ranges, 5), 2);
+        assert_eq!(page_for_offset(&ranges, 13), 3);
+    }
+}
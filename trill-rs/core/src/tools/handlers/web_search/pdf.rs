@@ -0,0 +1,404 @@
+//! Best-effort text extraction from PDF documents.
+//!
+//! This is not a full PDF renderer: it inflates content streams and collects
+//! the strings passed to the text-showing operators (`Tj`, `TJ`, `'`, `"`),
+//! inserting line breaks on text positioning operators. That covers the
+//! majority of text-based PDFs (papers, specs, manuals). Scanned documents and
+//! fonts without a usable encoding yield no text, which callers report to the
+//! model instead of returning garbage.
+
+use std::io::Read;
+
+use flate2::read::ZlibDecoder;
+
+/// Upper bound on the inflated size of a single stream.
+const MAX_INFLATED_STREAM_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Fraction of characters that must be printable for a decoded string to be
+/// kept. Strings from fonts with custom encodings decode to control bytes.
+const MIN_PRINTABLE_RATIO: f64 = 0.85;
+
+/// Extracts the text of a PDF. Returns `None` when nothing readable is found.
+pub(crate) fn extract_pdf_text(bytes: &[u8]) -> Option<String> {
+    let mut pages = Vec::new();
+    for (dictionary, data) in streams(bytes) {
+        if is_non_content_stream(dictionary) {
+            continue;
+        }
+        let content = if dictionary_contains(dictionary, b"/FlateDecode") {
+            match inflate(data) {
+                Some(content) => content,
+                None => continue,
+            }
+        } else if dictionary_contains(dictionary, b"/Filter") {
+            // Other filters (DCT, LZW, ...) are images or rare encodings.
+            continue;
+        } else {
+            data.to_vec()
+        };
+        let text = extract_text_operators(&content);
+        if !text.trim().is_empty() {
+            pages.push(text);
+        }
+    }
+
+    let text = pages
+        .iter()
+        .map(|page| tidy_lines(page))
+        .filter(|page| !page.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    (!text.is_empty()).then_some(text)
+}
+
+/// Yields `(dictionary, data)` for every `stream ... endstream` section.
+fn streams(bytes: &[u8]) -> Vec<(&[u8], &[u8])> {
+    let mut out = Vec::new();
+    let mut search_from = 0;
+    while let Some(offset) = find(&bytes[search_from..], b"stream") {
+        let keyword = search_from + offset;
+        search_from = keyword + b"stream".len();
+        // Skip the `stream` suffix of `endstream`.
+        if keyword >= 3 && &bytes[keyword - 3..keyword] == b"end" {
+            continue;
+        }
+        let mut data_start = search_from;
+        if bytes.get(data_start) == Some(&b'\r') {
+            data_start += 1;
+        }
+        if bytes.get(data_start) == Some(&b'\n') {
+            data_start += 1;
+        }
+        let Some(end_offset) = find(&bytes[data_start..], b"endstream") else {
+            break;
+        };
+        let data_end = data_start + end_offset;
+        let dictionary_start = rfind(&bytes[..keyword], b"obj").map_or(0, |start| start + 3);
+        out.push((
+            &bytes[dictionary_start..keyword],
+            trim_trailing_eol(&bytes[data_start..data_end]),
+        ));
+        search_from = data_end + b"endstream".len();
+    }
+    out
+}
+
+fn is_non_content_stream(dictionary: &[u8]) -> bool {
+    [
+        b"/Image".as_slice(),
+        b"/XRef",
+        b"/ObjStm",
+        b"/Metadata",
+        b"/FontFile",
+        b"/Length1",
+        b"/Length2",
+        b"/ICCBased",
+        b"/EmbeddedFile",
+    ]
+    .iter()
+    .any(|marker| dictionary_contains(dictionary, marker))
+}
+
+fn inflate(data: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut decoder = ZlibDecoder::new(data).take(MAX_INFLATED_STREAM_BYTES);
+    match decoder.read_to_end(&mut out) {
+        Ok(_) => Some(out),
+        // Truncated streams still often carry usable text.
+        Err(_) if !out.is_empty() => Some(out),
+        Err(_) => None,
+    }
+}
+
+/// Walks a content stream and collects the strings shown by text operators.
+fn extract_text_operators(content: &[u8]) -> String {
+    let mut text = String::new();
+    let mut operands: Vec<Vec<u8>> = Vec::new();
+    let mut pos = 0;
+
+    while pos < content.len() {
+        let byte = content[pos];
+        match byte {
+            b'(' => {
+                let (string, end) = read_literal_string(content, pos + 1);
+                operands.push(string);
+                pos = end;
+            }
+            b'<' if content.get(pos + 1) != Some(&b'<') => {
+                let (string, end) = read_hex_string(content, pos + 1);
+                operands.push(string);
+                pos = end;
+            }
+            b'[' => {
+                // TJ arrays mix strings with kerning adjustments; large negative
+                // adjustments are word gaps.
+                let mut combined = Vec::new();
+                pos += 1;
+                while pos < content.len() && content[pos] != b']' {
+                    match content[pos] {
+                        b'(' => {
+                            let (string, end) = read_literal_string(content, pos + 1);
+                            combined.extend(string);
+                            pos = end;
+                        }
+                        b'<' => {
+                            let (string, end) = read_hex_string(content, pos + 1);
+                            combined.extend(string);
+                            pos = end;
+                        }
+                        b'-' | b'0'..=b'9' | b'.' => {
+                            let start = pos;
+                            while pos < content.len()
+                                && matches!(content[pos], b'-' | b'0'..=b'9' | b'.')
+                            {
+                                pos += 1;
+                            }
+                            let adjustment = std::str::from_utf8(&content[start..pos])
+                                .ok()
+                                .and_then(|number| number.parse::<f64>().ok())
+                                .unwrap_or(0.0);
+                            if adjustment < -200.0 {
+                                combined.push(b' ');
+                            }
+                        }
+                        _ => pos += 1,
+                    }
+                }
+                operands.push(combined);
+                pos += 1;
+            }
+            b'/' => {
+                pos += 1;
+                while pos < content.len()
+                    && !content[pos].is_ascii_whitespace()
+                    && !b"/[]()<>{}%".contains(&content[pos])
+                {
+                    pos += 1;
+                }
+            }
+            b'<' => pos += 2,
+            b'%' => {
+                while pos < content.len() && !matches!(content[pos], b'\n' | b'\r') {
+                    pos += 1;
+                }
+            }
+            b'a'..=b'z' | b'A'..=b'Z' | b'\'' | b'"' | b'*' => {
+                let start = pos;
+                while pos < content.len()
+                    && (content[pos].is_ascii_alphabetic()
+                        || matches!(content[pos], b'\'' | b'"' | b'*'))
+                {
+                    pos += 1;
+                }
+                match &content[start..pos] {
+                    b"Tj" | b"TJ" => push_shown(&mut text, operands.last()),
+                    b"'" | b"\"" => {
+                        text.push('\n');
+                        push_shown(&mut text, operands.last());
+                    }
+                    b"Td" | b"TD" | b"T*" | b"Tm" | b"ET" => {
+                        if !text.ends_with('\n') {
+                            text.push('\n');
+                        }
+                    }
+                    _ => {}
+                }
+                operands.clear();
+            }
+            _ => pos += 1,
+        }
+    }
+    text
+}
+
+fn push_shown(text: &mut String, operand: Option<&Vec<u8>>) {
+    let Some(bytes) = operand else {
+        return;
+    };
+    let decoded = decode_pdf_string(bytes);
+    if decoded.is_empty() {
+        return;
+    }
+    let printable = decoded
+        .chars()
+        .filter(|ch| !ch.is_control() || ch.is_whitespace())
+        .count();
+    if (printable as f64) / (decoded.chars().count() as f64) < MIN_PRINTABLE_RATIO {
+        return;
+    }
+    text.push_str(&decoded);
+}
+
+/// Decodes a PDF string: UTF-16BE when it carries a BOM, PDFDocEncoding
+/// (treated as Latin-1) otherwise.
+fn decode_pdf_string(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = utf16
+            .chunks(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair.get(1).copied().unwrap_or(0)]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+    bytes.iter().map(|&byte| char::from(byte)).collect()
+}
+
+fn read_literal_string(content: &[u8], mut pos: usize) -> (Vec<u8>, usize) {
+    let mut out = Vec::new();
+    let mut depth = 1;
+    while pos < content.len() {
+        let byte = content[pos];
+        pos += 1;
+        match byte {
+            b'\\' => {
+                let Some(&escaped) = content.get(pos) else {
+                    break;
+                };
+                pos += 1;
+                match escaped {
+                    b'n' => out.push(b'\n'),
+                    b'r' => out.push(b'\r'),
+                    b't' => out.push(b'\t'),
+                    b'b' => out.push(0x08),
+                    b'f' => out.push(0x0C),
+                    b'0'..=b'7' => {
+                        let mut value = u32::from(escaped - b'0');
+                        for _ in 0..2 {
+                            match content.get(pos) {
+                                Some(&digit @ b'0'..=b'7') => {
+                                    value = value * 8 + u32::from(digit - b'0');
+                                    pos += 1;
+                                }
+                                _ => break,
+                            }
+                        }
+                        out.push((value & 0xFF) as u8);
+                    }
+                    b'\r' | b'\n' => {
+                        if escaped == b'\r' && content.get(pos) == Some(&b'\n') {
+                            pos += 1;
+                        }
+                    }
+                    other => out.push(other),
+                }
+            }
+            b'(' => {
+                depth += 1;
+                out.push(byte);
+            }
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+                out.push(byte);
+            }
+            _ => out.push(byte),
+        }
+    }
+    (out, pos)
+}
+
+fn read_hex_string(content: &[u8], mut pos: usize) -> (Vec<u8>, usize) {
+    let mut digits = Vec::new();
+    while pos < content.len() && content[pos] != b'>' {
+        if content[pos].is_ascii_hexdigit() {
+            digits.push(content[pos]);
+        }
+        pos += 1;
+    }
+    if digits.len() % 2 == 1 {
+        digits.push(b'0');
+    }
+    let bytes = digits
+        .chunks(2)
+        .filter_map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        })
+        .collect();
+    (bytes, pos + 1)
+}
+
+fn tidy_lines(text: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+fn dictionary_contains(dictionary: &[u8], marker: &[u8]) -> bool {
+    find(dictionary, marker).is_some()
+}
+
+fn trim_trailing_eol(data: &[u8]) -> &[u8] {
+    let data = data.strip_suffix(b"\n").unwrap_or(data);
+    data.strip_suffix(b"\r").unwrap_or(data)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .rposition(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::ZlibEncoder;
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+
+    fn pdf_with_stream(dictionary: &str, data: &[u8]) -> Vec<u8> {
+        let mut pdf = b"%PDF-1.4\n1 0 obj\n".to_vec();
+        pdf.extend_from_slice(dictionary.as_bytes());
+        pdf.extend_from_slice(b"\nstream\n");
+        pdf.extend_from_slice(data);
+        pdf.extend_from_slice(b"\nendstream\nendobj\n%%EOF\n");
+        pdf
+    }
+
+    #[test]
+    fn extracts_text_from_flate_stream() {
+        let content =
+            b"BT /F1 12 Tf 72 712 Td (Hello, PDF) Tj 0 -14 Td [(Second) -300 (line)] TJ ET";
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content).expect("compress");
+        let compressed = encoder.finish().expect("finish");
+        let pdf = pdf_with_stream("<< /Length 99 /Filter /FlateDecode >>", &compressed);
+
+        assert_eq!(
+            extract_pdf_text(&pdf).as_deref(),
+            Some("Hello, PDF\nSecond line")
+        );
+    }
+
+    #[test]
+    fn decodes_escapes_and_utf16_strings() {
+        let content = b"BT (a \\(paren\\) \\101) Tj T* <FEFF00E9> Tj ET";
+        let pdf = pdf_with_stream("<< /Length 40 >>", content);
+
+        assert_eq!(
+            extract_pdf_text(&pdf).as_deref(),
+            Some("a (paren) A\n\u{e9}")
+        );
+    }
+
+    #[test]
+    fn returns_none_without_text() {
+        let pdf = pdf_with_stream("<< /Subtype /Image /Length 3 >>", b"abc");
+        assert_eq!(extract_pdf_text(&pdf), None);
+    }
+}
@@ -0,0 +1,273 @@
+//! On-disk cache of search results and fetched pages (`[web_search.cache]`).
+//!
+//! The cache outlives the session, unlike [`super::WebPageCache`]. How it is
+//! used depends on the web search mode: `cached` serves fresh entries and
+//! revalidates stale pages with their HTTP validators, `live` always goes to
+//! the network but still records responses, and `offline = true` serves only
+//! what is already on disk.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use reqwest::header::ETAG;
+use reqwest::header::HeaderMap;
+use reqwest::header::LAST_MODIFIED;
+use trill_utils_cache::CacheValidators;
+use trill_utils_cache::DiskCache;
+use trill_utils_cache::DiskEntry;
+
+use super::backend::SearchOptions;
+use super::backend::SearchResults;
+use crate::config::types::WebSearchCacheConfig;
+use crate::config::types::WebSearchMode;
+
+const CONTENT_TYPE_ATTRIBUTE: &str = "content_type";
+const FINAL_URL_ATTRIBUTE: &str = "final_url";
+
+/// How a single tool call may use the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CachePolicy {
+    /// Always fetch; record responses for later sessions.
+    Live,
+    /// Serve fresh entries, revalidate or refetch stale ones.
+    Cached,
+    /// Serve only from the cache.
+    Offline,
+}
+
+pub(crate) struct WebCache {
+    disk: DiskCache,
+    ttl: Duration,
+    offline: bool,
+}
+
+/// A fetched page as stored in the cache.
+pub(crate) struct CachedPage {
+    pub(crate) body: Vec<u8>,
+    pub(crate) content_type: Option<String>,
+    pub(crate) final_url: Option<String>,
+    pub(crate) validators: CacheValidators,
+    pub(crate) fresh: bool,
+}
+
+impl WebCache {
+    pub(crate) fn new(config: &WebSearchCacheConfig) -> Self {
+        Self {
+            disk: DiskCache::new(config.dir.clone(), config.max_bytes),
+            ttl: config.ttl,
+            offline: config.offline,
+        }
+    }
+
+    pub(crate) fn policy(&self, mode: Option<WebSearchMode>) -> CachePolicy {
+        if self.offline {
+            CachePolicy::Offline
+        } else if mode == Some(WebSearchMode::Cached) {
+            CachePolicy::Cached
+        } else {
+            CachePolicy::Live
+        }
+    }
+
+    /// Returns cached results for one backend and query. Stale results are
+    /// ignored except offline, where a miss is an error.
+    pub(crate) fn search_results(
+        &self,
+        backend: &str,
+        query: &str,
+        options: &SearchOptions,
+        policy: CachePolicy,
+    ) -> Result<Option<SearchResults>, String> {
+        if policy == CachePolicy::Live {
+            return Ok(None);
+        }
+        let entry = self
+            .read(&search_key(backend, query, options))
+            .filter(|entry| policy == CachePolicy::Offline || entry.age() < self.ttl)
+            .and_then(|entry| serde_json::from_slice(&entry.body).ok());
+        match entry {
+            Some(results) => Ok(Some(results)),
+            None if policy == CachePolicy::Offline => Err(format!(
+                "no cached results for '{query}' (web search cache is offline)"
+            )),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn store_search_results(
+        &self,
+        backend: &str,
+        query: &str,
+        options: &SearchOptions,
+        results: &SearchResults,
+    ) {
+        let Ok(body) = serde_json::to_vec(results) else {
+            return;
+        };
+        self.write(
+            &search_key(backend, query, options),
+            &body,
+            CacheValidators::default(),
+            BTreeMap::new(),
+        );
+    }
+
+    /// Returns the cached copy of `url` when the policy allows reading.
+    pub(crate) fn page(&self, url: &str, policy: CachePolicy) -> Option<CachedPage> {
+        if policy == CachePolicy::Live {
+            return None;
+        }
+        let entry = self.read(&page_key(url))?;
+        let fresh = policy == CachePolicy::Offline || entry.age() < self.ttl;
+        let DiskEntry { meta, body } = entry;
+        let mut attributes = meta.attributes;
+        Some(CachedPage {
+            body,
+            content_type: attributes.remove(CONTENT_TYPE_ATTRIBUTE),
+            final_url: attributes.remove(FINAL_URL_ATTRIBUTE),
+            validators: meta.validators,
+            fresh,
+        })
+    }
+
+    pub(crate) fn store_page(
+        &self,
+        url: &str,
+        final_url: &str,
+        content_type: Option<&str>,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let validators = CacheValidators {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        };
+        let mut attributes =
+            BTreeMap::from([(FINAL_URL_ATTRIBUTE.to_string(), final_url.to_string())]);
+        if let Some(content_type) = content_type {
+            attributes.insert(CONTENT_TYPE_ATTRIBUTE.to_string(), content_type.to_string());
+        }
+        self.write(&page_key(url), body, validators, attributes);
+    }
+
+    /// Marks a page as fresh after the server confirmed it is unchanged.
+    pub(crate) fn refresh_page(&self, url: &str) {
+        if let Err(err) = self.disk.refresh(&page_key(url)) {
+            tracing::warn!("failed to refresh web cache entry for {url}: {err}");
+        }
+    }
+
+    fn read(&self, key: &str) -> Option<DiskEntry> {
+        self.disk.get(key).unwrap_or_else(|err| {
+            tracing::warn!(
+                "failed to read web cache in {}: {err}",
+                self.disk.dir().display()
+            );
+            None
+        })
+    }
+
+    fn write(
+        &self,
+        key: &str,
+        body: &[u8],
+        validators: CacheValidators,
+        attributes: BTreeMap<String, String>,
+    ) {
+        if let Err(err) = self.disk.put(key, body, validators, attributes) {
+            tracing::warn!(
+                "failed to write web cache in {}: {err}",
+                self.disk.dir().display()
+            );
+        }
+    }
+}
+
+fn page_key(url: &str) -> String {
+    format!("page\n{url}")
+}
+
+fn search_key(backend: &str, query: &str, options: &SearchOptions) -> String {
+    let options = serde_json::to_string(options).unwrap_or_default();
+    format!("search\n{backend}\n{query}\n{options}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::handlers::web_search::backend::SearchHit;
+    use pretty_assertions::assert_eq;
+
+    fn cache(dir: &std::path::Path, ttl: Duration, offline: bool) -> WebCache {
+        WebCache::new(&WebSearchCacheConfig {
+            dir: dir.to_path_buf(),
+            ttl,
+            max_bytes: 1024 * 1024,
+            offline,
+        })
+    }
+
+    #[test]
+    fn policy_follows_mode_and_offline_flag() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let online = cache(dir.path(), Duration::from_secs(60), false);
+        assert_eq!(
+            online.policy(Some(WebSearchMode::Cached)),
+            CachePolicy::Cached
+        );
+        assert_eq!(online.policy(Some(WebSearchMode::Live)), CachePolicy::Live);
+        let offline = cache(dir.path(), Duration::from_secs(60), true);
+        assert_eq!(
+            offline.policy(Some(WebSearchMode::Live)),
+            CachePolicy::Offline
+        );
+    }
+
+    #[test]
+    fn serves_search_results_by_policy_and_age() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let options = SearchOptions::default();
+        let results = SearchResults::from(vec![SearchHit {
+            title: "Tokio".to_string(),
+            url: "https://tokio.rs/".to_string(),
+            ..Default::default()
+        }]);
+        let fresh = cache(dir.path(), Duration::from_secs(60), false);
+        fresh.store_search_results("searx", "tokio", &options, &results);
+
+        assert_eq!(
+            fresh.search_results("searx", "tokio", &options, CachePolicy::Live),
+            Ok(None)
+        );
+        assert_eq!(
+            fresh.search_results("searx", "tokio", &options, CachePolicy::Cached),
+            Ok(Some(results.clone()))
+        );
+        assert_eq!(
+            fresh.search_results("other", "tokio", &options, CachePolicy::Cached),
+            Ok(None)
+        );
+
+        let expired = cache(dir.path(), Duration::ZERO, false);
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(
+            expired.search_results("searx", "tokio", &options, CachePolicy::Cached),
+            Ok(None)
+        );
+        assert_eq!(
+            expired.search_results("searx", "tokio", &options, CachePolicy::Offline),
+            Ok(Some(results))
+        );
+        assert!(
+            expired
+                .search_results("searx", "axum", &options, CachePolicy::Offline)
+                .is_err()
+        );
+    }
+}
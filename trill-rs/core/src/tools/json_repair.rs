@@ -0,0 +1,170 @@
+//! Best-effort repair of the almost-JSON that some models write as tool call
+//! arguments: trailing commas, single-quoted strings, raw control characters
+//! inside strings, invalid escapes and code fences around the object.
+//!
+//! Arguments cut short by a length stop are not repaired: closing a string or
+//! bracket that the model never closed can turn `rm -rf /tmp/build` into
+//! `rm -rf /tmp/`, so those calls go back to the model instead.
+
+use serde_json::Value;
+
+/// Returns a repaired copy of `input` that parses as JSON, or `None` when
+/// `input` is already valid, looks truncated, or could not be repaired.
+pub(crate) fn repair_json(input: &str) -> Option<String> {
+    if serde_json::from_str::<Value>(input).is_ok() {
+        return None;
+    }
+    let trimmed = strip_code_fence(input.trim());
+    if trimmed.is_empty() {
+        return Some("{}".to_string());
+    }
+    let repaired = rewrite(trimmed)?;
+    serde_json::from_str::<Value>(&repaired)
+        .is_ok()
+        .then_some(repaired)
+}
+
+fn strip_code_fence(input: &str) -> &str {
+    let Some(rest) = input.strip_prefix("```") else {
+        return input;
+    };
+    let body = rest.split_once('\n').map_or("", |(_, body)| body);
+    let body = body.trim_end();
+    body.strip_suffix("```").unwrap_or(body).trim()
+}
+
+/// Rewrites `input` into strict JSON, or returns `None` when that would mean
+/// inventing a closing quote, bracket or value.
+fn rewrite(input: &str) -> Option<String> {
+    let mut out = String::with_capacity(input.len() + 8);
+    let mut open = Vec::new();
+    let mut quote: Option<char> = None;
+    let mut chars = input.chars();
+
+    while let Some(ch) = chars.next() {
+        if let Some(delimiter) = quote {
+            match ch {
+                '\\' => match chars.next() {
+                    Some('\'') => out.push('\''),
+                    Some(next @ ('"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' | 'u')) => {
+                        out.push('\\');
+                        out.push(next);
+                    }
+                    Some(next) => {
+                        out.push_str("\\\\");
+                        push_string_char(&mut out, next);
+                    }
+                    None => return None,
+                },
+                _ if ch == delimiter => {
+                    out.push('"');
+                    quote = None;
+                }
+                '"' => out.push_str("\\\""),
+                _ => push_string_char(&mut out, ch),
+            }
+            continue;
+        }
+
+        match ch {
+            '"' | '\'' => {
+                out.push('"');
+                quote = Some(ch);
+            }
+            '{' | '[' => {
+                out.push(ch);
+                open.push(if ch == '{' { '}' } else { ']' });
+            }
+            '}' | ']' => {
+                drop_trailing_comma(&mut out);
+                out.push(ch);
+                open.pop()?;
+            }
+            _ => out.push(ch),
+        }
+    }
+
+    if quote.is_some() || !open.is_empty() {
+        return None;
+    }
+    drop_trailing_comma(&mut out);
+    Some(out)
+}
+
+fn push_string_char(out: &mut String, ch: char) {
+    match ch {
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        ch if u32::from(ch) < 0x20 => out.push_str(&format!("\\u{:04x}", u32::from(ch))),
+        ch => out.push(ch),
+    }
+}
+
+fn drop_trailing_comma(out: &mut String) {
+    let kept = out.trim_end().len();
+    out.truncate(kept);
+    if out.ends_with(',') {
+        out.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn repaired(input: &str) -> Value {
+        let repaired = repair_json(input).expect("repairable");
+        serde_json::from_str(&repaired).expect("valid json")
+    }
+
+    #[test]
+    fn leaves_valid_json_alone() {
+        assert_eq!(repair_json(r#"{"command": ["ls"]}"#), None);
+    }
+
+    #[test]
+    fn drops_trailing_commas_and_rewrites_single_quotes() {
+        assert_eq!(
+            repaired("{'command': ['ls', '-la',], 'workdir': \"it's\",}"),
+            json!({"command": ["ls", "-la"], "workdir": "it's"})
+        );
+        assert_eq!(
+            repaired(r#"{'pattern': 'say "hi"\d'}"#),
+            json!({"pattern": "say \"hi\"\\d"})
+        );
+    }
+
+    #[test]
+    fn escapes_raw_newlines_in_strings() {
+        assert_eq!(
+            repaired("{\"input\": \"*** Begin Patch\n*** Add File: a.txt\n+hi\n*** End Patch\"}"),
+            json!({"input": "*** Begin Patch\n*** Add File: a.txt\n+hi\n*** End Patch"})
+        );
+    }
+
+    #[test]
+    fn refuses_truncated_arguments() {
+        assert_eq!(repair_json(r#"{"command": ["rm", "-rf", "/tmp/"#), None);
+        assert_eq!(repair_json(r#"{"command": ["rm", "-rf"]"#), None);
+        assert_eq!(repair_json(r#"{"path": "a.txt", "limit":"#), None);
+        assert_eq!(repair_json(r#"{"pattern": "a\"#), None);
+    }
+
+    #[test]
+    fn strips_code_fences_and_fills_empty_arguments() {
+        assert_eq!(
+            repaired("```json\n{\"path\": \"a.txt\"}\n```"),
+            json!({"path": "a.txt"})
+        );
+        assert_eq!(repaired("  "), json!({}));
+    }
+
+    #[test]
+    fn gives_up_on_unrecoverable_input() {
+        assert_eq!(repair_json(r#"{"command": tru"#), None);
+        assert_eq!(repair_json("run ls please"), None);
+    }
+}
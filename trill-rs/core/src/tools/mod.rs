@@ -1,6 +1,7 @@
 pub mod context;
 pub mod events;
 pub(crate) mod handlers;
+mod json_repair;
 pub mod orchestrator;
 pub mod parallel;
 pub mod registry;
@@ -3,12 +3,18 @@ Module: orchestrator
 
 Central place for approvals + sandbox selection + retry semantics. Drives a
 simple sequence for any ToolRuntime: approval → select sandbox → attempt →
-retry without sandbox on denial (no re‑approval thanks to caching).
+retry without sandbox on denial (no re‑approval thanks to caching). Sandboxed
+attempts may run behind a per-command network proxy that asks the user before
+reaching hosts outside the network allowlist.
 */
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
+use crate::exec_policy::ToolRuleAmendment;
+use crate::protocol::EventMsg;
+use crate::protocol::WarningEvent;
 use crate::exec::ExecToolCallOutput;
 use crate::sandboxing::SandboxManager;
+use crate::sandboxing::SandboxNetworkProxy;
 use crate::tools::sandboxing::ApprovalCtx;
 use crate::tools::sandboxing::ExecApprovalRequirement;
 use crate::tools::sandboxing::SandboxAttempt;
@@ -17,9 +23,33 @@ use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::default_exec_approval_requirement;
+use crate::tools::sandboxing::with_cached_approval;
+use crate::trill::Session;
+use crate::trill::TurnContext;
 use trill_otel::ToolDecisionSource;
 use trill_protocol::protocol::AskForApproval;
 use trill_protocol::protocol::ReviewDecision;
+use trill_protocol::request_user_input::RequestUserInputArgs;
+use trill_protocol::request_user_input::RequestUserInputQuestion;
+use trill_protocol::request_user_input::RequestUserInputQuestionOption;
+use serde::Serialize;
+
+const POLICY_APPROVAL_QUESTION_ID_PREFIX: &str = "tool_policy_approval";
+const POLICY_APPROVAL_ALLOW: &str = "Allow";
+const POLICY_APPROVAL_ALWAYS_ALLOW: &str = "Always allow";
+const POLICY_APPROVAL_DECLINE: &str = "Decline";
+
+const NETWORK_APPROVAL_QUESTION_ID_PREFIX: &str = "network_approval";
+const NETWORK_APPROVAL_ALLOW_ONCE: &str = "Allow once";
+const NETWORK_APPROVAL_ALLOW_FOR_SESSION: &str = "Allow for session";
+const NETWORK_APPROVAL_DECLINE: &str = "Decline";
+
+/// Session approval cache key for hosts reached through the sandbox network proxy.
+#[derive(Serialize)]
+struct NetworkApprovalKey {
+    host: String,
+    port: u16,
+}
 
 pub(crate) struct ToolOrchestrator {
     sandbox: SandboxManager,
@@ -95,6 +125,23 @@ impl ToolOrchestrator {
             ),
         };
 
+        // Without network access, the Linux sandbox can still let a command
+        // reach the network through a proxy that asks before new hosts.
+        let mut network_proxy = match turn_ctx.trill_linux_sandbox_exe.as_ref() {
+            Some(exe)
+                if turn_ctx.sandbox_network_proxy
+                    && initial_sandbox == crate::exec::SandboxType::LinuxSeccomp
+                    && !turn_ctx.sandbox_policy.has_full_network_access()
+                    && tool.uses_network_proxy() =>
+            {
+                let proxy = SandboxNetworkProxy::start(exe)
+                    .await
+                    .map_err(|err| ToolError::Codex(CodexErr::Io(err)))?;
+                Some(proxy)
+            }
+            _ => None,
+        };
+
         // Platform-specific flag gating is handled by SandboxManager::select_initial
         // via crate::safety::get_platform_sandbox(..).
         let initial_attempt = SandboxAttempt {
@@ -102,11 +149,37 @@ impl ToolOrchestrator {
             policy: &turn_ctx.sandbox_policy,
             manager: &self.sandbox,
             sandbox_cwd: &turn_ctx.cwd,
+            deny_read: &turn_ctx.sandbox_deny_read,
+            network_proxy: network_proxy.as_ref().map(|proxy| proxy.endpoint().clone()),
             trill_linux_sandbox_exe: turn_ctx.trill_linux_sandbox_exe.as_ref(),
             windows_sandbox_level: turn_ctx.windows_sandbox_level,
         };
 
-        match tool.run(req, &initial_attempt, tool_ctx).await {
+        let initial_result = match network_proxy.as_mut() {
+            Some(proxy) => {
+                let session = tool_ctx.session;
+                let call_id = tool_ctx.call_id.as_str();
+                proxy
+                    .drive(
+                        tool.run(req, &initial_attempt, tool_ctx),
+                        move |host, port| {
+                            Self::approve_network_host(
+                                session,
+                                turn_ctx,
+                                call_id,
+                                approval_policy,
+                                host,
+                                port,
+                            )
+                        },
+                    )
+                    .await
+            }
+            None => tool.run(req, &initial_attempt, tool_ctx).await,
+        };
+        drop(network_proxy);
+
+        match initial_result {
             Ok(out) => {
                 // We have a successful initial result
                 Ok(out)
@@ -153,6 +226,8 @@ impl ToolOrchestrator {
                     policy: &turn_ctx.sandbox_policy,
                     manager: &self.sandbox,
                     sandbox_cwd: &turn_ctx.cwd,
+                    deny_read: &turn_ctx.sandbox_deny_read,
+                    network_proxy: None,
                     trill_linux_sandbox_exe: None,
                     windows_sandbox_level: turn_ctx.windows_sandbox_level,
                 };
@@ -163,6 +238,173 @@ impl ToolOrchestrator {
             other => other,
         }
     }
+
+    /// Asks whether a sandboxed command may connect to `host` through the
+    /// sandbox network proxy. "Allow for session" also covers later commands.
+    /// Under `AskForApproval::Never` nobody can answer, so the host is denied.
+    async fn approve_network_host(
+        session: &Session,
+        turn_ctx: &TurnContext,
+        call_id: &str,
+        approval_policy: AskForApproval,
+        host: String,
+        port: u16,
+    ) -> bool {
+        let otel = turn_ctx.client.get_otel_manager();
+        if matches!(approval_policy, AskForApproval::Never) {
+            otel.tool_decision(
+                "network",
+                call_id,
+                &ReviewDecision::Denied,
+                ToolDecisionSource::Config,
+            );
+            return false;
+        }
+
+        let keys = vec![NetworkApprovalKey {
+            host: host.clone(),
+            port,
+        }];
+        let decision = with_cached_approval(&session.services, "network", keys, || async {
+            let question_id = format!("{NETWORK_APPROVAL_QUESTION_ID_PREFIX}_{call_id}");
+            let args = RequestUserInputArgs {
+                questions: vec![RequestUserInputQuestion {
+                    id: question_id.clone(),
+                    header: "Allow network access?".to_string(),
+                    question: format!(
+                        "The command wants to connect to `{host}:{port}`, which is not in the network allowlist."
+                    ),
+                    is_other: false,
+                    is_secret: false,
+                    options: Some(vec![
+                        RequestUserInputQuestionOption {
+                            label: NETWORK_APPROVAL_ALLOW_ONCE.to_string(),
+                            description: "Allow this host and port for the rest of this command."
+                                .to_string(),
+                        },
+                        RequestUserInputQuestionOption {
+                            label: NETWORK_APPROVAL_ALLOW_FOR_SESSION.to_string(),
+                            description: "Allow this host and port for every command in this session."
+                                .to_string(),
+                        },
+                        RequestUserInputQuestionOption {
+                            label: NETWORK_APPROVAL_DECLINE.to_string(),
+                            description: "Block the connection and let the command continue."
+                                .to_string(),
+                        },
+                    ]),
+                }],
+            };
+            let response = session
+                .request_user_input(turn_ctx, call_id.to_string(), args)
+                .await;
+            let answer = response.and_then(|mut response| {
+                response
+                    .answers
+                    .remove(&question_id)
+                    .and_then(|answer| answer.answers.into_iter().next())
+            });
+            match answer.as_deref() {
+                Some(NETWORK_APPROVAL_ALLOW_ONCE) => ReviewDecision::Approved,
+                Some(NETWORK_APPROVAL_ALLOW_FOR_SESSION) => ReviewDecision::ApprovedForSession,
+                _ => ReviewDecision::Denied,
+            }
+        })
+        .await;
+
+        otel.tool_decision("network", call_id, &decision, ToolDecisionSource::User);
+        matches!(
+            decision,
+            ReviewDecision::Approved | ReviewDecision::ApprovedForSession
+        )
+    }
+
+    /// Enforces an execpolicy tool rule for calls that do not run through a [`ToolRuntime`]
+    /// (MCP tools and `web_search` page loads). `NeedsApproval` asks the user through
+    /// `request_user_input`. Returns the rejection reason when the call must not proceed.
+    ///
+    /// With an `amendment`, the prompt also offers "Always allow", which saves the amendment's
+    /// exact `allow` rules so the same target is not prompted for again.
+    pub(crate) async fn enforce_policy(
+        session: &Session,
+        turn_ctx: &TurnContext,
+        call_id: &str,
+        tool_name: &str,
+        requirement: ExecApprovalRequirement,
+        amendment: Option<ToolRuleAmendment>,
+    ) -> Result<(), String> {
+        let otel = turn_ctx.client.get_otel_manager();
+        let reason = match requirement {
+            ExecApprovalRequirement::Skip { .. } => {
+                otel.tool_decision(
+                    tool_name,
+                    call_id,
+                    &ReviewDecision::Approved,
+                    ToolDecisionSource::Config,
+                );
+                return Ok(());
+            }
+            ExecApprovalRequirement::Forbidden { reason } => return Err(reason),
+            ExecApprovalRequirement::NeedsApproval { reason, .. } => reason,
+        };
+
+        let question_id = format!("{POLICY_APPROVAL_QUESTION_ID_PREFIX}_{call_id}");
+        let mut options = vec![RequestUserInputQuestionOption {
+            label: POLICY_APPROVAL_ALLOW.to_string(),
+            description: "Run the tool and continue.".to_string(),
+        }];
+        if amendment.is_some() {
+            options.push(RequestUserInputQuestionOption {
+                label: POLICY_APPROVAL_ALWAYS_ALLOW.to_string(),
+                description: "Run the tool and save an allow rule for this exact target."
+                    .to_string(),
+            });
+        }
+        options.push(RequestUserInputQuestionOption {
+            label: POLICY_APPROVAL_DECLINE.to_string(),
+            description: "Decline this tool call and continue.".to_string(),
+        });
+        let args = RequestUserInputArgs {
+            questions: vec![RequestUserInputQuestion {
+                id: question_id.clone(),
+                header: "Approve tool call?".to_string(),
+                question: reason
+                    .unwrap_or_else(|| format!("`{tool_name}` requires approval by policy")),
+                is_other: false,
+                is_secret: false,
+                options: Some(options),
+            }],
+        };
+        let response = session
+            .request_user_input(turn_ctx, call_id.to_string(), args)
+            .await;
+        let answer = response.and_then(|mut response| {
+            response
+                .answers
+                .remove(&question_id)
+                .and_then(|answer| answer.answers.into_iter().next())
+        });
+        let decision = match (answer.as_deref(), amendment) {
+            (Some(POLICY_APPROVAL_ALLOW), _) => ReviewDecision::Approved,
+            (Some(POLICY_APPROVAL_ALWAYS_ALLOW), Some(amendment)) => {
+                if let Err(err) = session.persist_tool_rule_amendment(&amendment).await {
+                    let message = format!("Failed to remember approval for `{tool_name}`: {err}");
+                    tracing::warn!("{message}");
+                    session
+                        .send_event(turn_ctx, EventMsg::Warning(WarningEvent { message }))
+                        .await;
+                }
+                ReviewDecision::Approved
+            }
+            _ => ReviewDecision::Denied,
+        };
+
+        otel.tool_decision(tool_name, call_id, &decision, ToolDecisionSource::User);
+        match decision {
+            ReviewDecision::Approved => Ok(()),
+            _ => Err("rejected by user".to_string()),
+        }
+    }
 }
 
 fn build_denial_reason_from_output(_output: &ExecToolCallOutput) -> String {
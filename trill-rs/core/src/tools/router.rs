@@ -2,10 +2,12 @@ use crate::client_common::tools::ToolSpec;
 use crate::trill::Session;
 use crate::trill::TurnContext;
 use crate::function_tool::FunctionCallError;
+use crate::hooks;
 use crate::sandboxing::SandboxPermissions;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolPayload;
+use crate::tools::json_repair::repair_json;
 use crate::tools::registry::ConfiguredToolSpec;
 use crate::tools::registry::ToolRegistry;
 use crate::tools::spec::ToolsConfig;
@@ -17,6 +19,8 @@ use trill_protocol::models::ResponseItem;
 use trill_protocol::models::ShellToolCallParams;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use tracing::info;
 use tracing::instrument;
 
 #[derive(Clone, Debug)]
@@ -140,11 +144,43 @@ impl ToolRouter {
         let ToolCall {
             tool_name,
             call_id,
-            payload,
+            mut payload,
         } = call;
         let payload_outputs_custom = matches!(payload, ToolPayload::Custom { .. });
         let failure_call_id = call_id.clone();
 
+        if let Err(err) = self.repair_arguments(&tool_name, &mut payload) {
+            let malformed = turn.malformed_tool_calls.fetch_add(1, Ordering::SeqCst) + 1;
+            let limit = turn.client.config().max_malformed_tool_calls;
+            if malformed > limit {
+                return Err(FunctionCallError::Fatal(format!(
+                    "stopping turn after {malformed} consecutive tool calls with malformed arguments"
+                )));
+            }
+            return Ok(Self::failure_response(
+                failure_call_id,
+                payload_outputs_custom,
+                err,
+            ));
+        }
+        turn.malformed_tool_calls.store(0, Ordering::SeqCst);
+
+        if let Err(reason) =
+            hooks::run_pre_tool_use(&session, &turn, &tool_name, &call_id, &mut payload).await
+        {
+            return Ok(Self::failure_response(
+                failure_call_id,
+                payload_outputs_custom,
+                FunctionCallError::RespondToModel(format!(
+                    "`{tool_name}` call was blocked by a hook: {reason}"
+                )),
+            ));
+        }
+
+        let hook_session = Arc::clone(&session);
+        let hook_turn = Arc::clone(&turn);
+        let hook_tool_name = tool_name.clone();
+        let hook_payload = payload.clone();
         let invocation = ToolInvocation {
             session,
             turn,
@@ -155,7 +191,18 @@ impl ToolRouter {
         };
 
         match self.registry.dispatch(invocation).await {
-            Ok(response) => Ok(response),
+            Ok(response) => {
+                hooks::run_post_tool_use(
+                    &hook_session,
+                    &hook_turn,
+                    &hook_tool_name,
+                    &failure_call_id,
+                    &hook_payload,
+                    &response,
+                )
+                .await;
+                Ok(response)
+            }
             Err(FunctionCallError::Fatal(message)) => Err(FunctionCallError::Fatal(message)),
             Err(err) => Ok(Self::failure_response(
                 failure_call_id,
@@ -165,6 +212,56 @@ impl ToolRouter {
         }
     }
 
+    /// Makes sure JSON arguments parse before they reach a handler, repairing
+    /// them when possible.
+    fn repair_arguments(
+        &self,
+        tool_name: &str,
+        payload: &mut ToolPayload,
+    ) -> Result<(), FunctionCallError> {
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            // MCP handlers treat empty arguments as "no arguments".
+            ToolPayload::Mcp { raw_arguments, .. } if !raw_arguments.trim().is_empty() => {
+                raw_arguments
+            }
+            _ => return Ok(()),
+        };
+        let Err(err) = serde_json::from_str::<serde_json::Value>(arguments) else {
+            return Ok(());
+        };
+        if let Some(repaired) = repair_json(arguments) {
+            info!(
+                tool_name,
+                original = %arguments,
+                %repaired,
+                "repaired malformed tool call arguments"
+            );
+            *arguments = repaired;
+            return Ok(());
+        }
+        Err(FunctionCallError::RespondToModel(
+            self.malformed_arguments_message(tool_name, &err),
+        ))
+    }
+
+    fn malformed_arguments_message(&self, tool_name: &str, err: &serde_json::Error) -> String {
+        let schema = self.specs.iter().find_map(|config| match &config.spec {
+            ToolSpec::Function(tool) if tool.name == tool_name => {
+                serde_json::to_string(&tool.parameters).ok()
+            }
+            _ => None,
+        });
+        match schema {
+            Some(schema) => format!(
+                "failed to parse arguments for `{tool_name}`: {err}. Call it again with a single JSON object that matches this schema:\n{schema}"
+            ),
+            None => format!(
+                "failed to parse arguments for `{tool_name}`: {err}. Call it again with a single valid JSON object."
+            ),
+        }
+    }
+
     fn failure_response(
         call_id: String,
         payload_outputs_custom: bool,
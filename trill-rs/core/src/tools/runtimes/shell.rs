@@ -69,6 +69,9 @@ impl Sandboxable for ShellRuntime {
     fn escalate_on_failure(&self) -> bool {
         true
     }
+    fn uses_network_proxy(&self) -> bool {
+        true
+    }
 }
 
 impl Approvable<ShellRequest> for ShellRuntime {
@@ -244,6 +244,11 @@ pub(crate) trait Sandboxable {
     fn escalate_on_failure(&self) -> bool {
         true
     }
+    /// Whether sandboxed runs may reach the network through the sandbox
+    /// network proxy when `sandbox_network_proxy` is enabled.
+    fn uses_network_proxy(&self) -> bool {
+        false
+    }
 }
 
 pub(crate) struct ToolCtx<'a> {
@@ -273,6 +278,8 @@ pub(crate) struct SandboxAttempt<'a> {
     pub policy: &'a crate::protocol::SandboxPolicy,
     pub(crate) manager: &'a SandboxManager,
     pub(crate) sandbox_cwd: &'a Path,
+    pub(crate) deny_read: &'a crate::sandboxing::DenyRead,
+    pub(crate) network_proxy: Option<crate::sandboxing::NetworkProxyEndpoint>,
     pub trill_linux_sandbox_exe: Option<&'a std::path::PathBuf>,
     pub windows_sandbox_level: trill_protocol::config_types::WindowsSandboxLevel,
 }
@@ -287,6 +294,8 @@ impl<'a> SandboxAttempt<'a> {
             self.policy,
             self.sandbox,
             self.sandbox_cwd,
+            self.deny_read,
+            self.network_proxy.as_ref(),
             self.trill_linux_sandbox_exe,
             self.windows_sandbox_level,
         )
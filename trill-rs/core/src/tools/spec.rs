@@ -1,6 +1,7 @@
 use crate::agent::AgentRole;
 use crate::client_common::tools::ResponsesApiTool;
 use crate::client_common::tools::ToolSpec;
+use crate::config::types::WebSearchBackendConfig;
 use crate::features::Feature;
 use crate::features::Features;
 use crate::tools::handlers::PLAN_TOOL;
@@ -28,7 +29,7 @@ pub(crate) struct ToolsConfig {
     pub shell_type: ConfigShellToolType,
     pub apply_patch_tool_type: Option<ApplyPatchToolType>,
     pub web_search_mode: Option<WebSearchMode>,
-    pub searxng_url: String,
+    pub web_search_backends: BTreeMap<String, WebSearchBackendConfig>,
     pub collab_tools: bool,
     pub collaboration_modes_tools: bool,
     pub request_rule_enabled: bool,
@@ -39,7 +40,7 @@ pub(crate) struct ToolsConfigParams<'a> {
     pub(crate) model_info: &'a ModelInfo,
     pub(crate) features: &'a Features,
     pub(crate) web_search_mode: Option<WebSearchMode>,
-    pub(crate) searxng_url: String,
+    pub(crate) web_search_backends: BTreeMap<String, WebSearchBackendConfig>,
 }
 
 impl ToolsConfig {
@@ -48,7 +49,7 @@ impl ToolsConfig {
             model_info,
             features,
             web_search_mode,
-            searxng_url,
+            web_search_backends,
         } = params;
         let include_apply_patch_tool = features.enabled(Feature::ApplyPatchFreeform);
         let include_collab_tools = features.enabled(Feature::Collab);
@@ -84,7 +85,7 @@ impl ToolsConfig {
             shell_type,
             apply_patch_tool_type,
             web_search_mode: *web_search_mode,
-            searxng_url: searxng_url.clone(),
+            web_search_backends: web_search_backends.clone(),
             collab_tools: include_collab_tools,
             collaboration_modes_tools: include_collaboration_modes_tools,
             request_rule_enabled,
@@ -464,7 +465,7 @@ fn create_web_search_tool() -> ToolSpec {
             "queries".to_string(),
             JsonSchema::Array {
                 items: Box::new(JsonSchema::String { description: None }),
-                description: Some("Multiple search queries to execute (for Search action)".to_string()),
+                description: Some("Several reformulations of the search, run concurrently with the results fused into one ranked list (for Search action)".to_string()),
             },
         ),
         (
@@ -479,6 +480,62 @@ fn create_web_search_tool() -> ToolSpec {
                 description: Some("Pattern to find in page content (for FindInPage action)".to_string()),
             },
         ),
+        (
+            "regex".to_string(),
+            JsonSchema::Boolean {
+                description: Some("Treat 'pattern' as a regular expression instead of literal text (for FindInPage action)".to_string()),
+            },
+        ),
+        (
+            "context_lines".to_string(),
+            JsonSchema::Number {
+                description: Some("Lines of context to show around each match, like grep -C (for FindInPage action, default 2)".to_string()),
+            },
+        ),
+        (
+            "page".to_string(),
+            JsonSchema::Number {
+                description: Some("1-based page to return: the page of the document for OpenPage, or the page of results for Search (default 1)".to_string()),
+            },
+        ),
+        (
+            "categories".to_string(),
+            JsonSchema::Array {
+                items: Box::new(JsonSchema::String { description: None }),
+                description: Some("Search categories such as 'general', 'news', 'it', 'science' or 'images' (for Search action)".to_string()),
+            },
+        ),
+        (
+            "engines".to_string(),
+            JsonSchema::Array {
+                items: Box::new(JsonSchema::String { description: None }),
+                description: Some("Restrict the search to these engines, e.g. 'wikipedia' or 'github' (for Search action)".to_string()),
+            },
+        ),
+        (
+            "language".to_string(),
+            JsonSchema::String {
+                description: Some("Result language code such as 'en' or 'de-CH' (for Search action)".to_string()),
+            },
+        ),
+        (
+            "time_range".to_string(),
+            JsonSchema::String {
+                description: Some("Only return results from the last 'day', 'week', 'month' or 'year' (for Search action)".to_string()),
+            },
+        ),
+        (
+            "num_results".to_string(),
+            JsonSchema::Number {
+                description: Some("Number of results to return per query (for Search action, default 10, max 50)".to_string()),
+            },
+        ),
+        (
+            "cursor".to_string(),
+            JsonSchema::String {
+                description: Some("'next_cursor' value from a previous OpenPage response, to read the following page".to_string()),
+            },
+        ),
         (
             "action".to_string(),
             JsonSchema::String {
@@ -489,20 +546,25 @@ fn create_web_search_tool() -> ToolSpec {
 
     ToolSpec::Function(ResponsesApiTool {
         name: "web_search".to_string(),
-        description: r#"Searches the web or retrieves page content via local SearXNG instance.
+        description: r#"Searches the web or retrieves page content. Searches go to the configured search backends (a local SearXNG instance by default).
 
 Actions (auto-detected based on parameters):
-- Search: Provide 'query' or 'queries' to search the web
-- OpenPage: Provide 'url' to fetch and return page content
-- FindInPage: Provide 'url' and 'pattern' to search within a page
+- Search: Provide 'query' or 'queries' to search the web. Optionally narrow it with 'categories', 'engines', 'language' and 'time_range', and use 'page' for more results. Unknown categories, engines or languages are rejected with the values the instance supports.
+- OpenPage: Provide 'url' to fetch page content. Long documents are split into pages; the response includes 'page', 'total_pages' and, when more remains, 'next_cursor'. Pass 'cursor' (or 'page') to read further.
+- FindInPage: Provide 'url' and 'pattern' to search within a page. Matches are case-insensitive, returned with surrounding context lines ('N:' marks matching lines, 'N-' context) and the page each match is on. Set 'regex' for regular expressions.
 
 Examples:
 - Search: {"query": "rust async tutorial"}
 - Multiple queries: {"queries": ["rust async", "tokio tutorial"]}
+- Recent news: {"query": "rust release", "categories": ["news"], "time_range": "week"}
 - Open page: {"url": "https://docs.rs/tokio"}
+- Next page: {"url": "https://docs.rs/tokio", "cursor": "2"}
 - Find in page: {"url": "https://docs.rs/tokio", "pattern": "spawn"}
+- Regex find: {"url": "https://docs.rs/tokio", "pattern": "fn \\w+_blocking", "regex": true, "context_lines": 4}
+
+Fetched documents are cached for the session, so reading further pages or searching a page you already opened does not fetch it again.
 
-Returns search results with metadata including: title, url, snippet, engine, score, category."#
+Returns search results with metadata including: title, url, snippet, engine, score, category. With several queries, each result lists the 'matched_queries' that found it. When the backend provides them, search responses also include direct 'answers', 'infoboxes' (summary cards with attributes and links) and query 'suggestions'."#
             .to_string(),
         strict: false,
         parameters: JsonSchema::Object {
@@ -1451,10 +1513,10 @@ pub(crate) fn build_specs(
 
     // Register web_search as a function tool for LM Studio compatibility.
     // This replaces the OpenAI-specific "web_search" tool type with a standard
-    // function that routes to local SearXNG instance.
+    // function that routes to the configured search backends (SearXNG by default).
     match config.web_search_mode {
         Some(WebSearchMode::Cached) | Some(WebSearchMode::Live) => {
-            let web_search_handler = Arc::new(WebSearchHandler::new(config.searxng_url.clone()));
+            let web_search_handler = Arc::new(WebSearchHandler::new(&config.web_search_backends));
             builder.push_spec(create_web_search_tool());
             builder.register_handler("web_search", web_search_handler);
         }
@@ -1621,7 +1683,7 @@ mod tests {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Live),
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
         let (tools, _) = build_specs(&config, None, &[]).build();
 
@@ -1686,7 +1748,7 @@ mod tests {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
         let (tools, _) = build_specs(&tools_config, None, &[]).build();
         assert_contains_tool_names(
@@ -1705,7 +1767,7 @@ mod tests {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
         let (tools, _) = build_specs(&tools_config, None, &[]).build();
         assert!(
@@ -1718,7 +1780,7 @@ mod tests {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
         let (tools, _) = build_specs(&tools_config, None, &[]).build();
         assert_contains_tool_names(&tools, &["request_user_input"]);
@@ -1736,7 +1798,7 @@ mod tests {
             model_info: &model_info,
             features,
             web_search_mode,
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
         let (tools, _) = build_specs(&tools_config, Some(HashMap::new()), &[]).build();
         let tool_names = tools.iter().map(|t| t.spec.name()).collect::<Vec<_>>();
@@ -1753,7 +1815,7 @@ mod tests {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
         let (tools, _) = build_specs(&tools_config, None, &[]).build();
 
@@ -1772,7 +1834,7 @@ mod tests {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Live),
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
         let (tools, _) = build_specs(&tools_config, None, &[]).build();
 
@@ -2015,7 +2077,7 @@ mod tests {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Live),
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
         let (tools, _) = build_specs(&tools_config, Some(HashMap::new()), &[]).build();
 
@@ -2038,7 +2100,7 @@ mod tests {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
         let (tools, _) = build_specs(&tools_config, None, &[]).build();
 
@@ -2058,7 +2120,7 @@ mod tests {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
         let (tools, _) = build_specs(&tools_config, None, &[]).build();
 
@@ -2090,7 +2152,7 @@ mod tests {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Live),
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
         let (tools, _) = build_specs(
             &tools_config,
@@ -2187,7 +2249,7 @@ mod tests {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
 
         // Intentionally construct a map with keys that would sort alphabetically.
@@ -2265,7 +2327,7 @@ mod tests {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
 
         let (tools, _) = build_specs(
@@ -2324,7 +2386,7 @@ mod tests {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
 
         let (tools, _) = build_specs(
@@ -2380,7 +2442,7 @@ mod tests {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
 
         let (tools, _) = build_specs(
@@ -2438,7 +2500,7 @@ mod tests {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
 
         let (tools, _) = build_specs(
@@ -2552,7 +2614,7 @@ Examples of valid command strings:
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
-            searxng_url: "http://127.0.0.1:8080".to_string(),
+            web_search_backends: BTreeMap::new(),
         });
         let (tools, _) = build_specs(
             &tools_config,
@@ -4,6 +4,7 @@ use std::fmt::Debug;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU32;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
 
@@ -23,6 +24,10 @@ use crate::exec_policy::ExecPolicyManager;
 use crate::features::Feature;
 use crate::features::Features;
 use crate::features::maybe_push_unstable_features_warning;
+use crate::hooks;
+use crate::hooks::CompactTrigger;
+use crate::hooks::HookRunner;
+use crate::hooks::SessionStartSource;
 use crate::models_manager::manager::ModelsManager;
 use crate::parse_command::parse_command;
 use crate::parse_turn_item;
@@ -31,7 +36,10 @@ use crate::stream_events_utils::HandleOutputCtx;
 use crate::stream_events_utils::handle_non_tool_response_item;
 use crate::stream_events_utils::handle_output_item_done;
 use crate::stream_events_utils::last_assistant_message_from_item;
+use crate::structured_output::request_output_repair;
 use crate::terminal;
+use crate::tool_loop::ToolLoopDetector;
+use crate::tool_loop::check_tool_loop;
 use crate::transport_manager::TransportManager;
 use crate::truncate::TruncationPolicy;
 use crate::user_notification::UserNotifier;
@@ -55,6 +63,7 @@ use trill_protocol::protocol::HasLegacyEvent;
 use trill_protocol::protocol::ItemCompletedEvent;
 use trill_protocol::protocol::ItemStartedEvent;
 use trill_protocol::protocol::RawResponseItemEvent;
+use trill_protocol::protocol::UndoSnapshotsResponseEvent;
 use trill_protocol::protocol::ReviewRequest;
 use trill_protocol::protocol::RolloutItem;
 use trill_protocol::protocol::SessionSource;
@@ -70,13 +79,17 @@ use futures::future::BoxFuture;
 use futures::prelude::*;
 use futures::stream::FuturesOrdered;
 use mcp_types::CallToolResult;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
 use mcp_types::ListResourceTemplatesRequestParams;
 use mcp_types::ListResourceTemplatesResult;
 use mcp_types::ListResourcesRequestParams;
 use mcp_types::ListResourcesResult;
+use mcp_types::Prompt as McpPrompt;
 use mcp_types::ReadResourceRequestParams;
 use mcp_types::ReadResourceResult;
 use mcp_types::RequestId;
+use mcp_types::Root;
 use serde_json;
 use serde_json::Value;
 use tokio::sync::Mutex;
@@ -115,14 +128,18 @@ use crate::error::Result as CodexResult;
 #[cfg(test)]
 use crate::exec::StreamOutput;
 use crate::exec_policy::ExecPolicyUpdateError;
+use crate::exec_policy::ToolRuleAmendment;
 use crate::feedback_tags;
 use crate::instructions::UserInstructions;
 use crate::mcp::CODEX_APPS_MCP_SERVER_NAME;
 use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp::effective_mcp_servers;
 use crate::mcp::maybe_prompt_and_install_mcp_dependencies;
+use crate::mcp::sampling::McpSamplingManager;
+use crate::mcp::sampling::spawn_sampling_worker;
 use crate::mcp::with_codex_apps_mcp;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::mcp_connection_manager::workspace_roots;
 use crate::mentions::build_connector_slug_counts;
 use crate::mentions::build_skill_name_counts;
 use crate::mentions::collect_explicit_app_paths;
@@ -151,6 +168,7 @@ use crate::protocol::ReasoningRawContentDeltaEvent;
 use crate::protocol::RequestUserInputEvent;
 use crate::protocol::ReviewDecision;
 use crate::protocol::SandboxPolicy;
+use crate::sandboxing::DenyRead;
 use crate::protocol::SessionConfiguredEvent;
 use crate::protocol::SkillDependencies as ProtocolSkillDependencies;
 use crate::protocol::SkillErrorInfo;
@@ -184,13 +202,18 @@ use crate::skills::resolve_skill_dependencies_for_turn;
 use crate::state::ActiveTurn;
 use crate::state::SessionServices;
 use crate::state::SessionState;
+use crate::state::UndoneChange;
 use crate::state_db;
 use crate::tasks::GhostSnapshotTask;
 use crate::tasks::ReviewTask;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
+use crate::tasks::UndoTarget;
 use crate::tools::ToolRouter;
 use crate::tools::context::SharedTurnDiffTracker;
+use crate::tools::handlers::WebCache;
+use crate::tools::handlers::WebFetcher;
+use crate::tools::handlers::WebPageCache;
 use crate::tools::parallel::ToolCallRuntime;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::tools::spec::ToolsConfig;
@@ -493,12 +516,16 @@ pub(crate) struct TurnContext {
     pub(crate) approval_policy: AskForApproval,
     pub(crate) sandbox_policy: SandboxPolicy,
     pub(crate) windows_sandbox_level: WindowsSandboxLevel,
+    pub(crate) sandbox_deny_read: DenyRead,
+    pub(crate) sandbox_network_proxy: bool,
     pub(crate) shell_environment_policy: ShellEnvironmentPolicy,
     pub(crate) tools_config: ToolsConfig,
     pub(crate) ghost_snapshot: GhostSnapshotConfig,
     pub(crate) final_output_json_schema: Option<Value>,
     pub(crate) trill_linux_sandbox_exe: Option<PathBuf>,
     pub(crate) tool_call_gate: Arc<ReadinessFlag>,
+    /// Tool calls in a row whose arguments could not be parsed.
+    pub(crate) malformed_tool_calls: AtomicU32,
     pub(crate) truncation_policy: TruncationPolicy,
     pub(crate) dynamic_tools: Vec<DynamicToolSpec>,
 }
@@ -681,7 +708,7 @@ impl Session {
             model_info: &model_info,
             features: &per_turn_config.features,
             web_search_mode: per_turn_config.web_search_mode,
-            searxng_url: per_turn_config.searxng_url.clone(),
+            web_search_backends: per_turn_config.effective_web_search_backends(),
         });
 
         TurnContext {
@@ -696,12 +723,15 @@ impl Session {
             approval_policy: session_configuration.approval_policy.value(),
             sandbox_policy: session_configuration.sandbox_policy.get().clone(),
             windows_sandbox_level: session_configuration.windows_sandbox_level,
+            sandbox_deny_read: per_turn_config.sandbox_deny_read.clone(),
+            sandbox_network_proxy: per_turn_config.sandbox_network_proxy,
             shell_environment_policy: per_turn_config.shell_environment_policy.clone(),
             tools_config,
             ghost_snapshot: per_turn_config.ghost_snapshot.clone(),
             final_output_json_schema: None,
             trill_linux_sandbox_exe: per_turn_config.trill_linux_sandbox_exe.clone(),
             tool_call_gate: Arc::new(ReadinessFlag::new()),
+            malformed_tool_calls: AtomicU32::new(0),
             truncation_policy: model_info.truncation_policy.into(),
             dynamic_tools: session_configuration.dynamic_tools.clone(),
         }
@@ -901,11 +931,14 @@ impl Session {
         session_configuration.thread_name = thread_name.clone();
         let state = SessionState::new(session_configuration.clone());
 
+        let (mcp_sampling, sampling_requests) = McpSamplingManager::new();
         let services = SessionServices {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: Mutex::new(CancellationToken::new()),
+            mcp_sampling,
             unified_exec_manager: UnifiedExecProcessManager::default(),
             notifier: UserNotifier::new(config.notify.clone()),
+            hooks: HookRunner::new(&config.hooks),
             rollout: Mutex::new(rollout_recorder),
             user_shell: Arc::new(default_shell),
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -918,6 +951,9 @@ impl Session {
             agent_control,
             state_db: state_db_ctx.clone(),
             transport_manager: TransportManager::new(),
+            web_page_cache: WebPageCache::default(),
+            web_cache: WebCache::new(&config.web_search_cache),
+            web_fetcher: WebFetcher::new(&config.web_search_policy),
         };
 
         let sess = Arc::new(Session {
@@ -931,6 +967,7 @@ impl Session {
             services,
             next_internal_sub_id: AtomicU64::new(0),
         });
+        spawn_sampling_worker(Arc::downgrade(&sess), sampling_requests);
 
         // Dispatch the SessionConfiguredEvent first and then report any errors.
         // If resuming, include converted initial messages in the payload so UIs can render them immediately.
@@ -978,12 +1015,25 @@ impl Session {
                 tx_event.clone(),
                 cancel_token,
                 sandbox_state,
+                sess.services.mcp_sampling.requests(&config),
             )
             .await;
 
+        let session_start_source = match &initial_history {
+            InitialHistory::New => SessionStartSource::New,
+            InitialHistory::Resumed(_) => SessionStartSource::Resumed,
+            InitialHistory::Forked(_) => SessionStartSource::Forked,
+        };
         // record_initial_history can emit events. We record only after the SessionConfiguredEvent is emitted.
         sess.record_initial_history(initial_history).await;
 
+        // Hooks may be slow, so they must not hold up session creation.
+        let hook_sess = Arc::clone(&sess);
+        tokio::spawn(async move {
+            let turn_context = hook_sess.new_default_turn().await;
+            hooks::run_session_start(&hook_sess, &turn_context, session_start_source).await;
+        });
+
         Ok(sess)
     }
 
@@ -1160,14 +1210,16 @@ impl Session {
         sub_id: String,
         updates: SessionSettingsUpdate,
     ) -> ConstraintResult<Arc<TurnContext>> {
-        let (session_configuration, sandbox_policy_changed) = {
+        let (session_configuration, sandbox_state_changed) = {
             let mut state = self.state.lock().await;
             match state.session_configuration.clone().apply(&updates) {
                 Ok(next) => {
-                    let sandbox_policy_changed =
-                        state.session_configuration.sandbox_policy != next.sandbox_policy;
+                    // MCP servers see the cwd both in the sandbox state and as a root.
+                    let sandbox_state_changed = state.session_configuration.sandbox_policy
+                        != next.sandbox_policy
+                        || state.session_configuration.cwd != next.cwd;
                     state.session_configuration = next.clone();
-                    (next, sandbox_policy_changed)
+                    (next, sandbox_state_changed)
                 }
                 Err(err) => {
                     drop(state);
@@ -1189,7 +1241,7 @@ impl Session {
                 sub_id,
                 session_configuration,
                 updates.final_output_json_schema,
-                sandbox_policy_changed,
+                sandbox_state_changed,
             )
             .await)
     }
@@ -1199,11 +1251,11 @@ impl Session {
         sub_id: String,
         session_configuration: SessionConfiguration,
         final_output_json_schema: Option<Option<Value>>,
-        sandbox_policy_changed: bool,
+        sandbox_state_changed: bool,
     ) -> Arc<TurnContext> {
         let per_turn_config = Self::build_per_turn_config(&session_configuration);
 
-        if sandbox_policy_changed {
+        if sandbox_state_changed {
             let sandbox_state = SandboxState {
                 sandbox_policy: per_turn_config.sandbox_policy.get().clone(),
                 trill_linux_sandbox_exe: per_turn_config.trill_linux_sandbox_exe.clone(),
@@ -1504,6 +1556,30 @@ impl Session {
         Ok(())
     }
 
+    /// Adds the exact `allow` tool rules of `amendment` to both the in-memory and on-disk
+    /// policies so future calls for the same targets skip the approval prompt.
+    pub(crate) async fn persist_tool_rule_amendment(
+        &self,
+        amendment: &ToolRuleAmendment,
+    ) -> Result<(), ExecPolicyUpdateError> {
+        if !self.features.enabled(Feature::ExecPolicy) {
+            error!("attempted to append execpolicy rule while execpolicy feature is disabled");
+            return Err(ExecPolicyUpdateError::FeatureDisabled);
+        }
+        let trill_home = self
+            .state
+            .lock()
+            .await
+            .session_configuration
+            .trill_home()
+            .clone();
+
+        self.services
+            .exec_policy
+            .append_tool_rule_amendment_and_update(&trill_home, amendment)
+            .await
+    }
+
     async fn turn_context_for_sub_id(&self, sub_id: &str) -> Option<Arc<TurnContext>> {
         let active = self.active_turn.lock().await;
         active
@@ -1626,28 +1702,25 @@ impl Session {
         rx_approve
     }
 
+    /// Asks the user the given questions. The request is keyed by `call_id`,
+    /// made unique within the turn, so several requests can be pending at once.
     pub async fn request_user_input(
         &self,
         turn_context: &TurnContext,
         call_id: String,
         args: RequestUserInputArgs,
     ) -> Option<RequestUserInputResponse> {
-        let sub_id = turn_context.sub_id.clone();
         let (tx_response, rx_response) = oneshot::channel();
-        let event_id = sub_id.clone();
-        let prev_entry = {
+        let call_id = {
             let mut active = self.active_turn.lock().await;
             match active.as_mut() {
                 Some(at) => {
                     let mut ts = at.turn_state.lock().await;
-                    ts.insert_pending_user_input(sub_id, tx_response)
+                    ts.insert_pending_user_input(call_id, tx_response)
                 }
-                None => None,
+                None => call_id,
             }
         };
-        if prev_entry.is_some() {
-            warn!("Overwriting existing pending user input for sub_id: {event_id}");
-        }
 
         let event = EventMsg::RequestUserInput(RequestUserInputEvent {
             call_id,
@@ -1661,6 +1734,7 @@ impl Session {
     pub async fn notify_user_input_response(
         &self,
         sub_id: &str,
+        call_id: Option<&str>,
         response: RequestUserInputResponse,
     ) {
         let entry = {
@@ -1668,7 +1742,7 @@ impl Session {
             match active.as_mut() {
                 Some(at) => {
                     let mut ts = at.turn_state.lock().await;
-                    ts.remove_pending_user_input(sub_id)
+                    ts.remove_pending_user_input(call_id)
                 }
                 None => None,
             }
@@ -1678,7 +1752,7 @@ impl Session {
                 tx_response.send(response).ok();
             }
             None => {
-                warn!("No pending user input found for sub_id: {sub_id}");
+                warn!("No pending user input found for sub_id: {sub_id}, call_id: {call_id:?}");
             }
         }
     }
@@ -1739,6 +1813,18 @@ impl Session {
             .await
     }
 
+    pub async fn resolve_mcp_sampling(
+        &self,
+        server_name: String,
+        id: RequestId,
+        decision: ReviewDecision,
+    ) -> anyhow::Result<()> {
+        self.services
+            .mcp_sampling
+            .resolve(server_name, id, decision)
+            .await
+    }
+
     /// Records input items: always append to conversation history and
     /// persist these response items to rollout.
     pub(crate) async fn record_conversation_items(
@@ -2030,6 +2116,64 @@ impl Session {
         state.set_dependency_env(values);
     }
 
+    pub(crate) async fn push_redo(&self, change: UndoneChange) {
+        let mut state = self.state.lock().await;
+        state.push_redo(change);
+    }
+
+    pub(crate) async fn pop_redo(&self) -> Option<UndoneChange> {
+        let mut state = self.state.lock().await;
+        state.pop_redo()
+    }
+
+    pub(crate) async fn redo_available(&self) -> bool {
+        let state = self.state.lock().await;
+        state.redo_available()
+    }
+
+    async fn clear_redo(&self) {
+        let mut state = self.state.lock().await;
+        state.clear_redo();
+    }
+
+    /// Turns that can be undone, newest first.
+    pub(crate) async fn undo_snapshots(&self) -> anyhow::Result<UndoSnapshotsResponseEvent> {
+        let turn_context = self.new_default_turn().await;
+        crate::tasks::list_undo_snapshots(self, turn_context.as_ref())
+            .await
+            .map_err(anyhow::Error::msg)
+    }
+
+    /// Restores the working tree from before the last `num_turns` turns; see
+    /// `Op::UndoTurns`. Returns a summary of what was restored.
+    pub(crate) async fn undo_turns(
+        &self,
+        num_turns: u32,
+        paths: Vec<PathBuf>,
+    ) -> anyhow::Result<String> {
+        self.ensure_no_active_turn("undo").await?;
+        let turn_context = self.new_default_turn().await;
+        crate::tasks::undo_turns(self, turn_context.as_ref(), num_turns, paths)
+            .await
+            .map_err(anyhow::Error::msg)
+    }
+
+    /// Reapplies the most recent undo. Returns a summary of what was restored.
+    pub(crate) async fn redo(&self) -> anyhow::Result<String> {
+        self.ensure_no_active_turn("redo").await?;
+        let turn_context = self.new_default_turn().await;
+        crate::tasks::redo(self, turn_context.as_ref())
+            .await
+            .map_err(anyhow::Error::msg)
+    }
+
+    async fn ensure_no_active_turn(&self, action: &str) -> anyhow::Result<()> {
+        if self.active_turn.lock().await.is_some() {
+            anyhow::bail!("Cannot {action} while a turn is in progress.");
+        }
+        Ok(())
+    }
+
     pub(crate) async fn set_server_reasoning_included(&self, included: bool) {
         let mut state = self.state.lock().await;
         state.set_server_reasoning_included(included);
@@ -2233,6 +2377,35 @@ impl Session {
             .await
     }
 
+    pub async fn list_prompts(&self) -> HashMap<String, Vec<McpPrompt>> {
+        self.services
+            .mcp_connection_manager
+            .read()
+            .await
+            .list_all_prompts()
+            .await
+    }
+
+    pub async fn get_prompt(
+        &self,
+        server: &str,
+        params: GetPromptRequestParams,
+    ) -> anyhow::Result<GetPromptResult> {
+        self.services
+            .mcp_connection_manager
+            .read()
+            .await
+            .get_prompt(server, params)
+            .await
+    }
+
+    /// Roots currently advertised to MCP servers.
+    pub(crate) async fn mcp_roots(&self) -> Vec<Root> {
+        let state = self.state.lock().await;
+        let configuration = &state.session_configuration;
+        workspace_roots(&configuration.cwd, configuration.sandbox_policy.get())
+    }
+
     pub async fn call_tool(
         &self,
         server: &str,
@@ -2305,6 +2478,7 @@ impl Session {
                 self.get_tx_event(),
                 cancel_token,
                 sandbox_state,
+                self.services.mcp_sampling.requests(&config),
             )
             .await;
 
@@ -2442,8 +2616,12 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::PatchApproval { id, decision } => {
                 handlers::patch_approval(&sess, id, decision).await;
             }
-            Op::UserInputAnswer { id, response } => {
-                handlers::request_user_input_response(&sess, id, response).await;
+            Op::UserInputAnswer {
+                id,
+                call_id,
+                response,
+            } => {
+                handlers::request_user_input_response(&sess, id, call_id, response).await;
             }
             Op::DynamicToolResponse { id, response } => {
                 handlers::dynamic_tool_response(&sess, id, response).await;
@@ -2464,11 +2642,39 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::ListCustomPrompts => {
                 handlers::list_custom_prompts(&sess, sub.id.clone()).await;
             }
+            Op::ListMcpPrompts => {
+                handlers::list_mcp_prompts(&sess, sub.id.clone()).await;
+            }
+            Op::GetMcpPrompt {
+                server,
+                name,
+                arguments,
+            } => {
+                handlers::get_mcp_prompt(&sess, sub.id.clone(), server, name, arguments).await;
+            }
+            Op::RunMcpPrompt { messages } => {
+                handlers::run_mcp_prompt(&sess, sub.id.clone(), messages, &mut previous_context)
+                    .await;
+            }
             Op::ListSkills { cwds, force_reload } => {
                 handlers::list_skills(&sess, sub.id.clone(), cwds, force_reload).await;
             }
             Op::Undo => {
-                handlers::undo(&sess, sub.id.clone()).await;
+                handlers::undo(&sess, sub.id.clone(), UndoTarget::Latest).await;
+            }
+            Op::ListUndoSnapshots => {
+                handlers::list_undo_snapshots(&sess, sub.id.clone()).await;
+            }
+            Op::UndoTurns { num_turns, paths } => {
+                handlers::undo(
+                    &sess,
+                    sub.id.clone(),
+                    UndoTarget::Turns { num_turns, paths },
+                )
+                .await;
+            }
+            Op::Redo => {
+                handlers::undo(&sess, sub.id.clone(), UndoTarget::Redo).await;
             }
             Op::Compact => {
                 handlers::compact(&sess, sub.id.clone()).await;
@@ -2495,6 +2701,13 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             } => {
                 handlers::resolve_elicitation(&sess, server_name, request_id, decision).await;
             }
+            Op::ResolveMcpSampling {
+                server_name,
+                request_id,
+                decision,
+            } => {
+                handlers::resolve_mcp_sampling(&sess, server_name, request_id, decision).await;
+            }
             Op::Shutdown => {
                 if handlers::shutdown(&sess, sub.id.clone()).await {
                     break;
@@ -2521,18 +2734,24 @@ mod handlers {
     use crate::mcp::auth::compute_auth_statuses;
     use crate::mcp::collect_mcp_snapshot_from_manager;
     use crate::mcp::effective_mcp_servers;
+    use crate::mcp::prompts::prompt_turn_input;
     use crate::review_prompts::resolve_review_request;
     use crate::rollout::session_index;
     use crate::tasks::CompactTask;
     use crate::tasks::RegularTask;
+    use crate::tasks::UndoTarget;
     use crate::tasks::UndoTask;
     use crate::tasks::UserShellCommandTask;
     use trill_protocol::custom_prompts::CustomPrompt;
+    use trill_protocol::models::ResponseInputItem;
+    use trill_protocol::models::ResponseItem;
     use trill_protocol::protocol::CodexErrorInfo;
     use trill_protocol::protocol::ErrorEvent;
     use trill_protocol::protocol::Event;
     use trill_protocol::protocol::EventMsg;
+    use trill_protocol::protocol::GetMcpPromptResponseEvent;
     use trill_protocol::protocol::ListCustomPromptsResponseEvent;
+    use trill_protocol::protocol::ListMcpPromptsResponseEvent;
     use trill_protocol::protocol::ListSkillsResponseEvent;
     use trill_protocol::protocol::McpServerRefreshConfig;
     use trill_protocol::protocol::Op;
@@ -2553,7 +2772,10 @@ mod handlers {
     use trill_protocol::user_input::UserInput;
     use trill_rmcp_client::ElicitationAction;
     use trill_rmcp_client::ElicitationResponse;
+    use mcp_types::GetPromptRequestParams;
+    use mcp_types::PromptMessage;
     use mcp_types::RequestId;
+    use std::collections::HashMap;
     use std::path::PathBuf;
     use std::sync::Arc;
     use tracing::info;
@@ -2705,6 +2927,47 @@ mod handlers {
         }
     }
 
+    pub async fn run_mcp_prompt(
+        sess: &Arc<Session>,
+        sub_id: String,
+        messages: Vec<PromptMessage>,
+        previous_context: &mut Option<Arc<TurnContext>>,
+    ) {
+        let (history, items) = prompt_turn_input(messages);
+        if items.is_empty() {
+            let event = Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: "MCP prompt does not end with a user message".to_string(),
+                    codex_error_info: Some(CodexErrorInfo::BadRequest),
+                }),
+            };
+            sess.send_event_raw(event).await;
+            return;
+        }
+
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        let pending = history
+            .iter()
+            .filter_map(|item| match item {
+                ResponseItem::Message { role, content, .. } => Some(ResponseInputItem::Message {
+                    role: role.clone(),
+                    content: content.clone(),
+                }),
+                _ => None,
+            })
+            .chain(std::iter::once(ResponseInputItem::from(items.clone())))
+            .collect();
+        if sess.inject_response_items(pending).await.is_err() {
+            sess.seed_initial_context_if_needed(&turn_context).await;
+            sess.record_conversation_items(&turn_context, &history)
+                .await;
+            sess.spawn_task(Arc::clone(&turn_context), items, RegularTask)
+                .await;
+            *previous_context = Some(turn_context);
+        }
+    }
+
     pub async fn run_user_shell_command(
         sess: &Arc<Session>,
         sub_id: String,
@@ -2750,6 +3013,23 @@ mod handlers {
         }
     }
 
+    pub async fn resolve_mcp_sampling(
+        sess: &Arc<Session>,
+        server_name: String,
+        request_id: RequestId,
+        decision: ReviewDecision,
+    ) {
+        if let Err(err) = sess
+            .resolve_mcp_sampling(server_name, request_id, decision)
+            .await
+        {
+            warn!(
+                error = %err,
+                "failed to resolve MCP sampling request in session"
+            );
+        }
+    }
+
     /// Propagate a user's exec approval decision to the session.
     /// Also optionally applies an execpolicy amendment.
     pub async fn exec_approval(sess: &Arc<Session>, id: String, decision: ReviewDecision) {
@@ -2797,9 +3077,11 @@ mod handlers {
     pub async fn request_user_input_response(
         sess: &Arc<Session>,
         id: String,
+        call_id: Option<String>,
         response: RequestUserInputResponse,
     ) {
-        sess.notify_user_input_response(&id, response).await;
+        sess.notify_user_input_response(&id, call_id.as_deref(), response)
+            .await;
     }
 
     pub async fn dynamic_tool_response(
@@ -2896,6 +3178,46 @@ mod handlers {
         sess.send_event_raw(event).await;
     }
 
+    pub async fn list_mcp_prompts(sess: &Session, sub_id: String) {
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::ListMcpPromptsResponse(ListMcpPromptsResponseEvent {
+                prompts: sess.list_prompts().await,
+            }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn get_mcp_prompt(
+        sess: &Arc<Session>,
+        sub_id: String,
+        server: String,
+        name: String,
+        arguments: HashMap<String, String>,
+    ) {
+        let sess = Arc::clone(sess);
+        // A slow server must not hold up the submission loop.
+        tokio::spawn(async move {
+            let params = GetPromptRequestParams {
+                name: name.clone(),
+                arguments: (!arguments.is_empty()).then(|| serde_json::json!(arguments)),
+            };
+            let result = sess
+                .get_prompt(&server, params)
+                .await
+                .map_err(|err| format!("{err:#}"));
+            let event = Event {
+                id: sub_id,
+                msg: EventMsg::GetMcpPromptResponse(GetMcpPromptResponseEvent {
+                    server,
+                    name,
+                    result,
+                }),
+            };
+            sess.send_event_raw(event).await;
+        });
+    }
+
     pub async fn list_skills(
         sess: &Session,
         sub_id: String,
@@ -2929,12 +3251,24 @@ mod handlers {
         sess.send_event_raw(event).await;
     }
 
-    pub async fn undo(sess: &Arc<Session>, sub_id: String) {
+    pub async fn undo(sess: &Arc<Session>, sub_id: String, target: UndoTarget) {
         let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
-        sess.spawn_task(turn_context, Vec::new(), UndoTask::new())
+        sess.spawn_task(turn_context, Vec::new(), UndoTask::with_target(target))
             .await;
     }
 
+    pub async fn list_undo_snapshots(sess: &Session, sub_id: String) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id.clone()).await;
+        let msg = match crate::tasks::list_undo_snapshots(sess, turn_context.as_ref()).await {
+            Ok(response) => EventMsg::UndoSnapshotsResponse(response),
+            Err(message) => EventMsg::Error(ErrorEvent {
+                message,
+                codex_error_info: Some(CodexErrorInfo::Other),
+            }),
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
     pub async fn compact(sess: &Arc<Session>, sub_id: String) {
         let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
 
@@ -3166,7 +3500,7 @@ async fn spawn_review_thread(
         model_info: &review_model_info,
         features: &review_features,
         web_search_mode: Some(review_web_search_mode),
-        searxng_url: config.searxng_url.clone(),
+        web_search_backends: config.effective_web_search_backends(),
     });
 
     let review_prompt = resolved.prompt.clone();
@@ -3212,11 +3546,14 @@ async fn spawn_review_thread(
         approval_policy: parent_turn_context.approval_policy,
         sandbox_policy: parent_turn_context.sandbox_policy.clone(),
         windows_sandbox_level: parent_turn_context.windows_sandbox_level,
+        sandbox_deny_read: parent_turn_context.sandbox_deny_read.clone(),
+        sandbox_network_proxy: parent_turn_context.sandbox_network_proxy,
         shell_environment_policy: parent_turn_context.shell_environment_policy.clone(),
         cwd: parent_turn_context.cwd.clone(),
         final_output_json_schema: None,
         trill_linux_sandbox_exe: parent_turn_context.trill_linux_sandbox_exe.clone(),
         tool_call_gate: Arc::new(ReadinessFlag::new()),
+        malformed_tool_calls: AtomicU32::new(0),
         dynamic_tools: parent_turn_context.dynamic_tools.clone(),
         truncation_policy: model_info.truncation_policy.into(),
     };
@@ -3399,8 +3736,15 @@ pub(crate) async fn run_turn(
 
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input.clone());
     let response_item: ResponseItem = initial_input_for_turn.clone().into();
+    // A new turn diverges from anything that was undone.
+    sess.clear_redo().await;
     sess.record_user_prompt_and_emit_turn_item(turn_context.as_ref(), &input, response_item)
         .await;
+    let hook_context = hooks::run_user_prompt_submit(&sess, &turn_context, &input).await;
+    if !hook_context.is_empty() {
+        sess.record_conversation_items(&turn_context, &hook_context)
+            .await;
+    }
 
     if !skill_items.is_empty() {
         sess.record_conversation_items(&turn_context, &skill_items)
@@ -3415,6 +3759,12 @@ pub(crate) async fn run_turn(
     let turn_diff_tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::new()));
 
     let mut client_session = turn_context.client.new_session();
+    let mut output_schema_repairs = 0;
+    let mut output_repair_prompt: Option<ResponseItem> = None;
+    let mut tool_loop = ToolLoopDetector::new(
+        config.tool_loop_detection(&turn_context.client.get_model()),
+        sess.clone_history().await.raw_items(),
+    );
 
     loop {
         // Note that pending_input would be something like a message the user
@@ -3431,7 +3781,9 @@ pub(crate) async fn run_turn(
         let sampling_request_input: Vec<ResponseItem> = {
             sess.record_conversation_items(&turn_context, &pending_input)
                 .await;
-            sess.clone_history().await.for_prompt()
+            let mut input = sess.clone_history().await.for_prompt();
+            input.extend(output_repair_prompt.take());
+            input
         };
 
         let sampling_request_input_messages = sampling_request_input
@@ -3461,7 +3813,12 @@ pub(crate) async fn run_turn(
                 let SamplingRequestResult {
                     needs_follow_up,
                     last_agent_message: sampling_request_last_agent_message,
+                    sent_tools,
                 } = sampling_request_output;
+                if needs_follow_up && check_tool_loop(&sess, &turn_context, &mut tool_loop).await {
+                    last_agent_message = sampling_request_last_agent_message;
+                    break;
+                }
                 let total_usage_tokens = sess.get_total_token_usage().await;
                 let token_limit_reached = total_usage_tokens >= auto_compact_limit;
 
@@ -3472,15 +3829,33 @@ pub(crate) async fn run_turn(
                 }
 
                 if !needs_follow_up {
+                    output_repair_prompt = request_output_repair(
+                        &sess,
+                        &turn_context,
+                        sampling_request_last_agent_message.as_deref(),
+                        sent_tools,
+                        &mut output_schema_repairs,
+                    )
+                    .await;
+                    if output_repair_prompt.is_some() {
+                        continue;
+                    }
                     last_agent_message = sampling_request_last_agent_message;
                     sess.notifier()
                         .notify(&UserNotification::AgentTurnComplete {
                             thread_id: sess.conversation_id.to_string(),
                             turn_id: turn_context.sub_id.clone(),
                             cwd: turn_context.cwd.display().to_string(),
-                            input_messages: sampling_request_input_messages,
+                            input_messages: sampling_request_input_messages.clone(),
                             last_assistant_message: last_agent_message.clone(),
                         });
+                    hooks::run_turn_complete(
+                        &sess,
+                        &turn_context,
+                        sampling_request_input_messages,
+                        last_agent_message.clone(),
+                    )
+                    .await;
                     break;
                 }
                 continue;
@@ -3519,6 +3894,7 @@ pub(crate) async fn run_turn(
 }
 
 async fn run_auto_compact(sess: &Arc<Session>, turn_context: &Arc<TurnContext>) {
+    hooks::run_pre_compact(sess, turn_context, CompactTrigger::Auto).await;
     if should_use_remote_compact_task(sess.as_ref(), &turn_context.client.get_provider()) {
         run_inline_remote_auto_compact_task(Arc::clone(sess), Arc::clone(turn_context)).await;
     } else {
@@ -3770,6 +4146,8 @@ async fn run_sampling_request(
 struct SamplingRequestResult {
     needs_follow_up: bool,
     last_agent_message: Option<String>,
+    /// Whether the request offered any tools.
+    sent_tools: bool,
 }
 
 /// Ephemeral per-response state for streaming a single proposed plan.
@@ -4359,6 +4737,7 @@ async fn try_run_sampling_request(
                 break Ok(SamplingRequestResult {
                     needs_follow_up,
                     last_agent_message,
+                    sent_tools: !prompt.tools.is_empty(),
                 });
             }
             ResponseEvent::OutputTextDelta(delta) => {
@@ -4486,6 +4865,7 @@ pub(crate) use tests::make_session_and_context_with_rx;
 mod tests {
     use super::*;
     use crate::CodexAuth;
+    use crate::client_common::tools::ToolSpec;
     use crate::config::ConfigBuilder;
     use crate::config::test_config;
     use crate::exec::ExecToolCallOutput;
@@ -4515,6 +4895,7 @@ mod tests {
     use crate::tools::handlers::ShellHandler;
     use crate::tools::handlers::UnifiedExecHandler;
     use crate::tools::registry::ToolHandler;
+    use crate::tools::router::ToolCall;
     use crate::turn_diff_tracker::TurnDiffTracker;
     use trill_app_server_protocol::AppInfo;
     use trill_app_server_protocol::AuthMode;
@@ -5349,8 +5730,10 @@ mod tests {
         let services = SessionServices {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: Mutex::new(CancellationToken::new()),
+            mcp_sampling: McpSamplingManager::new().0,
             unified_exec_manager: UnifiedExecProcessManager::default(),
             notifier: UserNotifier::new(None),
+            hooks: HookRunner::default(),
             rollout: Mutex::new(None),
             user_shell: Arc::new(default_user_shell()),
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -5363,6 +5746,9 @@ mod tests {
             agent_control,
             state_db: None,
             transport_manager: TransportManager::new(),
+            web_page_cache: WebPageCache::default(),
+            web_cache: WebCache::new(&config.web_search_cache),
+            web_fetcher: WebFetcher::new(&config.web_search_policy),
         };
 
         let turn_context = Session::make_turn_context(
@@ -5466,8 +5852,10 @@ mod tests {
         let services = SessionServices {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: Mutex::new(CancellationToken::new()),
+            mcp_sampling: McpSamplingManager::new().0,
             unified_exec_manager: UnifiedExecProcessManager::default(),
             notifier: UserNotifier::new(None),
+            hooks: HookRunner::default(),
             rollout: Mutex::new(None),
             user_shell: Arc::new(default_user_shell()),
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -5480,6 +5868,9 @@ mod tests {
             agent_control,
             state_db: None,
             transport_manager: TransportManager::new(),
+            web_page_cache: WebPageCache::default(),
+            web_cache: WebCache::new(&config.web_search_cache),
+            web_fetcher: WebFetcher::new(&config.web_search_policy),
         };
 
         let turn_context = Arc::new(Session::make_turn_context(
@@ -5807,6 +6198,115 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn malformed_tool_arguments_quote_schema_until_limit() {
+        let (session, turn_context, _rx) = make_session_and_context_with_rx().await;
+        let router = ToolRouter::from_config(
+            &turn_context.tools_config,
+            None,
+            turn_context.dynamic_tools.as_slice(),
+        );
+        let tool_name = router
+            .specs()
+            .into_iter()
+            .find_map(|spec| match spec {
+                ToolSpec::Function(tool) => Some(tool.name),
+                _ => None,
+            })
+            .expect("a function tool");
+        let limit = turn_context.client.config().max_malformed_tool_calls;
+        let tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::new()));
+        let call = || ToolCall {
+            tool_name: tool_name.clone(),
+            call_id: "call-1".to_string(),
+            payload: ToolPayload::Function {
+                arguments: "run ls please".to_string(),
+            },
+        };
+
+        for _ in 0..limit {
+            let response = router
+                .dispatch_tool_call(
+                    Arc::clone(&session),
+                    Arc::clone(&turn_context),
+                    Arc::clone(&tracker),
+                    call(),
+                )
+                .await
+                .expect("malformed call is answered");
+            let ResponseInputItem::FunctionCallOutput { output, .. } = response else {
+                panic!("expected function call output, got {response:?}");
+            };
+            assert_eq!(output.success, Some(false));
+            let expected = format!("failed to parse arguments for `{tool_name}`");
+            assert!(output.content.starts_with(&expected), "{}", output.content);
+            assert!(
+                output.content.contains(r#""type":"object""#),
+                "{}",
+                output.content
+            );
+        }
+
+        let err = router
+            .dispatch_tool_call(
+                Arc::clone(&session),
+                Arc::clone(&turn_context),
+                tracker,
+                call(),
+            )
+            .await
+            .expect_err("expected the turn to stop");
+        assert!(matches!(err, FunctionCallError::Fatal(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn truncated_shell_arguments_are_not_run() {
+        let (session, turn_context, _rx) = make_session_and_context_with_rx().await;
+        let router = ToolRouter::from_config(
+            &turn_context.tools_config,
+            None,
+            turn_context.dynamic_tools.as_slice(),
+        );
+        let (tool_name, arguments) = router
+            .specs()
+            .into_iter()
+            .find_map(|spec| match spec {
+                ToolSpec::Function(tool) if tool.name == "shell" => {
+                    Some((tool.name, r#"{"command": ["rm", "-rf", "/tmp/"#))
+                }
+                ToolSpec::Function(tool) if tool.name == "shell_command" => {
+                    Some((tool.name, r#"{"command": "rm -rf /tmp/"#))
+                }
+                _ => None,
+            })
+            .expect("a shell tool");
+        let tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::new()));
+
+        let response = router
+            .dispatch_tool_call(
+                Arc::clone(&session),
+                Arc::clone(&turn_context),
+                tracker,
+                ToolCall {
+                    tool_name: tool_name.clone(),
+                    call_id: "call-1".to_string(),
+                    payload: ToolPayload::Function {
+                        arguments: arguments.to_string(),
+                    },
+                },
+            )
+            .await
+            .expect("truncated call is answered");
+
+        let ResponseInputItem::FunctionCallOutput { output, .. } = response else {
+            panic!("expected a function call output, got {response:?}");
+        };
+        assert_eq!(output.success, Some(false));
+        let expected = format!("failed to parse arguments for `{tool_name}`");
+        assert!(output.content.starts_with(&expected), "{}", output.content);
+        assert_eq!(turn_context.malformed_tool_calls.load(Ordering::SeqCst), 1);
+    }
+
     async fn sample_rollout(
         session: &Session,
         turn_context: &TurnContext,
@@ -371,22 +371,29 @@ async fn handle_request_user_input(
     let args = RequestUserInputArgs {
         questions: event.questions,
     };
-    let response_fut =
-        parent_session.request_user_input(parent_ctx, parent_ctx.sub_id.clone(), args);
+    let response_fut = parent_session.request_user_input(parent_ctx, event.call_id.clone(), args);
     let response = await_user_input_with_cancel(
         response_fut,
         parent_session,
         &parent_ctx.sub_id,
+        &event.call_id,
         cancel_token,
     )
     .await;
-    let _ = codex.submit(Op::UserInputAnswer { id, response }).await;
+    let _ = codex
+        .submit(Op::UserInputAnswer {
+            id,
+            call_id: Some(event.call_id),
+            response,
+        })
+        .await;
 }
 
 async fn await_user_input_with_cancel<F>(
     fut: F,
     parent_session: &Session,
     sub_id: &str,
+    call_id: &str,
     cancel_token: &CancellationToken,
 ) -> RequestUserInputResponse
 where
@@ -399,7 +406,7 @@ where
                 answers: HashMap::new(),
             };
             parent_session
-                .notify_user_input_response(sub_id, empty.clone())
+                .notify_user_input_response(sub_id, Some(call_id), empty.clone())
                 .await;
             empty
         }
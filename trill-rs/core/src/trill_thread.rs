@@ -9,6 +9,12 @@ use trill_protocol::openai_models::ReasoningEffort;
 use trill_protocol::protocol::AskForApproval;
 use trill_protocol::protocol::SandboxPolicy;
 use trill_protocol::protocol::SessionSource;
+use trill_protocol::protocol::UndoSnapshotsResponseEvent;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
+use mcp_types::Prompt;
+use mcp_types::Root;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use tokio::sync::watch;
 
@@ -73,4 +79,39 @@ impl TrillThread {
     pub async fn config_snapshot(&self) -> ThreadConfigSnapshot {
         self.codex.thread_config_snapshot().await
     }
+
+    /// Prompts offered by the thread's MCP servers, keyed by server name.
+    pub async fn list_mcp_prompts(&self) -> HashMap<String, Vec<Prompt>> {
+        self.codex.session.list_prompts().await
+    }
+
+    pub async fn get_mcp_prompt(
+        &self,
+        server: &str,
+        params: GetPromptRequestParams,
+    ) -> anyhow::Result<GetPromptResult> {
+        self.codex.session.get_prompt(server, params).await
+    }
+
+    /// Roots advertised to the thread's MCP servers: the cwd and any extra
+    /// writable roots.
+    pub async fn mcp_roots(&self) -> Vec<Root> {
+        self.codex.session.mcp_roots().await
+    }
+
+    /// Turns that can be undone, newest first, with the files each changed.
+    pub async fn undo_snapshots(&self) -> anyhow::Result<UndoSnapshotsResponseEvent> {
+        self.codex.session.undo_snapshots().await
+    }
+
+    /// Restores the working tree from before the last `num_turns` turns,
+    /// optionally limited to `paths`. Fails while a turn is running.
+    pub async fn undo_turns(&self, num_turns: u32, paths: Vec<PathBuf>) -> anyhow::Result<String> {
+        self.codex.session.undo_turns(num_turns, paths).await
+    }
+
+    /// Reapplies the most recent undo. Fails while a turn is running.
+    pub async fn redo(&self) -> anyhow::Result<String> {
+        self.codex.session.redo().await
+    }
 }
@@ -12,6 +12,7 @@ use tokio_util::sync::CancellationToken;
 
 use crate::exec_env::create_env;
 use crate::exec_policy::ExecApprovalRequest;
+use crate::exec_policy::exec_policy_match_context;
 use crate::protocol::ExecCommandSource;
 use crate::sandboxing::ExecEnv;
 use crate::tools::events::ToolEmitter;
@@ -498,6 +499,7 @@ impl UnifiedExecProcessManager {
                 sandbox_policy: &context.turn.sandbox_policy,
                 sandbox_permissions: request.sandbox_permissions,
                 prefix_rule: request.prefix_rule.clone(),
+                match_context: exec_policy_match_context(&cwd, &context.turn.sandbox_policy, &env),
             })
             .await;
         let req = UnifiedExecToolRequest::new(
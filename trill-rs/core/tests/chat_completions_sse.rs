@@ -60,6 +60,11 @@ async fn run_stream_with_bytes(sse_body: &[u8]) -> Vec<ResponseEvent> {
         stream_idle_timeout_ms: Some(5_000),
         requires_openai_auth: false,
         supports_websockets: false,
+        reasoning_tags: None,
+        sampling: None,
+        structured_output: None,
+        chat_capabilities: None,
+        tool_call_format: None,
     };
 
     let trill_home = match TempDir::new() {
@@ -58,6 +58,11 @@ async fn responses_stream_includes_subagent_header_on_review() {
         stream_idle_timeout_ms: Some(5_000),
         requires_openai_auth: false,
         supports_websockets: false,
+        reasoning_tags: None,
+        sampling: None,
+        structured_output: None,
+        chat_capabilities: None,
+        tool_call_format: None,
     };
 
     let trill_home = TempDir::new().expect("failed to create TempDir");
@@ -156,6 +161,11 @@ async fn responses_stream_includes_subagent_header_on_other() {
         stream_idle_timeout_ms: Some(5_000),
         requires_openai_auth: false,
         supports_websockets: false,
+        reasoning_tags: None,
+        sampling: None,
+        structured_output: None,
+        chat_capabilities: None,
+        tool_call_format: None,
     };
 
     let trill_home = TempDir::new().expect("failed to create TempDir");
@@ -310,6 +320,11 @@ async fn responses_respects_model_info_overrides_from_config() {
         stream_idle_timeout_ms: Some(5_000),
         requires_openai_auth: false,
         supports_websockets: false,
+        reasoning_tags: None,
+        sampling: None,
+        structured_output: None,
+        chat_capabilities: None,
+        tool_call_format: None,
     };
 
     let trill_home = TempDir::new().expect("failed to create TempDir");
@@ -1153,6 +1153,11 @@ async fn azure_responses_request_includes_store_and_reasoning_ids() {
         stream_idle_timeout_ms: Some(5_000),
         requires_openai_auth: false,
         supports_websockets: false,
+        reasoning_tags: None,
+        sampling: None,
+        structured_output: None,
+        chat_capabilities: None,
+        tool_call_format: None,
     };
 
     let trill_home = TempDir::new().unwrap();
@@ -1675,6 +1680,11 @@ async fn azure_overrides_assign_properties_used_for_responses_url() {
         stream_idle_timeout_ms: None,
         requires_openai_auth: false,
         supports_websockets: false,
+        reasoning_tags: None,
+        sampling: None,
+        structured_output: None,
+        chat_capabilities: None,
+        tool_call_format: None,
     };
 
     // Init session
@@ -1756,6 +1766,11 @@ async fn env_var_overrides_loaded_auth() {
         stream_idle_timeout_ms: None,
         requires_openai_auth: false,
         supports_websockets: false,
+        reasoning_tags: None,
+        sampling: None,
+        structured_output: None,
+        chat_capabilities: None,
+        tool_call_format: None,
     };
 
     // Init session
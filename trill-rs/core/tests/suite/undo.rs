@@ -2,6 +2,7 @@
 
 use std::fs;
 use std::path::Path;
+use std::path::PathBuf;
 use std::process::Command;
 use std::sync::Arc;
 
@@ -13,6 +14,7 @@ use trill_core::features::Feature;
 use trill_core::protocol::EventMsg;
 use trill_core::protocol::Op;
 use trill_core::protocol::UndoCompletedEvent;
+use trill_core::protocol::UndoSnapshotsResponseEvent;
 use core_test_support::responses::ev_apply_patch_function_call;
 use core_test_support::responses::ev_assistant_message;
 use core_test_support::responses::ev_completed;
@@ -548,3 +550,174 @@ async fn undo_preserves_unrelated_staged_changes() -> Result<()> {
 
     Ok(())
 }
+
+async fn submit_undo_op(codex: &Arc<TrillThread>, op: Op) -> Result<UndoCompletedEvent> {
+    codex.submit(op).await?;
+    let event = wait_for_event_match(codex, |msg| match msg {
+        EventMsg::UndoCompleted(done) => Some(done.clone()),
+        _ => None,
+    })
+    .await;
+    assert!(
+        event.success,
+        "expected undo to succeed but failed with message {:?}",
+        event.message
+    );
+    Ok(event)
+}
+
+async fn list_undo_snapshots(codex: &Arc<TrillThread>) -> Result<UndoSnapshotsResponseEvent> {
+    codex.submit(Op::ListUndoSnapshots).await?;
+    let event = wait_for_event_match(codex, |msg| match msg {
+        EventMsg::UndoSnapshotsResponse(response) => Some(response.clone()),
+        _ => None,
+    })
+    .await;
+    Ok(event)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn undo_turns_restores_several_turns_and_redo_reapplies_them() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    init_git_repo(harness.cwd())?;
+
+    let story = harness.path("story.txt");
+    fs::write(&story, "initial\n")?;
+    git(harness.cwd(), &["add", "story.txt"])?;
+    git(harness.cwd(), &["commit", "-m", "seed story"])?;
+
+    run_apply_patch_turn(
+        &harness,
+        "first change",
+        "multi-turn-1",
+        "*** Begin Patch\n*** Update File: story.txt\n@@\n-initial\n+turn one\n*** End Patch",
+        "ok",
+    )
+    .await?;
+    run_apply_patch_turn(
+        &harness,
+        "second change",
+        "multi-turn-2",
+        "*** Begin Patch\n*** Update File: story.txt\n@@\n-turn one\n+turn two\n*** End Patch",
+        "ok",
+    )
+    .await?;
+    assert_eq!(fs::read_to_string(&story)?, "turn two\n");
+
+    let codex = Arc::clone(&harness.test().trill);
+    let listed = list_undo_snapshots(&codex).await?;
+    let summary: Vec<(u32, String, Vec<PathBuf>)> = listed
+        .snapshots
+        .iter()
+        .map(|snapshot| {
+            (
+                snapshot.num_turns,
+                snapshot.user_message.clone(),
+                snapshot.changed_files.clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                1,
+                "second change".to_string(),
+                vec![PathBuf::from("story.txt")]
+            ),
+            (
+                2,
+                "first change".to_string(),
+                vec![PathBuf::from("story.txt")]
+            ),
+        ]
+    );
+    assert!(!listed.redo_available);
+
+    codex
+        .submit(Op::UndoTurns {
+            num_turns: 2,
+            paths: Vec::new(),
+        })
+        .await?;
+    let rolled_back = wait_for_event_match(&codex, |msg| match msg {
+        EventMsg::ThreadRolledBack(rollback) => Some(rollback.num_turns),
+        _ => None,
+    })
+    .await;
+    assert_eq!(rolled_back, 2);
+    let completed = wait_for_event_match(&codex, |msg| match msg {
+        EventMsg::UndoCompleted(done) => Some(done.clone()),
+        _ => None,
+    })
+    .await;
+    assert!(completed.success, "undo failed: {:?}", completed.message);
+    assert_eq!(fs::read_to_string(&story)?, "initial\n");
+
+    let listed = list_undo_snapshots(&codex).await?;
+    assert!(listed.snapshots.is_empty());
+    assert!(listed.redo_available);
+
+    submit_undo_op(&codex, Op::Redo).await?;
+    assert_eq!(fs::read_to_string(&story)?, "turn two\n");
+    let listed = list_undo_snapshots(&codex).await?;
+    assert_eq!(listed.snapshots.len(), 2);
+    assert!(!listed.redo_available);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn undo_turns_with_paths_restores_only_selected_files() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    init_git_repo(harness.cwd())?;
+
+    let first = harness.path("first.txt");
+    let second = harness.path("second.txt");
+    fs::write(&first, "first before\n")?;
+    fs::write(&second, "second before\n")?;
+    git(harness.cwd(), &["add", "first.txt", "second.txt"])?;
+    git(harness.cwd(), &["commit", "-m", "seed files"])?;
+
+    run_apply_patch_turn(
+        &harness,
+        "edit both files",
+        "per-file-turn",
+        "*** Begin Patch\n*** Update File: first.txt\n@@\n-first before\n+first after\n*** Update File: second.txt\n@@\n-second before\n+second after\n*** End Patch",
+        "ok",
+    )
+    .await?;
+    assert_eq!(fs::read_to_string(&first)?, "first after\n");
+    assert_eq!(fs::read_to_string(&second)?, "second after\n");
+
+    let codex = Arc::clone(&harness.test().trill);
+    submit_undo_op(
+        &codex,
+        Op::UndoTurns {
+            num_turns: 1,
+            paths: vec![PathBuf::from("first.txt")],
+        },
+    )
+    .await?;
+    assert_eq!(fs::read_to_string(&first)?, "first before\n");
+    assert_eq!(fs::read_to_string(&second)?, "second after\n");
+
+    // The turn stays in the conversation, so it can still be listed and undone.
+    let listed = list_undo_snapshots(&codex).await?;
+    assert_eq!(listed.snapshots.len(), 1);
+    assert_eq!(
+        listed.snapshots[0].changed_files,
+        vec![PathBuf::from("second.txt")]
+    );
+    assert!(listed.redo_available);
+
+    submit_undo_op(&codex, Op::Redo).await?;
+    assert_eq!(fs::read_to_string(&first)?, "first after\n");
+    assert_eq!(fs::read_to_string(&second)?, "second after\n");
+
+    Ok(())
+}
@@ -28,6 +28,7 @@ use trill_core::protocol::PatchApplyBeginEvent;
 use trill_core::protocol::PatchApplyEndEvent;
 use trill_core::protocol::SessionConfiguredEvent;
 use trill_core::protocol::StreamErrorEvent;
+use trill_core::protocol::ToolCallLoopDetectedEvent;
 use trill_core::protocol::TurnAbortReason;
 use trill_core::protocol::TurnCompleteEvent;
 use trill_core::protocol::TurnDiffEvent;
@@ -188,6 +189,22 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     "warning:".style(self.yellow).style(self.bold)
                 );
             }
+            EventMsg::ToolCallLoopDetected(ToolCallLoopDetectedEvent { message, .. }) => {
+                ts_msg!(
+                    self,
+                    "{} {message}",
+                    "warning:".style(self.yellow).style(self.bold)
+                );
+            }
+            EventMsg::HookCompleted(ev) => {
+                if let Some(message) = ev.warning_message() {
+                    ts_msg!(
+                        self,
+                        "{} {message}",
+                        "warning:".style(self.yellow).style(self.bold)
+                    );
+                }
+            }
             EventMsg::DeprecationNotice(DeprecationNoticeEvent { summary, details }) => {
                 ts_msg!(
                     self,
@@ -264,6 +281,27 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     "auto-cancelling (not supported in exec mode)".style(self.dimmed)
                 );
             }
+            EventMsg::McpSamplingRequest(ev) => {
+                ts_msg!(
+                    self,
+                    "{} {}",
+                    "sampling request".style(self.magenta),
+                    ev.server_name.style(self.dimmed)
+                );
+                ts_msg!(
+                    self,
+                    "{}",
+                    "auto-declining (not supported in exec mode)".style(self.dimmed)
+                );
+            }
+            EventMsg::McpSamplingProgress(ev) => {
+                ts_msg!(
+                    self,
+                    "{} {}",
+                    format!("{} sampling:", ev.server_name).style(self.magenta),
+                    ev.message.style(self.dimmed)
+                );
+            }
             EventMsg::TurnComplete(TurnCompleteEvent { last_agent_message }) => {
                 let last_message = last_agent_message
                     .as_deref()
@@ -771,6 +809,9 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::GetHistoryEntryResponse(_)
             | EventMsg::McpListToolsResponse(_)
             | EventMsg::ListCustomPromptsResponse(_)
+            | EventMsg::ListMcpPromptsResponse(_)
+            | EventMsg::GetMcpPromptResponse(_)
+            | EventMsg::UndoSnapshotsResponse(_)
             | EventMsg::ListSkillsResponse(_)
             | EventMsg::RawResponseItem(_)
             | EventMsg::UserMessage(_)
@@ -16,12 +16,14 @@ pub use cli::ReviewArgs;
 use trill_cloud_requirements::cloud_requirements_loader;
 use trill_common::oss::ensure_oss_provider_ready;
 use trill_common::oss::get_default_model_for_oss_provider;
+use trill_common::oss::detect_local_server_wire_api;
 use trill_common::oss::ollama_chat_deprecation_notice;
 use trill_core::AuthManager;
 use trill_core::LMSTUDIO_OSS_PROVIDER_ID;
 use trill_core::NewThread;
 use trill_core::OLLAMA_CHAT_PROVIDER_ID;
 use trill_core::OLLAMA_OSS_PROVIDER_ID;
+use trill_core::OPENAI_COMPATIBLE_OSS_PROVIDER_IDS;
 use trill_core::ThreadManager;
 use trill_core::auth::enforce_login_restrictions;
 use trill_core::config::Config;
@@ -38,6 +40,7 @@ use trill_core::protocol::AskForApproval;
 use trill_core::protocol::Event;
 use trill_core::protocol::EventMsg;
 use trill_core::protocol::Op;
+use trill_core::protocol::ReviewDecision;
 use trill_core::protocol::ReviewRequest;
 use trill_core::protocol::ReviewTarget;
 use trill_core::protocol::SessionSource;
@@ -218,8 +221,9 @@ pub async fn run_main(cli: Cli, trill_linux_sandbox_exe: Option<PathBuf>) -> any
         if let Some(provider) = resolved {
             Some(provider)
         } else {
+            let local_servers = OPENAI_COMPATIBLE_OSS_PROVIDER_IDS.join(", ");
             return Err(anyhow::anyhow!(
-                "No default OSS provider configured. Use --local-provider=provider or set oss_provider to one of: {LMSTUDIO_OSS_PROVIDER_ID}, {OLLAMA_OSS_PROVIDER_ID}, {OLLAMA_CHAT_PROVIDER_ID} in config.toml"
+                "No default OSS provider configured. Use --local-provider=provider or set oss_provider to one of: {LMSTUDIO_OSS_PROVIDER_ID}, {OLLAMA_OSS_PROVIDER_ID}, {OLLAMA_CHAT_PROVIDER_ID}, {local_servers} in config.toml"
             ));
         }
     } else {
@@ -260,7 +264,7 @@ pub async fn run_main(cli: Cli, trill_linux_sandbox_exe: Option<PathBuf>) -> any
         additional_writable_roots: add_dir,
     };
 
-    let config = ConfigBuilder::default()
+    let mut config = ConfigBuilder::default()
         .cli_overrides(cli_kv_overrides)
         .harness_overrides(overrides)
         .cloud_requirements(cloud_requirements)
@@ -280,6 +284,9 @@ pub async fn run_main(cli: Cli, trill_linux_sandbox_exe: Option<PathBuf>) -> any
             None
         }
     };
+    if let Err(err) = detect_local_server_wire_api(&mut config).await {
+        tracing::warn!(?err, "Failed to detect local server wire API");
+    }
 
     let otel = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
         trill_core::otel_init::build_provider(&config, env!("CARGO_PKG_VERSION"), None, false)
@@ -548,6 +555,16 @@ pub async fn run_main(cli: Cli, trill_linux_sandbox_exe: Option<PathBuf>) -> any
                 })
                 .await?;
         }
+        if let EventMsg::McpSamplingRequest(ev) = &event.msg {
+            // Sampling needs an approval prompt, which exec mode cannot show.
+            thread
+                .submit(Op::ResolveMcpSampling {
+                    server_name: ev.server_name.clone(),
+                    request_id: ev.id.clone(),
+                    decision: ReviewDecision::Denied,
+                })
+                .await?;
+        }
         if matches!(event.msg, EventMsg::Error(_)) {
             error_seen = true;
         }
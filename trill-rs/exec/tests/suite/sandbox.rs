@@ -50,6 +50,8 @@ async fn spawn_command_under_sandbox(
         command_cwd,
         sandbox_policy,
         sandbox_cwd,
+        &[],
+        None,
         stdio_policy,
         env,
     )
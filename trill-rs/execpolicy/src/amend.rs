@@ -9,6 +9,9 @@ use std::path::PathBuf;
 use serde_json;
 use thiserror::Error;
 
+use crate::tool_rule::FileOp;
+use crate::tool_rule::is_literal_glob;
+
 #[derive(Debug, Error)]
 pub enum AmendError {
     #[error("prefix rule requires at least one token")]
@@ -22,6 +25,12 @@ pub enum AmendError {
     },
     #[error("failed to format prefix tokens: {source}")]
     SerializePrefix { source: serde_json::Error },
+    #[error("failed to format rule arguments: {source}")]
+    SerializeRuleArgs { source: serde_json::Error },
+    #[error("cannot save an exact rule for `{value}`: it contains glob characters")]
+    NotLiteral { value: String },
+    #[error("file rule requires at least one op")]
+    EmptyFileOps,
     #[error("failed to open policy file {path}: {source}")]
     OpenPolicyFile {
         path: PathBuf,
@@ -71,7 +80,65 @@ pub fn blocking_append_allow_prefix_rule(
         .map_err(|source| AmendError::SerializePrefix { source })?;
     let pattern = format!("[{}]", tokens.join(", "));
     let rule = format!(r#"prefix_rule(pattern={pattern}, decision="allow")"#);
+    blocking_append_rule(policy_path, &rule)
+}
+
+/// Appends `mcp_tool_rule(server=..., tool=..., decision="allow")`. Same locking and blocking
+/// caveats as [`blocking_append_allow_prefix_rule`].
+pub fn blocking_append_allow_mcp_tool_rule(
+    policy_path: &Path,
+    server: &str,
+    tool: &str,
+) -> Result<(), AmendError> {
+    let server = quote_literal_arg(server)?;
+    let tool = quote_literal_arg(tool)?;
+    let rule = format!(r#"mcp_tool_rule(server={server}, tool={tool}, decision="allow")"#);
+    blocking_append_rule(policy_path, &rule)
+}
+
+/// Appends `url_rule(domain_glob=..., decision="allow")` for exactly `host`. Same locking and
+/// blocking caveats as [`blocking_append_allow_prefix_rule`].
+pub fn blocking_append_allow_url_rule(policy_path: &Path, host: &str) -> Result<(), AmendError> {
+    let host = quote_literal_arg(host)?;
+    let rule = format!(r#"url_rule(domain_glob={host}, decision="allow")"#);
+    blocking_append_rule(policy_path, &rule)
+}
+
+/// Appends `file_rule(path_glob=..., ops=[...], decision="allow")` for exactly `path`. Same
+/// locking and blocking caveats as [`blocking_append_allow_prefix_rule`].
+pub fn blocking_append_allow_file_rule(
+    policy_path: &Path,
+    path: &str,
+    ops: &[FileOp],
+) -> Result<(), AmendError> {
+    if ops.is_empty() {
+        return Err(AmendError::EmptyFileOps);
+    }
+    let path = quote_literal_arg(path)?;
+    let ops = ops
+        .iter()
+        .map(|op| quote_rule_arg(op.as_str()))
+        .collect::<Result<Vec<_>, _>>()?
+        .join(", ");
+    let rule = format!(r#"file_rule(path_glob={path}, ops=[{ops}], decision="allow")"#);
+    blocking_append_rule(policy_path, &rule)
+}
+
+fn quote_rule_arg(value: &str) -> Result<String, AmendError> {
+    serde_json::to_string(value).map_err(|source| AmendError::SerializeRuleArgs { source })
+}
 
+/// Quotes a glob argument that must match only `value` itself, so the saved rule is exact.
+fn quote_literal_arg(value: &str) -> Result<String, AmendError> {
+    if !is_literal_glob(value) {
+        return Err(AmendError::NotLiteral {
+            value: value.to_string(),
+        });
+    }
+    quote_rule_arg(value)
+}
+
+fn blocking_append_rule(policy_path: &Path, rule: &str) -> Result<(), AmendError> {
     let dir = policy_path
         .parent()
         .ok_or_else(|| AmendError::MissingParent {
@@ -87,7 +154,7 @@ pub fn blocking_append_allow_prefix_rule(
             });
         }
     }
-    append_locked_line(policy_path, &rule)
+    append_locked_line(policy_path, rule)
 }
 
 fn append_locked_line(policy_path: &Path, line: &str) -> Result<(), AmendError> {
@@ -222,4 +289,58 @@ prefix_rule(pattern=["echo", "Hello, world!"], decision="allow")
 "#
         );
     }
+
+    #[test]
+    fn appends_mcp_tool_rule() {
+        let tmp = tempdir().expect("create temp dir");
+        let policy_path = tmp.path().join("rules").join("default.rules");
+
+        blocking_append_allow_mcp_tool_rule(&policy_path, "github", "create_issue")
+            .expect("append mcp tool rule");
+
+        let contents = std::fs::read_to_string(&policy_path).expect("read policy");
+        assert_eq!(
+            contents,
+            r#"mcp_tool_rule(server="github", tool="create_issue", decision="allow")
+"#
+        );
+    }
+
+    #[test]
+    fn appends_url_and_file_rules() {
+        let tmp = tempdir().expect("create temp dir");
+        let policy_path = tmp.path().join("rules").join("default.rules");
+
+        blocking_append_allow_url_rule(&policy_path, "docs.rs").expect("append url rule");
+        blocking_append_allow_file_rule(
+            &policy_path,
+            "/workspace/Cargo.lock",
+            &[FileOp::Update, FileOp::Delete],
+        )
+        .expect("append file rule");
+
+        let contents = std::fs::read_to_string(&policy_path).expect("read policy");
+        assert_eq!(
+            contents,
+            r#"url_rule(domain_glob="docs.rs", decision="allow")
+file_rule(path_glob="/workspace/Cargo.lock", ops=["update", "delete"], decision="allow")
+"#
+        );
+    }
+
+    #[test]
+    fn refuses_to_save_globs() {
+        let tmp = tempdir().expect("create temp dir");
+        let policy_path = tmp.path().join("rules").join("default.rules");
+
+        assert!(matches!(
+            blocking_append_allow_url_rule(&policy_path, "*.example.com"),
+            Err(AmendError::NotLiteral { .. })
+        ));
+        assert!(matches!(
+            blocking_append_allow_file_rule(&policy_path, "src/[ab].rs", &[FileOp::Add]),
+            Err(AmendError::NotLiteral { .. })
+        ));
+        assert!(!policy_path.exists());
+    }
 }
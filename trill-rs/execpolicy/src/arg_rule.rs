@@ -0,0 +1,482 @@
+use crate::decision::Decision;
+use crate::error::Error;
+use crate::error::Result;
+use crate::rule::MatchContext;
+use crate::rule::Rule;
+use crate::rule::RuleMatch;
+use globset::Glob;
+use globset::GlobMatcher;
+use regex_lite::Regex;
+use std::any::Any;
+use std::fmt;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Matches a single command token. Unlike [`crate::rule::PatternToken`], tokens may also be
+/// matched by a glob (`{"glob": "--force*"}`) or an anchored regex (`{"regex": "-[a-z]*f"}`).
+#[derive(Clone, Debug)]
+pub enum TokenMatcher {
+    Literal(String),
+    Alts(Vec<String>),
+    Glob(GlobMatcher),
+    Regex { source: String, regex: Regex },
+}
+
+impl TokenMatcher {
+    pub fn glob(pattern: &str) -> Result<Self> {
+        Glob::new(pattern)
+            .map(|glob| Self::Glob(glob.compile_matcher()))
+            .map_err(|err| Error::InvalidPattern(format!("invalid glob `{pattern}`: {err}")))
+    }
+
+    /// Builds a regex matcher. The regex must match the whole token.
+    pub fn regex(pattern: &str) -> Result<Self> {
+        Regex::new(&format!("^(?:{pattern})$"))
+            .map(|regex| Self::Regex {
+                source: pattern.to_string(),
+                regex,
+            })
+            .map_err(|err| Error::InvalidPattern(format!("invalid regex `{pattern}`: {err}")))
+    }
+
+    pub fn matches(&self, token: &str) -> bool {
+        match self {
+            Self::Literal(expected) => expected == token,
+            Self::Alts(alternatives) => alternatives.iter().any(|alt| alt == token),
+            Self::Glob(matcher) => matcher.is_match(token),
+            Self::Regex { regex, .. } => regex.is_match(token),
+        }
+    }
+
+    /// Literal values this matcher accepts, or `None` for glob and regex matchers.
+    pub fn literal_alternatives(&self) -> Option<&[String]> {
+        match self {
+            Self::Literal(expected) => Some(std::slice::from_ref(expected)),
+            Self::Alts(alternatives) => Some(alternatives),
+            Self::Glob(_) | Self::Regex { .. } => None,
+        }
+    }
+}
+
+/// Renders the matcher in policy syntax.
+impl fmt::Display for TokenMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Literal(expected) => write!(f, "{expected:?}"),
+            Self::Alts(alternatives) => {
+                write!(f, "{}", render_list(alternatives, |alt| format!("{alt:?}")))
+            }
+            Self::Glob(matcher) => write!(f, "{{\"glob\": {:?}}}", matcher.glob().glob()),
+            Self::Regex { source, .. } => write!(f, "{{\"regex\": {source:?}}}"),
+        }
+    }
+}
+
+/// Prefix matcher for `arg_rule` / `path_rule`. As with prefix rules, the first token is fixed
+/// because policies are keyed by program.
+#[derive(Clone, Debug)]
+pub struct ArgPattern {
+    pub first: Arc<str>,
+    pub rest: Arc<[TokenMatcher]>,
+}
+
+impl ArgPattern {
+    /// Returns the length of the matched prefix.
+    fn matches_prefix(&self, cmd: &[String]) -> Option<usize> {
+        let pattern_length = self.rest.len() + 1;
+        if cmd.len() < pattern_length || cmd[0] != self.first.as_ref() {
+            return None;
+        }
+
+        self.rest
+            .iter()
+            .zip(&cmd[1..pattern_length])
+            .all(|(matcher, token)| matcher.matches(token))
+            .then_some(pattern_length)
+    }
+}
+
+impl fmt::Display for ArgPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{:?}", self.first.as_ref())?;
+        for matcher in self.rest.iter() {
+            write!(f, ", {matcher}")?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// Requires an environment variable to be set to a matching value, or to be unset when
+/// `matcher` is `None`.
+#[derive(Clone, Debug)]
+pub struct EnvCondition {
+    pub name: String,
+    pub matcher: Option<TokenMatcher>,
+}
+
+impl EnvCondition {
+    /// Returns a description of why the condition holds, or `None` when it does not.
+    fn check(&self, ctx: &MatchContext) -> Option<String> {
+        let name = &self.name;
+        let Some(env) = &ctx.env else {
+            return ctx
+                .assume_env
+                .then(|| format!("env `{name}` assumed to match (environment unknown)"));
+        };
+        match (&self.matcher, env.get(name)) {
+            (None, None) => Some(format!("env `{name}` is unset")),
+            (Some(matcher), Some(value)) if matcher.matches(value) => {
+                Some(format!("env `{name}={value}` matches {matcher}"))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Matches commands by prefix plus conditions on the remaining arguments: `any_arg` fires when
+/// any argument after the prefix matches (flags anywhere on the command line), `all_args` when
+/// every positional argument matches.
+#[derive(Clone, Debug)]
+pub struct ArgRule {
+    pub pattern: ArgPattern,
+    pub any_arg: Vec<TokenMatcher>,
+    pub all_args: Vec<TokenMatcher>,
+    pub env: Vec<EnvCondition>,
+    pub decision: Decision,
+    pub justification: Option<String>,
+}
+
+impl Rule for ArgRule {
+    fn program(&self) -> &str {
+        self.pattern.first.as_ref()
+    }
+
+    fn matches(&self, cmd: &[String]) -> Option<RuleMatch> {
+        self.matches_with_context(cmd, &MatchContext::default())
+    }
+
+    fn matches_with_context(&self, cmd: &[String], ctx: &MatchContext) -> Option<RuleMatch> {
+        let prefix_length = self.pattern.matches_prefix(cmd)?;
+        let mut reasons = check_env(&self.env, ctx)?;
+        let args = &cmd[prefix_length..];
+
+        if !self.any_arg.is_empty() {
+            let (token, matcher) = args.iter().find_map(|token| {
+                self.any_arg
+                    .iter()
+                    .find(|matcher| matcher.matches(token))
+                    .map(|matcher| (token, matcher))
+            })?;
+            reasons.push(format!("argument `{token}` matches {matcher}"));
+        }
+
+        if !self.all_args.is_empty() {
+            let positional = positional_args(args);
+            if !positional
+                .iter()
+                .all(|token| self.all_args.iter().any(|matcher| matcher.matches(token)))
+            {
+                return None;
+            }
+            reasons.push(format!(
+                "all {} positional argument(s) match {}",
+                positional.len(),
+                render_list(&self.all_args, ToString::to_string)
+            ));
+        }
+
+        Some(rule_match(
+            cmd,
+            prefix_length,
+            self.decision,
+            &self.justification,
+            self.to_string(),
+            reasons,
+        ))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl fmt::Display for ArgRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "arg_rule(pattern={}", self.pattern)?;
+        if !self.any_arg.is_empty() {
+            let any_arg = render_list(&self.any_arg, ToString::to_string);
+            write!(f, ", any_arg={any_arg}")?;
+        }
+        if !self.all_args.is_empty() {
+            let all_args = render_list(&self.all_args, ToString::to_string);
+            write!(f, ", all_args={all_args}")?;
+        }
+        write_env_and_decision(f, &self.env, self.decision)
+    }
+}
+
+/// A root that `path_rule` scopes path arguments to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PathRoot {
+    /// The command's cwd plus the sandbox's writable roots.
+    Workspace,
+    /// A fixed directory; relative directories resolve against the command's cwd.
+    Path(PathBuf),
+}
+
+impl PathRoot {
+    fn resolve(&self, ctx: &MatchContext) -> Vec<PathBuf> {
+        match self {
+            Self::Workspace => ctx
+                .cwd
+                .iter()
+                .chain(&ctx.writable_roots)
+                .map(PathBuf::as_path)
+                .map(canonicalize_existing)
+                .collect(),
+            Self::Path(path) => resolve_path(path, ctx.cwd.as_deref()).into_iter().collect(),
+        }
+    }
+}
+
+impl fmt::Display for PathRoot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Workspace => write!(f, "\"workspace\""),
+            Self::Path(path) => write!(f, "{:?}", path.to_string_lossy()),
+        }
+    }
+}
+
+/// Matches commands by prefix plus where their path arguments point: positional arguments and
+/// `--flag=value` values that look like paths. By default the rule fires when every path argument
+/// lies within one of `within`; with `outside` set it fires when any path argument lies outside
+/// of them. Paths and roots are canonicalized, so neither `..` nor a symlink inside a root can be
+/// used to escape it.
+#[derive(Clone, Debug)]
+pub struct PathRule {
+    pub pattern: ArgPattern,
+    pub within: Vec<PathRoot>,
+    pub outside: bool,
+    pub env: Vec<EnvCondition>,
+    pub decision: Decision,
+    pub justification: Option<String>,
+}
+
+impl Rule for PathRule {
+    fn program(&self) -> &str {
+        self.pattern.first.as_ref()
+    }
+
+    fn matches(&self, cmd: &[String]) -> Option<RuleMatch> {
+        self.matches_with_context(cmd, &MatchContext::default())
+    }
+
+    fn matches_with_context(&self, cmd: &[String], ctx: &MatchContext) -> Option<RuleMatch> {
+        let prefix_length = self.pattern.matches_prefix(cmd)?;
+        let mut reasons = check_env(&self.env, ctx)?;
+
+        let roots: Vec<PathBuf> = self
+            .within
+            .iter()
+            .flat_map(|root| root.resolve(ctx))
+            .collect();
+        let is_inside = |token: &str| {
+            path_arg(token, ctx).is_some_and(|path| roots.iter().any(|root| path.starts_with(root)))
+        };
+        let paths = path_args(&cmd[prefix_length..]);
+        let within = render_list(&self.within, ToString::to_string);
+
+        if self.outside {
+            let outside = paths.iter().find(|token| !is_inside(token))?;
+            reasons.push(format!("path `{outside}` is outside {within}"));
+        } else {
+            if paths.is_empty() || !paths.iter().all(|token| is_inside(token)) {
+                return None;
+            }
+            reasons.push(format!(
+                "path(s) {} are within {within}",
+                render_list(&paths, |path| format!("`{path}`"))
+            ));
+        }
+
+        Some(rule_match(
+            cmd,
+            prefix_length,
+            self.decision,
+            &self.justification,
+            self.to_string(),
+            reasons,
+        ))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl fmt::Display for PathRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let within = render_list(&self.within, ToString::to_string);
+        write!(f, "path_rule(pattern={}, within={within}", self.pattern)?;
+        if self.outside {
+            write!(f, ", outside=True")?;
+        }
+        write_env_and_decision(f, &self.env, self.decision)
+    }
+}
+
+fn check_env(conditions: &[EnvCondition], ctx: &MatchContext) -> Option<Vec<String>> {
+    conditions
+        .iter()
+        .map(|condition| condition.check(ctx))
+        .collect()
+}
+
+fn rule_match(
+    cmd: &[String],
+    prefix_length: usize,
+    decision: Decision,
+    justification: &Option<String>,
+    rule: String,
+    reasons: Vec<String>,
+) -> RuleMatch {
+    let matched_prefix = cmd[..prefix_length].to_vec();
+    let mut explanation = format!("prefix `{}` matched", matched_prefix.join(" "));
+    for reason in reasons {
+        explanation.push_str("; ");
+        explanation.push_str(&reason);
+    }
+
+    RuleMatch::ArgRuleMatch {
+        matched_prefix,
+        decision,
+        justification: justification.clone(),
+        rule,
+        explanation,
+    }
+}
+
+/// Arguments that are not flags. Everything after `--` is positional; a lone `-` (stdin) is not.
+fn positional_args(args: &[String]) -> Vec<&str> {
+    let mut positional = Vec::new();
+    let mut after_separator = false;
+    for arg in args {
+        if after_separator {
+            positional.push(arg.as_str());
+        } else if arg == "--" {
+            after_separator = true;
+        } else if !arg.starts_with('-') {
+            positional.push(arg.as_str());
+        }
+    }
+    positional
+}
+
+/// Positional arguments plus the values of `--flag=value` arguments that look like paths.
+fn path_args(args: &[String]) -> Vec<&str> {
+    let mut paths = positional_args(args);
+    let flags = args.iter().take_while(|arg| arg.as_str() != "--");
+    paths.extend(flags.filter_map(|arg| {
+        let (_, value) = arg.strip_prefix('-')?.split_once('=')?;
+        (value.contains('/') || value.starts_with('~') || value.starts_with('.')).then_some(value)
+    }));
+    paths
+}
+
+/// Resolves a path argument. Arguments the shell would still expand (`~`, `$VAR`) cannot be
+/// resolved and are never considered inside a root, and neither are relative paths when the
+/// command may not run in `ctx.cwd`.
+fn path_arg(token: &str, ctx: &MatchContext) -> Option<PathBuf> {
+    if token.starts_with('~') || token.contains('$') {
+        return None;
+    }
+    let path = Path::new(token);
+    if !path.is_absolute() && ctx.cwd_may_change {
+        return None;
+    }
+    resolve_path(path, ctx.cwd.as_deref())
+}
+
+fn resolve_path(path: &Path, cwd: Option<&Path>) -> Option<PathBuf> {
+    if path.is_absolute() {
+        Some(canonicalize_existing(path))
+    } else {
+        cwd.map(|cwd| canonicalize_existing(&cwd.join(path)))
+    }
+}
+
+/// Canonicalizes the longest existing ancestor of `path` (resolving symlinks) and appends the
+/// rest lexically. Paths that do not exist yet still resolve, so `rm new/file` can be scoped.
+fn canonicalize_existing(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut missing = Vec::new();
+    loop {
+        if let Ok(mut canonical) = existing.canonicalize() {
+            canonical.extend(missing.iter().rev());
+            return normalize_path(&canonical);
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => return normalize_path(path),
+        }
+    }
+}
+
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+fn render_list<T>(items: &[T], render: impl Fn(&T) -> String) -> String {
+    let rendered: Vec<String> = items.iter().map(render).collect();
+    format!("[{}]", rendered.join(", "))
+}
+
+fn write_env_and_decision(
+    f: &mut fmt::Formatter<'_>,
+    env: &[EnvCondition],
+    decision: Decision,
+) -> fmt::Result {
+    if !env.is_empty() {
+        let conditions: Vec<String> = env
+            .iter()
+            .map(|condition| match &condition.matcher {
+                Some(matcher) => format!("{:?}: {matcher}", condition.name),
+                None => format!("{:?}: None", condition.name),
+            })
+            .collect();
+        write!(f, ", env={{{}}}", conditions.join(", "))?;
+    }
+    write!(f, ", decision={:?})", decision.as_str())
+}
+
+/// Validates that a pattern's first matcher names literal programs and expands it, one program
+/// per alternative.
+pub(crate) fn split_program(
+    matchers: Vec<TokenMatcher>,
+) -> Result<(Vec<String>, Arc<[TokenMatcher]>)> {
+    let (first, rest) = matchers
+        .split_first()
+        .ok_or_else(|| Error::InvalidPattern("pattern cannot be empty".to_string()))?;
+    let programs = first.literal_alternatives().ok_or_else(|| {
+        Error::InvalidPattern(
+            "the first pattern element must be a string or list of strings".to_string(),
+        )
+    })?;
+    Ok((programs.to_vec(), rest.to_vec().into()))
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -7,6 +8,7 @@ use clap::Parser;
 use serde::Serialize;
 
 use crate::Decision;
+use crate::MatchContext;
 use crate::Policy;
 use crate::PolicyParser;
 use crate::RuleMatch;
@@ -22,6 +24,20 @@ pub struct ExecPolicyCheckCommand {
     #[arg(long)]
     pub pretty: bool,
 
+    /// Directory the command runs in; `path_rule` resolves relative paths against it.
+    /// Defaults to the current directory.
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Additional writable root that counts as part of the workspace (repeatable).
+    #[arg(long = "writable-root", value_name = "DIR")]
+    pub writable_roots: Vec<PathBuf>,
+
+    /// Environment variable the command runs with (repeatable). When omitted, `env`
+    /// conditions are assumed to hold.
+    #[arg(long = "env", value_name = "KEY=VALUE", value_parser = parse_env_var)]
+    pub env: Vec<(String, String)>,
+
     /// Command tokens to check against the policy.
     #[arg(
         value_name = "COMMAND",
@@ -36,13 +52,32 @@ impl ExecPolicyCheckCommand {
     /// Load the policies for this command, evaluate the command, and render JSON output.
     pub fn run(&self) -> Result<()> {
         let policy = load_policies(&self.rules)?;
-        let matched_rules = policy.matches_for_command(&self.command, None);
+        let matched_rules =
+            policy.matches_for_command_with_context(&self.command, &self.match_context(), None);
 
         let json = format_matches_json(&matched_rules, self.pretty)?;
         println!("{json}");
 
         Ok(())
     }
+
+    fn match_context(&self) -> MatchContext {
+        MatchContext {
+            cwd: self.cwd.clone().or_else(|| std::env::current_dir().ok()),
+            writable_roots: self.writable_roots.clone(),
+            env: (!self.env.is_empty())
+                .then(|| self.env.iter().cloned().collect::<HashMap<_, _>>()),
+            cwd_may_change: false,
+            assume_env: self.env.is_empty(),
+        }
+    }
+}
+
+fn parse_env_var(raw: &str) -> Result<(String, String)> {
+    let (key, value) = raw
+        .split_once('=')
+        .with_context(|| format!("expected KEY=VALUE, got `{raw}`"))?;
+    Ok((key.to_string(), value.to_string()))
 }
 
 pub fn format_matches_json(matched_rules: &[RuleMatch], pretty: bool) -> Result<String> {
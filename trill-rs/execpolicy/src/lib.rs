@@ -1,13 +1,18 @@
 pub mod amend;
+pub mod arg_rule;
 pub mod decision;
 pub mod error;
 pub mod execpolicycheck;
 pub mod parser;
 pub mod policy;
 pub mod rule;
+pub mod tool_rule;
 
 pub use amend::AmendError;
+pub use amend::blocking_append_allow_file_rule;
+pub use amend::blocking_append_allow_mcp_tool_rule;
 pub use amend::blocking_append_allow_prefix_rule;
+pub use amend::blocking_append_allow_url_rule;
 pub use decision::Decision;
 pub use error::Error;
 pub use error::ErrorLocation;
@@ -18,6 +23,9 @@ pub use execpolicycheck::ExecPolicyCheckCommand;
 pub use parser::PolicyParser;
 pub use policy::Evaluation;
 pub use policy::Policy;
+pub use rule::MatchContext;
 pub use rule::Rule;
 pub use rule::RuleMatch;
 pub use rule::RuleRef;
+pub use tool_rule::FileOp;
+pub use tool_rule::ToolRule;
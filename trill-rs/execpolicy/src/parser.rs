@@ -8,13 +8,22 @@ use starlark::starlark_module;
 use starlark::syntax::AstModule;
 use starlark::syntax::Dialect;
 use starlark::values::Value;
+use starlark::values::dict::DictRef;
 use starlark::values::list::ListRef;
 use starlark::values::list::UnpackList;
 use starlark::values::none::NoneType;
 use std::cell::RefCell;
 use std::cell::RefMut;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::arg_rule::ArgPattern;
+use crate::arg_rule::ArgRule;
+use crate::arg_rule::EnvCondition;
+use crate::arg_rule::PathRoot;
+use crate::arg_rule::PathRule;
+use crate::arg_rule::TokenMatcher;
+use crate::arg_rule::split_program;
 use crate::decision::Decision;
 use crate::error::Error;
 use crate::error::Result;
@@ -24,6 +33,8 @@ use crate::rule::PrefixRule;
 use crate::rule::RuleRef;
 use crate::rule::validate_match_examples;
 use crate::rule::validate_not_match_examples;
+use crate::tool_rule::FileOp;
+use crate::tool_rule::ToolRule;
 
 pub struct PolicyParser {
     builder: RefCell<PolicyBuilder>,
@@ -71,12 +82,14 @@ impl PolicyParser {
 #[derive(Debug, ProvidesStaticType)]
 struct PolicyBuilder {
     rules_by_program: MultiMap<String, RuleRef>,
+    tool_rules: Vec<ToolRule>,
 }
 
 impl PolicyBuilder {
     fn new() -> Self {
         Self {
             rules_by_program: MultiMap::new(),
+            tool_rules: Vec::new(),
         }
     }
 
@@ -85,8 +98,16 @@ impl PolicyBuilder {
             .insert(rule.program().to_string(), rule);
     }
 
+    fn add_tool_rule(&mut self, rule: ToolRule) {
+        self.tool_rules.push(rule);
+    }
+
     fn build(self) -> crate::policy::Policy {
-        crate::policy::Policy::new(self.rules_by_program)
+        let mut policy = crate::policy::Policy::new(self.rules_by_program);
+        self.tool_rules
+            .into_iter()
+            .for_each(|rule| policy.add_tool_rule(rule));
+        policy
     }
 }
 
@@ -138,6 +159,129 @@ fn parse_pattern_token<'v>(value: Value<'v>) -> Result<PatternToken> {
     }
 }
 
+fn parse_matchers<'v>(matchers: UnpackList<Value<'v>>) -> Result<Vec<TokenMatcher>> {
+    matchers.items.into_iter().map(parse_matcher).collect()
+}
+
+/// Parses a token matcher: a string, a list of alternative strings, or a single-entry dict
+/// `{"glob": "..."}` / `{"regex": "..."}`.
+fn parse_matcher<'v>(value: Value<'v>) -> Result<TokenMatcher> {
+    let Some(dict) = DictRef::from_value(value) else {
+        return match parse_pattern_token(value)? {
+            PatternToken::Single(token) => Ok(TokenMatcher::Literal(token)),
+            PatternToken::Alts(alternatives) => Ok(TokenMatcher::Alts(alternatives)),
+        };
+    };
+
+    let entries: Vec<(Value<'v>, Value<'v>)> = dict.iter().collect();
+    let [(kind, pattern)] = entries.as_slice() else {
+        return Err(Error::InvalidPattern(format!(
+            "matcher dict must have exactly one `glob` or `regex` entry (got {} entries)",
+            entries.len()
+        )));
+    };
+    let pattern = pattern.unpack_str().ok_or_else(|| {
+        Error::InvalidPattern(format!(
+            "matcher pattern must be a string (got {})",
+            pattern.get_type()
+        ))
+    })?;
+    match kind.unpack_str() {
+        Some("glob") => TokenMatcher::glob(pattern),
+        Some("regex") => TokenMatcher::regex(pattern),
+        _ => Err(Error::InvalidPattern(format!(
+            "matcher dict key must be `glob` or `regex` (got {kind})"
+        ))),
+    }
+}
+
+/// Parses `env = {"NAME": matcher_or_None}`.
+fn parse_env<'v>(env: Option<Value<'v>>) -> Result<Vec<EnvCondition>> {
+    let Some(env) = env.filter(|env| !env.is_none()) else {
+        return Ok(Vec::new());
+    };
+    let dict = DictRef::from_value(env).ok_or_else(|| {
+        Error::InvalidRule(format!(
+            "env must be a dict of variable names to matchers (got {})",
+            env.get_type()
+        ))
+    })?;
+
+    dict.iter()
+        .map(|(name, matcher)| -> Result<EnvCondition> {
+            let name = name.unpack_str().ok_or_else(|| {
+                Error::InvalidRule(format!(
+                    "env variable name must be a string (got {})",
+                    name.get_type()
+                ))
+            })?;
+            let matcher = if matcher.is_none() {
+                None
+            } else {
+                Some(parse_matcher(matcher)?)
+            };
+            Ok(EnvCondition {
+                name: name.to_string(),
+                matcher,
+            })
+        })
+        .collect()
+}
+
+fn parse_roots<'v>(within: UnpackList<Value<'v>>) -> Result<Vec<PathRoot>> {
+    let roots: Vec<PathRoot> = within
+        .items
+        .into_iter()
+        .map(|value| match value.unpack_str() {
+            Some("workspace") => Ok(PathRoot::Workspace),
+            Some(path) if !path.is_empty() => Ok(PathRoot::Path(PathBuf::from(path))),
+            _ => Err(Error::InvalidRule(format!(
+                "within entries must be \"workspace\" or a non-empty path (got {value})"
+            ))),
+        })
+        .collect::<Result<_>>()?;
+    if roots.is_empty() {
+        Err(Error::InvalidRule("within cannot be empty".to_string()))
+    } else {
+        Ok(roots)
+    }
+}
+
+fn parse_decision(decision: Option<&str>) -> Result<Decision> {
+    decision.map_or(Ok(Decision::Allow), Decision::parse)
+}
+
+fn parse_justification(justification: Option<&str>) -> Result<Option<String>> {
+    match justification {
+        Some(raw) if raw.trim().is_empty() => Err(Error::InvalidRule(
+            "justification cannot be empty".to_string(),
+        )),
+        Some(raw) => Ok(Some(raw.to_string())),
+        None => Ok(None),
+    }
+}
+
+/// Validates examples against the rules built for a pattern and registers them.
+fn add_validated_rules<'v>(
+    rules: Vec<RuleRef>,
+    r#match: Option<UnpackList<Value<'v>>>,
+    not_match: Option<UnpackList<Value<'v>>>,
+    eval: &mut Evaluator<'v, '_, '_>,
+) -> Result<()> {
+    let matches: Vec<Vec<String>> = r#match.map(parse_examples).transpose()?.unwrap_or_default();
+    let not_matches: Vec<Vec<String>> = not_match
+        .map(parse_examples)
+        .transpose()?
+        .unwrap_or_default();
+
+    validate_not_match_examples(&rules, &not_matches)?;
+    validate_match_examples(&rules, &matches)?;
+
+    let mut builder = policy_builder(eval);
+    rules.into_iter().for_each(|rule| builder.add_rule(rule));
+    Ok(())
+}
+
 fn parse_examples<'v>(examples: UnpackList<Value<'v>>) -> Result<Vec<Vec<String>>> {
     examples.items.into_iter().map(parse_example).collect()
 }
@@ -205,6 +349,8 @@ fn policy_builder<'v, 'a>(eval: &Evaluator<'v, 'a, '_>) -> RefMut<'a, PolicyBuil
         .borrow_mut()
 }
 
+// `arg_rule` and `path_rule` take every rule option as a keyword argument.
+#[allow(clippy::too_many_arguments)]
 #[starlark_module]
 fn policy_builtins(builder: &mut GlobalsBuilder) {
     fn prefix_rule<'v>(
@@ -215,30 +361,10 @@ fn policy_builtins(builder: &mut GlobalsBuilder) {
         justification: Option<&'v str>,
         eval: &mut Evaluator<'v, '_, '_>,
     ) -> anyhow::Result<NoneType> {
-        let decision = match decision {
-            Some(raw) => Decision::parse(raw)?,
-            None => Decision::Allow,
-        };
-
-        let justification = match justification {
-            Some(raw) if raw.trim().is_empty() => {
-                return Err(Error::InvalidRule("justification cannot be empty".to_string()).into());
-            }
-            Some(raw) => Some(raw.to_string()),
-            None => None,
-        };
-
+        let decision = parse_decision(decision)?;
+        let justification = parse_justification(justification)?;
         let pattern_tokens = parse_pattern(pattern)?;
 
-        let matches: Vec<Vec<String>> =
-            r#match.map(parse_examples).transpose()?.unwrap_or_default();
-        let not_matches: Vec<Vec<String>> = not_match
-            .map(parse_examples)
-            .transpose()?
-            .unwrap_or_default();
-
-        let mut builder = policy_builder(eval);
-
         let (first_token, remaining_tokens) = pattern_tokens
             .split_first()
             .ok_or_else(|| Error::InvalidPattern("pattern cannot be empty".to_string()))?;
@@ -260,10 +386,141 @@ fn policy_builtins(builder: &mut GlobalsBuilder) {
             })
             .collect();
 
-        validate_not_match_examples(&rules, &not_matches)?;
-        validate_match_examples(&rules, &matches)?;
+        add_validated_rules(rules, r#match, not_match, eval)?;
+        Ok(NoneType)
+    }
 
-        rules.into_iter().for_each(|rule| builder.add_rule(rule));
+    fn arg_rule<'v>(
+        pattern: UnpackList<Value<'v>>,
+        any_arg: Option<UnpackList<Value<'v>>>,
+        all_args: Option<UnpackList<Value<'v>>>,
+        env: Option<Value<'v>>,
+        decision: Option<&'v str>,
+        r#match: Option<UnpackList<Value<'v>>>,
+        not_match: Option<UnpackList<Value<'v>>>,
+        justification: Option<&'v str>,
+        eval: &mut Evaluator<'v, '_, '_>,
+    ) -> anyhow::Result<NoneType> {
+        let decision = parse_decision(decision)?;
+        let justification = parse_justification(justification)?;
+        let (programs, rest) = split_program(parse_matchers(pattern)?)?;
+        let any_arg = any_arg.map(parse_matchers).transpose()?.unwrap_or_default();
+        let all_args = all_args
+            .map(parse_matchers)
+            .transpose()?
+            .unwrap_or_default();
+        let env = parse_env(env)?;
+
+        let rules: Vec<RuleRef> = programs
+            .iter()
+            .map(|program| {
+                Arc::new(ArgRule {
+                    pattern: ArgPattern {
+                        first: Arc::from(program.as_str()),
+                        rest: rest.clone(),
+                    },
+                    any_arg: any_arg.clone(),
+                    all_args: all_args.clone(),
+                    env: env.clone(),
+                    decision,
+                    justification: justification.clone(),
+                }) as RuleRef
+            })
+            .collect();
+
+        add_validated_rules(rules, r#match, not_match, eval)?;
+        Ok(NoneType)
+    }
+
+    fn path_rule<'v>(
+        pattern: UnpackList<Value<'v>>,
+        within: UnpackList<Value<'v>>,
+        outside: Option<bool>,
+        env: Option<Value<'v>>,
+        decision: Option<&'v str>,
+        r#match: Option<UnpackList<Value<'v>>>,
+        not_match: Option<UnpackList<Value<'v>>>,
+        justification: Option<&'v str>,
+        eval: &mut Evaluator<'v, '_, '_>,
+    ) -> anyhow::Result<NoneType> {
+        let decision = parse_decision(decision)?;
+        let justification = parse_justification(justification)?;
+        let (programs, rest) = split_program(parse_matchers(pattern)?)?;
+        let within = parse_roots(within)?;
+        let env = parse_env(env)?;
+
+        let rules: Vec<RuleRef> = programs
+            .iter()
+            .map(|program| {
+                Arc::new(PathRule {
+                    pattern: ArgPattern {
+                        first: Arc::from(program.as_str()),
+                        rest: rest.clone(),
+                    },
+                    within: within.clone(),
+                    outside: outside.unwrap_or(false),
+                    env: env.clone(),
+                    decision,
+                    justification: justification.clone(),
+                }) as RuleRef
+            })
+            .collect();
+
+        add_validated_rules(rules, r#match, not_match, eval)?;
+        Ok(NoneType)
+    }
+
+    fn mcp_tool_rule<'v>(
+        server: &'v str,
+        tool: &'v str,
+        decision: Option<&'v str>,
+        justification: Option<&'v str>,
+        eval: &mut Evaluator<'v, '_, '_>,
+    ) -> anyhow::Result<NoneType> {
+        let rule = ToolRule::mcp_tool(
+            server,
+            tool,
+            parse_decision(decision)?,
+            parse_justification(justification)?,
+        )?;
+        policy_builder(eval).add_tool_rule(rule);
+        Ok(NoneType)
+    }
+
+    fn url_rule<'v>(
+        domain_glob: &'v str,
+        decision: Option<&'v str>,
+        justification: Option<&'v str>,
+        eval: &mut Evaluator<'v, '_, '_>,
+    ) -> anyhow::Result<NoneType> {
+        let rule = ToolRule::url(
+            domain_glob,
+            parse_decision(decision)?,
+            parse_justification(justification)?,
+        )?;
+        policy_builder(eval).add_tool_rule(rule);
+        Ok(NoneType)
+    }
+
+    fn file_rule<'v>(
+        path_glob: &'v str,
+        ops: UnpackList<&'v str>,
+        decision: Option<&'v str>,
+        justification: Option<&'v str>,
+        eval: &mut Evaluator<'v, '_, '_>,
+    ) -> anyhow::Result<NoneType> {
+        let ops = ops
+            .items
+            .into_iter()
+            .map(FileOp::parse)
+            .collect::<Result<Vec<_>>>()?;
+        let rule = ToolRule::file(
+            path_glob,
+            ops,
+            parse_decision(decision)?,
+            parse_justification(justification)?,
+        )?;
+        policy_builder(eval).add_tool_rule(rule);
         Ok(NoneType)
     }
 }
@@ -1,14 +1,18 @@
 use crate::decision::Decision;
 use crate::error::Error;
 use crate::error::Result;
+use crate::rule::MatchContext;
 use crate::rule::PatternToken;
 use crate::rule::PrefixPattern;
 use crate::rule::PrefixRule;
 use crate::rule::RuleMatch;
 use crate::rule::RuleRef;
+use crate::tool_rule::FileOp;
+use crate::tool_rule::ToolRule;
 use multimap::MultiMap;
 use serde::Deserialize;
 use serde::Serialize;
+use std::path::Path;
 use std::sync::Arc;
 
 type HeuristicsFallback<'a> = Option<&'a dyn Fn(&[String]) -> Decision>;
@@ -16,11 +20,15 @@ type HeuristicsFallback<'a> = Option<&'a dyn Fn(&[String]) -> Decision>;
 #[derive(Clone, Debug)]
 pub struct Policy {
     rules_by_program: MultiMap<String, RuleRef>,
+    tool_rules: Vec<ToolRule>,
 }
 
 impl Policy {
     pub fn new(rules_by_program: MultiMap<String, RuleRef>) -> Self {
-        Self { rules_by_program }
+        Self {
+            rules_by_program,
+            tool_rules: Vec::new(),
+        }
     }
 
     pub fn empty() -> Self {
@@ -31,6 +39,25 @@ impl Policy {
         &self.rules_by_program
     }
 
+    /// Rules for MCP tools, URLs and files (`mcp_tool_rule`, `url_rule`, `file_rule`).
+    pub fn tool_rules(&self) -> &[ToolRule] {
+        &self.tool_rules
+    }
+
+    pub fn add_tool_rule(&mut self, rule: ToolRule) {
+        self.tool_rules.push(rule);
+    }
+
+    /// Appends every rule of `other` to this policy.
+    pub fn extend(&mut self, other: &Policy) {
+        for (program, rules) in other.rules_by_program.iter_all() {
+            for rule in rules {
+                self.rules_by_program.insert(program.clone(), rule.clone());
+            }
+        }
+        self.tool_rules.extend(other.tool_rules.iter().cloned());
+    }
+
     pub fn get_allowed_prefixes(&self) -> Vec<Vec<String>> {
         let mut prefixes = Vec::new();
 
@@ -81,7 +108,21 @@ impl Policy {
     where
         F: Fn(&[String]) -> Decision,
     {
-        let matched_rules = self.matches_for_command(cmd, Some(heuristics_fallback));
+        self.check_with_context(cmd, &MatchContext::default(), heuristics_fallback)
+    }
+
+    /// Like [`Policy::check`], evaluating `path_rule` scoping and `env` conditions against `ctx`.
+    pub fn check_with_context<F>(
+        &self,
+        cmd: &[String],
+        ctx: &MatchContext,
+        heuristics_fallback: &F,
+    ) -> Evaluation
+    where
+        F: Fn(&[String]) -> Decision,
+    {
+        let matched_rules =
+            self.matches_for_command_with_context(cmd, ctx, Some(heuristics_fallback));
         Evaluation::from_matches(matched_rules)
     }
 
@@ -91,6 +132,21 @@ impl Policy {
         commands: Commands,
         heuristics_fallback: &F,
     ) -> Evaluation
+    where
+        Commands: IntoIterator,
+        Commands::Item: AsRef<[String]>,
+        F: Fn(&[String]) -> Decision,
+    {
+        self.check_multiple_with_context(commands, &MatchContext::default(), heuristics_fallback)
+    }
+
+    /// Checks multiple commands that share the same `ctx` and aggregates the results.
+    pub fn check_multiple_with_context<Commands, F>(
+        &self,
+        commands: Commands,
+        ctx: &MatchContext,
+        heuristics_fallback: &F,
+    ) -> Evaluation
     where
         Commands: IntoIterator,
         Commands::Item: AsRef<[String]>,
@@ -99,7 +155,11 @@ impl Policy {
         let matched_rules: Vec<RuleMatch> = commands
             .into_iter()
             .flat_map(|command| {
-                self.matches_for_command(command.as_ref(), Some(heuristics_fallback))
+                self.matches_for_command_with_context(
+                    command.as_ref(),
+                    ctx,
+                    Some(heuristics_fallback),
+                )
             })
             .collect();
 
@@ -117,12 +177,27 @@ impl Policy {
         &self,
         cmd: &[String],
         heuristics_fallback: HeuristicsFallback<'_>,
+    ) -> Vec<RuleMatch> {
+        self.matches_for_command_with_context(cmd, &MatchContext::default(), heuristics_fallback)
+    }
+
+    /// Like [`Policy::matches_for_command`], evaluating rules against `ctx`.
+    pub fn matches_for_command_with_context(
+        &self,
+        cmd: &[String],
+        ctx: &MatchContext,
+        heuristics_fallback: HeuristicsFallback<'_>,
     ) -> Vec<RuleMatch> {
         let matched_rules: Vec<RuleMatch> = match cmd.first() {
             Some(first) => self
                 .rules_by_program
                 .get_vec(first)
-                .map(|rules| rules.iter().filter_map(|rule| rule.matches(cmd)).collect())
+                .map(|rules| {
+                    rules
+                        .iter()
+                        .filter_map(|rule| rule.matches_with_context(cmd, ctx))
+                        .collect()
+                })
                 .unwrap_or_default(),
             None => Vec::new(),
         };
@@ -138,6 +213,71 @@ impl Policy {
             matched_rules
         }
     }
+
+    /// Evaluates a call to MCP tool `tool` on `server`. Returns `None` when no `mcp_tool_rule`
+    /// matches, leaving the decision to the caller.
+    pub fn check_mcp_tool(&self, server: &str, tool: &str) -> Option<Evaluation> {
+        self.check_tool_rules(|rule| rule.matches_mcp_tool(server, tool))
+    }
+
+    /// Evaluates a fetch from `host`. Returns `None` when no `url_rule` matches.
+    pub fn check_url_host(&self, host: &str) -> Option<Evaluation> {
+        self.check_tool_rules(|rule| rule.matches_url_host(host))
+    }
+
+    /// Evaluates file changes made relative to `cwd`, aggregating the strictest decision across
+    /// all of them. Returns `None` when no `file_rule` matches any change.
+    pub fn check_file_changes<'a, Changes>(
+        &self,
+        changes: Changes,
+        cwd: &Path,
+    ) -> Option<Evaluation>
+    where
+        Changes: IntoIterator<Item = (&'a Path, FileOp)>,
+    {
+        let evaluations: Vec<Evaluation> = changes
+            .into_iter()
+            .filter_map(|(path, op)| self.check_tool_rules(|rule| rule.matches_file(path, op, cwd)))
+            .collect();
+        let decision = evaluations
+            .iter()
+            .map(|evaluation| evaluation.decision)
+            .max()?;
+        Some(Evaluation {
+            decision,
+            matched_rules: evaluations
+                .into_iter()
+                .flat_map(|evaluation| evaluation.matched_rules)
+                .collect(),
+        })
+    }
+
+    /// Evaluates the tool rules matching one target. The strictest decision wins, except that
+    /// an exact `allow` rule (one without globs, as saved from an approval) settles `prompt`
+    /// rules for its target; `forbidden` rules still apply.
+    fn check_tool_rules(
+        &self,
+        matches: impl Fn(&ToolRule) -> Option<RuleMatch>,
+    ) -> Option<Evaluation> {
+        let mut exact_allow = false;
+        let matched_rules: Vec<RuleMatch> = self
+            .tool_rules
+            .iter()
+            .filter_map(|rule| {
+                let rule_match = matches(rule)?;
+                exact_allow |= rule.decision() == Decision::Allow && rule.is_exact();
+                Some(rule_match)
+            })
+            .collect();
+        if matched_rules.is_empty() {
+            return None;
+        }
+        let mut evaluation = Evaluation::from_matches(matched_rules);
+        if exact_allow && evaluation.decision == Decision::Prompt {
+            evaluation.decision = Decision::Allow;
+        }
+        Some(evaluation)
+    }
 }
 
 fn render_pattern_token(token: &PatternToken) -> String {
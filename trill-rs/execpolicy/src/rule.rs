@@ -5,7 +5,9 @@ use serde::Deserialize;
 use serde::Serialize;
 use shlex::try_join;
 use std::any::Any;
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 /// Matches a single command token, either a fixed string or one of several allowed alternatives.
@@ -70,6 +72,27 @@ pub enum RuleMatch {
         #[serde(skip_serializing_if = "Option::is_none")]
         justification: Option<String>,
     },
+    ArgRuleMatch {
+        #[serde(rename = "matchedPrefix")]
+        matched_prefix: Vec<String>,
+        decision: Decision,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        justification: Option<String>,
+        /// The rule that fired, rendered in policy syntax.
+        rule: String,
+        /// Which arguments, paths or env conditions made the rule fire.
+        explanation: String,
+    },
+    /// Match from `mcp_tool_rule`, `url_rule` or `file_rule`.
+    ToolRuleMatch {
+        /// The MCP tool, URL host or file the rule matched.
+        target: String,
+        decision: Decision,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        justification: Option<String>,
+        /// The rule that fired, rendered in policy syntax.
+        rule: String,
+    },
     HeuristicsRuleMatch {
         command: Vec<String>,
         decision: Decision,
@@ -80,9 +103,64 @@ impl RuleMatch {
     pub fn decision(&self) -> Decision {
         match self {
             Self::PrefixRuleMatch { decision, .. } => *decision,
+            Self::ArgRuleMatch { decision, .. } => *decision,
+            Self::ToolRuleMatch { decision, .. } => *decision,
             Self::HeuristicsRuleMatch { decision, .. } => *decision,
         }
     }
+
+    /// Returns the rule's justification, if the match came from a policy rule that has one.
+    pub fn justification(&self) -> Option<&str> {
+        match self {
+            Self::PrefixRuleMatch { justification, .. }
+            | Self::ArgRuleMatch { justification, .. }
+            | Self::ToolRuleMatch { justification, .. } => justification.as_deref(),
+            Self::HeuristicsRuleMatch { .. } => None,
+        }
+    }
+
+    /// Returns the command prefix matched by a command rule; `None` for tool rule and
+    /// heuristics matches.
+    pub fn matched_prefix(&self) -> Option<&[String]> {
+        match self {
+            Self::PrefixRuleMatch { matched_prefix, .. }
+            | Self::ArgRuleMatch { matched_prefix, .. } => Some(matched_prefix.as_slice()),
+            Self::ToolRuleMatch { .. } | Self::HeuristicsRuleMatch { .. } => None,
+        }
+    }
+}
+
+/// Facts about where and how a command runs, consulted by rules that look past the command
+/// tokens (`path_rule` scoping and `env` conditions).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MatchContext {
+    /// Directory relative path arguments are resolved against. Without it, relative paths are
+    /// never considered to be inside any root.
+    pub cwd: Option<PathBuf>,
+    /// Writable roots that count as part of the `"workspace"` alongside `cwd`.
+    pub writable_roots: Vec<PathBuf>,
+    /// Environment the command runs with. `None` means the environment is unknown, and `env`
+    /// conditions then fail unless `assume_env` is set.
+    pub env: Option<HashMap<String, String>>,
+    /// Set when the command is one of several in a script or follows a `cd`, so `cwd` may not be
+    /// the directory it runs in. Relative path arguments are then never inside any root.
+    pub cwd_may_change: bool,
+    /// Treat `env` conditions as satisfied when `env` is `None`.
+    pub assume_env: bool,
+}
+
+impl MatchContext {
+    /// Context used to validate `match` / `not_match` examples at load time: relative paths
+    /// resolve against `/workspace` and env conditions are assumed to hold.
+    pub(crate) fn for_examples() -> Self {
+        Self {
+            cwd: Some(PathBuf::from("/workspace")),
+            writable_roots: Vec::new(),
+            env: None,
+            cwd_may_change: false,
+            assume_env: true,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -97,6 +175,12 @@ pub trait Rule: Any + Debug + Send + Sync {
 
     fn matches(&self, cmd: &[String]) -> Option<RuleMatch>;
 
+    /// Like [`Rule::matches`], for rules whose outcome depends on the command's cwd, writable
+    /// roots or environment.
+    fn matches_with_context(&self, cmd: &[String], _ctx: &MatchContext) -> Option<RuleMatch> {
+        self.matches(cmd)
+    }
+
     fn as_any(&self) -> &dyn Any;
 }
 
@@ -124,10 +208,14 @@ impl Rule for PrefixRule {
 
 /// Count how many rules match each provided example and error if any example is unmatched.
 pub(crate) fn validate_match_examples(rules: &[RuleRef], matches: &[Vec<String>]) -> Result<()> {
+    let ctx = MatchContext::for_examples();
     let mut unmatched_examples = Vec::new();
 
     for example in matches {
-        if rules.iter().any(|rule| rule.matches(example).is_some()) {
+        if rules
+            .iter()
+            .any(|rule| rule.matches_with_context(example, &ctx).is_some())
+        {
             continue;
         }
 
@@ -152,8 +240,12 @@ pub(crate) fn validate_not_match_examples(
     rules: &[RuleRef],
     not_matches: &[Vec<String>],
 ) -> Result<()> {
+    let ctx = MatchContext::for_examples();
     for example in not_matches {
-        if let Some(rule) = rules.iter().find(|rule| rule.matches(example).is_some()) {
+        if let Some(rule) = rules
+            .iter()
+            .find(|rule| rule.matches_with_context(example, &ctx).is_some())
+        {
             return Err(Error::ExampleDidMatch {
                 rule: format!("{rule:?}"),
                 example: try_join(example.iter().map(String::as_str))
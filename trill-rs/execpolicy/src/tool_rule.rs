@@ -0,0 +1,252 @@
+use crate::decision::Decision;
+use crate::error::Error;
+use crate::error::Result;
+use crate::rule::RuleMatch;
+use globset::GlobBuilder;
+use globset::GlobMatcher;
+use std::fmt;
+use std::path::Path;
+
+/// A file operation governed by `file_rule`. Moves count as an `update` of the source and an
+/// `add` of the destination.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FileOp {
+    Add,
+    Update,
+    Delete,
+}
+
+impl FileOp {
+    pub fn parse(raw: &str) -> Result<Self> {
+        match raw {
+            "add" => Ok(Self::Add),
+            "update" => Ok(Self::Update),
+            "delete" => Ok(Self::Delete),
+            other => Err(Error::InvalidRule(format!(
+                "unknown file op `{other}` (expected add, update or delete)"
+            ))),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Update => "update",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+/// Rules for tool calls that are not shell commands: MCP tools, URLs fetched by web search and
+/// files touched by `apply_patch`.
+#[derive(Clone, Debug)]
+pub enum ToolRule {
+    /// `mcp_tool_rule(server, tool, decision)`: `server` and `tool` are globs.
+    McpTool {
+        server: GlobMatcher,
+        tool: GlobMatcher,
+        decision: Decision,
+        justification: Option<String>,
+    },
+    /// `url_rule(domain_glob, decision)`: matched case-insensitively against the URL host.
+    Url {
+        domain: GlobMatcher,
+        decision: Decision,
+        justification: Option<String>,
+    },
+    /// `file_rule(path_glob, ops, decision)`: `*` does not cross `/`, `**` does. Relative globs
+    /// match paths relative to the session cwd; absolute globs match absolute paths.
+    File {
+        path: GlobMatcher,
+        ops: Vec<FileOp>,
+        decision: Decision,
+        justification: Option<String>,
+    },
+}
+
+impl ToolRule {
+    pub fn mcp_tool(
+        server: &str,
+        tool: &str,
+        decision: Decision,
+        justification: Option<String>,
+    ) -> Result<Self> {
+        Ok(Self::McpTool {
+            server: compile_glob(server, false, false)?,
+            tool: compile_glob(tool, false, false)?,
+            decision,
+            justification,
+        })
+    }
+
+    pub fn url(domain: &str, decision: Decision, justification: Option<String>) -> Result<Self> {
+        Ok(Self::Url {
+            domain: compile_glob(domain, false, true)?,
+            decision,
+            justification,
+        })
+    }
+
+    pub fn file(
+        path: &str,
+        ops: Vec<FileOp>,
+        decision: Decision,
+        justification: Option<String>,
+    ) -> Result<Self> {
+        if ops.is_empty() {
+            return Err(Error::InvalidRule(
+                "file_rule ops cannot be empty".to_string(),
+            ));
+        }
+        Ok(Self::File {
+            path: compile_glob(path, true, false)?,
+            ops,
+            decision,
+            justification,
+        })
+    }
+
+    /// Whether the rule names exactly one target, with no glob characters in its patterns.
+    pub fn is_exact(&self) -> bool {
+        match self {
+            Self::McpTool { server, tool, .. } => {
+                is_literal_glob(server.glob().glob()) && is_literal_glob(tool.glob().glob())
+            }
+            Self::Url { domain, .. } => is_literal_glob(domain.glob().glob()),
+            Self::File { path, .. } => is_literal_glob(path.glob().glob()),
+        }
+    }
+
+    pub fn decision(&self) -> Decision {
+        match self {
+            Self::McpTool { decision, .. }
+            | Self::Url { decision, .. }
+            | Self::File { decision, .. } => *decision,
+        }
+    }
+
+    fn justification(&self) -> Option<String> {
+        match self {
+            Self::McpTool { justification, .. }
+            | Self::Url { justification, .. }
+            | Self::File { justification, .. } => justification.clone(),
+        }
+    }
+
+    pub(crate) fn matches_mcp_tool(&self, server: &str, tool: &str) -> Option<RuleMatch> {
+        match self {
+            Self::McpTool {
+                server: server_glob,
+                tool: tool_glob,
+                ..
+            } if server_glob.is_match(server) && tool_glob.is_match(tool) => {
+                Some(self.rule_match(format!("mcp tool `{server}/{tool}`")))
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn matches_url_host(&self, host: &str) -> Option<RuleMatch> {
+        match self {
+            Self::Url { domain, .. } if domain.is_match(host) => {
+                Some(self.rule_match(format!("url host `{host}`")))
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn matches_file(&self, path: &Path, op: FileOp, cwd: &Path) -> Option<RuleMatch> {
+        let Self::File {
+            path: path_glob,
+            ops,
+            ..
+        } = self
+        else {
+            return None;
+        };
+        if !ops.contains(&op) {
+            return None;
+        }
+        let relative = path.strip_prefix(cwd).ok();
+        (path_glob.is_match(path) || relative.is_some_and(|relative| path_glob.is_match(relative)))
+            .then(|| {
+                self.rule_match(format!(
+                    "file {} `{}`",
+                    op.as_str(),
+                    relative.unwrap_or(path).display()
+                ))
+            })
+    }
+
+    fn rule_match(&self, target: String) -> RuleMatch {
+        RuleMatch::ToolRuleMatch {
+            target,
+            decision: self.decision(),
+            justification: self.justification(),
+            rule: self.to_string(),
+        }
+    }
+}
+
+/// Renders the rule in policy syntax.
+impl fmt::Display for ToolRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::McpTool {
+                server,
+                tool,
+                decision,
+                ..
+            } => write!(
+                f,
+                "mcp_tool_rule(server={:?}, tool={:?}, decision={:?})",
+                server.glob().glob(),
+                tool.glob().glob(),
+                decision.as_str()
+            ),
+            Self::Url {
+                domain, decision, ..
+            } => write!(
+                f,
+                "url_rule(domain_glob={:?}, decision={:?})",
+                domain.glob().glob(),
+                decision.as_str()
+            ),
+            Self::File {
+                path,
+                ops,
+                decision,
+                ..
+            } => {
+                let ops: Vec<&str> = ops.iter().copied().map(FileOp::as_str).collect();
+                write!(
+                    f,
+                    "file_rule(path_glob={:?}, ops={ops:?}, decision={:?})",
+                    path.glob().glob(),
+                    decision.as_str()
+                )
+            }
+        }
+    }
+}
+
+/// Whether `pattern` matches only itself.
+pub(crate) fn is_literal_glob(pattern: &str) -> bool {
+    !pattern.contains(['*', '?', '[', ']', '{', '}', '\\'])
+}
+
+fn compile_glob(
+    pattern: &str,
+    literal_separator: bool,
+    case_insensitive: bool,
+) -> Result<GlobMatcher> {
+    if pattern.is_empty() {
+        return Err(Error::InvalidPattern("glob cannot be empty".to_string()));
+    }
+    GlobBuilder::new(pattern)
+        .literal_separator(literal_separator)
+        .case_insensitive(case_insensitive)
+        .build()
+        .map(|glob| glob.compile_matcher())
+        .map_err(|err| Error::InvalidPattern(format!("invalid glob `{pattern}`: {err}")))
+}
@@ -1,4 +1,7 @@
 use std::any::Any;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use anyhow::Context;
@@ -6,6 +9,8 @@ use anyhow::Result;
 use trill_execpolicy::Decision;
 use trill_execpolicy::Error;
 use trill_execpolicy::Evaluation;
+use trill_execpolicy::FileOp;
+use trill_execpolicy::MatchContext;
 use trill_execpolicy::Policy;
 use trill_execpolicy::PolicyParser;
 use trill_execpolicy::RuleMatch;
@@ -551,3 +556,413 @@ fn heuristics_match_is_returned_when_no_policy_matches() {
         evaluation
     );
 }
+
+#[test]
+fn arg_rule_matches_flags_anywhere_with_globs_and_regexes() -> Result<()> {
+    let policy_src = r#"
+arg_rule(
+    pattern = ["git", "push"],
+    any_arg = ["--force", {"glob": "--force-*"}, {"regex": "-[a-z]*f[a-z]*"}],
+    decision = "forbidden",
+    justification = "force pushes rewrite shared history",
+    match = ["git push origin main --force", "git push -uf origin main"],
+    not_match = ["git push origin main", "git push origin feature-force"],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    let policy = parser.build();
+
+    let evaluation = policy.check(
+        &tokens(&["git", "push", "origin", "main", "--force-with-lease"]),
+        &allow_all,
+    );
+    assert_eq!(
+        Evaluation {
+            decision: Decision::Forbidden,
+            matched_rules: vec![RuleMatch::ArgRuleMatch {
+                matched_prefix: tokens(&["git", "push"]),
+                decision: Decision::Forbidden,
+                justification: Some("force pushes rewrite shared history".to_string()),
+                rule: r#"arg_rule(pattern=["git", "push"], any_arg=["--force", {"glob": "--force-*"}, {"regex": "-[a-z]*f[a-z]*"}], decision="forbidden")"#.to_string(),
+                explanation: r#"prefix `git push` matched; argument `--force-with-lease` matches {"glob": "--force-*"}"#.to_string(),
+            }],
+        },
+        evaluation
+    );
+    Ok(())
+}
+
+#[test]
+fn arg_rule_checks_positional_args_and_env() -> Result<()> {
+    let policy_src = r#"
+arg_rule(
+    pattern = ["curl"],
+    all_args = [{"glob": "http://localhost*"}],
+    env = {"CI": "true", "HTTPS_PROXY": None},
+    match = ["curl -s http://localhost:8080/health"],
+    not_match = ["curl -s http://localhost:8080 https://example.com"],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    let policy = parser.build();
+    let command = tokens(&["curl", "-s", "http://localhost:8080/health"]);
+
+    let ci_env = |pairs: &[(&str, &str)]| MatchContext {
+        env: Some(
+            pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<HashMap<_, _>>(),
+        ),
+        ..MatchContext::default()
+    };
+
+    let evaluation = policy.check_with_context(&command, &ci_env(&[("CI", "true")]), &prompt_all);
+    assert_eq!(
+        Evaluation {
+            decision: Decision::Allow,
+            matched_rules: vec![RuleMatch::ArgRuleMatch {
+                matched_prefix: tokens(&["curl"]),
+                decision: Decision::Allow,
+                justification: None,
+                rule: r#"arg_rule(pattern=["curl"], all_args=[{"glob": "http://localhost*"}], env={"CI": "true", "HTTPS_PROXY": None}, decision="allow")"#.to_string(),
+                explanation: r#"prefix `curl` matched; env `CI=true` matches "true"; env `HTTPS_PROXY` is unset; all 1 positional argument(s) match [{"glob": "http://localhost*"}]"#.to_string(),
+            }],
+        },
+        evaluation
+    );
+
+    for env in [
+        ci_env(&[]),
+        ci_env(&[("CI", "false")]),
+        ci_env(&[("CI", "true"), ("HTTPS_PROXY", "http://proxy")]),
+    ] {
+        assert_eq!(
+            Evaluation {
+                decision: Decision::Prompt,
+                matched_rules: vec![RuleMatch::HeuristicsRuleMatch {
+                    command: command.clone(),
+                    decision: Decision::Prompt,
+                }],
+            },
+            policy.check_with_context(&command, &env, &prompt_all)
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn path_rule_scopes_paths_to_workspace_and_roots() -> Result<()> {
+    let policy_src = r#"
+path_rule(
+    pattern = ["rm"],
+    within = ["workspace", "/tmp"],
+    outside = True,
+    decision = "forbidden",
+    not_match = ["rm -rf build", "rm /tmp/scratch", "rm -- -weird-name"],
+    match = ["rm ../escape", "rm /etc/passwd", "rm ~/notes"],
+)
+path_rule(
+    pattern = ["touch"],
+    within = ["workspace"],
+    match = ["touch a b/c"],
+    not_match = ["touch", "touch a /etc/hosts"],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    let policy = parser.build();
+    let ctx = MatchContext {
+        cwd: Some(PathBuf::from("/repo")),
+        writable_roots: vec![PathBuf::from("/cache")],
+        ..MatchContext::default()
+    };
+
+    let evaluation = policy.check_with_context(
+        &tokens(&["rm", "-rf", "/cache/a", "src/../../secrets"]),
+        &ctx,
+        &allow_all,
+    );
+    assert_eq!(
+        Evaluation {
+            decision: Decision::Forbidden,
+            matched_rules: vec![RuleMatch::ArgRuleMatch {
+                matched_prefix: tokens(&["rm"]),
+                decision: Decision::Forbidden,
+                justification: None,
+                rule: r#"path_rule(pattern=["rm"], within=["workspace", "/tmp"], outside=True, decision="forbidden")"#.to_string(),
+                explanation: r#"prefix `rm` matched; path `src/../../secrets` is outside ["workspace", "/tmp"]"#.to_string(),
+            }],
+        },
+        evaluation
+    );
+
+    let evaluation = policy.check_with_context(
+        &tokens(&["touch", "src/lib.rs", "/cache/stamp"]),
+        &ctx,
+        &prompt_all,
+    );
+    assert_eq!(Decision::Allow, evaluation.decision);
+    Ok(())
+}
+
+#[test]
+fn path_rule_does_not_trust_relative_paths_or_symlinks() -> Result<()> {
+    let policy_src = r#"
+path_rule(
+    pattern = ["rm"],
+    within = ["workspace"],
+    match = ["rm -rf build"],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    let policy = parser.build();
+    let workspace = tempfile::tempdir()?;
+    let outside = tempfile::tempdir()?;
+    let repo = workspace.path().canonicalize()?;
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(outside.path(), repo.join("escape"))?;
+    let ctx = MatchContext {
+        cwd: Some(repo.clone()),
+        env: Some(HashMap::new()),
+        ..MatchContext::default()
+    };
+    let check = |cmd: &[&str], ctx: &MatchContext| {
+        policy
+            .check_with_context(&tokens(cmd), ctx, &prompt_all)
+            .decision
+    };
+
+    assert_eq!(Decision::Allow, check(&["rm", "-rf", "build"], &ctx));
+    // `cd / && rm -rf etc`: the script may have left the workspace.
+    let after_cd = MatchContext {
+        cwd_may_change: true,
+        ..ctx.clone()
+    };
+    assert_eq!(Decision::Prompt, check(&["rm", "-rf", "etc"], &after_cd));
+    let absolute = repo.join("build").to_string_lossy().to_string();
+    assert_eq!(Decision::Allow, check(&["rm", "-rf", &absolute], &after_cd));
+    #[cfg(unix)]
+    assert_eq!(Decision::Prompt, check(&["rm", "-rf", "escape/data"], &ctx));
+    assert_eq!(
+        Decision::Prompt,
+        check(&["rm", "-rf", "build", "--files-from=/etc/passwd"], &ctx)
+    );
+    assert_eq!(
+        Decision::Allow,
+        check(&["rm", "-rf", "build", "--interactive=never"], &ctx)
+    );
+    Ok(())
+}
+
+#[test]
+fn env_conditions_fail_when_environment_is_unknown() -> Result<()> {
+    let policy_src = r#"
+arg_rule(
+    pattern = ["deploy"],
+    env = {"CI": "true"},
+    match = ["deploy"],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    let policy = parser.build();
+
+    let evaluation =
+        policy.check_with_context(&tokens(&["deploy"]), &MatchContext::default(), &prompt_all);
+    assert_eq!(Decision::Prompt, evaluation.decision);
+    Ok(())
+}
+
+#[test]
+fn arg_rule_rejects_invalid_matchers() {
+    for (policy_src, expected) in [
+        (
+            r#"arg_rule(pattern = ["ls"], any_arg = [{"regex": "("}])"#,
+            "invalid pattern element: invalid regex `(`",
+        ),
+        (
+            r#"arg_rule(pattern = [{"glob": "l*"}])"#,
+            "the first pattern element must be a string or list of strings",
+        ),
+        (
+            r#"arg_rule(pattern = ["ls"], any_arg = [{"wildcard": "*"}])"#,
+            "matcher dict key must be `glob` or `regex`",
+        ),
+        (
+            r#"path_rule(pattern = ["rm"], within = [])"#,
+            "invalid rule: within cannot be empty",
+        ),
+    ] {
+        let mut parser = PolicyParser::new();
+        let err = parser
+            .parse("test.rules", policy_src)
+            .expect_err("expected parse error");
+        assert!(
+            err.to_string().contains(expected),
+            "unexpected error for {policy_src}: {err}"
+        );
+    }
+}
+
+#[test]
+fn tool_rules_govern_mcp_tools_urls_and_files() -> Result<()> {
+    let policy_src = r#"
+mcp_tool_rule(server = "github", tool = "delete_*", decision = "forbidden", justification = "no deletes")
+mcp_tool_rule(server = "*", tool = "search")
+url_rule(domain_glob = "*.internal.example.com", decision = "prompt")
+file_rule(path_glob = "**/*.lock", ops = ["update", "delete"], decision = "prompt")
+file_rule(path_glob = "/etc/**", ops = ["add", "update", "delete"], decision = "forbidden")
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    let policy = parser.build();
+
+    assert_eq!(
+        Some(Evaluation {
+            decision: Decision::Forbidden,
+            matched_rules: vec![RuleMatch::ToolRuleMatch {
+                target: "mcp tool `github/delete_repo`".to_string(),
+                decision: Decision::Forbidden,
+                justification: Some("no deletes".to_string()),
+                rule: r#"mcp_tool_rule(server="github", tool="delete_*", decision="forbidden")"#
+                    .to_string(),
+            }],
+        }),
+        policy.check_mcp_tool("github", "delete_repo")
+    );
+    assert_eq!(
+        Some(Decision::Allow),
+        policy
+            .check_mcp_tool("docs", "search")
+            .map(|evaluation| evaluation.decision)
+    );
+    assert_eq!(None, policy.check_mcp_tool("github", "create_issue"));
+
+    assert_eq!(
+        Some(Decision::Prompt),
+        policy
+            .check_url_host("Wiki.Internal.Example.com")
+            .map(|evaluation| evaluation.decision)
+    );
+    assert_eq!(None, policy.check_url_host("example.com"));
+
+    let cwd = Path::new("/workspace");
+    let lockfile = cwd.join("app/Cargo.lock");
+    assert_eq!(
+        Some(Evaluation {
+            decision: Decision::Prompt,
+            matched_rules: vec![RuleMatch::ToolRuleMatch {
+                target: "file update `app/Cargo.lock`".to_string(),
+                decision: Decision::Prompt,
+                justification: None,
+                rule: r#"file_rule(path_glob="**/*.lock", ops=["update", "delete"], decision="prompt")"#
+                    .to_string(),
+            }],
+        }),
+        policy.check_file_changes(
+            [
+                (cwd.join("src/lib.rs").as_path(), FileOp::Update),
+                (lockfile.as_path(), FileOp::Update),
+            ],
+            cwd,
+        )
+    );
+    assert_eq!(
+        None,
+        policy.check_file_changes([(lockfile.as_path(), FileOp::Add)], cwd)
+    );
+    assert_eq!(
+        Some(Decision::Forbidden),
+        policy
+            .check_file_changes(
+                [
+                    (lockfile.as_path(), FileOp::Delete),
+                    (Path::new("/etc/hosts"), FileOp::Update),
+                ],
+                cwd,
+            )
+            .map(|evaluation| evaluation.decision)
+    );
+    Ok(())
+}
+
+#[test]
+fn exact_allow_rules_settle_prompt_tool_rules() -> Result<()> {
+    let policy_src = r#"
+url_rule(domain_glob = "*.example.com", decision = "prompt")
+url_rule(domain_glob = "docs.example.com", decision = "allow")
+url_rule(domain_glob = "*.internal.example.com", decision = "forbidden")
+url_rule(domain_glob = "wiki.internal.example.com", decision = "allow")
+file_rule(path_glob = "**/*.lock", ops = ["update"], decision = "prompt")
+file_rule(path_glob = "/workspace/Cargo.lock", ops = ["update"], decision = "allow")
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    let policy = parser.build();
+    let decision =
+        |evaluation: Option<Evaluation>| evaluation.map(|evaluation| evaluation.decision);
+
+    assert_eq!(
+        Some(Decision::Allow),
+        decision(policy.check_url_host("docs.example.com"))
+    );
+    assert_eq!(
+        Some(Decision::Prompt),
+        decision(policy.check_url_host("api.example.com"))
+    );
+    assert_eq!(
+        Some(Decision::Forbidden),
+        decision(policy.check_url_host("wiki.internal.example.com"))
+    );
+
+    let cwd = Path::new("/workspace");
+    let root_lock = cwd.join("Cargo.lock");
+    let app_lock = cwd.join("app/Cargo.lock");
+    assert_eq!(
+        Some(Decision::Allow),
+        decision(policy.check_file_changes([(root_lock.as_path(), FileOp::Update)], cwd))
+    );
+    assert_eq!(
+        Some(Decision::Prompt),
+        decision(policy.check_file_changes(
+            [
+                (root_lock.as_path(), FileOp::Update),
+                (app_lock.as_path(), FileOp::Update),
+            ],
+            cwd,
+        ))
+    );
+    Ok(())
+}
+
+#[test]
+fn tool_rules_reject_invalid_arguments() {
+    for (policy_src, expected) in [
+        (
+            r#"file_rule(path_glob = "*.lock", ops = [])"#,
+            "invalid rule: file_rule ops cannot be empty",
+        ),
+        (
+            r#"file_rule(path_glob = "*.lock", ops = ["rename"])"#,
+            "unknown file op `rename`",
+        ),
+        (r#"url_rule(domain_glob = "")"#, "glob cannot be empty"),
+        (
+            r#"mcp_tool_rule(server = "github", tool = "[", decision = "allow")"#,
+            "invalid glob `[`",
+        ),
+    ] {
+        let mut parser = PolicyParser::new();
+        let err = parser
+            .parse("test.rules", policy_src)
+            .expect_err("expected parse error");
+        assert!(
+            err.to_string().contains(expected),
+            "unexpected error for {policy_src}: {err}"
+        );
+    }
+}
@@ -27,16 +27,25 @@ use seccompiler::apply_filter;
 
 /// Apply sandbox policies inside this thread so only the child inherits
 /// them, not the entire CLI process.
+///
+/// When `network_proxy_port` is set and the policy restricts network access,
+/// the command already runs in a network namespace whose only route is the
+/// proxy bridge (see `isolate_network_to_proxy`), so TCP sockets stay allowed.
 pub(crate) fn apply_sandbox_policy_to_current_thread(
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
+    network_proxy_port: Option<u16>,
 ) -> Result<()> {
     if !sandbox_policy.has_full_disk_write_access() || !sandbox_policy.has_full_network_access() {
         set_no_new_privs()?;
     }
 
     if !sandbox_policy.has_full_network_access() {
-        install_network_seccomp_filter_on_current_thread()?;
+        if network_proxy_port.is_some() {
+            install_proxy_network_seccomp_filter_on_current_thread()?;
+        } else {
+            install_network_seccomp_filter_on_current_thread()?;
+        }
     }
 
     if !sandbox_policy.has_full_disk_write_access() {
@@ -96,6 +105,73 @@ fn install_filesystem_landlock_rules_on_current_thread(
     Ok(())
 }
 
+/// Installs a seccomp filter for commands whose traffic goes through the
+/// network proxy. TCP sockets are allowed (the network namespace limits where
+/// they can connect), while UDP, raw and other socket families stay blocked,
+/// as do listening sockets.
+fn install_proxy_network_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
+    let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
+
+    rules.insert(libc::SYS_accept, vec![]);
+    rules.insert(libc::SYS_accept4, vec![]);
+    rules.insert(libc::SYS_bind, vec![]);
+    rules.insert(libc::SYS_listen, vec![]);
+    rules.insert(libc::SYS_ptrace, vec![]);
+
+    let domain_is = |domain: i32| {
+        SeccompCondition::new(0, SeccompCmpArgLen::Dword, SeccompCmpOp::Eq, domain as u64)
+    };
+    let domain_is_not = |domain: i32| {
+        SeccompCondition::new(0, SeccompCmpArgLen::Dword, SeccompCmpOp::Ne, domain as u64)
+    };
+
+    // `socket` is denied for anything but AF_UNIX and TCP over AF_INET/AF_INET6.
+    let mut socket_rules = vec![SeccompRule::new(vec![
+        domain_is_not(libc::AF_UNIX)?,
+        domain_is_not(libc::AF_INET)?,
+        domain_is_not(libc::AF_INET6)?,
+    ])?];
+    for domain in [libc::AF_INET, libc::AF_INET6] {
+        // The low bits of `type` hold the socket type; the rest are flags
+        // such as SOCK_CLOEXEC.
+        for socket_type in [
+            libc::SOCK_DGRAM,
+            libc::SOCK_RAW,
+            libc::SOCK_RDM,
+            libc::SOCK_SEQPACKET,
+            libc::SOCK_DCCP,
+            libc::SOCK_PACKET,
+        ] {
+            socket_rules.push(SeccompRule::new(vec![
+                domain_is(domain)?,
+                SeccompCondition::new(
+                    1,
+                    SeccompCmpArgLen::Dword,
+                    SeccompCmpOp::MaskedEq(0xf),
+                    socket_type as u64,
+                )?,
+            ])?);
+        }
+        socket_rules.push(SeccompRule::new(vec![
+            domain_is(domain)?,
+            SeccompCondition::new(2, SeccompCmpArgLen::Dword, SeccompCmpOp::Ne, 0)?,
+            SeccompCondition::new(
+                2,
+                SeccompCmpArgLen::Dword,
+                SeccompCmpOp::Ne,
+                libc::IPPROTO_TCP as u64,
+            )?,
+        ])?);
+    }
+    rules.insert(libc::SYS_socket, socket_rules);
+    rules.insert(
+        libc::SYS_socketpair,
+        vec![SeccompRule::new(vec![domain_is_not(libc::AF_UNIX)?])?],
+    );
+
+    apply_network_seccomp_rules(rules)
+}
+
 /// Installs a seccomp filter that blocks outbound network access except for
 /// AF_UNIX domain sockets.
 fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
@@ -136,6 +212,13 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
     rules.insert(libc::SYS_socket, vec![unix_only_rule.clone()]);
     rules.insert(libc::SYS_socketpair, vec![unix_only_rule]); // always deny (Unix can use socketpair but fine, keep open?)
 
+    apply_network_seccomp_rules(rules)
+}
+
+/// Applies `rules` to the current thread, returning EPERM for matching calls.
+fn apply_network_seccomp_rules(
+    rules: BTreeMap<i64, Vec<SeccompRule>>,
+) -> std::result::Result<(), SandboxErr> {
     let filter = SeccompFilter::new(
         rules,
         SeccompAction::Allow,                     // default – allow
@@ -4,6 +4,8 @@ mod landlock;
 mod linux_run_main;
 #[cfg(target_os = "linux")]
 mod mounts;
+#[cfg(target_os = "linux")]
+mod proxy_bridge;
 
 #[cfg(target_os = "linux")]
 pub fn run_main() -> ! {
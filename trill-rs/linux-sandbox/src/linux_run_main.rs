@@ -3,6 +3,10 @@ use std::ffi::CString;
 use std::path::PathBuf;
 
 use crate::landlock::apply_sandbox_policy_to_current_thread;
+use crate::mounts::apply_deny_read_mounts;
+use crate::mounts::drop_caps;
+use crate::mounts::is_running_as_root;
+use crate::proxy_bridge::isolate_network_to_proxy;
 
 #[derive(Debug, Parser)]
 pub struct LandlockCommand {
@@ -14,6 +18,16 @@ pub struct LandlockCommand {
     #[arg(long = "sandbox-policy")]
     pub sandbox_policy: trill_core::protocol::SandboxPolicy,
 
+    /// Existing paths to hide from the command. Repeatable.
+    #[arg(long = "deny-read")]
+    pub deny_read: Vec<PathBuf>,
+
+    /// Loopback port of the network proxy. When set, a command without full
+    /// network access runs in its own network namespace where this port on
+    /// 127.0.0.1 leads to the proxy and nothing else is reachable.
+    #[arg(long = "network-proxy-port")]
+    pub network_proxy_port: Option<u16>,
+
     /// Full command args to run under landlock.
     #[arg(trailing_var_arg = true)]
     pub command: Vec<String>,
@@ -23,10 +37,38 @@ pub fn run_main() -> ! {
     let LandlockCommand {
         sandbox_policy_cwd,
         sandbox_policy,
+        deny_read,
+        network_proxy_port,
         command,
     } = LandlockCommand::parse();
 
-    if let Err(e) = apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd) {
+    let network_proxy_port =
+        network_proxy_port.filter(|_| !sandbox_policy.has_full_network_access());
+    let running_as_root = is_running_as_root();
+    if let Some(port) = network_proxy_port
+        && let Err(e) = isolate_network_to_proxy(port)
+    {
+        panic!("error isolating the network: {e:?}");
+    }
+
+    if let Err(e) = apply_deny_read_mounts(&deny_read) {
+        panic!("error hiding deny_read paths: {e:?}");
+    }
+
+    // Drop the capabilities a non-root caller gained from the network's user
+    // namespace so the command cannot undo the setup above.
+    if network_proxy_port.is_some()
+        && !running_as_root
+        && let Err(e) = drop_caps()
+    {
+        panic!("error dropping capabilities: {e:?}");
+    }
+
+    if let Err(e) = apply_sandbox_policy_to_current_thread(
+        &sandbox_policy,
+        &sandbox_policy_cwd,
+        network_proxy_port,
+    ) {
         panic!("error running landlock: {e:?}");
     }
 
@@ -3,6 +3,7 @@
 use std::ffi::CString;
 use std::os::unix::ffi::OsStrExt;
 use std::path::Path;
+use std::path::PathBuf;
 
 use trill_core::error::CodexErr;
 use trill_core::error::Result;
@@ -22,17 +23,7 @@ pub(crate) fn apply_read_only_mounts(sandbox_policy: &SandboxPolicy, cwd: &Path)
         return Ok(());
     }
 
-    // Root can unshare the mount namespace directly; non-root needs a user
-    // namespace to gain capabilities for remounting.
-    if is_running_as_root() {
-        unshare_mount_namespace()?;
-    } else {
-        let original_euid = unsafe { libc::geteuid() };
-        let original_egid = unsafe { libc::getegid() };
-        unshare_user_and_mount_namespaces()?;
-        write_user_namespace_maps(original_euid, original_egid)?;
-    }
-    make_mounts_private()?;
+    enter_private_mount_namespace()?;
 
     for target in mount_targets {
         // Bind and remount read-only works for both files and directories.
@@ -48,6 +39,48 @@ pub(crate) fn apply_read_only_mounts(sandbox_policy: &SandboxPolicy, cwd: &Path)
     Ok(())
 }
 
+/// Hide `deny_read` paths from the sandboxed command before Landlock.
+///
+/// Directories are covered with an empty, read-only tmpfs and files with a
+/// read-only bind mount of `/dev/null`. Paths that no longer exist are skipped.
+pub(crate) fn apply_deny_read_mounts(deny_read: &[PathBuf]) -> Result<()> {
+    let targets: Vec<&PathBuf> = deny_read.iter().filter(|path| path.exists()).collect();
+    if targets.is_empty() {
+        return Ok(());
+    }
+
+    enter_private_mount_namespace()?;
+
+    for target in targets {
+        if target.is_dir() {
+            mount_empty_tmpfs(target)?;
+        } else {
+            bind_mount_dev_null(target)?;
+        }
+    }
+
+    if !is_running_as_root() {
+        drop_caps()?;
+    }
+
+    Ok(())
+}
+
+/// Move into a mount namespace whose changes do not propagate to the host.
+fn enter_private_mount_namespace() -> Result<()> {
+    // Root can unshare the mount namespace directly; non-root needs a user
+    // namespace to gain capabilities for remounting.
+    if is_running_as_root() {
+        unshare_mount_namespace()?;
+    } else {
+        let original_euid = unsafe { libc::geteuid() };
+        let original_egid = unsafe { libc::getegid() };
+        unshare_user_and_mount_namespaces()?;
+        write_user_namespace_maps(original_euid, original_egid)?;
+    }
+    make_mounts_private()
+}
+
 /// Collect read-only mount targets, resolving worktree `.git` pointer files.
 fn collect_read_only_mount_targets(
     writable_roots: &[WritableRoot],
@@ -137,7 +170,7 @@ fn unshare_user_and_mount_namespaces() -> Result<()> {
     Ok(())
 }
 
-fn is_running_as_root() -> bool {
+pub(crate) fn is_running_as_root() -> bool {
     unsafe { libc::geteuid() == 0 }
 }
 
@@ -157,7 +190,7 @@ struct CapUserData {
 const LINUX_CAPABILITY_VERSION_3: u32 = 0x2008_0522;
 
 /// Map the provided uid/gid to root inside the user namespace.
-fn write_user_namespace_maps(uid: libc::uid_t, gid: libc::gid_t) -> Result<()> {
+pub(crate) fn write_user_namespace_maps(uid: libc::uid_t, gid: libc::gid_t) -> Result<()> {
     write_proc_file("/proc/self/setgroups", "deny\n")?;
 
     write_proc_file("/proc/self/uid_map", format!("0 {uid} 1\n"))?;
@@ -166,7 +199,7 @@ fn write_user_namespace_maps(uid: libc::uid_t, gid: libc::gid_t) -> Result<()> {
 }
 
 /// Drop all capabilities in the current user namespace.
-fn drop_caps() -> Result<()> {
+pub(crate) fn drop_caps() -> Result<()> {
     let mut header = CapUserHeader {
         version: LINUX_CAPABILITY_VERSION_3,
         pid: 0,
@@ -220,12 +253,7 @@ fn make_mounts_private() -> Result<()> {
 
 /// Bind-mount a path onto itself and remount read-only.
 fn bind_mount_read_only(path: &Path) -> Result<()> {
-    let c_path = CString::new(path.as_os_str().as_bytes()).map_err(|_| {
-        CodexErr::UnsupportedOperation(format!(
-            "Sandbox mount path contains NUL byte: {path}",
-            path = path.display()
-        ))
-    })?;
+    let c_path = mount_path(path)?;
 
     let bind_result = unsafe {
         libc::mount(
@@ -256,6 +284,68 @@ fn bind_mount_read_only(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Cover a directory with an empty tmpfs that cannot be written to.
+fn mount_empty_tmpfs(path: &Path) -> Result<()> {
+    let c_path = mount_path(path)?;
+    let fstype = c"tmpfs";
+    let options = c"size=0,mode=000";
+    let result = unsafe {
+        libc::mount(
+            fstype.as_ptr(),
+            c_path.as_ptr(),
+            fstype.as_ptr(),
+            libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC | libc::MS_RDONLY,
+            options.as_ptr().cast(),
+        )
+    };
+    if result != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+/// Replace a file's contents with `/dev/null` for the sandboxed command.
+fn bind_mount_dev_null(path: &Path) -> Result<()> {
+    let c_path = mount_path(path)?;
+    let dev_null = c"/dev/null";
+    let bind_result = unsafe {
+        libc::mount(
+            dev_null.as_ptr(),
+            c_path.as_ptr(),
+            std::ptr::null(),
+            libc::MS_BIND,
+            std::ptr::null(),
+        )
+    };
+    if bind_result != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    let remount_result = unsafe {
+        libc::mount(
+            std::ptr::null(),
+            c_path.as_ptr(),
+            std::ptr::null(),
+            libc::MS_BIND | libc::MS_REMOUNT | libc::MS_RDONLY,
+            std::ptr::null(),
+        )
+    };
+    if remount_result != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    Ok(())
+}
+
+fn mount_path(path: &Path) -> Result<CString> {
+    CString::new(path.as_os_str().as_bytes()).map_err(|_| {
+        CodexErr::UnsupportedOperation(format!(
+            "Sandbox mount path contains NUL byte: {path}",
+            path = path.display()
+        ))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
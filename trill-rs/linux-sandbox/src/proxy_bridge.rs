@@ -0,0 +1,203 @@
+use std::io;
+use std::net::Ipv4Addr;
+use std::net::Shutdown;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
+use std::os::fd::RawFd;
+use std::os::unix::net::UnixStream;
+
+use trill_core::error::Result;
+
+use crate::mounts::is_running_as_root;
+use crate::mounts::write_user_namespace_maps;
+
+/// Move the command into a network namespace that only has a loopback
+/// interface, and bridge `127.0.0.1:port` inside it to the network proxy
+/// listening on the same port outside.
+///
+/// A bridge process is forked before the switch so it stays on the host
+/// network. The listener is created inside the new namespace and handed to the
+/// bridge, which forwards every connection to the proxy. The bridge is killed
+/// when the command exits.
+///
+/// Non-root callers also get a user namespace in which they are root; the
+/// caller must drop those capabilities before running the command.
+pub(crate) fn isolate_network_to_proxy(port: u16) -> Result<()> {
+    let (channel, bridge_channel) = UnixStream::pair()?;
+    let parent_pid = unsafe { libc::getpid() };
+    match unsafe { libc::fork() } {
+        -1 => return Err(io::Error::last_os_error().into()),
+        0 => {
+            drop(channel);
+            run_bridge(bridge_channel, parent_pid, port);
+        }
+        _ => drop(bridge_channel),
+    }
+
+    enter_network_namespace()?;
+    bring_loopback_up()?;
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    send_fd(&channel, listener.as_raw_fd())?;
+    Ok(())
+}
+
+/// Unshare the network namespace, with a user namespace for non-root.
+fn enter_network_namespace() -> Result<()> {
+    if is_running_as_root() {
+        return unshare(libc::CLONE_NEWNET);
+    }
+    let original_euid = unsafe { libc::geteuid() };
+    let original_egid = unsafe { libc::getegid() };
+    unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET)?;
+    write_user_namespace_maps(original_euid, original_egid)
+}
+
+fn unshare(flags: libc::c_int) -> Result<()> {
+    let result = unsafe { libc::unshare(flags) };
+    if result != 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+/// A fresh network namespace starts with `lo` down.
+fn bring_loopback_up() -> Result<()> {
+    let socket = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+    if socket < 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    let socket = unsafe { OwnedFd::from_raw_fd(socket) };
+
+    let mut request: libc::ifreq = unsafe { std::mem::zeroed() };
+    for (dst, src) in request.ifr_name.iter_mut().zip(b"lo") {
+        *dst = *src as libc::c_char;
+    }
+    if unsafe { libc::ioctl(socket.as_raw_fd(), libc::SIOCGIFFLAGS, &mut request) } < 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    unsafe {
+        request.ifr_ifru.ifru_flags |= libc::IFF_UP as libc::c_short;
+    }
+    if unsafe { libc::ioctl(socket.as_raw_fd(), libc::SIOCSIFFLAGS, &request) } < 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+/// Body of the bridge process. Never returns.
+fn run_bridge(channel: UnixStream, parent_pid: libc::pid_t, port: u16) -> ! {
+    unsafe {
+        libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL, 0, 0, 0);
+    }
+    // The command may already be gone, in which case the signal never comes.
+    if unsafe { libc::getppid() } != parent_pid {
+        unsafe { libc::_exit(0) };
+    }
+    // Don't hold the command's output pipes open.
+    detach_stdio();
+
+    let listener = match recv_fd(&channel) {
+        Ok(fd) => TcpListener::from(fd),
+        Err(_) => unsafe { libc::_exit(1) },
+    };
+    drop(channel);
+    for inbound in listener.incoming() {
+        let Ok(inbound) = inbound else {
+            continue;
+        };
+        std::thread::spawn(move || {
+            if let Ok(outbound) = TcpStream::connect((Ipv4Addr::LOCALHOST, port)) {
+                forward(inbound, outbound);
+            }
+        });
+    }
+    unsafe { libc::_exit(0) }
+}
+
+fn detach_stdio() {
+    let dev_null = unsafe { libc::open(c"/dev/null".as_ptr(), libc::O_RDWR) };
+    if dev_null < 0 {
+        return;
+    }
+    for fd in [libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+        unsafe { libc::dup2(dev_null, fd) };
+    }
+    if dev_null > libc::STDERR_FILENO {
+        unsafe { libc::close(dev_null) };
+    }
+}
+
+/// Copy bytes both ways until each side has finished writing.
+fn forward(inbound: TcpStream, outbound: TcpStream) {
+    let (Ok(mut inbound_reader), Ok(mut outbound_reader)) =
+        (inbound.try_clone(), outbound.try_clone())
+    else {
+        return;
+    };
+    let upload = std::thread::spawn(move || {
+        let _ = io::copy(&mut inbound_reader, &mut &outbound);
+        let _ = outbound.shutdown(Shutdown::Write);
+    });
+    let _ = io::copy(&mut outbound_reader, &mut &inbound);
+    let _ = inbound.shutdown(Shutdown::Write);
+    let _ = upload.join();
+}
+
+/// Room for the control message carrying one file descriptor, aligned for
+/// `cmsghdr`.
+type FdControl = [u64; 4];
+
+fn send_fd(channel: &UnixStream, fd: RawFd) -> io::Result<()> {
+    let mut payload = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: payload.as_mut_ptr().cast(),
+        iov_len: payload.len(),
+    };
+    let mut control: FdControl = [0; 4];
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr().cast();
+    msg.msg_controllen = unsafe { libc::CMSG_SPACE(size_of::<RawFd>() as u32) } as _;
+    unsafe {
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(size_of::<RawFd>() as u32) as _;
+        std::ptr::write_unaligned(libc::CMSG_DATA(cmsg).cast::<RawFd>(), fd);
+    }
+    if unsafe { libc::sendmsg(channel.as_raw_fd(), &msg, 0) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn recv_fd(channel: &UnixStream) -> io::Result<OwnedFd> {
+    let mut payload = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: payload.as_mut_ptr().cast(),
+        iov_len: payload.len(),
+    };
+    let mut control: FdControl = [0; 4];
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr().cast();
+    msg.msg_controllen = size_of::<FdControl>() as _;
+    let received = unsafe { libc::recvmsg(channel.as_raw_fd(), &mut msg, libc::MSG_CMSG_CLOEXEC) };
+    if received < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let cmsg = unsafe { libc::CMSG_FIRSTHDR(&msg) };
+    if cmsg.is_null()
+        || unsafe { (*cmsg).cmsg_level } != libc::SOL_SOCKET
+        || unsafe { (*cmsg).cmsg_type } != libc::SCM_RIGHTS
+    {
+        return Err(io::Error::other("no file descriptor received"));
+    }
+    let fd = unsafe { std::ptr::read_unaligned(libc::CMSG_DATA(cmsg).cast::<RawFd>()) };
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
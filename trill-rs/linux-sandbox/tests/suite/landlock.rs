@@ -269,3 +269,89 @@ async fn sandbox_blocks_dev_tcp_redirection() {
     // all images ship bash, so we guard against 127 as well.
     assert_network_blocked(&["bash", "-c", "echo hi > /dev/tcp/127.0.0.1/80"]).await;
 }
+
+#[tokio::test]
+async fn deny_read_paths_are_hidden_from_command() {
+    let tmp = tempfile::tempdir().unwrap();
+    let secret_dir = tmp.path().join("secrets");
+    std::fs::create_dir(&secret_dir).unwrap();
+    std::fs::write(secret_dir.join("token"), "dir-secret").unwrap();
+    let secret_file = tmp.path().join(".env");
+    std::fs::write(&secret_file, "file-secret").unwrap();
+    std::fs::write(tmp.path().join("visible"), "visible").unwrap();
+
+    let output = tokio::process::Command::new(env!("CARGO_BIN_EXE_trill-linux-sandbox"))
+        .arg("--sandbox-policy-cwd")
+        .arg(tmp.path())
+        .arg("--sandbox-policy")
+        .arg(r#"{"type":"read-only"}"#)
+        .arg("--deny-read")
+        .arg(&secret_dir)
+        .arg("--deny-read")
+        .arg(&secret_file)
+        .arg("--")
+        .args([
+            "bash",
+            "-c",
+            "cat visible .env; ls -A secrets; cat secrets/token",
+        ])
+        .current_dir(tmp.path())
+        .output()
+        .await
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "visible");
+    assert_ne!(output.status.code(), Some(0));
+    // The host still sees the original contents.
+    assert_eq!(
+        std::fs::read_to_string(&secret_file).unwrap(),
+        "file-secret"
+    );
+}
+
+#[tokio::test]
+async fn network_proxy_is_the_only_reachable_destination() {
+    use std::io::Read;
+    use std::io::Write;
+
+    let proxy = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let other = std::net::TcpListener::bind("0.0.0.0:0").unwrap();
+    let proxy_port = proxy.local_addr().unwrap().port();
+    let other_port = other.local_addr().unwrap().port();
+    let echo = std::thread::spawn(move || {
+        let (mut stream, _) = proxy.accept().unwrap();
+        let mut buf = [0u8; 3];
+        stream.read_exact(&mut buf).unwrap();
+        stream.write_all(&buf).unwrap();
+    });
+
+    // The proxy port leads to the proxy; other loopback ports and the proxy
+    // port on other addresses lead nowhere.
+    let script = format!(
+        "exec 3<>/dev/tcp/127.0.0.1/{proxy_port} && echo hi >&3 && read -r reply <&3 \
+         && [ \"$reply\" = hi ] \
+         && ! echo hi > /dev/tcp/127.0.0.1/{other_port} \
+         && ! echo hi > /dev/tcp/192.0.2.1/{proxy_port}"
+    );
+    let output = tokio::process::Command::new(env!("CARGO_BIN_EXE_trill-linux-sandbox"))
+        .arg("--sandbox-policy-cwd")
+        .arg(std::env::current_dir().unwrap())
+        .arg("--sandbox-policy")
+        .arg(r#"{"type":"read-only"}"#)
+        .arg("--network-proxy-port")
+        .arg(proxy_port.to_string())
+        .arg("--")
+        .args(["bash", "-c", &script])
+        .output()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    echo.join().unwrap();
+}
@@ -1,7 +1,12 @@
-use trill_core::LMSTUDIO_OSS_PROVIDER_ID;
-use trill_core::config::Config;
+use reqwest::StatusCode;
+use serde::Deserialize;
 use std::io;
 use std::path::Path;
+use std::time::Duration;
+use trill_core::LMSTUDIO_OSS_PROVIDER_ID;
+use trill_core::config::Config;
+use trill_ollama::PullEvent;
+use trill_ollama::PullProgressReporter;
 
 #[derive(Clone)]
 pub struct LMStudioClient {
@@ -10,6 +15,143 @@ pub struct LMStudioClient {
 }
 
 const LMSTUDIO_CONNECTION_ERROR: &str = "LM Studio is not responding. Install from https://lmstudio.ai/download and run 'lms server start'.";
+const DOWNLOAD_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A downloaded model as listed by LM Studio's native REST API.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LMStudioModel {
+    /// Identifier used to load the model, e.g. "openai/gpt-oss-20b".
+    pub key: String,
+    /// "llm" or "embedding".
+    #[serde(rename = "type")]
+    pub model_type: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub max_context_length: Option<u64>,
+    #[serde(default)]
+    pub loaded_instances: Vec<LoadedInstance>,
+}
+
+impl LMStudioModel {
+    pub fn is_loaded(&self) -> bool {
+        !self.loaded_instances.is_empty()
+    }
+
+    /// Whether `model` names this model or one of its loaded instances.
+    pub fn matches(&self, model: &str) -> bool {
+        self.key == model || self.loaded_instances.iter().any(|i| i.id == model)
+    }
+}
+
+/// A model currently loaded into memory.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LoadedInstance {
+    pub id: String,
+    #[serde(default)]
+    pub config: LoadedInstanceConfig,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct LoadedInstanceConfig {
+    #[serde(default)]
+    pub context_length: Option<u64>,
+}
+
+/// Settings for loading a model. Unset fields use LM Studio's defaults.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadOptions {
+    pub context_length: Option<u64>,
+    /// Unload the model after it has been idle this long.
+    pub ttl: Option<Duration>,
+    /// Fraction of the model to offload to the GPU, from 0.0 to 1.0.
+    pub gpu_offload: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct ModelList {
+    models: Vec<LMStudioModel>,
+}
+
+/// A model as listed by `/api/v0/models`, on LM Studio builds without the v1
+/// REST API.
+#[derive(Deserialize)]
+struct LegacyModel {
+    id: String,
+    #[serde(rename = "type")]
+    model_type: String,
+    #[serde(default)]
+    state: Option<String>,
+    #[serde(default)]
+    max_context_length: Option<u64>,
+    #[serde(default)]
+    loaded_context_length: Option<u64>,
+}
+
+impl From<LegacyModel> for LMStudioModel {
+    fn from(model: LegacyModel) -> Self {
+        let loaded_instances = if model.state.as_deref() == Some("loaded") {
+            vec![LoadedInstance {
+                id: model.id.clone(),
+                config: LoadedInstanceConfig {
+                    context_length: model.loaded_context_length,
+                },
+            }]
+        } else {
+            Vec::new()
+        };
+        Self {
+            key: model.id,
+            model_type: model.model_type,
+            display_name: None,
+            max_context_length: model.max_context_length,
+            loaded_instances,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LegacyModelList {
+    data: Vec<LegacyModel>,
+}
+
+#[derive(Deserialize)]
+struct LoadResponse {
+    instance_id: String,
+}
+
+/// State of a download job, as returned when it starts and when polled.
+#[derive(Debug, Deserialize)]
+struct DownloadJob {
+    #[serde(default)]
+    job_id: Option<String>,
+    status: String,
+    #[serde(default)]
+    downloaded_bytes: Option<u64>,
+    #[serde(default)]
+    total_size_bytes: Option<u64>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+impl DownloadJob {
+    fn to_event(&self) -> PullEvent {
+        match self.status.as_str() {
+            "completed" | "already_downloaded" => PullEvent::Success,
+            "failed" => PullEvent::Error(
+                self.error
+                    .clone()
+                    .unwrap_or_else(|| "download failed".to_string()),
+            ),
+            "downloading" => PullEvent::ChunkProgress {
+                digest: self.job_id.clone().unwrap_or_default(),
+                total: self.total_size_bytes,
+                completed: self.downloaded_bytes,
+            },
+            status => PullEvent::Status(status.to_string()),
+        }
+    }
+}
 
 impl LMStudioClient {
     pub async fn try_from_provider(config: &Config) -> std::io::Result<Self> {
@@ -61,8 +203,124 @@ impl LMStudioClient {
         }
     }
 
+    // Return the list of models available on the LM Studio server.
+    pub async fn fetch_models(&self) -> io::Result<Vec<String>> {
+        let url = format!("{}/models", self.base_url.trim_end_matches('/'));
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| io::Error::other(format!("Request failed: {e}")))?;
+
+        if response.status().is_success() {
+            let json: serde_json::Value = response.json().await.map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("JSON parse error: {e}"))
+            })?;
+            let models = json["data"]
+                .as_array()
+                .ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, "No 'data' array in response")
+                })?
+                .iter()
+                .filter_map(|model| model["id"].as_str())
+                .map(std::string::ToString::to_string)
+                .collect();
+            Ok(models)
+        } else {
+            Err(io::Error::other(format!(
+                "Failed to fetch models: {}",
+                response.status()
+            )))
+        }
+    }
+
+    /// List downloaded models with their loaded instances, using LM Studio's
+    /// native REST API, or its older `/api/v0` API when that is missing.
+    pub async fn list_models(&self) -> io::Result<Vec<LMStudioModel>> {
+        let response = self
+            .client
+            .get(self.native_url("models"))
+            .send()
+            .await
+            .map_err(|e| io::Error::other(format!("Request failed: {e}")))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return self.list_legacy_models().await;
+        }
+        if !response.status().is_success() {
+            return Err(io::Error::other(format!(
+                "Failed to list models: {}",
+                response.status()
+            )));
+        }
+        let list: ModelList = response.json().await.map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("JSON parse error: {e}"))
+        })?;
+        Ok(list.models)
+    }
+
+    async fn list_legacy_models(&self) -> io::Result<Vec<LMStudioModel>> {
+        let response = self
+            .client
+            .get(format!("{}/api/v0/models", self.host_root()))
+            .send()
+            .await
+            .map_err(|e| io::Error::other(format!("Request failed: {e}")))?;
+        if !response.status().is_success() {
+            return Err(io::Error::other(format!(
+                "Failed to list models: {}",
+                response.status()
+            )));
+        }
+        let list: LegacyModelList = response.json().await.map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("JSON parse error: {e}"))
+        })?;
+        Ok(list.data.into_iter().map(LMStudioModel::from).collect())
+    }
+
+    /// Load `model` with the given options and return the new instance id.
+    ///
+    /// LM Studio builds without the v1 REST API load the model on its first
+    /// request instead; the options are not applied there and the model key
+    /// is returned as the instance id.
+    pub async fn load_model(&self, model: &str, options: &LoadOptions) -> io::Result<String> {
+        let mut body = serde_json::json!({ "model": model });
+        if let Some(context_length) = options.context_length {
+            body["context_length"] = context_length.into();
+        }
+        if let Some(ttl) = options.ttl {
+            body["ttl"] = ttl.as_secs().into();
+        }
+        if let Some(gpu_offload) = options.gpu_offload {
+            body["gpu_offload"] = gpu_offload.into();
+        }
+        let response = self
+            .client
+            .post(self.native_url("models/load"))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| io::Error::other(format!("Request failed: {e}")))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            tracing::debug!("LM Studio has no v1 load endpoint; loading {model} on first request");
+            self.load_model_legacy(model).await?;
+            return Ok(model.to_string());
+        }
+        if !response.status().is_success() {
+            return Err(io::Error::other(format!(
+                "Failed to load model: {}",
+                response.status()
+            )));
+        }
+        let loaded: LoadResponse = response.json().await.map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("JSON parse error: {e}"))
+        })?;
+        tracing::info!("Successfully loaded model '{model}'");
+        Ok(loaded.instance_id)
+    }
+
     // Load a model by sending an empty request with max_tokens 1
-    pub async fn load_model(&self, model: &str) -> io::Result<()> {
+    async fn load_model_legacy(&self, model: &str) -> io::Result<()> {
         let url = format!("{}/responses", self.base_url.trim_end_matches('/'));
 
         let request_body = serde_json::json!({
@@ -91,38 +349,117 @@ impl LMStudioClient {
         }
     }
 
-    // Return the list of models available on the LM Studio server.
-    pub async fn fetch_models(&self) -> io::Result<Vec<String>> {
-        let url = format!("{}/models", self.base_url.trim_end_matches('/'));
+    /// Load `model`, reporting progress as status events.
+    pub async fn load_with_reporter(
+        &self,
+        model: &str,
+        options: &LoadOptions,
+        reporter: &mut dyn PullProgressReporter,
+    ) -> io::Result<String> {
+        reporter.on_event(&PullEvent::Status(format!("Loading model {model}...")))?;
+        match self.load_model(model, options).await {
+            Ok(instance_id) => {
+                reporter.on_event(&PullEvent::Success)?;
+                Ok(instance_id)
+            }
+            Err(err) => {
+                reporter.on_event(&PullEvent::Error(err.to_string()))?;
+                Err(err)
+            }
+        }
+    }
+
+    /// Unload a loaded model instance.
+    pub async fn unload_model(&self, instance_id: &str) -> io::Result<()> {
         let response = self
             .client
-            .get(&url)
+            .post(self.native_url("models/unload"))
+            .json(&serde_json::json!({ "instance_id": instance_id }))
             .send()
             .await
             .map_err(|e| io::Error::other(format!("Request failed: {e}")))?;
-
         if response.status().is_success() {
-            let json: serde_json::Value = response.json().await.map_err(|e| {
-                io::Error::new(io::ErrorKind::InvalidData, format!("JSON parse error: {e}"))
-            })?;
-            let models = json["data"]
-                .as_array()
-                .ok_or_else(|| {
-                    io::Error::new(io::ErrorKind::InvalidData, "No 'data' array in response")
-                })?
-                .iter()
-                .filter_map(|model| model["id"].as_str())
-                .map(std::string::ToString::to_string)
-                .collect();
-            Ok(models)
+            Ok(())
         } else {
             Err(io::Error::other(format!(
-                "Failed to fetch models: {}",
+                "Failed to unload model: {}",
                 response.status()
             )))
         }
     }
 
+    /// Download `model` and drive a progress reporter until the download
+    /// completes. Progress is polled from the download job; LM Studio builds
+    /// without the v1 REST API download through `lms get` instead.
+    pub async fn download_with_reporter(
+        &self,
+        model: &str,
+        reporter: &mut dyn PullProgressReporter,
+    ) -> io::Result<()> {
+        reporter.on_event(&PullEvent::Status(format!("Downloading model {model}...")))?;
+        let response = self
+            .client
+            .post(self.native_url("models/download"))
+            .json(&serde_json::json!({ "model": model }))
+            .send()
+            .await
+            .map_err(|e| io::Error::other(format!("Request failed: {e}")))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            Self::download_model_with_lms(model)?;
+            reporter.on_event(&PullEvent::Success)?;
+            return Ok(());
+        }
+        if !response.status().is_success() {
+            return Err(io::Error::other(format!(
+                "Failed to start download: {}",
+                response.status()
+            )));
+        }
+        let mut job: DownloadJob = response.json().await.map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("JSON parse error: {e}"))
+        })?;
+
+        loop {
+            let event = job.to_event();
+            reporter.on_event(&event)?;
+            match event {
+                PullEvent::Success => {
+                    tracing::info!("Successfully downloaded model '{model}'");
+                    return Ok(());
+                }
+                PullEvent::Error(err) => {
+                    return Err(io::Error::other(format!("Download failed: {err}")));
+                }
+                PullEvent::ChunkProgress { .. } | PullEvent::Status(_) => {}
+            }
+            let Some(job_id) = job.job_id.as_deref() else {
+                return Err(io::Error::other(
+                    "Download is in progress but LM Studio returned no job id",
+                ));
+            };
+            tokio::time::sleep(DOWNLOAD_POLL_INTERVAL).await;
+            job = self.download_status(job_id).await?;
+        }
+    }
+
+    async fn download_status(&self, job_id: &str) -> io::Result<DownloadJob> {
+        let response = self
+            .client
+            .get(self.native_url(&format!("models/download/status/{job_id}")))
+            .send()
+            .await
+            .map_err(|e| io::Error::other(format!("Request failed: {e}")))?;
+        if !response.status().is_success() {
+            return Err(io::Error::other(format!(
+                "Failed to query download status: {}",
+                response.status()
+            )));
+        }
+        response.json().await.map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("JSON parse error: {e}"))
+        })
+    }
+
     // Find lms, checking fallback paths if not in PATH
     fn find_lms() -> std::io::Result<String> {
         Self::find_lms_with_home_dir(None)
@@ -165,7 +502,7 @@ impl LMStudioClient {
         }
     }
 
-    pub async fn download_model(&self, model: &str) -> std::io::Result<()> {
+    fn download_model_with_lms(model: &str) -> std::io::Result<()> {
         let lms = Self::find_lms()?;
         eprintln!("Downloading model: {model}");
 
@@ -189,6 +526,19 @@ impl LMStudioClient {
         Ok(())
     }
 
+    /// URL of a native REST endpoint; the provider's base_url points at the
+    /// OpenAI-compatible `/v1` API instead. LM Studio builds older than the
+    /// v1 REST API answer these with 404, and callers fall back to the
+    /// `/api/v0` listing, loading on first request and `lms get`.
+    fn native_url(&self, path: &str) -> String {
+        format!("{}/api/v1/{path}", self.host_root())
+    }
+
+    fn host_root(&self) -> &str {
+        let base_url = self.base_url.trim_end_matches('/');
+        base_url.strip_suffix("/v1").unwrap_or(base_url)
+    }
+
     /// Low-level constructor given a raw host root, e.g. "http://localhost:1234".
     #[cfg(test)]
     fn from_host_root(host_root: impl Into<String>) -> Self {
@@ -351,6 +701,293 @@ mod tests {
         );
     }
 
+    /// Records reported events so tests can assert on them.
+    #[derive(Default)]
+    struct RecordingReporter(Vec<PullEvent>);
+
+    impl PullProgressReporter for RecordingReporter {
+        fn on_event(&mut self, event: &PullEvent) -> io::Result<()> {
+            self.0.push(event.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_models_reports_loaded_instances() {
+        if std::env::var(trill_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_list_models_reports_loaded_instances",
+                trill_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/v1/models"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "models": [
+                        {
+                            "type": "llm",
+                            "key": "openai/gpt-oss-20b",
+                            "display_name": "gpt-oss 20B",
+                            "max_context_length": 131072,
+                            "loaded_instances": [
+                                {"id": "openai/gpt-oss-20b", "config": {"context_length": 16384}}
+                            ]
+                        },
+                        {
+                            "type": "embedding",
+                            "key": "text-embedding-nomic-embed-text-v1.5",
+                            "loaded_instances": []
+                        }
+                    ]
+                })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = LMStudioClient::from_host_root(format!("{}/v1", server.uri()));
+        let models = client.list_models().await.expect("list models");
+
+        assert_eq!(models.len(), 2);
+        assert!(models[0].is_loaded());
+        assert!(models[0].matches("openai/gpt-oss-20b"));
+        assert_eq!(
+            models[0].loaded_instances[0].config.context_length,
+            Some(16384)
+        );
+        assert_eq!(models[1].model_type, "embedding");
+        assert!(!models[1].is_loaded());
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_legacy_endpoints_on_404() {
+        if std::env::var(trill_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_falls_back_to_legacy_endpoints_on_404",
+                trill_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/v0/models"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "data": [
+                        {
+                            "id": "openai/gpt-oss-20b",
+                            "type": "llm",
+                            "state": "loaded",
+                            "max_context_length": 131072,
+                            "loaded_context_length": 8192
+                        },
+                        {"id": "qwen/qwen3-8b", "type": "llm", "state": "not-loaded"}
+                    ]
+                })),
+            )
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/responses"))
+            .and(wiremock::matchers::body_json(serde_json::json!({
+                "model": "qwen/qwen3-8b",
+                "input": "",
+                "max_output_tokens": 1
+            })))
+            .respond_with(wiremock::ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = LMStudioClient::from_host_root(format!("{}/v1", server.uri()));
+        let models = client.list_models().await.expect("list models");
+        assert_eq!(models.len(), 2);
+        assert!(models[0].matches("openai/gpt-oss-20b"));
+        assert_eq!(
+            models[0].loaded_instances[0].config.context_length,
+            Some(8192)
+        );
+        assert!(!models[1].is_loaded());
+
+        let instance_id = client
+            .load_model("qwen/qwen3-8b", &LoadOptions::default())
+            .await
+            .expect("load model");
+        assert_eq!(instance_id, "qwen/qwen3-8b");
+    }
+
+    #[tokio::test]
+    async fn test_load_and_unload_model() {
+        if std::env::var(trill_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_load_and_unload_model",
+                trill_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/v1/models/load"))
+            .and(wiremock::matchers::body_json(serde_json::json!({
+                "model": "openai/gpt-oss-20b",
+                "context_length": 32768,
+                "ttl": 600,
+            })))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "type": "llm",
+                    "instance_id": "openai/gpt-oss-20b",
+                    "status": "loaded",
+                    "load_time_seconds": 3.2
+                })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/v1/models/unload"))
+            .and(wiremock::matchers::body_json(
+                serde_json::json!({"instance_id": "openai/gpt-oss-20b"}),
+            ))
+            .respond_with(wiremock::ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = LMStudioClient::from_host_root(server.uri());
+        let mut reporter = RecordingReporter::default();
+        let instance_id = client
+            .load_with_reporter(
+                "openai/gpt-oss-20b",
+                &LoadOptions {
+                    context_length: Some(32768),
+                    ttl: Some(Duration::from_secs(600)),
+                    gpu_offload: None,
+                },
+                &mut reporter,
+            )
+            .await
+            .expect("load model");
+        assert_eq!(instance_id, "openai/gpt-oss-20b");
+        assert!(matches!(
+            reporter.0.as_slice(),
+            [PullEvent::Status(_), PullEvent::Success]
+        ));
+
+        client
+            .unload_model(&instance_id)
+            .await
+            .expect("unload model");
+    }
+
+    #[tokio::test]
+    async fn test_download_with_reporter_polls_until_complete() {
+        if std::env::var(trill_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_download_with_reporter_polls_until_complete",
+                trill_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/v1/models/download"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "job_id": "job-1",
+                    "status": "downloading",
+                    "total_size_bytes": 1000,
+                    "downloaded_bytes": 0
+                })),
+            )
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path(
+                "/api/v1/models/download/status/job-1",
+            ))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "job_id": "job-1",
+                    "status": "downloading",
+                    "total_size_bytes": 1000,
+                    "downloaded_bytes": 600
+                })),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path(
+                "/api/v1/models/download/status/job-1",
+            ))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "job_id": "job-1",
+                    "status": "completed",
+                    "total_size_bytes": 1000,
+                    "downloaded_bytes": 1000
+                })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = LMStudioClient::from_host_root(server.uri());
+        let mut reporter = RecordingReporter::default();
+        client
+            .download_with_reporter("qwen/qwen3-4b", &mut reporter)
+            .await
+            .expect("download model");
+
+        let progress: Vec<Option<u64>> = reporter
+            .0
+            .iter()
+            .filter_map(|event| match event {
+                PullEvent::ChunkProgress { completed, .. } => Some(*completed),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(progress, vec![Some(0), Some(600)]);
+        assert!(matches!(reporter.0.last(), Some(PullEvent::Success)));
+    }
+
+    #[tokio::test]
+    async fn test_download_with_reporter_surfaces_failure() {
+        if std::env::var(trill_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_download_with_reporter_surfaces_failure",
+                trill_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/v1/models/download"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "status": "failed",
+                    "error": "model not found"
+                })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = LMStudioClient::from_host_root(server.uri());
+        let mut reporter = RecordingReporter::default();
+        let err = client
+            .download_with_reporter("missing/model", &mut reporter)
+            .await
+            .expect_err("download should fail");
+        assert!(err.to_string().contains("model not found"));
+    }
+
     #[test]
     fn test_find_lms() {
         let result = LMStudioClient::find_lms();
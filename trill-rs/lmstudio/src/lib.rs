@@ -1,7 +1,13 @@
 mod client;
 
 pub use client::LMStudioClient;
+pub use client::LMStudioModel;
+pub use client::LoadOptions;
+pub use client::LoadedInstance;
+pub use client::LoadedInstanceConfig;
+use std::time::Duration;
 use trill_core::config::Config;
+use trill_ollama::CliProgressReporter;
 
 /// Default OSS model to use when `--oss` is passed without an explicit `-m`.
 pub const DEFAULT_OSS_MODEL: &str = "openai/gpt-oss-20b";
@@ -10,6 +16,8 @@ pub const DEFAULT_OSS_MODEL: &str = "openai/gpt-oss-20b";
 ///
 /// - Ensures a local LM Studio server is reachable.
 /// - Checks if the model exists locally and downloads it if missing.
+/// - Loads the model with the configured context window, TTL and GPU offload
+///   unless it is already loaded, reporting progress.
 pub async fn ensure_oss_ready(config: &Config) -> std::io::Result<()> {
     let model = match config.model.as_ref() {
         Some(model) => model,
@@ -19,28 +27,48 @@ pub async fn ensure_oss_ready(config: &Config) -> std::io::Result<()> {
     // Verify local LM Studio is reachable.
     let lmstudio_client = LMStudioClient::try_from_provider(config).await?;
 
-    match lmstudio_client.fetch_models().await {
-        Ok(models) => {
-            if !models.iter().any(|m| m == model) {
-                lmstudio_client.download_model(model).await?;
+    let loaded = match lmstudio_client.list_models().await {
+        Ok(models) => match models.iter().find(|m| m.matches(model)) {
+            Some(found) => found.is_loaded(),
+            None => {
+                let mut reporter = CliProgressReporter::new();
+                lmstudio_client
+                    .download_with_reporter(model, &mut reporter)
+                    .await?;
+                false
             }
-        }
+        },
         Err(err) => {
             // Not fatal; higher layers may still proceed and surface errors later.
             tracing::warn!("Failed to query local models from LM Studio: {}.", err);
+            false
         }
+    };
+    if loaded {
+        return Ok(());
     }
 
-    // Load the model in the background
-    tokio::spawn({
-        let client = lmstudio_client.clone();
-        let model = model.to_string();
-        async move {
-            if let Err(e) = client.load_model(&model).await {
-                tracing::warn!("Failed to load model {}: {}", model, e);
-            }
-        }
-    });
+    let settings = config.model_settings.get(model);
+    let context_length = settings
+        .and_then(|settings| settings.context_window)
+        .or(config.model_context_window)
+        .and_then(|window| u64::try_from(window).ok());
+    let options = LoadOptions {
+        context_length,
+        ttl: settings
+            .and_then(|settings| settings.ttl_sec)
+            .map(Duration::from_secs),
+        gpu_offload: settings.and_then(|settings| settings.gpu_offload),
+    };
+
+    let mut reporter = CliProgressReporter::new();
+    if let Err(e) = lmstudio_client
+        .load_with_reporter(model, &options, &mut reporter)
+        .await
+    {
+        // Not fatal; the first request loads the model with LM Studio's defaults.
+        tracing::warn!("Failed to load model {}: {}", model, e);
+    }
 
     Ok(())
 }
@@ -0,0 +1,235 @@
+use serde::Deserialize;
+use std::io;
+use trill_core::KOBOLDCPP_OSS_PROVIDER_ID;
+use trill_core::LLAMACPP_OSS_PROVIDER_ID;
+use trill_core::ModelProviderInfo;
+use trill_core::TABBYAPI_OSS_PROVIDER_ID;
+use trill_core::VLLM_OSS_PROVIDER_ID;
+
+/// Client for an OpenAI-compatible local server (llama.cpp, vLLM, TabbyAPI,
+/// KoboldCpp). Only the standard `/v1` endpoints are used.
+#[derive(Clone)]
+pub struct LocalServerClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+#[derive(Deserialize)]
+struct ModelList {
+    #[serde(default)]
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Deserialize)]
+struct ModelEntry {
+    id: String,
+}
+
+impl LocalServerClient {
+    /// Build a client for the provider `provider_id` and verify that the
+    /// server answers on `/v1/models`. If it does not, returns an error with
+    /// instructions for starting that server.
+    pub async fn try_from_provider(
+        provider_id: &str,
+        provider: &ModelProviderInfo,
+    ) -> io::Result<Self> {
+        let base_url = provider.base_url.as_deref().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Provider {provider_id} has no base_url"),
+            )
+        })?;
+        let client = reqwest::Client::builder()
+            .connect_timeout(std::time::Duration::from_secs(5))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        let client = Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        };
+        client.probe_server(provider_id).await?;
+        Ok(client)
+    }
+
+    async fn probe_server(&self, provider_id: &str) -> io::Result<()> {
+        let url = self.url("models");
+        let resp = self.client.get(&url).send().await.map_err(|err| {
+            tracing::warn!("Failed to connect to {url}: {err:?}");
+            io::Error::other(connection_error(provider_id))
+        })?;
+        if resp.status().is_success() {
+            Ok(())
+        } else {
+            tracing::warn!("Failed to probe server at {url}: HTTP {}", resp.status());
+            Err(io::Error::other(connection_error(provider_id)))
+        }
+    }
+
+    /// Return the ids of the models the server currently serves.
+    pub async fn fetch_models(&self) -> io::Result<Vec<String>> {
+        let resp = self
+            .client
+            .get(self.url("models"))
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "Failed to list models: HTTP {}",
+                resp.status()
+            )));
+        }
+        let list = resp.json::<ModelList>().await.map_err(io::Error::other)?;
+        Ok(list.data.into_iter().map(|model| model.id).collect())
+    }
+
+    /// Whether the server exposes the Responses API. An empty request is
+    /// rejected with a validation error by servers that route `/responses`,
+    /// and with 404/405 by those that do not.
+    pub async fn supports_responses(&self) -> io::Result<Option<bool>> {
+        let resp = self
+            .client
+            .post(self.url("responses"))
+            .json(&serde_json::json!({}))
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        Ok(match resp.status().as_u16() {
+            404 | 405 | 501 => Some(false),
+            400 | 422 => Some(true),
+            status if (200..300).contains(&status) => Some(true),
+            _ => None,
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{path}", self.base_url)
+    }
+}
+
+fn connection_error(provider_id: &str) -> String {
+    let hint = match provider_id {
+        LLAMACPP_OSS_PROVIDER_ID => {
+            "No running llama.cpp server detected. Start it with: `llama-server -m <model.gguf> --jinja`"
+        }
+        VLLM_OSS_PROVIDER_ID => {
+            "No running vLLM server detected. Start it with: `vllm serve <model> --enable-auto-tool-choice --tool-call-parser <parser>`"
+        }
+        TABBYAPI_OSS_PROVIDER_ID => {
+            "No running TabbyAPI server detected. Start it with `./start.sh` from your TabbyAPI checkout."
+        }
+        KOBOLDCPP_OSS_PROVIDER_ID => {
+            "No running KoboldCpp server detected. Start it with: `koboldcpp --model <model.gguf>`"
+        }
+        _ => "No running OpenAI-compatible server detected.",
+    };
+    format!(
+        "{hint} Set `base_url` under `[model_providers.{provider_id}]` if it listens elsewhere."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    async fn client_for(server: &wiremock::MockServer) -> io::Result<LocalServerClient> {
+        let provider = trill_core::create_oss_provider_with_base_url(
+            &format!("{}/v1", server.uri()),
+            trill_core::WireApi::Chat,
+        );
+        LocalServerClient::try_from_provider(LLAMACPP_OSS_PROVIDER_ID, &provider).await
+    }
+
+    #[tokio::test]
+    async fn lists_models_from_v1_models() {
+        if std::env::var(trill_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping lists_models_from_v1_models",
+                trill_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/v1/models"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "object": "list",
+                    "data": [
+                        { "id": "qwen3-8b", "object": "model" },
+                        { "id": "gemma-3-12b", "object": "model" }
+                    ]
+                })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server).await.expect("server is reachable");
+        let models = client.fetch_models().await.expect("models");
+
+        assert_eq!(models, vec!["qwen3-8b", "gemma-3-12b"]);
+    }
+
+    #[tokio::test]
+    async fn probe_failure_explains_how_to_start_the_server() {
+        if std::env::var(trill_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping probe_failure_explains_how_to_start_the_server",
+                trill_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/v1/models"))
+            .respond_with(wiremock::ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let err = match client_for(&server).await {
+            Ok(_) => panic!("probe should fail"),
+            Err(err) => err,
+        };
+
+        assert!(err.to_string().contains("llama-server"));
+    }
+
+    #[tokio::test]
+    async fn detects_responses_support_from_status() {
+        if std::env::var(trill_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping detects_responses_support_from_status",
+                trill_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        for (status, expected) in [(400, Some(true)), (404, Some(false)), (500, None)] {
+            let server = wiremock::MockServer::start().await;
+            wiremock::Mock::given(wiremock::matchers::method("GET"))
+                .and(wiremock::matchers::path("/v1/models"))
+                .respond_with(
+                    wiremock::ResponseTemplate::new(200)
+                        .set_body_json(serde_json::json!({ "data": [] })),
+                )
+                .mount(&server)
+                .await;
+            wiremock::Mock::given(wiremock::matchers::method("POST"))
+                .and(wiremock::matchers::path("/v1/responses"))
+                .respond_with(wiremock::ResponseTemplate::new(status))
+                .mount(&server)
+                .await;
+
+            let client = client_for(&server).await.expect("server is reachable");
+
+            assert_eq!(
+                client.supports_responses().await.expect("probe"),
+                expected,
+                "HTTP {status}"
+            );
+        }
+    }
+}
@@ -0,0 +1,66 @@
+mod client;
+
+pub use client::LocalServerClient;
+use std::io;
+use trill_core::ModelProviderInfo;
+use trill_core::WireApi;
+use trill_core::config::Config;
+
+/// Prepare an OpenAI-compatible local server when `--oss` is selected.
+///
+/// - Ensures the server is reachable on `/v1/models`.
+/// - Checks that it serves the requested model. These servers cannot pull
+///   models, so a missing model is reported rather than downloaded.
+pub async fn ensure_oss_ready(provider_id: &str, config: &Config) -> io::Result<()> {
+    let provider = config.model_providers.get(provider_id).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Built-in provider {provider_id} not found"),
+        )
+    })?;
+    let client = LocalServerClient::try_from_provider(provider_id, provider).await?;
+
+    match client.fetch_models().await {
+        Ok(models) if models.is_empty() => {
+            return Err(io::Error::other(format!(
+                "The {} server at {} has no model loaded.",
+                provider.name,
+                provider.base_url.as_deref().unwrap_or_default()
+            )));
+        }
+        Ok(models) => {
+            if let Some(model) = config.model.as_deref()
+                && !models.iter().any(|served| served == model)
+            {
+                tracing::warn!(
+                    "Model {model} is not listed by {}; it serves: {}.",
+                    provider.name,
+                    models.join(", ")
+                );
+            }
+        }
+        Err(err) => {
+            // Not fatal; higher layers may still proceed and surface errors later.
+            tracing::warn!("Failed to query models from {}: {err}.", provider.name);
+        }
+    }
+
+    Ok(())
+}
+
+/// Detect which wire API the running server supports. Returns `Ok(None)` when
+/// the probe is inconclusive; callers should keep the configured default in
+/// that case.
+pub async fn detect_wire_api(
+    provider_id: &str,
+    provider: &ModelProviderInfo,
+) -> io::Result<Option<WireApi>> {
+    let client = LocalServerClient::try_from_provider(provider_id, provider).await?;
+    Ok(client.supports_responses().await?.map(|supported| {
+        if supported {
+            WireApi::Responses
+        } else {
+            WireApi::Chat
+        }
+    }))
+}
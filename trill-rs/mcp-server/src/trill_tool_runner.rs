@@ -19,6 +19,7 @@ use trill_core::protocol::Event;
 use trill_core::protocol::EventMsg;
 use trill_core::protocol::ExecApprovalRequestEvent;
 use trill_core::protocol::Op;
+use trill_core::protocol::ReviewDecision;
 use trill_core::protocol::Submission;
 use trill_core::protocol::TurnCompleteEvent;
 use trill_protocol::ThreadId;
@@ -272,6 +273,20 @@ async fn run_codex_tool_session_inner(
                         // TODO: forward elicitation requests to the client?
                         continue;
                     }
+                    EventMsg::McpSamplingRequest(ev) => {
+                        // There is no way to ask the MCP client for approval yet.
+                        if let Err(e) = thread
+                            .submit(Op::ResolveMcpSampling {
+                                server_name: ev.server_name,
+                                request_id: ev.id,
+                                decision: ReviewDecision::Denied,
+                            })
+                            .await
+                        {
+                            tracing::error!("Failed to decline sampling request: {e}");
+                        }
+                        continue;
+                    }
                     EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
                         call_id,
                         turn_id: _,
@@ -336,6 +351,9 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::McpToolCallEnd(_)
                     | EventMsg::McpListToolsResponse(_)
                     | EventMsg::ListCustomPromptsResponse(_)
+                    | EventMsg::ListMcpPromptsResponse(_)
+                    | EventMsg::GetMcpPromptResponse(_)
+                    | EventMsg::UndoSnapshotsResponse(_)
                     | EventMsg::ListSkillsResponse(_)
                     | EventMsg::ExecCommandBegin(_)
                     | EventMsg::TerminalInteraction(_)
@@ -343,6 +361,9 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::ExecCommandEnd(_)
                     | EventMsg::BackgroundEvent(_)
                     | EventMsg::StreamError(_)
+                    | EventMsg::ToolCallLoopDetected(_)
+                    | EventMsg::HookCompleted(_)
+                    | EventMsg::McpSamplingProgress(_)
                     | EventMsg::PatchApplyBegin(_)
                     | EventMsg::PatchApplyEnd(_)
                     | EventMsg::TurnDiff(_)
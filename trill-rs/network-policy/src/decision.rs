@@ -0,0 +1,276 @@
+use crate::config::NetworkMode;
+use crate::config::NetworkPolicy;
+use crate::policy::Host;
+use crate::policy::compile_globset;
+use crate::policy::is_loopback_host;
+use crate::policy::is_non_public_ip;
+use crate::policy::normalize_host;
+use crate::reasons::REASON_DENIED;
+use crate::reasons::REASON_NOT_ALLOWED;
+use crate::reasons::REASON_NOT_ALLOWED_LOCAL;
+use anyhow::Result;
+use globset::GlobSet;
+use serde::Serialize;
+use std::net::IpAddr;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::net::lookup_host;
+use tokio::time::timeout;
+
+const DNS_LOOKUP_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HostBlockReason {
+    Denied,
+    NotAllowed,
+    NotAllowedLocal,
+}
+
+impl HostBlockReason {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Denied => REASON_DENIED,
+            Self::NotAllowed => REASON_NOT_ALLOWED,
+            Self::NotAllowedLocal => REASON_NOT_ALLOWED_LOCAL,
+        }
+    }
+}
+
+impl std::fmt::Display for HostBlockReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HostBlockDecision {
+    Allowed,
+    Blocked(HostBlockReason),
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BlockedRequest {
+    pub host: String,
+    pub reason: String,
+    pub client: Option<String>,
+    pub method: Option<String>,
+    pub mode: Option<NetworkMode>,
+    pub protocol: String,
+    pub timestamp: i64,
+}
+
+pub struct BlockedRequestArgs {
+    pub host: String,
+    pub reason: String,
+    pub client: Option<String>,
+    pub method: Option<String>,
+    pub mode: Option<NetworkMode>,
+    pub protocol: String,
+}
+
+impl BlockedRequest {
+    pub fn new(args: BlockedRequestArgs) -> Self {
+        let BlockedRequestArgs {
+            host,
+            reason,
+            client,
+            method,
+            mode,
+            protocol,
+        } = args;
+        Self {
+            host,
+            reason,
+            client,
+            method,
+            mode,
+            protocol,
+            timestamp: unix_timestamp(),
+        }
+    }
+}
+
+/// A [`NetworkPolicy`] with its domain patterns compiled, for callers that
+/// evaluate hosts without a running proxy.
+#[derive(Clone, Debug)]
+pub struct DomainPolicy {
+    policy: NetworkPolicy,
+    allow_set: GlobSet,
+    deny_set: GlobSet,
+}
+
+impl DomainPolicy {
+    pub fn new(policy: NetworkPolicy) -> Result<Self> {
+        let allow_set = compile_globset(&policy.allowed_domains)?;
+        let deny_set = compile_globset(&policy.denied_domains)?;
+        Ok(Self {
+            policy,
+            allow_set,
+            deny_set,
+        })
+    }
+
+    pub fn policy(&self) -> &NetworkPolicy {
+        &self.policy
+    }
+
+    pub async fn host_blocked(&self, host: &str, port: u16) -> HostBlockDecision {
+        host_blocked(&self.policy, &self.allow_set, &self.deny_set, host, port).await
+    }
+}
+
+/// The default policy has an empty allowlist, so it blocks every host.
+impl Default for DomainPolicy {
+    fn default() -> Self {
+        Self {
+            policy: NetworkPolicy::default(),
+            allow_set: GlobSet::empty(),
+            deny_set: GlobSet::empty(),
+        }
+    }
+}
+
+/// Decides whether `host` may be reached under `policy`, given its compiled
+/// allow and deny sets.
+pub async fn host_blocked(
+    policy: &NetworkPolicy,
+    allow_set: &GlobSet,
+    deny_set: &GlobSet,
+    host: &str,
+    port: u16,
+) -> HostBlockDecision {
+    let host = match Host::parse(host) {
+        Ok(host) => host,
+        Err(_) => return HostBlockDecision::Blocked(HostBlockReason::NotAllowed),
+    };
+    let host_str = host.as_str();
+
+    // Decision order matters:
+    //  1) explicit deny always wins
+    //  2) local/private networking is opt-in (defense-in-depth)
+    //  3) allowlist is enforced when configured
+    if deny_set.is_match(host_str) {
+        return HostBlockDecision::Blocked(HostBlockReason::Denied);
+    }
+
+    let is_allowlisted = allow_set.is_match(host_str);
+    if !policy.allow_local_binding {
+        // If the intent is "prevent access to local/internal networks", we must not rely solely
+        // on string checks like `localhost` / `127.0.0.1`. Attackers can use DNS rebinding or
+        // public suffix services that map hostnames onto private IPs.
+        //
+        // We therefore do a best-effort DNS + IP classification check before allowing the
+        // request. Explicit local/loopback literals are allowed only when explicitly
+        // allowlisted; hostnames that resolve to local/private IPs are blocked even if
+        // allowlisted.
+        let local_literal = {
+            let host_no_scope = host_str
+                .split_once('%')
+                .map(|(ip, _)| ip)
+                .unwrap_or(host_str);
+            if is_loopback_host(&host) {
+                true
+            } else if let Ok(ip) = host_no_scope.parse::<IpAddr>() {
+                is_non_public_ip(ip)
+            } else {
+                false
+            }
+        };
+
+        if local_literal {
+            if !is_explicit_local_allowlisted(&policy.allowed_domains, &host) {
+                return HostBlockDecision::Blocked(HostBlockReason::NotAllowedLocal);
+            }
+        } else if host_resolves_to_non_public_ip(host_str, port).await {
+            return HostBlockDecision::Blocked(HostBlockReason::NotAllowedLocal);
+        }
+    }
+
+    if policy.allowed_domains.is_empty() || !is_allowlisted {
+        HostBlockDecision::Blocked(HostBlockReason::NotAllowed)
+    } else {
+        HostBlockDecision::Allowed
+    }
+}
+
+async fn host_resolves_to_non_public_ip(host: &str, port: u16) -> bool {
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return is_non_public_ip(ip);
+    }
+
+    // If DNS lookup fails, default to "not local/private" rather than blocking. In practice, the
+    // subsequent connect attempt will fail anyway, and blocking on transient resolver issues would
+    // make the proxy fragile. The allowlist/denylist remains the primary control plane.
+    let addrs = match timeout(DNS_LOOKUP_TIMEOUT, lookup_host((host, port))).await {
+        Ok(Ok(addrs)) => addrs,
+        Ok(Err(_)) | Err(_) => return false,
+    };
+
+    for addr in addrs {
+        if is_non_public_ip(addr.ip()) {
+            return true;
+        }
+    }
+
+    false
+}
+
+fn is_explicit_local_allowlisted(allowed_domains: &[String], host: &Host) -> bool {
+    let normalized_host = host.as_str();
+    allowed_domains.iter().any(|pattern| {
+        let pattern = pattern.trim();
+        if pattern == "*" || pattern.starts_with("*.") || pattern.starts_with("**.") {
+            return false;
+        }
+        if pattern.contains('*') || pattern.contains('?') {
+            return false;
+        }
+        normalize_host(pattern) == normalized_host
+    })
+}
+
+fn unix_timestamp() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn domain_policy_applies_proxy_host_rules() {
+        let policy = DomainPolicy::new(NetworkPolicy {
+            allowed_domains: vec!["*".to_string(), "127.0.0.1".to_string()],
+            denied_domains: vec!["blocked.example".to_string()],
+            ..NetworkPolicy::default()
+        })
+        .unwrap();
+
+        assert_eq!(
+            policy.host_blocked("blocked.example", 443).await,
+            HostBlockDecision::Blocked(HostBlockReason::Denied)
+        );
+        assert_eq!(
+            policy.host_blocked("169.254.169.254", 80).await,
+            HostBlockDecision::Blocked(HostBlockReason::NotAllowedLocal)
+        );
+        assert_eq!(
+            policy.host_blocked("127.0.0.1", 80).await,
+            HostBlockDecision::Allowed
+        );
+        assert_eq!(
+            policy.host_blocked("8.8.8.8", 443).await,
+            HostBlockDecision::Allowed
+        );
+    }
+
+    #[tokio::test]
+    async fn default_domain_policy_blocks_everything() {
+        assert_eq!(
+            DomainPolicy::default().host_blocked("8.8.8.8", 443).await,
+            HostBlockDecision::Blocked(HostBlockReason::NotAllowed)
+        );
+    }
+}
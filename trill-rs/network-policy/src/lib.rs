@@ -0,0 +1,17 @@
+//! Host allow/deny policy shared by the network proxy and in-process HTTP
+//! clients such as the `web_search` page fetcher.
+
+mod config;
+mod decision;
+pub mod policy;
+pub mod reasons;
+pub mod sandbox;
+
+pub use config::NetworkMode;
+pub use config::NetworkPolicy;
+pub use decision::BlockedRequest;
+pub use decision::BlockedRequestArgs;
+pub use decision::DomainPolicy;
+pub use decision::HostBlockDecision;
+pub use decision::HostBlockReason;
+pub use decision::host_blocked;
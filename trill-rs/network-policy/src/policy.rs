@@ -11,6 +11,7 @@ use std::net::IpAddr;
 use std::net::Ipv4Addr;
 use std::net::Ipv6Addr;
 use url::Host as UrlHost;
+use url::Url;
 
 /// A normalized host string for policy evaluation.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -144,7 +145,7 @@ fn normalize_pattern(pattern: &str) -> String {
     }
 }
 
-pub(crate) fn compile_globset(patterns: &[String]) -> Result<GlobSet> {
+pub fn compile_globset(patterns: &[String]) -> Result<GlobSet> {
     let mut builder = GlobSetBuilder::new();
     let mut seen = HashSet::new();
     for pattern in patterns {
@@ -168,8 +169,35 @@ pub(crate) fn compile_globset(patterns: &[String]) -> Result<GlobSet> {
     Ok(builder.build()?)
 }
 
+/// Compiles `denied_paths` entries. Each entry is `host/path`; the host part is lowercased like
+/// domain patterns, the path is case-sensitive, and `*` may span `/`.
+pub fn compile_path_globset(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = pattern.trim();
+        let (host, path) = pattern.split_at(pattern.find('/').unwrap_or(pattern.len()));
+        let candidate = format!("{}{path}", host.to_ascii_lowercase());
+        let glob = GlobBuilder::new(&candidate)
+            .build()
+            .with_context(|| format!("invalid path pattern: {pattern}"))?;
+        builder.add(glob);
+    }
+    Ok(builder.build()?)
+}
+
+/// The string `denied_paths` globs are matched against: the normalized host followed by the
+/// request path with dot segments resolved and the query dropped.
+pub fn path_candidate(host: &str, path: &str) -> String {
+    let host = normalize_host(host);
+    let path = Url::parse("http://localhost/")
+        .and_then(|base| base.join(path))
+        .map(|url| url.path().to_string())
+        .unwrap_or_else(|_| path.to_string());
+    format!("{host}{path}")
+}
+
 #[derive(Debug, Clone)]
-pub(crate) enum DomainPattern {
+pub enum DomainPattern {
     Any,
     ApexAndSubdomains(String),
     SubdomainsOnly(String),
@@ -181,7 +209,7 @@ impl DomainPattern {
     ///
     /// Validation of glob syntax happens when building the globset; here we only
     /// decode the wildcard prefixes to keep constraint checks lightweight.
-    pub(crate) fn parse(input: &str) -> Self {
+    pub fn parse(input: &str) -> Self {
         let input = input.trim();
         if input.is_empty() {
             return Self::Exact(String::new());
@@ -198,7 +226,7 @@ impl DomainPattern {
     }
 
     /// Parse a policy pattern for constraint comparisons, validating domain parts with `url`.
-    pub(crate) fn parse_for_constraints(input: &str) -> Self {
+    pub fn parse_for_constraints(input: &str) -> Self {
         let input = input.trim();
         if input.is_empty() {
             return Self::Exact(String::new());
@@ -223,7 +251,7 @@ impl DomainPattern {
         build(domain.to_string())
     }
 
-    pub(crate) fn allows(&self, candidate: &DomainPattern) -> bool {
+    pub fn allows(&self, candidate: &DomainPattern) -> bool {
         match self {
             DomainPattern::Any => true,
             DomainPattern::Exact(domain) => match candidate {
@@ -362,6 +390,26 @@ mod tests {
         assert_eq!(true, set.is_match("::1"));
     }
 
+    #[test]
+    fn path_globset_matches_normalized_paths() {
+        let set = compile_path_globset(&[
+            "GitHub.com/*/settings*".to_string(),
+            "api.example.com/v1/admin/**".to_string(),
+        ])
+        .unwrap();
+
+        assert!(set.is_match(path_candidate("github.com", "/org/repo/settings")));
+        assert!(set.is_match(path_candidate(
+            "GITHUB.COM.",
+            "/org/repo/settings/hooks?x=1"
+        )));
+        assert!(set.is_match(path_candidate("github.com", "/org/repo/x/../settings")));
+        assert!(set.is_match(path_candidate("api.example.com:443", "/v1/admin/users")));
+        assert!(!set.is_match(path_candidate("github.com", "/org/repo/Settings")));
+        assert!(!set.is_match(path_candidate("github.com", "/org/repo/issues")));
+        assert!(!set.is_match(path_candidate("example.com", "/v1/admin/users")));
+    }
+
     #[test]
     fn is_loopback_host_handles_localhost_variants() {
         assert!(is_loopback_host(&Host::parse("localhost").unwrap()));
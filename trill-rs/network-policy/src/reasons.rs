@@ -0,0 +1,23 @@
+pub const REASON_DENIED: &str = "denied";
+pub const REASON_METHOD_NOT_ALLOWED: &str = "method_not_allowed";
+pub const REASON_NOT_ALLOWED: &str = "not_allowed";
+pub const REASON_NOT_ALLOWED_LOCAL: &str = "not_allowed_local";
+pub const REASON_PATH_NOT_ALLOWED: &str = "path_not_allowed";
+pub const REASON_POLICY_DENIED: &str = "policy_denied";
+pub const REASON_PROXY_DISABLED: &str = "proxy_disabled";
+
+/// User-facing explanation for a blocked request.
+pub fn blocked_message(reason: &str) -> &'static str {
+    match reason {
+        REASON_NOT_ALLOWED => "Codex blocked this request: domain not in allowlist.",
+        REASON_NOT_ALLOWED_LOCAL => {
+            "Codex blocked this request: local/private addresses not allowed."
+        }
+        REASON_DENIED => "Codex blocked this request: domain denied by policy.",
+        REASON_METHOD_NOT_ALLOWED => {
+            "Codex blocked this request: method not allowed in limited mode."
+        }
+        REASON_PATH_NOT_ALLOWED => "Codex blocked this request: path denied by policy.",
+        _ => "Codex blocked this request by network policy.",
+    }
+}
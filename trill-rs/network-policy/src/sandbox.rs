@@ -0,0 +1,72 @@
+//! Line-delimited JSON protocol between a session and a `trill-network-proxy --sandbox` child that
+//! serves a single sandboxed command.
+
+use serde::Deserialize;
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+/// Written by the proxy to stdout, one JSON object per line.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SandboxProxyEvent {
+    /// The HTTP proxy accepts connections on `http_addr`. `ca_cert` is set when HTTPS is
+    /// intercepted and names the CA certificate clients must trust.
+    Ready {
+        http_addr: SocketAddr,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ca_cert: Option<PathBuf>,
+    },
+    /// A request to a host outside the allowlist is waiting for a [`SandboxProxyDecision`].
+    Decide { id: u64, host: String, port: u16 },
+}
+
+/// Written by the session to the proxy's stdin in reply to [`SandboxProxyEvent::Decide`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SandboxProxyDecision {
+    pub id: u64,
+    pub allow: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn events_use_tagged_json_lines() {
+        let event = SandboxProxyEvent::Decide {
+            id: 3,
+            host: "crates.io".to_string(),
+            port: 443,
+        };
+        let line = serde_json::to_string(&event).unwrap();
+        assert_eq!(
+            line,
+            r#"{"type":"decide","id":3,"host":"crates.io","port":443}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<SandboxProxyEvent>(&line).unwrap(),
+            event
+        );
+
+        let ready: SandboxProxyEvent =
+            serde_json::from_str(r#"{"type":"ready","http_addr":"127.0.0.1:4100"}"#).unwrap();
+        assert_eq!(
+            ready,
+            SandboxProxyEvent::Ready {
+                http_addr: "127.0.0.1:4100".parse().unwrap(),
+                ca_cert: None,
+            }
+        );
+
+        let ready = SandboxProxyEvent::Ready {
+            http_addr: "127.0.0.1:4100".parse().unwrap(),
+            ca_cert: Some(PathBuf::from("/home/dev/.trill/network-proxy/mitm-ca.pem")),
+        };
+        assert_eq!(
+            serde_json::to_string(&ready).unwrap(),
+            r#"{"type":"ready","http_addr":"127.0.0.1:4100","ca_cert":"/home/dev/.trill/network-proxy/mitm-ca.pem"}"#
+        );
+    }
+}
@@ -0,0 +1,334 @@
+use anyhow::Context;
+use anyhow::Result;
+use rama_net::tls::ApplicationProtocol;
+use rama_net::tls::DataEncoding;
+use rama_net::tls::server::ServerAuth;
+use rama_net::tls::server::ServerAuthData;
+use rama_net::tls::server::ServerConfig;
+use rama_tls_boring::core::asn1::Asn1Time;
+use rama_tls_boring::core::bn::BigNum;
+use rama_tls_boring::core::bn::MsbOption;
+use rama_tls_boring::core::ec::EcGroup;
+use rama_tls_boring::core::ec::EcKey;
+use rama_tls_boring::core::hash::MessageDigest;
+use rama_tls_boring::core::nid::Nid;
+use rama_tls_boring::core::pkey::PKey;
+use rama_tls_boring::core::pkey::Private;
+use rama_tls_boring::core::x509::X509;
+use rama_tls_boring::core::x509::X509Builder;
+use rama_tls_boring::core::x509::X509NameBuilder;
+use rama_tls_boring::core::x509::extension::AuthorityKeyIdentifier;
+use rama_tls_boring::core::x509::extension::BasicConstraints;
+use rama_tls_boring::core::x509::extension::ExtendedKeyUsage;
+use rama_tls_boring::core::x509::extension::KeyUsage;
+use rama_tls_boring::core::x509::extension::SubjectAlternativeName;
+use rama_tls_boring::core::x509::extension::SubjectKeyIdentifier;
+use rama_tls_boring::server::TlsAcceptorData;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::time::Duration;
+use trill_core::config::MITM_CA_KEY_FILE;
+
+const CA_CERT_FILE: &str = "mitm-ca.pem";
+const CA_KEY_FILE: &str = MITM_CA_KEY_FILE;
+const CA_COMMON_NAME: &str = "Trill network proxy CA";
+const CA_VALIDITY_DAYS: u32 = 365;
+const LEAF_VALIDITY_DAYS: u32 = 30;
+const MAX_CACHED_ACCEPTORS: usize = 1024;
+const CA_WAIT_ATTEMPTS: usize = 50;
+const CA_WAIT_INTERVAL: Duration = Duration::from_millis(20);
+
+/// The local CA used to terminate CONNECT tunnels when `network_proxy.mitm` is set.
+///
+/// The CA is created on first use and kept on disk so every proxy (and every client told to trust
+/// it) agrees on one certificate. It is replaced once it would expire before a leaf it issues.
+/// Leaf certificates are issued per host and cached in memory. Sandboxed commands cannot read the
+/// key, see [`MITM_CA_KEY_FILE`].
+pub(crate) struct MitmCa {
+    cert: X509,
+    key: PKey<Private>,
+    cert_path: PathBuf,
+    acceptors: Mutex<HashMap<String, TlsAcceptorData>>,
+}
+
+impl MitmCa {
+    /// Loads the CA from `dir`, creating it if it does not exist yet.
+    pub(crate) fn load_or_create(dir: &Path) -> Result<Self> {
+        let cert_path = dir.join(CA_CERT_FILE);
+        let key_path = dir.join(CA_KEY_FILE);
+        if !key_path.exists() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("create MITM CA directory {}", dir.display()))?;
+            create_ca_files(dir, &cert_path, &key_path)?;
+        }
+        let (mut cert, mut key) = load_ca_files(&cert_path, &key_path)?;
+        if needs_renewal(&cert)? {
+            // Clients are pointed at the certificate file, so they pick up the new CA.
+            remove_if_exists(&cert_path)?;
+            remove_if_exists(&key_path)?;
+            create_ca_files(dir, &cert_path, &key_path)?;
+            (cert, key) = load_ca_files(&cert_path, &key_path)?;
+        }
+        Ok(Self {
+            cert,
+            key,
+            cert_path,
+            acceptors: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// PEM file clients should trust.
+    pub(crate) fn cert_path(&self) -> &Path {
+        &self.cert_path
+    }
+
+    /// TLS acceptor presenting a certificate for `host`, issued on first use.
+    pub(crate) fn acceptor_for_host(&self, host: &str) -> Result<TlsAcceptorData> {
+        let mut acceptors = self
+            .acceptors
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(acceptor) = acceptors.get(host) {
+            return Ok(acceptor.clone());
+        }
+        let (leaf, leaf_key) = self.issue_leaf(host)?;
+        let config = ServerConfig {
+            application_layer_protocol_negotiation: Some(vec![ApplicationProtocol::HTTP_11]),
+            ..ServerConfig::new(ServerAuth::Single(ServerAuthData {
+                private_key: DataEncoding::Der(leaf_key.private_key_to_der()?),
+                cert_chain: DataEncoding::DerStack(vec![leaf.to_der()?, self.cert.to_der()?]),
+                ocsp: None,
+            }))
+        };
+        let acceptor = TlsAcceptorData::try_from(config)
+            .map_err(|err| anyhow::anyhow!("build TLS acceptor for {host}: {err}"))?;
+        if acceptors.len() >= MAX_CACHED_ACCEPTORS {
+            acceptors.clear();
+        }
+        acceptors.insert(host.to_string(), acceptor.clone());
+        Ok(acceptor)
+    }
+
+    pub(crate) fn issue_leaf(&self, host: &str) -> Result<(X509, PKey<Private>)> {
+        let key = generate_key()?;
+        let mut builder = cert_builder(host, &key, LEAF_VALIDITY_DAYS)?;
+        builder.set_issuer_name(self.cert.subject_name())?;
+
+        let mut san = SubjectAlternativeName::new();
+        match host.parse::<IpAddr>() {
+            Ok(_) => san.ip(host),
+            Err(_) => san.dns(host),
+        };
+        let san = san.build(&builder.x509v3_context(Some(&self.cert), None))?;
+        let aki = AuthorityKeyIdentifier::new()
+            .keyid(false)
+            .build(&builder.x509v3_context(Some(&self.cert), None))?;
+        builder.append_extension(BasicConstraints::new().build()?)?;
+        builder.append_extension(
+            KeyUsage::new()
+                .critical()
+                .digital_signature()
+                .key_encipherment()
+                .build()?,
+        )?;
+        builder.append_extension(ExtendedKeyUsage::new().server_auth().build()?)?;
+        builder.append_extension(san)?;
+        builder.append_extension(aki)?;
+        builder.sign(&self.key, MessageDigest::sha256())?;
+        Ok((builder.build(), key))
+    }
+}
+
+/// Whether `cert` expires before a leaf issued today would, or outlives the current CA lifetime
+/// (CAs created before it was shortened).
+fn needs_renewal(cert: &X509) -> Result<bool> {
+    let not_after = cert.not_after();
+    let leaf_expiry = Asn1Time::days_from_now(LEAF_VALIDITY_DAYS)?;
+    let latest_expiry = Asn1Time::days_from_now(CA_VALIDITY_DAYS + 1)?;
+    Ok(not_after.compare(&leaf_expiry)? == Ordering::Less
+        || not_after.compare(&latest_expiry)? == Ordering::Greater)
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err).with_context(|| format!("remove {}", path.display())),
+    }
+}
+
+fn generate_key() -> Result<PKey<Private>> {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+    Ok(PKey::from_ec_key(EcKey::generate(&group)?)?)
+}
+
+fn cert_builder(common_name: &str, key: &PKey<Private>, validity_days: u32) -> Result<X509Builder> {
+    let mut name = X509NameBuilder::new()?;
+    name.append_entry_by_nid(Nid::COMMONNAME, common_name)?;
+    let name = name.build();
+
+    let mut serial = BigNum::new()?;
+    serial.rand(127, MsbOption::MAYBE_ZERO, false)?;
+
+    let mut builder = X509Builder::new()?;
+    builder.set_version(2)?;
+    builder.set_serial_number(&serial.to_asn1_integer()?)?;
+    builder.set_subject_name(&name)?;
+    // Self-signed until the caller says otherwise.
+    builder.set_issuer_name(&name)?;
+    builder.set_pubkey(key)?;
+    builder.set_not_before(&Asn1Time::days_from_now(0)?)?;
+    builder.set_not_after(&Asn1Time::days_from_now(validity_days)?)?;
+    Ok(builder)
+}
+
+fn generate_ca(validity_days: u32) -> Result<(X509, PKey<Private>)> {
+    let key = generate_key()?;
+    let mut builder = cert_builder(CA_COMMON_NAME, &key, validity_days)?;
+    let ski = SubjectKeyIdentifier::new().build(&builder.x509v3_context(None, None))?;
+    builder.append_extension(BasicConstraints::new().critical().ca().build()?)?;
+    builder.append_extension(
+        KeyUsage::new()
+            .critical()
+            .key_cert_sign()
+            .crl_sign()
+            .build()?,
+    )?;
+    builder.append_extension(ski)?;
+    builder.sign(&key, MessageDigest::sha256())?;
+    Ok((builder.build(), key))
+}
+
+/// Writes a fresh CA. Proxies may start concurrently, so the key is published with a hard link
+/// (which fails if another process won) and the certificate is renamed into place afterwards.
+fn create_ca_files(dir: &Path, cert_path: &Path, key_path: &Path) -> Result<()> {
+    let (cert, key) = generate_ca(CA_VALIDITY_DAYS)?;
+    let suffix = format!("tmp-{}", std::process::id());
+    let tmp_cert = dir.join(format!("{CA_CERT_FILE}.{suffix}"));
+    let tmp_key = dir.join(format!("{CA_KEY_FILE}.{suffix}"));
+    write_private(&tmp_key, &key.private_key_to_pem_pkcs8()?)?;
+    fs::write(&tmp_cert, cert.to_pem()?)?;
+
+    let published = fs::hard_link(&tmp_key, key_path);
+    let _ = fs::remove_file(&tmp_key);
+    match published {
+        Ok(()) => fs::rename(&tmp_cert, cert_path)
+            .with_context(|| format!("write MITM CA certificate {}", cert_path.display())),
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+            let _ = fs::remove_file(&tmp_cert);
+            Ok(())
+        }
+        Err(err) => {
+            let _ = fs::remove_file(&tmp_cert);
+            Err(err).with_context(|| format!("write MITM CA key {}", key_path.display()))
+        }
+    }
+}
+
+fn load_ca_files(cert_path: &Path, key_path: &Path) -> Result<(X509, PKey<Private>)> {
+    let key =
+        fs::read(key_path).with_context(|| format!("read MITM CA key {}", key_path.display()))?;
+    let key = PKey::private_key_from_pem(&key)
+        .with_context(|| format!("parse MITM CA key {}", key_path.display()))?;
+
+    // Another proxy may have published the key but not yet the certificate.
+    let mut attempts = 0;
+    let cert = loop {
+        match fs::read(cert_path) {
+            Ok(cert) => break cert,
+            Err(err) if err.kind() == io::ErrorKind::NotFound && attempts < CA_WAIT_ATTEMPTS => {
+                attempts += 1;
+                std::thread::sleep(CA_WAIT_INTERVAL);
+            }
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("read MITM CA certificate {}", cert_path.display()));
+            }
+        }
+    };
+    let cert = X509::from_pem(&cert)
+        .with_context(|| format!("parse MITM CA certificate {}", cert_path.display()))?;
+    Ok((cert, key))
+}
+
+fn write_private(path: &Path, contents: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn ca_is_created_once_and_reused() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let first = MitmCa::load_or_create(dir.path()).unwrap();
+        let second = MitmCa::load_or_create(dir.path()).unwrap();
+
+        assert_eq!(first.cert_path(), dir.path().join(CA_CERT_FILE));
+        assert_eq!(first.cert.to_der().unwrap(), second.cert.to_der().unwrap());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dir.path().join(CA_KEY_FILE))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn expiring_and_long_lived_cas_are_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        for validity_days in [LEAF_VALIDITY_DAYS - 1, 3650] {
+            let (old_cert, old_key) = generate_ca(validity_days).unwrap();
+            fs::write(dir.path().join(CA_CERT_FILE), old_cert.to_pem().unwrap()).unwrap();
+            write_private(
+                &dir.path().join(CA_KEY_FILE),
+                &old_key.private_key_to_pem_pkcs8().unwrap(),
+            )
+            .unwrap();
+
+            let ca = MitmCa::load_or_create(dir.path()).unwrap();
+
+            assert_ne!(ca.cert.to_der().unwrap(), old_cert.to_der().unwrap());
+            assert!(!needs_renewal(&ca.cert).unwrap());
+            let on_disk = X509::from_pem(&fs::read(ca.cert_path()).unwrap()).unwrap();
+            assert_eq!(on_disk.to_der().unwrap(), ca.cert.to_der().unwrap());
+        }
+    }
+
+    #[test]
+    fn leaf_certificates_are_signed_by_the_ca() {
+        let dir = tempfile::tempdir().unwrap();
+        let ca = MitmCa::load_or_create(dir.path()).unwrap();
+
+        let (leaf, _) = ca.issue_leaf("example.com").unwrap();
+        let ca_key = ca.cert.public_key().unwrap();
+
+        assert!(leaf.verify(&ca_key).unwrap());
+        let names = leaf.subject_alt_names().unwrap();
+        let dns_names: Vec<_> = names.iter().filter_map(|name| name.dnsname()).collect();
+        assert_eq!(dns_names, vec!["example.com"]);
+        assert!(ca.acceptor_for_host("127.0.0.1").is_ok());
+    }
+}
@@ -8,6 +8,9 @@ use std::net::SocketAddr;
 use tracing::warn;
 use url::Url;
 
+pub use trill_network_policy::NetworkMode;
+pub use trill_network_policy::NetworkPolicy;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct NetworkProxyConfig {
     #[serde(default)]
@@ -36,6 +39,14 @@ pub struct NetworkProxySettings {
     pub dangerously_allow_non_loopback_admin: bool,
     #[serde(default)]
     pub mode: NetworkMode,
+    /// Terminate HTTPS CONNECT tunnels with certificates issued by a local CA so method and path
+    /// policy apply to the requests inside. Read at startup.
+    #[serde(default)]
+    pub mitm: bool,
+    /// `host/path` globs (for example `github.com/*/settings*`) refused for plain HTTP requests
+    /// and, with `mitm`, for HTTPS requests.
+    #[serde(default)]
+    pub denied_paths: Vec<String>,
     #[serde(default)]
     pub policy: NetworkPolicy,
 }
@@ -53,44 +64,13 @@ impl Default for NetworkProxySettings {
             dangerously_allow_non_loopback_proxy: false,
             dangerously_allow_non_loopback_admin: false,
             mode: NetworkMode::default(),
+            mitm: false,
+            denied_paths: Vec::new(),
             policy: NetworkPolicy::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct NetworkPolicy {
-    #[serde(default)]
-    pub allowed_domains: Vec<String>,
-    #[serde(default)]
-    pub denied_domains: Vec<String>,
-    #[serde(default)]
-    pub allow_unix_sockets: Vec<String>,
-    #[serde(default)]
-    pub allow_local_binding: bool,
-}
-
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
-#[serde(rename_all = "lowercase")]
-pub enum NetworkMode {
-    /// Limited (read-only) access: only GET/HEAD/OPTIONS are allowed for HTTP. HTTPS CONNECT is
-    /// blocked unless MITM is enabled so the proxy can enforce method policy on inner requests.
-    Limited,
-    /// Full network access: all HTTP methods are allowed, and HTTPS CONNECTs are tunneled without
-    /// MITM interception.
-    #[default]
-    Full,
-}
-
-impl NetworkMode {
-    pub fn allows_method(self, method: &str) -> bool {
-        match self {
-            Self::Full => true,
-            Self::Limited => matches!(method, "GET" | "HEAD" | "OPTIONS"),
-        }
-    }
-}
-
 fn default_proxy_url() -> String {
     "http://127.0.0.1:3128".to_string()
 }
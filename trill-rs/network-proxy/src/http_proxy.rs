@@ -1,4 +1,6 @@
+use crate::certs::MitmCa;
 use crate::config::NetworkMode;
+use crate::mitm;
 use crate::network_policy::NetworkDecision;
 use crate::network_policy::NetworkPolicyDecider;
 use crate::network_policy::NetworkPolicyRequest;
@@ -8,6 +10,7 @@ use crate::network_policy::evaluate_host_policy;
 use crate::policy::normalize_host;
 use crate::reasons::REASON_METHOD_NOT_ALLOWED;
 use crate::reasons::REASON_NOT_ALLOWED;
+use crate::reasons::REASON_PATH_NOT_ALLOWED;
 use crate::reasons::REASON_PROXY_DISABLED;
 use crate::responses::blocked_header_value;
 use crate::responses::json_response;
@@ -67,6 +70,7 @@ pub async fn run_http_proxy(
     state: Arc<NetworkProxyState>,
     addr: SocketAddr,
     policy_decider: Option<Arc<dyn NetworkPolicyDecider>>,
+    mitm: Option<Arc<MitmCa>>,
 ) -> Result<()> {
     let listener = TcpListener::build()
         .bind(addr)
@@ -85,7 +89,7 @@ pub async fn run_http_proxy(
                 MethodMatcher::CONNECT,
                 service_fn({
                     let policy_decider = policy_decider.clone();
-                    move |req| http_connect_accept(policy_decider.clone(), req)
+                    move |req| http_connect_accept(policy_decider.clone(), mitm.clone(), req)
                 }),
                 service_fn(http_connect_proxy),
             ),
@@ -108,6 +112,7 @@ pub async fn run_http_proxy(
 
 async fn http_connect_accept(
     policy_decider: Option<Arc<dyn NetworkPolicyDecider>>,
+    mitm: Option<Arc<MitmCa>>,
     mut req: Request,
 ) -> Result<(Response, Request), Response> {
     let app_state = req
@@ -189,7 +194,8 @@ async fn http_connect_accept(
         .await
         .map_err(|err| internal_error("failed to read network mode", err))?;
 
-    if mode == NetworkMode::Limited {
+    // Without interception the tunnel is opaque, so limited mode can't vouch for what is sent.
+    if mode == NetworkMode::Limited && mitm.is_none() {
         let _ = app_state
             .record_blocked(BlockedRequest::new(BlockedRequestArgs {
                 host: host.clone(),
@@ -207,6 +213,9 @@ async fn http_connect_accept(
 
     req.extensions_mut().insert(ProxyTarget(authority));
     req.extensions_mut().insert(mode);
+    if let Some(mitm) = mitm {
+        req.extensions_mut().insert(mitm);
+    }
 
     Ok((
         Response::builder()
@@ -223,6 +232,11 @@ async fn http_connect_proxy(upgraded: Upgraded) -> Result<(), Infallible> {
         return Ok(());
     }
 
+    if let Some(mitm) = upgraded.extensions().get::<Arc<MitmCa>>().cloned() {
+        mitm::mitm_tunnel(upgraded, mitm).await;
+        return Ok(());
+    }
+
     let allow_upstream_proxy = match upgraded
         .extensions()
         .get::<Arc<NetworkProxyState>>()
@@ -479,6 +493,31 @@ async fn http_plain_proxy(
         return Ok(json_blocked(&host, REASON_METHOD_NOT_ALLOWED));
     }
 
+    let path_allowed = match app_state
+        .path_allowed(&host, req.uri().path())
+        .await
+        .map_err(|err| internal_error("failed to evaluate path policy", err))
+    {
+        Ok(allowed) => allowed,
+        Err(resp) => return Ok(resp),
+    };
+    if !path_allowed {
+        let _ = app_state
+            .record_blocked(BlockedRequest::new(BlockedRequestArgs {
+                host: host.clone(),
+                reason: REASON_PATH_NOT_ALLOWED.to_string(),
+                client: client.clone(),
+                method: Some(req.method().as_str().to_string()),
+                mode: None,
+                protocol: "http".to_string(),
+            }))
+            .await;
+        let client = client.as_deref().unwrap_or_default();
+        let path = req.uri().path();
+        warn!("request blocked by path policy (client={client}, host={host}, path={path})");
+        return Ok(json_blocked(&host, REASON_PATH_NOT_ALLOWED));
+    }
+
     let client = client.as_deref().unwrap_or_default();
     let method = req.method();
     info!("request allowed (client={client}, host={host}, method={method})");
@@ -533,14 +572,14 @@ async fn proxy_via_unix_socket(req: Request, socket_path: &str) -> Result<Respon
     }
 }
 
-fn client_addr<T: ExtensionsRef>(input: &T) -> Option<String> {
+pub(crate) fn client_addr<T: ExtensionsRef>(input: &T) -> Option<String> {
     input
         .extensions()
         .get::<SocketInfo>()
         .map(|info| info.peer_addr().to_string())
 }
 
-fn json_blocked(host: &str, reason: &str) -> Response {
+pub(crate) fn json_blocked(host: &str, reason: &str) -> Response {
     let response = BlockedResponse {
         status: "blocked",
         host,
@@ -584,7 +623,7 @@ fn internal_error(context: &str, err: impl std::fmt::Display) -> Response {
     text_response(StatusCode::INTERNAL_SERVER_ERROR, "error")
 }
 
-fn text_response(status: StatusCode, body: &str) -> Response {
+pub(crate) fn text_response(status: StatusCode, body: &str) -> Response {
     Response::builder()
         .status(status)
         .header("content-type", "text/plain")
@@ -605,12 +644,55 @@ mod tests {
 
     use crate::config::NetworkMode;
     use crate::config::NetworkPolicy;
+    use crate::config::NetworkProxySettings;
     use crate::runtime::network_proxy_state_for_policy;
+    use crate::runtime::network_proxy_state_for_settings;
     use pretty_assertions::assert_eq;
     use rama_http::Method;
     use rama_http::Request;
+    use rama_tls_boring::core::ssl::SslConnector;
+    use rama_tls_boring::core::ssl::SslMethod;
+    use std::io::Read;
+    use std::io::Write;
     use std::sync::Arc;
 
+    fn limited_settings(denied_paths: &[&str]) -> NetworkProxySettings {
+        NetworkProxySettings {
+            enabled: true,
+            mode: NetworkMode::Limited,
+            denied_paths: denied_paths.iter().map(ToString::to_string).collect(),
+            policy: NetworkPolicy {
+                allowed_domains: vec!["example.com".to_string()],
+                ..Default::default()
+            },
+            ..NetworkProxySettings::default()
+        }
+    }
+
+    /// Opens a CONNECT tunnel through the proxy, completes a TLS handshake trusting only
+    /// `ca_cert`, sends `request` and returns the raw response.
+    fn request_through_mitm(proxy: SocketAddr, ca_cert: &std::path::Path, request: &str) -> String {
+        let mut stream = std::net::TcpStream::connect(proxy).unwrap();
+        stream
+            .write_all(b"CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n")
+            .unwrap();
+        let mut head = Vec::new();
+        let mut byte = [0u8; 1];
+        while !head.ends_with(b"\r\n\r\n") {
+            stream.read_exact(&mut byte).unwrap();
+            head.push(byte[0]);
+        }
+        assert!(String::from_utf8_lossy(&head).starts_with("HTTP/1.1 200"));
+
+        let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+        connector.set_ca_file(ca_cert).unwrap();
+        let mut tls = connector.build().connect("example.com", stream).unwrap();
+        tls.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        tls.read_to_string(&mut response).unwrap();
+        response
+    }
+
     #[tokio::test]
     async fn http_connect_accept_blocks_in_limited_mode() {
         let policy = NetworkPolicy {
@@ -628,11 +710,91 @@ mod tests {
             .unwrap();
         req.extensions_mut().insert(state);
 
-        let response = http_connect_accept(None, req).await.unwrap_err();
+        let response = http_connect_accept(None, None, req).await.unwrap_err();
         assert_eq!(response.status(), StatusCode::FORBIDDEN);
         assert_eq!(
             response.headers().get("x-proxy-error").unwrap(),
             "blocked-by-method-policy"
         );
     }
+
+    #[tokio::test]
+    async fn http_connect_accept_allows_limited_mode_with_mitm() {
+        let dir = tempfile::tempdir().unwrap();
+        let mitm = Arc::new(MitmCa::load_or_create(dir.path()).unwrap());
+        let state = Arc::new(network_proxy_state_for_settings(limited_settings(&[])));
+
+        let mut req = Request::builder()
+            .method(Method::CONNECT)
+            .uri("https://example.com:443")
+            .header("host", "example.com:443")
+            .body(Body::empty())
+            .unwrap();
+        req.extensions_mut().insert(state);
+
+        let (response, req) = http_connect_accept(None, Some(mitm), req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(req.extensions().get::<Arc<MitmCa>>().is_some());
+    }
+
+    #[tokio::test]
+    async fn http_plain_proxy_blocks_denied_paths() {
+        let state = Arc::new(network_proxy_state_for_settings(limited_settings(&[
+            "example.com/private/**",
+        ])));
+
+        let mut req = Request::builder()
+            .method(Method::GET)
+            .uri("http://example.com/private/keys")
+            .header("host", "example.com")
+            .body(Body::empty())
+            .unwrap();
+        req.extensions_mut().insert(state);
+
+        let response = http_plain_proxy(None, req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.headers().get("x-proxy-error").unwrap(),
+            "blocked-by-path-policy"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn mitm_applies_method_and_path_policy_inside_tls() {
+        let dir = tempfile::tempdir().unwrap();
+        let mitm = Arc::new(MitmCa::load_or_create(dir.path()).unwrap());
+        let ca_cert = mitm.cert_path().to_path_buf();
+        let state = Arc::new(network_proxy_state_for_settings(limited_settings(&[
+            "example.com/private/**",
+        ])));
+        let addr = std::net::TcpListener::bind(("127.0.0.1", 0))
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        tokio::spawn(run_http_proxy(state, addr, None, Some(mitm)));
+        while tokio::net::TcpStream::connect(addr).await.is_err() {
+            tokio::task::yield_now().await;
+        }
+
+        let (post, private) = tokio::task::spawn_blocking(move || {
+            let post = request_through_mitm(
+                addr,
+                &ca_cert,
+                "POST /upload HTTP/1.1\r\nHost: example.com\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            );
+            let private = request_through_mitm(
+                addr,
+                &ca_cert,
+                "GET /private/keys HTTP/1.1\r\nHost: example.com\r\nConnection: close\r\n\r\n",
+            );
+            (post, private)
+        })
+        .await
+        .unwrap();
+
+        assert!(post.starts_with("HTTP/1.1 403"), "{post}");
+        assert!(post.contains("blocked-by-method-policy"), "{post}");
+        assert!(private.starts_with("HTTP/1.1 403"), "{private}");
+        assert!(private.contains("blocked-by-path-policy"), "{private}");
+    }
 }
@@ -1,19 +1,22 @@
 #![deny(clippy::print_stdout, clippy::print_stderr)]
 
 mod admin;
+mod certs;
 mod config;
 mod http_proxy;
+mod mitm;
 mod network_policy;
-mod policy;
 mod proxy;
-mod reasons;
 mod responses;
 mod runtime;
+mod sandbox;
 mod socks5;
 mod state;
 mod upstream;
 
 use anyhow::Result;
+use trill_network_policy::policy;
+use trill_network_policy::reasons;
 pub use network_policy::NetworkDecision;
 pub use network_policy::NetworkPolicyDecider;
 pub use network_policy::NetworkPolicyRequest;
@@ -23,9 +26,12 @@ pub use proxy::Args;
 pub use proxy::NetworkProxy;
 pub use proxy::NetworkProxyBuilder;
 pub use proxy::NetworkProxyHandle;
+pub use sandbox::run_sandbox;
 
 pub async fn run_main(args: Args) -> Result<()> {
-    let _ = args;
+    if args.sandbox {
+        return run_sandbox().await;
+    }
     let proxy = NetworkProxy::builder().build().await?;
     proxy.run().await?.wait().await
 }
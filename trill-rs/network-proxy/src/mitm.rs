@@ -0,0 +1,196 @@
+use crate::certs::MitmCa;
+use crate::http_proxy::client_addr;
+use crate::http_proxy::json_blocked;
+use crate::http_proxy::text_response;
+use crate::policy::normalize_host;
+use crate::reasons::REASON_METHOD_NOT_ALLOWED;
+use crate::reasons::REASON_PATH_NOT_ALLOWED;
+use crate::state::BlockedRequest;
+use crate::state::BlockedRequestArgs;
+use crate::state::NetworkProxyState;
+use crate::upstream::UpstreamClient;
+use rama_core::Layer;
+use rama_core::Service;
+use rama_core::extensions::ExtensionsRef;
+use rama_core::rt::Executor;
+use rama_core::service::service_fn;
+use rama_http::Request;
+use rama_http::Response;
+use rama_http::StatusCode;
+use rama_http::header::HOST;
+use rama_http::layer::remove_header::RemoveRequestHeaderLayer;
+use rama_http::layer::remove_header::RemoveResponseHeaderLayer;
+use rama_http_backend::server::HttpServer;
+use rama_http_backend::server::layer::upgrade::Upgraded;
+use rama_net::address::Authority;
+use rama_net::proxy::ProxyTarget;
+use rama_tls_boring::server::TlsAcceptorLayer;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tracing::info;
+use tracing::warn;
+
+/// Terminates an accepted CONNECT tunnel with a certificate for its target and proxies the
+/// requests inside it, applying the method and path policy that plain HTTP requests get.
+///
+/// Clients must trust the CA from [`MitmCa::cert_path`]; tunnels that do not carry TLS fail.
+pub(crate) async fn mitm_tunnel(upgraded: Upgraded, ca: Arc<MitmCa>) {
+    let Some(state) = upgraded
+        .extensions()
+        .get::<Arc<NetworkProxyState>>()
+        .cloned()
+    else {
+        warn!("MITM tunnel missing app state");
+        return;
+    };
+    let Some(ProxyTarget(authority)) = upgraded.extensions().get::<ProxyTarget>().cloned() else {
+        warn!("MITM tunnel missing proxy target");
+        return;
+    };
+    let client = client_addr(&upgraded);
+
+    let host = normalize_host(&authority.host.to_string());
+    let acceptor = match ca.acceptor_for_host(&host) {
+        Ok(acceptor) => acceptor,
+        Err(err) => {
+            warn!("failed to issue MITM certificate for {host}: {err}");
+            return;
+        }
+    };
+
+    let http_service = HttpServer::auto(Executor::new()).service(
+        (
+            RemoveResponseHeaderLayer::hop_by_hop(),
+            RemoveRequestHeaderLayer::hop_by_hop(),
+        )
+            .into_layer(service_fn(move |req| {
+                handle_intercepted_request(state.clone(), authority.clone(), client.clone(), req)
+            })),
+    );
+    if let Err(err) = TlsAcceptorLayer::new(acceptor)
+        .into_layer(http_service)
+        .serve(upgraded)
+        .await
+    {
+        warn!("MITM tunnel to {host} failed: {err}");
+    }
+}
+
+async fn handle_intercepted_request(
+    state: Arc<NetworkProxyState>,
+    authority: Authority,
+    client: Option<String>,
+    req: Request,
+) -> Result<Response, Infallible> {
+    let host = normalize_host(&authority.host.to_string());
+    // The tunnel was approved for one host; don't let the inner request pick another.
+    let inner_host = req.uri().host().map(str::to_string).or_else(|| {
+        req.headers()
+            .get(HOST)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    });
+    if let Some(inner_host) = inner_host
+        && normalize_host(&inner_host) != host
+    {
+        warn!("MITM request host {inner_host} does not match tunnel host {host}");
+        return Ok(text_response(
+            StatusCode::MISDIRECTED_REQUEST,
+            "host mismatch",
+        ));
+    }
+
+    let method = req.method().as_str().to_string();
+    let path = req.uri().path().to_string();
+    let client_label = client.as_deref().unwrap_or_default();
+
+    let mode = match state.network_mode().await {
+        Ok(mode) => mode,
+        Err(err) => {
+            warn!("failed to evaluate method policy: {err}");
+            return Ok(text_response(StatusCode::INTERNAL_SERVER_ERROR, "error"));
+        }
+    };
+    if !mode.allows_method(&method) {
+        let _ = state
+            .record_blocked(BlockedRequest::new(BlockedRequestArgs {
+                host: host.clone(),
+                reason: REASON_METHOD_NOT_ALLOWED.to_string(),
+                client: client.clone(),
+                method: Some(method.clone()),
+                mode: Some(mode),
+                protocol: "https".to_string(),
+            }))
+            .await;
+        let allowed_methods = mode.allowed_methods().unwrap_or_default().join(", ");
+        warn!(
+            "HTTPS request blocked by method policy (client={client_label}, host={host}, method={method}, mode={}, allowed_methods={allowed_methods})",
+            mode.as_str()
+        );
+        return Ok(json_blocked(&host, REASON_METHOD_NOT_ALLOWED));
+    }
+
+    let path_allowed = match state.path_allowed(&host, &path).await {
+        Ok(allowed) => allowed,
+        Err(err) => {
+            warn!("failed to evaluate path policy: {err}");
+            return Ok(text_response(StatusCode::INTERNAL_SERVER_ERROR, "error"));
+        }
+    };
+    if !path_allowed {
+        let _ = state
+            .record_blocked(BlockedRequest::new(BlockedRequestArgs {
+                host: host.clone(),
+                reason: REASON_PATH_NOT_ALLOWED.to_string(),
+                client: client.clone(),
+                method: Some(method.clone()),
+                mode: None,
+                protocol: "https".to_string(),
+            }))
+            .await;
+        warn!(
+            "HTTPS request blocked by path policy (client={client_label}, host={host}, path={path})"
+        );
+        return Ok(json_blocked(&host, REASON_PATH_NOT_ALLOWED));
+    }
+
+    info!("HTTPS request allowed (client={client_label}, host={host}, method={method})");
+
+    let (mut parts, body) = req.into_parts();
+    let path_and_query = parts
+        .uri
+        .path_and_query()
+        .map(rama_http::uri::PathAndQuery::as_str)
+        .unwrap_or("/");
+    parts.uri = match format!("https://{authority}{path_and_query}").parse() {
+        Ok(uri) => uri,
+        Err(err) => {
+            warn!("invalid MITM request target {authority}{path_and_query}: {err}");
+            return Ok(text_response(
+                StatusCode::BAD_REQUEST,
+                "invalid request target",
+            ));
+        }
+    };
+    let req = Request::from_parts(parts, body);
+
+    let allow_upstream_proxy = match state.allow_upstream_proxy().await {
+        Ok(allow) => allow,
+        Err(err) => {
+            warn!("failed to read upstream proxy config: {err}");
+            return Ok(text_response(StatusCode::INTERNAL_SERVER_ERROR, "error"));
+        }
+    };
+    let upstream = if allow_upstream_proxy {
+        UpstreamClient::from_env_proxy()
+    } else {
+        UpstreamClient::direct()
+    };
+    match upstream.serve(req).await {
+        Ok(resp) => Ok(resp),
+        Err(err) => {
+            warn!("upstream request failed: {err}");
+            Ok(text_response(StatusCode::BAD_GATEWAY, "upstream failure"))
+        }
+    }
+}
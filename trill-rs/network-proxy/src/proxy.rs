@@ -1,4 +1,5 @@
 use crate::admin;
+use crate::certs::MitmCa;
 use crate::config;
 use crate::http_proxy;
 use crate::network_policy::NetworkPolicyDecider;
@@ -9,13 +10,22 @@ use anyhow::Context;
 use anyhow::Result;
 use clap::Parser;
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::Arc;
 use tokio::task::JoinHandle;
+use tracing::info;
 use tracing::warn;
+use trill_core::config::find_trill_home;
+use trill_core::config::NETWORK_PROXY_DIR;
 
 #[derive(Debug, Clone, Parser)]
 #[command(name = "trill-network-proxy", about = "Codex network sandbox proxy")]
-pub struct Args {}
+pub struct Args {
+    /// Serve a single sandboxed command: listen on ephemeral loopback ports and ask the parent
+    /// process over stdio before allowing hosts outside the allowlist.
+    #[arg(long)]
+    pub sandbox: bool,
+}
 
 #[derive(Clone, Default)]
 pub struct NetworkProxyBuilder {
@@ -23,6 +33,7 @@ pub struct NetworkProxyBuilder {
     http_addr: Option<SocketAddr>,
     admin_addr: Option<SocketAddr>,
     policy_decider: Option<Arc<dyn NetworkPolicyDecider>>,
+    disable_socks5: bool,
 }
 
 impl NetworkProxyBuilder {
@@ -41,6 +52,12 @@ impl NetworkProxyBuilder {
         self
     }
 
+    /// Skip the SOCKS5 listener even when `network_proxy.enable_socks5` is set.
+    pub fn without_socks5(mut self) -> Self {
+        self.disable_socks5 = true;
+        self
+    }
+
     pub fn policy_decider<D>(mut self, decider: D) -> Self
     where
         D: NetworkPolicyDecider,
@@ -68,6 +85,19 @@ impl NetworkProxyBuilder {
             self.admin_addr.unwrap_or(runtime.admin_addr),
             &current_cfg.network_proxy,
         );
+        let mitm = if current_cfg.network_proxy.mitm {
+            let dir = find_trill_home()
+                .context("failed to resolve CODEX_HOME")?
+                .join(NETWORK_PROXY_DIR);
+            let ca = MitmCa::load_or_create(&dir)?;
+            info!(
+                "intercepting HTTPS with the CA at {}",
+                ca.cert_path().display()
+            );
+            Some(Arc::new(ca))
+        } else {
+            None
+        };
 
         Ok(NetworkProxy {
             state,
@@ -75,6 +105,8 @@ impl NetworkProxyBuilder {
             socks_addr,
             admin_addr,
             policy_decider: self.policy_decider,
+            disable_socks5: self.disable_socks5,
+            mitm,
         })
     }
 }
@@ -86,6 +118,8 @@ pub struct NetworkProxy {
     socks_addr: SocketAddr,
     admin_addr: SocketAddr,
     policy_decider: Option<Arc<dyn NetworkPolicyDecider>>,
+    disable_socks5: bool,
+    mitm: Option<Arc<MitmCa>>,
 }
 
 impl NetworkProxy {
@@ -93,9 +127,14 @@ impl NetworkProxy {
         NetworkProxyBuilder::default()
     }
 
+    /// CA certificate clients must trust when `network_proxy.mitm` is set.
+    pub fn mitm_ca_cert(&self) -> Option<&Path> {
+        self.mitm.as_deref().map(MitmCa::cert_path)
+    }
+
     pub async fn run(&self) -> Result<NetworkProxyHandle> {
         let current_cfg = self.state.current_cfg().await?;
-        if !current_cfg.network_proxy.enabled {
+        if !self.state.enabled().await? {
             warn!("network_proxy.enabled is false; skipping proxy listeners");
             return Ok(NetworkProxyHandle::noop());
         }
@@ -108,8 +147,9 @@ impl NetworkProxy {
             self.state.clone(),
             self.http_addr,
             self.policy_decider.clone(),
+            self.mitm.clone(),
         ));
-        let socks_task = if current_cfg.network_proxy.enable_socks5 {
+        let socks_task = if current_cfg.network_proxy.enable_socks5 && !self.disable_socks5 {
             Some(tokio::spawn(socks5::run_socks5(
                 self.state.clone(),
                 self.socks_addr,
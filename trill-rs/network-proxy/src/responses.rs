@@ -2,6 +2,8 @@ use crate::reasons::REASON_DENIED;
 use crate::reasons::REASON_METHOD_NOT_ALLOWED;
 use crate::reasons::REASON_NOT_ALLOWED;
 use crate::reasons::REASON_NOT_ALLOWED_LOCAL;
+use crate::reasons::REASON_PATH_NOT_ALLOWED;
+use crate::reasons::blocked_message;
 use rama_http::Body;
 use rama_http::Response;
 use rama_http::StatusCode;
@@ -39,24 +41,11 @@ pub fn blocked_header_value(reason: &str) -> &'static str {
         REASON_NOT_ALLOWED | REASON_NOT_ALLOWED_LOCAL => "blocked-by-allowlist",
         REASON_DENIED => "blocked-by-denylist",
         REASON_METHOD_NOT_ALLOWED => "blocked-by-method-policy",
+        REASON_PATH_NOT_ALLOWED => "blocked-by-path-policy",
         _ => "blocked-by-policy",
     }
 }
 
-pub fn blocked_message(reason: &str) -> &'static str {
-    match reason {
-        REASON_NOT_ALLOWED => "Codex blocked this request: domain not in allowlist.",
-        REASON_NOT_ALLOWED_LOCAL => {
-            "Codex blocked this request: local/private addresses not allowed."
-        }
-        REASON_DENIED => "Codex blocked this request: domain denied by policy.",
-        REASON_METHOD_NOT_ALLOWED => {
-            "Codex blocked this request: method not allowed in limited mode."
-        }
-        _ => "Codex blocked this request by network policy.",
-    }
-}
-
 pub fn blocked_text_response(reason: &str) -> Response {
     Response::builder()
         .status(StatusCode::FORBIDDEN)
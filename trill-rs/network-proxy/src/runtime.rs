@@ -1,114 +1,36 @@
 use crate::config::NetworkMode;
 use crate::config::NetworkProxyConfig;
-use crate::policy::Host;
-use crate::policy::is_loopback_host;
-use crate::policy::is_non_public_ip;
-use crate::policy::normalize_host;
-use crate::reasons::REASON_DENIED;
-use crate::reasons::REASON_NOT_ALLOWED;
-use crate::reasons::REASON_NOT_ALLOWED_LOCAL;
 use crate::state::NetworkProxyConstraints;
 use crate::state::build_config_state;
 use crate::state::validate_policy_against_constraints;
 use anyhow::Context;
 use anyhow::Result;
+use trill_network_policy::host_blocked;
 use trill_utils_absolute_path::AbsolutePathBuf;
 use globset::GlobSet;
-use serde::Serialize;
 use std::collections::HashSet;
 use std::collections::VecDeque;
-use std::net::IpAddr;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
 use std::time::SystemTime;
-use time::OffsetDateTime;
-use tokio::net::lookup_host;
 use tokio::sync::RwLock;
-use tokio::time::timeout;
 use tracing::info;
 use tracing::warn;
 
-const MAX_BLOCKED_EVENTS: usize = 200;
-const DNS_LOOKUP_TIMEOUT: Duration = Duration::from_secs(2);
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum HostBlockReason {
-    Denied,
-    NotAllowed,
-    NotAllowedLocal,
-}
-
-impl HostBlockReason {
-    pub const fn as_str(self) -> &'static str {
-        match self {
-            Self::Denied => REASON_DENIED,
-            Self::NotAllowed => REASON_NOT_ALLOWED,
-            Self::NotAllowedLocal => REASON_NOT_ALLOWED_LOCAL,
-        }
-    }
-}
-
-impl std::fmt::Display for HostBlockReason {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(self.as_str())
-    }
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum HostBlockDecision {
-    Allowed,
-    Blocked(HostBlockReason),
-}
-
-#[derive(Clone, Debug, Serialize)]
-pub struct BlockedRequest {
-    pub host: String,
-    pub reason: String,
-    pub client: Option<String>,
-    pub method: Option<String>,
-    pub mode: Option<NetworkMode>,
-    pub protocol: String,
-    pub timestamp: i64,
-}
-
-pub struct BlockedRequestArgs {
-    pub host: String,
-    pub reason: String,
-    pub client: Option<String>,
-    pub method: Option<String>,
-    pub mode: Option<NetworkMode>,
-    pub protocol: String,
-}
+pub use trill_network_policy::BlockedRequest;
+pub use trill_network_policy::BlockedRequestArgs;
+pub use trill_network_policy::HostBlockDecision;
+pub use trill_network_policy::HostBlockReason;
 
-impl BlockedRequest {
-    pub fn new(args: BlockedRequestArgs) -> Self {
-        let BlockedRequestArgs {
-            host,
-            reason,
-            client,
-            method,
-            mode,
-            protocol,
-        } = args;
-        Self {
-            host,
-            reason,
-            client,
-            method,
-            mode,
-            protocol,
-            timestamp: unix_timestamp(),
-        }
-    }
-}
+const MAX_BLOCKED_EVENTS: usize = 200;
 
 #[derive(Clone)]
 pub(crate) struct ConfigState {
     pub(crate) config: NetworkProxyConfig,
     pub(crate) allow_set: GlobSet,
     pub(crate) deny_set: GlobSet,
+    pub(crate) denied_path_set: GlobSet,
     pub(crate) constraints: NetworkProxyConstraints,
     pub(crate) layer_mtimes: Vec<LayerMtime>,
     pub(crate) cfg_path: PathBuf,
@@ -131,6 +53,8 @@ impl LayerMtime {
 #[derive(Clone)]
 pub struct NetworkProxyState {
     state: Arc<RwLock<ConfigState>>,
+    /// Set for sandbox proxies, which run regardless of `network_proxy.enabled`.
+    always_enabled: bool,
 }
 
 impl std::fmt::Debug for NetworkProxyState {
@@ -146,6 +70,21 @@ impl NetworkProxyState {
         let cfg_state = build_config_state().await?;
         Ok(Self {
             state: Arc::new(RwLock::new(cfg_state)),
+            always_enabled: false,
+        })
+    }
+
+    /// State for a proxy that serves a sandboxed command. Policy still comes from
+    /// `[network_proxy]`, but the proxy runs even when `network_proxy.enabled` is unset, unless
+    /// managed config pins it off.
+    pub async fn for_sandbox() -> Result<Self> {
+        let cfg_state = build_config_state().await?;
+        if cfg_state.constraints.enabled == Some(false) {
+            anyhow::bail!("network_proxy.enabled is disabled by managed config");
+        }
+        Ok(Self {
+            state: Arc::new(RwLock::new(cfg_state)),
+            always_enabled: true,
         })
     }
 
@@ -169,7 +108,7 @@ impl NetworkProxyState {
     pub async fn enabled(&self) -> Result<bool> {
         self.reload_if_needed().await?;
         let guard = self.state.read().await;
-        Ok(guard.config.network_proxy.enabled)
+        Ok(self.always_enabled || guard.config.network_proxy.enabled)
     }
 
     pub async fn force_reload(&self) -> Result<()> {
@@ -200,69 +139,15 @@ impl NetworkProxyState {
 
     pub async fn host_blocked(&self, host: &str, port: u16) -> Result<HostBlockDecision> {
         self.reload_if_needed().await?;
-        let host = match Host::parse(host) {
-            Ok(host) => host,
-            Err(_) => return Ok(HostBlockDecision::Blocked(HostBlockReason::NotAllowed)),
-        };
-        let (deny_set, allow_set, allow_local_binding, allowed_domains_empty, allowed_domains) = {
+        let (policy, allow_set, deny_set) = {
             let guard = self.state.read().await;
             (
-                guard.deny_set.clone(),
+                guard.config.network_proxy.policy.clone(),
                 guard.allow_set.clone(),
-                guard.config.network_proxy.policy.allow_local_binding,
-                guard.config.network_proxy.policy.allowed_domains.is_empty(),
-                guard.config.network_proxy.policy.allowed_domains.clone(),
+                guard.deny_set.clone(),
             )
         };
-
-        let host_str = host.as_str();
-
-        // Decision order matters:
-        //  1) explicit deny always wins
-        //  2) local/private networking is opt-in (defense-in-depth)
-        //  3) allowlist is enforced when configured
-        if deny_set.is_match(host_str) {
-            return Ok(HostBlockDecision::Blocked(HostBlockReason::Denied));
-        }
-
-        let is_allowlisted = allow_set.is_match(host_str);
-        if !allow_local_binding {
-            // If the intent is "prevent access to local/internal networks", we must not rely solely
-            // on string checks like `localhost` / `127.0.0.1`. Attackers can use DNS rebinding or
-            // public suffix services that map hostnames onto private IPs.
-            //
-            // We therefore do a best-effort DNS + IP classification check before allowing the
-            // request. Explicit local/loopback literals are allowed only when explicitly
-            // allowlisted; hostnames that resolve to local/private IPs are blocked even if
-            // allowlisted.
-            let local_literal = {
-                let host_no_scope = host_str
-                    .split_once('%')
-                    .map(|(ip, _)| ip)
-                    .unwrap_or(host_str);
-                if is_loopback_host(&host) {
-                    true
-                } else if let Ok(ip) = host_no_scope.parse::<IpAddr>() {
-                    is_non_public_ip(ip)
-                } else {
-                    false
-                }
-            };
-
-            if local_literal {
-                if !is_explicit_local_allowlisted(&allowed_domains, &host) {
-                    return Ok(HostBlockDecision::Blocked(HostBlockReason::NotAllowedLocal));
-                }
-            } else if host_resolves_to_non_public_ip(host_str, port).await {
-                return Ok(HostBlockDecision::Blocked(HostBlockReason::NotAllowedLocal));
-            }
-        }
-
-        if allowed_domains_empty || !is_allowlisted {
-            Ok(HostBlockDecision::Blocked(HostBlockReason::NotAllowed))
-        } else {
-            Ok(HostBlockDecision::Allowed)
-        }
+        Ok(host_blocked(&policy, &allow_set, &deny_set, host, port).await)
     }
 
     pub async fn record_blocked(&self, entry: BlockedRequest) -> Result<()> {
@@ -330,6 +215,15 @@ impl NetworkProxyState {
         Ok(guard.config.network_proxy.mode.allows_method(method))
     }
 
+    /// Whether `network_proxy.denied_paths` lets a request for `path` on `host` through.
+    pub async fn path_allowed(&self, host: &str, path: &str) -> Result<bool> {
+        self.reload_if_needed().await?;
+        let guard = self.state.read().await;
+        Ok(!guard
+            .denied_path_set
+            .is_match(crate::policy::path_candidate(host, path)))
+    }
+
     pub async fn allow_upstream_proxy(&self) -> Result<bool> {
         self.reload_if_needed().await?;
         let guard = self.state.read().await;
@@ -392,28 +286,6 @@ pub(crate) fn unix_socket_permissions_supported() -> bool {
     cfg!(target_os = "macos")
 }
 
-async fn host_resolves_to_non_public_ip(host: &str, port: u16) -> bool {
-    if let Ok(ip) = host.parse::<IpAddr>() {
-        return is_non_public_ip(ip);
-    }
-
-    // If DNS lookup fails, default to "not local/private" rather than blocking. In practice, the
-    // subsequent connect attempt will fail anyway, and blocking on transient resolver issues would
-    // make the proxy fragile. The allowlist/denylist remains the primary control plane.
-    let addrs = match timeout(DNS_LOOKUP_TIMEOUT, lookup_host((host, port))).await {
-        Ok(Ok(addrs)) => addrs,
-        Ok(Err(_)) | Err(_) => return false,
-    };
-
-    for addr in addrs {
-        if is_non_public_ip(addr.ip()) {
-            return true;
-        }
-    }
-
-    false
-}
-
 fn log_policy_changes(previous: &NetworkProxyConfig, next: &NetworkProxyConfig) {
     log_domain_list_changes(
         "allowlist",
@@ -463,46 +335,38 @@ fn log_domain_list_changes(list_name: &str, previous: &[String], next: &[String]
     }
 }
 
-fn is_explicit_local_allowlisted(allowed_domains: &[String], host: &Host) -> bool {
-    let normalized_host = host.as_str();
-    allowed_domains.iter().any(|pattern| {
-        let pattern = pattern.trim();
-        if pattern == "*" || pattern.starts_with("*.") || pattern.starts_with("**.") {
-            return false;
-        }
-        if pattern.contains('*') || pattern.contains('?') {
-            return false;
-        }
-        normalize_host(pattern) == normalized_host
+#[cfg(test)]
+pub(crate) fn network_proxy_state_for_policy(
+    policy: crate::config::NetworkPolicy,
+) -> NetworkProxyState {
+    network_proxy_state_for_settings(crate::config::NetworkProxySettings {
+        enabled: true,
+        mode: NetworkMode::Full,
+        policy,
+        ..crate::config::NetworkProxySettings::default()
     })
 }
 
-fn unix_timestamp() -> i64 {
-    OffsetDateTime::now_utc().unix_timestamp()
-}
-
 #[cfg(test)]
-pub(crate) fn network_proxy_state_for_policy(
-    policy: crate::config::NetworkPolicy,
+pub(crate) fn network_proxy_state_for_settings(
+    settings: crate::config::NetworkProxySettings,
 ) -> NetworkProxyState {
     let config = NetworkProxyConfig {
-        network_proxy: crate::config::NetworkProxySettings {
-            enabled: true,
-            mode: NetworkMode::Full,
-            policy,
-            ..crate::config::NetworkProxySettings::default()
-        },
+        network_proxy: settings,
     };
 
     let allow_set =
         crate::policy::compile_globset(&config.network_proxy.policy.allowed_domains).unwrap();
     let deny_set =
         crate::policy::compile_globset(&config.network_proxy.policy.denied_domains).unwrap();
+    let denied_path_set =
+        crate::policy::compile_path_globset(&config.network_proxy.denied_paths).unwrap();
 
     let state = ConfigState {
         config,
         allow_set,
         deny_set,
+        denied_path_set,
         constraints: NetworkProxyConstraints::default(),
         layer_mtimes: Vec::new(),
         cfg_path: PathBuf::from("/nonexistent/config.toml"),
@@ -511,6 +375,7 @@ pub(crate) fn network_proxy_state_for_policy(
 
     NetworkProxyState {
         state: Arc::new(RwLock::new(state)),
+        always_enabled: false,
     }
 }
 
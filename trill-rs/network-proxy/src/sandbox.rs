@@ -0,0 +1,212 @@
+use crate::network_policy::NetworkDecision;
+use crate::network_policy::NetworkPolicyDecider;
+use crate::network_policy::NetworkPolicyRequest;
+use crate::proxy::NetworkProxy;
+use crate::reasons::REASON_NOT_ALLOWED;
+use crate::state::NetworkProxyState;
+use anyhow::Context;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::sync::Mutex;
+use tokio::sync::oneshot;
+use tracing::warn;
+use trill_network_policy::sandbox::SandboxProxyDecision;
+use trill_network_policy::sandbox::SandboxProxyEvent;
+
+const READY_TIMEOUT: Duration = Duration::from_secs(5);
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// How long a connection waits for the parent before it is denied.
+const DECISION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Runs the proxy for one sandboxed command (`trill-network-proxy --sandbox`).
+///
+/// Listeners bind ephemeral loopback ports. Once the HTTP proxy accepts connections a `ready`
+/// event is written to stdout; hosts outside the allowlist are then reported as `decide` events
+/// and wait for a decision on stdin. The proxy exits when stdin closes.
+pub async fn run_sandbox() -> Result<()> {
+    let state = Arc::new(NetworkProxyState::for_sandbox().await?);
+    let http_addr = reserve_loopback_addr()?;
+    let admin_addr = reserve_loopback_addr()?;
+    let decider = Arc::new(StdioDecider::default());
+    let proxy = NetworkProxy::builder()
+        .state(state)
+        .http_addr(http_addr)
+        .admin_addr(admin_addr)
+        .without_socks5()
+        .policy_decider_arc(decider.clone())
+        .build()
+        .await?;
+    let ca_cert = proxy.mitm_ca_cert().map(Path::to_path_buf);
+    let handle = proxy.run().await?;
+
+    wait_until_listening(http_addr).await?;
+    decider
+        .send(&SandboxProxyEvent::Ready { http_addr, ca_cert })
+        .await
+        .context("report sandbox proxy address")?;
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines.next_line().await? {
+        match serde_json::from_str::<SandboxProxyDecision>(&line) {
+            Ok(decision) => decider.resolve(decision).await,
+            Err(err) => warn!("ignoring invalid sandbox proxy decision: {err}"),
+        }
+    }
+    handle.shutdown().await
+}
+
+/// Asks the parent process about hosts outside the allowlist.
+#[derive(Default)]
+struct StdioDecider {
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<u64, oneshot::Sender<bool>>>,
+    stdout: Mutex<Option<tokio::io::Stdout>>,
+}
+
+impl StdioDecider {
+    async fn send(&self, event: &SandboxProxyEvent) -> Result<()> {
+        let mut line = serde_json::to_string(event)?;
+        line.push('\n');
+        let mut stdout = self.stdout.lock().await;
+        let stdout = stdout.get_or_insert_with(tokio::io::stdout);
+        stdout.write_all(line.as_bytes()).await?;
+        stdout.flush().await?;
+        Ok(())
+    }
+
+    async fn resolve(&self, decision: SandboxProxyDecision) {
+        if let Some(tx) = self.pending.lock().await.remove(&decision.id) {
+            let _ = tx.send(decision.allow);
+        }
+    }
+}
+
+#[async_trait]
+impl NetworkPolicyDecider for StdioDecider {
+    async fn decide(&self, req: NetworkPolicyRequest) -> NetworkDecision {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().await.insert(id, tx);
+        let event = SandboxProxyEvent::Decide {
+            id,
+            host: req.host,
+            port: req.port,
+        };
+        if let Err(err) = self.send(&event).await {
+            warn!("failed to request a sandbox network decision: {err}");
+            self.pending.lock().await.remove(&id);
+            return NetworkDecision::deny(REASON_NOT_ALLOWED);
+        }
+        // A dropped sender means stdin closed; the command is going away, so deny.
+        match tokio::time::timeout(DECISION_TIMEOUT, rx).await {
+            Ok(Ok(true)) => NetworkDecision::Allow,
+            Ok(Ok(false) | Err(_)) => NetworkDecision::deny(REASON_NOT_ALLOWED),
+            Err(_) => {
+                warn!("no sandbox network decision within {DECISION_TIMEOUT:?}; denying");
+                self.pending.lock().await.remove(&id);
+                NetworkDecision::deny(REASON_NOT_ALLOWED)
+            }
+        }
+    }
+}
+
+/// Picks a free loopback port. The port is released before the proxy binds it, so another
+/// process could take it in between; `wait_until_listening` catches that case.
+fn reserve_loopback_addr() -> Result<SocketAddr> {
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0))
+        .context("reserve a loopback port for the sandbox proxy")?;
+    Ok(listener.local_addr()?)
+}
+
+async fn wait_until_listening(addr: SocketAddr) -> Result<()> {
+    let poll = async {
+        while tokio::net::TcpStream::connect(addr).await.is_err() {
+            tokio::time::sleep(READY_POLL_INTERVAL).await;
+        }
+    };
+    tokio::time::timeout(READY_TIMEOUT, poll)
+        .await
+        .with_context(|| format!("sandbox proxy did not start listening on {addr}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network_policy::NetworkPolicyRequestArgs;
+    use crate::network_policy::NetworkProtocol;
+    use pretty_assertions::assert_eq;
+
+    fn request(host: &str) -> NetworkPolicyRequest {
+        NetworkPolicyRequest::new(NetworkPolicyRequestArgs {
+            protocol: NetworkProtocol::HttpsConnect,
+            host: host.to_string(),
+            port: 443,
+            client_addr: None,
+            method: None,
+            command: None,
+            exec_policy_hint: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn decider_waits_for_matching_decision() {
+        let decider = Arc::new(StdioDecider::default());
+        let pending = tokio::spawn({
+            let decider = decider.clone();
+            async move { decider.decide(request("crates.io")).await }
+        });
+        while decider.pending.lock().await.is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        decider
+            .resolve(SandboxProxyDecision { id: 7, allow: true })
+            .await;
+        decider
+            .resolve(SandboxProxyDecision { id: 0, allow: true })
+            .await;
+
+        assert_eq!(pending.await.unwrap(), NetworkDecision::Allow);
+        assert!(decider.pending.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn decider_denies_when_parent_goes_away() {
+        let decider = Arc::new(StdioDecider::default());
+        let pending = tokio::spawn({
+            let decider = decider.clone();
+            async move { decider.decide(request("pypi.org")).await }
+        });
+        while decider.pending.lock().await.is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        decider.pending.lock().await.clear();
+
+        assert_eq!(
+            pending.await.unwrap(),
+            NetworkDecision::deny(REASON_NOT_ALLOWED)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn decider_denies_when_no_decision_arrives() {
+        let decider = StdioDecider::default();
+
+        assert_eq!(
+            decider.decide(request("example.com")).await,
+            NetworkDecision::deny(REASON_NOT_ALLOWED)
+        );
+        assert!(decider.pending.lock().await.is_empty());
+    }
+}
@@ -2,6 +2,7 @@ use crate::config::NetworkMode;
 use crate::config::NetworkProxyConfig;
 use crate::policy::DomainPattern;
 use crate::policy::compile_globset;
+use crate::policy::compile_path_globset;
 use crate::runtime::ConfigState;
 use crate::runtime::LayerMtime;
 use anyhow::Context;
@@ -58,10 +59,12 @@ pub(crate) async fn build_config_state() -> Result<ConfigState> {
     let layer_mtimes = collect_layer_mtimes(&config_layer_stack);
     let deny_set = compile_globset(&config.network_proxy.policy.denied_domains)?;
     let allow_set = compile_globset(&config.network_proxy.policy.allowed_domains)?;
+    let denied_path_set = compile_path_globset(&config.network_proxy.denied_paths)?;
     Ok(ConfigState {
         config,
         allow_set,
         deny_set,
+        denied_path_set,
         constraints,
         layer_mtimes,
         cfg_path,
@@ -16,6 +16,7 @@ use rama_http_backend::client::proxy::layer::HttpProxyConnectorLayer;
 use rama_net::address::ProxyAddress;
 use rama_net::client::EstablishedClientConnection;
 use rama_net::http::RequestContext;
+use rama_net::tls::client::ServerVerifyMode;
 use rama_tcp::client::service::TcpConnector;
 use rama_tls_boring::client::TlsConnectorDataBuilder;
 use rama_tls_boring::client::TlsConnectorLayer;
@@ -165,7 +166,11 @@ fn build_http_connector() -> BoxService<
 > {
     let transport = TcpConnector::default();
     let proxy = HttpProxyConnectorLayer::optional().into_layer(transport);
-    let tls_config = TlsConnectorDataBuilder::new_http_auto().into_shared_builder();
+    // Upstream certificates are checked against the system roots; the proxy never relaxes
+    // verification, even for hosts it intercepts.
+    let tls_config = TlsConnectorDataBuilder::new_http_auto()
+        .with_server_verify_mode(ServerVerifyMode::Auto)
+        .into_shared_builder();
     let tls = TlsConnectorLayer::auto()
         .with_connector_data(tls_config)
         .into_layer(proxy);
@@ -186,3 +191,44 @@ fn build_unix_connector(
     let connector = HttpConnector::new(transport);
     connector.boxed()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::certs::MitmCa;
+    use rama_tls_boring::core::ssl::SslAcceptor;
+    use rama_tls_boring::core::ssl::SslMethod;
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rejects_upstream_certificates_from_untrusted_cas() {
+        let dir = tempfile::tempdir().unwrap();
+        let ca = MitmCa::load_or_create(dir.path()).unwrap();
+        let (leaf, leaf_key) = ca.issue_leaf("127.0.0.1").unwrap();
+        let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        acceptor.set_private_key(&leaf_key).unwrap();
+        acceptor.set_certificate(&leaf).unwrap();
+        let acceptor = acceptor.build();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (handshake_tx, handshake_rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let _ = handshake_tx.send(acceptor.accept(stream).is_ok());
+        });
+
+        let req = Request::builder()
+            .uri(format!("https://127.0.0.1:{port}/"))
+            .body(Body::empty())
+            .unwrap();
+        assert!(UpstreamClient::direct().serve(req).await.is_err());
+        // The server's certificate is otherwise valid for the address, so a failed handshake
+        // means the client refused it.
+        let handshake_completed = handshake_rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert!(!handshake_completed);
+    }
+}
@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use crate::parse_command::ParsedCommand;
 use crate::protocol::FileChange;
 use mcp_types::RequestId;
+use mcp_types::Role;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
@@ -77,6 +78,28 @@ pub enum ElicitationAction {
     Cancel,
 }
 
+/// An MCP server asked to sample the session's model (`sampling/createMessage`).
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct McpSamplingRequestEvent {
+    pub server_name: String,
+    pub id: RequestId,
+    /// Model the request will be sent to once the server's hints are mapped.
+    pub model: String,
+    /// Output token limit after the configured cap is applied.
+    pub max_tokens: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub system_prompt: Option<String>,
+    pub messages: Vec<McpSamplingMessage>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct McpSamplingMessage {
+    pub role: Role,
+    /// Text of the message; non-text content is shown as a placeholder.
+    pub text: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ApplyPatchApprovalRequestEvent {
     /// Responses API call id for the associated patch apply call, if available.
@@ -13,6 +13,7 @@ use std::time::Duration;
 
 use crate::ThreadId;
 use crate::approvals::ElicitationRequestEvent;
+use crate::approvals::McpSamplingRequestEvent;
 use crate::config_types::CollaborationMode;
 use crate::config_types::ModeKind;
 use crate::config_types::Personality;
@@ -36,6 +37,9 @@ use crate::request_user_input::RequestUserInputResponse;
 use crate::user_input::UserInput;
 use trill_utils_absolute_path::AbsolutePathBuf;
 use mcp_types::CallToolResult;
+use mcp_types::GetPromptResult;
+use mcp_types::Prompt as McpPrompt;
+use mcp_types::PromptMessage;
 use mcp_types::RequestId;
 use mcp_types::Resource as McpResource;
 use mcp_types::ResourceTemplate as McpResourceTemplate;
@@ -217,11 +221,26 @@ pub enum Op {
         decision: ElicitationAction,
     },
 
+    /// Approve or deny an MCP sampling request.
+    ResolveMcpSampling {
+        /// Name of the MCP server that issued the request.
+        server_name: String,
+        /// Request identifier from the MCP server.
+        request_id: RequestId,
+        /// `ApprovedForSession` approves later requests from the same server.
+        decision: ReviewDecision,
+    },
+
     /// Resolve a request_user_input tool call.
     #[serde(rename = "user_input_answer", alias = "request_user_input_response")]
     UserInputAnswer {
         /// Turn id for the in-flight request.
         id: String,
+        /// Call id of the request being answered, which tells apart several
+        /// requests pending in the same turn. When omitted, the turn's oldest
+        /// pending request is answered.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        call_id: Option<String>,
         /// User-provided answers.
         response: RequestUserInputResponse,
     },
@@ -253,6 +272,24 @@ pub enum Op {
     /// Request MCP servers to reinitialize and refresh cached tool lists.
     RefreshMcpServers { config: McpServerRefreshConfig },
 
+    /// Request the prompts offered by connected MCP servers.
+    /// Reply is delivered via `EventMsg::ListMcpPromptsResponse`.
+    ListMcpPrompts,
+
+    /// Fetch an MCP prompt with its arguments filled in.
+    /// Reply is delivered via `EventMsg::GetMcpPromptResponse`.
+    GetMcpPrompt {
+        server: String,
+        name: String,
+        #[serde(default)]
+        arguments: HashMap<String, String>,
+    },
+
+    /// Start a turn from the messages of a fetched MCP prompt. Messages up to
+    /// the last assistant message are recorded with their roles; the trailing
+    /// user messages become the turn input.
+    RunMcpPrompt { messages: Vec<PromptMessage> },
+
     /// Request the list of available custom prompts.
     ListCustomPrompts,
 
@@ -282,6 +319,24 @@ pub enum Op {
     /// Request Codex to undo a turn (turn are stacked so it is the same effect as CMD + Z).
     Undo,
 
+    /// List the ghost snapshots this thread can be restored to.
+    /// Reply is delivered via `EventMsg::UndoSnapshotsResponse`.
+    ListUndoSnapshots,
+
+    /// Restore the working tree to how it was before the last `num_turns`
+    /// turns and drop those turns from the conversation.
+    UndoTurns {
+        num_turns: u32,
+
+        /// Restore only these files, relative to the session cwd. The
+        /// conversation is kept when set.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        paths: Vec<PathBuf>,
+    },
+
+    /// Reapply the most recent undo that has not been redone.
+    Redo,
+
     /// Request Codex to drop the last N user turns from in-memory context.
     ///
     /// This does not attempt to revert local filesystem changes. Clients are
@@ -780,6 +835,12 @@ pub enum EventMsg {
 
     ElicitationRequest(ElicitationRequestEvent),
 
+    McpSamplingRequest(McpSamplingRequestEvent),
+
+    /// Status update for an MCP sampling request: approval outcome, the
+    /// model response, or the failure.
+    McpSamplingProgress(McpSamplingProgressEvent),
+
     ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent),
 
     /// Notification advising the user that something they are using has been
@@ -796,6 +857,12 @@ pub enum EventMsg {
     /// and the system is handling it (e.g., retrying with backoff).
     StreamError(StreamErrorEvent),
 
+    /// The model kept repeating the same tool calls, so the turn was ended.
+    ToolCallLoopDetected(ToolCallLoopDetectedEvent),
+
+    /// A command configured under `[hooks]` finished running.
+    HookCompleted(HookCompletedEvent),
+
     /// Notification that the agent is about to apply a code patch. Mirrors
     /// `ExecCommandBegin` so front‑ends can show progress indicators.
     PatchApplyBegin(PatchApplyBeginEvent),
@@ -814,6 +881,15 @@ pub enum EventMsg {
     /// List of custom prompts available to the agent.
     ListCustomPromptsResponse(ListCustomPromptsResponseEvent),
 
+    /// List of prompts offered by MCP servers.
+    ListMcpPromptsResponse(ListMcpPromptsResponseEvent),
+
+    /// Response to `Op::GetMcpPrompt`.
+    GetMcpPromptResponse(GetMcpPromptResponseEvent),
+
+    /// Response to `Op::ListUndoSnapshots`.
+    UndoSnapshotsResponse(UndoSnapshotsResponseEvent),
+
     /// List of skills available to the agent.
     ListSkillsResponse(ListSkillsResponseEvent),
 
@@ -1425,6 +1501,14 @@ impl McpToolCallEndEvent {
     }
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct McpSamplingProgressEvent {
+    pub server_name: String,
+    /// Request identifier from the MCP server.
+    pub id: RequestId,
+    pub message: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct WebSearchBeginEvent {
     pub call_id: String,
@@ -1974,12 +2058,99 @@ pub struct UndoCompletedEvent {
     pub message: Option<String>,
 }
 
+/// A turn whose ghost snapshot can be restored.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct UndoSnapshot {
+    /// Turns undone by restoring this snapshot; 1 is the latest turn.
+    pub num_turns: u32,
+    /// Ghost commit holding the working tree from before the turn.
+    pub commit_id: String,
+    /// Message that started the turn.
+    pub user_message: String,
+    /// Files the turn changed, relative to the session cwd.
+    pub changed_files: Vec<PathBuf>,
+}
+
+/// Response payload for `Op::ListUndoSnapshots`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct UndoSnapshotsResponseEvent {
+    /// Newest turn first.
+    pub snapshots: Vec<UndoSnapshot>,
+    /// Whether `Op::Redo` has an undo to reapply.
+    pub redo_available: bool,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ThreadRolledBackEvent {
     /// Number of user turns that were removed from context.
     pub num_turns: u32,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ToolCallLoopDetectedEvent {
+    pub message: String,
+    /// Tools called in the repeated cycle, in call order.
+    pub tool_names: Vec<String>,
+    /// Times the cycle occurred in a row.
+    pub repeats: u32,
+}
+
+/// Lifecycle points at which `[hooks]` commands run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEventName {
+    SessionStart,
+    UserPromptSubmit,
+    PreToolUse,
+    PostToolUse,
+    TurnComplete,
+    PreCompact,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct HookCompletedEvent {
+    pub hook_event_name: HookEventName,
+    pub command: Vec<String>,
+    /// Tool the hook ran for; only set for `pre_tool_use` and `post_tool_use`.
+    pub tool_name: Option<String>,
+    /// `None` when the hook timed out or could not be started.
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub outcome: HookOutcome,
+}
+
+impl HookCompletedEvent {
+    /// Message to show the user when the hook blocked a call or failed.
+    pub fn warning_message(&self) -> Option<String> {
+        let command = self.command.join(" ");
+        match &self.outcome {
+            HookOutcome::Success => None,
+            HookOutcome::Blocked { reason } => Some(match &self.tool_name {
+                Some(tool_name) => format!("hook `{command}` blocked `{tool_name}`: {reason}"),
+                None => format!("hook `{command}` blocked the call: {reason}"),
+            }),
+            HookOutcome::Failed { message } => Some(format!("hook `{command}` failed: {message}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type")]
+pub enum HookOutcome {
+    Success,
+    /// A `pre_tool_use` hook stopped the tool call.
+    Blocked {
+        reason: String,
+    },
+    /// The hook failed, timed out or could not be started. The session
+    /// carries on as if it had not been configured.
+    Failed {
+        message: String,
+    },
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct StreamErrorEvent {
     pub message: String,
@@ -2052,6 +2223,9 @@ pub struct McpListToolsResponseEvent {
     pub resources: std::collections::HashMap<String, Vec<McpResource>>,
     /// Known resource templates grouped by server name.
     pub resource_templates: std::collections::HashMap<String, Vec<McpResourceTemplate>>,
+    /// Known prompts grouped by server name.
+    #[serde(default)]
+    pub prompts: std::collections::HashMap<String, Vec<McpPrompt>>,
     /// Authentication status for each configured MCP server.
     pub auth_statuses: std::collections::HashMap<String, McpAuthStatus>,
 }
@@ -2115,6 +2289,22 @@ pub struct ListCustomPromptsResponseEvent {
     pub custom_prompts: Vec<CustomPrompt>,
 }
 
+/// Response payload for `Op::ListMcpPrompts`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ListMcpPromptsResponseEvent {
+    /// Prompts grouped by server name.
+    pub prompts: HashMap<String, Vec<McpPrompt>>,
+}
+
+/// Response payload for `Op::GetMcpPrompt`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct GetMcpPromptResponseEvent {
+    pub server: String,
+    pub name: String,
+    /// The prompt messages, or the error reported for the request.
+    pub result: Result<GetPromptResult, String>,
+}
+
 /// Response payload for `Op::ListSkills`.
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ListSkillsResponseEvent {
@@ -1,4 +1,5 @@
 use crate::error::ApiError;
+use crate::provider::WireApi;
 use trill_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
 use trill_protocol::config_types::Verbosity as VerbosityConfig;
 use trill_protocol::models::ResponseItem;
@@ -7,6 +8,7 @@ use trill_protocol::protocol::RateLimitSnapshot;
 use trill_protocol::protocol::TokenUsage;
 use futures::Stream;
 use serde::Serialize;
+use serde_json::Map;
 use serde_json::Value;
 use std::pin::Pin;
 use std::task::Context;
@@ -122,6 +124,62 @@ impl From<VerbosityConfig> for OpenAiVerbosity {
     }
 }
 
+/// Sampling controls, mostly for local and self-hosted models. Unset fields
+/// are left out of the request so the server's defaults apply.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SamplingParams {
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub top_k: Option<u64>,
+    pub min_p: Option<f64>,
+    pub repeat_penalty: Option<f64>,
+    pub seed: Option<i64>,
+    pub max_tokens: Option<u64>,
+    pub stop: Vec<String>,
+    /// Provider-specific fields copied into the request body as-is, such as
+    /// llama.cpp `grammar` or vLLM `guided_json`. These win over every other
+    /// field of the same name.
+    pub extra_body: Map<String, Value>,
+}
+
+impl SamplingParams {
+    /// Top-level request fields for `wire`. The Responses API calls the
+    /// token limit `max_output_tokens`; the other names are shared.
+    pub fn body_fields(&self, wire: &WireApi) -> Map<String, Value> {
+        let mut fields = Map::new();
+        let max_tokens_key = match wire {
+            WireApi::Chat => "max_tokens",
+            WireApi::Responses | WireApi::Compact => "max_output_tokens",
+        };
+        let numbers = [
+            ("temperature", self.temperature.map(Value::from)),
+            ("top_p", self.top_p.map(Value::from)),
+            ("top_k", self.top_k.map(Value::from)),
+            ("min_p", self.min_p.map(Value::from)),
+            ("repeat_penalty", self.repeat_penalty.map(Value::from)),
+            ("seed", self.seed.map(Value::from)),
+            (max_tokens_key, self.max_tokens.map(Value::from)),
+        ];
+        for (key, value) in numbers {
+            if let Some(value) = value {
+                fields.insert(key.to_string(), value);
+            }
+        }
+        if !self.stop.is_empty() {
+            fields.insert("stop".to_string(), Value::from(self.stop.clone()));
+        }
+        fields.extend(self.extra_body.clone());
+        fields
+    }
+
+    /// Merges [`Self::body_fields`] into an encoded request body.
+    pub(crate) fn apply_to(&self, body: &mut Value, wire: &WireApi) {
+        if let Value::Object(body) = body {
+            body.extend(self.body_fields(wire));
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct ResponsesApiRequest<'a> {
     pub model: &'a str,
@@ -156,6 +214,9 @@ pub struct ResponseCreateWsRequest {
     pub prompt_cache_key: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<TextControls>,
+    /// Sampling fields from [`SamplingParams::body_fields`].
+    #[serde(flatten)]
+    pub sampling: Map<String, Value>,
 }
 
 #[derive(Debug, Serialize)]
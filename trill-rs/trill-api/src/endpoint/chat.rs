@@ -3,10 +3,16 @@ use crate::auth::AuthProvider;
 use crate::common::Prompt as ApiPrompt;
 use crate::common::ResponseEvent;
 use crate::common::ResponseStream;
+use crate::common::SamplingParams;
 use crate::endpoint::streaming::StreamingClient;
 use crate::error::ApiError;
 use crate::provider::Provider;
 use crate::provider::WireApi;
+use crate::requests::ChatCapabilities;
+use crate::requests::StructuredOutput;
+use crate::sse::ReasoningTags;
+use crate::sse::ToolCallFormat;
+use crate::sse::chat::ChatReasoning;
 use crate::sse::chat::spawn_chat_stream;
 use crate::telemetry::SseTelemetry;
 use trill_client::HttpTransport;
@@ -27,12 +33,20 @@ use std::task::Poll;
 
 pub struct ChatClient<T: HttpTransport, A: AuthProvider> {
     streaming: StreamingClient<T, A>,
+    reasoning: ChatReasoning,
+    structured_output: StructuredOutput,
+    capabilities: ChatCapabilities,
+    tool_call_format: ToolCallFormat,
 }
 
 impl<T: HttpTransport, A: AuthProvider> ChatClient<T, A> {
     pub fn new(transport: T, provider: Provider, auth: A) -> Self {
         Self {
             streaming: StreamingClient::new(transport, provider, auth),
+            reasoning: ChatReasoning::default(),
+            structured_output: StructuredOutput::default(),
+            capabilities: ChatCapabilities::default(),
+            tool_call_format: ToolCallFormat::default(),
         }
     }
 
@@ -43,6 +57,55 @@ impl<T: HttpTransport, A: AuthProvider> ChatClient<T, A> {
     ) -> Self {
         Self {
             streaming: self.streaming.with_telemetry(request, sse),
+            ..self
+        }
+    }
+
+    /// Treats content between `tags` as reasoning, for models that write it
+    /// inline (e.g. `<think>...</think>`).
+    pub fn with_reasoning_tags(self, tags: Option<ReasoningTags>) -> Self {
+        Self {
+            reasoning: ChatReasoning {
+                tags,
+                ..self.reasoning
+            },
+            ..self
+        }
+    }
+
+    /// Reads reasoning from `field` (e.g. `reasoning_content`) instead of
+    /// `reasoning`.
+    pub fn with_reasoning_field(self, field: Option<String>) -> Self {
+        Self {
+            reasoning: ChatReasoning {
+                field,
+                ..self.reasoning
+            },
+            ..self
+        }
+    }
+
+    /// Sets which optional tool fields the server accepts.
+    pub fn with_capabilities(self, capabilities: ChatCapabilities) -> Self {
+        Self {
+            capabilities,
+            ..self
+        }
+    }
+
+    /// Sets how tool calls are exchanged, natively or written in the text.
+    pub fn with_tool_call_format(self, tool_call_format: ToolCallFormat) -> Self {
+        Self {
+            tool_call_format,
+            ..self
+        }
+    }
+
+    /// Sets how `output_schema` from the prompt is sent to the server.
+    pub fn with_structured_output(self, structured_output: StructuredOutput) -> Self {
+        Self {
+            structured_output,
+            ..self
         }
     }
 
@@ -56,6 +119,7 @@ impl<T: HttpTransport, A: AuthProvider> ChatClient<T, A> {
         prompt: &ApiPrompt,
         conversation_id: Option<String>,
         session_source: Option<SessionSource>,
+        sampling: SamplingParams,
     ) -> Result<ResponseStream, ApiError> {
         use crate::requests::ChatRequestBuilder;
 
@@ -63,6 +127,10 @@ impl<T: HttpTransport, A: AuthProvider> ChatClient<T, A> {
             ChatRequestBuilder::new(model, &prompt.instructions, &prompt.input, &prompt.tools)
                 .conversation_id(conversation_id)
                 .session_source(session_source)
+                .sampling(sampling)
+                .output_schema(prompt.output_schema.as_ref(), self.structured_output)
+                .tool_options(self.capabilities, prompt.parallel_tool_calls)
+                .tool_call_format(self.tool_call_format)
                 .build(self.streaming.provider())?;
 
         self.stream_request(request).await
@@ -87,7 +155,7 @@ impl<T: HttpTransport, A: AuthProvider> ChatClient<T, A> {
                 extra_headers,
                 RequestCompression::None,
                 spawn_chat_stream,
-                None,
+                (self.reasoning.clone(), self.tool_call_format),
             )
             .await
     }
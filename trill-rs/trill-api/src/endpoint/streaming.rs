@@ -13,7 +13,6 @@ use http::HeaderMap;
 use http::Method;
 use serde_json::Value;
 use std::sync::Arc;
-use std::sync::OnceLock;
 use std::time::Duration;
 
 pub(crate) struct StreamingClient<T: HttpTransport, A: AuthProvider> {
@@ -24,12 +23,10 @@ pub(crate) struct StreamingClient<T: HttpTransport, A: AuthProvider> {
     sse_telemetry: Option<Arc<dyn SseTelemetry>>,
 }
 
-type StreamSpawner = fn(
-    StreamResponse,
-    Duration,
-    Option<Arc<dyn SseTelemetry>>,
-    Option<Arc<OnceLock<String>>>,
-) -> ResponseStream;
+/// Turns a response body into events. `S` is per-endpoint state handed to
+/// the spawner, such as the turn-state slot for Responses.
+type StreamSpawner<S> =
+    fn(StreamResponse, Duration, Option<Arc<dyn SseTelemetry>>, S) -> ResponseStream;
 
 impl<T: HttpTransport, A: AuthProvider> StreamingClient<T, A> {
     pub(crate) fn new(transport: T, provider: Provider, auth: A) -> Self {
@@ -56,14 +53,14 @@ impl<T: HttpTransport, A: AuthProvider> StreamingClient<T, A> {
         &self.provider
     }
 
-    pub(crate) async fn stream(
+    pub(crate) async fn stream<S>(
         &self,
         path: &str,
         body: Value,
         extra_headers: HeaderMap,
         compression: RequestCompression,
-        spawner: StreamSpawner,
-        turn_state: Option<Arc<OnceLock<String>>>,
+        spawner: StreamSpawner<S>,
+        spawner_state: S,
     ) -> Result<ResponseStream, ApiError> {
         let builder = || {
             let mut req = self.provider.build_request(Method::POST, path);
@@ -89,7 +86,7 @@ impl<T: HttpTransport, A: AuthProvider> StreamingClient<T, A> {
             stream_response,
             self.provider.stream_idle_timeout,
             self.sse_telemetry.clone(),
-            turn_state,
+            spawner_state,
         ))
     }
 }
@@ -21,6 +21,7 @@ pub use crate::common::ResponseCreateWsRequest;
 pub use crate::common::ResponseEvent;
 pub use crate::common::ResponseStream;
 pub use crate::common::ResponsesApiRequest;
+pub use crate::common::SamplingParams;
 pub use crate::common::create_text_param_for_request;
 pub use crate::endpoint::chat::AggregateStreamExt;
 pub use crate::endpoint::chat::ChatClient;
@@ -34,9 +35,14 @@ pub use crate::error::ApiError;
 pub use crate::provider::Provider;
 pub use crate::provider::WireApi;
 pub use crate::provider::is_azure_responses_wire_base_url;
+pub use crate::requests::ChatCapabilities;
 pub use crate::requests::ChatRequest;
 pub use crate::requests::ChatRequestBuilder;
 pub use crate::requests::ResponsesRequest;
 pub use crate::requests::ResponsesRequestBuilder;
+pub use crate::requests::StructuredOutput;
+pub use crate::sse::ReasoningTags;
+pub use crate::sse::ToolCallFormat;
+pub use crate::sse::chat::ChatReasoning;
 pub use crate::sse::stream_from_fixture;
 pub use crate::telemetry::SseTelemetry;
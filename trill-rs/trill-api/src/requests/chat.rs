@@ -1,8 +1,14 @@
+use crate::common::SamplingParams;
 use crate::error::ApiError;
 use crate::provider::Provider;
+use crate::requests::gbnf::json_schema_to_gbnf;
 use crate::requests::headers::build_conversation_headers;
 use crate::requests::headers::insert_header;
 use crate::requests::headers::subagent_header;
+use crate::requests::text_tool_calls::encode_call;
+use crate::requests::text_tool_calls::encode_output;
+use crate::requests::text_tool_calls::tool_protocol_prompt;
+use crate::sse::ToolCallFormat;
 use trill_protocol::models::ContentItem;
 use trill_protocol::models::FunctionCallOutputContentItem;
 use trill_protocol::models::ReasoningItemContent;
@@ -12,6 +18,84 @@ use http::HeaderMap;
 use serde_json::Value;
 use serde_json::json;
 use std::collections::HashMap;
+use tracing::debug;
+
+/// How a Chat Completions server is asked to follow an output JSON schema.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StructuredOutput {
+    /// OpenAI `response_format` with a `json_schema`, understood by LM
+    /// Studio, vLLM and llama.cpp.
+    #[default]
+    JsonSchema,
+    /// Ollama's `format` field.
+    OllamaFormat,
+    /// A llama.cpp GBNF `grammar` generated from the schema.
+    Grammar,
+    /// The server has no constrained decoding; the schema is not sent.
+    None,
+}
+
+impl StructuredOutput {
+    /// Whether `schema` is enforced by the server under this mode for a
+    /// request that does or does not offer tools. When it is not, callers
+    /// should validate the reply themselves.
+    ///
+    /// Ollama's `format` and a GBNF `grammar` constrain every token, so they
+    /// would keep the model from calling tools; they are only sent with
+    /// requests that offer none.
+    pub fn enforces(self, schema: &Value, with_tools: bool) -> bool {
+        match self {
+            Self::JsonSchema => true,
+            Self::OllamaFormat => !with_tools,
+            Self::Grammar => !with_tools && json_schema_to_gbnf(schema).is_ok(),
+            Self::None => false,
+        }
+    }
+
+    fn apply(self, body: &mut Value, schema: &Value, with_tools: bool) {
+        let Value::Object(body) = body else {
+            return;
+        };
+        if with_tools && matches!(self, Self::OllamaFormat | Self::Grammar) {
+            return;
+        }
+        match self {
+            Self::JsonSchema => {
+                body.insert(
+                    "response_format".to_string(),
+                    json!({
+                        "type": "json_schema",
+                        "json_schema": {
+                            "name": "codex_output_schema",
+                            "schema": schema,
+                            "strict": true,
+                        },
+                    }),
+                );
+            }
+            Self::OllamaFormat => {
+                body.insert("format".to_string(), schema.clone());
+            }
+            Self::Grammar => match json_schema_to_gbnf(schema) {
+                Ok(grammar) => {
+                    body.insert("grammar".to_string(), Value::String(grammar));
+                }
+                Err(err) => debug!("output schema has no GBNF equivalent: {err}"),
+            },
+            Self::None => {}
+        }
+    }
+}
+
+/// Optional Chat Completions request fields a server is known to accept.
+/// Servers differ here, so nothing is sent unless enabled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChatCapabilities {
+    /// Send `tool_choice: "auto"` with tools.
+    pub tool_choice: bool,
+    /// Send `parallel_tool_calls` with tools.
+    pub parallel_tool_calls: bool,
+}
 
 /// Assembled request body plus headers for Chat Completions streaming calls.
 pub struct ChatRequest {
@@ -26,6 +110,12 @@ pub struct ChatRequestBuilder<'a> {
     tools: &'a [Value],
     conversation_id: Option<String>,
     session_source: Option<SessionSource>,
+    sampling: SamplingParams,
+    output_schema: Option<&'a Value>,
+    structured_output: StructuredOutput,
+    capabilities: ChatCapabilities,
+    parallel_tool_calls: bool,
+    tool_call_format: ToolCallFormat,
 }
 
 impl<'a> ChatRequestBuilder<'a> {
@@ -42,6 +132,12 @@ impl<'a> ChatRequestBuilder<'a> {
             tools,
             conversation_id: None,
             session_source: None,
+            sampling: SamplingParams::default(),
+            output_schema: None,
+            structured_output: StructuredOutput::default(),
+            capabilities: ChatCapabilities::default(),
+            parallel_tool_calls: false,
+            tool_call_format: ToolCallFormat::default(),
         }
     }
 
@@ -55,11 +151,61 @@ impl<'a> ChatRequestBuilder<'a> {
         self
     }
 
-    pub fn build(self, _provider: &Provider) -> Result<ChatRequest, ApiError> {
+    pub fn sampling(mut self, sampling: SamplingParams) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Asks for a final message matching `schema`, encoded as `mode`.
+    pub fn output_schema(mut self, schema: Option<&'a Value>, mode: StructuredOutput) -> Self {
+        self.output_schema = schema;
+        self.structured_output = mode;
+        self
+    }
+
+    /// Sends the tool options the server supports; `parallel_tool_calls` is
+    /// only sent when `capabilities` allows it.
+    pub fn tool_options(
+        mut self,
+        capabilities: ChatCapabilities,
+        parallel_tool_calls: bool,
+    ) -> Self {
+        self.capabilities = capabilities;
+        self.parallel_tool_calls = parallel_tool_calls;
+        self
+    }
+
+    /// With a text format, tools are described in the system prompt instead
+    /// of sent as `tools`, and earlier calls and their results are written
+    /// back as assistant and user text in the same format.
+    pub fn tool_call_format(mut self, format: ToolCallFormat) -> Self {
+        self.tool_call_format = format;
+        self
+    }
+
+    pub fn build(self, provider: &Provider) -> Result<ChatRequest, ApiError> {
+        let text_tools = self.tool_call_format.is_text();
         let mut messages = Vec::<Value>::new();
-        messages.push(json!({"role": "system", "content": self.instructions}));
+        if text_tools && !self.tools.is_empty() {
+            let protocol = tool_protocol_prompt(self.tool_call_format, self.tools);
+            messages.push(json!({
+                "role": "system",
+                "content": format!("{}\n\n{protocol}", self.instructions),
+            }));
+        } else {
+            messages.push(json!({"role": "system", "content": self.instructions}));
+        }
 
         let input = self.input;
+        let tool_names: HashMap<&str, &str> = input
+            .iter()
+            .filter_map(|item| match item {
+                ResponseItem::FunctionCall { name, call_id, .. } => {
+                    Some((call_id.as_str(), name.as_str()))
+                }
+                _ => None,
+            })
+            .collect();
         let mut reasoning_by_anchor_index: HashMap<usize, String> = HashMap::new();
         let mut last_emitted_role: Option<&str> = None;
         for item in input {
@@ -198,6 +344,12 @@ impl<'a> ChatRequestBuilder<'a> {
                     }
                     messages.push(msg);
                 }
+                ResponseItem::FunctionCall {
+                    name, arguments, ..
+                } if text_tools => {
+                    let text = encode_call(self.tool_call_format, name, arguments);
+                    push_text_message(&mut messages, "assistant", text);
+                }
                 ResponseItem::FunctionCall {
                     name,
                     arguments,
@@ -230,6 +382,24 @@ impl<'a> ChatRequestBuilder<'a> {
                     });
                     push_tool_call_message(&mut messages, tool_call, reasoning);
                 }
+                ResponseItem::FunctionCallOutput { call_id, output } if text_tools => {
+                    let text = match &output.content_items {
+                        Some(items) => items
+                            .iter()
+                            .filter_map(|item| match item {
+                                FunctionCallOutputContentItem::InputText { text } => {
+                                    Some(text.as_str())
+                                }
+                                FunctionCallOutputContentItem::InputImage { .. } => None,
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                        None => output.content.clone(),
+                    };
+                    let name = tool_names.get(call_id.as_str()).copied();
+                    let text = encode_output(self.tool_call_format, name, &text);
+                    push_text_message(&mut messages, "user", text);
+                }
                 ResponseItem::FunctionCallOutput { call_id, output } => {
                     let content_value = if let Some(items) = &output.content_items {
                         let mapped: Vec<Value> = items
@@ -291,12 +461,26 @@ impl<'a> ChatRequestBuilder<'a> {
             }
         }
 
-        let payload = json!({
+        let tools: &[Value] = if text_tools { &[] } else { self.tools };
+        let mut payload = json!({
             "model": self.model,
             "messages": messages,
             "stream": true,
-            "tools": self.tools,
+            "tools": tools,
         });
+        if !tools.is_empty() {
+            if self.capabilities.tool_choice {
+                payload["tool_choice"] = json!("auto");
+            }
+            if self.capabilities.parallel_tool_calls {
+                payload["parallel_tool_calls"] = json!(self.parallel_tool_calls);
+            }
+        }
+        if let Some(schema) = self.output_schema {
+            self.structured_output
+                .apply(&mut payload, schema, !self.tools.is_empty());
+        }
+        self.sampling.apply_to(&mut payload, &provider.wire);
 
         let mut headers = build_conversation_headers(self.conversation_id);
         if let Some(subagent) = subagent_header(&self.session_source) {
@@ -310,6 +494,22 @@ impl<'a> ChatRequestBuilder<'a> {
     }
 }
 
+/// Appends `text` to the previous message when it has the same role, so calls
+/// and results written as text keep roles alternating.
+fn push_text_message(messages: &mut Vec<Value>, role: &str, text: String) {
+    if let Some(Value::Object(obj)) = messages.last_mut()
+        && obj.get("role").and_then(Value::as_str) == Some(role)
+        && let Some(Value::String(content)) = obj.get_mut("content")
+    {
+        if !content.is_empty() {
+            content.push('\n');
+        }
+        content.push_str(&text);
+        return;
+    }
+    messages.push(json!({"role": role, "content": text}));
+}
+
 fn push_tool_call_message(messages: &mut Vec<Value>, tool_call: Value, reasoning: Option<&str>) {
     // Chat Completions requires that tool calls are grouped into a single assistant message
     // (with `tool_calls: [...]`) followed by tool role responses.
@@ -404,6 +604,120 @@ mod tests {
         );
     }
 
+    #[test]
+    fn merges_sampling_params_and_extra_body() {
+        let sampling = SamplingParams {
+            temperature: Some(0.7),
+            top_p: Some(0.8),
+            top_k: Some(20),
+            min_p: Some(0.0),
+            repeat_penalty: Some(1.05),
+            seed: Some(42),
+            max_tokens: Some(4096),
+            stop: vec!["<|im_end|>".to_string()],
+            extra_body: serde_json::Map::from_iter([
+                ("grammar".to_string(), json!("root ::= \"ok\"")),
+                ("top_k".to_string(), json!(40)),
+            ]),
+        };
+        let req = ChatRequestBuilder::new("qwen2.5-coder", "inst", &[], &[])
+            .sampling(sampling)
+            .build(&provider())
+            .expect("request");
+
+        let body = req.body.as_object().expect("object body");
+        assert_eq!(body["temperature"], json!(0.7));
+        assert_eq!(body["top_p"], json!(0.8));
+        assert_eq!(body["top_k"], json!(40));
+        assert_eq!(body["min_p"], json!(0.0));
+        assert_eq!(body["repeat_penalty"], json!(1.05));
+        assert_eq!(body["seed"], json!(42));
+        assert_eq!(body["max_tokens"], json!(4096));
+        assert_eq!(body["stop"], json!(["<|im_end|>"]));
+        assert_eq!(body["grammar"], json!("root ::= \"ok\""));
+        assert_eq!(body["stream"], json!(true));
+
+        let req = ChatRequestBuilder::new("qwen2.5-coder", "inst", &[], &[])
+            .build(&provider())
+            .expect("request");
+        assert_eq!(req.body.get("temperature"), None);
+        assert_eq!(req.body.get("stop"), None);
+    }
+
+    #[test]
+    fn encodes_output_schema_for_each_structured_output_mode() {
+        let schema = json!({
+            "type": "object",
+            "properties": { "answer": { "type": "string" } },
+            "required": ["answer"],
+        });
+        let build = |mode| {
+            ChatRequestBuilder::new("local", "inst", &[], &[])
+                .output_schema(Some(&schema), mode)
+                .build(&provider())
+                .expect("request")
+                .body
+        };
+
+        let body = build(StructuredOutput::JsonSchema);
+        assert_eq!(
+            body["response_format"],
+            json!({
+                "type": "json_schema",
+                "json_schema": {
+                    "name": "codex_output_schema",
+                    "schema": schema,
+                    "strict": true,
+                },
+            })
+        );
+
+        let body = build(StructuredOutput::OllamaFormat);
+        assert_eq!(body["format"], schema);
+        assert_eq!(body.get("response_format"), None);
+
+        let body = build(StructuredOutput::Grammar);
+        let grammar = body["grammar"].as_str().expect("grammar string");
+        assert!(grammar.starts_with("root ::= ws root-value\n"));
+
+        let body = build(StructuredOutput::None);
+        for key in ["response_format", "format", "grammar"] {
+            assert_eq!(body.get(key), None, "{key}");
+        }
+    }
+
+    #[test]
+    fn sends_only_non_constraining_output_schemas_with_tools() {
+        let schema = json!({
+            "type": "object",
+            "properties": { "answer": { "type": "string" } },
+            "required": ["answer"],
+        });
+        let tools = vec![json!({"type": "function", "function": {"name": "shell"}})];
+        let build = |mode| {
+            ChatRequestBuilder::new("local", "inst", &[], &tools)
+                .output_schema(Some(&schema), mode)
+                .build(&provider())
+                .expect("request")
+                .body
+        };
+
+        assert!(
+            build(StructuredOutput::JsonSchema)
+                .get("response_format")
+                .is_some()
+        );
+        assert!(StructuredOutput::JsonSchema.enforces(&schema, true));
+        for (mode, key) in [
+            (StructuredOutput::OllamaFormat, "format"),
+            (StructuredOutput::Grammar, "grammar"),
+        ] {
+            assert_eq!(build(mode).get(key), None, "{key}");
+            assert!(!mode.enforces(&schema, true), "{key}");
+            assert!(mode.enforces(&schema, false), "{key}");
+        }
+    }
+
     #[test]
     fn groups_consecutive_tool_calls_into_a_single_assistant_message() {
         let prompt_input = vec![
@@ -489,4 +803,106 @@ mod tests {
         assert_eq!(messages[5]["role"], "tool");
         assert_eq!(messages[5]["tool_call_id"], "call-c");
     }
+
+    #[test]
+    fn sends_tool_options_only_when_supported() {
+        let tools = vec![json!({"type": "function", "function": {"name": "shell"}})];
+        let build = |capabilities| {
+            ChatRequestBuilder::new("gpt-test", "inst", &[], &tools)
+                .tool_options(capabilities, true)
+                .build(&provider())
+                .expect("request")
+                .body
+        };
+
+        let body = build(ChatCapabilities::default());
+        assert_eq!(body.get("tool_choice"), None);
+        assert_eq!(body.get("parallel_tool_calls"), None);
+
+        let body = build(ChatCapabilities {
+            tool_choice: true,
+            parallel_tool_calls: true,
+        });
+        assert_eq!(body["tool_choice"], json!("auto"));
+        assert_eq!(body["parallel_tool_calls"], json!(true));
+
+        let without_tools = ChatRequestBuilder::new("gpt-test", "inst", &[], &[])
+            .tool_options(
+                ChatCapabilities {
+                    tool_choice: true,
+                    parallel_tool_calls: true,
+                },
+                true,
+            )
+            .build(&provider())
+            .expect("request")
+            .body;
+        assert_eq!(without_tools.get("tool_choice"), None);
+    }
+
+    #[test]
+    fn writes_tools_and_history_as_text_in_text_mode() {
+        let tools = vec![json!({
+            "type": "function",
+            "function": {"name": "read_file", "parameters": {"type": "object"}}
+        })];
+        let input = vec![
+            ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![ContentItem::InputText {
+                    text: "read a".to_string(),
+                }],
+                end_turn: None,
+            },
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "read_file".to_string(),
+                arguments: r#"{"path":"a"}"#.to_string(),
+                call_id: "call-a".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "call-a".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "hello".to_string(),
+                    ..Default::default()
+                },
+            },
+        ];
+
+        let body = ChatRequestBuilder::new("gpt-test", "inst", &input, &tools)
+            .tool_call_format(ToolCallFormat::Hermes)
+            .tool_options(
+                ChatCapabilities {
+                    tool_choice: true,
+                    parallel_tool_calls: true,
+                },
+                true,
+            )
+            .build(&provider())
+            .expect("request")
+            .body;
+
+        assert_eq!(body["tools"], json!([]));
+        assert_eq!(body.get("tool_choice"), None);
+        let system = body["messages"][0]["content"]
+            .as_str()
+            .expect("system text");
+        assert!(system.starts_with("inst\n\n# Tools"));
+        assert!(system.contains(r#"{"name":"read_file","parameters":{"type":"object"}}"#));
+        assert_eq!(
+            body["messages"][2],
+            json!({
+                "role": "assistant",
+                "content": "<tool_call>\n{\"name\": \"read_file\", \"arguments\": {\"path\":\"a\"}}\n</tool_call>",
+            })
+        );
+        assert_eq!(
+            body["messages"][3],
+            json!({
+                "role": "user",
+                "content": "<tool_response>\n{\"name\": \"read_file\", \"content\": \"hello\"}\n</tool_response>",
+            })
+        );
+    }
 }
@@ -0,0 +1,426 @@
+//! Converts a JSON schema into a llama.cpp GBNF grammar.
+//!
+//! Covers the subset of JSON Schema used for `--output-schema`: objects with
+//! `properties`/`required`, arrays, the scalar types, `enum`/`const`,
+//! `anyOf`/`oneOf` and local `$ref`s. Objects accept exactly the listed
+//! properties, required ones first. String patterns, formats and numeric
+//! bounds are not enforced. Schemas using anything else are rejected so the
+//! caller can fall back to validating the reply instead.
+
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+const PRIMITIVE_RULES: &str = r#"ws ::= [ \t\n]*
+string ::= "\"" ( [^"\\\x7F\x00-\x1F] | "\\" ( ["\\/bfnrt] | "u" [0-9a-fA-F] [0-9a-fA-F] [0-9a-fA-F] [0-9a-fA-F] ) )* "\"" ws
+number ::= "-"? ( [0-9] | [1-9] [0-9]* ) ( "." [0-9]+ )? ( [eE] [-+]? [0-9]+ )? ws
+integer ::= "-"? ( [0-9] | [1-9] [0-9]* ) ws
+boolean ::= ( "true" | "false" ) ws
+null ::= "null" ws
+value ::= object | array | string | number | boolean | null
+object ::= "{" ws ( string ":" ws value ( "," ws string ":" ws value )* )? "}" ws
+array ::= "[" ws ( value ( "," ws value )* )? "]" ws
+"#;
+
+/// Returns a grammar whose `root` rule matches JSON documents that follow
+/// `schema`, or a description of the first unsupported construct.
+pub fn json_schema_to_gbnf(schema: &Value) -> Result<String, String> {
+    let mut converter = Converter {
+        root: schema,
+        rules: BTreeMap::new(),
+        refs: HashMap::new(),
+    };
+    let root = converter.visit(schema, "root")?;
+    let mut grammar = format!("root ::= ws {root}\n");
+    for (name, body) in &converter.rules {
+        grammar.push_str(&format!("{name} ::= {body}\n"));
+    }
+    grammar.push_str(PRIMITIVE_RULES);
+    Ok(grammar)
+}
+
+struct Converter<'a> {
+    root: &'a Value,
+    rules: BTreeMap<String, String>,
+    /// Rule names of the `$ref` paths resolved so far.
+    refs: HashMap<String, String>,
+}
+
+impl<'a> Converter<'a> {
+    /// Returns a grammar expression for `schema`, adding named rules for
+    /// objects, arrays and references along the way.
+    fn visit(&mut self, schema: &'a Value, name: &str) -> Result<String, String> {
+        let schema = match schema {
+            Value::Bool(true) => return Ok("value".to_string()),
+            Value::Object(schema) => schema,
+            other => return Err(format!("unsupported schema {other}")),
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            return self.visit_ref(reference);
+        }
+        if let Some(value) = schema.get("const") {
+            return Ok(literal(value));
+        }
+        if let Some(values) = schema.get("enum") {
+            let values = values
+                .as_array()
+                .ok_or_else(|| "`enum` must be an array".to_string())?;
+            return Ok(alternatives(values.iter().map(literal)));
+        }
+        for keyword in ["anyOf", "oneOf"] {
+            if let Some(variants) = schema.get(keyword) {
+                let variants = variants
+                    .as_array()
+                    .ok_or_else(|| format!("`{keyword}` must be an array"))?;
+                let mut options = Vec::with_capacity(variants.len());
+                for (index, variant) in variants.iter().enumerate() {
+                    options.push(self.visit(variant, &format!("{name}-{index}"))?);
+                }
+                return Ok(alternatives(options.into_iter()));
+            }
+        }
+        for keyword in ["allOf", "not", "if", "patternProperties"] {
+            if schema.contains_key(keyword) {
+                return Err(format!("`{keyword}` is not supported"));
+            }
+        }
+
+        match schema.get("type") {
+            Some(Value::String(kind)) => self.visit_type(schema, kind, name),
+            Some(Value::Array(kinds)) => {
+                let mut options = Vec::with_capacity(kinds.len());
+                for kind in kinds {
+                    let kind = kind
+                        .as_str()
+                        .ok_or_else(|| "`type` entries must be strings".to_string())?;
+                    options.push(self.visit_type(schema, kind, &format!("{name}-{kind}"))?);
+                }
+                Ok(alternatives(options.into_iter()))
+            }
+            Some(other) => Err(format!("unsupported `type` {other}")),
+            None if schema.contains_key("properties") => self.visit_type(schema, "object", name),
+            None => Ok("value".to_string()),
+        }
+    }
+
+    fn visit_type(
+        &mut self,
+        schema: &'a serde_json::Map<String, Value>,
+        kind: &str,
+        name: &str,
+    ) -> Result<String, String> {
+        match kind {
+            "string" | "number" | "integer" | "boolean" | "null" => Ok(kind.to_string()),
+            "array" => {
+                let item = match schema.get("items") {
+                    Some(items) => self.visit(items, &format!("{name}-item"))?,
+                    None => "value".to_string(),
+                };
+                let min_items = schema.get("minItems").and_then(Value::as_u64).unwrap_or(0);
+                let items = if min_items > 0 {
+                    format!("{item} ( \",\" ws {item} )*")
+                } else {
+                    format!("( {item} ( \",\" ws {item} )* )?")
+                };
+                Ok(self.add_rule(name, format!("\"[\" ws {items} \"]\" ws")))
+            }
+            "object" => {
+                let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+                    return Ok("object".to_string());
+                };
+                let required: Vec<&str> = schema
+                    .get("required")
+                    .and_then(Value::as_array)
+                    .map(|required| required.iter().filter_map(Value::as_str).collect())
+                    .unwrap_or_default();
+                let mut required_members = Vec::new();
+                let mut optional_members = Vec::new();
+                for (key, property) in properties {
+                    let value = self.visit(property, &format!("{name}-{}", rule_name(key)))?;
+                    let member = format!("{} ws \":\" ws {value}", literal_string(key));
+                    if required.contains(&key.as_str()) {
+                        required_members.push(member);
+                    } else {
+                        optional_members.push(member);
+                    }
+                }
+                let members = object_members(&required_members, &optional_members);
+                Ok(self.add_rule(name, format!("\"{{\" ws {members} \"}}\" ws")))
+            }
+            other => Err(format!("unsupported `type` \"{other}\"")),
+        }
+    }
+
+    fn visit_ref(&mut self, reference: &str) -> Result<String, String> {
+        let path = reference
+            .strip_prefix("#/")
+            .ok_or_else(|| format!("only local `$ref`s are supported, got {reference}"))?;
+        if let Some(name) = self.refs.get(path) {
+            return Ok(name.clone());
+        }
+        let target = path
+            .split('/')
+            .try_fold(self.root, |node, segment| node.get(segment))
+            .ok_or_else(|| format!("unresolved `$ref` {reference}"))?;
+        // Record the name first so recursive references terminate.
+        let name = self.claim_name(&format!("ref-{path}"));
+        self.refs.insert(path.to_string(), name.clone());
+        let body = self.visit(target, &format!("{name}-def"))?;
+        self.rules.insert(name.clone(), body);
+        Ok(name)
+    }
+
+    fn add_rule(&mut self, name: &str, body: String) -> String {
+        let name = self.claim_name(if name == "root" { "root-value" } else { name });
+        self.rules.insert(name.clone(), body);
+        name
+    }
+
+    /// Reserves a rule name for `name`. Different schema paths can map to the
+    /// same name, e.g. properties `a_b` and `a-b`, so a name that is already
+    /// taken gets a numeric suffix.
+    fn claim_name(&mut self, name: &str) -> String {
+        let base = rule_name(name);
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while self.rules.contains_key(&name) {
+            name = format!("{base}-{suffix}");
+            suffix += 1;
+        }
+        self.rules.insert(name.clone(), String::new());
+        name
+    }
+}
+
+/// Joins object members with commas: required members in order, then each
+/// optional member at most once, in order.
+fn object_members(required: &[String], optional: &[String]) -> String {
+    let optional_tail = |members: &[String]| {
+        members
+            .iter()
+            .map(|member| format!(" ( \",\" ws {member} )?"))
+            .collect::<String>()
+    };
+    if !required.is_empty() {
+        return format!("{}{}", required.join(" \",\" ws "), optional_tail(optional));
+    }
+    if optional.is_empty() {
+        return String::new();
+    }
+    // Without a required member, any optional one may come first.
+    let starts: Vec<String> = (0..optional.len())
+        .map(|start| {
+            format!(
+                "{}{}",
+                optional[start],
+                optional_tail(&optional[start + 1..])
+            )
+        })
+        .collect();
+    format!("( {} )?", starts.join(" | "))
+}
+
+fn alternatives(options: impl Iterator<Item = String>) -> String {
+    let options: Vec<String> = options.collect();
+    match options.as_slice() {
+        [only] => only.clone(),
+        _ => format!("( {} )", options.join(" | ")),
+    }
+}
+
+/// Grammar for exactly the JSON encoding of `value`.
+fn literal(value: &Value) -> String {
+    format!("{} ws", quote(&value.to_string()))
+}
+
+fn literal_string(key: &str) -> String {
+    quote(&Value::String(key.to_string()).to_string())
+}
+
+/// Quotes `text` as a GBNF string literal.
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn rule_name(name: &str) -> String {
+    name.chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '-' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn rules(grammar: &str) -> Vec<&str> {
+        grammar
+            .lines()
+            .take_while(|line| !line.starts_with("ws ::="))
+            .collect()
+    }
+
+    #[test]
+    fn converts_object_with_required_and_optional_members() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "files": { "type": "array", "items": { "type": "string" } },
+                "status": { "enum": ["ok", "failed"] },
+                "summary": { "type": "string" }
+            },
+            "required": ["status", "summary"],
+            "additionalProperties": false
+        });
+
+        let grammar = json_schema_to_gbnf(&schema).expect("supported schema");
+
+        assert_eq!(
+            rules(&grammar),
+            vec![
+                "root ::= ws root-value",
+                r#"root-files ::= "[" ws ( string ( "," ws string )* )? "]" ws"#,
+                r#"root-value ::= "{" ws "\"status\"" ws ":" ws ( "\"ok\"" ws | "\"failed\"" ws ) "," ws "\"summary\"" ws ":" ws string ( "," ws "\"files\"" ws ":" ws root-files )? "}" ws"#,
+            ]
+        );
+        assert!(grammar.contains("\nstring ::= "));
+    }
+
+    #[test]
+    fn resolves_local_refs_and_nullable_types() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "node": { "$ref": "#/$defs/node" }
+            },
+            "required": ["node"],
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": ["string", "null"] },
+                        "child": { "$ref": "#/$defs/node" }
+                    }
+                }
+            }
+        });
+
+        let grammar = json_schema_to_gbnf(&schema).expect("supported schema");
+
+        assert_eq!(
+            rules(&grammar),
+            vec![
+                "root ::= ws root-value",
+                r#"ref--defs-node ::= ref--defs-node-def"#,
+                r#"ref--defs-node-def ::= "{" ws ( "\"child\"" ws ":" ws ref--defs-node ( "," ws "\"name\"" ws ":" ws ( string | null ) )? | "\"name\"" ws ":" ws ( string | null ) )? "}" ws"#,
+                r#"root-value ::= "{" ws "\"node\"" ws ":" ws ref--defs-node "}" ws"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn suffixes_names_that_map_to_the_same_rule() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "a_b": { "type": "array" },
+                "a-b": { "type": "array", "items": { "type": "string" } },
+                "c": { "$ref": "#/$defs/a_b" },
+                "d": { "$ref": "#/$defs/a-b" }
+            },
+            "required": ["a_b", "a-b", "c", "d"],
+            "$defs": {
+                "a_b": { "type": "string" },
+                "a-b": { "type": "integer" }
+            }
+        });
+
+        let grammar = json_schema_to_gbnf(&schema).expect("supported schema");
+
+        assert_eq!(
+            rules(&grammar),
+            vec![
+                "root ::= ws root-value",
+                "ref--defs-a-b ::= string",
+                "ref--defs-a-b-2 ::= integer",
+                r#"root-a-b ::= "[" ws ( string ( "," ws string )* )? "]" ws"#,
+                r#"root-a-b-2 ::= "[" ws ( value ( "," ws value )* )? "]" ws"#,
+                r#"root-value ::= "{" ws "\"a-b\"" ws ":" ws root-a-b "," ws "\"a_b\"" ws ":" ws root-a-b-2 "," ws "\"c\"" ws ":" ws ref--defs-a-b "," ws "\"d\"" ws ":" ws ref--defs-a-b-2 "}" ws"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_the_root_rule_apart_from_a_value_property() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "value": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["value"]
+        });
+
+        let grammar = json_schema_to_gbnf(&schema).expect("supported schema");
+
+        assert_eq!(
+            rules(&grammar),
+            vec![
+                "root ::= ws root-value-2",
+                r#"root-value ::= "[" ws ( string ( "," ws string )* )? "]" ws"#,
+                r#"root-value-2 ::= "{" ws "\"value\"" ws ":" ws root-value "}" ws"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_array_item_rules_apart_from_item_properties() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "object", "properties": { "x": { "type": "string" } } }
+                },
+                "tags_item": { "type": "array" }
+            },
+            "required": ["tags", "tags_item"]
+        });
+
+        let grammar = json_schema_to_gbnf(&schema).expect("supported schema");
+
+        assert_eq!(
+            rules(&grammar),
+            vec![
+                "root ::= ws root-value",
+                r#"root-tags ::= "[" ws ( root-tags-item ( "," ws root-tags-item )* )? "]" ws"#,
+                r#"root-tags-item ::= "{" ws ( "\"x\"" ws ":" ws string )? "}" ws"#,
+                r#"root-tags-item-2 ::= "[" ws ( value ( "," ws value )* )? "]" ws"#,
+                r#"root-value ::= "{" ws "\"tags\"" ws ":" ws root-tags "," ws "\"tags_item\"" ws ":" ws root-tags-item-2 "}" ws"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_keywords() {
+        assert_eq!(
+            json_schema_to_gbnf(&json!({ "allOf": [{ "type": "string" }] })),
+            Err("`allOf` is not supported".to_string())
+        );
+        assert!(json_schema_to_gbnf(&json!({ "$ref": "https://example.com/schema" })).is_err());
+    }
+}
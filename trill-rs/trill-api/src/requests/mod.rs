@@ -1,8 +1,13 @@
 pub mod chat;
+mod gbnf;
 pub(crate) mod headers;
 pub mod responses;
+mod text_tool_calls;
 
 pub use chat::ChatRequest;
 pub use chat::ChatRequestBuilder;
+pub use chat::ChatCapabilities;
+pub use chat::StructuredOutput;
+pub use gbnf::json_schema_to_gbnf;
 pub use responses::ResponsesRequest;
 pub use responses::ResponsesRequestBuilder;
@@ -1,5 +1,6 @@
 use crate::common::Reasoning;
 use crate::common::ResponsesApiRequest;
+use crate::common::SamplingParams;
 use crate::common::TextControls;
 use crate::error::ApiError;
 use crate::provider::Provider;
@@ -41,6 +42,7 @@ pub struct ResponsesRequestBuilder<'a> {
     store_override: Option<bool>,
     headers: HeaderMap,
     compression: Compression,
+    sampling: SamplingParams,
 }
 
 impl<'a> ResponsesRequestBuilder<'a> {
@@ -108,6 +110,11 @@ impl<'a> ResponsesRequestBuilder<'a> {
         self
     }
 
+    pub fn sampling(mut self, sampling: SamplingParams) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
     pub fn build(self, provider: &Provider) -> Result<ResponsesRequest, ApiError> {
         let model = self
             .model
@@ -145,6 +152,7 @@ impl<'a> ResponsesRequestBuilder<'a> {
         if store && provider.is_azure_responses_endpoint() {
             attach_item_ids(&mut body, input);
         }
+        self.sampling.apply_to(&mut body, &provider.wire);
 
         let mut headers = self.headers;
         headers.extend(build_conversation_headers(self.conversation_id));
@@ -260,4 +268,29 @@ mod tests {
             Some(&HeaderValue::from_static("review"))
         );
     }
+
+    #[test]
+    fn sampling_params_use_responses_field_names() {
+        let provider = provider("lmstudio", "http://localhost:1234/v1");
+        let sampling = SamplingParams {
+            temperature: Some(0.2),
+            max_tokens: Some(512),
+            extra_body: serde_json::Map::from_iter([("store".to_string(), Value::Bool(true))]),
+            ..SamplingParams::default()
+        };
+
+        let request = ResponsesRequestBuilder::new("qwen", "inst", &[])
+            .sampling(sampling)
+            .build(&provider)
+            .expect("request");
+
+        assert_eq!(request.body.get("temperature"), Some(&Value::from(0.2)));
+        assert_eq!(
+            request.body.get("max_output_tokens"),
+            Some(&Value::from(512))
+        );
+        assert_eq!(request.body.get("max_tokens"), None);
+        assert_eq!(request.body.get("top_p"), None);
+        assert_eq!(request.body.get("store"), Some(&Value::Bool(true)));
+    }
 }
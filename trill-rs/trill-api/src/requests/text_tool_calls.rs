@@ -0,0 +1,63 @@
+//! Text encoding of tools, calls and results for models that call tools in
+//! their message content. The counterpart parser lives in
+//! `sse::text_tool_calls`.
+
+use crate::sse::ToolCallFormat;
+use serde_json::Value;
+
+/// System prompt section describing `tools` and how to call them.
+pub(crate) fn tool_protocol_prompt(format: ToolCallFormat, tools: &[Value]) -> String {
+    let specs = tools
+        .iter()
+        .map(|tool| tool.get("function").unwrap_or(tool).to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let usage = match format {
+        ToolCallFormat::Native | ToolCallFormat::Hermes => {
+            "To call a function, write a JSON object with its name and arguments inside <tool_call></tool_call> tags:\n<tool_call>\n{\"name\": <function-name>, \"arguments\": <args-json-object>}\n</tool_call>\nResults come back in the next user message inside <tool_response></tool_response> tags."
+        }
+        ToolCallFormat::Mistral => {
+            "To call functions, end your reply with [TOOL_CALLS] followed by a JSON array of calls:\n[TOOL_CALLS] [{\"name\": <function-name>, \"arguments\": <args-json-object>}]\nResults come back in the next user message between [TOOL_RESULTS] and [/TOOL_RESULTS]."
+        }
+        ToolCallFormat::FencedJson => {
+            "To call a function, write a fenced code block tagged tool_call that holds its name and arguments:\n```tool_call\n{\"name\": <function-name>, \"arguments\": <args-json-object>}\n```\nResults come back in the next user message in fenced code blocks tagged tool_result."
+        }
+    };
+    format!(
+        "# Tools\n\nYou may call one or more functions to help with the task. The functions are described by these JSON schemas:\n<tools>\n{specs}\n</tools>\n\n{usage} Stop writing after your calls and wait for the results."
+    )
+}
+
+/// Assistant text for a call to `name` with JSON-encoded `arguments`.
+pub(crate) fn encode_call(format: ToolCallFormat, name: &str, arguments: &str) -> String {
+    let arguments = serde_json::from_str::<Value>(arguments)
+        .unwrap_or_else(|_| Value::String(arguments.into()));
+    // Written by hand so `name` comes first whatever the map ordering.
+    let call = format!(
+        "{{\"name\": {}, \"arguments\": {arguments}}}",
+        Value::from(name)
+    );
+    match format {
+        ToolCallFormat::Native | ToolCallFormat::Hermes => {
+            format!("<tool_call>\n{call}\n</tool_call>")
+        }
+        ToolCallFormat::Mistral => format!("[TOOL_CALLS] [{call}]"),
+        ToolCallFormat::FencedJson => format!("```tool_call\n{call}\n```"),
+    }
+}
+
+/// User text carrying the result of a call to `name`.
+pub(crate) fn encode_output(format: ToolCallFormat, name: Option<&str>, output: &str) -> String {
+    let result = format!(
+        "{{\"name\": {}, \"content\": {}}}",
+        Value::from(name),
+        Value::from(output)
+    );
+    match format {
+        ToolCallFormat::Native | ToolCallFormat::Hermes => {
+            format!("<tool_response>\n{result}\n</tool_response>")
+        }
+        ToolCallFormat::Mistral => format!("[TOOL_RESULTS] {result} [/TOOL_RESULTS]"),
+        ToolCallFormat::FencedJson => format!("```tool_result\n{result}\n```"),
+    }
+}
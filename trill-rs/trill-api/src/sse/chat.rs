@@ -1,6 +1,12 @@
 use crate::common::ResponseEvent;
 use crate::common::ResponseStream;
 use crate::error::ApiError;
+use crate::sse::reasoning_tags::ContentSegment;
+use crate::sse::reasoning_tags::ReasoningTagSplitter;
+use crate::sse::reasoning_tags::ReasoningTags;
+use crate::sse::text_tool_calls::TextSegment;
+use crate::sse::text_tool_calls::TextToolCallParser;
+use crate::sse::text_tool_calls::ToolCallFormat;
 use crate::telemetry::SseTelemetry;
 use trill_client::StreamResponse;
 use trill_protocol::models::ContentItem;
@@ -12,23 +18,41 @@ use futures::StreamExt;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::sync::Arc;
-use std::sync::OnceLock;
 use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::time::Instant;
 use tokio::time::timeout;
 use tracing::debug;
 use tracing::trace;
+use uuid::Uuid;
+
+/// How a provider reports reasoning in a Chat Completions stream.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChatReasoning {
+    /// Content between these tags is reported as reasoning.
+    pub tags: Option<ReasoningTags>,
+    /// Field of `delta` and `message` that carries reasoning text, e.g.
+    /// `reasoning_content`. Defaults to `reasoning`.
+    pub field: Option<String>,
+}
 
 pub(crate) fn spawn_chat_stream(
     stream_response: StreamResponse,
     idle_timeout: Duration,
     telemetry: Option<Arc<dyn SseTelemetry>>,
-    _turn_state: Option<Arc<OnceLock<String>>>,
+    (reasoning, tool_call_format): (ChatReasoning, ToolCallFormat),
 ) -> ResponseStream {
     let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent, ApiError>>(1600);
     tokio::spawn(async move {
-        process_chat_sse(stream_response.bytes, tx_event, idle_timeout, telemetry).await;
+        process_chat_sse(
+            stream_response.bytes,
+            tx_event,
+            idle_timeout,
+            telemetry,
+            reasoning,
+            tool_call_format,
+        )
+        .await;
     });
     ResponseStream { rx_event }
 }
@@ -48,11 +72,18 @@ pub(crate) fn spawn_chat_stream(
 /// Higher-level workflows/tests that wait for completion before issuing subsequent model
 /// calls will then stall, which shows up as "expected N requests, got 1" verification
 /// failures in the mock server.
+///
+/// When `reasoning.tags` is set, content between those tags is reported as
+/// reasoning rather than assistant text. With a text `tool_call_format`,
+/// tool calls written in the content are emitted as function calls once the
+/// message ends.
 pub async fn process_chat_sse<S>(
     stream: S,
     tx_event: mpsc::Sender<Result<ResponseEvent, ApiError>>,
     idle_timeout: Duration,
     telemetry: Option<std::sync::Arc<dyn SseTelemetry>>,
+    reasoning: ChatReasoning,
+    tool_call_format: ToolCallFormat,
 ) where
     S: Stream<Item = Result<bytes::Bytes, trill_client::TransportError>> + Unpin,
 {
@@ -73,13 +104,21 @@ pub async fn process_chat_sse<S>(
     let mut last_tool_call_index: Option<usize> = None;
     let mut assistant_item: Option<ResponseItem> = None;
     let mut reasoning_item: Option<ResponseItem> = None;
+    let reasoning_field = reasoning.field.as_deref().unwrap_or("reasoning");
+    let mut parsers = ContentParsers {
+        splitter: reasoning.tags.map(ReasoningTagSplitter::new),
+        tool_calls: TextToolCallParser::new(tool_call_format),
+        pending_calls: Vec::new(),
+    };
     let mut completed_sent = false;
 
     async fn flush_and_complete(
         tx_event: &mpsc::Sender<Result<ResponseEvent, ApiError>>,
+        parsers: &mut ContentParsers,
         reasoning_item: &mut Option<ResponseItem>,
         assistant_item: &mut Option<ResponseItem>,
     ) {
+        flush_content(tx_event, parsers, reasoning_item, assistant_item).await;
         if let Some(reasoning) = reasoning_item.take() {
             let _ = tx_event
                 .send(Ok(ResponseEvent::OutputItemDone(reasoning)))
@@ -91,6 +130,7 @@ pub async fn process_chat_sse<S>(
                 .send(Ok(ResponseEvent::OutputItemDone(assistant)))
                 .await;
         }
+        emit_text_tool_calls(tx_event, parsers).await;
 
         let _ = tx_event
             .send(Ok(ResponseEvent::Completed {
@@ -114,7 +154,13 @@ pub async fn process_chat_sse<S>(
             }
             Ok(None) => {
                 if !completed_sent {
-                    flush_and_complete(&tx_event, &mut reasoning_item, &mut assistant_item).await;
+                    flush_and_complete(
+                        &tx_event,
+                        &mut parsers,
+                        &mut reasoning_item,
+                        &mut assistant_item,
+                    )
+                    .await;
                 }
                 return;
             }
@@ -136,7 +182,13 @@ pub async fn process_chat_sse<S>(
 
         if data == "[DONE]" || data == "DONE" {
             if !completed_sent {
-                flush_and_complete(&tx_event, &mut reasoning_item, &mut assistant_item).await;
+                flush_and_complete(
+                    &tx_event,
+                    &mut parsers,
+                    &mut reasoning_item,
+                    &mut assistant_item,
+                )
+                .await;
             }
             return;
         }
@@ -158,7 +210,7 @@ pub async fn process_chat_sse<S>(
 
         for choice in choices {
             if let Some(delta) = choice.get("delta") {
-                if let Some(reasoning) = delta.get("reasoning") {
+                if let Some(reasoning) = delta.get(reasoning_field) {
                     if let Some(text) = reasoning.as_str() {
                         append_reasoning_text(&tx_event, &mut reasoning_item, text.to_string())
                             .await;
@@ -175,17 +227,25 @@ pub async fn process_chat_sse<S>(
                     if content.is_array() {
                         for item in content.as_array().unwrap_or(&vec![]) {
                             if let Some(text) = item.get("text").and_then(|t| t.as_str()) {
-                                append_assistant_text(
+                                append_content_text(
                                     &tx_event,
+                                    &mut parsers,
+                                    &mut reasoning_item,
                                     &mut assistant_item,
-                                    text.to_string(),
+                                    text,
                                 )
                                 .await;
                             }
                         }
                     } else if let Some(text) = content.as_str() {
-                        append_assistant_text(&tx_event, &mut assistant_item, text.to_string())
-                            .await;
+                        append_content_text(
+                            &tx_event,
+                            &mut parsers,
+                            &mut reasoning_item,
+                            &mut assistant_item,
+                            text,
+                        )
+                        .await;
                     }
                 }
 
@@ -245,7 +305,7 @@ pub async fn process_chat_sse<S>(
             }
 
             if let Some(message) = choice.get("message")
-                && let Some(reasoning) = message.get("reasoning")
+                && let Some(reasoning) = message.get(reasoning_field)
             {
                 if let Some(text) = reasoning.as_str() {
                     append_reasoning_text(&tx_event, &mut reasoning_item, text.to_string()).await;
@@ -258,6 +318,13 @@ pub async fn process_chat_sse<S>(
 
             let finish_reason = choice.get("finish_reason").and_then(|r| r.as_str());
             if finish_reason == Some("stop") {
+                flush_content(
+                    &tx_event,
+                    &mut parsers,
+                    &mut reasoning_item,
+                    &mut assistant_item,
+                )
+                .await;
                 if let Some(reasoning) = reasoning_item.take() {
                     let _ = tx_event
                         .send(Ok(ResponseEvent::OutputItemDone(reasoning)))
@@ -269,6 +336,7 @@ pub async fn process_chat_sse<S>(
                         .send(Ok(ResponseEvent::OutputItemDone(assistant)))
                         .await;
                 }
+                emit_text_tool_calls(&tx_event, &mut parsers).await;
                 if !completed_sent {
                     let _ = tx_event
                         .send(Ok(ResponseEvent::Completed {
@@ -287,6 +355,13 @@ pub async fn process_chat_sse<S>(
             }
 
             if finish_reason == Some("tool_calls") {
+                flush_content(
+                    &tx_event,
+                    &mut parsers,
+                    &mut reasoning_item,
+                    &mut assistant_item,
+                )
+                .await;
                 if let Some(reasoning) = reasoning_item.take() {
                     let _ = tx_event
                         .send(Ok(ResponseEvent::OutputItemDone(reasoning)))
@@ -315,11 +390,121 @@ pub async fn process_chat_sse<S>(
                     };
                     let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
                 }
+                emit_text_tool_calls(&tx_event, &mut parsers).await;
+            }
+        }
+    }
+}
+
+/// Parsers applied to `delta.content` before it reaches the assistant message.
+struct ContentParsers {
+    splitter: Option<ReasoningTagSplitter>,
+    tool_calls: Option<TextToolCallParser>,
+    /// Calls recovered from the text, emitted once the message is done.
+    pending_calls: Vec<ResponseItem>,
+}
+
+/// Routes a content delta to the assistant message, through the splitter to
+/// the reasoning item when the provider marks reasoning with tags, and
+/// through the tool-call parser when tools are called in text.
+async fn append_content_text(
+    tx_event: &mpsc::Sender<Result<ResponseEvent, ApiError>>,
+    parsers: &mut ContentParsers,
+    reasoning_item: &mut Option<ResponseItem>,
+    assistant_item: &mut Option<ResponseItem>,
+    text: &str,
+) {
+    let segments = match &mut parsers.splitter {
+        Some(splitter) => splitter.push(text),
+        None => vec![ContentSegment::Text(text.to_string())],
+    };
+    append_segments(tx_event, parsers, reasoning_item, assistant_item, segments).await;
+}
+
+/// Emits content the parsers were holding back in case it started a tag.
+async fn flush_content(
+    tx_event: &mpsc::Sender<Result<ResponseEvent, ApiError>>,
+    parsers: &mut ContentParsers,
+    reasoning_item: &mut Option<ResponseItem>,
+    assistant_item: &mut Option<ResponseItem>,
+) {
+    if let Some(splitter) = &mut parsers.splitter {
+        let segments = splitter.finish();
+        append_segments(tx_event, parsers, reasoning_item, assistant_item, segments).await;
+    }
+    if let Some(tool_calls) = &mut parsers.tool_calls {
+        let segments = tool_calls.finish();
+        append_text_segments(
+            tx_event,
+            &mut parsers.pending_calls,
+            assistant_item,
+            segments,
+        )
+        .await;
+    }
+}
+
+async fn append_segments(
+    tx_event: &mpsc::Sender<Result<ResponseEvent, ApiError>>,
+    parsers: &mut ContentParsers,
+    reasoning_item: &mut Option<ResponseItem>,
+    assistant_item: &mut Option<ResponseItem>,
+    segments: Vec<ContentSegment>,
+) {
+    for segment in segments {
+        match segment {
+            ContentSegment::Reasoning(text) => {
+                append_reasoning_text(tx_event, reasoning_item, text).await;
+            }
+            ContentSegment::Text(text) => match &mut parsers.tool_calls {
+                Some(tool_calls) => {
+                    let segments = tool_calls.push(&text);
+                    append_text_segments(
+                        tx_event,
+                        &mut parsers.pending_calls,
+                        assistant_item,
+                        segments,
+                    )
+                    .await;
+                }
+                None => append_assistant_text(tx_event, assistant_item, text).await,
+            },
+        }
+    }
+}
+
+async fn append_text_segments(
+    tx_event: &mpsc::Sender<Result<ResponseEvent, ApiError>>,
+    pending_calls: &mut Vec<ResponseItem>,
+    assistant_item: &mut Option<ResponseItem>,
+    segments: Vec<TextSegment>,
+) {
+    for segment in segments {
+        match segment {
+            // Whitespace around a call is not worth a message of its own.
+            TextSegment::Text(text) if assistant_item.is_none() && text.trim().is_empty() => {}
+            TextSegment::Text(text) => append_assistant_text(tx_event, assistant_item, text).await,
+            TextSegment::ToolCall { name, arguments } => {
+                pending_calls.push(ResponseItem::FunctionCall {
+                    id: None,
+                    name,
+                    arguments,
+                    call_id: format!("call_{}", Uuid::new_v4().simple()),
+                });
             }
         }
     }
 }
 
+async fn emit_text_tool_calls(
+    tx_event: &mpsc::Sender<Result<ResponseEvent, ApiError>>,
+    parsers: &mut ContentParsers,
+) {
+    for item in parsers.pending_calls.drain(..) {
+        let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
+    }
+}
+
 async fn append_assistant_text(
     tx_event: &mpsc::Sender<Result<ResponseEvent, ApiError>>,
     assistant_item: &mut Option<ResponseItem>,
@@ -385,6 +570,7 @@ async fn append_reasoning_text(
 mod tests {
     use super::*;
     use assert_matches::assert_matches;
+    use pretty_assertions::assert_eq;
     use trill_protocol::models::ResponseItem;
     use futures::TryStreamExt;
     use serde_json::json;
@@ -410,6 +596,21 @@ mod tests {
     }
 
     async fn collect_events(body: &str) -> Vec<ResponseEvent> {
+        collect_events_with_reasoning(body, ChatReasoning::default()).await
+    }
+
+    async fn collect_events_with_reasoning(
+        body: &str,
+        reasoning: ChatReasoning,
+    ) -> Vec<ResponseEvent> {
+        collect_events_with_options(body, reasoning, ToolCallFormat::Native).await
+    }
+
+    async fn collect_events_with_options(
+        body: &str,
+        reasoning: ChatReasoning,
+        tool_call_format: ToolCallFormat,
+    ) -> Vec<ResponseEvent> {
         let reader = ReaderStream::new(std::io::Cursor::new(body.to_string()))
             .map_err(|err| trill_client::TransportError::Network(err.to_string()));
         let (tx, mut rx) = mpsc::channel::<Result<ResponseEvent, ApiError>>(16);
@@ -418,6 +619,8 @@ mod tests {
             tx,
             Duration::from_millis(1000),
             None,
+            reasoning,
+            tool_call_format,
         ));
 
         let mut out = Vec::new();
@@ -713,4 +916,120 @@ mod tests {
         }));
         assert_matches!(events.last(), Some(ResponseEvent::Completed { .. }));
     }
+
+    #[tokio::test]
+    async fn routes_think_tagged_content_to_reasoning() {
+        let deltas = ["<thi", "nk>Check the", " units.</th", "ink>\n\n42 m"]
+            .into_iter()
+            .map(|text| json!({ "choices": [{ "delta": { "content": text } }] }));
+        let finish = json!({ "choices": [{ "finish_reason": "stop" }] });
+        let body = build_body(&deltas.chain([finish]).collect::<Vec<_>>());
+
+        let events = collect_events_with_reasoning(
+            &body,
+            ChatReasoning {
+                tags: Some(ReasoningTags::default()),
+                field: None,
+            },
+        )
+        .await;
+
+        let reasoning: String = events
+            .iter()
+            .filter_map(|ev| match ev {
+                ResponseEvent::ReasoningContentDelta { delta, .. } => Some(delta.as_str()),
+                _ => None,
+            })
+            .collect();
+        let text: String = events
+            .iter()
+            .filter_map(|ev| match ev {
+                ResponseEvent::OutputTextDelta(delta) => Some(delta.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(reasoning, "Check the units.");
+        assert_eq!(text, "42 m");
+        assert_matches!(
+            &events[events.len() - 3..],
+            [
+                ResponseEvent::OutputItemDone(ResponseItem::Reasoning { .. }),
+                ResponseEvent::OutputItemDone(ResponseItem::Message { .. }),
+                ResponseEvent::Completed { .. }
+            ]
+        );
+
+        let untagged = collect_events(&body).await;
+        assert!(
+            !untagged
+                .iter()
+                .any(|ev| matches!(ev, ResponseEvent::ReasoningContentDelta { .. }))
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_reasoning_from_configured_field() {
+        let delta = json!({
+            "choices": [{ "delta": { "reasoning_content": "think", "content": "answer" } }]
+        });
+        let finish = json!({ "choices": [{ "finish_reason": "stop" }] });
+        let body = build_body(&[delta, finish]);
+
+        let events = collect_events_with_reasoning(
+            &body,
+            ChatReasoning {
+                tags: None,
+                field: Some("reasoning_content".to_string()),
+            },
+        )
+        .await;
+
+        assert_matches!(
+            &events[..2],
+            [
+                ResponseEvent::OutputItemAdded(ResponseItem::Reasoning { .. }),
+                ResponseEvent::ReasoningContentDelta { delta, .. },
+            ] if delta == "think"
+        );
+        let default_field = collect_events(&body).await;
+        assert!(
+            !default_field
+                .iter()
+                .any(|ev| matches!(ev, ResponseEvent::ReasoningContentDelta { .. }))
+        );
+    }
+
+    #[tokio::test]
+    async fn emits_text_tool_calls_after_the_message() {
+        let deltas = [
+            "Checking.\n<tool_",
+            "call>{\"name\": \"shell\", \"arguments\": {\"command\": [\"ls\"]}}",
+            "</tool_call>",
+        ]
+        .into_iter()
+        .map(|text| json!({ "choices": [{ "delta": { "content": text } }] }));
+        let finish = json!({ "choices": [{ "finish_reason": "stop" }] });
+        let body = build_body(&deltas.chain([finish]).collect::<Vec<_>>());
+
+        let events =
+            collect_events_with_options(&body, ChatReasoning::default(), ToolCallFormat::Hermes)
+                .await;
+
+        let text: String = events
+            .iter()
+            .filter_map(|ev| match ev {
+                ResponseEvent::OutputTextDelta(delta) => Some(delta.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(text, "Checking.\n");
+        assert_matches!(
+            &events[events.len() - 3..],
+            [
+                ResponseEvent::OutputItemDone(ResponseItem::Message { .. }),
+                ResponseEvent::OutputItemDone(ResponseItem::FunctionCall { name, arguments, .. }),
+                ResponseEvent::Completed { .. }
+            ] if name == "shell" && arguments == r#"{"command":["ls"]}"#
+        );
+    }
 }
@@ -1,6 +1,10 @@
 pub mod chat;
+mod reasoning_tags;
 pub mod responses;
+mod text_tool_calls;
 
+pub use reasoning_tags::ReasoningTags;
 pub use responses::process_sse;
 pub use responses::spawn_response_stream;
 pub use responses::stream_from_fixture;
+pub use text_tool_calls::ToolCallFormat;
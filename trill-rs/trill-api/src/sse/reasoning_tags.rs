@@ -0,0 +1,290 @@
+//! Splits reasoning that a model writes inline in Chat Completions content.
+//!
+//! Reasoning models served through LM Studio, llama.cpp or vLLM often stream
+//! their chain of thought as `<think>...</think>` inside `delta.content`
+//! instead of a separate `delta.reasoning` field. [`ReasoningTagSplitter`]
+//! separates that text from the visible answer as the deltas arrive. Once a
+//! tag has been seen it holds back only the few bytes that might be the start
+//! of a tag split across chunks.
+
+/// Delimiters around reasoning written inline in message content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReasoningTags {
+    pub open: String,
+    pub close: String,
+}
+
+impl Default for ReasoningTags {
+    fn default() -> Self {
+        Self {
+            open: "<think>".to_string(),
+            close: "</think>".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ContentSegment {
+    Reasoning(String),
+    Text(String),
+}
+
+/// Streaming splitter for one assistant message.
+///
+/// The opening tag is only recognized before any visible text, so an answer
+/// that talks about the tags themselves is left alone. Chat templates that
+/// put the opening tag in the prompt make the model start mid-reasoning, so
+/// text before a closing tag with no opening tag is reasoning too. Until
+/// either tag shows up the content is held back. Whitespace that directly
+/// follows either tag is dropped.
+#[derive(Debug)]
+pub(crate) struct ReasoningTagSplitter {
+    tags: ReasoningTags,
+    pending: String,
+    in_reasoning: bool,
+    /// Set once the message is known to be answer text from here on.
+    in_answer: bool,
+    seen_text: bool,
+    trim_start: bool,
+}
+
+impl ReasoningTagSplitter {
+    pub(crate) fn new(tags: ReasoningTags) -> Self {
+        Self {
+            tags,
+            pending: String::new(),
+            in_reasoning: false,
+            in_answer: false,
+            seen_text: false,
+            trim_start: false,
+        }
+    }
+
+    /// Feeds one content delta and returns the segments that are now certain.
+    pub(crate) fn push(&mut self, chunk: &str) -> Vec<ContentSegment> {
+        self.pending.push_str(chunk);
+        let mut segments = Vec::new();
+        loop {
+            if self.in_reasoning {
+                let close = self.tags.close.as_str();
+                let found = (!close.is_empty())
+                    .then(|| self.pending.find(close))
+                    .flatten();
+                let Some(pos) = found else {
+                    let ready = self.pending.len() - partial_tag_suffix(&self.pending, close);
+                    let ready: String = self.pending.drain(..ready).collect();
+                    self.emit(&mut segments, ready);
+                    return segments;
+                };
+                self.end_reasoning(&mut segments, pos);
+                continue;
+            }
+            if self.in_answer {
+                let rest = std::mem::take(&mut self.pending);
+                self.emit(&mut segments, rest);
+                return segments;
+            }
+
+            let open = find_tag(&self.pending, &self.tags.open);
+            let close = find_tag(&self.pending, &self.tags.close);
+            if let Some(close) = close.filter(|&close| open.is_none_or(|open| close < open)) {
+                // A bare closing tag ends reasoning that started in the prompt.
+                self.in_reasoning = true;
+                self.trim_start = true;
+                self.end_reasoning(&mut segments, close);
+                continue;
+            }
+            let Some(open) = open else {
+                if self.tags.open.is_empty() && self.tags.close.is_empty() {
+                    self.in_answer = true;
+                    continue;
+                }
+                return segments;
+            };
+            if !self.pending[..open].trim().is_empty() {
+                // Visible text came first; the tag is part of the answer.
+                self.in_answer = true;
+                continue;
+            }
+            self.pending.drain(..open + self.tags.open.len());
+            self.in_reasoning = true;
+            self.trim_start = true;
+        }
+    }
+
+    /// Flushes whatever is still held back at the end of the message.
+    pub(crate) fn finish(&mut self) -> Vec<ContentSegment> {
+        let mut segments = Vec::new();
+        let rest = std::mem::take(&mut self.pending);
+        self.emit(&mut segments, rest);
+        segments
+    }
+
+    /// Emits the reasoning before the closing tag at `pos` and switches to
+    /// answer text.
+    fn end_reasoning(&mut self, segments: &mut Vec<ContentSegment>, pos: usize) {
+        let before = self.pending[..pos].to_string();
+        self.pending.drain(..pos + self.tags.close.len());
+        self.emit(segments, before);
+        self.in_reasoning = false;
+        self.in_answer = true;
+        self.trim_start = true;
+    }
+
+    fn emit(&mut self, segments: &mut Vec<ContentSegment>, text: String) {
+        let text = if self.trim_start {
+            let trimmed = text.trim_start();
+            if trimmed.is_empty() {
+                return;
+            }
+            self.trim_start = false;
+            trimmed.to_string()
+        } else {
+            text
+        };
+        if text.is_empty() {
+            return;
+        }
+        if self.in_reasoning {
+            segments.push(ContentSegment::Reasoning(text));
+        } else {
+            // Leading whitespace before the opening tag is not visible text.
+            if !self.seen_text && text.trim().is_empty() {
+                return;
+            }
+            self.seen_text = true;
+            segments.push(ContentSegment::Text(text));
+        }
+    }
+}
+
+fn find_tag(text: &str, tag: &str) -> Option<usize> {
+    if tag.is_empty() { None } else { text.find(tag) }
+}
+
+/// Length of the longest suffix of `text` that is a proper prefix of `tag`.
+pub(crate) fn partial_tag_suffix(text: &str, tag: &str) -> usize {
+    (1..tag.len().min(text.len() + 1))
+        .rev()
+        .find(|&len| {
+            let start = text.len() - len;
+            text.is_char_boundary(start) && tag.is_char_boundary(len) && text[start..] == tag[..len]
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn split(chunks: &[&str]) -> Vec<ContentSegment> {
+        let mut splitter = ReasoningTagSplitter::new(ReasoningTags::default());
+        let mut segments = Vec::new();
+        for chunk in chunks {
+            segments.extend(splitter.push(chunk));
+        }
+        segments.extend(splitter.finish());
+        merge(segments)
+    }
+
+    /// Joins adjacent segments of the same kind so tests can ignore how the
+    /// input happened to be chunked.
+    fn merge(segments: Vec<ContentSegment>) -> Vec<ContentSegment> {
+        let mut merged: Vec<ContentSegment> = Vec::new();
+        for segment in segments {
+            match (merged.last_mut(), segment) {
+                (Some(ContentSegment::Reasoning(prev)), ContentSegment::Reasoning(next))
+                | (Some(ContentSegment::Text(prev)), ContentSegment::Text(next)) => {
+                    prev.push_str(&next);
+                }
+                (_, segment) => merged.push(segment),
+            }
+        }
+        merged
+    }
+
+    fn reasoning(text: &str) -> ContentSegment {
+        ContentSegment::Reasoning(text.to_string())
+    }
+
+    fn text(text: &str) -> ContentSegment {
+        ContentSegment::Text(text.to_string())
+    }
+
+    #[test]
+    fn splits_reasoning_from_answer() {
+        assert_eq!(
+            split(&["<think>\nLet me check.\n</think>\n\nThe answer is 4."]),
+            vec![reasoning("Let me check.\n"), text("The answer is 4.")]
+        );
+    }
+
+    #[test]
+    fn handles_tags_split_across_chunks() {
+        let input = "<think>plan it</think>done";
+        let expected = vec![reasoning("plan it"), text("done")];
+        for size in 1..input.len() {
+            let chunks: Vec<&str> = input
+                .as_bytes()
+                .chunks(size)
+                .map(|chunk| std::str::from_utf8(chunk).expect("ascii input"))
+                .collect();
+            assert_eq!(split(&chunks), expected, "chunk size {size}");
+        }
+    }
+
+    #[test]
+    fn passes_through_content_without_tags() {
+        assert_eq!(split(&["Hello", " <", "world"]), vec![text("Hello <world")]);
+        assert_eq!(split(&["<b>bold</b>"]), vec![text("<b>bold</b>")]);
+    }
+
+    #[test]
+    fn ignores_tags_after_visible_text() {
+        assert_eq!(
+            split(&["Use ", "<think>", " to wrap reasoning</think>."]),
+            vec![text("Use <think> to wrap reasoning</think>.")]
+        );
+    }
+
+    #[test]
+    fn flushes_unterminated_reasoning() {
+        assert_eq!(
+            split(&["<think>still going</th"]),
+            vec![reasoning("still going</th")]
+        );
+        assert_eq!(split(&["<thi"]), vec![text("<thi")]);
+    }
+
+    #[test]
+    fn treats_text_before_a_bare_closing_tag_as_reasoning() {
+        assert_eq!(
+            split(&["\nThe user wants", " a sum.\n</th", "ink>\n\n4"]),
+            vec![reasoning("The user wants a sum.\n"), text("4")]
+        );
+        assert_eq!(
+            split(&["Überlegung</think>Antwort"]),
+            vec![reasoning("Überlegung"), text("Antwort")]
+        );
+    }
+
+    #[test]
+    fn holds_back_text_until_a_tag_or_the_end() {
+        let mut splitter = ReasoningTagSplitter::new(ReasoningTags::default());
+        assert_eq!(splitter.push("maybe reasoning"), Vec::new());
+        assert_eq!(splitter.finish(), vec![text("maybe reasoning")]);
+    }
+
+    #[test]
+    fn supports_custom_tags() {
+        let mut splitter = ReasoningTagSplitter::new(ReasoningTags {
+            open: "<|begin_of_thought|>".to_string(),
+            close: "<|end_of_thought|>".to_string(),
+        });
+        let mut segments = splitter.push("<|begin_of_thought|>hmm<|end_of_");
+        segments.extend(splitter.push("thought|>ok"));
+        segments.extend(splitter.finish());
+        assert_eq!(merge(segments), vec![reasoning("hmm"), text("ok")]);
+    }
+}
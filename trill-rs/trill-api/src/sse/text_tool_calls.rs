@@ -0,0 +1,322 @@
+//! Recovers tool calls that a model writes as text in Chat Completions content.
+//!
+//! Models without a tool-call chat template can still call tools when the
+//! system prompt describes a text protocol (see
+//! [`crate::requests::ChatRequestBuilder::tool_call_format`]). They then
+//! write Hermes `<tool_call>{...}</tool_call>` blocks, Mistral
+//! `[TOOL_CALLS] [...]` or fenced JSON into `delta.content`.
+//! [`TextToolCallParser`] pulls those blocks out of the streamed text as they
+//! arrive, holding back only what might still turn into a call.
+
+use crate::sse::reasoning_tags::partial_tag_suffix;
+use serde_json::Value;
+
+/// How tool calls are exchanged with a Chat Completions server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToolCallFormat {
+    /// The server's own `tools` / `delta.tool_calls` support.
+    #[default]
+    Native,
+    /// `<tool_call>{"name": ..., "arguments": {...}}</tool_call>`, as used by
+    /// Hermes, Qwen and many fine-tunes.
+    Hermes,
+    /// `[TOOL_CALLS] [{"name": ..., "arguments": {...}}]` at the end of the
+    /// message, as used by Mistral models.
+    Mistral,
+    /// A fenced ```` ```tool_call ```` (or ```` ```json ````) block holding
+    /// `{"name": ..., "arguments": {...}}`.
+    FencedJson,
+}
+
+impl ToolCallFormat {
+    /// Whether tools are described in the prompt instead of sent as `tools`.
+    pub fn is_text(self) -> bool {
+        self != Self::Native
+    }
+
+    /// Opening marker, and the closing one for formats whose block does not
+    /// run to the end of the message.
+    fn markers(self) -> Option<(&'static str, Option<&'static str>)> {
+        match self {
+            Self::Native => None,
+            Self::Hermes => Some(("<tool_call>", Some("</tool_call>"))),
+            Self::Mistral => Some(("[TOOL_CALLS]", None)),
+            Self::FencedJson => Some(("```", Some("```"))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TextSegment {
+    Text(String),
+    ToolCall { name: String, arguments: String },
+}
+
+/// Streaming parser for one assistant message.
+///
+/// Blocks that do not hold a well-formed call are passed through as text,
+/// markers included, so ordinary code fences survive.
+#[derive(Debug)]
+pub(crate) struct TextToolCallParser {
+    format: ToolCallFormat,
+    open: &'static str,
+    close: Option<&'static str>,
+    pending: String,
+    in_block: bool,
+}
+
+impl TextToolCallParser {
+    /// Returns `None` for [`ToolCallFormat::Native`], which needs no parsing.
+    pub(crate) fn new(format: ToolCallFormat) -> Option<Self> {
+        let (open, close) = format.markers()?;
+        Some(Self {
+            format,
+            open,
+            close,
+            pending: String::new(),
+            in_block: false,
+        })
+    }
+
+    /// Feeds one content delta and returns the segments that are now certain.
+    pub(crate) fn push(&mut self, chunk: &str) -> Vec<TextSegment> {
+        self.pending.push_str(chunk);
+        let mut segments = Vec::new();
+        loop {
+            if !self.in_block {
+                let Some(pos) = self.pending.find(self.open) else {
+                    let ready = self.pending.len() - partial_tag_suffix(&self.pending, self.open);
+                    let ready: String = self.pending.drain(..ready).collect();
+                    push_text(&mut segments, ready);
+                    return segments;
+                };
+                let before: String = self.pending.drain(..pos + self.open.len()).collect();
+                push_text(&mut segments, before[..pos].to_string());
+                self.in_block = true;
+                continue;
+            }
+
+            // Mistral calls run to the end of the message.
+            let Some(close) = self.close else {
+                return segments;
+            };
+            let Some(pos) = self.pending.find(close) else {
+                return segments;
+            };
+            let block: String = self.pending.drain(..pos + close.len()).collect();
+            self.in_block = false;
+            self.push_block(&mut segments, &block[..pos], true);
+        }
+    }
+
+    /// Flushes whatever is still held back at the end of the message. An
+    /// unterminated Hermes block is still parsed, since models often stop
+    /// right after the JSON.
+    pub(crate) fn finish(&mut self) -> Vec<TextSegment> {
+        let mut segments = Vec::new();
+        let rest = std::mem::take(&mut self.pending);
+        if self.in_block {
+            self.in_block = false;
+            self.push_block(&mut segments, &rest, false);
+        } else {
+            push_text(&mut segments, rest);
+        }
+        segments
+    }
+
+    fn push_block(&self, segments: &mut Vec<TextSegment>, body: &str, closed: bool) {
+        let calls = match self.format {
+            ToolCallFormat::Native => None,
+            ToolCallFormat::Hermes => parse_calls(body),
+            ToolCallFormat::Mistral => parse_mistral_calls(body),
+            ToolCallFormat::FencedJson if closed => parse_fenced_call(body),
+            ToolCallFormat::FencedJson => None,
+        };
+        match calls {
+            Some(calls) => {
+                segments.extend(
+                    calls
+                        .into_iter()
+                        .map(|(name, arguments)| TextSegment::ToolCall { name, arguments }),
+                );
+            }
+            None => {
+                let close = if closed {
+                    self.close.unwrap_or_default()
+                } else {
+                    ""
+                };
+                push_text(segments, format!("{}{body}{close}", self.open));
+            }
+        }
+    }
+}
+
+fn push_text(segments: &mut Vec<TextSegment>, text: String) {
+    if text.is_empty() {
+        return;
+    }
+    if let Some(TextSegment::Text(last)) = segments.last_mut() {
+        last.push_str(&text);
+    } else {
+        segments.push(TextSegment::Text(text));
+    }
+}
+
+/// Parses one call object or an array of them.
+fn parse_calls(text: &str) -> Option<Vec<(String, String)>> {
+    match serde_json::from_str::<Value>(text.trim()).ok()? {
+        Value::Array(items) => {
+            let calls = items.iter().map(parse_call).collect::<Option<Vec<_>>>()?;
+            (!calls.is_empty()).then_some(calls)
+        }
+        value => parse_call(&value).map(|call| vec![call]),
+    }
+}
+
+fn parse_call(value: &Value) -> Option<(String, String)> {
+    let name = value.get("name")?.as_str()?.to_string();
+    let arguments = match value.get("arguments").or_else(|| value.get("parameters")) {
+        Some(Value::String(arguments)) => arguments.clone(),
+        Some(arguments) => arguments.to_string(),
+        None => "{}".to_string(),
+    };
+    Some((name, arguments))
+}
+
+/// Accepts both `[TOOL_CALLS] [{...}]` and the newer
+/// `[TOOL_CALLS]name[ARGS]{...}` form, repeated for each call.
+fn parse_mistral_calls(body: &str) -> Option<Vec<(String, String)>> {
+    let mut calls = Vec::new();
+    for part in body.split("[TOOL_CALLS]") {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+        if let Some(parsed) = parse_calls(part) {
+            calls.extend(parsed);
+        } else {
+            let (name, arguments) = part.split_once("[ARGS]")?;
+            let arguments = arguments.trim();
+            serde_json::from_str::<Value>(arguments).ok()?;
+            calls.push((name.trim().to_string(), arguments.to_string()));
+        }
+    }
+    (!calls.is_empty()).then_some(calls)
+}
+
+fn parse_fenced_call(body: &str) -> Option<Vec<(String, String)>> {
+    let (info, json) = body.split_once('\n')?;
+    if !matches!(info.trim(), "" | "json" | "tool_call") {
+        return None;
+    }
+    let value: Value = serde_json::from_str(json.trim()).ok()?;
+    // Plain JSON examples are left alone unless they look like a call.
+    value.get("arguments")?;
+    parse_call(&value).map(|call| vec![call])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn parse(format: ToolCallFormat, chunks: &[&str]) -> Vec<TextSegment> {
+        let mut parser = TextToolCallParser::new(format).expect("text format");
+        let mut segments = Vec::new();
+        for chunk in chunks {
+            for segment in parser.push(chunk) {
+                match (segments.last_mut(), segment) {
+                    (Some(TextSegment::Text(last)), TextSegment::Text(text)) => {
+                        last.push_str(&text)
+                    }
+                    (_, segment) => segments.push(segment),
+                }
+            }
+        }
+        segments.extend(parser.finish());
+        segments
+    }
+
+    fn call(name: &str, arguments: &str) -> TextSegment {
+        TextSegment::ToolCall {
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+        }
+    }
+
+    #[test]
+    fn extracts_hermes_calls_split_across_chunks() {
+        let segments = parse(
+            ToolCallFormat::Hermes,
+            &[
+                "Let me look.\n<tool",
+                "_call>\n{\"name\": \"shell\", \"arguments\": {\"command\": [\"ls\"]}}\n</tool_",
+                "call>\n<tool_call>{\"name\": \"read_file\", \"arguments\": {\"path\": \"a\"}}",
+            ],
+        );
+
+        assert_eq!(
+            segments,
+            vec![
+                TextSegment::Text("Let me look.\n".to_string()),
+                call("shell", r#"{"command":["ls"]}"#),
+                TextSegment::Text("\n".to_string()),
+                call("read_file", r#"{"path":"a"}"#),
+            ]
+        );
+    }
+
+    #[test]
+    fn extracts_mistral_calls_in_both_forms() {
+        let segments = parse(
+            ToolCallFormat::Mistral,
+            &[r#"[TOOL_CALLS] [{"name": "shell", "arguments": {"command": ["pwd"]}}]"#],
+        );
+        assert_eq!(segments, vec![call("shell", r#"{"command":["pwd"]}"#)]);
+
+        let segments = parse(
+            ToolCallFormat::Mistral,
+            &[r#"[TOOL_CALLS]shell[ARGS]{"command": ["pwd"]}[TOOL_CALLS]plan[ARGS]{}"#],
+        );
+        assert_eq!(
+            segments,
+            vec![call("shell", r#"{"command": ["pwd"]}"#), call("plan", "{}"),]
+        );
+    }
+
+    #[test]
+    fn fenced_calls_leave_other_code_blocks_alone() {
+        let segments = parse(
+            ToolCallFormat::FencedJson,
+            &[
+                "Example:\n```rust\nfn main() {}\n```\n",
+                "```tool_call\n{\"name\": \"shell\", \"arguments\": {}}\n``",
+                "`",
+            ],
+        );
+
+        assert_eq!(
+            segments,
+            vec![
+                TextSegment::Text("Example:\n```rust\nfn main() {}\n```\n".to_string()),
+                call("shell", "{}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_blocks_stay_text() {
+        let segments = parse(
+            ToolCallFormat::Hermes,
+            &["<tool_call>not json</tool_call> ok"],
+        );
+
+        assert_eq!(
+            segments,
+            vec![TextSegment::Text(
+                "<tool_call>not json</tool_call> ok".to_string()
+            )]
+        );
+    }
+}
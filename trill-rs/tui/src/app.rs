@@ -7,6 +7,7 @@ use crate::app_event::WindowsSandboxEnableMode;
 use crate::app_event::WindowsSandboxFallbackReason;
 use crate::app_event_sender::AppEventSender;
 use crate::bottom_pane::ApprovalRequest;
+use crate::bottom_pane::mcp_sampling_lines;
 use crate::bottom_pane::FeedbackAudience;
 use crate::bottom_pane::SelectionItem;
 use crate::bottom_pane::SelectionViewParams;
@@ -34,6 +35,7 @@ use crate::tui::TuiEvent;
 use crate::update_action::UpdateAction;
 use trill_ansi_escape::ansi_escape_line;
 use trill_app_server_protocol::ConfigLayerSource;
+use trill_common::oss::detect_local_server_wire_api;
 use trill_core::AuthManager;
 use trill_core::CodexAuth;
 use trill_core::ThreadManager;
@@ -618,13 +620,17 @@ impl App {
         let mut overrides = self.harness_overrides.clone();
         overrides.cwd = Some(cwd.clone());
         let cwd_display = cwd.display().to_string();
-        ConfigBuilder::default()
+        let mut config = ConfigBuilder::default()
             .trill_home(self.config.trill_home.clone())
             .cli_overrides(self.cli_kv_overrides.clone())
             .harness_overrides(overrides)
             .build()
             .await
-            .wrap_err_with(|| format!("Failed to rebuild config for cwd {cwd_display}"))
+            .wrap_err_with(|| format!("Failed to rebuild config for cwd {cwd_display}"))?;
+        if let Err(err) = detect_local_server_wire_api(&mut config).await {
+            tracing::warn!(?err, "Failed to detect local server wire API");
+        }
+        Ok(config)
     }
 
     fn apply_runtime_policy_overrides(&mut self, config: &mut Config) {
@@ -1583,6 +1589,12 @@ impl App {
             AppEvent::OpenReasoningPopup { model } => {
                 self.chat_widget.open_reasoning_popup(model);
             }
+            AppEvent::OpenUndoFilesPopup { snapshot } => {
+                self.chat_widget.open_undo_files_popup(snapshot);
+            }
+            AppEvent::UndoTurns { num_turns, paths } => {
+                self.apply_undo(num_turns, paths);
+            }
             AppEvent::OpenAllModelsPopup { models } => {
                 self.chat_widget.open_all_models_popup(models);
             }
@@ -2206,6 +2218,28 @@ impl App {
                         "E L I C I T A T I O N".to_string(),
                     ));
                 }
+                ApprovalRequest::McpSampling {
+                    server_name,
+                    model,
+                    max_tokens,
+                    system_prompt,
+                    messages,
+                    ..
+                } => {
+                    let _ = tui.enter_alt_screen();
+                    let paragraph = Paragraph::new(mcp_sampling_lines(
+                        &server_name,
+                        &model,
+                        max_tokens,
+                        system_prompt.as_deref(),
+                        &messages,
+                    ))
+                    .wrap(Wrap { trim: false });
+                    self.overlay = Some(Overlay::new_static_with_renderables(
+                        vec![Box::new(paragraph)],
+                        "S A M P L I N G".to_string(),
+                    ));
+                }
             },
         }
         Ok(AppRunControl::Continue)
@@ -37,6 +37,7 @@ use trill_core::protocol::CodexErrorInfo;
 use trill_core::protocol::ErrorEvent;
 use trill_core::protocol::EventMsg;
 use trill_core::protocol::Op;
+use trill_core::protocol::UndoCompletedEvent;
 use trill_protocol::ThreadId;
 use trill_protocol::user_input::TextElement;
 use color_eyre::eyre::Result;
@@ -217,6 +218,41 @@ impl App {
         }
     }
 
+    /// Request an undo of the last `num_turns` turns picked from `/undo`.
+    ///
+    /// Restoring every file also drops the turns from the thread, so this stages the same
+    /// `pending_rollback` guard as a backtrack and trims the transcript once core emits
+    /// `ThreadRolledBack`. Per-file restores leave the conversation alone.
+    pub(crate) fn apply_undo(&mut self, num_turns: u32, paths: Vec<PathBuf>) {
+        if !paths.is_empty() {
+            self.chat_widget.submit_op(Op::UndoTurns { num_turns, paths });
+            return;
+        }
+
+        if self.backtrack.pending_rollback.is_some() {
+            self.chat_widget
+                .add_error_message("Backtrack rollback already in progress.".to_string());
+            return;
+        }
+
+        let user_total = user_count(&self.transcript_cells);
+        let nth_user_message =
+            user_total.saturating_sub(usize::try_from(num_turns).unwrap_or(usize::MAX));
+        self.backtrack.pending_rollback = Some(PendingBacktrackRollback {
+            selection: BacktrackSelection {
+                nth_user_message,
+                prefill: String::new(),
+                text_elements: Vec::new(),
+                local_image_paths: Vec::new(),
+            },
+            thread_id: self.chat_widget.thread_id(),
+        });
+        self.chat_widget.submit_op(Op::UndoTurns {
+            num_turns,
+            paths: Vec::new(),
+        });
+    }
+
     /// Open transcript overlay (enters alternate screen and shows full transcript).
     pub(crate) fn open_transcript_overlay(&mut self, tui: &mut tui::Tui) {
         let _ = tui.enter_alt_screen();
@@ -461,6 +497,10 @@ impl App {
                 // Core rejected the rollback; clear the guard so the user can retry.
                 self.backtrack.pending_rollback = None;
             }
+            EventMsg::UndoCompleted(UndoCompletedEvent { success: false, .. }) => {
+                // A failed undo never rolls the thread back.
+                self.backtrack.pending_rollback = None;
+            }
             _ => {}
         }
     }
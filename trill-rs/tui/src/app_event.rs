@@ -24,6 +24,7 @@ use crate::history_cell::HistoryCell;
 use trill_core::features::Feature;
 use trill_core::protocol::AskForApproval;
 use trill_core::protocol::SandboxPolicy;
+use trill_core::protocol::UndoSnapshot;
 use trill_protocol::config_types::CollaborationModeMask;
 use trill_protocol::config_types::Personality;
 use trill_protocol::openai_models::ReasoningEffort;
@@ -144,6 +145,17 @@ pub(crate) enum AppEvent {
         model: ModelPreset,
     },
 
+    /// Open the per-file picker for an undo snapshot.
+    OpenUndoFilesPopup {
+        snapshot: UndoSnapshot,
+    },
+
+    /// Undo the last `num_turns` turns, or only restore `paths` when non-empty.
+    UndoTurns {
+        num_turns: u32,
+        paths: Vec<PathBuf>,
+    },
+
     /// Open the full model picker (non-auto models).
     OpenAllModelsPopup {
         models: Vec<ModelPreset>,
@@ -23,11 +23,13 @@ use trill_core::protocol::ExecPolicyAmendment;
 use trill_core::protocol::FileChange;
 use trill_core::protocol::Op;
 use trill_core::protocol::ReviewDecision;
+use trill_protocol::approvals::McpSamplingMessage;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
 use mcp_types::RequestId;
+use mcp_types::Role;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::Stylize;
@@ -56,6 +58,14 @@ pub(crate) enum ApprovalRequest {
         request_id: RequestId,
         message: String,
     },
+    McpSampling {
+        server_name: String,
+        request_id: RequestId,
+        model: String,
+        max_tokens: u32,
+        system_prompt: Option<String>,
+        messages: Vec<McpSamplingMessage>,
+    },
 }
 
 /// Modal overlay asking the user to approve or deny one or more requests.
@@ -123,6 +133,10 @@ impl ApprovalOverlay {
                 elicitation_options(),
                 format!("{server_name} needs your approval."),
             ),
+            ApprovalVariant::McpSampling { server_name, .. } => (
+                sampling_options(),
+                format!("{server_name} wants to use the model. Allow it?"),
+            ),
         };
 
         let header = Box::new(ColumnRenderable::with([
@@ -183,6 +197,15 @@ impl ApprovalOverlay {
                 ) => {
                     self.handle_elicitation_decision(server_name, request_id, *decision);
                 }
+                (
+                    ApprovalVariant::McpSampling {
+                        server_name,
+                        request_id,
+                    },
+                    ApprovalDecision::Review(decision),
+                ) => {
+                    self.handle_sampling_decision(server_name, request_id, decision.clone());
+                }
                 _ => {}
             }
         }
@@ -221,6 +244,20 @@ impl ApprovalOverlay {
             }));
     }
 
+    fn handle_sampling_decision(
+        &self,
+        server_name: &str,
+        request_id: &RequestId,
+        decision: ReviewDecision,
+    ) {
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::ResolveMcpSampling {
+                server_name: server_name.to_string(),
+                request_id: request_id.clone(),
+                decision,
+            }));
+    }
+
     fn advance_queue(&mut self) {
         if let Some(next) = self.queue.pop() {
             self.set_current(next);
@@ -296,6 +333,12 @@ impl BottomPaneView for ApprovalOverlay {
                         ElicitationAction::Cancel,
                     );
                 }
+                ApprovalVariant::McpSampling {
+                    server_name,
+                    request_id,
+                } => {
+                    self.handle_sampling_decision(server_name, request_id, ReviewDecision::Denied);
+                }
             }
         }
         self.queue.clear();
@@ -405,10 +448,68 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     header: Box::new(header),
                 }
             }
+            ApprovalRequest::McpSampling {
+                server_name,
+                request_id,
+                model,
+                max_tokens,
+                system_prompt,
+                messages,
+            } => {
+                let header = Paragraph::new(mcp_sampling_lines(
+                    &server_name,
+                    &model,
+                    max_tokens,
+                    system_prompt.as_deref(),
+                    &messages,
+                ))
+                .wrap(Wrap { trim: false });
+                Self {
+                    variant: ApprovalVariant::McpSampling {
+                        server_name,
+                        request_id,
+                    },
+                    header: Box::new(header),
+                }
+            }
         }
     }
 }
 
+/// Describes an MCP sampling request: the server, the model it will run on,
+/// and the messages it wants completed.
+pub(crate) fn mcp_sampling_lines(
+    server_name: &str,
+    model: &str,
+    max_tokens: u32,
+    system_prompt: Option<&str>,
+    messages: &[McpSamplingMessage],
+) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(vec!["Server: ".into(), server_name.to_string().bold()]),
+        Line::from(vec![
+            "Model: ".into(),
+            model.to_string().bold(),
+            format!(" (max {max_tokens} tokens)").dim(),
+        ]),
+    ];
+    if let Some(system_prompt) = system_prompt.filter(|prompt| !prompt.is_empty()) {
+        lines.push(Line::from(vec![
+            "System: ".into(),
+            system_prompt.to_string().italic(),
+        ]));
+    }
+    lines.push(Line::from(""));
+    for message in messages {
+        let role = match message.role {
+            Role::User => "user: ",
+            Role::Assistant => "assistant: ",
+        };
+        lines.push(Line::from(vec![role.dim(), message.text.clone().into()]));
+    }
+    lines
+}
+
 #[derive(Clone)]
 enum ApprovalVariant {
     Exec {
@@ -423,6 +524,10 @@ enum ApprovalVariant {
         server_name: String,
         request_id: RequestId,
     },
+    McpSampling {
+        server_name: String,
+        request_id: RequestId,
+    },
 }
 
 #[derive(Clone)]
@@ -536,6 +641,29 @@ fn elicitation_options() -> Vec<ApprovalOption> {
     ]
 }
 
+fn sampling_options() -> Vec<ApprovalOption> {
+    vec![
+        ApprovalOption {
+            label: "Yes, run this request".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Approved),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+        },
+        ApprovalOption {
+            label: "Yes, and don't ask again for this server".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::ApprovedForSession),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
+        },
+        ApprovalOption {
+            label: "No, decline the request".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Denied),
+            display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+        },
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -581,6 +709,51 @@ mod tests {
         assert!(saw_op, "expected approval decision to emit an op");
     }
 
+    #[test]
+    fn sampling_shortcuts_resolve_the_request() {
+        let request = ApprovalRequest::McpSampling {
+            server_name: "docs".to_string(),
+            request_id: RequestId::Integer(7),
+            model: "qwen3-coder-30b".to_string(),
+            max_tokens: 512,
+            system_prompt: None,
+            messages: vec![McpSamplingMessage {
+                role: Role::User,
+                text: "Summarize the page".to_string(),
+            }],
+        };
+        let decision_for = |key: KeyCode| {
+            let (tx, mut rx) = unbounded_channel::<AppEvent>();
+            let tx = AppEventSender::new(tx);
+            let mut view = ApprovalOverlay::new(request.clone(), tx, Features::with_defaults());
+            view.handle_key_event(KeyEvent::new(key, KeyModifiers::NONE));
+            let mut decision = None;
+            while let Ok(ev) = rx.try_recv() {
+                if let AppEvent::CodexOp(Op::ResolveMcpSampling {
+                    server_name,
+                    request_id,
+                    decision: resolved,
+                }) = ev
+                {
+                    assert_eq!(server_name, "docs");
+                    assert_eq!(request_id, RequestId::Integer(7));
+                    decision = Some(resolved);
+                }
+            }
+            decision
+        };
+
+        assert_eq!(
+            decision_for(KeyCode::Char('y')),
+            Some(ReviewDecision::Approved)
+        );
+        assert_eq!(
+            decision_for(KeyCode::Char('a')),
+            Some(ReviewDecision::ApprovedForSession)
+        );
+        assert_eq!(decision_for(KeyCode::Esc), Some(ReviewDecision::Denied));
+    }
+
     #[test]
     fn exec_prefix_option_emits_execpolicy_amendment() {
         let (tx, mut rx) = unbounded_channel::<AppEvent>();
@@ -130,6 +130,8 @@ use super::slash_commands;
 use crate::bottom_pane::paste_burst::FlushResult;
 use crate::bottom_pane::prompt_args::expand_custom_prompt;
 use crate::bottom_pane::prompt_args::expand_if_numeric_with_positional_args;
+use crate::bottom_pane::prompt_args::mcp_prompt_command_with_arg_placeholders;
+use crate::bottom_pane::prompt_args::parse_mcp_prompt_arguments;
 use crate::bottom_pane::prompt_args::parse_slash_name;
 use crate::bottom_pane::prompt_args::prompt_argument_names;
 use crate::bottom_pane::prompt_args::prompt_command_with_arg_placeholders;
@@ -160,6 +162,7 @@ use trill_chatgpt::connectors;
 use trill_chatgpt::connectors::AppInfo;
 use trill_core::skills::model::SkillMetadata;
 use trill_file_search::FileMatch;
+use mcp_types::Prompt as McpPrompt;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -185,6 +188,12 @@ pub enum InputResult {
     },
     Command(SlashCommand),
     CommandWithArgs(SlashCommand, String),
+    /// An MCP prompt to fetch from `server` and send once it resolves.
+    McpPrompt {
+        server: String,
+        name: String,
+        arguments: HashMap<String, String>,
+    },
     None,
 }
 
@@ -274,6 +283,8 @@ pub(crate) struct ChatComposer {
     // When true, disables paste-burst logic and inserts characters immediately.
     disable_paste_burst: bool,
     custom_prompts: Vec<CustomPrompt>,
+    /// `(server, prompt)` pairs offered by MCP servers, invoked as `/server:prompt`.
+    mcp_prompts: Vec<(String, McpPrompt)>,
     footer_mode: FooterMode,
     footer_hint_override: Option<Vec<(String, String)>>,
     footer_flash: Option<FooterFlash>,
@@ -364,6 +375,7 @@ impl ChatComposer {
             paste_burst: PasteBurst::default(),
             disable_paste_burst: false,
             custom_prompts: Vec::new(),
+            mcp_prompts: Vec::new(),
             footer_mode: FooterMode::ComposerEmpty,
             footer_hint_override: None,
             footer_flash: None,
@@ -1048,6 +1060,21 @@ impl ChatComposer {
                                 }
                             }
                         }
+                        CommandItem::McpPrompt(idx) => {
+                            if let Some((server, prompt)) = popup.mcp_prompt(idx) {
+                                let command = format!("{server}:{}", prompt.name);
+                                let already_typed = parse_slash_name(first_line)
+                                    .is_some_and(|(name, _, _)| name == command);
+                                if already_typed {
+                                    cursor_target = Some(self.textarea.text().len());
+                                } else {
+                                    let (text, cursor) =
+                                        mcp_prompt_command_with_arg_placeholders(server, prompt);
+                                    self.textarea.set_text_clearing_elements(&text);
+                                    cursor_target = Some(cursor);
+                                }
+                            }
+                        }
                     }
                     if let Some(pos) = cursor_target {
                         self.textarea.set_cursor(pos);
@@ -1135,6 +1162,35 @@ impl ChatComposer {
                             }
                             return (InputResult::None, true);
                         }
+                        CommandItem::McpPrompt(idx) => {
+                            if let Some((server, prompt)) = popup.mcp_prompt(idx).cloned() {
+                                let command = format!("{server}:{}", prompt.name);
+                                let already_typed = parse_slash_name(first_line)
+                                    .is_some_and(|(name, _, _)| name == command);
+                                if !already_typed {
+                                    // Prompts with arguments get `key=""` placeholders to fill in.
+                                    if prompt
+                                        .arguments
+                                        .as_ref()
+                                        .is_some_and(|args| !args.is_empty())
+                                    {
+                                        let (text, cursor) =
+                                            mcp_prompt_command_with_arg_placeholders(
+                                                &server, &prompt,
+                                            );
+                                        self.textarea.set_text_clearing_elements(&text);
+                                        self.textarea.set_cursor(cursor);
+                                        return (InputResult::None, true);
+                                    }
+                                    self.textarea
+                                        .set_text_clearing_elements(&format!("/{command}"));
+                                }
+                                if let Some(result) = self.try_dispatch_mcp_prompt() {
+                                    return (result, true);
+                                }
+                            }
+                            return (InputResult::None, true);
+                        }
                     }
                 }
                 // Fallback to default newline handling if no command selected.
@@ -1977,6 +2033,9 @@ impl ChatComposer {
         if let Some(result) = self.try_dispatch_slash_command_with_args() {
             return (result, true);
         }
+        if let Some(result) = self.try_dispatch_mcp_prompt() {
+            return (result, true);
+        }
 
         if let Some((text, text_elements)) = self.prepare_submission_text() {
             if should_queue {
@@ -2033,6 +2092,53 @@ impl ChatComposer {
         }
     }
 
+    /// Check if the first line invokes an MCP prompt (`/server:prompt key=value ...`) and
+    /// dispatch it. Argument errors are reported and leave the input in place.
+    fn try_dispatch_mcp_prompt(&mut self) -> Option<InputResult> {
+        if !self.slash_commands_enabled() {
+            return None;
+        }
+        let mut text = self.textarea.text().to_string();
+        if text.starts_with(' ') {
+            return None;
+        }
+        if !self.pending_pastes.is_empty() {
+            let (expanded, _) = Self::expand_pending_pastes(
+                &text,
+                self.textarea.text_elements(),
+                &self.pending_pastes,
+            );
+            text = expanded;
+        }
+        let first_line = text.lines().next().unwrap_or("");
+        let (command, rest, _rest_offset) = parse_slash_name(first_line)?;
+        let (server, prompt) = self.mcp_prompts.iter().find(|(server, prompt)| {
+            server != PROMPTS_CMD_PREFIX
+                && command
+                    .strip_prefix(server.as_str())
+                    .and_then(|name| name.strip_prefix(':'))
+                    == Some(prompt.name.as_str())
+        })?;
+        match parse_mcp_prompt_arguments(&format!("/{command}"), rest, prompt) {
+            Ok(arguments) => {
+                let result = InputResult::McpPrompt {
+                    server: server.clone(),
+                    name: prompt.name.clone(),
+                    arguments,
+                };
+                self.pending_pastes.clear();
+                self.textarea.set_text_clearing_elements("");
+                Some(result)
+            }
+            Err(err) => {
+                self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                    history_cell::new_error_event(err.user_message()),
+                )));
+                Some(InputResult::None)
+            }
+        }
+    }
+
     /// Check if the input is a slash command with args (e.g., /review args) and dispatch it.
     /// Returns Some(InputResult) if a command was dispatched, None otherwise.
     fn try_dispatch_slash_command_with_args(&mut self) -> Option<InputResult> {
@@ -2558,6 +2664,8 @@ impl ChatComposer {
 
         self.custom_prompts.iter().any(|prompt| {
             fuzzy_match(&format!("{PROMPTS_CMD_PREFIX}:{}", prompt.name), name).is_some()
+        }) || self.mcp_prompts.iter().any(|(server, prompt)| {
+            fuzzy_match(&format!("{server}:{}", prompt.name), name).is_some()
         })
     }
 
@@ -2613,6 +2721,7 @@ impl ChatComposer {
                             windows_degraded_sandbox_active: self.windows_degraded_sandbox_active,
                         },
                     );
+                    command_popup.set_mcp_prompts(self.mcp_prompts.clone());
                     command_popup.on_composer_text_change(first_line.to_string());
                     self.active_popup = ActivePopup::Command(command_popup);
                 }
@@ -2626,6 +2735,13 @@ impl ChatComposer {
         }
     }
 
+    pub(crate) fn set_mcp_prompts(&mut self, prompts: Vec<(String, McpPrompt)>) {
+        self.mcp_prompts = prompts.clone();
+        if let ActivePopup::Command(popup) = &mut self.active_popup {
+            popup.set_mcp_prompts(prompts);
+        }
+    }
+
     /// Synchronize `self.file_search_popup` with the current text in the textarea.
     /// Note this is only called when self.active_popup is NOT Command.
     fn sync_file_search_popup(&mut self, query: String) {
@@ -4470,7 +4586,7 @@ mod tests {
                 Some(CommandItem::Builtin(cmd)) => {
                     assert_eq!(cmd.command(), "model")
                 }
-                Some(CommandItem::UserPrompt(_)) => {
+                Some(CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_)) => {
                     panic!("unexpected prompt selected for '/mo'")
                 }
                 None => panic!("no selected command for '/mo'"),
@@ -4527,7 +4643,7 @@ mod tests {
                 Some(CommandItem::Builtin(cmd)) => {
                     assert_eq!(cmd.command(), "resume")
                 }
-                Some(CommandItem::UserPrompt(_)) => {
+                Some(CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_)) => {
                     panic!("unexpected prompt selected for '/res'")
                 }
                 None => panic!("no selected command for '/res'"),
@@ -5684,6 +5800,56 @@ mod tests {
         assert!(composer.textarea.is_empty());
     }
 
+    #[test]
+    fn mcp_prompt_selection_inserts_args_then_dispatches() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        composer.set_steer_enabled(true);
+
+        composer.set_mcp_prompts(vec![(
+            "github".to_string(),
+            McpPrompt {
+                arguments: Some(vec![mcp_types::PromptArgument {
+                    description: None,
+                    name: "issue".to_string(),
+                    required: Some(true),
+                    title: None,
+                }]),
+                description: None,
+                name: "triage".to_string(),
+                title: None,
+            },
+        )]);
+
+        type_chars_humanlike(&mut composer, &['/', 't', 'r', 'i', 'a', 'g', 'e']);
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(result, InputResult::None);
+        assert_eq!(composer.textarea.text(), "/github:triage issue=\"\"");
+
+        composer
+            .textarea
+            .set_text_clearing_elements("/github:triage issue=42");
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(
+            result,
+            InputResult::McpPrompt {
+                server: "github".to_string(),
+                name: "triage".to_string(),
+                arguments: HashMap::from([("issue".to_string(), "42".to_string())]),
+            }
+        );
+        assert!(composer.textarea.is_empty());
+    }
+
     #[test]
     fn custom_prompt_submission_accepts_quoted_values() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
@@ -12,6 +12,7 @@ use crate::render::RectExt;
 use crate::slash_command::SlashCommand;
 use trill_protocol::custom_prompts::CustomPrompt;
 use trill_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
+use mcp_types::Prompt as McpPrompt;
 use std::collections::HashSet;
 
 // Hide alias commands in the default popup list so each unique action appears once.
@@ -19,18 +20,23 @@ use std::collections::HashSet;
 // `approvals` is an alias of `permissions`.
 const ALIAS_COMMANDS: &[SlashCommand] = &[SlashCommand::Quit, SlashCommand::Approvals];
 
-/// A selectable item in the popup: either a built-in command or a user prompt.
+/// A selectable item in the popup: a built-in command, a user prompt, or a
+/// prompt offered by an MCP server.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum CommandItem {
     Builtin(SlashCommand),
     // Index into `prompts`
     UserPrompt(usize),
+    // Index into `mcp_prompts`
+    McpPrompt(usize),
 }
 
 pub(crate) struct CommandPopup {
     command_filter: String,
     builtins: Vec<(&'static str, SlashCommand)>,
     prompts: Vec<CustomPrompt>,
+    /// `(server, prompt)` pairs, shown as `/server:prompt`.
+    mcp_prompts: Vec<(String, McpPrompt)>,
     state: ScrollState,
 }
 
@@ -59,6 +65,7 @@ impl CommandPopup {
             command_filter: String::new(),
             builtins,
             prompts,
+            mcp_prompts: Vec::new(),
             state: ScrollState::new(),
         }
     }
@@ -78,6 +85,20 @@ impl CommandPopup {
         self.prompts.get(idx)
     }
 
+    /// Replace the MCP prompts. Prompts from a server named like the custom
+    /// prompts namespace are dropped so `/prompts:name` stays unambiguous.
+    pub(crate) fn set_mcp_prompts(&mut self, mut prompts: Vec<(String, McpPrompt)>) {
+        prompts.retain(|(server, _)| server != PROMPTS_CMD_PREFIX);
+        prompts.sort_by(|(a_server, a), (b_server, b)| {
+            a_server.cmp(b_server).then_with(|| a.name.cmp(&b.name))
+        });
+        self.mcp_prompts = prompts;
+    }
+
+    pub(crate) fn mcp_prompt(&self, idx: usize) -> Option<&(String, McpPrompt)> {
+        self.mcp_prompts.get(idx)
+    }
+
     /// Update the filter string based on the current composer text. The text
     /// passed in is expected to start with a leading '/'. Everything after the
     /// *first* '/" on the *first* line becomes the active filter that is used
@@ -136,6 +157,10 @@ impl CommandPopup {
             for idx in 0..self.prompts.len() {
                 out.push((CommandItem::UserPrompt(idx), None));
             }
+            // Then MCP prompts, sorted by server and name.
+            for idx in 0..self.mcp_prompts.len() {
+                out.push((CommandItem::McpPrompt(idx), None));
+            }
             return out;
         }
 
@@ -182,6 +207,16 @@ impl CommandPopup {
                 prompt_prefix_len,
             );
         }
+        // MCP prompts match on "server:name" as well as the bare prompt name.
+        for (idx, (server, p)) in self.mcp_prompts.iter().enumerate() {
+            let display = format!("{server}:{}", p.name);
+            push_match(
+                CommandItem::McpPrompt(idx),
+                &display,
+                Some(&p.name),
+                server.chars().count() + 1,
+            );
+        }
 
         out.extend(exact);
         out.extend(prefix);
@@ -214,6 +249,15 @@ impl CommandPopup {
                             description,
                         )
                     }
+                    CommandItem::McpPrompt(i) => {
+                        let (server, prompt) = &self.mcp_prompts[i];
+                        let description = prompt
+                            .description
+                            .clone()
+                            .or_else(|| prompt.title.clone())
+                            .unwrap_or_else(|| format!("send prompt from MCP server {server}"));
+                        (format!("/{server}:{}", prompt.name), description)
+                    }
                 };
                 GenericDisplayRow {
                     name,
@@ -283,7 +327,7 @@ mod tests {
         let matches = popup.filtered_items();
         let has_init = matches.iter().any(|item| match item {
             CommandItem::Builtin(cmd) => cmd.command() == "init",
-            CommandItem::UserPrompt(_) => false,
+            CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_) => false,
         });
         assert!(
             has_init,
@@ -301,7 +345,9 @@ mod tests {
         let selected = popup.selected_item();
         match selected {
             Some(CommandItem::Builtin(cmd)) => assert_eq!(cmd.command(), "init"),
-            Some(CommandItem::UserPrompt(_)) => panic!("unexpected prompt selected for '/init'"),
+            Some(CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_)) => {
+                panic!("unexpected prompt selected for '/init'")
+            }
             None => panic!("expected a selected command for exact match"),
         }
     }
@@ -313,7 +359,7 @@ mod tests {
         let matches = popup.filtered_items();
         match matches.first() {
             Some(CommandItem::Builtin(cmd)) => assert_eq!(cmd.command(), "model"),
-            Some(CommandItem::UserPrompt(_)) => {
+            Some(CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_)) => {
                 panic!("unexpected prompt ranked before '/model' for '/mo'")
             }
             None => panic!("expected at least one match for '/mo'"),
@@ -330,7 +376,7 @@ mod tests {
             .into_iter()
             .filter_map(|item| match item {
                 CommandItem::Builtin(cmd) => Some(cmd.command()),
-                CommandItem::UserPrompt(_) => None,
+                CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_) => None,
             })
             .collect();
         assert_eq!(cmds, vec!["model", "mention", "mcp"]);
@@ -428,6 +474,39 @@ mod tests {
         assert_eq!(description, Some("send saved prompt"));
     }
 
+    fn mcp_prompt(name: &str, description: Option<&str>) -> McpPrompt {
+        McpPrompt {
+            arguments: None,
+            description: description.map(str::to_string),
+            name: name.to_string(),
+            title: None,
+        }
+    }
+
+    #[test]
+    fn mcp_prompts_match_by_server_and_bare_name() {
+        let mut popup = CommandPopup::new(Vec::new(), CommandPopupFlags::default());
+        popup.set_mcp_prompts(vec![
+            (
+                "github".to_string(),
+                mcp_prompt("triage", Some("Triage an issue")),
+            ),
+            ("prompts".to_string(), mcp_prompt("shadowed", None)),
+        ]);
+
+        popup.on_composer_text_change("/tri".to_string());
+        assert_eq!(popup.selected_item(), Some(CommandItem::McpPrompt(0)));
+
+        popup.on_composer_text_change("/github:triage".to_string());
+        let rows = popup.rows_from_matches(popup.filtered());
+        let row = rows.first().expect("mcp prompt row");
+        assert_eq!(row.name, "/github:triage");
+        assert_eq!(row.description.as_deref(), Some("Triage an issue"));
+
+        popup.on_composer_text_change("/shadowed".to_string());
+        assert_eq!(popup.selected_item(), None);
+    }
+
     #[test]
     fn prefix_filter_limits_matches_for_ac() {
         let mut popup = CommandPopup::new(Vec::new(), CommandPopupFlags::default());
@@ -438,7 +517,7 @@ mod tests {
             .into_iter()
             .filter_map(|item| match item {
                 CommandItem::Builtin(cmd) => Some(cmd.command()),
-                CommandItem::UserPrompt(_) => None,
+                CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_) => None,
             })
             .collect();
         assert!(
@@ -469,7 +548,7 @@ mod tests {
             .into_iter()
             .filter_map(|item| match item {
                 CommandItem::Builtin(cmd) => Some(cmd.command()),
-                CommandItem::UserPrompt(_) => None,
+                CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_) => None,
             })
             .collect();
         assert!(
@@ -538,7 +617,7 @@ mod tests {
             .into_iter()
             .filter_map(|item| match item {
                 CommandItem::Builtin(cmd) => Some(cmd.command()),
-                CommandItem::UserPrompt(_) => None,
+                CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_) => None,
             })
             .collect();
         assert!(
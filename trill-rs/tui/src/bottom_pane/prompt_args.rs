@@ -3,6 +3,7 @@ use trill_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
 use trill_protocol::user_input::ByteRange;
 use trill_protocol::user_input::TextElement;
 use lazy_static::lazy_static;
+use mcp_types::Prompt as McpPrompt;
 use regex_lite::Regex;
 use shlex::Shlex;
 use std::collections::HashMap;
@@ -548,7 +549,59 @@ fn append_joined_args_with_elements(
 /// Constructs a command text for a custom prompt with arguments.
 /// Returns the text and the cursor position (inside the first double quote).
 pub fn prompt_command_with_arg_placeholders(name: &str, args: &[String]) -> (String, usize) {
-    let mut text = format!("/{PROMPTS_CMD_PREFIX}:{name}");
+    command_with_arg_placeholders(format!("/{PROMPTS_CMD_PREFIX}:{name}"), args)
+}
+
+/// Constructs a command text for an MCP prompt, with a `key=""` placeholder for
+/// each argument the prompt declares.
+/// Returns the text and the cursor position (inside the first double quote).
+pub fn mcp_prompt_command_with_arg_placeholders(
+    server: &str,
+    prompt: &McpPrompt,
+) -> (String, usize) {
+    let args: Vec<String> = prompt
+        .arguments
+        .iter()
+        .flatten()
+        .map(|arg| arg.name.clone())
+        .collect();
+    command_with_arg_placeholders(format!("/{server}:{}", prompt.name), &args)
+}
+
+/// Parses the `key=value` pairs that follow an MCP prompt command and checks
+/// that every argument the prompt marks as required was given a value.
+pub fn parse_mcp_prompt_arguments(
+    command: &str,
+    rest: &str,
+    prompt: &McpPrompt,
+) -> Result<HashMap<String, String>, PromptExpansionError> {
+    let mut inputs =
+        parse_prompt_inputs(rest, &[]).map_err(|error| PromptExpansionError::Args {
+            command: command.to_string(),
+            error,
+        })?;
+    // Placeholders left as `key=""` count as not provided.
+    inputs.retain(|_, value| !value.text.is_empty());
+    let missing: Vec<String> = prompt
+        .arguments
+        .iter()
+        .flatten()
+        .filter(|arg| arg.required == Some(true) && !inputs.contains_key(&arg.name))
+        .map(|arg| arg.name.clone())
+        .collect();
+    if !missing.is_empty() {
+        return Err(PromptExpansionError::MissingArgs {
+            command: command.to_string(),
+            missing,
+        });
+    }
+    Ok(inputs
+        .into_iter()
+        .map(|(key, value)| (key, value.text))
+        .collect())
+}
+
+fn command_with_arg_placeholders(mut text: String, args: &[String]) -> (String, usize) {
     let mut cursor: usize = text.len();
     for (i, arg) in args.iter().enumerate() {
         text.push_str(format!(" {arg}=\"\"").as_str());
@@ -851,4 +904,59 @@ mod tests {
             })
         );
     }
+
+    fn mcp_prompt(arguments: Vec<(&str, bool)>) -> McpPrompt {
+        McpPrompt {
+            arguments: Some(
+                arguments
+                    .into_iter()
+                    .map(|(name, required)| mcp_types::PromptArgument {
+                        description: None,
+                        name: name.to_string(),
+                        required: Some(required),
+                        title: None,
+                    })
+                    .collect(),
+            ),
+            description: None,
+            name: "review".to_string(),
+            title: None,
+        }
+    }
+
+    #[test]
+    fn mcp_prompt_command_inserts_placeholders_for_declared_args() {
+        let prompt = mcp_prompt(vec![("path", true), ("focus", false)]);
+
+        let (text, cursor) = mcp_prompt_command_with_arg_placeholders("github", &prompt);
+
+        assert_eq!(text, "/github:review path=\"\" focus=\"\"");
+        assert_eq!(cursor, "/github:review path=\"".len());
+    }
+
+    #[test]
+    fn mcp_prompt_arguments_parse_key_values() {
+        let prompt = mcp_prompt(vec![("path", true), ("focus", false)]);
+
+        let args = parse_mcp_prompt_arguments("/github:review", "path=\"src/a b.rs\"", &prompt)
+            .expect("arguments");
+
+        assert_eq!(
+            args,
+            HashMap::from([("path".to_string(), "src/a b.rs".to_string())])
+        );
+    }
+
+    #[test]
+    fn mcp_prompt_arguments_report_missing_required() {
+        let prompt = mcp_prompt(vec![("path", true), ("focus", false)]);
+
+        let err = parse_mcp_prompt_arguments("/github:review", "path=\"\" focus=tests", &prompt)
+            .expect_err("missing path");
+
+        assert_eq!(
+            err.user_message(),
+            "Missing required args for /github:review: path. Provide as key=value (quote values with spaces)."
+        );
+    }
 }
@@ -61,9 +61,12 @@ use trill_core::protocol::ExecCommandEndEvent;
 use trill_core::protocol::ExecCommandOutputDeltaEvent;
 use trill_core::protocol::ExecCommandSource;
 use trill_core::protocol::ExitedReviewModeEvent;
+use trill_core::protocol::GetMcpPromptResponseEvent;
 use trill_core::protocol::ListCustomPromptsResponseEvent;
+use trill_core::protocol::ListMcpPromptsResponseEvent;
 use trill_core::protocol::ListSkillsResponseEvent;
 use trill_core::protocol::McpListToolsResponseEvent;
+use trill_core::protocol::McpSamplingProgressEvent;
 use trill_core::protocol::McpStartupCompleteEvent;
 use trill_core::protocol::McpStartupStatus;
 use trill_core::protocol::McpStartupUpdateEvent;
@@ -79,10 +82,13 @@ use trill_core::protocol::StreamErrorEvent;
 use trill_core::protocol::TerminalInteractionEvent;
 use trill_core::protocol::TokenUsage;
 use trill_core::protocol::TokenUsageInfo;
+use trill_core::protocol::ToolCallLoopDetectedEvent;
 use trill_core::protocol::TurnAbortReason;
 use trill_core::protocol::TurnCompleteEvent;
 use trill_core::protocol::TurnDiffEvent;
 use trill_core::protocol::UndoCompletedEvent;
+use trill_core::protocol::UndoSnapshot;
+use trill_core::protocol::UndoSnapshotsResponseEvent;
 use trill_core::protocol::UndoStartedEvent;
 use trill_core::protocol::UserMessageEvent;
 use trill_core::protocol::ViewImageToolCallEvent;
@@ -96,6 +102,7 @@ use trill_otel::OtelManager;
 use trill_protocol::ThreadId;
 use trill_protocol::account::PlanType;
 use trill_protocol::approvals::ElicitationRequestEvent;
+use trill_protocol::approvals::McpSamplingRequestEvent;
 use trill_protocol::config_types::CollaborationMode;
 use trill_protocol::config_types::CollaborationModeMask;
 use trill_protocol::config_types::ModeKind;
@@ -112,6 +119,7 @@ use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
+use mcp_types::Prompt as McpPrompt;
 use rand::Rng;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
@@ -1359,6 +1367,9 @@ impl ChatWidget {
             self.on_warning(format!("MCP startup incomplete ({})", parts.join("; ")));
         }
 
+        // Servers are up now, so their prompts can join the slash popup.
+        self.submit_op(Op::ListMcpPrompts);
+
         self.mcp_startup_status = None;
         self.update_task_running_state();
         self.maybe_send_next_queued_input();
@@ -1482,6 +1493,14 @@ impl ChatWidget {
         );
     }
 
+    fn on_mcp_sampling_request(&mut self, ev: McpSamplingRequestEvent) {
+        let ev2 = ev.clone();
+        self.defer_or_handle(
+            |q| q.push_mcp_sampling(ev),
+            |s| s.handle_mcp_sampling_request_now(ev2),
+        );
+    }
+
     fn on_request_user_input(&mut self, ev: RequestUserInputEvent) {
         let ev2 = ev.clone();
         self.defer_or_handle(
@@ -1806,6 +1825,125 @@ impl ChatWidget {
         }
     }
 
+    /// Show the turns `/undo` can restore, newest first.
+    fn open_undo_popup(&mut self, ev: UndoSnapshotsResponseEvent) {
+        let UndoSnapshotsResponseEvent {
+            snapshots,
+            redo_available,
+        } = ev;
+        if snapshots.is_empty() && !redo_available {
+            self.add_info_message(
+                "No snapshots to undo yet.".to_string(),
+                Some("Snapshots are taken at the start of each turn in a git repository.".into()),
+            );
+            return;
+        }
+
+        let mut items: Vec<SelectionItem> = snapshots
+            .into_iter()
+            .map(|snapshot| {
+                let turns = if snapshot.num_turns == 1 {
+                    "turn"
+                } else {
+                    "turns"
+                };
+                let files = if snapshot.changed_files.len() == 1 {
+                    "file"
+                } else {
+                    "files"
+                };
+                let description = format!(
+                    "Undo {} {turns} · {} {files} changed",
+                    snapshot.num_turns,
+                    snapshot.changed_files.len()
+                );
+                let name =
+                    truncate_text(snapshot.user_message.lines().next().unwrap_or_default(), 60);
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::OpenUndoFilesPopup {
+                        snapshot: snapshot.clone(),
+                    });
+                })];
+                SelectionItem {
+                    name,
+                    description: Some(description),
+                    actions,
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+        if redo_available {
+            items.push(SelectionItem {
+                name: "Redo last undo".to_string(),
+                description: Some("Reapply the most recent undo.".to_string()),
+                actions: vec![Box::new(|tx| {
+                    tx.send(AppEvent::CodexOp(Op::Redo));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            });
+        }
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Undo".to_string()),
+            subtitle: Some("Pick the oldest turn to undo.".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    /// Let the user restore every file from a snapshot or just one of them.
+    pub(crate) fn open_undo_files_popup(&mut self, snapshot: UndoSnapshot) {
+        let UndoSnapshot {
+            num_turns,
+            user_message,
+            changed_files,
+            ..
+        } = snapshot;
+        let mut items = vec![SelectionItem {
+            name: "Restore all files".to_string(),
+            description: Some("Also removes these turns from the conversation.".to_string()),
+            actions: vec![Box::new(move |tx| {
+                tx.send(AppEvent::UndoTurns {
+                    num_turns,
+                    paths: Vec::new(),
+                });
+            })],
+            dismiss_on_select: true,
+            ..Default::default()
+        }];
+        items.extend(changed_files.into_iter().map(|path| {
+            let name = path.display().to_string();
+            let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                tx.send(AppEvent::UndoTurns {
+                    num_turns,
+                    paths: vec![path.clone()],
+                });
+            })];
+            SelectionItem {
+                name,
+                description: Some("Restore only this file.".to_string()),
+                actions,
+                dismiss_on_select: true,
+                ..Default::default()
+            }
+        }));
+
+        let turns = if num_turns == 1 { "turn" } else { "turns" };
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some(format!("Undo {num_turns} {turns}")),
+            subtitle: Some(truncate_text(
+                user_message.lines().next().unwrap_or_default(),
+                60,
+            )),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
     fn on_stream_error(&mut self, message: String, additional_details: Option<String>) {
         if self.retry_status_header.is_none() {
             self.retry_status_header = Some(self.current_status_header.clone());
@@ -2045,6 +2183,26 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    pub(crate) fn handle_mcp_sampling_request_now(&mut self, ev: McpSamplingRequestEvent) {
+        self.flush_answer_stream_with_separator();
+
+        self.notify(Notification::McpSamplingRequested {
+            server_name: ev.server_name.clone(),
+        });
+
+        let request = ApprovalRequest::McpSampling {
+            server_name: ev.server_name,
+            request_id: ev.id,
+            model: ev.model,
+            max_tokens: ev.max_tokens,
+            system_prompt: ev.system_prompt,
+            messages: ev.messages,
+        };
+        self.bottom_pane
+            .push_approval_request(request, &self.config.features);
+        self.request_redraw();
+    }
+
     pub(crate) fn handle_request_user_input_now(&mut self, ev: RequestUserInputEvent) {
         self.flush_answer_stream_with_separator();
         self.bottom_pane.push_user_input_request(ev);
@@ -2716,6 +2874,17 @@ impl ChatWidget {
                 InputResult::CommandWithArgs(cmd, args) => {
                     self.dispatch_command_with_args(cmd, args);
                 }
+                InputResult::McpPrompt {
+                    server,
+                    name,
+                    arguments,
+                } => {
+                    self.submit_op(Op::GetMcpPrompt {
+                        server,
+                        name,
+                        arguments,
+                    });
+                }
                 InputResult::None => {}
             },
         }
@@ -2911,9 +3080,9 @@ impl ChatWidget {
                 }
                 self.request_quit_without_confirmation();
             }
-            // SlashCommand::Undo => {
-            //     self.app_event_tx.send(AppEvent::CodexOp(Op::Undo));
-            // }
+            SlashCommand::Undo => {
+                self.submit_op(Op::ListUndoSnapshots);
+            }
             SlashCommand::Diff => {
                 self.add_diff_in_progress();
                 let tx = self.app_event_tx.clone();
@@ -3347,6 +3516,14 @@ impl ChatWidget {
                 self.on_rate_limit_snapshot(ev.rate_limits);
             }
             EventMsg::Warning(WarningEvent { message }) => self.on_warning(message),
+            EventMsg::ToolCallLoopDetected(ToolCallLoopDetectedEvent { message, .. }) => {
+                self.on_warning(message)
+            }
+            EventMsg::HookCompleted(ev) => {
+                if let Some(message) = ev.warning_message() {
+                    self.on_warning(message);
+                }
+            }
             EventMsg::Error(ErrorEvent {
                 message,
                 codex_error_info,
@@ -3391,6 +3568,16 @@ impl ChatWidget {
             EventMsg::ElicitationRequest(ev) => {
                 self.on_elicitation_request(ev);
             }
+            EventMsg::McpSamplingRequest(ev) => {
+                self.on_mcp_sampling_request(ev);
+            }
+            EventMsg::McpSamplingProgress(McpSamplingProgressEvent {
+                server_name,
+                message,
+                ..
+            }) => {
+                self.add_info_message(format!("{server_name} sampling: {message}"), None);
+            }
             EventMsg::RequestUserInput(ev) => {
                 self.on_request_user_input(ev);
             }
@@ -3408,6 +3595,9 @@ impl ChatWidget {
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
+            EventMsg::ListMcpPromptsResponse(ev) => self.on_list_mcp_prompts(ev),
+            EventMsg::GetMcpPromptResponse(ev) => self.on_get_mcp_prompt(ev),
+            EventMsg::UndoSnapshotsResponse(ev) => self.open_undo_popup(ev),
             EventMsg::ListSkillsResponse(ev) => self.on_list_skills(ev),
             EventMsg::SkillsUpdateAvailable => {
                 self.submit_op(Op::ListSkills {
@@ -5796,6 +5986,43 @@ impl ChatWidget {
         self.bottom_pane.set_custom_prompts(ev.custom_prompts);
     }
 
+    fn on_list_mcp_prompts(&mut self, ev: ListMcpPromptsResponseEvent) {
+        let prompts: Vec<(String, McpPrompt)> = ev
+            .prompts
+            .into_iter()
+            .flat_map(|(server, prompts)| {
+                prompts
+                    .into_iter()
+                    .map(move |prompt| (server.clone(), prompt))
+            })
+            .collect();
+        debug!("received {} MCP prompts", prompts.len());
+        self.bottom_pane.set_mcp_prompts(prompts);
+    }
+
+    /// Start a turn from a fetched MCP prompt; core keeps each message's role.
+    fn on_get_mcp_prompt(&mut self, ev: GetMcpPromptResponseEvent) {
+        let GetMcpPromptResponseEvent {
+            server,
+            name,
+            result,
+        } = ev;
+        let result = match result {
+            Ok(result) => result,
+            Err(err) => {
+                self.add_error_message(format!("Failed to get prompt /{server}:{name}: {err}"));
+                return;
+            }
+        };
+        if result.messages.is_empty() {
+            self.add_error_message(format!("Prompt /{server}:{name} returned no messages."));
+            return;
+        }
+        self.submit_op(Op::RunMcpPrompt {
+            messages: result.messages,
+        });
+    }
+
     fn on_list_skills(&mut self, ev: ListSkillsResponseEvent) {
         self.set_skills_from_response(&ev);
     }
@@ -6072,6 +6299,7 @@ enum Notification {
     ExecApprovalRequested { command: String },
     EditApprovalRequested { cwd: PathBuf, changes: Vec<PathBuf> },
     ElicitationRequested { server_name: String },
+    McpSamplingRequested { server_name: String },
 }
 
 impl Notification {
@@ -6098,6 +6326,9 @@ impl Notification {
             Notification::ElicitationRequested { server_name } => {
                 format!("Approval requested by {server_name}")
             }
+            Notification::McpSamplingRequested { server_name } => {
+                format!("{server_name} wants to use the model")
+            }
         }
     }
 
@@ -6106,7 +6337,8 @@ impl Notification {
             Notification::AgentTurnComplete { .. } => "agent-turn-complete",
             Notification::ExecApprovalRequested { .. }
             | Notification::EditApprovalRequested { .. }
-            | Notification::ElicitationRequested { .. } => "approval-requested",
+            | Notification::ElicitationRequested { .. }
+            | Notification::McpSamplingRequested { .. } => "approval-requested",
         }
     }
 
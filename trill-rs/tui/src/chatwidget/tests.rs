@@ -58,6 +58,8 @@ use trill_core::protocol::TokenUsageInfo;
 use trill_core::protocol::TurnCompleteEvent;
 use trill_core::protocol::TurnStartedEvent;
 use trill_core::protocol::UndoCompletedEvent;
+use trill_core::protocol::UndoSnapshot;
+use trill_core::protocol::UndoSnapshotsResponseEvent;
 use trill_core::protocol::UndoStartedEvent;
 use trill_core::protocol::ViewImageToolCallEvent;
 use trill_core::protocol::WarningEvent;
@@ -2626,6 +2628,64 @@ async fn undo_failure_events_render_error_message() {
     );
 }
 
+#[tokio::test]
+async fn undo_snapshots_open_turn_picker_with_redo() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.handle_codex_event(Event {
+        id: "undo-list".to_string(),
+        msg: EventMsg::UndoSnapshotsResponse(UndoSnapshotsResponseEvent {
+            snapshots: vec![
+                UndoSnapshot {
+                    num_turns: 1,
+                    commit_id: "bbbbbbb".to_string(),
+                    user_message: "rename the helper".to_string(),
+                    changed_files: vec![PathBuf::from("src/lib.rs")],
+                },
+                UndoSnapshot {
+                    num_turns: 2,
+                    commit_id: "aaaaaaa".to_string(),
+                    user_message: "add a readme".to_string(),
+                    changed_files: vec![PathBuf::from("README.md"), PathBuf::from("docs/a.md")],
+                },
+            ],
+            redo_available: true,
+        }),
+    });
+
+    let popup = render_bottom_popup(&chat, 80);
+    assert!(popup.contains("rename the helper"), "popup: {popup}");
+    assert!(popup.contains("Undo 2 turns · 2 files changed"), "popup: {popup}");
+    assert!(popup.contains("Redo last undo"), "popup: {popup}");
+}
+
+#[tokio::test]
+async fn undo_files_popup_offers_full_and_per_file_restore() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.open_undo_files_popup(UndoSnapshot {
+        num_turns: 1,
+        commit_id: "bbbbbbb".to_string(),
+        user_message: "rename the helper".to_string(),
+        changed_files: vec![PathBuf::from("src/lib.rs")],
+    });
+
+    let popup = render_bottom_popup(&chat, 80);
+    assert!(popup.contains("Restore all files"), "popup: {popup}");
+    assert!(popup.contains("src/lib.rs"), "popup: {popup}");
+
+    chat.handle_key_event(KeyEvent::from(KeyCode::Down));
+    chat.handle_key_event(KeyEvent::from(KeyCode::Enter));
+
+    let mut undo_event = None;
+    while let Ok(event) = rx.try_recv() {
+        if let AppEvent::UndoTurns { num_turns, paths } = event {
+            undo_event = Some((num_turns, paths));
+        }
+    }
+    assert_eq!(undo_event, Some((1, vec![PathBuf::from("src/lib.rs")])));
+}
+
 #[tokio::test]
 async fn undo_started_hides_interrupt_hint() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(None).await;
@@ -11,6 +11,7 @@ use trill_app_server_protocol::AuthMode;
 use trill_cloud_requirements::cloud_requirements_loader;
 use trill_common::oss::ensure_oss_provider_ready;
 use trill_common::oss::get_default_model_for_oss_provider;
+use trill_common::oss::detect_local_server_wire_api;
 use trill_common::oss::ollama_chat_deprecation_notice;
 use trill_core::AuthManager;
 use trill_core::CodexAuth;
@@ -145,11 +146,12 @@ pub async fn run_main(
         )
     };
 
-    // Map the legacy --search flag to the canonical web_search mode.
+    // Map the legacy --search flag to the canonical web_search mode. Setting
+    // `web_search.mode` keeps any `[web_search]` backends from config.toml.
     if cli.web_search {
         cli.config_overrides
             .raw_overrides
-            .push("web_search=\"live\"".to_string());
+            .push("web_search.mode=\"live\"".to_string());
     }
 
     // When using `--oss`, let the bootstrapper pick the model (defaulting to
@@ -661,7 +663,7 @@ async fn run_ratatui_app(
         None => None,
     };
 
-    let config = match &session_selection {
+    let mut config = match &session_selection {
         resume_picker::SessionSelection::Resume(_) | resume_picker::SessionSelection::Fork(_) => {
             load_config_or_exit_with_fallback_cwd(
                 cli_kv_overrides.clone(),
@@ -673,6 +675,9 @@ async fn run_ratatui_app(
         }
         _ => config,
     };
+    if let Err(err) = detect_local_server_wire_api(&mut config).await {
+        tracing::warn!(?err, "Failed to detect local server wire API");
+    }
     let active_profile = config.active_profile.clone();
     let should_show_trust_screen = should_show_trust_screen(&config);
 
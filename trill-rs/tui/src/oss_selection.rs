@@ -1,11 +1,19 @@
 use std::io;
 use std::sync::LazyLock;
 
+use trill_core::DEFAULT_KOBOLDCPP_PORT;
+use trill_core::DEFAULT_LLAMACPP_PORT;
 use trill_core::DEFAULT_LMSTUDIO_PORT;
 use trill_core::DEFAULT_OLLAMA_PORT;
+use trill_core::DEFAULT_TABBYAPI_PORT;
+use trill_core::DEFAULT_VLLM_PORT;
+use trill_core::KOBOLDCPP_OSS_PROVIDER_ID;
+use trill_core::LLAMACPP_OSS_PROVIDER_ID;
 use trill_core::LMSTUDIO_OSS_PROVIDER_ID;
 use trill_core::OLLAMA_CHAT_PROVIDER_ID;
 use trill_core::OLLAMA_OSS_PROVIDER_ID;
+use trill_core::TABBYAPI_OSS_PROVIDER_ID;
+use trill_core::VLLM_OSS_PROVIDER_ID;
 use trill_core::config::set_default_oss_provider;
 use crossterm::event::Event;
 use crossterm::event::KeyCode;
@@ -38,12 +46,6 @@ use ratatui::widgets::WidgetRef;
 use ratatui::widgets::Wrap;
 use std::time::Duration;
 
-#[derive(Clone)]
-struct ProviderOption {
-    name: String,
-    status: ProviderStatus,
-}
-
 #[derive(Clone)]
 enum ProviderStatus {
     Running,
@@ -59,6 +61,11 @@ struct SelectOption {
     description: &'static str,
     key: KeyCode,
     provider_id: &'static str,
+    /// Name shown next to the status indicator.
+    name: &'static str,
+    /// Port and path probed to tell whether the server is running.
+    port: u16,
+    probe_path: &'static str,
 }
 
 static OSS_SELECT_OPTIONS: LazyLock<Vec<SelectOption>> = LazyLock::new(|| {
@@ -68,18 +75,63 @@ static OSS_SELECT_OPTIONS: LazyLock<Vec<SelectOption>> = LazyLock::new(|| {
             description: "Local LM Studio server (default port 1234)",
             key: KeyCode::Char('l'),
             provider_id: LMSTUDIO_OSS_PROVIDER_ID,
+            name: "LM Studio",
+            port: DEFAULT_LMSTUDIO_PORT,
+            probe_path: "",
         },
         SelectOption {
             label: Line::from(vec!["O".underlined(), "llama".into()]),
             description: "Local Ollama server (Responses API, default port 11434)",
             key: KeyCode::Char('o'),
             provider_id: OLLAMA_OSS_PROVIDER_ID,
+            name: "Ollama (Responses)",
+            port: DEFAULT_OLLAMA_PORT,
+            probe_path: "",
         },
         SelectOption {
             label: Line::from(vec!["Ollama (".into(), "c".underlined(), "hat)".into()]),
             description: "Local Ollama server (chat wire API, default port 11434)",
             key: KeyCode::Char('c'),
             provider_id: OLLAMA_CHAT_PROVIDER_ID,
+            name: "Ollama (Chat)",
+            port: DEFAULT_OLLAMA_PORT,
+            probe_path: "",
+        },
+        SelectOption {
+            label: Line::from(vec!["ll".into(), "a".underlined(), "ma.cpp".into()]),
+            description: "Local llama-server (default port 8080)",
+            key: KeyCode::Char('a'),
+            provider_id: LLAMACPP_OSS_PROVIDER_ID,
+            name: "llama.cpp",
+            port: DEFAULT_LLAMACPP_PORT,
+            probe_path: "/v1/models",
+        },
+        SelectOption {
+            label: Line::from(vec!["v".underlined(), "LLM".into()]),
+            description: "Local vLLM server (default port 8000)",
+            key: KeyCode::Char('v'),
+            provider_id: VLLM_OSS_PROVIDER_ID,
+            name: "vLLM",
+            port: DEFAULT_VLLM_PORT,
+            probe_path: "/v1/models",
+        },
+        SelectOption {
+            label: Line::from(vec!["T".underlined(), "abbyAPI".into()]),
+            description: "Local TabbyAPI server (default port 5000)",
+            key: KeyCode::Char('t'),
+            provider_id: TABBYAPI_OSS_PROVIDER_ID,
+            name: "TabbyAPI",
+            port: DEFAULT_TABBYAPI_PORT,
+            probe_path: "/v1/models",
+        },
+        SelectOption {
+            label: Line::from(vec!["K".underlined(), "oboldCpp".into()]),
+            description: "Local KoboldCpp server (default port 5001)",
+            key: KeyCode::Char('k'),
+            provider_id: KOBOLDCPP_OSS_PROVIDER_ID,
+            name: "KoboldCpp",
+            port: DEFAULT_KOBOLDCPP_PORT,
+            probe_path: "/v1/models",
         },
     ]
 });
@@ -99,22 +151,8 @@ pub struct OssSelectionWidget<'a> {
 }
 
 impl OssSelectionWidget<'_> {
-    fn new(lmstudio_status: ProviderStatus, ollama_status: ProviderStatus) -> io::Result<Self> {
-        let providers = vec![
-            ProviderOption {
-                name: "LM Studio".to_string(),
-                status: lmstudio_status,
-            },
-            ProviderOption {
-                name: "Ollama (Responses)".to_string(),
-                status: ollama_status.clone(),
-            },
-            ProviderOption {
-                name: "Ollama (Chat)".to_string(),
-                status: ollama_status,
-            },
-        ];
-
+    /// `statuses` holds one entry per option in `OSS_SELECT_OPTIONS`.
+    fn new(statuses: &[ProviderStatus]) -> io::Result<Self> {
         let mut contents: Vec<Line> = vec![
             Line::from(vec![
                 "? ".fg(Color::Blue),
@@ -126,12 +164,12 @@ impl OssSelectionWidget<'_> {
         ];
 
         // Add status indicators for each provider
-        for provider in &providers {
-            let (status_symbol, status_color) = get_status_symbol_and_color(&provider.status);
+        for (option, status) in OSS_SELECT_OPTIONS.iter().zip(statuses) {
+            let (status_symbol, status_color) = get_status_symbol_and_color(status);
             contents.push(Line::from(vec![
                 Span::raw("  "),
                 Span::styled(status_symbol, Style::default().fg(status_color)),
-                Span::raw(format!(" {} ", provider.name)),
+                Span::raw(format!(" {} ", option.name)),
             ]));
         }
         contents.push(Line::from(""));
@@ -296,25 +334,18 @@ fn get_status_symbol_and_color(status: &ProviderStatus) -> (&'static str, Color)
 
 pub async fn select_oss_provider(trill_home: &std::path::Path) -> io::Result<String> {
     // Check provider statuses first
-    let lmstudio_status = check_lmstudio_status().await;
-    let ollama_status = check_ollama_status().await;
-
-    // Autoselect if only one is running
-    match (&lmstudio_status, &ollama_status) {
-        (ProviderStatus::Running, ProviderStatus::NotRunning) => {
-            let provider = LMSTUDIO_OSS_PROVIDER_ID.to_string();
-            return Ok(provider);
-        }
-        (ProviderStatus::NotRunning, ProviderStatus::Running) => {
-            let provider = OLLAMA_OSS_PROVIDER_ID.to_string();
-            return Ok(provider);
-        }
-        _ => {
-            // Both running or both not running - show UI
-        }
+    let mut statuses = Vec::with_capacity(OSS_SELECT_OPTIONS.len());
+    for option in OSS_SELECT_OPTIONS.iter() {
+        statuses.push(check_provider_status(option.port, option.probe_path).await);
+    }
+
+    // Autoselect if only one server is running. Both Ollama options share a
+    // server; the Responses one is preferred.
+    if let Some(provider_id) = autoselect_provider(&statuses) {
+        return Ok(provider_id.to_string());
     }
 
-    let mut widget = OssSelectionWidget::new(lmstudio_status, ollama_status)?;
+    let mut widget = OssSelectionWidget::new(&statuses)?;
 
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -349,32 +380,78 @@ pub async fn select_oss_provider(trill_home: &std::path::Path) -> io::Result<Str
     result
 }
 
-async fn check_lmstudio_status() -> ProviderStatus {
-    match check_port_status(DEFAULT_LMSTUDIO_PORT).await {
-        Ok(true) => ProviderStatus::Running,
-        Ok(false) => ProviderStatus::NotRunning,
-        Err(_) => ProviderStatus::Unknown,
+/// Returns the provider to use without asking when every server has been
+/// probed and exactly one of them is running.
+fn autoselect_provider(statuses: &[ProviderStatus]) -> Option<&'static str> {
+    let mut running = None;
+    for (option, status) in OSS_SELECT_OPTIONS.iter().zip(statuses) {
+        match status {
+            ProviderStatus::Running if option.provider_id == OLLAMA_CHAT_PROVIDER_ID => {}
+            ProviderStatus::Running if running.is_some() => return None,
+            ProviderStatus::Running => running = Some(option.provider_id),
+            ProviderStatus::NotRunning => {}
+            ProviderStatus::Unknown => return None,
+        }
     }
+    running
 }
 
-async fn check_ollama_status() -> ProviderStatus {
-    match check_port_status(DEFAULT_OLLAMA_PORT).await {
+async fn check_provider_status(port: u16, probe_path: &str) -> ProviderStatus {
+    match check_port_status(port, probe_path).await {
         Ok(true) => ProviderStatus::Running,
         Ok(false) => ProviderStatus::NotRunning,
         Err(_) => ProviderStatus::Unknown,
     }
 }
 
-async fn check_port_status(port: u16) -> io::Result<bool> {
+async fn check_port_status(port: u16, probe_path: &str) -> io::Result<bool> {
     let client = reqwest::Client::builder()
         .timeout(Duration::from_secs(2))
         .build()
         .map_err(io::Error::other)?;
 
-    let url = format!("http://localhost:{port}");
+    let url = format!("http://localhost:{port}{probe_path}");
 
     match client.get(&url).send().await {
         Ok(response) => Ok(response.status().is_success()),
         Err(_) => Ok(false), // Connection failed = not running
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn statuses_with_running(provider_ids: &[&str]) -> Vec<ProviderStatus> {
+        OSS_SELECT_OPTIONS
+            .iter()
+            .map(|option| {
+                if provider_ids.contains(&option.provider_id) {
+                    ProviderStatus::Running
+                } else {
+                    ProviderStatus::NotRunning
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn autoselects_the_only_running_server() {
+        let statuses = statuses_with_running(&[VLLM_OSS_PROVIDER_ID]);
+        assert_eq!(autoselect_provider(&statuses), Some(VLLM_OSS_PROVIDER_ID));
+
+        // Both Ollama options report the same server.
+        let statuses = statuses_with_running(&[OLLAMA_OSS_PROVIDER_ID, OLLAMA_CHAT_PROVIDER_ID]);
+        assert_eq!(autoselect_provider(&statuses), Some(OLLAMA_OSS_PROVIDER_ID));
+    }
+
+    #[test]
+    fn asks_when_several_or_no_servers_are_running() {
+        let statuses = statuses_with_running(&[LMSTUDIO_OSS_PROVIDER_ID, LLAMACPP_OSS_PROVIDER_ID]);
+        assert_eq!(autoselect_provider(&statuses), None);
+
+        let statuses = statuses_with_running(&[]);
+        assert_eq!(autoselect_provider(&statuses), None);
+    }
+}
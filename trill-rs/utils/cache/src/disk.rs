@@ -0,0 +1,313 @@
+//! A size-capped, content-addressed cache of byte blobs on disk.
+//!
+//! Each entry is stored as `<sha1(key)>.body` next to a `<sha1(key)>.json`
+//! sidecar holding HTTP validators and timestamps. Writes go through a
+//! temporary file and a rename so readers never see a partial entry. When the
+//! bodies exceed the size cap, the least recently accessed entries are removed.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use serde::Deserialize;
+use serde::Serialize;
+use tempfile::NamedTempFile;
+
+use crate::sha1_digest;
+
+const BODY_EXTENSION: &str = "body";
+const META_EXTENSION: &str = "json";
+
+/// HTTP validators used to revalidate a stale entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheValidators {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+/// Sidecar metadata stored alongside each body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskEntryMeta {
+    /// The unhashed key, used to detect digest collisions.
+    pub key: String,
+    /// Milliseconds since the Unix epoch when the body was stored or last
+    /// revalidated.
+    pub stored_at_ms: u64,
+    /// Milliseconds since the Unix epoch of the last read or write.
+    pub accessed_at_ms: u64,
+    #[serde(default)]
+    pub validators: CacheValidators,
+    /// Caller-defined attributes such as the content type.
+    #[serde(default)]
+    pub attributes: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskEntry {
+    pub meta: DiskEntryMeta,
+    pub body: Vec<u8>,
+}
+
+impl DiskEntry {
+    /// Time since the entry was stored or last revalidated.
+    #[must_use]
+    pub fn age(&self) -> Duration {
+        Duration::from_millis(now_ms().saturating_sub(self.meta.stored_at_ms))
+    }
+}
+
+pub struct DiskCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    /// Serializes writers within this process so eviction sees a consistent
+    /// directory.
+    write_lock: Mutex<()>,
+}
+
+impl DiskCache {
+    /// Creates a cache rooted at `dir`, which is created on first write.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>, max_bytes: u64) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the entry for `key`, marking it as recently used. The access
+    /// time is updated on a best-effort basis, so a cache directory that can
+    /// no longer be written still serves reads.
+    pub fn get(&self, key: &str) -> io::Result<Option<DiskEntry>> {
+        let Some(mut meta) = self.read_meta(key)? else {
+            return Ok(None);
+        };
+        let body = match fs::read(self.path(key, BODY_EXTENSION)) {
+            Ok(body) => body,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        meta.accessed_at_ms = now_ms();
+        // Only eviction order depends on the access time.
+        let _ = self.write_meta(&meta);
+        Ok(Some(DiskEntry { meta, body }))
+    }
+
+    /// Stores `body` under `key`, replacing any previous entry, then evicts
+    /// least recently used entries until the cache fits its size cap. Bodies
+    /// larger than the cap are not stored.
+    pub fn put(
+        &self,
+        key: &str,
+        body: &[u8],
+        validators: CacheValidators,
+        attributes: BTreeMap<String, String>,
+    ) -> io::Result<()> {
+        if body.len() as u64 > self.max_bytes {
+            return Ok(());
+        }
+        let _guard = self
+            .write_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        fs::create_dir_all(&self.dir)?;
+        self.write_atomic(&self.path(key, BODY_EXTENSION), body)?;
+        let now = now_ms();
+        self.write_meta(&DiskEntryMeta {
+            key: key.to_string(),
+            stored_at_ms: now,
+            accessed_at_ms: now,
+            validators,
+            attributes,
+        })?;
+        self.evict()
+    }
+
+    /// Marks a stale entry as fresh again, e.g. after an HTTP 304.
+    pub fn refresh(&self, key: &str) -> io::Result<()> {
+        let Some(mut meta) = self.read_meta(key)? else {
+            return Ok(());
+        };
+        let now = now_ms();
+        meta.stored_at_ms = now;
+        meta.accessed_at_ms = now;
+        self.write_meta(&meta)
+    }
+
+    /// Removes the entry for `key`, if any.
+    pub fn remove(&self, key: &str) -> io::Result<()> {
+        for extension in [META_EXTENSION, BODY_EXTENSION] {
+            match fs::remove_file(self.path(key, extension)) {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    fn evict(&self) -> io::Result<()> {
+        let mut entries = Vec::new();
+        let mut total: u64 = 0;
+        for dir_entry in fs::read_dir(&self.dir)? {
+            let path = dir_entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(META_EXTENSION) {
+                continue;
+            }
+            let Some(meta) = read_meta_file(&path)? else {
+                continue;
+            };
+            let size = fs::metadata(path.with_extension(BODY_EXTENSION))
+                .map(|metadata| metadata.len())
+                .unwrap_or(0);
+            total += size;
+            entries.push((meta.accessed_at_ms, size, path));
+        }
+        if total <= self.max_bytes {
+            return Ok(());
+        }
+        entries.sort_by_key(|(accessed_at_ms, _, _)| *accessed_at_ms);
+        for (_, size, meta_path) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            fs::remove_file(&meta_path)?;
+            match fs::remove_file(meta_path.with_extension(BODY_EXTENSION)) {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+            total -= size;
+        }
+        Ok(())
+    }
+
+    fn read_meta(&self, key: &str) -> io::Result<Option<DiskEntryMeta>> {
+        Ok(read_meta_file(&self.path(key, META_EXTENSION))?.filter(|meta| meta.key == key))
+    }
+
+    fn write_meta(&self, meta: &DiskEntryMeta) -> io::Result<()> {
+        let json = serde_json::to_vec(meta).map_err(io::Error::other)?;
+        self.write_atomic(&self.path(&meta.key, META_EXTENSION), &json)
+    }
+
+    fn write_atomic(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let mut tmp = NamedTempFile::new_in(&self.dir)?;
+        tmp.write_all(contents)?;
+        tmp.persist(path).map_err(|err| err.error)?;
+        Ok(())
+    }
+
+    fn path(&self, key: &str, extension: &str) -> PathBuf {
+        let digest: String = sha1_digest(key.as_bytes())
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        self.dir.join(format!("{digest}.{extension}"))
+    }
+}
+
+fn read_meta_file(path: &Path) -> io::Result<Option<DiskEntryMeta>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    // A corrupt sidecar is treated as a miss and overwritten on the next put.
+    Ok(serde_json::from_slice(&bytes).ok())
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn put(cache: &DiskCache, key: &str, body: &[u8]) {
+        cache
+            .put(key, body, CacheValidators::default(), BTreeMap::new())
+            .expect("put");
+    }
+
+    #[test]
+    fn round_trips_entries_with_validators() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cache = DiskCache::new(dir.path().join("web"), 1024);
+        assert_eq!(cache.get("missing").expect("get"), None);
+
+        let validators = CacheValidators {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+        };
+        let attributes = BTreeMap::from([("content_type".to_string(), "text/html".to_string())]);
+        cache
+            .put("page", b"<p>hi</p>", validators.clone(), attributes.clone())
+            .expect("put");
+
+        let entry = cache.get("page").expect("get").expect("entry");
+        assert_eq!(entry.body, b"<p>hi</p>");
+        assert_eq!(entry.meta.validators, validators);
+        assert_eq!(entry.meta.attributes, attributes);
+        assert!(entry.age() < Duration::from_secs(60));
+
+        cache.remove("page").expect("remove");
+        assert_eq!(cache.get("page").expect("get"), None);
+    }
+
+    #[test]
+    fn evicts_least_recently_accessed_entries() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cache = DiskCache::new(dir.path(), 10);
+        put(&cache, "a", b"aaaa");
+        std::thread::sleep(Duration::from_millis(5));
+        put(&cache, "b", b"bbbb");
+        std::thread::sleep(Duration::from_millis(5));
+        // Reading `a` makes `b` the least recently used entry.
+        assert!(cache.get("a").expect("get").is_some());
+        std::thread::sleep(Duration::from_millis(5));
+        put(&cache, "c", b"cccc");
+
+        assert!(cache.get("a").expect("get").is_some());
+        assert_eq!(cache.get("b").expect("get"), None);
+        assert!(cache.get("c").expect("get").is_some());
+
+        put(&cache, "too-big", b"0123456789abc");
+        assert_eq!(cache.get("too-big").expect("get"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reads_survive_a_read_only_cache_dir() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cache = DiskCache::new(dir.path().join("web"), 1024);
+        put(&cache, "page", b"body");
+        fs::set_permissions(cache.dir(), fs::Permissions::from_mode(0o555)).expect("chmod");
+
+        let entry = cache.get("page");
+        fs::set_permissions(cache.dir(), fs::Permissions::from_mode(0o755)).expect("chmod");
+        assert_eq!(entry.expect("get").expect("entry").body, b"body");
+    }
+}
@@ -1,3 +1,5 @@
+mod disk;
+
 use std::borrow::Borrow;
 use std::hash::Hash;
 use std::num::NonZeroUsize;
@@ -8,6 +10,11 @@ use sha1::Sha1;
 use tokio::sync::Mutex;
 use tokio::sync::MutexGuard;
 
+pub use disk::CacheValidators;
+pub use disk::DiskCache;
+pub use disk::DiskEntry;
+pub use disk::DiskEntryMeta;
+
 /// A minimal LRU cache protected by a Tokio mutex.
 /// Calls outside a Tokio runtime are no-ops.
 pub struct BlockingLruCache<K, V> {
@@ -453,6 +453,161 @@ pub fn restore_ghost_commit_with_options(
     )
 }
 
+/// Restore only `paths` (relative to `options.repo_path`) from the ghost commit.
+///
+/// Directories stand for every file under them. Files under `paths` that the snapshot lacks are
+/// deleted only when they are known to be new: ignored files, files under directories snapshots
+/// always skip, and untracked files that already existed when the snapshot was captured are kept.
+pub fn restore_ghost_commit_paths(
+    options: &RestoreGhostCommitOptions<'_>,
+    commit: &GhostCommit,
+    paths: &[PathBuf],
+) -> Result<(), GitToolingError> {
+    ensure_git_repository(options.repo_path)?;
+
+    let repo_root = resolve_repository_root(options.repo_path)?;
+    let repo_prefix = repo_subdir(repo_root.as_path(), options.repo_path);
+    let mut repo_paths = Vec::with_capacity(paths.len());
+    for path in paths {
+        let normalized = normalize_relative_path(path)?;
+        repo_paths.push(match repo_prefix.as_deref() {
+            Some(prefix) => prefix.join(normalized),
+            None => normalized,
+        });
+    }
+    let repo_paths = dedupe_paths(repo_paths);
+    if repo_paths.is_empty() {
+        return Ok(());
+    }
+
+    // Example:
+    //   git ls-tree -r -z --name-only <commit> -- <paths>
+    let mut ls_tree_args = vec![
+        OsString::from("ls-tree"),
+        OsString::from("-r"),
+        OsString::from("-z"),
+        OsString::from("--name-only"),
+        OsString::from(commit.id()),
+        OsString::from("--"),
+    ];
+    ls_tree_args.extend(
+        repo_paths
+            .iter()
+            .map(|path| path.as_os_str().to_os_string()),
+    );
+    let in_snapshot = split_nul_paths(&run_git_for_stdout_all(
+        repo_root.as_path(),
+        ls_tree_args,
+        None,
+    )?);
+
+    // Tracked and untracked files currently under `paths`, leaving out ignored ones.
+    // Example:
+    //   git ls-files -z --cached --others --exclude-standard -- <paths>
+    let mut ls_files_args = vec![
+        OsString::from("ls-files"),
+        OsString::from("-z"),
+        OsString::from("--cached"),
+        OsString::from("--others"),
+        OsString::from("--exclude-standard"),
+        OsString::from("--"),
+    ];
+    ls_files_args.extend(
+        repo_paths
+            .iter()
+            .map(|path| path.as_os_str().to_os_string()),
+    );
+    let current = split_nul_paths(&run_git_for_stdout_all(
+        repo_root.as_path(),
+        ls_files_args,
+        None,
+    )?);
+
+    let restore = dedupe_paths(in_snapshot);
+    let in_snapshot_set: HashSet<&PathBuf> = restore.iter().collect();
+    let chunk_size = usize::try_from(64_i64).unwrap_or(1);
+    for chunk in restore.chunks(chunk_size) {
+        let mut restore_args = vec![
+            OsString::from("restore"),
+            OsString::from("--source"),
+            OsString::from(commit.id()),
+            OsString::from("--worktree"),
+            OsString::from("--"),
+        ];
+        restore_args.extend(chunk.iter().map(|path| path.as_os_str().to_os_string()));
+        run_git_for_status(repo_root.as_path(), restore_args, None)?;
+    }
+
+    let preserved_files: HashSet<PathBuf> = commit
+        .preexisting_untracked_files()
+        .iter()
+        .cloned()
+        .collect();
+    for path in dedupe_paths(current) {
+        if in_snapshot_set.contains(&path)
+            || should_ignore_for_snapshot(&path)
+            || should_preserve(&path, &preserved_files, commit.preexisting_untracked_dirs())
+        {
+            continue;
+        }
+        remove_path(&repo_root.join(&path))?;
+        remove_empty_parents(repo_root.as_path(), &path);
+    }
+    Ok(())
+}
+
+fn split_nul_paths(output: &str) -> Vec<PathBuf> {
+    output
+        .split('\0')
+        .filter(|entry| !entry.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Removes directories left empty after deleting `path`, stopping at the first one that is not.
+fn remove_empty_parents(repo_root: &Path, path: &Path) {
+    for parent in path.ancestors().skip(1) {
+        if parent.as_os_str().is_empty() || fs::remove_dir(repo_root.join(parent)).is_err() {
+            break;
+        }
+    }
+}
+
+/// Lists the files that differ between two commits, relative to `repo_path`.
+pub fn ghost_commit_changed_paths(
+    repo_path: &Path,
+    from_commit: &str,
+    to_commit: &str,
+) -> Result<Vec<PathBuf>, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+
+    let repo_root = resolve_repository_root(repo_path)?;
+    let repo_prefix = repo_subdir(repo_root.as_path(), repo_path);
+    // Example:
+    //   git diff --name-only -z --no-renames <from> <to> -- <prefix>
+    let mut args = vec![
+        OsString::from("diff"),
+        OsString::from("--name-only"),
+        OsString::from("-z"),
+        OsString::from("--no-renames"),
+        OsString::from(from_commit),
+        OsString::from(to_commit),
+        OsString::from("--"),
+    ];
+    if let Some(prefix) = repo_prefix.as_deref() {
+        args.push(prefix.as_os_str().to_os_string());
+    } else {
+        args.push(OsString::from("."));
+    }
+
+    let output = run_git_for_stdout_all(repo_root.as_path(), args, None)?;
+    Ok(output
+        .split('\0')
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| to_session_relative_path(Path::new(entry), repo_prefix.as_deref()))
+        .collect())
+}
+
 /// Restore the working tree to match the given commit ID.
 pub fn restore_to_commit(repo_path: &Path, commit_id: &str) -> Result<(), GitToolingError> {
     ensure_git_repository(repo_path)?;
@@ -1776,4 +1931,190 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    /// Restoring selected paths leaves other changes alone and deletes files the snapshot lacks.
+    fn restore_paths_only_touches_selected_files() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+
+        std::fs::write(repo.join("a.txt"), "a before\n")?;
+        std::fs::write(repo.join("b.txt"), "b before\n")?;
+        run_git_in(repo, &["add", "."]);
+        run_git_in(
+            repo,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "initial",
+            ],
+        );
+        std::fs::write(repo.join("notes.txt"), "notes before\n")?;
+
+        let ghost = create_ghost_commit(&CreateGhostCommitOptions::new(repo))?;
+
+        std::fs::write(repo.join("a.txt"), "a after\n")?;
+        std::fs::write(repo.join("b.txt"), "b after\n")?;
+        std::fs::write(repo.join("new.txt"), "new\n")?;
+        std::fs::write(repo.join("notes.txt"), "notes after\n")?;
+
+        restore_ghost_commit_paths(
+            &RestoreGhostCommitOptions::new(repo),
+            &ghost,
+            &[PathBuf::from("a.txt"), PathBuf::from("new.txt")],
+        )?;
+
+        assert_eq!(std::fs::read_to_string(repo.join("a.txt"))?, "a before\n");
+        assert_eq!(std::fs::read_to_string(repo.join("b.txt"))?, "b after\n");
+        assert!(!repo.join("new.txt").exists());
+        assert_eq!(
+            std::fs::read_to_string(repo.join("notes.txt"))?,
+            "notes after\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    /// Restoring a directory path undoes and redoes every file under it.
+    fn restore_paths_undoes_and_redoes_a_directory() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+
+        let src = repo.join("src");
+        std::fs::create_dir_all(&src)?;
+        std::fs::write(src.join("lib.rs"), "before\n")?;
+        std::fs::write(repo.join("other.txt"), "other before\n")?;
+        run_git_in(repo, &["add", "."]);
+        run_git_in(
+            repo,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "initial",
+            ],
+        );
+
+        let before = create_ghost_commit(&CreateGhostCommitOptions::new(repo))?;
+        std::fs::write(src.join("lib.rs"), "after\n")?;
+        std::fs::create_dir_all(src.join("nested"))?;
+        std::fs::write(src.join("nested").join("new.rs"), "new\n")?;
+        std::fs::write(repo.join("other.txt"), "other after\n")?;
+        let after = create_ghost_commit(&CreateGhostCommitOptions::new(repo))?;
+
+        let options = RestoreGhostCommitOptions::new(repo);
+        restore_ghost_commit_paths(&options, &before, &[PathBuf::from("src")])?;
+        assert_eq!(std::fs::read_to_string(src.join("lib.rs"))?, "before\n");
+        assert!(!src.join("nested").exists());
+        assert_eq!(
+            std::fs::read_to_string(repo.join("other.txt"))?,
+            "other after\n"
+        );
+
+        restore_ghost_commit_paths(&options, &after, &[PathBuf::from("src")])?;
+        assert_eq!(std::fs::read_to_string(src.join("lib.rs"))?, "after\n");
+        assert_eq!(
+            std::fs::read_to_string(src.join("nested").join("new.rs"))?,
+            "new\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    /// Restoring a directory never deletes the ignored files inside it.
+    fn restore_paths_keeps_ignored_files_in_directory() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+
+        let logs = repo.join("logs");
+        std::fs::create_dir_all(&logs)?;
+        std::fs::write(repo.join(".gitignore"), "*.log\n")?;
+        std::fs::write(logs.join("README.md"), "readme\n")?;
+        std::fs::write(logs.join("old.log"), "old\n")?;
+        run_git_in(repo, &["add", "."]);
+        run_git_in(
+            repo,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "initial",
+            ],
+        );
+
+        let ghost = create_ghost_commit(&CreateGhostCommitOptions::new(repo))?;
+        std::fs::write(logs.join("README.md"), "changed\n")?;
+        std::fs::write(logs.join("new.log"), "new\n")?;
+        std::fs::write(logs.join("new.txt"), "new\n")?;
+
+        restore_ghost_commit_paths(
+            &RestoreGhostCommitOptions::new(repo),
+            &ghost,
+            &[PathBuf::from("logs")],
+        )?;
+
+        assert_eq!(std::fs::read_to_string(logs.join("README.md"))?, "readme\n");
+        assert_eq!(std::fs::read_to_string(logs.join("old.log"))?, "old\n");
+        assert_eq!(std::fs::read_to_string(logs.join("new.log"))?, "new\n");
+        assert!(!logs.join("new.txt").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    /// Changed paths between snapshots are reported relative to the snapshot directory.
+    fn changed_paths_between_snapshots() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+
+        let workspace = repo.join("workspace");
+        std::fs::create_dir_all(&workspace)?;
+        std::fs::write(repo.join("root.txt"), "root\n")?;
+        std::fs::write(workspace.join("kept.txt"), "kept\n")?;
+        std::fs::write(workspace.join("edited.txt"), "before\n")?;
+        run_git_in(repo, &["add", "."]);
+        run_git_in(
+            repo,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "initial",
+            ],
+        );
+
+        let before = create_ghost_commit(&CreateGhostCommitOptions::new(&workspace))?;
+        std::fs::write(repo.join("root.txt"), "root changed\n")?;
+        std::fs::write(workspace.join("edited.txt"), "after\n")?;
+        std::fs::write(workspace.join("added.txt"), "added\n")?;
+        let after = create_ghost_commit(&CreateGhostCommitOptions::new(&workspace))?;
+
+        let mut changed = ghost_commit_changed_paths(&workspace, before.id(), after.id())?;
+        changed.sort();
+        assert_eq!(
+            changed,
+            vec![PathBuf::from("added.txt"), PathBuf::from("edited.txt")]
+        );
+
+        Ok(())
+    }
 }